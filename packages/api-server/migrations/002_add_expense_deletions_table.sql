@@ -0,0 +1,29 @@
+-- Migration: 経費の削除履歴テーブルの追加
+-- 説明: デスクトップアプリの差分同期（GET /api/v1/expenses?since=）で
+--       削除された経費のIDを返すため、削除履歴（トゥームストーン）を保存する
+
+-- ============================================
+-- Step 1: expense_deletionsテーブルの作成
+-- ============================================
+CREATE TABLE IF NOT EXISTS expense_deletions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id TEXT NOT NULL,                -- ユーザーID（nanoId形式）
+    expense_id INTEGER NOT NULL,          -- 削除された経費のID
+    deleted_at TEXT NOT NULL,             -- RFC3339形式（UTC）
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- expense_deletionsテーブルのインデックス
+CREATE INDEX IF NOT EXISTS idx_expense_deletions_user_deleted_at ON expense_deletions(user_id, deleted_at);
+
+-- ============================================
+-- Step 2: 差分取得用のインデックス作成
+-- ============================================
+CREATE INDEX IF NOT EXISTS idx_expenses_user_updated_at ON expenses(user_id, updated_at);
+
+-- ============================================
+-- 注意事項:
+-- - 削除履歴は保持期間（90日）を過ぎたものから経費削除時に削除する
+-- - 保持期間より古いリビジョンでの差分取得にはfull_resync_requiredを返し、
+--   クライアントに全件を取得し直させる
+-- ============================================
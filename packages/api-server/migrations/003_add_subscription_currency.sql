@@ -0,0 +1,10 @@
+-- Migration: サブスクリプションの通貨カラムの追加
+-- 説明: 日本円以外の金額（Appleの購入履歴の"$9.99"など）を通貨とともに保存するため、
+--       subscriptionsテーブルにcurrencyカラムを追加する
+
+-- ============================================
+-- Step 1: subscriptionsテーブルに currency カラムを追加
+-- 既存のサブスクリプションは日本円として扱う
+-- ============================================
+ALTER TABLE subscriptions ADD COLUMN currency TEXT NOT NULL DEFAULT 'JPY'
+    CHECK (currency IN ('JPY', 'USD', 'EUR', 'GBP'));
@@ -0,0 +1,17 @@
+-- Migration: 経費の消費税カラムの追加
+-- 説明: デスクトップアプリから送信される消費税率・消費税額を保存するため、
+--       expensesテーブルにtax_rate・tax_amountカラムを追加する
+
+-- ============================================
+-- Step 1: expensesテーブルに tax_rate カラムを追加
+-- 10%の場合は0.1（未入力の経費はNULL）
+-- ============================================
+ALTER TABLE expenses ADD COLUMN tax_rate REAL
+    CHECK (tax_rate IS NULL OR (tax_rate >= 0 AND tax_rate < 1));
+
+-- ============================================
+-- Step 2: expensesテーブルに tax_amount カラムを追加
+-- amountに含まれる消費税額（未入力の経費はNULL）
+-- ============================================
+ALTER TABLE expenses ADD COLUMN tax_amount REAL
+    CHECK (tax_amount IS NULL OR tax_amount >= 0);
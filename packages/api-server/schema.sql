@@ -26,11 +26,15 @@ CREATE TABLE IF NOT EXISTS expenses (
     category_id INTEGER,              -- カテゴリID（categoriesテーブルへの外部キー）
     description TEXT,                 -- 説明（オプション）
     receipt_url TEXT,                 -- 領収書URL（HTTPS）
+    tax_rate REAL,                    -- 消費税率（10%の場合は0.1、オプション）
+    tax_amount REAL,                  -- 消費税額（amountに含まれる、オプション）
     created_at TEXT NOT NULL,         -- RFC3339形式（JST）
     updated_at TEXT NOT NULL,         -- RFC3339形式（JST）
     FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
     FOREIGN KEY (category_id) REFERENCES categories(id),
-    CHECK (receipt_url IS NULL OR receipt_url LIKE 'https://%')
+    CHECK (receipt_url IS NULL OR receipt_url LIKE 'https://%'),
+    CHECK (tax_rate IS NULL OR (tax_rate >= 0 AND tax_rate < 1)),
+    CHECK (tax_amount IS NULL OR tax_amount >= 0)
 );
 
 -- expensesテーブルのインデックス
@@ -38,6 +42,19 @@ CREATE INDEX IF NOT EXISTS idx_expenses_user_id ON expenses(user_id);
 CREATE INDEX IF NOT EXISTS idx_expenses_date ON expenses(date);
 CREATE INDEX IF NOT EXISTS idx_expenses_category ON expenses(category);
 CREATE INDEX IF NOT EXISTS idx_expenses_category_id ON expenses(category_id);
+CREATE INDEX IF NOT EXISTS idx_expenses_user_updated_at ON expenses(user_id, updated_at);
+
+-- expense_deletionsテーブル（差分同期用の削除履歴）
+CREATE TABLE IF NOT EXISTS expense_deletions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id TEXT NOT NULL,            -- ユーザーID（nanoId形式）
+    expense_id INTEGER NOT NULL,      -- 削除された経費のID
+    deleted_at TEXT NOT NULL,         -- RFC3339形式（UTC）
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- expense_deletionsテーブルのインデックス
+CREATE INDEX IF NOT EXISTS idx_expense_deletions_user_deleted_at ON expense_deletions(user_id, deleted_at);
 
 -- subscriptionsテーブル
 CREATE TABLE IF NOT EXISTS subscriptions (
@@ -50,12 +67,14 @@ CREATE TABLE IF NOT EXISTS subscriptions (
     category TEXT NOT NULL,           -- カテゴリ（後方互換性のため残す）
     category_id INTEGER,              -- カテゴリID（categoriesテーブルへの外部キー）
     is_active INTEGER NOT NULL DEFAULT 1, -- 0=無効, 1=有効
+    currency TEXT NOT NULL DEFAULT 'JPY', -- ISO 4217の通貨コード（JPY, USD, EUR, GBP）
     receipt_path TEXT,                -- 領収書パス（将来的にreceipt_urlに移行）
     created_at TEXT NOT NULL,         -- RFC3339形式（JST）
     updated_at TEXT NOT NULL,         -- RFC3339形式（JST）
     FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
     FOREIGN KEY (category_id) REFERENCES categories(id),
-    CHECK (billing_cycle IN ('monthly', 'annual'))
+    CHECK (billing_cycle IN ('monthly', 'annual')),
+    CHECK (currency IN ('JPY', 'USD', 'EUR', 'GBP'))
 );
 
 -- subscriptionsテーブルのインデックス
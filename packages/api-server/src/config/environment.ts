@@ -26,6 +26,7 @@ const envSchema = z.object({
   R2_SECRET_ACCESS_KEY: z.string(),
   R2_BUCKET_NAME: z.string(),
   R2_REGION: z.string().default("auto"),
+  R2_PUBLIC_BASE_URL: z.string().optional(),
 
   // 認証設定
   JWT_SECRET: z.string(),
@@ -110,6 +111,7 @@ export function loadConfig(): ApiServerConfig {
       secretAccessKey: env.R2_SECRET_ACCESS_KEY,
       bucketName: env.R2_BUCKET_NAME,
       region: env.R2_REGION,
+      publicDomain: env.R2_PUBLIC_BASE_URL,
     };
 
     // R2設定の詳細バリデーション
@@ -22,6 +22,7 @@ export function loadWorkerConfig(env: Env): ApiServerConfig {
       secretAccessKey: env.R2_SECRET_ACCESS_KEY || "binding", // Workersではバインディングを使用
       bucketName: env.R2_BUCKET_NAME || "orano-keihi-dev", // バケット名は必要
       region: env.R2_REGION || "auto",
+      publicDomain: env.R2_PUBLIC_BASE_URL,
     },
 
     // 認証設定
@@ -7,6 +7,7 @@ import type { D1Database } from "@cloudflare/workers-types";
 import type { Expense } from "../types/d1-models.js";
 import type { CreateExpenseDto, UpdateExpenseDto } from "../types/d1-dtos.js";
 import { logger } from "../utils/logger.js";
+import { deletionRetentionCutoff } from "../utils/expense-sync.js";
 
 /**
  * 経費リポジトリクラス
@@ -26,8 +27,8 @@ export class ExpenseRepository {
 
       const result = await this.db
         .prepare(
-          `INSERT INTO expenses (user_id, date, amount, category, category_id, description, created_at, updated_at)
-           VALUES (?, ?, ?, ?, ?, ?, ?, ?)`,
+          `INSERT INTO expenses (user_id, date, amount, category, category_id, description, tax_rate, tax_amount, created_at, updated_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
         )
         .bind(
           userId,
@@ -36,6 +37,8 @@ export class ExpenseRepository {
           dto.category,
           dto.category_id || null,
           dto.description || null,
+          dto.tax_rate ?? null,
+          dto.tax_amount ?? null,
           now,
           now,
         )
@@ -77,6 +80,61 @@ export class ExpenseRepository {
     }
   }
 
+  /**
+   * 経費を一括で作成する（すべて作成するか、何も作成しない）
+   * @param dtos 経費作成DTOの一覧
+   * @param userId ユーザーID
+   * @returns 作成された経費一覧（dtosと同じ順序）
+   */
+  async createMany(dtos: CreateExpenseDto[], userId: string): Promise<Expense[]> {
+    try {
+      const now = new Date().toISOString(); // RFC3339形式（JST）
+
+      // D1のbatchは1つのトランザクションで実行される
+      const results = await this.db.batch<Expense>(
+        dtos.map((dto) =>
+          this.db
+            .prepare(
+              `INSERT INTO expenses (user_id, date, amount, category, category_id, description, tax_rate, tax_amount, created_at, updated_at)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+               RETURNING *`,
+            )
+            .bind(
+              userId,
+              dto.date,
+              dto.amount,
+              dto.category,
+              dto.category_id || null,
+              dto.description || null,
+              dto.tax_rate ?? null,
+              dto.tax_amount ?? null,
+              now,
+              now,
+            ),
+        ),
+      );
+
+      const expenses = results.map((result) => result.results[0]);
+      if (expenses.some((expense) => !expense)) {
+        throw new Error("作成した経費の取得に失敗しました");
+      }
+
+      logger.info("経費を一括作成しました", {
+        userId,
+        count: expenses.length,
+      });
+
+      return expenses;
+    } catch (error) {
+      logger.error("createManyでエラーが発生しました", {
+        userId,
+        count: dtos.length,
+        error: error instanceof Error ? error.message : String(error),
+      });
+      throw error;
+    }
+  }
+
   /**
    * 経費IDで経費を取得する
    * @param id 経費ID
@@ -205,6 +263,14 @@ export class ExpenseRepository {
         updates.push("description = ?");
         params.push(dto.description);
       }
+      if (dto.tax_rate !== undefined) {
+        updates.push("tax_rate = ?");
+        params.push(dto.tax_rate);
+      }
+      if (dto.tax_amount !== undefined) {
+        updates.push("tax_amount = ?");
+        params.push(dto.tax_amount);
+      }
       if (dto.receipt_url !== undefined) {
         updates.push("receipt_url = ?");
         // 空文字列の場合はNULLに変換（CHECK制約対応）
@@ -277,10 +343,21 @@ export class ExpenseRepository {
    */
   async delete(id: number, userId: string): Promise<void> {
     try {
-      const result = await this.db
-        .prepare("DELETE FROM expenses WHERE id = ? AND user_id = ?")
-        .bind(id, userId)
-        .run();
+      const now = new Date().toISOString();
+
+      // 差分同期で削除を伝えるため、削除履歴の記録と削除を同じバッチで実行する
+      const [, result] = await this.db.batch([
+        this.db
+          .prepare(
+            `INSERT INTO expense_deletions (user_id, expense_id, deleted_at)
+             SELECT user_id, id, ? FROM expenses WHERE id = ? AND user_id = ?`,
+          )
+          .bind(now, id, userId),
+        this.db.prepare("DELETE FROM expenses WHERE id = ? AND user_id = ?").bind(id, userId),
+        this.db
+          .prepare("DELETE FROM expense_deletions WHERE deleted_at < ?")
+          .bind(deletionRetentionCutoff()),
+      ]);
 
       if (!result.success) {
         logger.error("経費削除に失敗しました", {
@@ -311,6 +388,95 @@ export class ExpenseRepository {
     }
   }
 
+  /**
+   * 指定したリビジョン以降に作成・更新された経費を取得する
+   * @param userId ユーザーID
+   * @param since リビジョン（RFC3339形式、UTC）
+   * @returns 作成・更新された経費一覧
+   */
+  async findChangedSince(userId: string, since: string): Promise<Expense[]> {
+    try {
+      const result = await this.db
+        .prepare(
+          `SELECT * FROM expenses
+           WHERE user_id = ? AND updated_at >= ?
+           ORDER BY updated_at ASC, id ASC`,
+        )
+        .bind(userId, since)
+        .all<Expense>();
+
+      if (!result.success) {
+        throw new Error(`経費の差分取得に失敗しました: ${result.error}`);
+      }
+
+      return result.results;
+    } catch (error) {
+      logger.error("findChangedSinceでエラーが発生しました", {
+        userId,
+        since,
+        error: error instanceof Error ? error.message : String(error),
+      });
+      throw error;
+    }
+  }
+
+  /**
+   * 指定したリビジョン以降に削除された経費のIDを取得する
+   * @param userId ユーザーID
+   * @param since リビジョン（RFC3339形式、UTC）
+   * @returns 削除された経費のID一覧
+   */
+  async findDeletedIdsSince(userId: string, since: string): Promise<number[]> {
+    try {
+      const result = await this.db
+        .prepare(
+          `SELECT DISTINCT expense_id FROM expense_deletions
+           WHERE user_id = ? AND deleted_at >= ?`,
+        )
+        .bind(userId, since)
+        .all<{ expense_id: number }>();
+
+      if (!result.success) {
+        throw new Error(`削除された経費の取得に失敗しました: ${result.error}`);
+      }
+
+      return result.results.map((row) => row.expense_id);
+    } catch (error) {
+      logger.error("findDeletedIdsSinceでエラーが発生しました", {
+        userId,
+        since,
+        error: error instanceof Error ? error.message : String(error),
+      });
+      throw error;
+    }
+  }
+
+  /**
+   * チェックサム算出用に全経費のIDと更新日時を取得する
+   * @param userId ユーザーID
+   * @returns IDの昇順に並べたIDと更新日時の一覧
+   */
+  async findChecksumEntries(userId: string): Promise<{ id: number; updated_at: string }[]> {
+    try {
+      const result = await this.db
+        .prepare("SELECT id, updated_at FROM expenses WHERE user_id = ? ORDER BY id ASC")
+        .bind(userId)
+        .all<{ id: number; updated_at: string }>();
+
+      if (!result.success) {
+        throw new Error(`経費のチェックサム算出に失敗しました: ${result.error}`);
+      }
+
+      return result.results;
+    } catch (error) {
+      logger.error("findChecksumEntriesでエラーが発生しました", {
+        userId,
+        error: error instanceof Error ? error.message : String(error),
+      });
+      throw error;
+    }
+  }
+
   /**
    * 領収書URLを設定する
    * @param id 経費ID
@@ -26,8 +26,8 @@ export class SubscriptionRepository {
 
       const result = await this.db
         .prepare(
-          `INSERT INTO subscriptions (user_id, name, amount, billing_cycle, start_date, category, category_id, is_active, created_at, updated_at)
-           VALUES (?, ?, ?, ?, ?, ?, ?, 1, ?, ?)`,
+          `INSERT INTO subscriptions (user_id, name, amount, billing_cycle, start_date, category, category_id, currency, is_active, created_at, updated_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?, 1, ?, ?)`,
         )
         .bind(
           userId,
@@ -37,6 +37,7 @@ export class SubscriptionRepository {
           dto.start_date,
           dto.category,
           dto.category_id || null,
+          dto.currency || "JPY",
           now,
           now,
         )
@@ -79,6 +80,61 @@ export class SubscriptionRepository {
     }
   }
 
+  /**
+   * サブスクリプションを一括で作成する（すべて作成するか、何も作成しない）
+   * @param dtos サブスクリプション作成DTOの一覧
+   * @param userId ユーザーID
+   * @returns 作成されたサブスクリプション一覧（dtosと同じ順序）
+   */
+  async createMany(dtos: CreateSubscriptionDto[], userId: string): Promise<Subscription[]> {
+    try {
+      const now = new Date().toISOString(); // RFC3339形式（JST）
+
+      // D1のbatchは1つのトランザクションで実行される
+      const results = await this.db.batch<Subscription>(
+        dtos.map((dto) =>
+          this.db
+            .prepare(
+              `INSERT INTO subscriptions (user_id, name, amount, billing_cycle, start_date, category, category_id, currency, is_active, created_at, updated_at)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, 1, ?, ?)
+               RETURNING *`,
+            )
+            .bind(
+              userId,
+              dto.name,
+              dto.amount,
+              dto.billing_cycle,
+              dto.start_date,
+              dto.category,
+              dto.category_id || null,
+              dto.currency || "JPY",
+              now,
+              now,
+            ),
+        ),
+      );
+
+      const subscriptions = results.map((result) => result.results[0]);
+      if (subscriptions.some((subscription) => !subscription)) {
+        throw new Error("作成したサブスクリプションの取得に失敗しました");
+      }
+
+      logger.info("サブスクリプションを一括作成しました", {
+        userId,
+        count: subscriptions.length,
+      });
+
+      return subscriptions;
+    } catch (error) {
+      logger.error("createManyでエラーが発生しました", {
+        userId,
+        count: dtos.length,
+        error: error instanceof Error ? error.message : String(error),
+      });
+      throw error;
+    }
+  }
+
   /**
    * サブスクリプションIDでサブスクリプションを取得する
    * @param id サブスクリプションID
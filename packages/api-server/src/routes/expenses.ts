@@ -9,6 +9,122 @@ import { handleError, createValidationError, createNotFoundError } from "../util
 import type { ExpenseRepository } from "../repositories/expense-repository.js";
 import type { CreateExpenseDto, UpdateExpenseDto } from "../types/d1-dtos.js";
 import type { R2ClientInterface } from "../services/r2-client.js";
+import {
+  computeExpenseChecksum,
+  isRevisionExpired,
+  normalizeRevision,
+} from "../utils/expense-sync.js";
+
+/** 一括作成で受け付ける経費の最大件数 */
+const MAX_BATCH_EXPENSES = 500;
+
+/**
+ * 消費税率・消費税額のバリデーションを行う
+ * @param taxRate 消費税率（10%の場合は0.1、未指定・nullの場合は検証しない）
+ * @param taxAmount 消費税額（未指定・nullの場合は検証しない）
+ * @param amount 金額（指定されている場合は税額が金額以下であることを検証する）
+ * @throws バリデーションエラー
+ */
+function validateTaxFields(
+  taxRate: number | null | undefined,
+  taxAmount: number | null | undefined,
+  amount?: number,
+): void {
+  if (
+    taxRate !== undefined &&
+    taxRate !== null &&
+    (typeof taxRate !== "number" || !Number.isFinite(taxRate) || taxRate < 0 || taxRate >= 1)
+  ) {
+    throw createValidationError(
+      "消費税率は0以上1未満の数値である必要があります（10%の場合は0.1）",
+      "tax_rate",
+      taxRate,
+      "number in [0, 1) required",
+    );
+  }
+
+  if (
+    taxAmount !== undefined &&
+    taxAmount !== null &&
+    (typeof taxAmount !== "number" || !Number.isFinite(taxAmount) || taxAmount < 0)
+  ) {
+    throw createValidationError(
+      "消費税額は0以上の数値である必要があります",
+      "tax_amount",
+      taxAmount,
+      "non-negative number required",
+    );
+  }
+
+  if (typeof taxAmount === "number" && typeof amount === "number" && taxAmount > amount) {
+    throw createValidationError(
+      "消費税額は金額以下である必要があります",
+      "tax_amount",
+      taxAmount,
+      "number not greater than amount required",
+    );
+  }
+}
+
+/**
+ * 経費作成DTOのバリデーションを行う
+ * @param body 経費作成DTO
+ * @throws バリデーションエラー
+ */
+function validateCreateExpenseDto(body: CreateExpenseDto): void {
+  if (!body.date || typeof body.date !== "string") {
+    throw createValidationError(
+      "日付は必須で文字列である必要があります",
+      "date",
+      body.date,
+      "string required (YYYY-MM-DD format)",
+    );
+  }
+
+  if (!body.amount || typeof body.amount !== "number") {
+    throw createValidationError(
+      "金額は必須で数値である必要があります",
+      "amount",
+      body.amount,
+      "number required",
+    );
+  }
+
+  if (!body.category || typeof body.category !== "string") {
+    throw createValidationError(
+      "カテゴリは必須で文字列である必要があります",
+      "category",
+      body.category,
+      "string required",
+    );
+  }
+
+  if (
+    body.description !== undefined &&
+    body.description !== null &&
+    typeof body.description !== "string"
+  ) {
+    throw createValidationError(
+      "説明は文字列である必要があります",
+      "description",
+      body.description,
+      "string required",
+    );
+  }
+
+  // 日付形式のバリデーション（YYYY-MM-DD）
+  const datePattern = /^\d{4}-\d{2}-\d{2}$/;
+  if (!datePattern.test(body.date)) {
+    throw createValidationError(
+      "日付はYYYY-MM-DD形式である必要があります",
+      "date",
+      body.date,
+      "YYYY-MM-DD format required",
+    );
+  }
+
+  validateTaxFields(body.tax_rate, body.tax_amount, body.amount);
+}
 
 /**
  * 経費ルーターを作成
@@ -41,77 +157,84 @@ export function createExpensesRouter(
       });
 
       // バリデーション
-      if (!body.date || typeof body.date !== "string") {
-        throw createValidationError(
-          "日付は必須で文字列である必要があります",
-          "date",
-          body.date,
-          "string required (YYYY-MM-DD format)",
-        );
-      }
+      validateCreateExpenseDto(body);
 
-      if (!body.amount || typeof body.amount !== "number") {
-        throw createValidationError(
-          "金額は必須で数値である必要があります",
-          "amount",
-          body.amount,
-          "number required",
-        );
-      }
+      // 経費を作成
+      const expense = await expenseRepository.create(body, user.id);
 
-      if (!body.category || typeof body.category !== "string") {
-        throw createValidationError(
-          "カテゴリは必須で文字列である必要があります",
-          "category",
-          body.category,
-          "string required",
-        );
+      logger.info("経費を作成しました", {
+        userId: user.id,
+        expenseId: expense.id,
+        amount: expense.amount,
+      });
+
+      return c.json(
+        {
+          success: true,
+          expense,
+          timestamp: new Date().toISOString(),
+        },
+        201,
+      );
+    } catch (error) {
+      return handleError(c, error instanceof Error ? error : new Error(String(error)), {
+        context: "経費作成",
+      });
+    }
+  });
+
+  // POST /api/v1/expenses/batch - 経費を一括作成（CSV取り込み用、すべて登録するか何も登録しない）
+  expensesApp.post("/batch", async (c: Context) => {
+    try {
+      const user = c.get("user");
+
+      if (!user) {
+        logger.error("ユーザー情報が見つかりません");
+        throw createNotFoundError("ユーザー情報が見つかりません");
       }
 
-      if (
-        body.description !== undefined &&
-        body.description !== null &&
-        typeof body.description !== "string"
-      ) {
+      const body = await c.req.json<{ expenses?: CreateExpenseDto[] }>();
+
+      if (!Array.isArray(body.expenses) || body.expenses.length === 0) {
         throw createValidationError(
-          "説明は文字列である必要があります",
-          "description",
-          body.description,
-          "string required",
+          "経費の一覧は必須で1件以上である必要があります",
+          "expenses",
+          body.expenses,
+          "non-empty array required",
         );
       }
 
-      // 日付形式のバリデーション（YYYY-MM-DD）
-      const datePattern = /^\d{4}-\d{2}-\d{2}$/;
-      if (!datePattern.test(body.date)) {
+      if (body.expenses.length > MAX_BATCH_EXPENSES) {
         throw createValidationError(
-          "日付はYYYY-MM-DD形式である必要があります",
-          "date",
-          body.date,
-          "YYYY-MM-DD format required",
+          `一度に作成できる経費は${MAX_BATCH_EXPENSES}件までです`,
+          "expenses",
+          body.expenses.length,
+          `at most ${MAX_BATCH_EXPENSES} items`,
         );
       }
 
-      // 経費を作成
-      const expense = await expenseRepository.create(body, user.id);
+      // 1件でも不正な経費があれば何も登録しない
+      body.expenses.forEach(validateCreateExpenseDto);
 
-      logger.info("経費を作成しました", {
+      const expenses = await expenseRepository.createMany(body.expenses, user.id);
+
+      logger.info("経費を一括作成しました", {
         userId: user.id,
-        expenseId: expense.id,
-        amount: expense.amount,
+        count: expenses.length,
       });
 
       return c.json(
         {
           success: true,
-          expense,
+          expenses,
+          count: expenses.length,
           timestamp: new Date().toISOString(),
         },
         201,
       );
     } catch (error) {
       return handleError(c, error instanceof Error ? error : new Error(String(error)), {
-        context: "経費作成",
+        context: "経費一括作成",
       });
     }
   });
@@ -129,6 +252,58 @@ export function createExpensesRouter(
       // クエリパラメータを取得
       const month = c.req.query("month"); // YYYY-MM形式
       const category = c.req.query("category");
+      const since = c.req.query("since"); // 前回同期したリビジョン
+
+      // 取得前の時刻を次のリビジョンとする（取得中の変更は次回の差分に含まれる）
+      const revision = new Date().toISOString();
+
+      // 差分同期（デスクトップアプリの経費ミラー用）
+      if (since !== undefined) {
+        const normalizedSince = normalizeRevision(since);
+        if (!normalizedSince) {
+          throw createValidationError(
+            "リビジョンはRFC3339形式である必要があります",
+            "since",
+            since,
+            "RFC3339 timestamp required",
+          );
+        }
+
+        // 削除履歴の保持期間より古いリビジョンには全件の取得し直しを要求する
+        if (isRevisionExpired(normalizedSince)) {
+          logger.info("リビジョンが古いため全件の再同期を要求します", {
+            userId: user.id,
+            since: normalizedSince,
+          });
+          return c.json({
+            success: true,
+            full_resync_required: true,
+            revision,
+            timestamp: new Date().toISOString(),
+          });
+        }
+
+        const [upserted, deleted] = await Promise.all([
+          expenseRepository.findChangedSince(user.id, normalizedSince),
+          expenseRepository.findDeletedIdsSince(user.id, normalizedSince),
+        ]);
+
+        logger.info("経費の差分を取得しました", {
+          userId: user.id,
+          upserted: upserted.length,
+          deleted: deleted.length,
+        });
+
+        return c.json({
+          success: true,
+          changes: {
+            upserted,
+            deleted,
+          },
+          revision,
+          timestamp: new Date().toISOString(),
+        });
+      }
 
       logger.debug("経費一覧取得リクエスト", {
         userId: user.id,
@@ -167,6 +342,7 @@ export function createExpensesRouter(
           month: month || null,
           category: category || null,
         },
+        revision,
         timestamp: new Date().toISOString(),
       });
     } catch (error) {
@@ -176,6 +352,37 @@ export function createExpensesRouter(
     }
   });
 
+  // GET /api/v1/expenses/checksum - 経費の件数とチェックサムを取得（/:idより先に登録する）
+  expensesApp.get("/checksum", async (c: Context) => {
+    try {
+      const user = c.get("user");
+
+      if (!user) {
+        logger.error("ユーザー情報が見つかりません");
+        throw createNotFoundError("ユーザー情報が見つかりません");
+      }
+
+      const entries = await expenseRepository.findChecksumEntries(user.id);
+      const { count, checksum } = await computeExpenseChecksum(entries);
+
+      logger.debug("経費のチェックサムを算出しました", {
+        userId: user.id,
+        count,
+      });
+
+      return c.json({
+        success: true,
+        count,
+        checksum,
+        timestamp: new Date().toISOString(),
+      });
+    } catch (error) {
+      return handleError(c, error instanceof Error ? error : new Error(String(error)), {
+        context: "経費チェックサム取得",
+      });
+    }
+  });
+
   // GET /api/v1/expenses/:id - 経費を取得
   expensesApp.get("/:id", async (c: Context) => {
     try {
@@ -314,6 +521,8 @@ export function createExpensesRouter(
         );
       }
 
+      validateTaxFields(body.tax_rate, body.tax_amount, body.amount);
+
       // 日付形式のバリデーション（指定されている場合）
       if (body.date) {
         const datePattern = /^\d{4}-\d{2}-\d{2}$/;
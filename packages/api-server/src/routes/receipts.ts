@@ -12,6 +12,7 @@ import {
 } from "../utils/error-handler.js";
 import { logSecurityEvent } from "../middleware/index.js";
 import type { R2ClientInterface } from "../services/r2-client.js";
+import { extractFileKeyFromReceiptUrl } from "../utils/receipt-url.js";
 
 /**
  * 領収書ルーターを作成
@@ -28,31 +29,17 @@ export function createReceiptsRouter(r2Client: R2ClientInterface): Hono {
    */
   function extractFileKeyFromUrl(receiptUrl: string): string {
     try {
-      const url = new URL(receiptUrl);
+      // R2エンドポイント形式と公開URL（カスタムドメイン）の両方に対応する
+      const publicDomain = r2Client.getConfig().publicDomain;
+      const fileKey = extractFileKeyFromReceiptUrl(receiptUrl, publicDomain);
 
-      // R2 URLの形式: https://{account_id}.r2.cloudflarestorage.com/{bucket_name}/{file_key}
-      // パスから先頭の'/'とバケット名を除去してファイルキーとして使用
-      const pathParts = url.pathname.substring(1).split("/");
-
-      // 最初の部分はバケット名なので除去
-      if (pathParts.length > 1) {
-        const encodedFileKey = pathParts.slice(1).join("/");
-
-        // URLデコードを実行
-        const fileKey = decodeURIComponent(encodedFileKey);
-
-        logger.debug("URLからファイルキーを抽出", {
-          receiptUrl,
-          pathname: url.pathname,
-          pathParts,
-          encodedFileKey,
-          decodedFileKey: fileKey,
-        });
+      logger.debug("URLからファイルキーを抽出", {
+        receiptUrl,
+        publicDomain,
+        fileKey,
+      });
 
-        return fileKey;
-      } else {
-        throw new Error("ファイルキーの抽出に失敗しました");
-      }
+      return fileKey;
     } catch (error) {
       logger.error("URLからのファイルキー抽出エラー", {
         receiptUrl,
@@ -9,6 +9,92 @@ import { handleError, createValidationError, createNotFoundError } from "../util
 import type { SubscriptionRepository } from "../repositories/subscription-repository.js";
 import type { CreateSubscriptionDto, UpdateSubscriptionDto } from "../types/d1-dtos.js";
 import type { R2ClientInterface } from "../services/r2-client.js";
+import { CURRENCY_CODES } from "../types/d1-models.js";
+
+/** 一括作成で受け付けるサブスクリプションの最大件数 */
+const MAX_BATCH_SUBSCRIPTIONS = 500;
+
+/**
+ * サブスクリプション作成DTOのバリデーションを行う
+ * @param body サブスクリプション作成DTO
+ * @throws バリデーションエラー
+ */
+function validateCreateSubscriptionDto(body: CreateSubscriptionDto): void {
+  if (!body.name || typeof body.name !== "string") {
+    throw createValidationError(
+      "サービス名は必須で文字列である必要があります",
+      "name",
+      body.name,
+      "string required",
+    );
+  }
+
+  if (!body.amount || typeof body.amount !== "number") {
+    throw createValidationError(
+      "金額は必須で数値である必要があります",
+      "amount",
+      body.amount,
+      "number required",
+    );
+  }
+
+  if (!body.billing_cycle || typeof body.billing_cycle !== "string") {
+    throw createValidationError(
+      "請求サイクルは必須で文字列である必要があります",
+      "billing_cycle",
+      body.billing_cycle,
+      "string required",
+    );
+  }
+
+  // billing_cycleの値チェック
+  if (body.billing_cycle !== "monthly" && body.billing_cycle !== "annual") {
+    throw createValidationError(
+      "請求サイクルは'monthly'または'annual'である必要があります",
+      "billing_cycle",
+      body.billing_cycle,
+      "'monthly' or 'annual' required",
+    );
+  }
+
+  if (!body.start_date || typeof body.start_date !== "string") {
+    throw createValidationError(
+      "開始日は必須で文字列である必要があります",
+      "start_date",
+      body.start_date,
+      "string required (YYYY-MM-DD format)",
+    );
+  }
+
+  if (!body.category || typeof body.category !== "string") {
+    throw createValidationError(
+      "カテゴリは必須で文字列である必要があります",
+      "category",
+      body.category,
+      "string required",
+    );
+  }
+
+  // 日付形式のバリデーション（YYYY-MM-DD）
+  const datePattern = /^\d{4}-\d{2}-\d{2}$/;
+  if (!datePattern.test(body.start_date)) {
+    throw createValidationError(
+      "開始日はYYYY-MM-DD形式である必要があります",
+      "start_date",
+      body.start_date,
+      "YYYY-MM-DD format required",
+    );
+  }
+
+  if (body.currency !== undefined && !CURRENCY_CODES.includes(body.currency)) {
+    throw createValidationError(
+      `通貨は${CURRENCY_CODES.join("、")}のいずれかである必要があります`,
+      "currency",
+      body.currency,
+      CURRENCY_CODES.join(" | "),
+    );
+  }
+}
 
 /**
  * サブスクリプションルーターを作成
@@ -129,92 +215,84 @@ export function createSubscriptionsRouter(
       });
 
       // バリデーション
-      if (!body.name || typeof body.name !== "string") {
-        throw createValidationError(
-          "サービス名は必須で文字列である必要があります",
-          "name",
-          body.name,
-          "string required",
-        );
-      }
+      validateCreateSubscriptionDto(body);
 
-      if (!body.amount || typeof body.amount !== "number") {
-        throw createValidationError(
-          "金額は必須で数値である必要があります",
-          "amount",
-          body.amount,
-          "number required",
-        );
-      }
+      // サブスクリプションを作成
+      const subscription = await subscriptionRepository.create(body, user.id);
 
-      if (!body.billing_cycle || typeof body.billing_cycle !== "string") {
-        throw createValidationError(
-          "請求サイクルは必須で文字列である必要があります",
-          "billing_cycle",
-          body.billing_cycle,
-          "string required",
-        );
-      }
+      logger.info("サブスクリプションを作成しました", {
+        userId: user.id,
+        subscriptionId: subscription.id,
+        name: subscription.name,
+      });
 
-      // billing_cycleの値チェック
-      if (body.billing_cycle !== "monthly" && body.billing_cycle !== "annual") {
-        throw createValidationError(
-          "請求サイクルは'monthly'または'annual'である必要があります",
-          "billing_cycle",
-          body.billing_cycle,
-          "'monthly' or 'annual' required",
-        );
-      }
+      return c.json(
+        {
+          success: true,
+          subscription,
+          timestamp: new Date().toISOString(),
+        },
+        201,
+      );
+    } catch (error) {
+      return handleError(c, error instanceof Error ? error : new Error(String(error)), {
+        context: "サブスクリプション作成",
+      });
+    }
+  });
 
-      if (!body.start_date || typeof body.start_date !== "string") {
-        throw createValidationError(
-          "開始日は必須で文字列である必要があります",
-          "start_date",
-          body.start_date,
-          "string required (YYYY-MM-DD format)",
-        );
+  // POST /api/v1/subscriptions/batch - サブスクリプションを一括作成（エクスポートファイルの取り込み用、すべて登録するか何も登録しない）
+  subscriptionsApp.post("/batch", async (c: Context) => {
+    try {
+      const user = c.get("user");
+
+      if (!user) {
+        logger.error("ユーザー情報が見つかりません");
+        throw createNotFoundError("ユーザー情報が見つかりません");
       }
 
-      if (!body.category || typeof body.category !== "string") {
+      const body = await c.req.json<{ subscriptions?: CreateSubscriptionDto[] }>();
+
+      if (!Array.isArray(body.subscriptions) || body.subscriptions.length === 0) {
         throw createValidationError(
-          "カテゴリは必須で文字列である必要があります",
-          "category",
-          body.category,
-          "string required",
+          "サブスクリプションの一覧は必須で1件以上である必要があります",
+          "subscriptions",
+          body.subscriptions,
+          "non-empty array required",
         );
       }
 
-      // 日付形式のバリデーション（YYYY-MM-DD）
-      const datePattern = /^\d{4}-\d{2}-\d{2}$/;
-      if (!datePattern.test(body.start_date)) {
+      if (body.subscriptions.length > MAX_BATCH_SUBSCRIPTIONS) {
         throw createValidationError(
-          "開始日はYYYY-MM-DD形式である必要があります",
-          "start_date",
-          body.start_date,
-          "YYYY-MM-DD format required",
+          `一度に作成できるサブスクリプションは${MAX_BATCH_SUBSCRIPTIONS}件までです`,
+          "subscriptions",
+          body.subscriptions.length,
+          `at most ${MAX_BATCH_SUBSCRIPTIONS} items`,
         );
       }
 
-      // サブスクリプションを作成
-      const subscription = await subscriptionRepository.create(body, user.id);
+      // 1件でも不正なサブスクリプションがあれば何も登録しない
+      body.subscriptions.forEach(validateCreateSubscriptionDto);
 
-      logger.info("サブスクリプションを作成しました", {
+      const subscriptions = await subscriptionRepository.createMany(body.subscriptions, user.id);
+
+      logger.info("サブスクリプションを一括作成しました", {
         userId: user.id,
-        subscriptionId: subscription.id,
-        name: subscription.name,
+        count: subscriptions.length,
       });
 
       return c.json(
         {
           success: true,
-          subscription,
+          subscriptions,
+          count: subscriptions.length,
           timestamp: new Date().toISOString(),
         },
         201,
       );
     } catch (error) {
       return handleError(c, error instanceof Error ? error : new Error(String(error)), {
-        context: "サブスクリプション作成",
+        context: "サブスクリプション一括作成",
       });
     }
   });
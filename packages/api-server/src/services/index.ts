@@ -35,7 +35,7 @@ export function createEnvironmentAwareR2Client(
       throw new Error("Workers環境ではアカウントIDが必要です");
     }
     // R2WorkerClientを使用
-    return createR2WorkerClient(r2Bucket, config.bucketName, accountId);
+    return createR2WorkerClient(r2Bucket, config.bucketName, accountId, config.publicDomain);
   }
 
   // Node.js環境の場合（AWS SDK使用）
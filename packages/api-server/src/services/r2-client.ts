@@ -12,6 +12,7 @@ import {
 } from "@aws-sdk/client-s3";
 import { getSignedUrl } from "@aws-sdk/s3-request-presigner";
 import type { R2Config } from "../types/config.js";
+import { buildReceiptUrl } from "../utils/receipt-url.js";
 import { logger } from "../utils/logger.js";
 import { withR2Retry } from "../utils/retry.js";
 import { ErrorCode, createR2Error } from "../utils/error-handler.js";
@@ -399,7 +400,7 @@ export class R2Client implements R2ClientInterface {
    * @returns パブリックURL
    */
   private generatePublicUrl(key: string): string {
-    // CloudflareのR2パブリックURLの正しい形式
+    // 公開ドメインが未設定の場合はR2エンドポイント形式
     // https://<account-id>.r2.cloudflarestorage.com/<bucket-name>/<key>
 
     // エンドポイントからアカウントIDを抽出
@@ -408,8 +409,11 @@ export class R2Client implements R2ClientInterface {
       accountId = accountId.replace("https://", "").replace(".r2.cloudflarestorage.com", "");
     }
 
-    // R2のパブリックURLはバケット名を含む
-    return `https://${accountId}.r2.cloudflarestorage.com/${this.bucketName}/${key}`;
+    return buildReceiptUrl(key, {
+      accountId,
+      bucketName: this.bucketName,
+      publicDomain: this.config.publicDomain,
+    });
   }
 
   /**
@@ -462,6 +466,7 @@ export class R2Client implements R2ClientInterface {
       secretAccessKey: "[HIDDEN]",
       bucketName: this.config.bucketName,
       region: this.config.region,
+      publicDomain: this.config.publicDomain,
     };
   }
 }
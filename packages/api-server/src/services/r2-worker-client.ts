@@ -6,6 +6,7 @@
 import type { R2ClientInterface } from "./r2-client.js";
 import { logger } from "../utils/logger.js";
 import { withR2Retry } from "../utils/retry.js";
+import { buildReceiptUrl } from "../utils/receipt-url.js";
 import { ErrorCode, createR2Error } from "../utils/error-handler.js";
 
 /**
@@ -17,10 +18,12 @@ export class R2WorkerClient implements R2ClientInterface {
     private r2Bucket: R2Bucket,
     private bucketName: string,
     private accountId: string,
+    private publicDomain?: string,
   ) {
     logger.info("Workers環境用R2クライアントを初期化しました", {
       bucketName: this.bucketName,
       accountId: this.accountId,
+      publicDomain: this.publicDomain,
     });
   }
 
@@ -215,11 +218,14 @@ export class R2WorkerClient implements R2ClientInterface {
   /**
    * パブリックURLを生成
    * @param key ファイルキー
-   * @returns 実際のR2パブリックURL
+   * @returns 公開ドメインが設定されている場合は公開URL、それ以外はR2エンドポイント形式のURL
    */
   private generatePublicUrl(key: string): string {
-    // CloudflareのR2パブリックURLの正しい形式（バケット名を含む）
-    return `https://${this.accountId}.r2.cloudflarestorage.com/${this.bucketName}/${key}`;
+    return buildReceiptUrl(key, {
+      accountId: this.accountId,
+      bucketName: this.bucketName,
+      publicDomain: this.publicDomain,
+    });
   }
 
   /**
@@ -339,6 +345,7 @@ export class R2WorkerClient implements R2ClientInterface {
       secretAccessKey: "[WORKERS_BINDING]",
       bucketName: this.bucketName,
       region: "auto",
+      publicDomain: this.publicDomain,
     };
   }
 }
@@ -348,12 +355,14 @@ export class R2WorkerClient implements R2ClientInterface {
  * @param r2Bucket R2バケットバインディング
  * @param bucketName バケット名
  * @param accountId CloudflareアカウントID
+ * @param publicDomain 領収書の公開ドメイン（オプション）
  * @returns Workers環境用R2クライアントインスタンス
  */
 export function createR2WorkerClient(
   r2Bucket: R2Bucket,
   bucketName: string,
   accountId: string,
+  publicDomain?: string,
 ): R2WorkerClient {
-  return new R2WorkerClient(r2Bucket, bucketName, accountId);
+  return new R2WorkerClient(r2Bucket, bucketName, accountId, publicDomain);
 }
@@ -8,6 +8,7 @@ export interface R2Config {
   secretAccessKey: string;
   bucketName: string;
   region: string;
+  /** 領収書の公開ドメインまたはベースURL（カスタムドメイン） */
   publicDomain?: string;
 }
 
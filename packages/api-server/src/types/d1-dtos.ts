@@ -2,6 +2,8 @@
  * D1データベース用のDTO（Data Transfer Object）型定義
  */
 
+import type { CurrencyCode } from "./d1-models.js";
+
 /**
  * Google OAuth ユーザー情報
  */
@@ -49,6 +51,8 @@ export interface CreateExpenseDto {
   category: string; // カテゴリ（後方互換性のため残す）
   category_id?: number; // カテゴリID（推奨）
   description?: string; // 説明（オプション）
+  tax_rate?: number; // 消費税率（10%の場合は0.1、オプション）
+  tax_amount?: number; // 消費税額（amountに含まれる、オプション）
 }
 
 /**
@@ -61,6 +65,8 @@ export interface UpdateExpenseDto {
   category_id?: number; // カテゴリID（推奨）
   description?: string; // 説明
   receipt_url?: string; // 領収書URL（HTTPS）
+  tax_rate?: number | null; // 消費税率（nullの場合は削除）
+  tax_amount?: number | null; // 消費税額（nullの場合は削除）
 }
 
 /**
@@ -73,6 +79,7 @@ export interface CreateSubscriptionDto {
   start_date: string; // YYYY-MM-DD形式
   category: string; // カテゴリ（後方互換性のため残す）
   category_id?: number; // カテゴリID（推奨）
+  currency?: CurrencyCode; // 金額の通貨（未指定の場合は日本円）
 }
 
 /**
@@ -3,7 +3,8 @@
  */
 
 // モデル型
-export type { User, Expense, Subscription } from "./d1-models.js";
+export type { User, Expense, Subscription, CurrencyCode } from "./d1-models.js";
+export { CURRENCY_CODES } from "./d1-models.js";
 
 // DTO型
 export type {
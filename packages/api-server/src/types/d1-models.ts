@@ -40,10 +40,22 @@ export interface Expense {
   category_id: number | null; // カテゴリID（categoriesテーブルへの外部キー）
   description: string | null; // 説明（オプション）
   receipt_url: string | null; // 領収書URL（HTTPS）
+  tax_rate: number | null; // 消費税率（10%の場合は0.1）
+  tax_amount: number | null; // 消費税額（amountに含まれる）
   created_at: string; // RFC3339形式（JST）
   updated_at: string; // RFC3339形式（JST）
 }
 
+/**
+ * 通貨コード（ISO 4217）
+ */
+export type CurrencyCode = "JPY" | "USD" | "EUR" | "GBP";
+
+/**
+ * 対応している通貨コード
+ */
+export const CURRENCY_CODES: readonly CurrencyCode[] = ["JPY", "USD", "EUR", "GBP"];
+
 /**
  * サブスクリプション型
  */
@@ -57,6 +69,7 @@ export interface Subscription {
   category: string; // カテゴリ（後方互換性のため残す）
   category_id: number | null; // カテゴリID（categoriesテーブルへの外部キー）
   is_active: boolean; // 有効/無効（0=無効, 1=有効）
+  currency: CurrencyCode; // 金額の通貨（ISO 4217）
   receipt_path: string | null; // 領収書パス
   created_at: string; // RFC3339形式（JST）
   updated_at: string; // RFC3339形式（JST）
@@ -0,0 +1,43 @@
+/**
+ * 経費の差分同期ユーティリティのテスト
+ */
+
+import { describe, it, expect } from "vitest";
+import {
+  computeExpenseChecksum,
+  isRevisionExpired,
+  normalizeRevision,
+} from "./expense-sync.js";
+
+describe("経費の差分同期", () => {
+  it("リビジョンをUTCのRFC3339形式に正規化する", () => {
+    expect(normalizeRevision("2026-10-15T10:00:00+09:00")).toBe("2026-10-15T01:00:00.000Z");
+    expect(normalizeRevision("2026-10-15T01:00:00.000Z")).toBe("2026-10-15T01:00:00.000Z");
+    expect(normalizeRevision("rev-1")).toBeNull();
+  });
+
+  it("削除履歴の保持期間より古いリビジョンを期限切れとする", () => {
+    const now = new Date("2026-10-15T00:00:00.000Z");
+    expect(isRevisionExpired("2026-10-01T00:00:00.000Z", now)).toBe(false);
+    expect(isRevisionExpired("2026-01-01T00:00:00.000Z", now)).toBe(true);
+  });
+
+  it("デスクトップアプリと同じ規則でチェックサムを算出する", async () => {
+    const a = await computeExpenseChecksum([
+      { id: 2, updated_at: "b" },
+      { id: 1, updated_at: "a" },
+    ]);
+    const b = await computeExpenseChecksum([
+      { id: 1, updated_at: "a" },
+      { id: 2, updated_at: "b" },
+    ]);
+    expect(a).toEqual(b);
+    expect(a.count).toBe(2);
+    expect(a.checksum).toBe("b173219e5a48cb2ef75498db207f085235147922fcad012c30bc977070a28a68");
+
+    // 空の場合は空文字列のSHA-256
+    expect((await computeExpenseChecksum([])).checksum).toBe(
+      "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+    );
+  });
+});
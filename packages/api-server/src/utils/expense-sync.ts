@@ -0,0 +1,68 @@
+/**
+ * 経費の差分同期ユーティリティ
+ *
+ * デスクトップアプリの経費ミラー（features::expenses::sync）との取り決め:
+ * - リビジョンはRFC3339形式（UTC）の時刻で、差分はupdated_at・deleted_atがリビジョン以降のもの
+ * - 削除履歴の保持期間より古いリビジョンには差分を返さず、全件の取得し直しを要求する
+ * - チェックサムはIDの昇順に`id:updated_at`を改行区切りで並べた文字列のSHA-256（16進数）
+ */
+
+/** 削除履歴の保持期間（日） */
+export const EXPENSE_DELETION_RETENTION_DAYS = 90;
+
+/** RFC3339形式の時刻（Date.parseは他の形式も受け付けるため事前に確認する） */
+const RFC3339_PATTERN = /^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(\.\d+)?(Z|[+-]\d{2}:\d{2})$/;
+
+/**
+ * リビジョンを正規化する
+ * @param revision クライアントから受け取ったリビジョン
+ * @returns RFC3339形式（UTC）のリビジョン。解析できない場合はnull
+ */
+export function normalizeRevision(revision: string): string | null {
+  if (!RFC3339_PATTERN.test(revision)) {
+    return null;
+  }
+  const time = Date.parse(revision);
+  if (Number.isNaN(time)) {
+    return null;
+  }
+  return new Date(time).toISOString();
+}
+
+/**
+ * 削除履歴の保持期間の開始時刻を取得する
+ * @param now 現在時刻
+ * @returns これより前の削除履歴は保持されない
+ */
+export function deletionRetentionCutoff(now: Date = new Date()): string {
+  return new Date(
+    now.getTime() - EXPENSE_DELETION_RETENTION_DAYS * 24 * 60 * 60 * 1000,
+  ).toISOString();
+}
+
+/**
+ * リビジョンが古すぎて差分を返せないかどうか
+ * @param revision 正規化済みのリビジョン
+ * @param now 現在時刻
+ * @returns 削除履歴の保持期間より古い場合はtrue
+ */
+export function isRevisionExpired(revision: string, now: Date = new Date()): boolean {
+  return revision < deletionRetentionCutoff(now);
+}
+
+/**
+ * 経費のIDと更新日時からチェックサムを算出する
+ * @param entries 経費のIDと更新日時
+ * @returns 件数とチェックサム
+ */
+export async function computeExpenseChecksum(
+  entries: { id: number; updated_at: string }[],
+): Promise<{ count: number; checksum: string }> {
+  const sorted = [...entries].sort((a, b) => a.id - b.id);
+  const plain = sorted.map((entry) => `${entry.id}:${entry.updated_at}\n`).join("");
+  const digest = await crypto.subtle.digest("SHA-256", new TextEncoder().encode(plain));
+  const checksum = Array.from(new Uint8Array(digest))
+    .map((byte) => byte.toString(16).padStart(2, "0"))
+    .join("");
+  return { count: sorted.length, checksum };
+}
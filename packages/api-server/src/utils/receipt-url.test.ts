@@ -0,0 +1,75 @@
+/**
+ * 領収書URLユーティリティのテスト
+ */
+
+import { describe, it, expect } from "vitest";
+import {
+  buildReceiptUrl,
+  extractFileKeyFromReceiptUrl,
+  normalizePublicBaseUrl,
+} from "./receipt-url.js";
+
+const ACCOUNT_ID = "d6392b1230a419b37b30f45fc13de9cf";
+const FILE_KEY = "users/2/receipts/6/test.png";
+const ENDPOINT_URL = `https://${ACCOUNT_ID}.r2.cloudflarestorage.com/orano-keihi-dev/${FILE_KEY}`;
+const CUSTOM_URL = `https://receipts.example.com/${FILE_KEY}`;
+
+describe("領収書URL", () => {
+  it("公開ドメインの設定値を正規化する", () => {
+    expect(normalizePublicBaseUrl(undefined)).toBeUndefined();
+    expect(normalizePublicBaseUrl("  ")).toBeUndefined();
+    expect(normalizePublicBaseUrl("receipts.example.com")).toBe("https://receipts.example.com");
+    expect(normalizePublicBaseUrl("https://cdn.example.com/receipts/")).toBe(
+      "https://cdn.example.com/receipts",
+    );
+  });
+
+  it("公開ドメインの有無に応じてURLを生成する", () => {
+    const options = { accountId: ACCOUNT_ID, bucketName: "orano-keihi-dev" };
+    expect(buildReceiptUrl(FILE_KEY, options)).toBe(ENDPOINT_URL);
+    expect(
+      buildReceiptUrl(`/${FILE_KEY}`, { ...options, publicDomain: "receipts.example.com" }),
+    ).toBe(CUSTOM_URL);
+  });
+
+  it("R2エンドポイント形式のURLからファイルキーを抽出する", () => {
+    for (const publicDomain of [undefined, "receipts.example.com"]) {
+      expect(extractFileKeyFromReceiptUrl(ENDPOINT_URL, publicDomain)).toBe(FILE_KEY);
+      expect(
+        extractFileKeyFromReceiptUrl(
+          `https://orano-keihi-dev.${ACCOUNT_ID}.r2.cloudflarestorage.com/${FILE_KEY}`,
+          publicDomain,
+        ),
+      ).toBe(FILE_KEY);
+    }
+  });
+
+  it("公開URLからバケット名を取り除かずにファイルキーを抽出する", () => {
+    for (const publicDomain of [undefined, "receipts.example.com"]) {
+      expect(extractFileKeyFromReceiptUrl(CUSTOM_URL, publicDomain)).toBe(FILE_KEY);
+    }
+    expect(extractFileKeyFromReceiptUrl(`https://pub-1234.r2.dev/${FILE_KEY}?v=1`)).toBe(FILE_KEY);
+    expect(
+      extractFileKeyFromReceiptUrl(
+        `https://cdn.example.com/receipts/${FILE_KEY}`,
+        "https://cdn.example.com/receipts",
+      ),
+    ).toBe(FILE_KEY);
+    expect(
+      extractFileKeyFromReceiptUrl(
+        "https://receipts.example.com/users/2/%E9%A0%98%E5%8F%8E%E6%9B%B8.png",
+      ),
+    ).toBe("users/2/領収書.png");
+  });
+
+  it("認識できないURLはエラーになる", () => {
+    expect(() => extractFileKeyFromReceiptUrl("http://receipts.example.com/a.png")).toThrow();
+    expect(() => extractFileKeyFromReceiptUrl("https://receipts.example.com/")).toThrow();
+    expect(() =>
+      extractFileKeyFromReceiptUrl(`https://${ACCOUNT_ID}.r2.cloudflarestorage.com/bucket`),
+    ).toThrow();
+    expect(() =>
+      extractFileKeyFromReceiptUrl("https://other.example.com/a.png", "receipts.example.com"),
+    ).toThrow();
+  });
+});
@@ -0,0 +1,98 @@
+/**
+ * 領収書URLの生成・解析ユーティリティ
+ *
+ * 領収書URLには次の形式がある:
+ * - R2エンドポイント形式: https://{account_id}.r2.cloudflarestorage.com/{bucket}/{file_key}
+ * - バケット名をホストに含む形式: https://{bucket}.{account_id}.r2.cloudflarestorage.com/{file_key}
+ * - 公開URL（r2.devまたはカスタムドメイン）: https://receipts.example.com/{file_key}
+ *
+ * デスクトップアプリ側の receipts::url と同じ規則で解析する
+ */
+
+const R2_ENDPOINT_HOST = "r2.cloudflarestorage.com";
+const R2_DEV_HOST_SUFFIX = ".r2.dev";
+
+/**
+ * 公開ドメインの設定値を正規化する
+ * @param publicDomain ドメイン名（例: receipts.example.com）またはベースURL（例: https://cdn.example.com/receipts）
+ * @returns 末尾のスラッシュを除いたベースURL。未設定の場合はundefined
+ */
+export function normalizePublicBaseUrl(publicDomain?: string): string | undefined {
+  const trimmed = publicDomain?.trim().replace(/\/+$/, "");
+  if (!trimmed) {
+    return undefined;
+  }
+  return trimmed.startsWith("https://") ? trimmed : `https://${trimmed}`;
+}
+
+/**
+ * ファイルキーから領収書URLを生成する
+ * @param key ファイルキー
+ * @param options アカウントID・バケット名・公開ドメイン
+ * @returns 公開ドメインが設定されている場合は公開URL、それ以外はR2エンドポイント形式のURL
+ */
+export function buildReceiptUrl(
+  key: string,
+  options: { accountId: string; bucketName: string; publicDomain?: string },
+): string {
+  const normalizedKey = key.replace(/^\/+/, "");
+  const publicBaseUrl = normalizePublicBaseUrl(options.publicDomain);
+  if (publicBaseUrl) {
+    return `${publicBaseUrl}/${normalizedKey}`;
+  }
+  return `https://${options.accountId}.${R2_ENDPOINT_HOST}/${options.bucketName}/${normalizedKey}`;
+}
+
+/**
+ * 領収書URLからファイルキーを抽出する
+ * @param receiptUrl 領収書URL
+ * @param publicDomain 公開ドメイン（設定されている場合）
+ * @returns URLデコード済みのファイルキー
+ * @throws 認識できない形式のURLの場合
+ */
+export function extractFileKeyFromReceiptUrl(receiptUrl: string, publicDomain?: string): string {
+  const publicBaseUrl = normalizePublicBaseUrl(publicDomain);
+
+  // 設定された公開ドメイン配下のURLは、ベース以降をそのままファイルキーとする
+  if (publicBaseUrl && receiptUrl.startsWith(`${publicBaseUrl}/`)) {
+    const path = receiptUrl.substring(publicBaseUrl.length + 1).split(/[?#]/)[0] ?? "";
+    return toFileKey(path);
+  }
+
+  const url = new URL(receiptUrl);
+  if (url.protocol !== "https:") {
+    throw new Error("URLの形式が正しくありません");
+  }
+  const path = url.pathname.substring(1);
+  const host = url.hostname;
+
+  if (host === R2_ENDPOINT_HOST || host.endsWith(`.${R2_ENDPOINT_HOST}`)) {
+    const subdomain = host.slice(0, -R2_ENDPOINT_HOST.length).replace(/\.$/, "");
+
+    // {bucket}.{account_id}.r2.cloudflarestorage.com の場合はパス全体がファイルキー
+    if (subdomain.includes(".")) {
+      return toFileKey(path);
+    }
+
+    // パスの先頭がバケット名
+    const separator = path.indexOf("/");
+    if (separator < 0) {
+      throw new Error("ファイルキーの抽出に失敗しました");
+    }
+    return toFileKey(path.substring(separator + 1));
+  }
+
+  // r2.devやカスタムドメインはバケットのルートを公開しているため、パス全体がファイルキー
+  if (host.endsWith(R2_DEV_HOST_SUFFIX) || !publicBaseUrl) {
+    return toFileKey(path);
+  }
+
+  throw new Error(`認識できない領収書URLのホストです: ${host}`);
+}
+
+function toFileKey(encodedPath: string): string {
+  if (!encodedPath || encodedPath.endsWith("/")) {
+    throw new Error("ファイルキーの抽出に失敗しました");
+  }
+  return decodeURIComponent(encodedPath);
+}
@@ -25,6 +25,7 @@ export interface Env {
   R2_SECRET_ACCESS_KEY?: string;
   R2_BUCKET_NAME?: string;
   R2_REGION?: string;
+  R2_PUBLIC_BASE_URL?: string;
 
   // Google OAuth設定（機密情報）
   GOOGLE_CLIENT_ID?: string;
@@ -0,0 +1,151 @@
+//! SQLiteのクエリ性能のベンチマーク
+//!
+//! インメモリのSQLiteに1万・10万・100万件の経費を投入し、次のクエリを計測します。
+//! - `get_expenses`コマンドが読み込むミラーテーブルの一覧取得（月指定・全期間）
+//! - ローカルの経費テーブルの全件検索（`find_expenses`）
+//! - カテゴリ別の件数・合計金額の集計（`GROUP BY category`）
+//!
+//! 実行方法: `cargo bench --bench db_benchmark`
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rusqlite::{params, Connection};
+use subscription_memo_lib::features::expenses::models::{Expense, ExpenseFilter};
+use subscription_memo_lib::features::expenses::{repository, sync};
+use subscription_memo_lib::shared::database::connection::open_and_migrate_in_memory_database;
+
+/// 計測する件数
+const ROW_COUNTS: [usize; 3] = [10_000, 100_000, 1_000_000];
+
+/// ミラーテーブルに投入するユーザーID
+const USER_ID: &str = "bench-user";
+
+/// 投入する経費のカテゴリ（初期データのカテゴリと同じ）
+const CATEGORIES: [&str; 6] = [
+    "交通費",
+    "飲食費",
+    "通信費",
+    "消耗品費",
+    "接待交際費",
+    "その他",
+];
+
+/// i番目の経費を作成する（日付は2020年1月から月ごとに均等に分散させる）
+fn bench_expense(i: usize) -> Expense {
+    let month = i % 60;
+    let date = format!(
+        "{:04}-{:02}-{:02}",
+        2020 + month / 12,
+        month % 12 + 1,
+        i % 28 + 1
+    );
+    let timestamp = format!("{date}T12:00:00+09:00");
+
+    Expense {
+        id: i as i64 + 1,
+        date,
+        amount: (i % 10_000) as f64 + 100.0,
+        category: CATEGORIES[i % CATEGORIES.len()].to_string(),
+        category_id: None,
+        description: Some(format!("ベンチマーク用の経費{i}")),
+        receipt_url: None,
+        created_at: timestamp.clone(),
+        updated_at: timestamp,
+        category_color: None,
+        category_icon: None,
+        unknown_category: false,
+        tax_rate: Some(0.1),
+        tax_amount: None,
+        latitude: None,
+        longitude: None,
+        location_opt_in: false,
+        merchant_id: None,
+        missing_receipt: false,
+    }
+}
+
+/// 指定件数の経費をミラーテーブルと経費テーブルに投入した接続を作成する
+fn seeded_connection(rows: usize) -> Connection {
+    let mut conn = open_and_migrate_in_memory_database().expect("データベースの初期化に失敗");
+
+    let tx = conn.transaction().unwrap();
+    {
+        let mut insert = tx
+            .prepare(
+                "INSERT INTO expenses (date, amount, category, description, user_id, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )
+            .unwrap();
+        for i in 0..rows {
+            let expense = bench_expense(i);
+            sync::upsert_mirrored(&tx, USER_ID, &expense).unwrap();
+            insert
+                .execute(params![
+                    expense.date,
+                    expense.amount,
+                    expense.category,
+                    expense.description,
+                    USER_ID,
+                    expense.created_at,
+                    expense.updated_at,
+                ])
+                .unwrap();
+        }
+    }
+    tx.commit().unwrap();
+
+    conn
+}
+
+fn bench_queries(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sqlite_queries");
+    // 100万件では1回の計測に時間がかかるため、サンプル数を最小限にする
+    group.sample_size(10);
+
+    for rows in ROW_COUNTS {
+        let conn = seeded_connection(rows);
+        let filter = ExpenseFilter::new();
+        group.throughput(Throughput::Elements(rows as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("get_expenses_month", rows),
+            &rows,
+            |b, _| {
+                b.iter(|| sync::list_mirrored(&conn, USER_ID, Some("2022-06"), &filter).unwrap())
+            },
+        );
+
+        group.bench_with_input(BenchmarkId::new("get_expenses_all", rows), &rows, |b, _| {
+            b.iter(|| sync::list_mirrored(&conn, USER_ID, None, &filter).unwrap())
+        });
+
+        group.bench_with_input(BenchmarkId::new("find_all", rows), &rows, |b, _| {
+            b.iter(|| repository::find_expenses(&conn, USER_ID, &filter).unwrap())
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("count_by_category", rows),
+            &rows,
+            |b, _| {
+                b.iter(|| {
+                    repository::get_expense_count_by_category(&conn, USER_ID, None, None).unwrap()
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("count_by_category_month", rows),
+            &rows,
+            |b, _| {
+                b.iter(|| {
+                    repository::get_expense_count_by_category(&conn, USER_ID, Some(2022), Some(6))
+                        .unwrap()
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_queries);
+criterion_main!(benches);
@@ -1,4 +1,5 @@
 use std::env;
+use std::path::Path;
 
 fn main() {
     // 必須の環境変数をコンパイル時に埋め込む
@@ -9,6 +10,12 @@ fn main() {
     embed_env_var("API_MAX_RETRIES", false);
     embed_env_var("LOG_LEVEL", false);
     embed_env_var("ENVIRONMENT", false);
+    embed_env_var("UPDATE_BASE_URL", false);
+    embed_env_var("ENABLE_QUERY_CONSOLE", false);
+    embed_env_var("ENABLE_RELEASE_SMOKE_TEST", false);
+
+    // ビルド情報（コミットハッシュ・ビルド日）を埋め込む
+    embed_build_metadata();
 
     // Tauriのビルド処理を実行
     tauri_build::build()
@@ -20,6 +27,9 @@ fn main() {
 /// * `var_name` - 環境変数名
 /// * `required` - 必須かどうか
 fn embed_env_var(var_name: &str, required: bool) {
+    // 環境変数を変更した場合に埋め込み直す
+    println!("cargo:rerun-if-env-changed={var_name}");
+
     match env::var(var_name) {
         Ok(value) => {
             // 環境変数が設定されている場合は、cargo:rustc-env で埋め込む
@@ -37,3 +47,61 @@ fn embed_env_var(var_name: &str, required: bool) {
         }
     }
 }
+
+/// ビルド情報をコンパイル時に埋め込む
+///
+/// vergenと同じ環境変数名（`VERGEN_GIT_SHA`, `VERGEN_BUILD_DATE`）で埋め込みます。
+/// CIなどで環境変数が設定されている場合はその値を優先します。
+fn embed_build_metadata() {
+    println!("cargo:rerun-if-env-changed=VERGEN_GIT_SHA");
+    println!("cargo:rerun-if-env-changed=VERGEN_BUILD_DATE");
+    watch_git_head();
+
+    let git_sha = env::var("VERGEN_GIT_SHA")
+        .ok()
+        .or_else(|| git_output(&["rev-parse", "HEAD"]));
+    match git_sha {
+        Some(sha) => println!("cargo:rustc-env=VERGEN_GIT_SHA={sha}"),
+        None => eprintln!("警告: コミットハッシュを取得できませんでした"),
+    }
+
+    let build_date = env::var("VERGEN_BUILD_DATE")
+        .unwrap_or_else(|_| chrono::Utc::now().format("%Y-%m-%d").to_string());
+    println!("cargo:rustc-env=VERGEN_BUILD_DATE={build_date}");
+}
+
+/// コミットが変わった場合に再実行されるよう、HEADと参照先のファイルを監視する
+///
+/// `.git/HEAD`はブランチを切り替えたときにしか変わらないため、HEADが指すブランチの
+/// 参照ファイルと`packed-refs`も監視します。パスはworktreeでも正しい場所を指すよう
+/// `git rev-parse --git-path`で解決します。
+fn watch_git_head() {
+    let mut refs = vec!["HEAD".to_string(), "packed-refs".to_string()];
+    refs.extend(git_output(&["symbolic-ref", "-q", "HEAD"]));
+
+    for git_ref in refs {
+        if let Some(path) = git_output(&["rev-parse", "--git-path", &git_ref]) {
+            // 存在しないパスを指定すると毎回再実行されるため、存在するものだけ監視する
+            if Path::new(&path).exists() {
+                println!("cargo:rerun-if-changed={path}");
+            }
+        }
+    }
+}
+
+/// gitコマンドを実行し、標準出力を取得する
+///
+/// # 引数
+/// * `args` - gitコマンドの引数
+///
+/// # 戻り値
+/// 前後の空白を除いた標準出力（失敗した場合や空の場合はNone）
+fn git_output(args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let stdout = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!stdout.is_empty()).then_some(stdout)
+}
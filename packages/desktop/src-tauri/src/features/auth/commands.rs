@@ -1,7 +1,9 @@
+use crate::features::auth::middleware::AuthMiddleware;
 use crate::features::auth::models::{AuthState, User};
 use crate::features::auth::secure_storage::{SecureStorage, StoredAuthInfo};
 use crate::features::auth::service::AuthService;
-use chrono::Utc;
+use crate::features::auth::storage_backend::{secure_storage_status, SecureStorageStatus};
+use crate::shared::utils::get_current_jst_timestamp;
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 use tauri::{AppHandle, State};
@@ -143,13 +145,16 @@ pub async fn wait_for_auth_completion(
     let auth_info = StoredAuthInfo {
         session_token: auth_result.access_token.clone(),
         user_id: auth_result.user.id.clone(),
-        last_login: Utc::now().to_rfc3339(),
+        last_login: get_current_jst_timestamp(),
     };
 
-    secure_storage.save_auth_info(&auth_info).map_err(|e| {
-        log::error!("認証情報の保存エラー: {e}");
-        format!("認証情報の保存に失敗しました: {e}")
-    })?;
+    secure_storage
+        .save_auth_info(&auth_info)
+        .await
+        .map_err(|e| {
+            log::error!("認証情報の保存エラー: {e}");
+            format!("認証情報の保存に失敗しました: {e}")
+        })?;
 
     let response = WaitForAuthResponse {
         user: auth_result.user,
@@ -196,13 +201,20 @@ pub async fn validate_session(
 ///
 /// # 引数
 /// * `auth_service` - 認証サービス
+/// * `auth_middleware` - 認証ミドルウェア
 ///
 /// # 戻り値
 /// ログアウト結果
 #[tauri::command]
-pub async fn logout(auth_service: State<'_, AuthService>) -> Result<(), String> {
+pub async fn logout(
+    auth_service: State<'_, AuthService>,
+    auth_middleware: State<'_, AuthMiddleware>,
+) -> Result<(), String> {
     log::info!("ログアウトコマンドを実行");
 
+    // ログアウト後にキャッシュ済みのセッションで認証されないようにする
+    auth_middleware.invalidate_cache();
+
     // セキュアストレージから認証情報を削除
     auth_service.logout().await.map_err(|e| {
         log::error!("ログアウト処理エラー: {e}");
@@ -235,7 +247,7 @@ pub async fn get_auth_state(
         Some(t) => Some(t),
         None => {
             let secure_storage = SecureStorage::new(app_handle);
-            secure_storage.get_session_token().ok().flatten()
+            secure_storage.get_session_token().await.ok().flatten()
         }
     };
 
@@ -273,7 +285,7 @@ pub async fn get_stored_auth_info(app_handle: AppHandle) -> Result<Option<Stored
     log::debug!("保存された認証情報取得コマンドを実行");
 
     let secure_storage = SecureStorage::new(app_handle);
-    let auth_info = secure_storage.get_auth_info().map_err(|e| {
+    let auth_info = secure_storage.get_auth_info().await.map_err(|e| {
         log::error!("認証情報の取得エラー: {e}");
         format!("認証情報の取得に失敗しました: {e}")
     })?;
@@ -304,3 +316,14 @@ pub async fn cleanup_expired_sessions(
     log::info!("APIサーバー経由の認証では、セッション管理はAPIサーバー側で行われます");
     Ok(0)
 }
+
+/// セキュアストレージの状態を取得する
+///
+/// フォールバックストレージを使用している場合は、利用者に表示する警告を含みます。
+///
+/// # 戻り値
+/// セキュアストレージの状態
+#[tauri::command]
+pub async fn get_secure_storage_status() -> Result<SecureStorageStatus, String> {
+    Ok(secure_storage_status())
+}
@@ -32,6 +32,10 @@ pub struct LoopbackServer {
 impl LoopbackServer {
     /// 新しいループバックサーバーを作成する
     ///
+    /// ポートは固定せず、OSが割り当てた空きポートを使用します。
+    /// リダイレクトURIはこのポートから組み立てるため（[`Self::get_redirect_uri`]）、
+    /// 特定のポートが使用中でも認証フローは失敗しません。
+    ///
     /// # 戻り値
     /// (LoopbackServer, ポート番号)
     pub fn new() -> Result<(Self, u16), Box<dyn std::error::Error + Send + Sync>> {
@@ -308,4 +312,19 @@ mod tests {
         assert!(port > 0);
         assert!(server.get_redirect_uri().contains(&port.to_string()));
     }
+
+    #[tokio::test]
+    async fn test_loopback_server_uses_free_port_when_busy() {
+        // 1つ目のサーバーがポートを使用中でも、2つ目は別の空きポートで起動できる
+        let (mut first, first_port) = LoopbackServer::new().unwrap();
+        let _receiver = first.start_and_wait().await.unwrap();
+
+        let (mut second, second_port) = LoopbackServer::new().unwrap();
+        assert_ne!(first_port, second_port);
+        assert!(second.start_and_wait().await.is_ok());
+        assert_eq!(
+            second.get_redirect_uri(),
+            format!("http://127.0.0.1:{second_port}/callback")
+        );
+    }
 }
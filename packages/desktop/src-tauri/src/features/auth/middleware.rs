@@ -1,19 +1,72 @@
 use crate::features::auth::models::{AuthError, User};
 use crate::features::auth::service::AuthService;
 use crate::features::security::service::SecurityService;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{Runtime, Wry};
+
+/// セッション検証結果のキャッシュの既定の有効期間
+pub const DEFAULT_SESSION_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// セッション検証結果のキャッシュ
+///
+/// 検証に成功したセッションのみを保持し、TTLを過ぎたエントリは参照のたびに削除します。
+struct SessionCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (User, Instant)>>,
+}
+
+impl SessionCache {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// TTL内のキャッシュを取得する（期限切れのエントリはこのとき削除する）
+    fn get(&self, token: &str, now: Instant) -> Option<User> {
+        let mut entries = self.entries.lock().ok()?;
+        entries.retain(|_, (_, cached_at)| now.duration_since(*cached_at) < self.ttl);
+        entries.get(token).map(|(user, _)| user.clone())
+    }
+
+    fn insert(&self, token: &str, user: User, now: Instant) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(token.to_string(), (user, now));
+        }
+    }
+
+    fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+}
 
 /// API認証ミドルウェア
 /// すべてのAPIリクエストに認証トークンを含めて、不正アクセスを検出・処理する
-#[derive(Clone)]
-pub struct AuthMiddleware {
+pub struct AuthMiddleware<R: Runtime = Wry> {
     /// 認証サービス（APIサーバー経由）
-    auth_service: Arc<AuthService>,
+    auth_service: Arc<AuthService<R>>,
     /// セキュリティサービス
-    security_service: Arc<SecurityService>,
+    security_service: SecurityService,
+    /// セッション検証結果のキャッシュ（`with_cache`で有効化）
+    session_cache: Option<Arc<SessionCache>>,
 }
 
-impl AuthMiddleware {
+impl<R: Runtime> Clone for AuthMiddleware<R> {
+    fn clone(&self) -> Self {
+        Self {
+            auth_service: Arc::clone(&self.auth_service),
+            security_service: self.security_service.clone(),
+            session_cache: self.session_cache.clone(),
+        }
+    }
+}
+
+impl<R: Runtime> AuthMiddleware<R> {
     /// 新しいAuthMiddlewareを作成する
     ///
     /// # 引数
@@ -22,10 +75,30 @@ impl AuthMiddleware {
     ///
     /// # 戻り値
     /// AuthMiddlewareインスタンス
-    pub fn new(auth_service: Arc<AuthService>, security_service: Arc<SecurityService>) -> Self {
+    pub fn new(auth_service: Arc<AuthService<R>>, security_service: SecurityService) -> Self {
         Self {
             auth_service,
             security_service,
+            session_cache: None,
+        }
+    }
+
+    /// セッション検証結果をキャッシュする
+    ///
+    /// 呼び出し頻度の高いコマンドで毎回セッションを検証しないよう、
+    /// 検証に成功したセッションを`ttl`の間再利用します。
+    ///
+    /// # 引数
+    /// * `ttl` - キャッシュの有効期間
+    pub fn with_cache(mut self, ttl: Duration) -> Self {
+        self.session_cache = Some(Arc::new(SessionCache::new(ttl)));
+        self
+    }
+
+    /// キャッシュしたセッション検証結果をすべて破棄する（ログアウト時など）
+    pub fn invalidate_cache(&self) {
+        if let Some(cache) = &self.session_cache {
+            cache.clear();
         }
     }
 
@@ -73,6 +146,19 @@ impl AuthMiddleware {
             return Err(AuthError::InvalidToken);
         }
 
+        // キャッシュ済みの検証結果があれば再利用する
+        if let Some(user) = self
+            .session_cache
+            .as_ref()
+            .and_then(|cache| cache.get(token, Instant::now()))
+        {
+            log::debug!(
+                "APIリクエスト認証成功（キャッシュ）: user_id={}, path={request_path}",
+                user.id
+            );
+            return Ok(user);
+        }
+
         // 認証サービスでセッションを検証
         match self.auth_service.validate_session(token.to_string()).await {
             Ok(user) => {
@@ -80,6 +166,9 @@ impl AuthMiddleware {
                     "APIリクエスト認証成功: user_id={}, path={request_path}",
                     user.id
                 );
+                if let Some(cache) = &self.session_cache {
+                    cache.insert(token, user.clone(), Instant::now());
+                }
                 Ok(user)
             }
             Err(e) => {
@@ -353,6 +442,54 @@ mod tests {
         assert!(!is_public_endpoint("/admin/users"));
     }
 
+    fn test_user(id: &str) -> User {
+        User {
+            id: id.to_string(),
+            email: format!("{id}@example.com"),
+            name: id.to_string(),
+            picture_url: None,
+            google_id: format!("google-{id}"),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_session_cache_returns_entries_within_ttl() {
+        let cache = SessionCache::new(Duration::from_secs(30));
+        let now = Instant::now();
+        cache.insert("token-a", test_user("a"), now);
+
+        let cached = cache.get("token-a", now + Duration::from_secs(29)).unwrap();
+        assert_eq!(cached.id, "a");
+        assert!(cache.get("token-b", now).is_none());
+    }
+
+    #[test]
+    fn test_session_cache_evicts_expired_entries_on_lookup() {
+        let cache = SessionCache::new(Duration::from_secs(30));
+        let now = Instant::now();
+        cache.insert("token-a", test_user("a"), now);
+        cache.insert("token-b", test_user("b"), now + Duration::from_secs(20));
+
+        // 別のトークンの参照でも期限切れのエントリは削除される
+        assert!(cache
+            .get("token-b", now + Duration::from_secs(30))
+            .is_some());
+        let entries = cache.entries.lock().unwrap();
+        assert!(!entries.contains_key("token-a"));
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[test]
+    fn test_session_cache_clear() {
+        let cache = SessionCache::new(Duration::from_secs(30));
+        let now = Instant::now();
+        cache.insert("token-a", test_user("a"), now);
+        cache.clear();
+        assert!(cache.get("token-a", now).is_none());
+    }
+
     #[test]
     fn test_requires_admin_permission() {
         use auth_helpers::*;
@@ -7,6 +7,7 @@ pub mod repository;
 pub mod secure_storage;
 pub mod service;
 pub mod session;
+pub mod storage_backend;
 
 pub use loopback::*;
 pub use middleware::*;
@@ -15,3 +16,20 @@ pub use repository::*;
 pub use secure_storage::*;
 pub use service::*;
 pub use session::*;
+pub use storage_backend::*;
+
+use crate::shared::capabilities::{Capability, Requirement};
+
+/// 認証機能の利用者向け機能
+pub const CAPABILITIES: &[Capability] = &[
+    Capability::new("auth.login", "capability.auth.login", "start_oauth_flow")
+        .requires(&[Requirement::ApiServerConfigured]),
+    Capability::new("auth.logout", "capability.auth.logout", "logout").mutating(),
+    Capability::new(
+        "auth.cleanup_expired_sessions",
+        "capability.auth.cleanup_expired_sessions",
+        "cleanup_expired_sessions",
+    )
+    .mutating()
+    .internal(),
+];
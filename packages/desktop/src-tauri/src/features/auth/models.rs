@@ -15,8 +15,10 @@ pub struct User {
     /// プロフィール画像URL
     pub picture_url: Option<String>,
     /// 作成日時
+    #[serde(with = "crate::shared::utils::jst_datetime")]
     pub created_at: DateTime<Utc>,
     /// 更新日時
+    #[serde(with = "crate::shared::utils::jst_datetime")]
     pub updated_at: DateTime<Utc>,
 }
 
@@ -43,8 +45,10 @@ pub struct Session {
     /// ユーザーID（nanoId形式）
     pub user_id: String,
     /// 有効期限
+    #[serde(with = "crate::shared::utils::jst_datetime")]
     pub expires_at: DateTime<Utc>,
     /// 作成日時
+    #[serde(with = "crate::shared::utils::jst_datetime")]
     pub created_at: DateTime<Utc>,
 }
 
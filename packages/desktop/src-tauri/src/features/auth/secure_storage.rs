@@ -2,11 +2,26 @@
 ///
 /// Tauri Storeプラグインを使用して、セッショントークンやその他の秘匿情報を
 /// 安全に保存・取得します。
+/// ストレージ障害時の扱いは`storage_backend::ResilientStorage`に集約しています。
+use super::storage_backend::{
+    shared_fallback_state, EncryptedFileBackend, InMemoryBackend, ResilientStorage,
+    SecureStorageBackend, SecureStorageError,
+};
+use crate::features::security::encryption::TokenEncryption;
+use crate::shared::events::{emit_event, AppEvent};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager, Runtime, Wry};
 use tauri_plugin_store::StoreExt;
 
+/// フォールバック先の暗号化ファイル名
+const FALLBACK_STORE_NAME: &str = "secure_fallback.json";
+
+/// フォールバック先の暗号化キーを指定する環境変数
+///
+/// キーをバイナリに埋め込むとすべてのインストールで同じキーになるため、起動時の環境変数からのみ取得します。
+const FALLBACK_KEY_ENV_VAR: &str = "SECURE_STORAGE_FALLBACK_KEY";
+
 /// セキュアストレージのキー定義
 pub struct SecureStorageKeys;
 
@@ -30,16 +45,125 @@ pub struct StoredAuthInfo {
     pub last_login: String,
 }
 
+/// Tauri Storeプラグインを使用するバックエンド
+pub struct TauriStoreBackend<R: Runtime = Wry> {
+    /// Tauriアプリハンドル
+    app_handle: Arc<AppHandle<R>>,
+    /// ストアファイル名
+    store_name: String,
+}
+
+impl<R: Runtime> TauriStoreBackend<R> {
+    /// 新しいバックエンドを作成
+    ///
+    /// # 引数
+    /// * `app_handle` - Tauriアプリハンドル
+    /// * `store_name` - ストアファイル名
+    pub fn new(app_handle: Arc<AppHandle<R>>, store_name: &str) -> Self {
+        Self {
+            app_handle,
+            store_name: store_name.to_string(),
+        }
+    }
+
+    fn store(&self) -> Result<Arc<tauri_plugin_store::Store<R>>, SecureStorageError> {
+        self.app_handle
+            .store(&self.store_name)
+            .map_err(|e| classify_store_error(e, &self.store_name))
+    }
+
+    fn save(store: &tauri_plugin_store::Store<R>, name: &str) -> Result<(), SecureStorageError> {
+        store.save().map_err(|e| classify_store_error(e, name))
+    }
+}
+
+/// ストアのエラーを障害の種類に分類する
+fn classify_store_error(error: tauri_plugin_store::Error, store_name: &str) -> SecureStorageError {
+    use std::io::ErrorKind;
+    use tauri_plugin_store::Error;
+
+    match error {
+        Error::Io(e)
+            if matches!(
+                e.kind(),
+                ErrorKind::WouldBlock | ErrorKind::PermissionDenied
+            ) =>
+        {
+            SecureStorageError::Locked(e.to_string())
+        }
+        Error::Json(_) | Error::Deserialize(_) => SecureStorageError::StoreCorrupted {
+            store: store_name.to_string(),
+        },
+        Error::Io(e) => SecureStorageError::NoBackend(e.to_string()),
+        Error::Tauri(e) => SecureStorageError::NoBackend(e.to_string()),
+        other => SecureStorageError::Other(other.to_string()),
+    }
+}
+
+impl<R: Runtime> SecureStorageBackend for TauriStoreBackend<R> {
+    fn name(&self) -> &str {
+        "tauri-store"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, SecureStorageError> {
+        match self.store()?.get(key) {
+            None => Ok(None),
+            Some(value) => value.as_str().map(|s| Some(s.to_string())).ok_or_else(|| {
+                SecureStorageError::Corrupted {
+                    key: key.to_string(),
+                }
+            }),
+        }
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), SecureStorageError> {
+        let store = self.store()?;
+        store.set(key, value);
+        Self::save(&store, &self.store_name)
+    }
+
+    fn delete(&self, key: &str) -> Result<(), SecureStorageError> {
+        let store = self.store()?;
+        store.delete(key);
+        Self::save(&store, &self.store_name)
+    }
+
+    fn clear(&self) -> Result<(), SecureStorageError> {
+        let store = self.store()?;
+        store.clear();
+        Self::save(&store, &self.store_name)
+    }
+
+    fn reset(&self) -> Result<(), SecureStorageError> {
+        // 解析できないファイルはストアとして開けないため、ファイルごと削除する
+        let path = tauri_plugin_store::resolve_store_path(&self.app_handle, &self.store_name)
+            .map_err(|e| classify_store_error(e, &self.store_name))?;
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(SecureStorageError::Other(e.to_string())),
+        }
+    }
+}
+
 /// セキュアストレージサービス
-#[derive(Clone)]
-pub struct SecureStorage {
+pub struct SecureStorage<R: Runtime = Wry> {
     /// Tauriアプリハンドル
-    app_handle: Arc<AppHandle>,
+    app_handle: Arc<AppHandle<R>>,
     /// ストアファイル名
     store_name: String,
 }
 
-impl SecureStorage {
+impl<R: Runtime> Clone for SecureStorage<R> {
+    fn clone(&self) -> Self {
+        Self {
+            app_handle: Arc::clone(&self.app_handle),
+            store_name: self.store_name.clone(),
+        }
+    }
+}
+
+impl<R: Runtime> SecureStorage<R> {
     /// 新しいSecureStorageを作成する
     ///
     /// # 引数
@@ -47,13 +171,78 @@ impl SecureStorage {
     ///
     /// # 戻り値
     /// SecureStorageインスタンス
-    pub fn new(app_handle: AppHandle) -> Self {
+    pub fn new(app_handle: AppHandle<R>) -> Self {
         Self {
             app_handle: Arc::new(app_handle),
             store_name: "secure.json".to_string(),
         }
     }
 
+    /// フォールバック先のバックエンドを作成する
+    ///
+    /// アプリデータディレクトリの暗号化ファイルを使用します。
+    /// 暗号化キー（`SECURE_STORAGE_FALLBACK_KEY`）が設定されていない場合は、
+    /// 誰でも復号できるファイルを作らないようメモリ上に保存します（再起動で失われます）。
+    fn fallback_backend(&self) -> Box<dyn SecureStorageBackend> {
+        let Some(encryption_key) = std::env::var(FALLBACK_KEY_ENV_VAR)
+            .ok()
+            .filter(|key| !key.is_empty())
+        else {
+            log::error!(
+                "{FALLBACK_KEY_ENV_VAR}が設定されていないため、認証情報をファイルに保存せずメモリ上に保存します"
+            );
+            return Box::new(InMemoryBackend::new());
+        };
+        let encryption = TokenEncryption::new(encryption_key);
+
+        match (self.app_handle.path().app_data_dir(), encryption) {
+            (Ok(dir), Ok(encryption)) => Box::new(EncryptedFileBackend::new(
+                dir.join(FALLBACK_STORE_NAME),
+                encryption,
+            )),
+            (Err(e), _) => {
+                log::error!(
+                    "フォールバック先のディレクトリを取得できないため、メモリ上に保存します: {e}"
+                );
+                Box::new(InMemoryBackend::new())
+            }
+            (_, Err(e)) => {
+                log::error!(
+                    "フォールバック先の暗号化を初期化できないため、メモリ上に保存します: {e}"
+                );
+                Box::new(InMemoryBackend::new())
+            }
+        }
+    }
+
+    /// 障害時の方針を適用したストレージを取得する
+    fn storage(&self) -> ResilientStorage {
+        let app_handle = Arc::clone(&self.app_handle);
+        ResilientStorage::new(
+            Box::new(TauriStoreBackend::new(
+                Arc::clone(&self.app_handle),
+                &self.store_name,
+            )),
+            shared_fallback_state(|| self.fallback_backend()),
+        )
+        .with_notifier(Arc::new(move |event| {
+            if let Err(e) = emit_event(&*app_handle, AppEvent::SecureStorage(event.clone())) {
+                log::warn!("セキュアストレージイベントの送信に失敗しました: {e}");
+            }
+        }))
+    }
+
+    async fn get_value(&self, key: &str) -> Result<Option<String>, String> {
+        self.storage().get(key).await.map_err(|e| e.to_string())
+    }
+
+    async fn set_value(&self, key: &str, value: &str) -> Result<(), String> {
+        self.storage()
+            .set(key, value)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
     /// セッショントークンを保存する
     ///
     /// # 引数
@@ -61,17 +250,9 @@ impl SecureStorage {
     ///
     /// # 戻り値
     /// 処理結果
-    pub fn save_session_token(&self, token: &str) -> Result<(), String> {
-        let store = self
-            .app_handle
-            .store(&self.store_name)
-            .map_err(|e| format!("ストアの取得に失敗しました: {e}"))?;
-
-        store.set(SecureStorageKeys::SESSION_TOKEN, token);
-
-        store
-            .save()
-            .map_err(|e| format!("ストアの保存に失敗しました: {e}"))?;
+    pub async fn save_session_token(&self, token: &str) -> Result<(), String> {
+        self.set_value(SecureStorageKeys::SESSION_TOKEN, token)
+            .await?;
 
         log::info!("セッショントークンを保存しました");
         Ok(())
@@ -81,17 +262,8 @@ impl SecureStorage {
     ///
     /// # 戻り値
     /// セッショントークン（存在しない場合はNone）
-    pub fn get_session_token(&self) -> Result<Option<String>, String> {
-        let store = self
-            .app_handle
-            .store(&self.store_name)
-            .map_err(|e| format!("ストアの取得に失敗しました: {e}"))?;
-
-        let token = store
-            .get(SecureStorageKeys::SESSION_TOKEN)
-            .and_then(|v| v.as_str().map(|s| s.to_string()));
-
-        Ok(token)
+    pub async fn get_session_token(&self) -> Result<Option<String>, String> {
+        self.get_value(SecureStorageKeys::SESSION_TOKEN).await
     }
 
     /// ユーザーIDを保存する
@@ -101,17 +273,8 @@ impl SecureStorage {
     ///
     /// # 戻り値
     /// 処理結果
-    pub fn save_user_id(&self, user_id: &str) -> Result<(), String> {
-        let store = self
-            .app_handle
-            .store(&self.store_name)
-            .map_err(|e| format!("ストアの取得に失敗しました: {e}"))?;
-
-        store.set(SecureStorageKeys::USER_ID, user_id);
-
-        store
-            .save()
-            .map_err(|e| format!("ストアの保存に失敗しました: {e}"))?;
+    pub async fn save_user_id(&self, user_id: &str) -> Result<(), String> {
+        self.set_value(SecureStorageKeys::USER_ID, user_id).await?;
 
         log::debug!("ユーザーIDを保存しました: user_id={user_id}");
         Ok(())
@@ -121,17 +284,8 @@ impl SecureStorage {
     ///
     /// # 戻り値
     /// ユーザーID（存在しない場合はNone）
-    pub fn get_user_id(&self) -> Result<Option<String>, String> {
-        let store = self
-            .app_handle
-            .store(&self.store_name)
-            .map_err(|e| format!("ストアの取得に失敗しました: {e}"))?;
-
-        let user_id = store
-            .get(SecureStorageKeys::USER_ID)
-            .and_then(|v| v.as_str().map(|s| s.to_string()));
-
-        Ok(user_id)
+    pub async fn get_user_id(&self) -> Result<Option<String>, String> {
+        self.get_value(SecureStorageKeys::USER_ID).await
     }
 
     /// 最終ログイン日時を保存する
@@ -141,17 +295,9 @@ impl SecureStorage {
     ///
     /// # 戻り値
     /// 処理結果
-    pub fn save_last_login(&self, last_login: &str) -> Result<(), String> {
-        let store = self
-            .app_handle
-            .store(&self.store_name)
-            .map_err(|e| format!("ストアの取得に失敗しました: {e}"))?;
-
-        store.set(SecureStorageKeys::LAST_LOGIN, last_login);
-
-        store
-            .save()
-            .map_err(|e| format!("ストアの保存に失敗しました: {e}"))?;
+    pub async fn save_last_login(&self, last_login: &str) -> Result<(), String> {
+        self.set_value(SecureStorageKeys::LAST_LOGIN, last_login)
+            .await?;
 
         log::debug!("最終ログイン日時を保存しました: last_login={last_login}");
         Ok(())
@@ -161,17 +307,8 @@ impl SecureStorage {
     ///
     /// # 戻り値
     /// 最終ログイン日時（存在しない場合はNone）
-    pub fn get_last_login(&self) -> Result<Option<String>, String> {
-        let store = self
-            .app_handle
-            .store(&self.store_name)
-            .map_err(|e| format!("ストアの取得に失敗しました: {e}"))?;
-
-        let last_login = store
-            .get(SecureStorageKeys::LAST_LOGIN)
-            .and_then(|v| v.as_str().map(|s| s.to_string()));
-
-        Ok(last_login)
+    pub async fn get_last_login(&self) -> Result<Option<String>, String> {
+        self.get_value(SecureStorageKeys::LAST_LOGIN).await
     }
 
     /// 認証情報をまとめて保存する
@@ -181,10 +318,10 @@ impl SecureStorage {
     ///
     /// # 戻り値
     /// 処理結果
-    pub fn save_auth_info(&self, auth_info: &StoredAuthInfo) -> Result<(), String> {
-        self.save_session_token(&auth_info.session_token)?;
-        self.save_user_id(&auth_info.user_id)?;
-        self.save_last_login(&auth_info.last_login)?;
+    pub async fn save_auth_info(&self, auth_info: &StoredAuthInfo) -> Result<(), String> {
+        self.save_session_token(&auth_info.session_token).await?;
+        self.save_user_id(&auth_info.user_id).await?;
+        self.save_last_login(&auth_info.last_login).await?;
 
         log::info!("認証情報を保存しました: user_id={}", auth_info.user_id);
         Ok(())
@@ -194,10 +331,10 @@ impl SecureStorage {
     ///
     /// # 戻り値
     /// 認証情報（存在しない場合はNone）
-    pub fn get_auth_info(&self) -> Result<Option<StoredAuthInfo>, String> {
-        let session_token = self.get_session_token()?;
-        let user_id = self.get_user_id()?;
-        let last_login = self.get_last_login()?;
+    pub async fn get_auth_info(&self) -> Result<Option<StoredAuthInfo>, String> {
+        let session_token = self.get_session_token().await?;
+        let user_id = self.get_user_id().await?;
+        let last_login = self.get_last_login().await?;
 
         match (session_token, user_id, last_login) {
             (Some(session_token), Some(user_id), Some(last_login)) => Ok(Some(StoredAuthInfo {
@@ -213,19 +350,15 @@ impl SecureStorage {
     ///
     /// # 戻り値
     /// 処理結果
-    pub fn clear_auth_info(&self) -> Result<(), String> {
-        let store = self
-            .app_handle
-            .store(&self.store_name)
-            .map_err(|e| format!("ストアの取得に失敗しました: {e}"))?;
-
-        store.delete(SecureStorageKeys::SESSION_TOKEN);
-        store.delete(SecureStorageKeys::USER_ID);
-        store.delete(SecureStorageKeys::LAST_LOGIN);
-
-        store
-            .save()
-            .map_err(|e| format!("ストアの保存に失敗しました: {e}"))?;
+    pub async fn clear_auth_info(&self) -> Result<(), String> {
+        let storage = self.storage();
+        for key in [
+            SecureStorageKeys::SESSION_TOKEN,
+            SecureStorageKeys::USER_ID,
+            SecureStorageKeys::LAST_LOGIN,
+        ] {
+            storage.delete(key).await.map_err(|e| e.to_string())?;
+        }
 
         log::info!("認証情報を削除しました");
         Ok(())
@@ -235,17 +368,8 @@ impl SecureStorage {
     ///
     /// # 戻り値
     /// 処理結果
-    pub fn clear_all(&self) -> Result<(), String> {
-        let store = self
-            .app_handle
-            .store(&self.store_name)
-            .map_err(|e| format!("ストアの取得に失敗しました: {e}"))?;
-
-        store.clear();
-
-        store
-            .save()
-            .map_err(|e| format!("ストアの保存に失敗しました: {e}"))?;
+    pub async fn clear_all(&self) -> Result<(), String> {
+        self.storage().clear().await.map_err(|e| e.to_string())?;
 
         log::warn!("ストアをクリアしました");
         Ok(())
@@ -7,10 +7,11 @@ use crate::features::auth::loopback::{LoopbackServer, OAuthCallback};
 use crate::features::auth::models::{AuthError, User};
 use crate::features::auth::repository::UserRepository;
 use crate::features::auth::secure_storage::SecureStorage;
+use crate::shared::utils::get_current_jst_timestamp;
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
-use tauri::AppHandle;
+use tauri::{AppHandle, Runtime, Wry};
 use tokio::sync::oneshot;
 
 /// APIサーバーからの認証開始レスポンス
@@ -103,8 +104,7 @@ pub struct AuthResult {
 }
 
 /// APIサーバー経由のOAuth認証サービス
-#[derive(Clone)]
-pub struct AuthService {
+pub struct AuthService<R: Runtime = Wry> {
     /// APIサーバーのベースURL
     api_base_url: String,
     /// HTTPクライアント
@@ -112,10 +112,33 @@ pub struct AuthService {
     /// データベース接続
     db_connection: Arc<Mutex<Connection>>,
     /// Tauriアプリハンドル
-    app_handle: AppHandle,
+    app_handle: AppHandle<R>,
 }
 
-impl AuthService {
+/// 複製したAuthServiceは、データベース接続とHTTPクライアントの接続プールを元のインスタンスと共有する
+///
+/// # スレッド安全性
+/// データベース接続は`Arc<Mutex<Connection>>`のまま共有するため、複製ごとに接続を開き直すことはなく、
+/// どの複製からの操作も同じ`Mutex`で直列化されます。`app.manage`で登録したインスタンスと
+/// コマンドや認証ミドルウェアに渡した複製は、同じユーザー情報を参照します。
+impl<R: Runtime> Clone for AuthService<R> {
+    fn clone(&self) -> Self {
+        Self {
+            api_base_url: self.api_base_url.clone(),
+            http_client: self.http_client.clone(),
+            db_connection: Arc::clone(&self.db_connection),
+            app_handle: self.app_handle.clone(),
+        }
+    }
+}
+
+// Tauriの状態として管理するため、AuthServiceはスレッド間で共有できる必要がある
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<AuthService>();
+};
+
+impl<R: Runtime> AuthService<R> {
     /// 新しいAuthServiceを作成する
     ///
     /// # 引数
@@ -128,7 +151,7 @@ impl AuthService {
     pub fn new(
         api_base_url: String,
         db_connection: Arc<Mutex<Connection>>,
-        app_handle: AppHandle,
+        app_handle: AppHandle<R>,
     ) -> Result<Self, AuthError> {
         // HTTPクライアントを作成
         let http_client = reqwest::Client::builder()
@@ -327,16 +350,19 @@ impl AuthService {
         let secure_storage = SecureStorage::new(self.app_handle.clone());
         secure_storage
             .save_session_token(&auth_callback_response.access_token)
+            .await
             .map_err(|e| AuthError::StorageError(format!("トークン保存エラー: {e}")))?;
 
         secure_storage
             .save_user_id(&user.id)
+            .await
             .map_err(|e| AuthError::StorageError(format!("ユーザーID保存エラー: {e}")))?;
 
         // 最終ログイン日時を保存
-        let now = chrono::Utc::now().to_rfc3339();
+        let now = get_current_jst_timestamp();
         secure_storage
             .save_last_login(&now)
+            .await
             .map_err(|e| AuthError::StorageError(format!("最終ログイン日時保存エラー: {e}")))?;
 
         log::info!(
@@ -414,6 +440,7 @@ impl AuthService {
         let secure_storage = SecureStorage::new(self.app_handle.clone());
         secure_storage
             .clear_auth_info()
+            .await
             .map_err(|e| AuthError::StorageError(format!("認証情報削除エラー: {e}")))?;
 
         log::info!("ログアウト処理が完了しました");
@@ -424,10 +451,11 @@ impl AuthService {
     ///
     /// # 戻り値
     /// セッショントークン（存在しない場合はNone）
-    pub fn get_stored_token(&self) -> Result<Option<String>, AuthError> {
+    pub async fn get_stored_token(&self) -> Result<Option<String>, AuthError> {
         let secure_storage = SecureStorage::new(self.app_handle.clone());
         secure_storage
             .get_session_token()
+            .await
             .map_err(|e| AuthError::StorageError(format!("トークン取得エラー: {e}")))
     }
 
@@ -435,10 +463,11 @@ impl AuthService {
     ///
     /// # 戻り値
     /// ユーザーID（存在しない場合はNone）
-    pub fn get_stored_user_id(&self) -> Result<Option<String>, AuthError> {
+    pub async fn get_stored_user_id(&self) -> Result<Option<String>, AuthError> {
         let secure_storage = SecureStorage::new(self.app_handle.clone());
         secure_storage
             .get_user_id()
+            .await
             .map_err(|e| AuthError::StorageError(format!("ユーザーID取得エラー: {e}")))
     }
 }
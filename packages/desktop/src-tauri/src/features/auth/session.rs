@@ -9,6 +9,39 @@ use rand::RngCore;
 use rusqlite::{params, Connection};
 use std::sync::{Arc, Mutex};
 
+/// sessionsテーブルの行をセッションに変換する
+fn row_to_session(row: &rusqlite::Row) -> rusqlite::Result<Session> {
+    let expires_at_str: String = row.get(2)?;
+    let created_at_str: String = row.get(3)?;
+
+    let expires_at = DateTime::parse_from_rfc3339(&expires_at_str)
+        .map_err(|_e| {
+            rusqlite::Error::InvalidColumnType(
+                2,
+                "expires_at".to_string(),
+                rusqlite::types::Type::Text,
+            )
+        })?
+        .with_timezone(&Utc);
+
+    let created_at = DateTime::parse_from_rfc3339(&created_at_str)
+        .map_err(|_e| {
+            rusqlite::Error::InvalidColumnType(
+                3,
+                "created_at".to_string(),
+                rusqlite::types::Type::Text,
+            )
+        })?
+        .with_timezone(&Utc);
+
+    Ok(Session {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        expires_at,
+        created_at,
+    })
+}
+
 /// セッション管理を行う構造体
 #[derive(Clone)]
 pub struct SessionManager {
@@ -106,37 +139,7 @@ impl SessionManager {
             .prepare("SELECT id, user_id, expires_at, created_at FROM sessions WHERE id = ?1")
             .map_err(|e| SessionError::DatabaseError(e.to_string()))?;
 
-        let session_result = stmt.query_row(params![session_id], |row| {
-            let expires_at_str: String = row.get(2)?;
-            let created_at_str: String = row.get(3)?;
-
-            let expires_at = DateTime::parse_from_rfc3339(&expires_at_str)
-                .map_err(|_e| {
-                    rusqlite::Error::InvalidColumnType(
-                        2,
-                        "expires_at".to_string(),
-                        rusqlite::types::Type::Text,
-                    )
-                })?
-                .with_timezone(&Utc);
-
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map_err(|_e| {
-                    rusqlite::Error::InvalidColumnType(
-                        3,
-                        "created_at".to_string(),
-                        rusqlite::types::Type::Text,
-                    )
-                })?
-                .with_timezone(&Utc);
-
-            Ok(Session {
-                id: row.get(0)?,
-                user_id: row.get(1)?,
-                expires_at,
-                created_at,
-            })
-        });
+        let session_result = stmt.query_row(params![session_id], row_to_session);
 
         let session = match session_result {
             Ok(session) => session,
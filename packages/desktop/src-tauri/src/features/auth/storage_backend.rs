@@ -0,0 +1,770 @@
+/// セキュアストレージのバックエンド抽象化
+///
+/// ストレージの障害を種類ごとに明示し、すべての呼び出し元が同じ方針で
+/// 扱えるように共通のラッパー（`ResilientStorage`）を提供します。
+///
+/// - ロック中: 再試行可能。イベントで通知してから再試行する
+/// - バックエンドなし: 暗号化ファイルのフォールバックに切り替え、警告を保持する
+/// - エントリ破損: 存在しないものとして扱い、セキュリティイベントを記録する
+/// - ストア全体の破損: 保存先のファイルを作り直し、セキュリティイベントを記録する
+use crate::features::migrations::security_audit::{log_security_event, SecurityEventType};
+use crate::features::security::encryption::{EncryptedData, TokenEncryption};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+/// ロック中の場合の最大再試行回数
+pub const DEFAULT_LOCK_RETRIES: u32 = 2;
+
+/// ロック中の場合の再試行間隔
+pub const DEFAULT_LOCK_RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// セキュアストレージのエラー
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SecureStorageError {
+    /// ストレージがロックされている（再試行可能）
+    #[error("セキュアストレージがロックされています: {0}")]
+    Locked(String),
+
+    /// 利用可能なバックエンドがない
+    #[error("セキュアストレージのバックエンドが利用できません: {0}")]
+    NoBackend(String),
+
+    /// 保存されているエントリが破損している
+    #[error("セキュアストレージのエントリが破損しています: {key}")]
+    Corrupted { key: String },
+
+    /// ストア全体（保存先のファイル）が破損している
+    #[error("セキュアストレージのファイルが破損しています: {store}")]
+    StoreCorrupted { store: String },
+
+    /// その他のエラー
+    #[error("セキュアストレージエラー: {0}")]
+    Other(String),
+}
+
+impl SecureStorageError {
+    /// 再試行で回復する可能性があるかどうか
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, SecureStorageError::Locked(_))
+    }
+}
+
+/// セキュアストレージのバックエンド
+pub trait SecureStorageBackend: Send + Sync {
+    /// バックエンド名を取得
+    fn name(&self) -> &str;
+
+    /// 値を取得する
+    fn get(&self, key: &str) -> Result<Option<String>, SecureStorageError>;
+
+    /// 値を保存する
+    fn set(&self, key: &str, value: &str) -> Result<(), SecureStorageError>;
+
+    /// 値を削除する
+    fn delete(&self, key: &str) -> Result<(), SecureStorageError>;
+
+    /// すべての値を削除する
+    fn clear(&self) -> Result<(), SecureStorageError>;
+
+    /// 破損したストアを作り直す
+    ///
+    /// ストア全体を読み込めない場合に使用します。保存されていた値はすべて失われます。
+    fn reset(&self) -> Result<(), SecureStorageError>;
+}
+
+/// メモリ上のバックエンド
+///
+/// 永続化されないため、フォールバック先のファイルの場所や暗号化キーを決められない場合の
+/// 最終手段として使用します。
+#[derive(Debug, Default)]
+pub struct InMemoryBackend {
+    values: Mutex<HashMap<String, String>>,
+}
+
+impl InMemoryBackend {
+    /// 新しいメモリバックエンドを作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock_values(
+        &self,
+    ) -> Result<std::sync::MutexGuard<'_, HashMap<String, String>>, SecureStorageError> {
+        self.values
+            .lock()
+            .map_err(|e| SecureStorageError::Other(format!("ロックエラー: {e}")))
+    }
+}
+
+impl SecureStorageBackend for InMemoryBackend {
+    fn name(&self) -> &str {
+        "memory"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, SecureStorageError> {
+        Ok(self.lock_values()?.get(key).cloned())
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), SecureStorageError> {
+        self.lock_values()?
+            .insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), SecureStorageError> {
+        self.lock_values()?.remove(key);
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<(), SecureStorageError> {
+        self.lock_values()?.clear();
+        Ok(())
+    }
+
+    fn reset(&self) -> Result<(), SecureStorageError> {
+        self.clear()
+    }
+}
+
+/// 暗号化ファイルのバックエンド
+///
+/// プライマリが利用できない場合のフォールバック先です。値はAES-256-GCMで暗号化して
+/// JSONファイルに保存するため、アプリを再起動しても失われません。
+pub struct EncryptedFileBackend {
+    path: PathBuf,
+    encryption: TokenEncryption,
+    file_lock: Mutex<()>,
+}
+
+impl EncryptedFileBackend {
+    /// 新しい暗号化ファイルバックエンドを作成
+    ///
+    /// # 引数
+    /// * `path` - 保存先のファイルパス
+    /// * `encryption` - 値の暗号化に使用するサービス
+    pub fn new(path: PathBuf, encryption: TokenEncryption) -> Self {
+        Self {
+            path,
+            encryption,
+            file_lock: Mutex::new(()),
+        }
+    }
+
+    fn lock_file(&self) -> Result<std::sync::MutexGuard<'_, ()>, SecureStorageError> {
+        self.file_lock
+            .lock()
+            .map_err(|e| SecureStorageError::Other(format!("ロックエラー: {e}")))
+    }
+
+    /// ファイルを読み込む
+    ///
+    /// ファイルが存在しない場合は空として扱い、解析できない場合はストア全体の破損として扱います。
+    fn load(&self) -> Result<HashMap<String, EncryptedData>, SecureStorageError> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(content) => {
+                serde_json::from_str(&content).map_err(|_| SecureStorageError::StoreCorrupted {
+                    store: self.path.display().to_string(),
+                })
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(e) => Err(SecureStorageError::Other(e.to_string())),
+        }
+    }
+
+    /// 一時ファイルに書き込んでから置き換え、書き込み途中のファイルが残らないようにする
+    fn save(&self, values: &HashMap<String, EncryptedData>) -> Result<(), SecureStorageError> {
+        let io_error = |e: std::io::Error| SecureStorageError::Other(e.to_string());
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let content =
+            serde_json::to_string(values).map_err(|e| SecureStorageError::Other(e.to_string()))?;
+        let temp_path = self.path.with_extension("tmp");
+        std::fs::write(&temp_path, content).map_err(io_error)?;
+        std::fs::rename(&temp_path, &self.path).map_err(io_error)
+    }
+}
+
+impl SecureStorageBackend for EncryptedFileBackend {
+    fn name(&self) -> &str {
+        "encrypted-file"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, SecureStorageError> {
+        let _guard = self.lock_file()?;
+        match self.load()?.get(key) {
+            None => Ok(None),
+            Some(data) => {
+                self.encryption
+                    .decrypt(data)
+                    .map(Some)
+                    .map_err(|_| SecureStorageError::Corrupted {
+                        key: key.to_string(),
+                    })
+            }
+        }
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), SecureStorageError> {
+        let _guard = self.lock_file()?;
+        let mut values = self.load()?;
+        let data = self
+            .encryption
+            .encrypt(value)
+            .map_err(|e| SecureStorageError::Other(e.to_string()))?;
+        values.insert(key.to_string(), data);
+        self.save(&values)
+    }
+
+    fn delete(&self, key: &str) -> Result<(), SecureStorageError> {
+        let _guard = self.lock_file()?;
+        // 解析できないファイルは作り直す
+        let mut values = self.load().unwrap_or_default();
+        values.remove(key);
+        self.save(&values)
+    }
+
+    fn clear(&self) -> Result<(), SecureStorageError> {
+        let _guard = self.lock_file()?;
+        self.save(&HashMap::new())
+    }
+
+    fn reset(&self) -> Result<(), SecureStorageError> {
+        self.clear()
+    }
+}
+
+/// セキュアストレージの状態変化を通知するイベント
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SecureStorageEvent {
+    /// ストレージがロックされている（ユーザーに解除を促す）
+    Locked { attempt: u32, max_attempts: u32 },
+    /// フォールバックストレージに切り替えた
+    FallbackActivated { reason: String },
+    /// 破損したエントリを破棄した（再認証が必要）
+    CorruptedEntry { key: String },
+    /// 破損したストアを作り直した（再認証が必要）
+    CorruptedStore { store: String },
+}
+
+/// イベント通知関数
+pub type SecureStorageNotifier = Arc<dyn Fn(&SecureStorageEvent) + Send + Sync>;
+
+/// フォールバック状態
+///
+/// `SecureStorage`は呼び出しごとに生成されるため、フォールバック先と警告は
+/// アプリ全体で共有します。
+pub struct FallbackState {
+    backend: Box<dyn SecureStorageBackend>,
+    warning: Mutex<Option<String>>,
+}
+
+impl FallbackState {
+    /// 新しいフォールバック状態を作成
+    ///
+    /// # 引数
+    /// * `backend` - フォールバック先のバックエンド
+    pub fn new(backend: Box<dyn SecureStorageBackend>) -> Self {
+        Self {
+            backend,
+            warning: Mutex::new(None),
+        }
+    }
+
+    /// フォールバック中の警告を取得（フォールバックしていない場合はNone）
+    pub fn warning(&self) -> Option<String> {
+        self.warning.lock().ok().and_then(|w| w.clone())
+    }
+
+    fn activate(&self, reason: &str) -> bool {
+        let Ok(mut warning) = self.warning.lock() else {
+            return false;
+        };
+        if warning.is_some() {
+            return false;
+        }
+        *warning = Some(format!(
+            "セキュアストレージが利用できないため、認証情報を代替ストレージ（{}）に保存しています: {reason}",
+            self.backend.name()
+        ));
+        true
+    }
+}
+
+/// アプリ全体で共有するフォールバック状態
+static SHARED_FALLBACK_STATE: OnceLock<Arc<FallbackState>> = OnceLock::new();
+
+/// 共有フォールバック状態を取得
+///
+/// # 引数
+/// * `create_backend` - 初回呼び出し時にフォールバック先のバックエンドを作成する関数
+pub fn shared_fallback_state(
+    create_backend: impl FnOnce() -> Box<dyn SecureStorageBackend>,
+) -> Arc<FallbackState> {
+    SHARED_FALLBACK_STATE
+        .get_or_init(|| Arc::new(FallbackState::new(create_backend())))
+        .clone()
+}
+
+/// セキュアストレージの状態
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecureStorageStatus {
+    /// フォールバックストレージを使用しているかどうか
+    pub using_fallback: bool,
+    /// 利用者に表示する警告
+    pub warning: Option<String>,
+}
+
+/// 現在のセキュアストレージの状態を取得
+pub fn secure_storage_status() -> SecureStorageStatus {
+    let warning = SHARED_FALLBACK_STATE
+        .get()
+        .and_then(|state| state.warning());
+    SecureStorageStatus {
+        using_fallback: warning.is_some(),
+        warning,
+    }
+}
+
+/// 障害時の方針を適用する共通ラッパー
+pub struct ResilientStorage {
+    primary: Box<dyn SecureStorageBackend>,
+    fallback: Arc<FallbackState>,
+    notifier: Option<SecureStorageNotifier>,
+    max_lock_retries: u32,
+    retry_delay: Duration,
+}
+
+impl ResilientStorage {
+    /// 新しいラッパーを作成
+    ///
+    /// # 引数
+    /// * `primary` - プライマリバックエンド
+    /// * `fallback` - フォールバック状態
+    pub fn new(primary: Box<dyn SecureStorageBackend>, fallback: Arc<FallbackState>) -> Self {
+        Self {
+            primary,
+            fallback,
+            notifier: None,
+            max_lock_retries: DEFAULT_LOCK_RETRIES,
+            retry_delay: DEFAULT_LOCK_RETRY_DELAY,
+        }
+    }
+
+    /// イベント通知関数を設定
+    pub fn with_notifier(mut self, notifier: SecureStorageNotifier) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// ロック中の再試行設定を変更
+    pub fn with_lock_retry(mut self, max_retries: u32, delay: Duration) -> Self {
+        self.max_lock_retries = max_retries;
+        self.retry_delay = delay;
+        self
+    }
+
+    /// フォールバックを使用しているかどうか
+    pub fn is_using_fallback(&self) -> bool {
+        self.fallback.warning().is_some()
+    }
+
+    fn notify(&self, event: SecureStorageEvent) {
+        if let Some(notifier) = &self.notifier {
+            notifier(&event);
+        }
+    }
+
+    /// ロック時の再試行とバックエンド不在時のフォールバックを適用して操作を実行する
+    ///
+    /// 再試行の待機中は非同期ランタイムのワーカーをブロックしません。
+    async fn run<T>(
+        &self,
+        operation: impl Fn(&dyn SecureStorageBackend) -> Result<T, SecureStorageError>,
+    ) -> Result<T, SecureStorageError> {
+        if self.is_using_fallback() {
+            return operation(self.fallback.backend.as_ref());
+        }
+
+        let mut attempt = 0;
+        loop {
+            match operation(self.primary.as_ref()) {
+                Err(SecureStorageError::Locked(reason)) if attempt < self.max_lock_retries => {
+                    attempt += 1;
+                    log::warn!(
+                        "セキュアストレージがロックされています（{attempt}/{}回目の再試行）: {reason}",
+                        self.max_lock_retries
+                    );
+                    self.notify(SecureStorageEvent::Locked {
+                        attempt,
+                        max_attempts: self.max_lock_retries,
+                    });
+                    tokio::time::sleep(self.retry_delay).await;
+                }
+                Err(SecureStorageError::NoBackend(reason)) => {
+                    log::warn!(
+                        "セキュアストレージ（{}）が利用できないため、フォールバックに切り替えます: {reason}",
+                        self.primary.name()
+                    );
+                    if self.fallback.activate(&reason) {
+                        self.notify(SecureStorageEvent::FallbackActivated { reason });
+                    }
+                    return operation(self.fallback.backend.as_ref());
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// 値を取得する
+    ///
+    /// 破損したエントリは削除し、存在しないものとして扱います。
+    pub async fn get(&self, key: &str) -> Result<Option<String>, SecureStorageError> {
+        match self.run(|backend| backend.get(key)).await {
+            Err(SecureStorageError::Corrupted { key }) => {
+                self.discard_corrupted(&key).await;
+                Ok(None)
+            }
+            Err(SecureStorageError::StoreCorrupted { store }) => {
+                self.reset_corrupted_store(&store).await?;
+                Ok(None)
+            }
+            result => result,
+        }
+    }
+
+    /// 値を保存する
+    pub async fn set(&self, key: &str, value: &str) -> Result<(), SecureStorageError> {
+        match self.run(|backend| backend.set(key, value)).await {
+            Err(SecureStorageError::Corrupted { key: corrupted }) => {
+                // 破損したエントリを破棄してから1度だけ再試行する
+                self.discard_corrupted(&corrupted).await;
+                self.run(|backend| backend.set(key, value)).await
+            }
+            Err(SecureStorageError::StoreCorrupted { store }) => {
+                // ストアを作り直してから1度だけ再試行する
+                self.reset_corrupted_store(&store).await?;
+                self.run(|backend| backend.set(key, value)).await
+            }
+            result => result,
+        }
+    }
+
+    /// 値を削除する
+    ///
+    /// ストア全体が破損している場合は作り直します（値はすべて失われます）。
+    pub async fn delete(&self, key: &str) -> Result<(), SecureStorageError> {
+        match self.run(|backend| backend.delete(key)).await {
+            Err(SecureStorageError::StoreCorrupted { store }) => {
+                self.reset_corrupted_store(&store).await
+            }
+            result => result,
+        }
+    }
+
+    /// すべての値を削除する
+    ///
+    /// ストア全体が破損している場合は作り直します。
+    pub async fn clear(&self) -> Result<(), SecureStorageError> {
+        match self.run(|backend| backend.clear()).await {
+            Err(SecureStorageError::StoreCorrupted { store }) => {
+                self.reset_corrupted_store(&store).await
+            }
+            result => result,
+        }
+    }
+
+    async fn discard_corrupted(&self, key: &str) {
+        log::warn!("破損したセキュアストレージのエントリを破棄します: key={key}");
+        log_security_event(
+            SecurityEventType::DataIntegrityViolation,
+            &format!("セキュアストレージのエントリが破損していたため破棄しました: key={key}"),
+            None,
+            None,
+        );
+        if let Err(e) = self.run(|backend| backend.delete(key)).await {
+            log::warn!("破損したエントリの削除に失敗しました: {e}");
+        }
+        self.notify(SecureStorageEvent::CorruptedEntry {
+            key: key.to_string(),
+        });
+    }
+
+    /// 破損したストアを作り直す
+    ///
+    /// エントリ単位の削除では破損したファイルが残るため、ストアごと作り直します。
+    async fn reset_corrupted_store(&self, store: &str) -> Result<(), SecureStorageError> {
+        log::warn!("破損したセキュアストレージを作り直します: store={store}");
+        log_security_event(
+            SecurityEventType::DataIntegrityViolation,
+            &format!("セキュアストレージのファイルが破損していたため作り直しました: store={store}"),
+            None,
+            None,
+        );
+        self.run(|backend| backend.reset()).await?;
+        self.notify(SecureStorageEvent::CorruptedStore {
+            store: store.to_string(),
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use tempfile::TempDir;
+
+    /// 障害を再現するモックバックエンド
+    struct MockBackend {
+        inner: InMemoryBackend,
+        locked_failures: AtomicU32,
+        unavailable: bool,
+        corrupted_key: Mutex<Option<String>>,
+    }
+
+    impl MockBackend {
+        fn new() -> Self {
+            Self {
+                inner: InMemoryBackend::new(),
+                locked_failures: AtomicU32::new(0),
+                unavailable: false,
+                corrupted_key: Mutex::new(None),
+            }
+        }
+
+        fn check(&self, key: &str) -> Result<(), SecureStorageError> {
+            if self.unavailable {
+                return Err(SecureStorageError::NoBackend(
+                    "secret serviceが起動していません".to_string(),
+                ));
+            }
+            if self
+                .locked_failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok()
+            {
+                return Err(SecureStorageError::Locked("keychain locked".to_string()));
+            }
+            if self.corrupted_key.lock().unwrap().as_deref() == Some(key) {
+                return Err(SecureStorageError::Corrupted {
+                    key: key.to_string(),
+                });
+            }
+            Ok(())
+        }
+    }
+
+    impl SecureStorageBackend for MockBackend {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        fn get(&self, key: &str) -> Result<Option<String>, SecureStorageError> {
+            self.check(key)?;
+            self.inner.get(key)
+        }
+
+        fn set(&self, key: &str, value: &str) -> Result<(), SecureStorageError> {
+            self.check(key)?;
+            self.inner.set(key, value)
+        }
+
+        fn delete(&self, key: &str) -> Result<(), SecureStorageError> {
+            if self.unavailable {
+                return Err(SecureStorageError::NoBackend("unavailable".to_string()));
+            }
+            self.corrupted_key.lock().unwrap().take();
+            self.inner.delete(key)
+        }
+
+        fn clear(&self) -> Result<(), SecureStorageError> {
+            self.inner.clear()
+        }
+
+        fn reset(&self) -> Result<(), SecureStorageError> {
+            self.inner.reset()
+        }
+    }
+
+    fn file_backend(dir: &TempDir) -> EncryptedFileBackend {
+        EncryptedFileBackend::new(
+            dir.path().join("secure_fallback.json"),
+            TokenEncryption::new("test_encryption_key_32_bytes_long".to_string()).unwrap(),
+        )
+    }
+
+    fn storage_with_events(
+        backend: MockBackend,
+        fallback: EncryptedFileBackend,
+    ) -> (ResilientStorage, Arc<Mutex<Vec<SecureStorageEvent>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        let fallback = Arc::new(FallbackState::new(Box::new(fallback)));
+        let storage = ResilientStorage::new(Box::new(backend), fallback)
+            .with_lock_retry(2, Duration::ZERO)
+            .with_notifier(Arc::new(move |event| {
+                sink.lock().unwrap().push(event.clone());
+            }));
+        (storage, events)
+    }
+
+    #[tokio::test]
+    async fn test_locked_backend_is_retried() {
+        let backend = MockBackend::new();
+        backend.locked_failures.store(2, Ordering::SeqCst);
+        let dir = TempDir::new().unwrap();
+        let (storage, events) = storage_with_events(backend, file_backend(&dir));
+
+        storage.set("session_token", "token").await.unwrap();
+        assert_eq!(
+            storage.get("session_token").await.unwrap(),
+            Some("token".to_string())
+        );
+        assert!(!storage.is_using_fallback());
+
+        let events = events.lock().unwrap();
+        assert_eq!(
+            *events,
+            vec![
+                SecureStorageEvent::Locked {
+                    attempt: 1,
+                    max_attempts: 2
+                },
+                SecureStorageEvent::Locked {
+                    attempt: 2,
+                    max_attempts: 2
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_locked_backend_gives_up_after_retries() {
+        let backend = MockBackend::new();
+        backend.locked_failures.store(10, Ordering::SeqCst);
+        let dir = TempDir::new().unwrap();
+        let (storage, events) = storage_with_events(backend, file_backend(&dir));
+
+        let err = storage.set("session_token", "token").await.unwrap_err();
+        assert!(err.is_retryable());
+        assert_eq!(events.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_missing_backend_falls_back_to_encrypted_file() {
+        let mut backend = MockBackend::new();
+        backend.unavailable = true;
+        let dir = TempDir::new().unwrap();
+        let (storage, events) = storage_with_events(backend, file_backend(&dir));
+
+        storage.set("session_token", "token").await.unwrap();
+        assert!(storage.is_using_fallback());
+        assert_eq!(
+            storage.get("session_token").await.unwrap(),
+            Some("token".to_string())
+        );
+
+        // フォールバック通知は1度だけ
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            events[0],
+            SecureStorageEvent::FallbackActivated { .. }
+        ));
+        assert!(storage.fallback.warning().is_some());
+
+        // 再起動後も暗号化ファイルから取得でき、平文では保存されない
+        let content = std::fs::read_to_string(dir.path().join("secure_fallback.json")).unwrap();
+        assert!(!content.contains("\"token\""));
+        assert_eq!(
+            file_backend(&dir).get("session_token").unwrap(),
+            Some("token".to_string())
+        );
+    }
+
+    #[test]
+    fn test_encrypted_file_backend_treats_unreadable_file_as_corrupted() {
+        let dir = TempDir::new().unwrap();
+        let backend = file_backend(&dir);
+        std::fs::write(dir.path().join("secure_fallback.json"), "not json").unwrap();
+
+        assert!(matches!(
+            backend.get("session_token").unwrap_err(),
+            SecureStorageError::StoreCorrupted { .. }
+        ));
+
+        // 削除でファイルを作り直す
+        backend.delete("session_token").unwrap();
+        backend.set("session_token", "token").unwrap();
+        assert_eq!(
+            backend.get("session_token").unwrap(),
+            Some("token".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_entry_is_treated_as_missing() {
+        let backend = MockBackend::new();
+        backend.inner.set("session_token", "garbage").unwrap();
+        *backend.corrupted_key.lock().unwrap() = Some("session_token".to_string());
+        let dir = TempDir::new().unwrap();
+        let (storage, events) = storage_with_events(backend, file_backend(&dir));
+
+        assert_eq!(storage.get("session_token").await.unwrap(), None);
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![SecureStorageEvent::CorruptedEntry {
+                key: "session_token".to_string()
+            }]
+        );
+
+        // 破棄後は通常どおり保存できる
+        storage.set("session_token", "new_token").await.unwrap();
+        assert_eq!(
+            storage.get("session_token").await.unwrap(),
+            Some("new_token".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_store_file_is_reset() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("secure_fallback.json");
+        std::fs::write(&path, "not json").unwrap();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        let storage = ResilientStorage::new(
+            Box::new(file_backend(&dir)),
+            Arc::new(FallbackState::new(Box::new(InMemoryBackend::new()))),
+        )
+        .with_notifier(Arc::new(move |event| {
+            sink.lock().unwrap().push(event.clone());
+        }));
+
+        assert_eq!(storage.get("session_token").await.unwrap(), None);
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![SecureStorageEvent::CorruptedStore {
+                store: path.display().to_string()
+            }]
+        );
+
+        // ファイルが作り直され、以降は通常どおり保存できる
+        assert!(serde_json::from_str::<HashMap<String, EncryptedData>>(
+            &std::fs::read_to_string(&path).unwrap()
+        )
+        .is_ok());
+        storage.set("session_token", "token").await.unwrap();
+        assert_eq!(
+            storage.get("session_token").await.unwrap(),
+            Some("token".to_string())
+        );
+        assert_eq!(events.lock().unwrap().len(), 1);
+    }
+}
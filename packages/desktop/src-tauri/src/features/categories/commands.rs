@@ -0,0 +1,27 @@
+// カテゴリー機能のTauriコマンドハンドラー（ローカル処理）
+
+use crate::shared::utils::color::{check_badge_color, suggest_palette, BadgeColorCheck};
+
+/// カテゴリー色がライト/ダーク両方のバッジ背景で読み取れるか検証する
+///
+/// # 引数
+/// * `color` - 色（#RRGGBB形式）
+///
+/// # 戻り値
+/// コントラスト比と、基準を満たさない場合の代替色、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn check_category_color(color: String) -> Result<BadgeColorCheck, String> {
+    check_badge_color(&color).map_err(|e| e.user_message().to_string())
+}
+
+/// カテゴリーの一括作成用に、区別しやすく読み取りやすい配色を提案する
+///
+/// # 引数
+/// * `count` - 色数
+///
+/// # 戻り値
+/// #RRGGBB形式の色の一覧、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn suggest_category_palette(count: usize) -> Result<Vec<String>, String> {
+    suggest_palette(count).map_err(|e| e.user_message().to_string())
+}
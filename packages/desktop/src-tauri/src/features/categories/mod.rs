@@ -2,7 +2,19 @@
 ///
 /// カテゴリーに関連するモデルとAPIコマンドを提供します。
 pub mod api_commands;
+pub mod commands;
 pub mod models;
 
 pub use api_commands::*;
+pub use commands::*;
 pub use models::*;
+
+use crate::shared::capabilities::{Capability, Requirement};
+
+/// カテゴリー機能の利用者向け機能
+pub const CAPABILITIES: &[Capability] = &[Capability::new(
+    "categories.list",
+    "capability.categories.list",
+    "get_categories",
+)
+.requires(&[Requirement::Authenticated, Requirement::ApiServerConfigured])];
@@ -2,11 +2,22 @@
 ///
 /// ローカルSQLiteの代わりにAPI Serverを使用して経費データを管理します
 use crate::features::auth::middleware::AuthMiddleware;
+use crate::features::expenses::csv_import;
+use crate::features::expenses::location;
+use crate::features::expenses::merchants;
 use crate::features::expenses::models::*;
+use crate::features::expenses::receipt_policies::{self, ReceiptCheck};
+use crate::features::expenses::sync::{self, ExpenseIntegrityReport, ExpenseSyncReport};
+use crate::features::receipts::annotations::delete_annotations_for_expense;
 use crate::shared::api_client::ApiClient;
-use log::info;
+use crate::shared::errors::ValidationError;
+use crate::shared::events::{emit_event, AppEvent};
+use crate::shared::mutation::{DeleteResponse, DeleteResult};
+use crate::AppState;
+use chrono::Utc;
+use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use tauri::{AppHandle, Manager, Runtime, State};
 
 /// API Serverからの経費作成レスポンス
 #[derive(Debug, Serialize, Deserialize)]
@@ -16,21 +27,26 @@ struct CreateExpenseResponse {
     timestamp: String,
 }
 
-/// API Serverからの経費一覧取得レスポンス
+/// API Serverからの経費更新レスポンス
 #[derive(Debug, Serialize, Deserialize)]
-struct GetExpensesResponse {
+struct UpdateExpenseResponse {
     success: bool,
-    expenses: Vec<Expense>,
-    count: usize,
-    filters: Option<serde_json::Value>,
+    expense: Expense,
     timestamp: String,
 }
 
-/// API Serverからの経費更新レスポンス
+/// API Serverへの経費一括作成リクエスト
+#[derive(Debug, Serialize)]
+struct CreateExpensesRequest<'a> {
+    expenses: &'a [CreateExpenseDto],
+}
+
+/// API Serverからの経費一括作成レスポンス
 #[derive(Debug, Serialize, Deserialize)]
-struct UpdateExpenseResponse {
+struct CreateExpensesResponse {
     success: bool,
-    expense: Expense,
+    expenses: Vec<Expense>,
+    count: usize,
     timestamp: String,
 }
 
@@ -39,22 +55,43 @@ struct UpdateExpenseResponse {
 /// # 引数
 /// * `dto` - 経費作成用DTO
 /// * `session_token` - セッショントークン
-/// * `auth_middleware` - 認証ミドルウェア
+/// * `app_handle` - Tauriアプリケーションハンドル（アプリケーション状態と認証ミドルウェアを取得する）
 ///
 /// # 戻り値
 /// 作成された経費、または失敗時はエラーメッセージ
 #[tauri::command]
-pub async fn create_expense(
+pub async fn create_expense<R: Runtime>(
     dto: CreateExpenseDto,
     session_token: Option<String>,
-    auth_middleware: State<'_, AuthMiddleware>,
+    app_handle: AppHandle<R>,
 ) -> Result<Expense, String> {
+    let state = app_handle.state::<AppState>();
+    let auth_middleware = app_handle.state::<AuthMiddleware<R>>();
+
     // 認証チェック
-    let _user = auth_middleware
+    let user = auth_middleware
         .authenticate_request(session_token.as_deref(), "/expenses/create")
         .await
         .map_err(|e| format!("認証エラー: {e}"))?;
 
+    // 入力値の検証（すべてのエラーをまとめて返す）
+    dto.validate_all()
+        .and_then(|errors| ValidationError::ensure_none(&errors))
+        .map_err(|e| e.user_message().to_string())?;
+
+    // 領収書の添付ルールの確認（領収書は作成後に添付するため、後で添付する指定がなければ必須のルールで保存できない）
+    enforce_receipt_policy(
+        &state,
+        &user.id,
+        &ReceiptCheck {
+            amount: dto.amount,
+            category: &dto.category,
+            category_id: dto.category_id,
+            has_receipt: false,
+        },
+        dto.receipt_followup,
+    )?;
+
     // APIクライアントを作成
     let api_client = ApiClient::new().map_err(|e| format!("APIクライアント作成エラー: {e}"))?;
 
@@ -64,60 +101,351 @@ pub async fn create_expense(
         .await
         .map_err(|e| format!("経費作成APIエラー: {e}"))?;
 
-    info!("経費作成成功: expense_id={}", response.expense.id);
-    Ok(response.expense)
+    mirror_expense(&state, &user.id, &response.expense);
+    let expense = track_missing_receipt(&state, &user.id, response.expense, dto.receipt_followup);
+
+    info!("経費作成成功: expense_id={}", expense.id);
+    Ok(expense)
+}
+
+/// CSVファイルから経費を一括で取り込む（API Server経由）
+///
+/// 問題のない行だけをAPI Serverの一括作成でまとめて登録し、問題のある行は行番号と理由を返します。
+/// 登録に失敗した場合はどの行も登録されません。
+///
+/// # 引数
+/// * `file_path` - 取り込むCSVファイルのパス
+/// * `dry_run` - trueの場合は検証のみを行い、登録しない（省略時はfalse）
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 取り込み結果、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn import_expenses_csv(
+    file_path: String,
+    dry_run: Option<bool>,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, AuthMiddleware>,
+) -> Result<ImportResult, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/expenses/import")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    let content = tokio::fs::read_to_string(&file_path)
+        .await
+        .map_err(|e| format!("ファイル読み込みエラー: {e}"))?;
+
+    let (dtos, skipped) =
+        csv_import::parse_expenses_csv(&content).map_err(|e| e.user_message().to_string())?;
+    csv_import::ensure_import_size(&dtos).map_err(|e| e.user_message().to_string())?;
+
+    let dry_run = dry_run.unwrap_or(false);
+    if !dry_run && !dtos.is_empty() {
+        // APIクライアントを作成
+        let api_client = ApiClient::new().map_err(|e| format!("APIクライアント作成エラー: {e}"))?;
+
+        // API Serverに経費一括作成リクエストを送信
+        let response: CreateExpensesResponse = api_client
+            .post(
+                "/api/v1/expenses/batch",
+                &CreateExpensesRequest { expenses: &dtos },
+                session_token.as_deref(),
+            )
+            .await
+            .map_err(|e| format!("経費一括作成APIエラー: {e}"))?;
+
+        for expense in response.expenses {
+            mirror_expense(&state, &user.id, &expense);
+            track_missing_receipt(&state, &user.id, expense, false);
+        }
+    }
+
+    info!(
+        "経費CSV取り込み完了: imported={}, skipped={}, dry_run={dry_run}",
+        dtos.len(),
+        skipped.len()
+    );
+    Ok(ImportResult {
+        imported: dtos.len(),
+        skipped,
+    })
 }
 
 /// 経費一覧を取得する（API Server経由）
 ///
+/// 一覧はローカルのミラーから返し、APIサーバーとの差分同期はバックグラウンドで行います。
+/// 同期でミラーが変更された場合は`expenses-synced`イベントを通知します。
+/// 一度も同期していない場合は、同期が完了してから返します。
+///
+/// `query`を指定した場合は、条件に一致した件数と指定したページの経費だけを返します。
+///
 /// # 引数
 /// * `month` - 月フィルター（オプション、YYYY-MM形式）
-/// * `category` - カテゴリフィルター（オプション）
+/// * `filter` - 検索条件（オプション）
+/// * `query` - ページ取得条件（オプション）
 /// * `session_token` - セッショントークン
+/// * `app_handle` - Tauriアプリケーションハンドル
+/// * `state` - アプリケーション状態
 /// * `auth_middleware` - 認証ミドルウェア
 ///
 /// # 戻り値
-/// 経費一覧、または失敗時はエラーメッセージ
+/// 経費一覧（`query`指定時は件数付きのページ）、または失敗時はエラーメッセージ
 #[tauri::command]
-pub async fn get_expenses(
+pub async fn get_expenses<R: Runtime>(
     month: Option<String>,
-    category: Option<String>,
+    filter: Option<ExpenseFilter>,
+    query: Option<GetExpensesQuery>,
     session_token: Option<String>,
-    auth_middleware: State<'_, AuthMiddleware>,
-) -> Result<Vec<Expense>, String> {
+    app_handle: AppHandle<R>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, AuthMiddleware<R>>,
+) -> Result<ExpenseList, String> {
     // 認証チェック
-    let _user = auth_middleware
+    let user = auth_middleware
         .authenticate_request(session_token.as_deref(), "/expenses/list")
         .await
         .map_err(|e| format!("認証エラー: {e}"))?;
 
-    // APIクライアントを作成
-    let api_client = ApiClient::new().map_err(|e| format!("APIクライアント作成エラー: {e}"))?;
+    let filter = filter.unwrap_or_default();
+    filter
+        .validate()
+        .map_err(|e| e.user_message().to_string())?;
+    if let Some(query) = &query {
+        query.validate().map_err(|e| e.user_message().to_string())?;
+    }
 
-    // クエリパラメータを構築
-    let mut endpoint = "/api/v1/expenses".to_string();
-    let mut params = vec![];
+    let sync_state = state
+        .try_db(|db| sync::get_sync_state(db, &user.id))
+        .map_err(|e| e.to_string())?;
 
-    if let Some(m) = month {
-        params.push(format!("month={m}"));
-    }
-    if let Some(c) = category {
-        params.push(format!("category={c}"));
+    match sync_state {
+        None => {
+            // 初回は全件を取得してから返す
+            let api_client =
+                ApiClient::new().map_err(|e| format!("APIクライアント作成エラー: {e}"))?;
+            let _guard = sync::sync_lock().lock().await;
+            sync::sync_expenses(
+                &api_client,
+                &state.db,
+                &user.id,
+                session_token.as_deref(),
+                false,
+            )
+            .await
+            .map_err(|e| format!("経費一覧取得APIエラー: {e}"))?;
+            resolve_receipt_flags(&state, &user.id);
+        }
+        Some(sync_state) => {
+            spawn_background_sync(
+                app_handle,
+                user.id.clone(),
+                session_token.clone(),
+                sync_state.integrity_check_due(Utc::now()),
+            );
+        }
     }
 
-    if !params.is_empty() {
-        endpoint.push('?');
-        endpoint.push_str(&params.join("&"));
+    let expenses = state
+        .try_db(|db| {
+            let mut expenses = match &query {
+                None => ExpenseList::All(sync::list_mirrored(
+                    db,
+                    &user.id,
+                    month.as_deref(),
+                    &filter,
+                )?),
+                Some(query) => ExpenseList::Page(sync::list_mirrored_page(
+                    db,
+                    &user.id,
+                    month.as_deref(),
+                    &filter,
+                    query,
+                )?),
+            };
+            let flagged = receipt_policies::flagged_expense_ids(db, &user.id)?;
+            for expense in expenses.expenses_mut() {
+                expense.missing_receipt = flagged.contains(&expense.id);
+            }
+            Ok(expenses)
+        })
+        .map_err(|e| e.to_string())?;
+
+    info!("経費一覧取得成功: count={}", expenses.expenses().len());
+    Ok(expenses)
+}
+
+/// バックグラウンドでAPIサーバーと差分同期する
+///
+/// 他の同期が実行中の場合は何もしません。
+/// 整合性チェックの時期の場合は、同期後に件数とチェックサムも比較します。
+fn spawn_background_sync<R: Runtime>(
+    app_handle: AppHandle<R>,
+    user_id: String,
+    session_token: Option<String>,
+    check_integrity: bool,
+) {
+    tauri::async_runtime::spawn(async move {
+        let Ok(_guard) = sync::sync_lock().try_lock() else {
+            debug!("経費の同期が実行中のため、バックグラウンド同期をスキップします");
+            return;
+        };
+        let api_client = match ApiClient::new() {
+            Ok(api_client) => api_client,
+            Err(e) => {
+                warn!("経費のバックグラウンド同期に失敗: {e}");
+                return;
+            }
+        };
+        let state = app_handle.state::<AppState>();
+
+        match sync::sync_expenses(
+            &api_client,
+            &state.db,
+            &user_id,
+            session_token.as_deref(),
+            false,
+        )
+        .await
+        {
+            Ok(report) if report.changed => {
+                resolve_receipt_flags(&state, &user_id);
+                notify_synced(&app_handle, report)
+            }
+            Ok(_) => {}
+            Err(e) => {
+                warn!("経費のバックグラウンド同期に失敗: {e}");
+                return;
+            }
+        }
+
+        if check_integrity {
+            match sync::check_integrity(&api_client, &state.db, &user_id, session_token.as_deref())
+                .await
+            {
+                Ok(ExpenseIntegrityReport {
+                    resync: Some(report),
+                    ..
+                }) => {
+                    resolve_receipt_flags(&state, &user_id);
+                    notify_synced(&app_handle, report)
+                }
+                Ok(_) => {}
+                Err(e) => warn!("経費の整合性チェックに失敗: {e}"),
+            }
+        }
+    });
+}
+
+/// 同期でミラーが変更されたことを通知する
+fn notify_synced<R: Runtime>(app_handle: &AppHandle<R>, report: ExpenseSyncReport) {
+    if let Err(e) = emit_event(app_handle, AppEvent::ExpensesSynced(report)) {
+        warn!("経費の同期イベントの通知に失敗: {e}");
     }
+}
+
+/// APIサーバーと経費を同期するコマンド
+///
+/// 前回のリビジョン以降の変更を取得してローカルのミラーに反映します。
+///
+/// # 引数
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 同期の結果、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn sync_expenses(
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, AuthMiddleware>,
+) -> Result<ExpenseSyncReport, String> {
+    run_sync(session_token, &state, &auth_middleware, false).await
+}
 
-    // API Serverに経費一覧取得リクエストを送信
-    let response: GetExpensesResponse = api_client
-        .get(&endpoint, session_token.as_deref())
+/// APIサーバーから経費を全件取得し直すコマンド
+///
+/// ローカルのミラーを破棄して置き換えます。
+///
+/// # 引数
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 同期の結果、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn force_full_expense_resync(
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, AuthMiddleware>,
+) -> Result<ExpenseSyncReport, String> {
+    info!("経費の全件再同期を開始します");
+    run_sync(session_token, &state, &auth_middleware, true).await
+}
+
+async fn run_sync(
+    session_token: Option<String>,
+    state: &AppState,
+    auth_middleware: &AuthMiddleware,
+    force_full: bool,
+) -> Result<ExpenseSyncReport, String> {
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/expenses/sync")
         .await
-        .map_err(|e| format!("経費一覧取得APIエラー: {e}"))?;
+        .map_err(|e| format!("認証エラー: {e}"))?;
+    let api_client = ApiClient::new().map_err(|e| format!("APIクライアント作成エラー: {e}"))?;
 
-    info!("経費一覧取得成功: count={}", response.count);
-    Ok(response.expenses)
+    let _guard = sync::sync_lock().lock().await;
+    let report = sync::sync_expenses(
+        &api_client,
+        &state.db,
+        &user.id,
+        session_token.as_deref(),
+        force_full,
+    )
+    .await
+    .map_err(|e| format!("経費同期APIエラー: {e}"))?;
+    resolve_receipt_flags(state, &user.id);
+    Ok(report)
+}
+
+/// ローカルのミラーとAPIサーバーの経費の件数・チェックサムを比較するコマンド
+///
+/// 一致しない場合は全件を取得し直します。
+///
+/// # 引数
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 整合性チェックの結果、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn check_expense_sync_integrity(
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, AuthMiddleware>,
+) -> Result<ExpenseIntegrityReport, String> {
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/expenses/sync")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+    let api_client = ApiClient::new().map_err(|e| format!("APIクライアント作成エラー: {e}"))?;
+
+    let _guard = sync::sync_lock().lock().await;
+    let report = sync::check_integrity(&api_client, &state.db, &user.id, session_token.as_deref())
+        .await
+        .map_err(|e| format!("経費の整合性チェックエラー: {e}"))?;
+    if report.resync.is_some() {
+        resolve_receipt_flags(&state, &user.id);
+    }
+    Ok(report)
 }
 
 /// 経費を更新する（API Server経由）
@@ -135,16 +463,49 @@ pub async fn update_expense(
     id: i64,
     dto: UpdateExpenseDto,
     session_token: Option<String>,
+    state: State<'_, AppState>,
     auth_middleware: State<'_, AuthMiddleware>,
 ) -> Result<Expense, String> {
     info!("経費更新処理開始: expense_id={id}, dto={dto:?}");
 
     // 認証チェック
-    let _user = auth_middleware
+    let user = auth_middleware
         .authenticate_request(session_token.as_deref(), "/expenses/update")
         .await
         .map_err(|e| format!("認証エラー: {e}"))?;
 
+    // 入力値の検証（すべてのエラーをまとめて返す）
+    dto.validate_all()
+        .and_then(|errors| ValidationError::ensure_none(&errors))
+        .map_err(|e| e.user_message().to_string())?;
+
+    // 領収書の添付ルールの確認（ミラーにない経費は更新後に判定する）
+    let current = state
+        .try_db(|db| sync::get_mirrored(db, &user.id, id))
+        .map_err(|e| e.to_string())?;
+    if let Some(current) = current {
+        let receipt_url = dto
+            .receipt_url
+            .as_deref()
+            .or(current.receipt_url.as_deref());
+        enforce_receipt_policy(
+            &state,
+            &user.id,
+            &ReceiptCheck {
+                amount: dto.amount.unwrap_or(current.amount),
+                category: dto.category.as_deref().unwrap_or(&current.category),
+                // カテゴリ名だけを変更した場合は、以前のカテゴリーIDを使用しない
+                category_id: if dto.category.is_some() {
+                    dto.category_id
+                } else {
+                    dto.category_id.or(current.category_id)
+                },
+                has_receipt: receipt_url.is_some_and(|url| !url.trim().is_empty()),
+            },
+            dto.receipt_followup,
+        )?;
+    }
+
     // APIクライアントを作成
     let api_client = ApiClient::new().map_err(|e| format!("APIクライアント作成エラー: {e}"))?;
 
@@ -155,8 +516,11 @@ pub async fn update_expense(
         .await
         .map_err(|e| format!("経費更新APIエラー: {e}"))?;
 
+    mirror_expense(&state, &user.id, &response.expense);
+    let expense = track_missing_receipt(&state, &user.id, response.expense, dto.receipt_followup);
+
     info!("経費更新成功: expense_id={id}");
-    Ok(response.expense)
+    Ok(expense)
 }
 
 /// 経費を削除する（API Server経由）
@@ -164,20 +528,23 @@ pub async fn update_expense(
 /// # 引数
 /// * `id` - 経費ID
 /// * `session_token` - セッショントークン
-/// * `auth_middleware` - 認証ミドルウェア
+/// * `app_handle` - Tauriアプリケーションハンドル（アプリケーション状態と認証ミドルウェアを取得する）
 ///
 /// # 戻り値
-/// 成功時はOk(())、失敗時はエラーメッセージ
+/// 削除した経費のIDとリビジョン、または失敗時はエラーメッセージ
 #[tauri::command]
-pub async fn delete_expense(
+pub async fn delete_expense<R: Runtime>(
     id: i64,
     session_token: Option<String>,
-    auth_middleware: State<'_, AuthMiddleware>,
-) -> Result<(), String> {
+    app_handle: AppHandle<R>,
+) -> Result<DeleteResult, String> {
     info!("経費削除処理開始: expense_id={id}");
 
+    let state = app_handle.state::<AppState>();
+    let auth_middleware = app_handle.state::<AuthMiddleware<R>>();
+
     // 認証チェック
-    let _user = auth_middleware
+    let user = auth_middleware
         .authenticate_request(session_token.as_deref(), "/expenses/delete")
         .await
         .map_err(|e| format!("認証エラー: {e}"))?;
@@ -187,13 +554,16 @@ pub async fn delete_expense(
 
     // API Serverに経費削除リクエストを送信
     let endpoint = format!("/api/v1/expenses/{id}");
-    api_client
-        .delete(&endpoint, session_token.as_deref())
+    let response: DeleteResponse = api_client
+        .delete_json(&endpoint, session_token.as_deref())
         .await
         .map_err(|e| format!("経費削除APIエラー: {e}"))?;
 
+    remove_local_annotations(&state, id, &user.id);
+    unmirror_expense(&state, id, &user.id);
+
     info!("経費削除成功: expense_id={id}");
-    Ok(())
+    Ok(response.into())
 }
 
 /// 経費の領収書を削除する（API Server経由）
@@ -201,20 +571,22 @@ pub async fn delete_expense(
 /// # 引数
 /// * `expense_id` - 経費ID
 /// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
 /// * `auth_middleware` - 認証ミドルウェア
 ///
 /// # 戻り値
-/// 削除成功時はtrue、失敗時はエラーメッセージ
+/// 領収書を削除した後の経費、または失敗時はエラーメッセージ
 #[tauri::command]
 pub async fn delete_expense_receipt(
     expense_id: i64,
     session_token: Option<String>,
+    state: State<'_, AppState>,
     auth_middleware: State<'_, AuthMiddleware>,
-) -> Result<bool, String> {
+) -> Result<Expense, String> {
     info!("経費の領収書削除処理開始: expense_id={expense_id}");
 
     // 認証チェック
-    let _user = auth_middleware
+    let user = auth_middleware
         .authenticate_request(session_token.as_deref(), "/expenses/delete-receipt")
         .await
         .map_err(|e| format!("認証エラー: {e}"))?;
@@ -231,14 +603,171 @@ pub async fn delete_expense_receipt(
         category_id: None,
         description: None,
         receipt_url: Some("".to_string()),
+        receipt_followup: false,
     };
 
     let endpoint = format!("/api/v1/expenses/{expense_id}");
-    let _response: UpdateExpenseResponse = api_client
+    let response: UpdateExpenseResponse = api_client
         .put(&endpoint, &dto, session_token.as_deref())
         .await
         .map_err(|e| format!("領収書削除APIエラー: {e}"))?;
 
+    remove_local_annotations(&state, expense_id, &user.id);
+    mirror_expense(&state, &user.id, &response.expense);
+    // 領収書の削除は拒否せず、ルールが適用される場合は未添付として記録する
+    let expense = track_missing_receipt(&state, &user.id, response.expense, false);
+
     info!("経費の領収書削除成功: expense_id={expense_id}");
-    Ok(true)
+    Ok(expense)
+}
+
+/// 経費に付けたローカルの注釈を削除する
+///
+/// API Server側の削除は完了しているため、失敗してもエラーにはせず警告のみ出力します。
+fn remove_local_annotations(state: &AppState, expense_id: i64, user_id: &str) {
+    let result = state.try_db(|db| delete_annotations_for_expense(db, expense_id, user_id));
+    if let Err(e) = result {
+        warn!("注釈の削除に失敗しました: expense_id={expense_id}, error={e}");
+    }
+}
+
+/// API Serverでの作成・更新の結果をローカルのミラーに反映する
+///
+/// 失敗しても次回の同期で反映されるため、警告のみ出力します。
+fn mirror_expense(state: &AppState, user_id: &str, expense: &Expense) {
+    let result = state.try_db(|db| sync::upsert_mirrored(db, user_id, expense));
+    if let Err(e) = result {
+        warn!(
+            "経費のミラーへの反映に失敗しました: expense_id={}, error={e}",
+            expense.id
+        );
+    }
+}
+
+/// API Serverで削除した経費をローカルのミラー・位置情報・店舗の紐付け・領収書未添付の記録から削除する
+fn unmirror_expense(state: &AppState, expense_id: i64, user_id: &str) {
+    let result = state.try_db(|db| {
+        sync::remove_mirrored(db, user_id, expense_id)?;
+        location::remove_expense_location(db, user_id, expense_id)?;
+        merchants::unlink_expense_merchant(db, user_id, expense_id)?;
+        receipt_policies::clear_flag(db, user_id, expense_id)
+    });
+    if let Err(e) = result {
+        warn!("経費のミラーからの削除に失敗しました: expense_id={expense_id}, error={e}");
+    }
+}
+
+/// 領収書の添付ルールを確認し、領収書が必須の経費を保存しない
+fn enforce_receipt_policy(
+    state: &AppState,
+    user_id: &str,
+    check: &ReceiptCheck,
+    followup: bool,
+) -> Result<(), String> {
+    let policies = state
+        .try_db(|db| receipt_policies::list_policies(db, user_id))
+        .map_err(|e| e.to_string())?;
+    receipt_policies::evaluate(&policies, check, followup)
+        .into_result()
+        .map(|_| ())
+        .map_err(|e| e.user_message().to_string())
+}
+
+/// 保存した経費に領収書の添付ルールを適用し、未添付の記録を更新する
+///
+/// 保存は完了しているため、記録に失敗しても警告のみ出力します。
+fn track_missing_receipt(
+    state: &AppState,
+    user_id: &str,
+    mut expense: Expense,
+    followup_requested: bool,
+) -> Expense {
+    let result = state.try_db(|db| {
+        let policies = receipt_policies::list_policies(db, user_id)?;
+        // 保存済みの経費は拒否せず、未添付として記録する
+        let decision =
+            receipt_policies::evaluate(&policies, &ReceiptCheck::for_expense(&expense), true);
+        receipt_policies::apply_decision(db, user_id, expense.id, &decision, followup_requested)
+    });
+    match result {
+        Ok(missing_receipt) => expense.missing_receipt = missing_receipt,
+        Err(e) => warn!(
+            "領収書未添付の記録に失敗しました: expense_id={}, error={e}",
+            expense.id
+        ),
+    }
+    expense
+}
+
+/// 同期で領収書が添付された経費の未添付の記録を解除する
+fn resolve_receipt_flags(state: &AppState, user_id: &str) {
+    let result = state.try_db(|db| receipt_policies::clear_resolved_flags(db, user_id));
+    match result {
+        Ok(0) => {}
+        Ok(cleared) => info!("領収書が添付された経費の未添付の記録を解除しました: count={cleared}"),
+        Err(e) => warn!("領収書未添付の記録の解除に失敗しました: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::migrations::auto_migration::executor::MigrationExecutorTrait;
+
+    /// API Serverが返す経費（サーバー側で設定される日時・カテゴリ情報を含む）
+    fn server_expense(receipt_url: Option<&str>) -> serde_json::Value {
+        serde_json::json!({
+            "id": 7,
+            "date": "2025-04-10",
+            "amount": 3500.0,
+            "category": "飲食費",
+            "category_id": 2,
+            "description": "打ち合わせランチ",
+            "receipt_url": receipt_url,
+            "created_at": "2025-04-10T12:00:00+09:00",
+            "updated_at": "2025-04-10T12:30:00+09:00",
+            "category_color": "#F59E0B",
+            "category_icon": "🍽️",
+            "tax_rate": 0.1,
+            "tax_amount": 318.0
+        })
+    }
+
+    /// 同期でミラーに保存され、一覧取得で返される経費をシリアライズする
+    fn fetched(expense: serde_json::Value) -> serde_json::Value {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        sync::ExpenseMirrorSchemaMigration.execute(&conn).unwrap();
+        let expense: Expense = serde_json::from_value(expense).unwrap();
+        sync::replace_all(&mut conn, "u1", &[expense], "rev-1").unwrap();
+
+        let expenses = sync::list_mirrored(&conn, "u1", None, &ExpenseFilter::new()).unwrap();
+        serde_json::to_value(&expenses[0]).unwrap()
+    }
+
+    #[test]
+    fn test_mutation_responses_match_fetch() {
+        let created: CreateExpenseResponse = serde_json::from_value(serde_json::json!({
+            "success": true,
+            "expense": server_expense(Some("https://receipts.example.com/users/1/receipts/7/a.png")),
+            "timestamp": "2025-04-10T03:00:00.000Z"
+        }))
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(&created.expense).unwrap(),
+            fetched(server_expense(Some(
+                "https://receipts.example.com/users/1/receipts/7/a.png"
+            )))
+        );
+
+        // 領収書の削除は更新レスポンスの経費をそのまま返す
+        let updated: UpdateExpenseResponse = serde_json::from_value(serde_json::json!({
+            "success": true,
+            "expense": server_expense(None),
+            "timestamp": "2025-04-10T03:30:00.000Z"
+        }))
+        .unwrap();
+        let updated = serde_json::to_value(&updated.expense).unwrap();
+        assert_eq!(updated, fetched(server_expense(None)));
+        assert_eq!(updated["receipt_status"]["status"], "none");
+    }
 }
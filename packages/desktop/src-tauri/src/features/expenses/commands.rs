@@ -0,0 +1,863 @@
+// 経費機能のTauriコマンドハンドラー（ローカルデータベース）
+
+use super::{
+    csv_export, location, merchants,
+    models::{
+        CategorySummary, ConfirmMerchantGroupDto, CreateReceiptPolicyDto, Expense, ExpenseFilter,
+        Merchant, MerchantGroupProposal, MerchantSpending, MissingReceiptExpense, NearbyExpense,
+        ReceiptPolicy, UpdateReceiptPolicyDto,
+    },
+    receipt_policies, repository,
+};
+use crate::shared::errors::AppError;
+use crate::shared::export::{wrap_json_export, ExportMeta};
+use crate::shared::utils::calc::{self, CalcExpression, CalcResult};
+use crate::shared::utils::disk_space::{check_disk_space, EXPORT_HEADROOM_BYTES};
+use crate::shared::utils::validate_month;
+use crate::AppState;
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::State;
+
+/// 領収書が添付されていない経費を取得する
+///
+/// # 引数
+/// * `filter` - 検索条件（任意、領収書の有無は無視されます）
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 領収書未添付の経費一覧、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn get_expenses_without_receipts(
+    filter: Option<ExpenseFilter>,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<Vec<Expense>, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/expenses/without-receipts")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| {
+            repository::find_expenses_without_receipts(db, &user.id, &filter.unwrap_or_default())
+        })
+        .map_err(|e| format!("領収書未添付の経費取得に失敗しました: {e}"))
+}
+
+/// 期間内の経費を取得する
+///
+/// # 引数
+/// * `start_date` - 開始日（YYYY-MM-DD形式、この日を含む）
+/// * `end_date` - 終了日（YYYY-MM-DD形式、この日を含む）
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 期間内の経費一覧（日付の新しい順）、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn get_expenses_by_date_range(
+    start_date: String,
+    end_date: String,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<Vec<Expense>, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/expenses/date-range")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| repository::find_by_date_range(db, &user.id, &start_date, &end_date))
+        .map_err(|e| e.user_message().to_string())
+}
+
+/// カテゴリ別の経費件数と合計金額を取得する
+///
+/// # 引数
+/// * `year` - 対象年（任意）
+/// * `month` - 対象月（1〜12、任意）
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// カテゴリ名をキーとした (件数, 合計金額) のマップ、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn get_expense_summary_by_category(
+    year: Option<i32>,
+    month: Option<u32>,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<HashMap<String, (i64, f64)>, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/expenses/summary/category")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| repository::get_expense_count_by_category(db, &user.id, year, month))
+        .map_err(|e| format!("カテゴリ別集計の取得に失敗しました: {e}"))
+}
+
+/// 月別のカテゴリごとの件数・合計金額・割合を取得する
+///
+/// # 引数
+/// * `month` - 対象月（YYYY-MM形式）
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// カテゴリごとの集計（合計金額の多い順）、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn get_monthly_expense_summary(
+    month: String,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<Vec<CategorySummary>, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/expenses/summary/monthly")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    validate_month(&month).map_err(String::from)?;
+
+    state
+        .try_db(|db| repository::find_monthly_summary(db, &user.id, &month))
+        .map_err(|e| e.user_message().to_string())
+}
+
+/// 指定地点の近くで発生した経費を取得する
+///
+/// # 引数
+/// * `latitude` - 緯度
+/// * `longitude` - 経度
+/// * `radius_m` - 半径（メートル）
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 半径内の経費一覧（近い順）、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn get_expenses_near(
+    latitude: f64,
+    longitude: f64,
+    radius_m: f64,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<Vec<NearbyExpense>, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/expenses/near")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| repository::find_expenses_near(db, &user.id, latitude, longitude, radius_m))
+        .map_err(|e| format!("近くの経費の取得に失敗しました: {e}"))
+}
+
+/// 領収書の位置情報を保存する設定を取得する
+///
+/// # 引数
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 保存する場合はtrue、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn get_receipt_location_setting(
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<bool, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/settings/receipt-location")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| location::is_location_storage_enabled(db, &user.id))
+        .map_err(|e| format!("位置情報の設定の取得に失敗しました: {e}"))
+}
+
+/// 領収書の位置情報を保存する設定を変更する
+///
+/// # 引数
+/// * `enabled` - 保存する場合はtrue
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 成功時はOk(())、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn set_receipt_location_setting(
+    enabled: bool,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<(), String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/settings/receipt-location")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| location::set_location_storage_enabled(db, &user.id, enabled))
+        .map_err(|e| format!("位置情報の設定の変更に失敗しました: {e}"))
+}
+
+/// 保存済みの経費の位置情報をすべて削除する
+///
+/// # 引数
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 位置情報を削除した経費の件数、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn strip_location_data(
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<usize, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/expenses/location/strip")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    let cleared = state
+        .try_db(|db| location::strip_location_data(db, &user.id))
+        .map_err(|e| format!("位置情報の削除に失敗しました: {e}"))?;
+    log::info!(
+        "経費の位置情報を削除しました: user_id={}, 件数={cleared}",
+        user.id
+    );
+    Ok(cleared)
+}
+
+/// 検索条件に一致する経費をJSONファイルに書き出す
+///
+/// # 引数
+/// * `filter` - 検索条件（任意）
+/// * `include_location` - 位置情報を含める場合はtrue（既定では含めない）
+/// * `file_path` - 出力先ファイルパス
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 書き出した経費の件数、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn export_expenses_json(
+    filter: Option<ExpenseFilter>,
+    include_location: Option<bool>,
+    file_path: String,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<usize, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/expenses/export")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    let expenses = state
+        .try_db(|db| repository::find_expenses(db, &user.id, &filter.unwrap_or_default()))
+        .map_err(|e| format!("経費の取得に失敗しました: {e}"))?;
+
+    let expenses: Vec<Expense> = if include_location.unwrap_or(false) {
+        expenses
+    } else {
+        expenses
+            .into_iter()
+            .map(Expense::without_location)
+            .collect()
+    };
+
+    let export = wrap_json_export(&ExportMeta::current(Some(&user.id)), &expenses)
+        .map_err(|e| format!("エクスポートの作成に失敗しました: {e}"))?;
+    let json = serde_json::to_string_pretty(&export)
+        .map_err(|e| format!("エクスポートの作成に失敗しました: {e}"))?;
+    check_disk_space(
+        Path::new(&file_path),
+        json.len() as u64 + EXPORT_HEADROOM_BYTES,
+    )
+    .map_err(|e| e.to_string())?;
+    std::fs::write(&file_path, json)
+        .map_err(|e| format!("出力ファイルの書き込みに失敗しました: {e}"))?;
+
+    Ok(expenses.len())
+}
+
+/// 期間内の経費をCSVファイルに書き出す
+///
+/// # 引数
+/// * `start_date` - 開始日（YYYY-MM-DD形式、この日を含む）
+/// * `end_date` - 終了日（YYYY-MM-DD形式、この日を含む）
+/// * `file_path` - 出力先ファイルパス
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 書き出した経費の件数、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn export_expenses_csv(
+    start_date: String,
+    end_date: String,
+    file_path: String,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<usize, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/expenses/export")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    let (count, csv) = state
+        .try_db(|db| {
+            let mut csv = Vec::new();
+            let count =
+                csv_export::export_expenses_csv(db, &user.id, &start_date, &end_date, &mut csv)?;
+            Ok((count, csv))
+        })
+        .map_err(|e| e.user_message().to_string())?;
+
+    check_disk_space(
+        Path::new(&file_path),
+        csv.len() as u64 + EXPORT_HEADROOM_BYTES,
+    )
+    .map_err(|e| e.user_message().to_string())?;
+    std::fs::write(&file_path, csv).map_err(|e| String::from(AppError::from(e)))?;
+
+    Ok(count)
+}
+
+/// 店舗一覧を取得する
+///
+/// # 引数
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 店舗一覧（別名を含む、名前順）、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn get_merchants(
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<Vec<Merchant>, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/merchants")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| merchants::list_merchants(db, &user.id))
+        .map_err(|e| format!("店舗の取得に失敗しました: {e}"))
+}
+
+/// 店舗を作成する
+///
+/// 作成後、店舗が未設定の経費のうち説明が別名に一致するものを紐付けます。
+///
+/// # 引数
+/// * `name` - 正式名称
+/// * `aliases` - 別名（任意）
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 作成した店舗、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn create_merchant(
+    name: String,
+    aliases: Option<Vec<String>>,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<Merchant, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/merchants/create")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| {
+            let merchant =
+                merchants::create_merchant(db, &user.id, &name, &aliases.unwrap_or_default())?;
+            link_unassigned_expenses(db, &user.id);
+            Ok(merchant)
+        })
+        .map_err(|e| format!("店舗の作成に失敗しました: {e}"))
+}
+
+/// 店舗の正式名称を変更する
+///
+/// # 引数
+/// * `merchant_id` - 店舗ID
+/// * `name` - 新しい正式名称（変更前の名称は別名として残ります）
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 更新後の店舗、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn update_merchant_name(
+    merchant_id: i64,
+    name: String,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<Merchant, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/merchants/update")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| {
+            let merchant = merchants::rename_merchant(db, &user.id, merchant_id, &name)?;
+            link_unassigned_expenses(db, &user.id);
+            Ok(merchant)
+        })
+        .map_err(|e| format!("店舗名の変更に失敗しました: {e}"))
+}
+
+/// 店舗を削除する
+///
+/// 紐付けていた経費の店舗は未設定に戻ります（経費と説明は変更しません）。
+///
+/// # 引数
+/// * `merchant_id` - 店舗ID
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 削除した場合はtrue、該当する店舗がない場合はfalse、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn delete_merchant(
+    merchant_id: i64,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<bool, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/merchants/delete")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| merchants::delete_merchant(db, &user.id, merchant_id))
+        .map_err(|e| format!("店舗の削除に失敗しました: {e}"))
+}
+
+/// 店舗に別名を追加する
+///
+/// 追加後、店舗が未設定の経費のうち説明が別名に一致するものを紐付けます。
+///
+/// # 引数
+/// * `merchant_id` - 店舗ID
+/// * `alias` - 別名
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 更新後の店舗、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn add_merchant_alias(
+    merchant_id: i64,
+    alias: String,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<Merchant, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/merchants/aliases")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| {
+            let merchant = merchants::add_merchant_alias(db, &user.id, merchant_id, &alias)?;
+            link_unassigned_expenses(db, &user.id);
+            Ok(merchant)
+        })
+        .map_err(|e| format!("別名の追加に失敗しました: {e}"))
+}
+
+/// 店舗から別名を削除する
+///
+/// 既に紐付けた経費の店舗は変更しません。
+///
+/// # 引数
+/// * `merchant_id` - 店舗ID
+/// * `alias` - 削除する別名
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 更新後の店舗、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn delete_merchant_alias(
+    merchant_id: i64,
+    alias: String,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<Merchant, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/merchants/aliases/delete")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| merchants::remove_merchant_alias(db, &user.id, merchant_id, &alias))
+        .map_err(|e| format!("別名の削除に失敗しました: {e}"))
+}
+
+/// 経費の説明または領収書の読み取り結果を店舗の別名と照合し、経費に紐付ける
+///
+/// 経費の説明は変更しません。
+///
+/// # 引数
+/// * `expense_id` - 経費ID
+/// * `ocr_text` - 領収書の読み取り結果（任意、説明が一致しない場合に照合）
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 紐付けた店舗のID（一致しない場合はNone）、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn link_expense_merchant(
+    expense_id: i64,
+    ocr_text: Option<String>,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<Option<i64>, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/merchants/link")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| {
+            merchants::link_expense_merchant(db, &user.id, expense_id, ocr_text.as_deref())
+        })
+        .map_err(|e| format!("店舗の紐付けに失敗しました: {e}"))
+}
+
+/// 店舗別の経費件数と合計金額を取得する
+///
+/// # 引数
+/// * `start_date` - 開始日（YYYY-MM-DD形式、任意）
+/// * `end_date` - 終了日（YYYY-MM-DD形式、任意）
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 店舗別の集計（合計金額の多い順）、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn get_spending_by_merchant(
+    start_date: Option<String>,
+    end_date: Option<String>,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<Vec<MerchantSpending>, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/expenses/summary/merchant")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| {
+            merchants::get_spending_by_merchant(
+                db,
+                &user.id,
+                start_date.as_deref(),
+                end_date.as_deref(),
+            )
+        })
+        .map_err(|e| format!("店舗別集計の取得に失敗しました: {e}"))
+}
+
+/// 店舗が未設定の経費から店舗のグループ化の提案を取得する
+///
+/// 提案を返すだけで、店舗の作成や紐付けは`confirm_merchant_group`で確定するまで行いません。
+///
+/// # 引数
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// グループ化の提案（件数の多い順）、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn get_merchant_group_proposals(
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<Vec<MerchantGroupProposal>, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/merchants/proposals")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| merchants::propose_merchant_groups(db, &user.id))
+        .map_err(|e| format!("店舗のグループ化の提案に失敗しました: {e}"))
+}
+
+/// 店舗のグループ化の提案を確定する
+///
+/// # 引数
+/// * `dto` - 紐付け先の店舗（新規作成する場合は名前）・追加する別名・紐付ける経費
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 紐付け先の店舗、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn confirm_merchant_group(
+    dto: ConfirmMerchantGroupDto,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<Merchant, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/merchants/proposals/confirm")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| merchants::confirm_merchant_group(db, &user.id, &dto))
+        .map_err(|e| format!("店舗のグループ化の確定に失敗しました: {e}"))
+}
+
+/// 領収書の添付ルールを取得する
+///
+/// # 引数
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 領収書の添付ルール一覧（カテゴリ名順）、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn get_receipt_policies(
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<Vec<ReceiptPolicy>, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/receipt-policies")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| receipt_policies::list_policies(db, &user.id))
+        .map_err(|e| format!("領収書の添付ルールの取得に失敗しました: {e}"))
+}
+
+/// 領収書の添付ルールを作成する
+///
+/// # 引数
+/// * `dto` - 領収書の添付ルール作成用DTO
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 作成したルール、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn create_receipt_policy(
+    dto: CreateReceiptPolicyDto,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<ReceiptPolicy, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/receipt-policies/create")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| receipt_policies::create_policy(db, &user.id, &dto))
+        .map_err(|e| format!("領収書の添付ルールの作成に失敗しました: {e}"))
+}
+
+/// 領収書の添付ルールの最低金額・強さを変更する
+///
+/// 変更は以後に保存する経費に適用されます（記録済みの未添付の経費はそのまま残ります）。
+///
+/// # 引数
+/// * `policy_id` - ルールID
+/// * `dto` - 領収書の添付ルール更新用DTO
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 更新後のルール、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn update_receipt_policy(
+    policy_id: i64,
+    dto: UpdateReceiptPolicyDto,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<ReceiptPolicy, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/receipt-policies/update")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| receipt_policies::update_policy(db, &user.id, policy_id, &dto))
+        .map_err(|e| format!("領収書の添付ルールの更新に失敗しました: {e}"))
+}
+
+/// 領収書の添付ルールを削除する
+///
+/// このルールによる領収書未添付の記録も解除します。
+///
+/// # 引数
+/// * `policy_id` - ルールID
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 削除した場合はtrue、該当するルールがない場合はfalse、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn delete_receipt_policy(
+    policy_id: i64,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<bool, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/receipt-policies/delete")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| receipt_policies::delete_policy(db, &user.id, policy_id))
+        .map_err(|e| format!("領収書の添付ルールの削除に失敗しました: {e}"))
+}
+
+/// 領収書の添付ルールに反して領収書が未添付の経費を取得する
+///
+/// 月次の集計では`start_date`・`end_date`に月初と月末を指定して件数を取得します。
+///
+/// # 引数
+/// * `start_date` - 開始日（任意、YYYY-MM-DD形式、この日を含む）
+/// * `end_date` - 終了日（任意、YYYY-MM-DD形式、この日を含む）
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 領収書未添付の経費一覧（日付の新しい順）、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn get_missing_receipt_expenses(
+    start_date: Option<String>,
+    end_date: Option<String>,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<Vec<MissingReceiptExpense>, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/receipt-policies/missing")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| {
+            receipt_policies::missing_receipt_expenses(
+                db,
+                &user.id,
+                start_date.as_deref(),
+                end_date.as_deref(),
+            )
+        })
+        .map_err(|e| format!("領収書未添付の経費取得に失敗しました: {e}"))
+}
+
+/// 金額の計算式を評価する
+///
+/// 経費フォームでの税抜金額・消費税額などのプレビューに使用します。
+/// 計算のみを行い、データベースにはアクセスしません。
+///
+/// # 引数
+/// * `expression` - 計算式（`op`で計算の種類を指定）
+///
+/// # 戻り値
+/// 計算結果（端数処理で増減した額を含む）、または失敗時はエラーメッセージ
+#[tauri::command]
+pub fn calculate(expression: CalcExpression) -> Result<CalcResult, String> {
+    calc::calculate(&expression).map_err(|e| e.user_message().to_string())
+}
+
+/// 店舗が未設定の経費を別名と照合して紐付ける
+///
+/// 店舗・別名の変更自体は完了しているため、失敗してもログに記録するだけにします。
+fn link_unassigned_expenses(db: &rusqlite::Connection, user_id: &str) {
+    match merchants::link_unassigned_expenses(db, user_id) {
+        Ok(linked) if linked > 0 => {
+            log::info!("経費を店舗に紐付けました: user_id={user_id}, 件数={linked}");
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("経費の店舗の紐付けに失敗しました: {e}"),
+    }
+}
@@ -0,0 +1,204 @@
+/// 経費のCSVエクスポート（税務申告・会計事務所への提出用）
+///
+/// 日本語版WindowsのExcelで文字化けせずに開けるよう、UTF-8のBOM付きで書き出します。
+/// 最終行には金額の合計行を付けます。
+use crate::features::expenses::models::{Expense, ExpenseFilter, ExpenseSortKey, GetExpensesQuery};
+use crate::features::expenses::sync;
+use crate::shared::errors::AppResult;
+use crate::shared::export::escape_csv_field;
+use crate::shared::utils::{format_amount, jst_datetime, round_to_currency_precision};
+use rusqlite::Connection;
+use std::io::Write;
+
+/// UTF-8のBOM
+const UTF8_BOM: &str = "\u{feff}";
+
+/// CSVのヘッダー行
+const CSV_HEADERS: [&str; 6] = [
+    "date",
+    "amount",
+    "category",
+    "description",
+    "receipt_url",
+    "created_at",
+];
+
+/// 合計行の見出し（日付の列に出力する）
+pub(crate) const TOTAL_ROW_LABEL: &str = "合計";
+
+/// 日時をJSTのRFC3339形式に変換する
+///
+/// 解析できない値はそのまま出力します。
+fn to_jst(value: &str) -> String {
+    jst_datetime::parse(value)
+        .map(|datetime| jst_datetime::format(&datetime))
+        .unwrap_or_else(|_| value.to_string())
+}
+
+/// 経費1件分のCSVの行を作成する
+fn expense_row(expense: &Expense) -> String {
+    [
+        escape_csv_field(&expense.date),
+        format_amount(expense.amount),
+        escape_csv_field(&expense.category),
+        escape_csv_field(expense.description.as_deref().unwrap_or("")),
+        escape_csv_field(expense.receipt_url.as_deref().unwrap_or("")),
+        escape_csv_field(&to_jst(&expense.created_at)),
+    ]
+    .join(",")
+}
+
+/// 経費一覧をCSVとして書き出す
+///
+/// # 引数
+/// * `expenses` - 経費一覧（この順序で出力します）
+/// * `writer` - 書き込み先
+///
+/// # 戻り値
+/// 金額の合計
+pub fn write_expenses_csv<W: Write + ?Sized>(
+    expenses: &[Expense],
+    writer: &mut W,
+) -> AppResult<f64> {
+    write!(writer, "{UTF8_BOM}")?;
+    writeln!(writer, "{}", CSV_HEADERS.join(","))?;
+
+    let mut total = 0.0;
+    for expense in expenses {
+        writeln!(writer, "{}", expense_row(expense))?;
+        total += expense.amount;
+    }
+    let total = round_to_currency_precision(total);
+
+    let padding = ",".repeat(CSV_HEADERS.len() - 2);
+    writeln!(
+        writer,
+        "{TOTAL_ROW_LABEL},{}{padding}",
+        format_amount(total)
+    )?;
+    writer.flush()?;
+    Ok(total)
+}
+
+/// 期間内の経費を日付の古い順にCSVとして書き出す
+///
+/// API Server版で一覧に表示している経費のミラー（`expense_mirror`）から読み込みます。
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+/// * `start_date` - 開始日（YYYY-MM-DD形式、この日を含む）
+/// * `end_date` - 終了日（YYYY-MM-DD形式、この日を含む）
+/// * `writer` - 書き込み先
+///
+/// # 戻り値
+/// 書き出した経費の件数
+pub fn export_expenses_csv<W: Write + ?Sized>(
+    conn: &Connection,
+    user_id: &str,
+    start_date: &str,
+    end_date: &str,
+    writer: &mut W,
+) -> AppResult<usize> {
+    let query = GetExpensesQuery {
+        from_date: Some(start_date.to_string()),
+        to_date: Some(end_date.to_string()),
+        sort_by: ExpenseSortKey::DateAsc,
+        ..GetExpensesQuery::default()
+    };
+    let page = sync::list_mirrored_page(conn, user_id, None, &ExpenseFilter::new(), &query)?;
+    write_expenses_csv(&page.expenses, writer)?;
+    Ok(page.expenses.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::database::connection::open_and_migrate_in_memory_database;
+
+    fn expense(date: &str, amount: f64, description: Option<&str>, created_at: &str) -> Expense {
+        serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "date": date,
+            "amount": amount,
+            "category": "交通費",
+            "description": description,
+            "receipt_url": null,
+            "created_at": created_at,
+            "updated_at": created_at,
+        }))
+        .unwrap()
+    }
+
+    fn write(expenses: &[Expense]) -> (String, f64) {
+        let mut output = Vec::new();
+        let total = write_expenses_csv(expenses, &mut output).unwrap();
+        (String::from_utf8(output).unwrap(), total)
+    }
+
+    #[test]
+    fn test_write_expenses_csv() {
+        let (csv, total) = write(&[
+            expense(
+                "2024-04-01",
+                1200.0,
+                Some("新幹線, 東京→大阪"),
+                "2024-04-01T09:00:00+09:00",
+            ),
+            expense(
+                "2024-04-02",
+                880.5,
+                Some("打ち合わせ\n\"カフェ\""),
+                "2024-04-02T01:30:00Z",
+            ),
+        ]);
+
+        assert_eq!(total, 2080.5);
+        assert!(csv.starts_with('\u{feff}'));
+        assert_eq!(
+            csv.trim_start_matches('\u{feff}'),
+            "date,amount,category,description,receipt_url,created_at\n\
+             2024-04-01,1200,交通費,\"新幹線, 東京→大阪\",,2024-04-01T09:00:00+09:00\n\
+             2024-04-02,880.50,交通費,\"打ち合わせ\n\"\"カフェ\"\"\",,2024-04-02T10:30:00+09:00\n\
+             合計,2080.50,,,,\n"
+        );
+    }
+
+    #[test]
+    fn test_write_expenses_csv_empty() {
+        let (csv, total) = write(&[]);
+
+        assert_eq!(total, 0.0);
+        assert_eq!(
+            csv,
+            "\u{feff}date,amount,category,description,receipt_url,created_at\n合計,0,,,,\n"
+        );
+    }
+
+    #[test]
+    fn test_export_expenses_csv_is_scoped_to_user() {
+        let conn = open_and_migrate_in_memory_database().unwrap();
+        for (id, user_id, date, amount) in [
+            (1, "user-1", "2024-04-02", 500.0),
+            (2, "user-1", "2024-04-01", 1200.0),
+            (3, "user-2", "2024-04-01", 9999.0),
+            (4, "user-1", "2024-05-01", 300.0),
+        ] {
+            let mut expense = expense(date, amount, None, "2024-04-01T09:00:00+09:00");
+            expense.id = id;
+            sync::upsert_mirrored(&conn, user_id, &expense).unwrap();
+        }
+
+        let mut output = Vec::new();
+        let count =
+            export_expenses_csv(&conn, "user-1", "2024-04-01", "2024-04-30", &mut output).unwrap();
+
+        assert_eq!(count, 2);
+        let csv = String::from_utf8(output).unwrap();
+        assert!(!csv.contains("9999"));
+        assert!(!csv.contains("2024-05-01"));
+        // 日付の古い順に書き出す
+        assert!(csv.find("2024-04-01,1200").unwrap() < csv.find("2024-04-02,500").unwrap());
+        assert!(csv.ends_with("合計,1700,,,,\n"));
+    }
+}
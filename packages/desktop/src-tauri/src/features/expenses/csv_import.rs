@@ -0,0 +1,210 @@
+/// 経費のCSV取り込み（表計算ソフトからの移行用）
+///
+/// 行ごとに日付・金額・カテゴリ・説明を検証し、問題のない行だけをAPIサーバーの一括作成
+/// （`POST /api/v1/expenses/batch`）でまとめて登録します。
+/// 問題のある行は行番号と理由を返し、取り込みは中断しません。
+/// 経費のCSVエクスポートで出力したファイルもそのまま取り込めます（合計行は無視します）。
+use crate::features::expenses::csv_export::TOTAL_ROW_LABEL;
+use crate::features::expenses::models::{CreateExpenseDto, RowError};
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::export::{parse_csv_records, CsvHeaderIndex};
+use crate::shared::utils::{
+    validate_amount, validate_category, validate_date, validate_description,
+};
+
+const DATE_COLUMNS: &[&str] = &["date", "日付"];
+const AMOUNT_COLUMNS: &[&str] = &["amount", "金額"];
+const CATEGORY_COLUMNS: &[&str] = &["category", "カテゴリ"];
+const DESCRIPTION_COLUMNS: &[&str] = &["description", "説明", "摘要"];
+
+/// 一度に取り込める経費の件数（APIサーバーの一括作成の上限）
+pub const MAX_IMPORT_ROWS: usize = 500;
+
+/// 金額の表記を数値に変換する
+///
+/// "1,200"、"¥1,200"、"1200円" のような表記を受け付けます。
+fn parse_amount(text: &str) -> AppResult<f64> {
+    let numeric: String = text
+        .chars()
+        .filter(|c| !matches!(c, ',' | '¥' | '￥' | '円') && !c.is_whitespace())
+        .collect();
+    if numeric.is_empty() {
+        return Err(AppError::validation("金額が空です"));
+    }
+
+    numeric
+        .parse::<f64>()
+        .map_err(|_| AppError::validation(format!("金額を解析できません: {}", text.trim())))
+}
+
+/// CSVの1行を経費作成用DTOに変換し、検証する
+///
+/// # 戻り値
+/// 作成用DTO、または問題がある場合はすべての理由を結合したメッセージ
+fn parse_row(columns: &ParsedColumns, fields: &[String]) -> Result<CreateExpenseDto, String> {
+    let field = |index: usize| fields.get(index).map(|f| f.trim()).unwrap_or("");
+
+    let amount = parse_amount(field(columns.amount));
+    let dto = CreateExpenseDto {
+        date: field(columns.date).to_string(),
+        amount: *amount.as_ref().unwrap_or(&0.0),
+        category: field(columns.category).to_string(),
+        category_id: None,
+        description: columns
+            .description
+            .map(field)
+            .filter(|description| !description.is_empty())
+            .map(str::to_string),
+        user_id: None,
+        tax_rate: None,
+        tax_amount: None,
+        receipt_followup: false,
+        currency: None,
+    };
+
+    let errors: Vec<String> = [
+        validate_date(&dto.date),
+        amount.and_then(validate_amount),
+        validate_category(&dto.category),
+        validate_description(&dto.description),
+    ]
+    .into_iter()
+    .filter_map(|result| result.err())
+    .map(|e| e.user_message().to_string())
+    .collect();
+
+    if errors.is_empty() {
+        Ok(dto)
+    } else {
+        Err(errors.join("、"))
+    }
+}
+
+/// 取り込みに使う列の番号
+struct ParsedColumns {
+    date: usize,
+    amount: usize,
+    category: usize,
+    description: Option<usize>,
+}
+
+/// 経費CSVの内容を解析し、行ごとに検証する
+///
+/// # 引数
+/// * `content` - CSVファイルの内容（1行目はヘッダー）
+///
+/// # 戻り値
+/// (取り込み可能な行の作成用DTO, 取り込まない行)、またはCSVが空・必要な列がない場合はバリデーションエラー
+pub fn parse_expenses_csv(content: &str) -> AppResult<(Vec<CreateExpenseDto>, Vec<RowError>)> {
+    let records = parse_csv_records(content);
+    let Some((_, header)) = records.first() else {
+        return Err(AppError::validation("CSVファイルが空です"));
+    };
+
+    let index = CsvHeaderIndex::new(header);
+    let columns = ParsedColumns {
+        date: index.require(DATE_COLUMNS)?,
+        amount: index.require(AMOUNT_COLUMNS)?,
+        category: index.require(CATEGORY_COLUMNS)?,
+        description: index.find(DESCRIPTION_COLUMNS),
+    };
+
+    let mut dtos = Vec::new();
+    let mut skipped = Vec::new();
+    for (line, fields) in &records[1..] {
+        // エクスポートで付けた合計行は経費ではない
+        if fields.get(columns.date).map(|f| f.trim()) == Some(TOTAL_ROW_LABEL) {
+            continue;
+        }
+
+        match parse_row(&columns, fields) {
+            Ok(dto) => dtos.push(dto),
+            Err(reason) => skipped.push(RowError {
+                line: *line,
+                reason,
+            }),
+        }
+    }
+
+    Ok((dtos, skipped))
+}
+
+/// 取り込む経費の件数がAPIサーバーの一括作成の上限以内か確認する
+///
+/// # 引数
+/// * `dtos` - 取り込む経費の作成用DTO
+///
+/// # 戻り値
+/// 上限以内の場合はOk(())、超える場合はバリデーションエラー
+pub fn ensure_import_size(dtos: &[CreateExpenseDto]) -> AppResult<()> {
+    if dtos.len() > MAX_IMPORT_ROWS {
+        return Err(AppError::validation(format!(
+            "一度に取り込める経費は{MAX_IMPORT_ROWS}件までです（{}件）。ファイルを分割してください",
+            dtos.len()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "\u{feff}date,amount,category,description\n\
+        2024-01-15,\"1,200\",交通費,電車代\n\
+        2024/01/16,500,飲食費,\n\
+        2024-01-17,-100,,返金\n\
+        2024-01-18,¥3000,消耗品費,\"文具, ノート\"\n\
+        合計,4200,,\n";
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("1,200").unwrap(), 1200.0);
+        assert_eq!(parse_amount("￥980").unwrap(), 980.0);
+        assert_eq!(parse_amount("1500円").unwrap(), 1500.0);
+        assert!(parse_amount("").is_err());
+        assert!(parse_amount("千円").is_err());
+    }
+
+    #[test]
+    fn test_parse_expenses_csv_reports_invalid_rows() {
+        let (dtos, skipped) = parse_expenses_csv(CSV).unwrap();
+
+        assert_eq!(dtos.len(), 2);
+        assert_eq!(dtos[0].amount, 1200.0);
+        assert_eq!(dtos[1].description.as_deref(), Some("文具, ノート"));
+        let lines: Vec<usize> = skipped.iter().map(|e| e.line).collect();
+        assert_eq!(lines, [3, 4]);
+        // 1行に複数の問題がある場合はすべての理由を返す
+        assert!(skipped[1].reason.contains('、'));
+    }
+
+    #[test]
+    fn test_parse_expenses_csv_with_japanese_headers() {
+        let (dtos, skipped) =
+            parse_expenses_csv("日付,金額,カテゴリ\n2024-02-01,800,交通費\n").unwrap();
+
+        assert_eq!(dtos.len(), 1);
+        assert_eq!(dtos[0].description, None);
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn test_parse_expenses_csv_requires_columns() {
+        assert!(parse_expenses_csv("").is_err());
+        let err = parse_expenses_csv("date,category\n2024-01-01,交通費\n").unwrap_err();
+        assert!(err.user_message().contains("amount"));
+    }
+
+    #[test]
+    fn test_ensure_import_size() {
+        let csv = format!(
+            "date,amount,category\n{}",
+            "2024-02-01,800,交通費\n".repeat(MAX_IMPORT_ROWS + 1)
+        );
+        let (dtos, _) = parse_expenses_csv(&csv).unwrap();
+
+        assert!(ensure_import_size(&dtos[..MAX_IMPORT_ROWS]).is_ok());
+        assert!(ensure_import_size(&dtos).is_err());
+    }
+}
@@ -0,0 +1,460 @@
+/// 経費の位置情報
+///
+/// 領収書画像のEXIFから取り出したGPS座標を経費に保存します。
+/// 位置情報の保存はユーザーごとの設定で、既定では無効です。
+/// 無効の場合もEXIFはアップロード前に削除され、座標は保存されません。
+///
+/// 経費はAPIサーバーで管理されるため、座標は(ユーザーID, 経費ID)をキーとした
+/// ローカルの`expense_locations`テーブルに保存し、検索時に経費ミラーと結合します。
+use crate::features::migrations::auto_migration::executor::MigrationExecutorTrait;
+use crate::features::migrations::auto_migration::models::{
+    ExecutableMigrationDefinition, MigrationDefinition,
+};
+use crate::features::migrations::auto_migration::registry::MigrationRegistry;
+use crate::features::receipts::exif::GpsCoordinates;
+use crate::shared::database::connection::check_column_exists;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::utils::get_current_jst_timestamp;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// 近くの経費を検索する際の最大半径（メートル）
+pub const MAX_NEARBY_RADIUS_M: f64 = 50_000.0;
+
+/// 地球の平均半径（メートル）
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// 経費テーブルに追加する位置情報カラム（カラム名, 定義）
+const EXPENSE_LOCATION_COLUMNS: &[(&str, &str)] = &[
+    ("latitude", "REAL"),
+    ("longitude", "REAL"),
+    ("location_opt_in", "INTEGER"),
+];
+
+/// 位置情報マイグレーションのSQL（カラム追加は存在確認のうえ個別に実行）
+const EXPENSE_LOCATION_SQL: &str = "
+    ALTER TABLE expenses ADD COLUMN latitude REAL;
+    ALTER TABLE expenses ADD COLUMN longitude REAL;
+    ALTER TABLE expenses ADD COLUMN location_opt_in INTEGER;
+    CREATE INDEX IF NOT EXISTS idx_expenses_location
+        ON expenses(latitude, longitude) WHERE latitude IS NOT NULL;
+    CREATE TABLE IF NOT EXISTS user_privacy_settings (
+        user_id TEXT PRIMARY KEY,
+        store_receipt_location INTEGER NOT NULL DEFAULT 0,
+        updated_at TEXT NOT NULL
+    );
+";
+
+/// 経費の位置情報テーブルのスキーマ
+const EXPENSE_LOCATIONS_SCHEMA_SQL: &str = "
+    CREATE TABLE IF NOT EXISTS expense_locations (
+        user_id TEXT NOT NULL,
+        expense_id INTEGER NOT NULL,
+        latitude REAL NOT NULL,
+        longitude REAL NOT NULL,
+        updated_at TEXT NOT NULL,
+        PRIMARY KEY (user_id, expense_id)
+    );
+    CREATE INDEX IF NOT EXISTS idx_expense_locations_user_latitude
+        ON expense_locations(user_id, latitude);
+";
+
+/// 経費の位置情報マイグレーション実行器
+pub struct ExpenseLocationMigration;
+
+impl MigrationExecutorTrait for ExpenseLocationMigration {
+    fn name(&self) -> &str {
+        "009_add_expense_location"
+    }
+
+    fn execute(&self, conn: &Connection) -> Result<(), String> {
+        add_expense_location_columns(conn).map_err(|e| format!("位置情報カラム追加エラー: {e}"))
+    }
+}
+
+/// 経費の位置情報用マイグレーション定義を取得する
+///
+/// # 戻り値
+/// 実行可能なマイグレーション定義
+pub fn get_expense_location_definition() -> ExecutableMigrationDefinition {
+    let definition = MigrationDefinition::new(
+        "009_add_expense_location".to_string(),
+        "3.5.0".to_string(),
+        "経費の位置情報カラムとプライバシー設定テーブルの追加".to_string(),
+        MigrationRegistry::calculate_checksum(EXPENSE_LOCATION_SQL),
+    );
+
+    ExecutableMigrationDefinition::new(definition, Box::new(ExpenseLocationMigration))
+}
+
+/// 経費テーブルに位置情報カラムを追加し、プライバシー設定テーブルを作成する
+///
+/// # 引数
+/// * `conn` - データベース接続
+pub fn add_expense_location_columns(conn: &Connection) -> AppResult<()> {
+    for (column, definition) in EXPENSE_LOCATION_COLUMNS {
+        if !check_column_exists(conn, "expenses", column) {
+            conn.execute(
+                &format!("ALTER TABLE expenses ADD COLUMN {column} {definition}"),
+                [],
+            )?;
+        }
+    }
+
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_expenses_location
+             ON expenses(latitude, longitude) WHERE latitude IS NOT NULL;
+         CREATE TABLE IF NOT EXISTS user_privacy_settings (
+             user_id TEXT PRIMARY KEY,
+             store_receipt_location INTEGER NOT NULL DEFAULT 0,
+             updated_at TEXT NOT NULL
+         );",
+    )?;
+    Ok(())
+}
+
+/// 経費の位置情報テーブル作成マイグレーション実行器
+pub struct ExpenseLocationsSchemaMigration;
+
+impl MigrationExecutorTrait for ExpenseLocationsSchemaMigration {
+    fn name(&self) -> &str {
+        "015_create_expense_locations"
+    }
+
+    fn execute(&self, conn: &Connection) -> Result<(), String> {
+        conn.execute_batch(EXPENSE_LOCATIONS_SCHEMA_SQL)
+            .map_err(|e| format!("expense_locationsテーブル作成エラー: {e}"))
+    }
+}
+
+/// 経費の位置情報テーブル用マイグレーション定義を取得する
+///
+/// # 戻り値
+/// 実行可能なマイグレーション定義
+pub fn get_expense_locations_schema_definition() -> ExecutableMigrationDefinition {
+    let definition = MigrationDefinition::new(
+        "015_create_expense_locations".to_string(),
+        "3.11.0".to_string(),
+        "API Server版の経費の位置情報テーブルの作成".to_string(),
+        MigrationRegistry::calculate_checksum(EXPENSE_LOCATIONS_SCHEMA_SQL),
+    );
+
+    ExecutableMigrationDefinition::new(definition, Box::new(ExpenseLocationsSchemaMigration))
+}
+
+/// 領収書の位置情報を保存する設定を取得する
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+///
+/// # 戻り値
+/// 保存する場合はtrue（未設定の場合はfalse）
+pub fn is_location_storage_enabled(conn: &Connection, user_id: &str) -> AppResult<bool> {
+    let enabled = conn
+        .query_row(
+            "SELECT store_receipt_location FROM user_privacy_settings WHERE user_id = ?1",
+            params![user_id],
+            |row| row.get::<_, bool>(0),
+        )
+        .optional()?;
+    Ok(enabled.unwrap_or(false))
+}
+
+/// 領収書の位置情報を保存する設定を変更する
+///
+/// 無効にしても保存済みの座標は残ります。削除する場合は`strip_location_data`を使用します。
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+/// * `enabled` - 保存する場合はtrue
+pub fn set_location_storage_enabled(
+    conn: &Connection,
+    user_id: &str,
+    enabled: bool,
+) -> AppResult<()> {
+    conn.execute(
+        "INSERT INTO user_privacy_settings (user_id, store_receipt_location, updated_at)
+         VALUES (?1, ?2, ?3)
+         ON CONFLICT(user_id) DO UPDATE SET
+             store_receipt_location = excluded.store_receipt_location,
+             updated_at = excluded.updated_at",
+        params![user_id, enabled, get_current_jst_timestamp()],
+    )?;
+    Ok(())
+}
+
+/// 経費に位置情報を保存する
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+/// * `expense_id` - 経費ID（APIサーバーの経費ID）
+/// * `location` - GPS座標
+pub fn save_expense_location(
+    conn: &Connection,
+    user_id: &str,
+    expense_id: i64,
+    location: &GpsCoordinates,
+) -> AppResult<()> {
+    if !location.is_valid() {
+        return Err(AppError::validation("位置情報の座標が範囲外です"));
+    }
+
+    conn.execute(
+        "INSERT INTO expense_locations (user_id, expense_id, latitude, longitude, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(user_id, expense_id) DO UPDATE SET
+             latitude = excluded.latitude,
+             longitude = excluded.longitude,
+             updated_at = excluded.updated_at",
+        params![
+            user_id,
+            expense_id,
+            location.latitude,
+            location.longitude,
+            get_current_jst_timestamp()
+        ],
+    )?;
+    Ok(())
+}
+
+/// 経費の位置情報を削除する
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+/// * `expense_id` - 経費ID
+pub fn remove_expense_location(conn: &Connection, user_id: &str, expense_id: i64) -> AppResult<()> {
+    conn.execute(
+        "DELETE FROM expense_locations WHERE user_id = ?1 AND expense_id = ?2",
+        params![user_id, expense_id],
+    )?;
+    Ok(())
+}
+
+/// ユーザーの保存済みの位置情報をすべて削除する
+///
+/// 位置情報テーブルに加え、ローカルの経費テーブルに残っている座標も削除します。
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+///
+/// # 戻り値
+/// 位置情報を削除した経費の件数
+pub fn strip_location_data(conn: &Connection, user_id: &str) -> AppResult<usize> {
+    let removed = conn.execute(
+        "DELETE FROM expense_locations WHERE user_id = ?1",
+        params![user_id],
+    )?;
+    let cleared = conn.execute(
+        "UPDATE expenses SET latitude = NULL, longitude = NULL, location_opt_in = NULL
+         WHERE user_id = ?1
+           AND (latitude IS NOT NULL OR longitude IS NOT NULL OR location_opt_in IS NOT NULL)",
+        params![user_id],
+    )?;
+    Ok(removed + cleared)
+}
+
+/// 位置検索の条件を検証する
+///
+/// # 引数
+/// * `latitude` - 緯度
+/// * `longitude` - 経度
+/// * `radius_m` - 半径（メートル）
+pub fn validate_nearby_query(latitude: f64, longitude: f64, radius_m: f64) -> AppResult<()> {
+    let center = GpsCoordinates {
+        latitude,
+        longitude,
+    };
+    if !center.is_valid() {
+        return Err(AppError::validation(
+            "緯度は-90〜90、経度は-180〜180の範囲で指定してください",
+        ));
+    }
+    if !radius_m.is_finite() || radius_m <= 0.0 || radius_m > MAX_NEARBY_RADIUS_M {
+        return Err(AppError::validation(format!(
+            "半径は0より大きく{MAX_NEARBY_RADIUS_M}メートル以下で指定してください"
+        )));
+    }
+    Ok(())
+}
+
+/// 2点間の距離を計算する（ハバーサイン公式、メートル）
+pub fn distance_m(a: &GpsCoordinates, b: &GpsCoordinates) -> f64 {
+    let (lat1, lat2) = (a.latitude.to_radians(), b.latitude.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = (b.longitude - a.longitude).to_radians();
+    let h = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * h.sqrt().min(1.0).asin()
+}
+
+/// 中心から半径内を含む緯度・経度の範囲を計算する（SQLでの絞り込み用）
+///
+/// # 戻り値
+/// (最小緯度, 最大緯度, 最小経度, 最大経度)
+pub fn bounding_box(center: &GpsCoordinates, radius_m: f64) -> (f64, f64, f64, f64) {
+    let d_lat = (radius_m / EARTH_RADIUS_M).to_degrees();
+    // 極付近では経度方向の範囲が広がるため、全経度を対象にする
+    let cos_lat = center.latitude.to_radians().cos();
+    let d_lon = if cos_lat < 1e-6 {
+        180.0
+    } else {
+        (d_lat / cos_lat).min(180.0)
+    };
+    (
+        center.latitude - d_lat,
+        center.latitude + d_lat,
+        center.longitude - d_lon,
+        center.longitude + d_lon,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::database::connection::create_in_memory_connection;
+
+    fn create_test_connection() -> Connection {
+        let conn = create_in_memory_connection().unwrap();
+        // 認証機能のマイグレーション後と同じくユーザーIDを持たせる
+        conn.execute("ALTER TABLE expenses ADD COLUMN user_id TEXT", [])
+            .unwrap();
+        ExpenseLocationMigration.execute(&conn).unwrap();
+        ExpenseLocationsSchemaMigration.execute(&conn).unwrap();
+        conn
+    }
+
+    fn saved_location(conn: &Connection, user_id: &str, expense_id: i64) -> Option<(f64, f64)> {
+        conn.query_row(
+            "SELECT latitude, longitude FROM expense_locations WHERE user_id = ?1 AND expense_id = ?2",
+            params![user_id, expense_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .unwrap()
+    }
+
+    fn insert_expense(conn: &Connection, user_id: &str) -> i64 {
+        conn.execute(
+            "INSERT INTO expenses (date, amount, category, user_id, created_at, updated_at)
+             VALUES ('2024-01-10', 1000.0, '交通費', ?1, '2024-01-10', '2024-01-10')",
+            params![user_id],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    #[test]
+    fn test_location_setting_defaults_to_disabled() {
+        let conn = create_test_connection();
+        // 2回目も失敗しない
+        ExpenseLocationMigration.execute(&conn).unwrap();
+
+        assert!(!is_location_storage_enabled(&conn, "user_a").unwrap());
+        set_location_storage_enabled(&conn, "user_a", true).unwrap();
+        assert!(is_location_storage_enabled(&conn, "user_a").unwrap());
+        // ユーザーごとの設定
+        assert!(!is_location_storage_enabled(&conn, "user_b").unwrap());
+
+        set_location_storage_enabled(&conn, "user_a", false).unwrap();
+        assert!(!is_location_storage_enabled(&conn, "user_a").unwrap());
+    }
+
+    #[test]
+    fn test_save_and_strip_location() {
+        let conn = create_test_connection();
+        // 2回目も失敗しない
+        ExpenseLocationsSchemaMigration.execute(&conn).unwrap();
+        let tokyo = GpsCoordinates {
+            latitude: 35.681,
+            longitude: 139.767,
+        };
+        let osaka = GpsCoordinates {
+            latitude: 34.702,
+            longitude: 135.495,
+        };
+
+        // ローカルの経費テーブルに存在しない経費IDにも保存できる
+        save_expense_location(&conn, "user_a", 42, &tokyo).unwrap();
+        assert_eq!(saved_location(&conn, "user_a", 42), Some((35.681, 139.767)));
+        save_expense_location(&conn, "user_a", 42, &osaka).unwrap();
+        assert_eq!(saved_location(&conn, "user_a", 42), Some((34.702, 135.495)));
+
+        let invalid = GpsCoordinates {
+            latitude: 91.0,
+            longitude: 0.0,
+        };
+        assert!(save_expense_location(&conn, "user_a", 43, &invalid).is_err());
+        assert_eq!(saved_location(&conn, "user_a", 43), None);
+
+        remove_expense_location(&conn, "user_a", 42).unwrap();
+        assert_eq!(saved_location(&conn, "user_a", 42), None);
+
+        // ローカルの経費テーブルに残っている座標も削除する
+        save_expense_location(&conn, "user_a", 42, &tokyo).unwrap();
+        let legacy = insert_expense(&conn, "user_a");
+        conn.execute(
+            "UPDATE expenses SET latitude = 35.0, longitude = 139.0, location_opt_in = 1 WHERE id = ?1",
+            params![legacy],
+        )
+        .unwrap();
+
+        assert_eq!(strip_location_data(&conn, "user_a").unwrap(), 2);
+        assert_eq!(saved_location(&conn, "user_a", 42), None);
+        let (latitude, opt_in): (Option<f64>, Option<bool>) = conn
+            .query_row(
+                "SELECT latitude, location_opt_in FROM expenses WHERE id = ?1",
+                params![legacy],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((latitude, opt_in), (None, None));
+        assert_eq!(strip_location_data(&conn, "user_a").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_location_is_scoped_to_user() {
+        let conn = create_test_connection();
+        let tokyo = GpsCoordinates {
+            latitude: 35.681,
+            longitude: 139.767,
+        };
+
+        save_expense_location(&conn, "user_a", 1, &tokyo).unwrap();
+        save_expense_location(&conn, "user_b", 1, &tokyo).unwrap();
+
+        // 他のユーザーの位置情報は削除しない
+        remove_expense_location(&conn, "user_a", 1).unwrap();
+        assert_eq!(saved_location(&conn, "user_b", 1), Some((35.681, 139.767)));
+        save_expense_location(&conn, "user_a", 1, &tokyo).unwrap();
+        assert_eq!(strip_location_data(&conn, "user_a").unwrap(), 1);
+        assert_eq!(saved_location(&conn, "user_b", 1), Some((35.681, 139.767)));
+    }
+
+    #[test]
+    fn test_distance_and_bounding_box() {
+        let tokyo_station = GpsCoordinates {
+            latitude: 35.681_236,
+            longitude: 139.767_125,
+        };
+        let shinjuku_station = GpsCoordinates {
+            latitude: 35.690_921,
+            longitude: 139.700_258,
+        };
+        let distance = distance_m(&tokyo_station, &shinjuku_station);
+        assert!((distance - 6_150.0).abs() < 100.0, "{distance}");
+        assert_eq!(distance_m(&tokyo_station, &tokyo_station), 0.0);
+
+        let (min_lat, max_lat, min_lon, max_lon) = bounding_box(&tokyo_station, 10_000.0);
+        assert!(min_lat < shinjuku_station.latitude && shinjuku_station.latitude < max_lat);
+        assert!(min_lon < shinjuku_station.longitude && shinjuku_station.longitude < max_lon);
+
+        assert!(validate_nearby_query(35.0, 139.0, 500.0).is_ok());
+        assert!(validate_nearby_query(95.0, 139.0, 500.0).is_err());
+        assert!(validate_nearby_query(35.0, 181.0, 500.0).is_err());
+        assert!(validate_nearby_query(35.0, 139.0, 0.0).is_err());
+        assert!(validate_nearby_query(35.0, 139.0, MAX_NEARBY_RADIUS_M + 1.0).is_err());
+    }
+}
@@ -0,0 +1,1077 @@
+/// 経費の店舗
+///
+/// 説明に含まれる店舗名の表記ゆれ（「スタバ」「スターバックス」「STARBUCKS #123」など）を
+/// 別名でまとめ、経費に店舗IDを紐付けます。
+/// 別名との照合は正規化した文字列で行い、経費の説明は変更しません。
+///
+/// 経費はAPIサーバーで管理されるため、紐付けは(ユーザーID, 経費ID)をキーとした
+/// ローカルの`expense_merchants`テーブルに保存し、経費ミラーと結合して集計します。
+use crate::features::expenses::models::{
+    ConfirmMerchantGroupDto, ExpenseFilter, Merchant, MerchantGroupProposal, MerchantSpending,
+};
+use crate::features::migrations::auto_migration::executor::MigrationExecutorTrait;
+use crate::features::migrations::auto_migration::models::{
+    ExecutableMigrationDefinition, MigrationDefinition,
+};
+use crate::features::migrations::auto_migration::registry::MigrationRegistry;
+use crate::shared::database::connection::check_column_exists;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::utils::merchant_name::normalize_merchant_name;
+use crate::shared::utils::{
+    get_current_jst_timestamp, validate_required_field, validate_text_length,
+};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, ToSql};
+use std::collections::{BTreeMap, HashMap};
+
+/// 店舗名・別名の最大文字数
+const MAX_MERCHANT_NAME_LENGTH: usize = 100;
+
+/// 説明の一部として照合する別名の最小文字数（短すぎる別名による誤判定を防ぐ）
+const MIN_PARTIAL_MATCH_CHARS: usize = 2;
+
+/// 店舗マイグレーションのSQL（カラム追加は存在確認のうえ実行）
+const MERCHANTS_SCHEMA_SQL: &str = "
+    CREATE TABLE IF NOT EXISTS merchants (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        user_id TEXT NOT NULL,
+        name TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        updated_at TEXT NOT NULL,
+        UNIQUE(user_id, name)
+    );
+    CREATE TABLE IF NOT EXISTS merchant_aliases (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        merchant_id INTEGER NOT NULL,
+        user_id TEXT NOT NULL,
+        alias TEXT NOT NULL,
+        normalized_alias TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        UNIQUE(user_id, normalized_alias),
+        FOREIGN KEY (merchant_id) REFERENCES merchants(id) ON DELETE CASCADE
+    );
+    ALTER TABLE expenses ADD COLUMN merchant_id INTEGER;
+    CREATE INDEX IF NOT EXISTS idx_expenses_merchant
+        ON expenses(merchant_id) WHERE merchant_id IS NOT NULL;
+    CREATE TRIGGER IF NOT EXISTS trg_merchants_deleted
+        AFTER DELETE ON merchants
+    BEGIN
+        DELETE FROM merchant_aliases WHERE merchant_id = OLD.id;
+        UPDATE expenses SET merchant_id = NULL WHERE merchant_id = OLD.id;
+    END;
+";
+
+/// 経費と店舗の紐付けテーブルのスキーマ
+const EXPENSE_MERCHANTS_SCHEMA_SQL: &str = "
+    CREATE TABLE IF NOT EXISTS expense_merchants (
+        user_id TEXT NOT NULL,
+        expense_id INTEGER NOT NULL,
+        merchant_id INTEGER NOT NULL,
+        linked_at TEXT NOT NULL,
+        PRIMARY KEY (user_id, expense_id),
+        FOREIGN KEY (merchant_id) REFERENCES merchants(id) ON DELETE CASCADE
+    );
+    CREATE INDEX IF NOT EXISTS idx_expense_merchants_merchant
+        ON expense_merchants(merchant_id);
+    CREATE TRIGGER IF NOT EXISTS trg_merchants_deleted_unlink_expenses
+        AFTER DELETE ON merchants
+    BEGIN
+        DELETE FROM expense_merchants WHERE merchant_id = OLD.id;
+    END;
+";
+
+/// 店舗テーブル作成マイグレーション実行器
+pub struct MerchantsSchemaMigration;
+
+impl MigrationExecutorTrait for MerchantsSchemaMigration {
+    fn name(&self) -> &str {
+        "011_create_merchants"
+    }
+
+    fn execute(&self, conn: &Connection) -> Result<(), String> {
+        create_merchant_tables(conn).map_err(|e| format!("merchantsテーブル作成エラー: {e}"))
+    }
+}
+
+/// 店舗テーブル用マイグレーション定義を取得する
+///
+/// # 戻り値
+/// 実行可能なマイグレーション定義
+pub fn get_merchants_schema_definition() -> ExecutableMigrationDefinition {
+    let definition = MigrationDefinition::new(
+        "011_create_merchants".to_string(),
+        "3.7.0".to_string(),
+        "店舗・別名テーブルと経費の店舗IDカラムの追加".to_string(),
+        MigrationRegistry::calculate_checksum(MERCHANTS_SCHEMA_SQL),
+    );
+
+    ExecutableMigrationDefinition::new(definition, Box::new(MerchantsSchemaMigration))
+}
+
+/// 店舗・別名テーブルを作成し、経費テーブルに店舗IDカラムを追加する
+///
+/// # 引数
+/// * `conn` - データベース接続
+pub fn create_merchant_tables(conn: &Connection) -> AppResult<()> {
+    if !check_column_exists(conn, "expenses", "merchant_id") {
+        conn.execute("ALTER TABLE expenses ADD COLUMN merchant_id INTEGER", [])?;
+    }
+
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS merchants (
+             id INTEGER PRIMARY KEY AUTOINCREMENT,
+             user_id TEXT NOT NULL,
+             name TEXT NOT NULL,
+             created_at TEXT NOT NULL,
+             updated_at TEXT NOT NULL,
+             UNIQUE(user_id, name)
+         );
+         CREATE TABLE IF NOT EXISTS merchant_aliases (
+             id INTEGER PRIMARY KEY AUTOINCREMENT,
+             merchant_id INTEGER NOT NULL,
+             user_id TEXT NOT NULL,
+             alias TEXT NOT NULL,
+             normalized_alias TEXT NOT NULL,
+             created_at TEXT NOT NULL,
+             UNIQUE(user_id, normalized_alias),
+             FOREIGN KEY (merchant_id) REFERENCES merchants(id) ON DELETE CASCADE
+         );
+         CREATE INDEX IF NOT EXISTS idx_expenses_merchant
+             ON expenses(merchant_id) WHERE merchant_id IS NOT NULL;
+         CREATE TRIGGER IF NOT EXISTS trg_merchants_deleted
+             AFTER DELETE ON merchants
+         BEGIN
+             DELETE FROM merchant_aliases WHERE merchant_id = OLD.id;
+             UPDATE expenses SET merchant_id = NULL WHERE merchant_id = OLD.id;
+         END;",
+    )?;
+    Ok(())
+}
+
+/// 経費と店舗の紐付けテーブル作成マイグレーション実行器
+pub struct ExpenseMerchantsSchemaMigration;
+
+impl MigrationExecutorTrait for ExpenseMerchantsSchemaMigration {
+    fn name(&self) -> &str {
+        "016_create_expense_merchants"
+    }
+
+    fn execute(&self, conn: &Connection) -> Result<(), String> {
+        conn.execute_batch(EXPENSE_MERCHANTS_SCHEMA_SQL)
+            .map_err(|e| format!("expense_merchantsテーブル作成エラー: {e}"))
+    }
+}
+
+/// 経費と店舗の紐付けテーブル用マイグレーション定義を取得する
+///
+/// # 戻り値
+/// 実行可能なマイグレーション定義
+pub fn get_expense_merchants_schema_definition() -> ExecutableMigrationDefinition {
+    let definition = MigrationDefinition::new(
+        "016_create_expense_merchants".to_string(),
+        "3.12.0".to_string(),
+        "API Server版の経費と店舗の紐付けテーブルの作成".to_string(),
+        MigrationRegistry::calculate_checksum(EXPENSE_MERCHANTS_SCHEMA_SQL),
+    );
+
+    ExecutableMigrationDefinition::new(definition, Box::new(ExpenseMerchantsSchemaMigration))
+}
+
+/// 店舗名・別名を検証し、前後の空白を除いた値を返す
+fn validate_merchant_text(text: &str, field_name: &str) -> AppResult<String> {
+    validate_required_field(text, field_name)?;
+    let text = text.trim();
+    validate_text_length(text, MAX_MERCHANT_NAME_LENGTH, field_name)?;
+    if normalize_merchant_name(text).is_empty() {
+        return Err(AppError::validation(format!(
+            "{field_name}に店舗番号以外の文字を含めてください"
+        )));
+    }
+    Ok(text.to_string())
+}
+
+/// 店舗を取得する
+///
+/// # 戻り値
+/// 店舗（別名を含む）、該当する店舗がない場合はNone
+pub fn find_merchant(
+    conn: &Connection,
+    user_id: &str,
+    merchant_id: i64,
+) -> AppResult<Option<Merchant>> {
+    let merchant = conn
+        .query_row(
+            "SELECT id, name, created_at, updated_at FROM merchants WHERE id = ?1 AND user_id = ?2",
+            params![merchant_id, user_id],
+            |row| {
+                Ok(Merchant {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    aliases: Vec::new(),
+                    created_at: row.get(2)?,
+                    updated_at: row.get(3)?,
+                })
+            },
+        )
+        .optional()?;
+
+    match merchant {
+        Some(mut merchant) => {
+            merchant.aliases = find_aliases(conn, merchant.id)?;
+            Ok(Some(merchant))
+        }
+        None => Ok(None),
+    }
+}
+
+fn find_aliases(conn: &Connection, merchant_id: i64) -> AppResult<Vec<String>> {
+    let mut stmt =
+        conn.prepare("SELECT alias FROM merchant_aliases WHERE merchant_id = ?1 ORDER BY id")?;
+    let aliases = stmt
+        .query_map(params![merchant_id], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(aliases)
+}
+
+/// 店舗を取得し、存在しない場合はエラーを返す
+fn require_merchant(conn: &Connection, user_id: &str, merchant_id: i64) -> AppResult<Merchant> {
+    find_merchant(conn, user_id, merchant_id)?
+        .ok_or_else(|| AppError::not_found(format!("店舗（ID: {merchant_id}）")))
+}
+
+/// ユーザーの店舗一覧を取得する
+///
+/// # 戻り値
+/// 店舗一覧（名前順）
+pub fn list_merchants(conn: &Connection, user_id: &str) -> AppResult<Vec<Merchant>> {
+    let mut stmt = conn.prepare("SELECT id FROM merchants WHERE user_id = ?1 ORDER BY name, id")?;
+    let ids = stmt
+        .query_map(params![user_id], |row| row.get::<_, i64>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    ids.into_iter()
+        .map(|id| require_merchant(conn, user_id, id))
+        .collect()
+}
+
+/// 別名を追加する
+///
+/// 正規化した別名が同じユーザーの別の店舗に登録済みの場合はエラーを返します。
+/// 同じ店舗に登録済みの場合は何もしません。
+fn insert_alias(conn: &Connection, user_id: &str, merchant_id: i64, alias: &str) -> AppResult<()> {
+    let alias = validate_merchant_text(alias, "別名")?;
+    let normalized = normalize_merchant_name(&alias);
+
+    let existing: Option<(i64, String)> = conn
+        .query_row(
+            "SELECT a.merchant_id, m.name FROM merchant_aliases a
+             JOIN merchants m ON m.id = a.merchant_id
+             WHERE a.user_id = ?1 AND a.normalized_alias = ?2",
+            params![user_id, normalized],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    match existing {
+        Some((id, _)) if id == merchant_id => Ok(()),
+        Some((_, name)) => Err(AppError::validation(format!(
+            "別名「{alias}」は店舗「{name}」に登録されています"
+        ))),
+        None => {
+            conn.execute(
+                "INSERT INTO merchant_aliases (merchant_id, user_id, alias, normalized_alias, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![merchant_id, user_id, alias, normalized, get_current_jst_timestamp()],
+            )?;
+            Ok(())
+        }
+    }
+}
+
+/// 店舗を作成する
+///
+/// 正式名称も別名として登録します。
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+/// * `name` - 正式名称
+/// * `aliases` - 別名
+///
+/// # 戻り値
+/// 作成した店舗、または入力値が不正な場合・別名が他の店舗と重複する場合はエラー
+pub fn create_merchant(
+    conn: &Connection,
+    user_id: &str,
+    name: &str,
+    aliases: &[String],
+) -> AppResult<Merchant> {
+    let tx = conn.unchecked_transaction()?;
+    let merchant_id = insert_merchant(&tx, user_id, name, aliases)?;
+    tx.commit()?;
+
+    require_merchant(conn, user_id, merchant_id)
+}
+
+/// 店舗と別名を追加する（トランザクションは呼び出し側で開始する）
+///
+/// # 戻り値
+/// 追加した店舗のID
+fn insert_merchant(
+    conn: &Connection,
+    user_id: &str,
+    name: &str,
+    aliases: &[String],
+) -> AppResult<i64> {
+    let name = validate_merchant_text(name, "店舗名")?;
+    let now = get_current_jst_timestamp();
+    conn.execute(
+        "INSERT INTO merchants (user_id, name, created_at, updated_at) VALUES (?1, ?2, ?3, ?3)",
+        params![user_id, name, now],
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::SqliteFailure(err, _)
+            if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+        {
+            AppError::validation(format!("店舗「{name}」は既に登録されています"))
+        }
+        e => e.into(),
+    })?;
+    let merchant_id = conn.last_insert_rowid();
+
+    for alias in std::iter::once(&name).chain(aliases) {
+        insert_alias(conn, user_id, merchant_id, alias)?;
+    }
+    Ok(merchant_id)
+}
+
+/// 店舗の正式名称を変更する
+///
+/// 変更前の名称は別名として残るため、既存の紐付けや照合には影響しません。
+pub fn rename_merchant(
+    conn: &Connection,
+    user_id: &str,
+    merchant_id: i64,
+    name: &str,
+) -> AppResult<Merchant> {
+    let name = validate_merchant_text(name, "店舗名")?;
+    require_merchant(conn, user_id, merchant_id)?;
+
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "UPDATE merchants SET name = ?1, updated_at = ?2 WHERE id = ?3 AND user_id = ?4",
+        params![name, get_current_jst_timestamp(), merchant_id, user_id],
+    )?;
+    insert_alias(&tx, user_id, merchant_id, &name)?;
+    tx.commit()?;
+
+    require_merchant(conn, user_id, merchant_id)
+}
+
+/// 店舗を削除する
+///
+/// 別名は削除され、紐付けていた経費の店舗IDは未設定に戻ります（経費自体は削除しません）。
+///
+/// # 戻り値
+/// 削除した場合はtrue、該当する店舗がない場合はfalse
+pub fn delete_merchant(conn: &Connection, user_id: &str, merchant_id: i64) -> AppResult<bool> {
+    let deleted = conn.execute(
+        "DELETE FROM merchants WHERE id = ?1 AND user_id = ?2",
+        params![merchant_id, user_id],
+    )?;
+    Ok(deleted > 0)
+}
+
+/// 店舗に別名を追加する
+///
+/// # 戻り値
+/// 更新後の店舗
+pub fn add_merchant_alias(
+    conn: &Connection,
+    user_id: &str,
+    merchant_id: i64,
+    alias: &str,
+) -> AppResult<Merchant> {
+    require_merchant(conn, user_id, merchant_id)?;
+    insert_alias(conn, user_id, merchant_id, alias)?;
+    require_merchant(conn, user_id, merchant_id)
+}
+
+/// 店舗から別名を削除する
+///
+/// 正規化した結果が一致する別名を削除します。正式名称の別名は削除できません。
+/// 既に紐付けた経費の店舗IDは変更しません。
+///
+/// # 戻り値
+/// 更新後の店舗
+pub fn remove_merchant_alias(
+    conn: &Connection,
+    user_id: &str,
+    merchant_id: i64,
+    alias: &str,
+) -> AppResult<Merchant> {
+    let merchant = require_merchant(conn, user_id, merchant_id)?;
+    let normalized = normalize_merchant_name(alias);
+    if normalized == normalize_merchant_name(&merchant.name) {
+        return Err(AppError::validation(
+            "正式名称の別名は削除できません。先に店舗名を変更してください",
+        ));
+    }
+
+    conn.execute(
+        "DELETE FROM merchant_aliases WHERE merchant_id = ?1 AND user_id = ?2 AND normalized_alias = ?3",
+        params![merchant_id, user_id, normalized],
+    )?;
+    require_merchant(conn, user_id, merchant_id)
+}
+
+/// 文字列に一致する店舗を探す
+///
+/// 正規化した文字列が別名と完全に一致する店舗を優先し、
+/// 一致しない場合は文字列に含まれる最も長い別名の店舗を返します。
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+/// * `text` - 経費の説明や領収書の読み取り結果
+///
+/// # 戻り値
+/// 一致した店舗のID、一致しない場合はNone
+pub fn match_merchant(conn: &Connection, user_id: &str, text: &str) -> AppResult<Option<i64>> {
+    let normalized = normalize_merchant_name(text);
+    if normalized.is_empty() {
+        return Ok(None);
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT normalized_alias, merchant_id FROM merchant_aliases WHERE user_id = ?1")?;
+    let aliases = stmt
+        .query_map(params![user_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(best_alias_match(&normalized, &aliases))
+}
+
+/// 正規化した文字列に最もよく一致する別名の店舗IDを選ぶ
+fn best_alias_match(normalized: &str, aliases: &[(String, i64)]) -> Option<i64> {
+    if let Some((_, merchant_id)) = aliases.iter().find(|(alias, _)| alias == normalized) {
+        return Some(*merchant_id);
+    }
+
+    aliases
+        .iter()
+        .filter(|(alias, _)| {
+            alias.chars().count() >= MIN_PARTIAL_MATCH_CHARS && normalized.contains(alias.as_str())
+        })
+        // 長い別名を優先し、同じ長さの場合は先に登録された店舗を選ぶ
+        .max_by_key(|(alias, merchant_id)| (alias.chars().count(), std::cmp::Reverse(*merchant_id)))
+        .map(|(_, merchant_id)| *merchant_id)
+}
+
+/// 経費の説明または領収書の読み取り結果から店舗を照合し、経費に紐付ける
+///
+/// 店舗IDのみを更新し、説明は変更しません。一致しない場合は何もしません。
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+/// * `expense_id` - 経費ID
+/// * `ocr_text` - 領収書の読み取り結果（説明に一致しない場合に照合）
+///
+/// # 戻り値
+/// 紐付けた店舗のID、一致しない場合はNone
+pub fn link_expense_merchant(
+    conn: &Connection,
+    user_id: &str,
+    expense_id: i64,
+    ocr_text: Option<&str>,
+) -> AppResult<Option<i64>> {
+    let description: Option<Option<String>> = conn
+        .query_row(
+            "SELECT json_extract(payload, '$.description') FROM expense_mirror
+             WHERE user_id = ?1 AND id = ?2",
+            params![user_id, expense_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let Some(description) = description else {
+        return Err(AppError::not_found(format!("経費（ID: {expense_id}）")));
+    };
+
+    for text in [description.as_deref(), ocr_text].into_iter().flatten() {
+        if let Some(merchant_id) = match_merchant(conn, user_id, text)? {
+            set_expense_merchant(conn, user_id, expense_id, merchant_id)?;
+            return Ok(Some(merchant_id));
+        }
+    }
+    Ok(None)
+}
+
+/// 経費に店舗を紐付ける（紐付け済みの場合は置き換える）
+fn set_expense_merchant(
+    conn: &Connection,
+    user_id: &str,
+    expense_id: i64,
+    merchant_id: i64,
+) -> AppResult<()> {
+    conn.execute(
+        "INSERT INTO expense_merchants (user_id, expense_id, merchant_id, linked_at)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(user_id, expense_id) DO UPDATE SET
+             merchant_id = excluded.merchant_id,
+             linked_at = excluded.linked_at",
+        params![
+            user_id,
+            expense_id,
+            merchant_id,
+            get_current_jst_timestamp()
+        ],
+    )?;
+    Ok(())
+}
+
+/// 経費と店舗の紐付けを解除する
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+/// * `expense_id` - 経費ID
+pub fn unlink_expense_merchant(conn: &Connection, user_id: &str, expense_id: i64) -> AppResult<()> {
+    conn.execute(
+        "DELETE FROM expense_merchants WHERE user_id = ?1 AND expense_id = ?2",
+        params![user_id, expense_id],
+    )?;
+    Ok(())
+}
+
+/// 店舗が未設定の経費を説明で照合し、一致した店舗に紐付ける
+///
+/// 店舗や別名を追加した後に、既存の経費へ反映するために使用します。
+///
+/// # 戻り値
+/// 紐付けた経費の件数
+pub fn link_unassigned_expenses(conn: &Connection, user_id: &str) -> AppResult<usize> {
+    let mut stmt = conn
+        .prepare("SELECT normalized_alias, merchant_id FROM merchant_aliases WHERE user_id = ?1")?;
+    let aliases = stmt
+        .query_map(params![user_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    if aliases.is_empty() {
+        return Ok(0);
+    }
+
+    let unassigned = find_unassigned_expenses(conn, user_id)?;
+    let tx = conn.unchecked_transaction()?;
+    let mut linked = 0;
+    for (expense_id, description, _) in unassigned {
+        if let Some(merchant_id) =
+            best_alias_match(&normalize_merchant_name(&description), &aliases)
+        {
+            set_expense_merchant(&tx, user_id, expense_id, merchant_id)?;
+            linked += 1;
+        }
+    }
+    tx.commit()?;
+    Ok(linked)
+}
+
+/// 店舗が未設定で説明のある経費を取得する
+///
+/// # 戻り値
+/// (経費ID, 説明, 金額) の一覧（ID順）
+fn find_unassigned_expenses(
+    conn: &Connection,
+    user_id: &str,
+) -> AppResult<Vec<(i64, String, f64)>> {
+    let mut stmt = conn.prepare(
+        "SELECT m.id, json_extract(m.payload, '$.description'), json_extract(m.payload, '$.amount')
+         FROM expense_mirror m
+         LEFT JOIN expense_merchants em ON em.user_id = m.user_id AND em.expense_id = m.id
+         WHERE m.user_id = ?1 AND em.expense_id IS NULL
+           AND TRIM(COALESCE(json_extract(m.payload, '$.description'), '')) != ''
+         ORDER BY m.id",
+    )?;
+    let expenses = stmt
+        .query_map(params![user_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(expenses)
+}
+
+/// 店舗別の経費件数と合計金額を取得する
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+/// * `start_date` - 開始日（YYYY-MM-DD形式、任意）
+/// * `end_date` - 終了日（YYYY-MM-DD形式、任意）
+///
+/// # 戻り値
+/// 店舗別の集計（合計金額の多い順）
+pub fn get_spending_by_merchant(
+    conn: &Connection,
+    user_id: &str,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> AppResult<Vec<MerchantSpending>> {
+    ExpenseFilter::new()
+        .with_date_range(start_date, end_date)
+        .validate()?;
+
+    let mut sql =
+        "SELECT m.id, m.name, COUNT(e.id), COALESCE(SUM(json_extract(e.payload, '$.amount')), 0)
+         FROM merchants m
+         JOIN expense_merchants em ON em.merchant_id = m.id AND em.user_id = m.user_id
+         JOIN expense_mirror e ON e.user_id = em.user_id AND e.id = em.expense_id
+         WHERE m.user_id = ?"
+            .to_string();
+    let mut params: Vec<Box<dyn ToSql>> = vec![Box::new(user_id.to_string())];
+    if let Some(start_date) = start_date {
+        sql.push_str(" AND e.date >= ?");
+        params.push(Box::new(start_date.to_string()));
+    }
+    if let Some(end_date) = end_date {
+        sql.push_str(" AND e.date <= ?");
+        params.push(Box::new(end_date.to_string()));
+    }
+    sql.push_str(" GROUP BY m.id, m.name ORDER BY 4 DESC, m.name");
+
+    let mut stmt = conn.prepare(&sql)?;
+    let spending = stmt
+        .query_map(params_from_iter(params.iter()), |row| {
+            Ok(MerchantSpending {
+                merchant_id: row.get(0)?,
+                merchant_name: row.get(1)?,
+                count: row.get(2)?,
+                total_amount: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(spending)
+}
+
+/// 店舗が未設定の経費から店舗のグループ化を提案する
+///
+/// 説明を正規化した結果が同じ経費を1つのグループにまとめます。
+/// 提案を作成するだけで、店舗の作成や紐付けは`confirm_merchant_group`で確定するまで行いません。
+///
+/// # 戻り値
+/// グループ化の提案（件数の多い順）
+pub fn propose_merchant_groups(
+    conn: &Connection,
+    user_id: &str,
+) -> AppResult<Vec<MerchantGroupProposal>> {
+    struct Group {
+        expense_ids: Vec<i64>,
+        total_amount: f64,
+        // 表記ごとの件数（登場順を保つため最初の経費IDも保持する）
+        spellings: HashMap<String, (usize, i64)>,
+    }
+
+    let mut groups: BTreeMap<String, Group> = BTreeMap::new();
+    for (expense_id, description, amount) in find_unassigned_expenses(conn, user_id)? {
+        let normalized = normalize_merchant_name(&description);
+        if normalized.is_empty() {
+            continue;
+        }
+        let group = groups.entry(normalized).or_insert_with(|| Group {
+            expense_ids: Vec::new(),
+            total_amount: 0.0,
+            spellings: HashMap::new(),
+        });
+        group.expense_ids.push(expense_id);
+        group.total_amount += amount;
+        group
+            .spellings
+            .entry(description.trim().to_string())
+            .or_insert((0, expense_id))
+            .0 += 1;
+    }
+
+    let mut proposals = Vec::with_capacity(groups.len());
+    for (normalized_name, group) in groups {
+        let mut spellings: Vec<(String, (usize, i64))> = group.spellings.into_iter().collect();
+        // 多い表記を先に、同数の場合は先に登録された表記を先にする
+        spellings.sort_by_key(|(_, (count, first_id))| (std::cmp::Reverse(*count), *first_id));
+
+        proposals.push(MerchantGroupProposal {
+            existing_merchant_id: match_merchant(conn, user_id, &normalized_name)?,
+            suggested_name: spellings[0].0.clone(),
+            descriptions: spellings
+                .into_iter()
+                .map(|(spelling, _)| spelling)
+                .collect(),
+            normalized_name,
+            expense_ids: group.expense_ids,
+            total_amount: group.total_amount,
+        });
+    }
+    proposals.sort_by_key(|proposal| std::cmp::Reverse(proposal.expense_ids.len()));
+    Ok(proposals)
+}
+
+/// 店舗のグループ化の提案を確定する
+///
+/// 既存の店舗を指定した場合はその店舗に、指定しない場合は新しい店舗に別名を追加し、
+/// 指定した経費を紐付けます。経費の説明は変更しません。
+///
+/// # 戻り値
+/// 紐付け先の店舗
+pub fn confirm_merchant_group(
+    conn: &Connection,
+    user_id: &str,
+    dto: &ConfirmMerchantGroupDto,
+) -> AppResult<Merchant> {
+    let tx = conn.unchecked_transaction()?;
+    let merchant_id = match dto.merchant_id {
+        Some(merchant_id) => {
+            require_merchant(&tx, user_id, merchant_id)?;
+            for alias in &dto.aliases {
+                insert_alias(&tx, user_id, merchant_id, alias)?;
+            }
+            merchant_id
+        }
+        None => insert_merchant(&tx, user_id, &dto.name, &dto.aliases)?,
+    };
+
+    for &expense_id in &dto.expense_ids {
+        let exists: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM expense_mirror WHERE user_id = ?1 AND id = ?2)",
+            params![user_id, expense_id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(AppError::not_found(format!("経費（ID: {expense_id}）")));
+        }
+        set_expense_merchant(&tx, user_id, expense_id, merchant_id)?;
+    }
+    tx.commit()?;
+    require_merchant(conn, user_id, merchant_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::expenses::models::Expense;
+    use crate::features::expenses::sync;
+
+    fn create_test_connection() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE expenses (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                date TEXT NOT NULL,
+                amount REAL NOT NULL,
+                category TEXT NOT NULL,
+                description TEXT,
+                user_id TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );",
+        )
+        .unwrap();
+        MerchantsSchemaMigration.execute(&conn).unwrap();
+        sync::ExpenseMirrorSchemaMigration.execute(&conn).unwrap();
+        ExpenseMerchantsSchemaMigration.execute(&conn).unwrap();
+        conn
+    }
+
+    /// API Serverから同期した経費としてミラーに登録する
+    fn insert_expense(
+        conn: &Connection,
+        user_id: &str,
+        date: &str,
+        amount: f64,
+        description: &str,
+    ) -> i64 {
+        let id: i64 = conn
+            .query_row(
+                "SELECT COALESCE(MAX(id), 0) + 1 FROM expense_mirror",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let expense: Expense = serde_json::from_value(serde_json::json!({
+            "id": id,
+            "date": date,
+            "amount": amount,
+            "category": "飲食費",
+            "description": description,
+            "receipt_url": null,
+            "created_at": "2025-04-01T10:00:00+09:00",
+            "updated_at": "2025-04-01T10:00:00+09:00",
+        }))
+        .unwrap();
+        sync::upsert_mirrored(conn, user_id, &expense).unwrap();
+        id
+    }
+
+    fn description_of(conn: &Connection, expense_id: i64) -> String {
+        conn.query_row(
+            "SELECT json_extract(payload, '$.description') FROM expense_mirror WHERE id = ?1",
+            [expense_id],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    fn merchant_of(conn: &Connection, expense_id: i64) -> Option<i64> {
+        conn.query_row(
+            "SELECT merchant_id FROM expense_merchants WHERE expense_id = ?1",
+            [expense_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_create_merchant_registers_name_as_alias() {
+        let conn = create_test_connection();
+        // 2回目も失敗しない
+        MerchantsSchemaMigration.execute(&conn).unwrap();
+        ExpenseMerchantsSchemaMigration.execute(&conn).unwrap();
+
+        let merchant =
+            create_merchant(&conn, "u1", " スターバックス ", &["スタバ".to_string()]).unwrap();
+        assert_eq!(merchant.name, "スターバックス");
+        assert_eq!(merchant.aliases, ["スターバックス", "スタバ"]);
+
+        // 同じ名前・同じ別名は登録できない
+        assert!(create_merchant(&conn, "u1", "スターバックス", &[]).is_err());
+        assert!(create_merchant(&conn, "u1", "スターバックス新宿", &["ｽﾀﾊﾞ".to_string()]).is_err());
+        // 他のユーザーは登録できる
+        assert!(create_merchant(&conn, "u2", "スターバックス", &[]).is_ok());
+        // 店舗番号のみの名前は登録できない
+        assert!(create_merchant(&conn, "u1", "#123", &[]).is_err());
+
+        assert_eq!(list_merchants(&conn, "u1").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_match_merchant() {
+        let conn = create_test_connection();
+        let starbucks = create_merchant(
+            &conn,
+            "u1",
+            "スターバックス",
+            &["スタバ".to_string(), "STARBUCKS".to_string()],
+        )
+        .unwrap();
+        let coffee = create_merchant(&conn, "u1", "コーヒー", &[]).unwrap();
+
+        for text in [
+            "スタバ",
+            "ｽﾀﾊﾞ",
+            "STARBUCKS #123",
+            "ｓｔａｒｂｕｃｋｓ",
+            "スタバで打ち合わせ",
+        ] {
+            assert_eq!(
+                match_merchant(&conn, "u1", text).unwrap(),
+                Some(starbucks.id),
+                "{text}"
+            );
+        }
+        // 長い別名を優先する
+        assert_eq!(
+            match_merchant(&conn, "u1", "スターバックスコーヒー").unwrap(),
+            Some(starbucks.id)
+        );
+        assert_eq!(
+            match_merchant(&conn, "u1", "缶コーヒー").unwrap(),
+            Some(coffee.id)
+        );
+        assert_eq!(match_merchant(&conn, "u1", "ドトール").unwrap(), None);
+        // 他のユーザーの別名とは照合しない
+        assert_eq!(match_merchant(&conn, "u2", "スタバ").unwrap(), None);
+    }
+
+    #[test]
+    fn test_link_expense_merchant_does_not_rewrite_description() {
+        let conn = create_test_connection();
+        let starbucks =
+            create_merchant(&conn, "u1", "スターバックス", &["スタバ".to_string()]).unwrap();
+        let expense_id = insert_expense(&conn, "u1", "2025-04-01", 500.0, "ｽﾀﾊﾞ #12 打ち合わせ");
+        let unmatched = insert_expense(&conn, "u1", "2025-04-02", 800.0, "会議費");
+
+        assert_eq!(
+            link_expense_merchant(&conn, "u1", expense_id, None).unwrap(),
+            Some(starbucks.id)
+        );
+        assert_eq!(merchant_of(&conn, expense_id), Some(starbucks.id));
+        assert_eq!(description_of(&conn, expense_id), "ｽﾀﾊﾞ #12 打ち合わせ");
+
+        // 説明が一致しない場合は領収書の読み取り結果で照合する
+        assert_eq!(
+            link_expense_merchant(&conn, "u1", unmatched, None).unwrap(),
+            None
+        );
+        assert_eq!(
+            link_expense_merchant(&conn, "u1", unmatched, Some("STARBUCKS COFFEE JAPAN")).unwrap(),
+            None
+        );
+        add_merchant_alias(&conn, "u1", starbucks.id, "starbucks coffee").unwrap();
+        assert_eq!(
+            link_expense_merchant(&conn, "u1", unmatched, Some("STARBUCKS COFFEE JAPAN")).unwrap(),
+            Some(starbucks.id)
+        );
+        assert_eq!(description_of(&conn, unmatched), "会議費");
+
+        // 他のユーザーの経費は紐付けない
+        assert!(link_expense_merchant(&conn, "u2", expense_id, None).is_err());
+
+        unlink_expense_merchant(&conn, "u1", expense_id).unwrap();
+        assert_eq!(merchant_of(&conn, expense_id), None);
+    }
+
+    #[test]
+    fn test_spending_by_merchant() {
+        let conn = create_test_connection();
+        let starbucks =
+            create_merchant(&conn, "u1", "スターバックス", &["スタバ".to_string()]).unwrap();
+        let doutor = create_merchant(&conn, "u1", "ドトール", &[]).unwrap();
+        insert_expense(&conn, "u1", "2025-03-31", 1000.0, "スタバ");
+        insert_expense(&conn, "u1", "2025-04-01", 500.0, "スターバックス");
+        insert_expense(&conn, "u1", "2025-04-10", 450.0, "STARBUCKS #123");
+        insert_expense(&conn, "u1", "2025-04-15", 2000.0, "ドトール");
+        insert_expense(&conn, "u1", "2025-04-20", 300.0, "コンビニ");
+        assert_eq!(link_unassigned_expenses(&conn, "u1").unwrap(), 3);
+
+        // STARBUCKSは別名に登録していないため紐付かない
+        let april =
+            get_spending_by_merchant(&conn, "u1", Some("2025-04-01"), Some("2025-04-30")).unwrap();
+        assert_eq!(
+            april,
+            vec![
+                MerchantSpending {
+                    merchant_id: doutor.id,
+                    merchant_name: "ドトール".to_string(),
+                    count: 1,
+                    total_amount: 2000.0,
+                },
+                MerchantSpending {
+                    merchant_id: starbucks.id,
+                    merchant_name: "スターバックス".to_string(),
+                    count: 1,
+                    total_amount: 500.0,
+                },
+            ]
+        );
+
+        let all = get_spending_by_merchant(&conn, "u1", None, None).unwrap();
+        assert_eq!(all[0].merchant_id, doutor.id);
+        assert_eq!(all[1].count, 2);
+        assert!(get_spending_by_merchant(&conn, "u2", None, None)
+            .unwrap()
+            .is_empty());
+        assert!(
+            get_spending_by_merchant(&conn, "u1", Some("2025-05-01"), Some("2025-04-01")).is_err()
+        );
+    }
+
+    #[test]
+    fn test_delete_merchant_unlinks_expenses() {
+        let conn = create_test_connection();
+        let merchant = create_merchant(&conn, "u1", "スターバックス", &[]).unwrap();
+        let expense_id = insert_expense(&conn, "u1", "2025-04-01", 500.0, "スターバックス");
+        link_expense_merchant(&conn, "u1", expense_id, None).unwrap();
+
+        assert!(!delete_merchant(&conn, "u2", merchant.id).unwrap());
+        assert!(delete_merchant(&conn, "u1", merchant.id).unwrap());
+        assert_eq!(merchant_of(&conn, expense_id), None);
+        assert_eq!(description_of(&conn, expense_id), "スターバックス");
+        // 別名も削除され、同じ名前で再登録できる
+        assert!(create_merchant(&conn, "u1", "ｽﾀｰﾊﾞｯｸｽ", &[]).is_ok());
+    }
+
+    #[test]
+    fn test_rename_and_remove_alias() {
+        let conn = create_test_connection();
+        let merchant = create_merchant(&conn, "u1", "スタバ", &["STARBUCKS".to_string()]).unwrap();
+
+        let renamed = rename_merchant(&conn, "u1", merchant.id, "スターバックス").unwrap();
+        assert_eq!(renamed.name, "スターバックス");
+        // 変更前の名称は別名として残る
+        assert_eq!(renamed.aliases, ["スタバ", "STARBUCKS", "スターバックス"]);
+
+        let removed = remove_merchant_alias(&conn, "u1", merchant.id, "starbucks").unwrap();
+        assert_eq!(removed.aliases, ["スタバ", "スターバックス"]);
+        assert!(remove_merchant_alias(&conn, "u1", merchant.id, "スターバックス").is_err());
+        assert!(rename_merchant(&conn, "u2", merchant.id, "ドトール").is_err());
+    }
+
+    #[test]
+    fn test_propose_and_confirm_merchant_groups() {
+        let conn = create_test_connection();
+        let a = insert_expense(&conn, "u1", "2025-04-01", 500.0, "STARBUCKS #123");
+        let b = insert_expense(&conn, "u1", "2025-04-02", 450.0, "Starbucks #45");
+        let c = insert_expense(&conn, "u1", "2025-04-03", 600.0, "ＳＴＡＲＢＵＣＫＳ");
+        let d = insert_expense(&conn, "u1", "2025-04-04", 300.0, "ｽﾀﾊﾞ");
+        insert_expense(&conn, "u2", "2025-04-04", 300.0, "STARBUCKS");
+
+        let proposals = propose_merchant_groups(&conn, "u1").unwrap();
+        assert_eq!(proposals.len(), 2);
+        let starbucks = &proposals[0];
+        assert_eq!(starbucks.normalized_name, "starbucks");
+        assert_eq!(starbucks.expense_ids, [a, b, c]);
+        assert_eq!(starbucks.total_amount, 1550.0);
+        assert_eq!(starbucks.suggested_name, "STARBUCKS #123");
+        assert_eq!(starbucks.existing_merchant_id, None);
+        assert_eq!(proposals[1].expense_ids, [d]);
+
+        // 提案しただけでは店舗は作成されない
+        assert!(list_merchants(&conn, "u1").unwrap().is_empty());
+
+        let merchant = confirm_merchant_group(
+            &conn,
+            "u1",
+            &ConfirmMerchantGroupDto {
+                merchant_id: None,
+                name: "スターバックス".to_string(),
+                aliases: starbucks.descriptions.clone(),
+                expense_ids: starbucks.expense_ids.clone(),
+            },
+        )
+        .unwrap();
+        for expense_id in [a, b, c] {
+            assert_eq!(merchant_of(&conn, expense_id), Some(merchant.id));
+        }
+        assert_eq!(description_of(&conn, b), "Starbucks #45");
+
+        // 残りのグループは既存の店舗への追加として確定できる
+        let proposals = propose_merchant_groups(&conn, "u1").unwrap();
+        assert_eq!(proposals.len(), 1);
+        assert_eq!(proposals[0].existing_merchant_id, None);
+        confirm_merchant_group(
+            &conn,
+            "u1",
+            &ConfirmMerchantGroupDto {
+                merchant_id: Some(merchant.id),
+                name: String::new(),
+                aliases: vec!["スタバ".to_string()],
+                expense_ids: vec![d],
+            },
+        )
+        .unwrap();
+        assert_eq!(merchant_of(&conn, d), Some(merchant.id));
+        assert!(propose_merchant_groups(&conn, "u1").unwrap().is_empty());
+
+        // 他のユーザーの経費は紐付けられない（全体を取り消す）
+        let other = confirm_merchant_group(
+            &conn,
+            "u2",
+            &ConfirmMerchantGroupDto {
+                merchant_id: None,
+                name: "スターバックス".to_string(),
+                aliases: vec![],
+                expense_ids: vec![a],
+            },
+        );
+        assert!(other.is_err());
+        assert!(list_merchants(&conn, "u2").unwrap().is_empty());
+    }
+}
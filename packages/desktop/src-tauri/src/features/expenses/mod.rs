@@ -4,22 +4,267 @@
 /// - 経費の作成、読み取り、更新、削除（CRUD操作）
 /// - 経費データのバリデーション
 /// - 月別・カテゴリ別の経費取得
+/// - 期間内の経費のCSVエクスポートとCSVからの一括取り込み
 /// - 領収書URLの管理
+/// - 領収書未添付の経費検索
+/// - 領収書のEXIFから取得した位置情報の保存と近くの経費の検索
+/// - カテゴリ別の件数・合計金額の集計と月別のカテゴリごとの割合
+/// - 店舗名の表記ゆれをまとめた店舗の管理と店舗別の集計
 /// - 領収書キャッシュの管理
+/// - API Server版のローカルミラーと差分同期
+/// - カテゴリごとの領収書の添付ルールと領収書未添付の経費の記録
 // サブモジュールの宣言
 pub mod api_commands;
+pub mod commands;
+pub mod csv_export;
+pub mod csv_import;
+pub mod location;
+pub mod merchants;
 pub mod models;
+pub mod receipt_policies;
+pub mod repository;
+pub mod sync;
 
 // 公開インターフェース：外部から使用可能な型と関数をエクスポート
 
 // モデル
-pub use models::{CreateExpenseDto, Expense, ReceiptCache, UpdateExpenseDto};
+pub use models::{
+    CategorySummary, ConfirmMerchantGroupDto, CreateExpenseDto, Expense, ExpenseFilter,
+    ImportResult, Merchant, MerchantGroupProposal, MerchantSpending, MissingReceiptExpense,
+    NearbyExpense, ReceiptCache, ReceiptEnforcement, ReceiptPolicy, ReceiptStatus, RowError,
+    UpdateExpenseDto,
+};
 
 // APIコマンド（API Server経由のTauriコマンドハンドラー）
 pub use api_commands::{
-    create_expense, delete_expense, delete_expense_receipt, get_expenses, update_expense,
+    check_expense_sync_integrity, create_expense, delete_expense, delete_expense_receipt,
+    force_full_expense_resync, get_expenses, import_expenses_csv, sync_expenses, update_expense,
 };
 
+use crate::shared::capabilities::{Capability, Requirement};
+
+/// 経費機能の利用者向け機能
+pub const CAPABILITIES: &[Capability] = &[
+    Capability::new(
+        "expenses.create",
+        "capability.expenses.create",
+        "create_expense",
+    )
+    .requires(&[Requirement::Authenticated, Requirement::ApiServerConfigured])
+    .mutating(),
+    Capability::new("expenses.list", "capability.expenses.list", "get_expenses")
+        .requires(&[Requirement::Authenticated, Requirement::ApiServerConfigured]),
+    Capability::new(
+        "expenses.update",
+        "capability.expenses.update",
+        "update_expense",
+    )
+    .requires(&[Requirement::Authenticated, Requirement::ApiServerConfigured])
+    .mutating(),
+    Capability::new(
+        "expenses.delete",
+        "capability.expenses.delete",
+        "delete_expense",
+    )
+    .requires(&[Requirement::Authenticated, Requirement::ApiServerConfigured])
+    .destructive(),
+    Capability::new(
+        "expenses.delete_receipt",
+        "capability.expenses.delete_receipt",
+        "delete_expense_receipt",
+    )
+    .requires(&[
+        Requirement::Authenticated,
+        Requirement::ApiServerConfigured,
+        Requirement::StorageOnline,
+    ])
+    .destructive(),
+    Capability::new("expenses.sync", "capability.expenses.sync", "sync_expenses")
+        .requires(&[Requirement::Authenticated, Requirement::ApiServerConfigured])
+        .mutating(),
+    Capability::new(
+        "expenses.force_full_resync",
+        "capability.expenses.force_full_resync",
+        "force_full_expense_resync",
+    )
+    .requires(&[Requirement::Authenticated, Requirement::ApiServerConfigured])
+    .mutating()
+    .internal(),
+    Capability::new(
+        "expenses.check_sync_integrity",
+        "capability.expenses.check_sync_integrity",
+        "check_expense_sync_integrity",
+    )
+    .requires(&[Requirement::Authenticated, Requirement::ApiServerConfigured])
+    .mutating()
+    .internal(),
+    Capability::new(
+        "expenses.without_receipts",
+        "capability.expenses.without_receipts",
+        "get_expenses_without_receipts",
+    )
+    .requires(&[Requirement::Authenticated]),
+    Capability::new(
+        "expenses.summary_by_category",
+        "capability.expenses.summary_by_category",
+        "get_expense_summary_by_category",
+    )
+    .requires(&[Requirement::Authenticated]),
+    Capability::new(
+        "expenses.monthly_summary",
+        "capability.expenses.monthly_summary",
+        "get_monthly_expense_summary",
+    )
+    .requires(&[Requirement::Authenticated]),
+    Capability::new(
+        "expenses.near",
+        "capability.expenses.near",
+        "get_expenses_near",
+    )
+    .requires(&[
+        Requirement::Authenticated,
+        Requirement::LocationStorageEnabled,
+    ]),
+    Capability::new(
+        "expenses.set_location_setting",
+        "capability.expenses.set_location_setting",
+        "set_receipt_location_setting",
+    )
+    .requires(&[Requirement::Authenticated])
+    .mutating(),
+    Capability::new(
+        "expenses.strip_location",
+        "capability.expenses.strip_location",
+        "strip_location_data",
+    )
+    .requires(&[Requirement::Authenticated])
+    .destructive(),
+    Capability::new(
+        "expenses.list_merchants",
+        "capability.expenses.list_merchants",
+        "get_merchants",
+    )
+    .requires(&[Requirement::Authenticated]),
+    Capability::new(
+        "expenses.create_merchant",
+        "capability.expenses.create_merchant",
+        "create_merchant",
+    )
+    .requires(&[Requirement::Authenticated])
+    .mutating(),
+    Capability::new(
+        "expenses.rename_merchant",
+        "capability.expenses.rename_merchant",
+        "update_merchant_name",
+    )
+    .requires(&[Requirement::Authenticated])
+    .mutating(),
+    Capability::new(
+        "expenses.delete_merchant",
+        "capability.expenses.delete_merchant",
+        "delete_merchant",
+    )
+    .requires(&[Requirement::Authenticated])
+    .destructive(),
+    Capability::new(
+        "expenses.add_merchant_alias",
+        "capability.expenses.add_merchant_alias",
+        "add_merchant_alias",
+    )
+    .requires(&[Requirement::Authenticated])
+    .mutating(),
+    Capability::new(
+        "expenses.delete_merchant_alias",
+        "capability.expenses.delete_merchant_alias",
+        "delete_merchant_alias",
+    )
+    .requires(&[Requirement::Authenticated])
+    .destructive(),
+    Capability::new(
+        "expenses.link_merchant",
+        "capability.expenses.link_merchant",
+        "link_expense_merchant",
+    )
+    .requires(&[Requirement::Authenticated])
+    .mutating(),
+    Capability::new(
+        "expenses.spending_by_merchant",
+        "capability.expenses.spending_by_merchant",
+        "get_spending_by_merchant",
+    )
+    .requires(&[Requirement::Authenticated]),
+    Capability::new(
+        "expenses.merchant_group_proposals",
+        "capability.expenses.merchant_group_proposals",
+        "get_merchant_group_proposals",
+    )
+    .requires(&[Requirement::Authenticated]),
+    Capability::new(
+        "expenses.confirm_merchant_group",
+        "capability.expenses.confirm_merchant_group",
+        "confirm_merchant_group",
+    )
+    .requires(&[Requirement::Authenticated])
+    .mutating(),
+    Capability::new(
+        "expenses.list_receipt_policies",
+        "capability.expenses.list_receipt_policies",
+        "get_receipt_policies",
+    )
+    .requires(&[Requirement::Authenticated]),
+    Capability::new(
+        "expenses.create_receipt_policy",
+        "capability.expenses.create_receipt_policy",
+        "create_receipt_policy",
+    )
+    .requires(&[Requirement::Authenticated])
+    .mutating(),
+    Capability::new(
+        "expenses.update_receipt_policy",
+        "capability.expenses.update_receipt_policy",
+        "update_receipt_policy",
+    )
+    .requires(&[Requirement::Authenticated])
+    .mutating(),
+    Capability::new(
+        "expenses.delete_receipt_policy",
+        "capability.expenses.delete_receipt_policy",
+        "delete_receipt_policy",
+    )
+    .requires(&[Requirement::Authenticated])
+    .destructive(),
+    Capability::new(
+        "expenses.missing_receipts",
+        "capability.expenses.missing_receipts",
+        "get_missing_receipt_expenses",
+    )
+    .requires(&[Requirement::Authenticated]),
+    Capability::new(
+        "expenses.calculate",
+        "capability.expenses.calculate",
+        "calculate",
+    ),
+    Capability::new(
+        "expenses.export_json",
+        "capability.expenses.export_json",
+        "export_expenses_json",
+    )
+    .requires(&[Requirement::Authenticated]),
+    Capability::new(
+        "expenses.export_csv",
+        "capability.expenses.export_csv",
+        "export_expenses_csv",
+    )
+    .requires(&[Requirement::Authenticated]),
+    Capability::new(
+        "expenses.import_csv",
+        "capability.expenses.import_csv",
+        "import_expenses_csv",
+    )
+    .requires(&[Requirement::Authenticated, Requirement::ApiServerConfigured])
+    .mutating(),
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,7 +1,17 @@
-use serde::{Deserialize, Serialize};
+use crate::shared::errors::{AppError, AppResult, ValidationError};
+use crate::shared::export::escape_csv_field;
+use crate::shared::utils::{
+    calc, round_to_currency_precision, validate_amount, validate_amount_for_currency,
+    validate_category, validate_date, validate_description, validate_https_url, validate_tax_rate,
+    validate_text_length, CurrencyCode,
+};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
 
 /// 経費データモデル
-#[derive(Debug, Serialize, Deserialize, Clone)]
+///
+/// シリアライズ時は`receipt_status`（`Expense::receipt_status()`の結果）を追加で出力します。
+#[derive(Debug, Deserialize, Clone)]
 pub struct Expense {
     pub id: i64,
     pub date: String,
@@ -12,6 +22,326 @@ pub struct Expense {
     pub receipt_url: Option<String>, // receipt_pathからreceipt_urlに変更
     pub created_at: String,
     pub updated_at: String,
+    #[serde(default)]
+    pub category_color: Option<String>, // カテゴリの表示色（一致するカテゴリがない場合はNone）
+    #[serde(default)]
+    pub category_icon: Option<String>, // カテゴリのアイコン（一致するカテゴリがない場合はNone）
+    #[serde(default)]
+    pub unknown_category: bool, // カテゴリ名がcategoriesテーブルに存在しない（削除・名称変更済み）
+    #[serde(default)]
+    pub tax_rate: Option<f64>, // 消費税率（10%の場合は0.1）
+    #[serde(default)]
+    pub tax_amount: Option<f64>, // 消費税額（amountに含まれる）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>, // 領収書のEXIFから取得した緯度（保存を許可した場合のみ）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>, // 領収書のEXIFから取得した経度（保存を許可した場合のみ）
+    #[serde(default)]
+    pub location_opt_in: bool, // 利用者の許可に基づいて位置情報を保存した経費
+    #[serde(default)]
+    pub merchant_id: Option<i64>, // 説明などが別名に一致した店舗（説明の文字列は変更しない）
+    #[serde(default)]
+    pub missing_receipt: bool, // 領収書の添付ルールに反して領収書が未添付
+}
+
+impl Serialize for Expense {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Expense", 20)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("date", &self.date)?;
+        state.serialize_field("amount", &self.amount)?;
+        state.serialize_field("category", &self.category)?;
+        state.serialize_field("category_id", &self.category_id)?;
+        state.serialize_field("description", &self.description)?;
+        state.serialize_field("receipt_url", &self.receipt_url)?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.serialize_field("updated_at", &self.updated_at)?;
+        state.serialize_field("category_color", &self.category_color)?;
+        state.serialize_field("category_icon", &self.category_icon)?;
+        state.serialize_field("unknown_category", &self.unknown_category)?;
+        state.serialize_field("tax_rate", &self.tax_rate)?;
+        state.serialize_field("tax_amount", &self.tax_amount)?;
+        match self.latitude {
+            Some(latitude) => state.serialize_field("latitude", &latitude)?,
+            None => state.skip_field("latitude")?,
+        }
+        match self.longitude {
+            Some(longitude) => state.serialize_field("longitude", &longitude)?,
+            None => state.skip_field("longitude")?,
+        }
+        state.serialize_field("location_opt_in", &self.location_opt_in)?;
+        state.serialize_field("merchant_id", &self.merchant_id)?;
+        state.serialize_field("missing_receipt", &self.missing_receipt)?;
+        state.serialize_field("receipt_status", &self.receipt_status())?;
+        state.end()
+    }
+}
+
+/// 領収書の添付状態
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ReceiptStatus {
+    /// 領収書が添付されていない
+    None,
+    /// 有効なHTTPS URLの領収書が添付されている
+    Attached { url: String },
+    /// URLが記録されているが、有効なHTTPS URLではない（旧形式のパスなど）
+    Broken,
+}
+
+/// 位置検索で見つかった経費
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NearbyExpense {
+    #[serde(flatten)]
+    pub expense: Expense,
+    pub distance_m: f64, // 検索地点からの距離（メートル）
+}
+
+/// 店舗（表記ゆれを別名でまとめた店舗名）
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Merchant {
+    pub id: i64,
+    pub name: String,         // 正式名称
+    pub aliases: Vec<String>, // 別名（登録時の表記、正式名称を含む）
+    pub created_at: String,   // 作成日時（RFC3339形式、JST）
+    pub updated_at: String,   // 更新日時（RFC3339形式、JST）
+}
+
+/// 店舗別の経費件数と合計金額
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MerchantSpending {
+    pub merchant_id: i64,
+    pub merchant_name: String,
+    pub count: i64,
+    pub total_amount: f64,
+}
+
+/// 月別のカテゴリごとの経費の集計
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CategorySummary {
+    pub category: String,
+    pub total_amount: f64,
+    pub expense_count: usize,
+    pub percentage_of_total: f64, // 月の合計金額に占める割合（%、小数点以下2桁）
+}
+
+/// 経費CSVの取り込み結果
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ImportResult {
+    pub imported: usize,        // 取り込んだ件数（dry_runの場合は取り込み可能な件数）
+    pub skipped: Vec<RowError>, // 取り込まなかった行
+}
+
+/// 取り込まなかったCSVの行
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RowError {
+    pub line: usize,    // CSVファイル上の行番号（1始まり、ヘッダー行を含む）
+    pub reason: String, // 取り込まなかった理由
+}
+
+/// 既存の説明から作成した店舗のグループ化の提案
+///
+/// 店舗が未設定の経費を説明の正規化結果でまとめたものです。
+/// 利用者が確認するまで店舗の作成や経費の紐付けは行いません。
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MerchantGroupProposal {
+    pub normalized_name: String,           // 正規化した説明
+    pub suggested_name: String,            // 提案する店舗名（最も多い表記）
+    pub existing_merchant_id: Option<i64>, // 既存の店舗の別名に一致する場合はその店舗
+    pub descriptions: Vec<String>,         // グループに含まれる説明の表記
+    pub expense_ids: Vec<i64>,
+    pub total_amount: f64,
+}
+
+/// 店舗のグループ化の確定内容
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfirmMerchantGroupDto {
+    pub merchant_id: Option<i64>, // 既存の店舗に追加する場合に指定
+    pub name: String,             // 新しい店舗の正式名称（merchant_id指定時は無視）
+    pub aliases: Vec<String>,     // 追加する別名
+    pub expense_ids: Vec<i64>,    // 店舗に紐付ける経費
+}
+
+/// 領収書の添付を求める強さ
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiptEnforcement {
+    /// 領収書がない経費は保存しない（後で添付することを明示した場合を除く）
+    Hard,
+    /// 保存するが、領収書未添付として記録する
+    Soft,
+}
+
+impl ReceiptEnforcement {
+    /// データベースに保存する値
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReceiptEnforcement::Hard => "hard",
+            ReceiptEnforcement::Soft => "soft",
+        }
+    }
+
+    /// データベースに保存した値から変換する
+    pub fn parse(value: &str) -> AppResult<Self> {
+        match value {
+            "hard" => Ok(ReceiptEnforcement::Hard),
+            "soft" => Ok(ReceiptEnforcement::Soft),
+            other => Err(AppError::Database(format!(
+                "不明な領収書の添付ルールの種類です: {other}"
+            ))),
+        }
+    }
+}
+
+/// カテゴリごとの領収書の添付ルール
+///
+/// `min_amount`以上の経費に領収書の添付を求めます。
+/// カテゴリはIDで照合し、IDが分からない場合のみ名前で照合します（名称変更後も同じルールを適用するため）。
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ReceiptPolicy {
+    pub id: i64,
+    pub category: String,         // 登録時のカテゴリ名
+    pub category_id: Option<i64>, // カテゴリーID（分かる場合）
+    pub min_amount: f64,          // この金額以上の経費に領収書を求める
+    pub enforcement: ReceiptEnforcement,
+    pub created_at: String, // 作成日時（RFC3339形式、JST）
+    pub updated_at: String, // 更新日時（RFC3339形式、JST）
+}
+
+/// 領収書の添付ルール作成用DTO
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateReceiptPolicyDto {
+    pub category: String,
+    pub category_id: Option<i64>,
+    pub min_amount: f64,
+    pub enforcement: ReceiptEnforcement,
+}
+
+/// 領収書の添付ルール更新用DTO（指定した項目のみ更新）
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct UpdateReceiptPolicyDto {
+    pub min_amount: Option<f64>,
+    pub enforcement: Option<ReceiptEnforcement>,
+}
+
+/// 領収書の添付ルールに反して領収書が未添付の経費
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MissingReceiptExpense {
+    #[serde(flatten)]
+    pub expense: Expense,
+    pub policy_id: i64,                  // 適用したルール
+    pub enforcement: ReceiptEnforcement, // 適用したルールの強さ
+    pub followup_requested: bool,        // 後で添付することを明示して保存した
+    pub flagged_at: String,              // 未添付として記録した日時（RFC3339形式、JST）
+}
+
+/// 標準税率（10%）
+pub const STANDARD_TAX_RATE: f64 = 0.1;
+
+/// 軽減税率（8%、飲食料品など）
+pub const REDUCED_TAX_RATE: f64 = 0.08;
+
+impl Expense {
+    /// 領収書の添付状態を取得する
+    pub fn receipt_status(&self) -> ReceiptStatus {
+        match self.receipt_url.as_deref().map(str::trim) {
+            None | Some("") => ReceiptStatus::None,
+            Some(url) if validate_https_url(url).is_ok() => ReceiptStatus::Attached {
+                url: url.to_string(),
+            },
+            Some(_) => ReceiptStatus::Broken,
+        }
+    }
+
+    /// 位置情報を取り除く（エクスポートなどで位置情報を含めない場合に使用）
+    pub fn without_location(mut self) -> Self {
+        self.latitude = None;
+        self.longitude = None;
+        self.location_opt_in = false;
+        self
+    }
+
+    /// 税抜金額を取得する
+    ///
+    /// `amount`は税込金額として扱います。税額が記録されていればそれを差し引き、
+    /// 税率のみ記録されている場合は税率から逆算します。
+    /// どちらも記録されていない場合は`amount`をそのまま返します。
+    ///
+    /// 円単位の金額は`calc::extract_tax`で税額を切り捨てて求めるため、
+    /// 税抜金額と税額の合計は税込金額と一致します。
+    pub fn pretax_amount(&self) -> f64 {
+        match (self.tax_amount, self.tax_rate) {
+            (Some(tax_amount), _) => round_to_currency_precision(self.amount - tax_amount),
+            (None, Some(tax_rate)) => yen_tax(self.amount, tax_rate, calc::extract_tax)
+                .map(|breakdown| breakdown.exclusive as f64)
+                .unwrap_or_else(|| round_to_currency_precision(self.amount / (1.0 + tax_rate))),
+            (None, None) => self.amount,
+        }
+    }
+
+    /// 指定した税率での税込金額を取得する
+    ///
+    /// 税抜金額（`pretax_amount`）に税率を適用します。
+    /// 記録されている税率と異なる税率で再計算する場合にも使用できます。
+    ///
+    /// # 引数
+    /// * `tax_rate` - 税率（10%の場合は0.1）
+    pub fn total_with_tax(&self, tax_rate: f64) -> f64 {
+        let pretax = self.pretax_amount();
+        yen_tax(pretax, tax_rate, calc::add_tax)
+            .map(|breakdown| breakdown.inclusive as f64)
+            .unwrap_or_else(|| round_to_currency_precision(pretax * (1.0 + tax_rate)))
+    }
+
+    /// CSVのヘッダー行の列名を取得する
+    ///
+    /// `serialize_for_csv`が返す列と同じ順序です。
+    pub fn csv_headers() -> Vec<&'static str> {
+        vec![
+            "id",
+            "date",
+            "amount",
+            "category",
+            "description",
+            "receipt_url",
+            "tax_rate",
+            "tax_amount",
+            "created_at",
+            "updated_at",
+        ]
+    }
+
+    /// CSVの1行分の値を取得する
+    ///
+    /// 各値はエスケープ済みのため、カンマで連結するだけでCSVの行になります。
+    /// 値がない項目は空文字列になります。
+    pub fn serialize_for_csv(&self) -> Vec<String> {
+        let optional_number = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+
+        vec![
+            self.id.to_string(),
+            escape_csv_field(&self.date),
+            self.amount.to_string(),
+            escape_csv_field(&self.category),
+            escape_csv_field(self.description.as_deref().unwrap_or("")),
+            escape_csv_field(self.receipt_url.as_deref().unwrap_or("")),
+            optional_number(self.tax_rate),
+            optional_number(self.tax_amount),
+            escape_csv_field(&self.created_at),
+            escape_csv_field(&self.updated_at),
+        ]
+    }
+}
+
+/// 円単位の金額と有効な税率の場合のみ、`calc`の税計算（切り捨て）を適用する
+fn yen_tax(
+    amount: f64,
+    tax_rate: f64,
+    compute: fn(i64, calc::Rate, calc::RoundingMode) -> AppResult<calc::TaxBreakdown>,
+) -> Option<calc::TaxBreakdown> {
+    let amount = calc::whole_yen(amount)?;
+    let rate = calc::Rate::tax(tax_rate).ok()?;
+    compute(amount, rate, calc::RoundingMode::Floor).ok()
 }
 
 /// 経費作成用DTO
@@ -24,6 +354,68 @@ pub struct CreateExpenseDto {
     pub description: Option<String>,
     /// ユーザーID（認証後に設定される、nanoId形式）
     pub user_id: Option<String>,
+    /// 消費税率（10%の場合は0.1）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tax_rate: Option<f64>,
+    /// 消費税額（amountに含まれる）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tax_amount: Option<f64>,
+    /// 領収書を後で添付する（領収書が必須のルールでも保存し、未添付として記録する）
+    #[serde(default, skip_serializing)]
+    pub receipt_followup: bool,
+    /// 金額の通貨（未指定の場合は日本円）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<CurrencyCode>,
+}
+
+impl CreateExpenseDto {
+    /// 税率・税額のバリデーションを行う
+    ///
+    /// # 戻り値
+    /// 正しい場合はOk(())、不正な場合はバリデーションエラー
+    pub fn validate_tax(&self) -> AppResult<()> {
+        self.validate_tax_rate()?;
+        self.validate_tax_amount()
+    }
+
+    /// すべてのフィールドのバリデーションを行う
+    ///
+    /// 最初のエラーで中断せず、すべてのフィールドを検証します。
+    ///
+    /// # 戻り値
+    /// フィールドごとのバリデーションエラー一覧（問題がない場合は空）
+    pub fn validate_all(&self) -> AppResult<Vec<ValidationError>> {
+        Ok(ValidationError::collect([
+            ("date", validate_date(&self.date)),
+            (
+                "amount",
+                validate_amount_for_currency(self.amount, self.currency.unwrap_or_default()),
+            ),
+            ("category", validate_category(&self.category)),
+            ("description", validate_description(&self.description)),
+            ("tax_rate", self.validate_tax_rate()),
+            ("tax_amount", self.validate_tax_amount()),
+        ]))
+    }
+
+    fn validate_tax_rate(&self) -> AppResult<()> {
+        match self.tax_rate {
+            Some(tax_rate) => validate_tax_rate(tax_rate),
+            None => Ok(()),
+        }
+    }
+
+    fn validate_tax_amount(&self) -> AppResult<()> {
+        if let Some(tax_amount) = self.tax_amount {
+            if !tax_amount.is_finite() || tax_amount < 0.0 {
+                return Err(AppError::validation("税額は0以上の数値で入力してください"));
+            }
+            if tax_amount > self.amount {
+                return Err(AppError::validation("税額は金額以下で入力してください"));
+            }
+        }
+        Ok(())
+    }
 }
 
 /// 経費更新用DTO
@@ -41,19 +433,283 @@ pub struct UpdateExpenseDto {
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub receipt_url: Option<String>,
+    /// 領収書を後で添付する（領収書が必須のルールでも保存し、未添付として記録する）
+    #[serde(default, skip_serializing)]
+    pub receipt_followup: bool,
 }
 
-/// 領収書キャッシュデータモデル
+impl UpdateExpenseDto {
+    /// 指定されたすべてのフィールドのバリデーションを行う
+    ///
+    /// 未指定（None）のフィールドは検証しません。`receipt_url`の空文字は
+    /// 領収書の削除として扱うため有効です。
+    ///
+    /// # 戻り値
+    /// フィールドごとのバリデーションエラー一覧（問題がない場合は空）
+    pub fn validate_all(&self) -> AppResult<Vec<ValidationError>> {
+        Ok(ValidationError::collect([
+            ("date", self.date.as_deref().map_or(Ok(()), validate_date)),
+            ("amount", self.amount.map_or(Ok(()), validate_amount)),
+            (
+                "category",
+                self.category.as_deref().map_or(Ok(()), validate_category),
+            ),
+            ("description", validate_description(&self.description)),
+            (
+                "receipt_url",
+                match self.receipt_url.as_deref() {
+                    Some(url) if !url.is_empty() => validate_https_url(url),
+                    _ => Ok(()),
+                },
+            ),
+        ]))
+    }
+}
+
+/// 経費の検索条件
+///
+/// 各コマンドで共通して使う絞り込み条件です。すべて任意で、指定した条件はAND結合されます。
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct ExpenseFilter {
+    pub start_date: Option<String>, // 開始日（YYYY-MM-DD形式、この日を含む）
+    pub end_date: Option<String>,   // 終了日（YYYY-MM-DD形式、この日を含む）
+    pub category: Option<String>,   // カテゴリ名
+    pub search: Option<String>,     // 説明・カテゴリの部分一致（大文字小文字を区別しない）
+    pub has_receipt: Option<bool>,  // 領収書の有無
+    pub min_amount: Option<f64>,    // 最小金額（この金額を含む）
+    pub max_amount: Option<f64>,    // 最大金額（この金額を含む）
+}
+
+impl ExpenseFilter {
+    /// 条件なしの検索条件を作成する
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 期間を指定する
+    pub fn with_date_range(
+        mut self,
+        start_date: Option<impl Into<String>>,
+        end_date: Option<impl Into<String>>,
+    ) -> Self {
+        self.start_date = start_date.map(Into::into);
+        self.end_date = end_date.map(Into::into);
+        self
+    }
+
+    /// カテゴリを指定する
+    pub fn with_category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// 検索キーワードを指定する
+    pub fn with_search(mut self, search: impl Into<String>) -> Self {
+        self.search = Some(search.into());
+        self
+    }
+
+    /// 領収書の有無を指定する
+    pub fn with_has_receipt(mut self, has_receipt: bool) -> Self {
+        self.has_receipt = Some(has_receipt);
+        self
+    }
+
+    /// 金額の範囲を指定する
+    pub fn with_amount_range(mut self, min_amount: Option<f64>, max_amount: Option<f64>) -> Self {
+        self.min_amount = min_amount;
+        self.max_amount = max_amount;
+        self
+    }
+
+    /// 空白のみの検索キーワードを未指定として扱う
+    pub fn search_term(&self) -> Option<&str> {
+        self.search
+            .as_deref()
+            .map(str::trim)
+            .filter(|search| !search.is_empty())
+    }
+
+    /// 検索条件のバリデーションを行う
+    ///
+    /// # 戻り値
+    /// 条件が正しい場合はOk(())、不正な場合はバリデーションエラー
+    pub fn validate(&self) -> AppResult<()> {
+        if let Some(start_date) = &self.start_date {
+            validate_date(start_date)?;
+        }
+        if let Some(end_date) = &self.end_date {
+            validate_date(end_date)?;
+        }
+        if let (Some(start), Some(end)) = (&self.start_date, &self.end_date) {
+            if start > end {
+                return Err(AppError::validation(
+                    "開始日は終了日以前の日付を指定してください",
+                ));
+            }
+        }
+
+        if let Some(search) = &self.search {
+            validate_text_length(search, 100, "検索キーワード")?;
+        }
+
+        for amount in [self.min_amount, self.max_amount].into_iter().flatten() {
+            if !amount.is_finite() || amount < 0.0 {
+                return Err(AppError::validation(
+                    "金額の範囲は0以上の数値で指定してください",
+                ));
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_amount, self.max_amount) {
+            if min > max {
+                return Err(AppError::validation(
+                    "最小金額は最大金額以下の値を指定してください",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 経費が検索条件に一致するかどうか
+    ///
+    /// API Serverが対応していない条件を取得後に適用する場合に使用します。
+    pub fn matches(&self, expense: &Expense) -> bool {
+        let in_range = |bound: &Option<String>, ok: fn(&str, &str) -> bool| {
+            bound
+                .as_deref()
+                .is_none_or(|bound| ok(&expense.date, bound))
+        };
+        let has_receipt = expense
+            .receipt_url
+            .as_deref()
+            .is_some_and(|url| !url.is_empty());
+
+        in_range(&self.start_date, |date, start| date >= start)
+            && in_range(&self.end_date, |date, end| date <= end)
+            && self
+                .category
+                .as_deref()
+                .is_none_or(|category| expense.category == category)
+            && self
+                .has_receipt
+                .is_none_or(|expected| has_receipt == expected)
+            && self.min_amount.is_none_or(|min| expense.amount >= min)
+            && self.max_amount.is_none_or(|max| expense.amount <= max)
+            && self.search_term().is_none_or(|search| {
+                let search = search.to_lowercase();
+                expense.category.to_lowercase().contains(&search)
+                    || expense
+                        .description
+                        .as_deref()
+                        .is_some_and(|description| description.to_lowercase().contains(&search))
+            })
+    }
+}
+
+/// 1ページで取得できる経費の最大件数
+pub const MAX_EXPENSES_PAGE_SIZE: u32 = 500;
+
+/// 経費一覧の並び順
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpenseSortKey {
+    /// 日付の新しい順（従来の一覧と同じ）
+    #[default]
+    DateDesc,
+    /// 日付の古い順
+    DateAsc,
+    /// 金額の大きい順
+    AmountDesc,
+    /// 金額の小さい順
+    AmountAsc,
+}
+
+/// 経費一覧のページ取得条件
+///
+/// `get_expenses`に指定すると、一致した件数（`total_count`）と指定範囲の経費だけを返します。
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct GetExpensesQuery {
+    pub from_date: Option<String>, // 開始日（YYYY-MM-DD形式、この日を含む）
+    pub to_date: Option<String>,   // 終了日（YYYY-MM-DD形式、この日を含む）
+    pub category: Option<String>,  // カテゴリ名
+    pub limit: Option<u32>,        // 取得件数（未指定の場合は一致したすべて）
+    pub offset: Option<u32>,       // 読み飛ばす件数
+    pub sort_by: ExpenseSortKey,   // 並び順
+}
+
+impl GetExpensesQuery {
+    /// ページ取得条件のバリデーションを行う
+    ///
+    /// # 戻り値
+    /// 条件が正しい場合はOk(())、不正な場合はバリデーションエラー
+    pub fn validate(&self) -> AppResult<()> {
+        if let Some(from_date) = &self.from_date {
+            validate_date(from_date)?;
+        }
+        if let Some(to_date) = &self.to_date {
+            validate_date(to_date)?;
+        }
+        if let (Some(from), Some(to)) = (&self.from_date, &self.to_date) {
+            if from > to {
+                return Err(AppError::validation(
+                    "開始日は終了日以前の日付を指定してください",
+                ));
+            }
+        }
+
+        if let Some(limit) = self.limit {
+            if limit == 0 || limit > MAX_EXPENSES_PAGE_SIZE {
+                return Err(AppError::validation(format!(
+                    "取得件数は1〜{MAX_EXPENSES_PAGE_SIZE}件の範囲で指定してください"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// ページ単位の経費一覧
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct ReceiptCache {
-    pub id: i64,
-    pub receipt_url: String,
-    pub local_path: String,
-    pub cached_at: String,
-    pub file_size: i64,
-    pub last_accessed: String,
+pub struct ExpensePage {
+    pub expenses: Vec<Expense>,
+    pub total_count: usize, // ページに関係なく条件に一致した件数
+}
+
+/// 経費一覧コマンドの戻り値
+///
+/// ページ取得条件を指定しない場合は、従来どおり経費の配列として返します。
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum ExpenseList {
+    All(Vec<Expense>),
+    Page(ExpensePage),
 }
 
+impl ExpenseList {
+    /// 返却する経費一覧
+    pub fn expenses(&self) -> &[Expense] {
+        match self {
+            ExpenseList::All(expenses) => expenses,
+            ExpenseList::Page(page) => &page.expenses,
+        }
+    }
+
+    /// 返却する経費一覧（変更用）
+    pub fn expenses_mut(&mut self) -> &mut [Expense] {
+        match self {
+            ExpenseList::All(expenses) => expenses,
+            ExpenseList::Page(page) => &mut page.expenses,
+        }
+    }
+}
+
+/// 領収書キャッシュデータモデル（領収書機能のモデルを共有）
+pub use crate::features::receipts::models::ReceiptCache;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -71,6 +727,16 @@ mod tests {
             receipt_url: Some("https://example.com/receipt.pdf".to_string()),
             created_at: "2024-01-01T00:00:00+09:00".to_string(),
             updated_at: "2024-01-01T00:00:00+09:00".to_string(),
+            category_color: Some("#FF6B6B".to_string()),
+            category_icon: Some("🍽️".to_string()),
+            unknown_category: false,
+            tax_rate: None,
+            tax_amount: None,
+            latitude: None,
+            longitude: None,
+            location_opt_in: false,
+            merchant_id: None,
+            missing_receipt: false,
         };
 
         // JSONシリアライゼーション
@@ -86,6 +752,102 @@ mod tests {
         assert_eq!(deserialized.category, expense.category);
     }
 
+    #[test]
+    fn test_serialize_for_csv() {
+        let mut expense: Expense = serde_json::from_value(serde_json::json!({
+            "id": 7,
+            "date": "2024-03-15",
+            "amount": 1980.0,
+            "category": "交通費",
+            "description": "タクシー代, 深夜\n\"急ぎ\"の移動",
+            "receipt_url": null,
+            "created_at": "2024-03-15T00:00:00+09:00",
+            "updated_at": "2024-03-15T00:00:00+09:00",
+            "tax_rate": 0.1,
+            "tax_amount": 180.0
+        }))
+        .unwrap();
+
+        let row = expense.serialize_for_csv();
+        assert_eq!(row.len(), Expense::csv_headers().len());
+        assert_eq!(
+            row,
+            vec![
+                "7",
+                "2024-03-15",
+                "1980",
+                "交通費",
+                "\"タクシー代, 深夜\n\"\"急ぎ\"\"の移動\"",
+                "",
+                "0.1",
+                "180",
+                "2024-03-15T00:00:00+09:00",
+                "2024-03-15T00:00:00+09:00",
+            ]
+        );
+
+        // 説明・税情報がない場合は空欄
+        expense.description = None;
+        expense.tax_rate = None;
+        expense.tax_amount = None;
+        let row = expense.serialize_for_csv();
+        assert_eq!(row[4], "");
+        assert_eq!(row[6], "");
+        assert_eq!(row[7], "");
+    }
+
+    #[test]
+    fn test_receipt_status() {
+        let mut expense: Expense = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "date": "2024-01-01",
+            "amount": 1000.0,
+            "category": "食費",
+            "category_id": null,
+            "description": null,
+            "receipt_url": null,
+            "created_at": "2024-01-01T00:00:00+09:00",
+            "updated_at": "2024-01-01T00:00:00+09:00"
+        }))
+        .unwrap();
+        assert_eq!(expense.receipt_status(), ReceiptStatus::None);
+
+        expense.receipt_url = Some("  ".to_string());
+        assert_eq!(expense.receipt_status(), ReceiptStatus::None);
+
+        expense.receipt_url = Some("/Users/demo/receipts/1.png".to_string());
+        assert_eq!(expense.receipt_status(), ReceiptStatus::Broken);
+        expense.receipt_url = Some("http://example.com/receipt.png".to_string());
+        assert_eq!(expense.receipt_status(), ReceiptStatus::Broken);
+
+        expense.receipt_url = Some("https://example.com/receipt.png".to_string());
+        assert_eq!(
+            expense.receipt_status(),
+            ReceiptStatus::Attached {
+                url: "https://example.com/receipt.png".to_string()
+            }
+        );
+
+        // シリアライズ結果に添付状態が含まれ、位置情報は未設定なら省略される
+        let json = serde_json::to_value(&expense).unwrap();
+        assert_eq!(
+            json["receipt_status"],
+            serde_json::json!({"status": "attached", "url": "https://example.com/receipt.png"})
+        );
+        assert!(json.get("latitude").is_none());
+
+        expense.receipt_url = None;
+        let json = serde_json::to_value(&expense).unwrap();
+        assert_eq!(
+            json["receipt_status"],
+            serde_json::json!({"status": "none"})
+        );
+
+        // 出力したJSONはそのまま読み込める
+        let deserialized: Expense = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized.receipt_status(), ReceiptStatus::None);
+    }
+
     #[test]
     fn test_create_expense_dto_deserialization() {
         // 経費作成DTOのデシリアライゼーションテスト
@@ -116,6 +878,202 @@ mod tests {
         assert_eq!(dto.description, None);
     }
 
+    #[test]
+    fn test_create_expense_dto_tax_fields() {
+        let json = r#"{
+            "date": "2024-01-01",
+            "amount": 1100.0,
+            "category": "消耗品費",
+            "tax_rate": 0.1,
+            "tax_amount": 100.0
+        }"#;
+        let dto: CreateExpenseDto = serde_json::from_str(json).unwrap();
+        assert_eq!(dto.tax_rate, Some(STANDARD_TAX_RATE));
+        assert_eq!(dto.tax_amount, Some(100.0));
+        assert!(dto.validate_tax().is_ok());
+
+        let percent = CreateExpenseDto {
+            tax_rate: Some(10.0),
+            ..dto
+        };
+        assert!(percent.validate_tax().is_err());
+        let too_much_tax = CreateExpenseDto {
+            tax_rate: None,
+            tax_amount: Some(2000.0),
+            ..percent
+        };
+        assert!(too_much_tax.validate_tax().is_err());
+
+        // 税率・税額が未指定の場合はAPI Serverに送信しない
+        let dto: CreateExpenseDto =
+            serde_json::from_str(r#"{"date": "2024-01-01", "amount": 1.0, "category": "a"}"#)
+                .unwrap();
+        let json = serde_json::to_string(&dto).unwrap();
+        assert!(!json.contains("tax_rate"));
+        assert!(dto.validate_tax().is_ok());
+    }
+
+    #[test]
+    fn test_create_expense_dto_validate_all() {
+        let dto: CreateExpenseDto = serde_json::from_str(
+            r#"{"date": "2024-01-01", "amount": 1100.0, "category": "消耗品費", "tax_rate": 0.1}"#,
+        )
+        .unwrap();
+        assert!(dto.validate_all().unwrap().is_empty());
+
+        // すべてのエラーがまとめて返される
+        let invalid = CreateExpenseDto {
+            date: "2024/01/01".to_string(),
+            amount: -1.0,
+            category: " ".to_string(),
+            description: Some("あ".repeat(501)),
+            tax_rate: Some(10.0),
+            tax_amount: Some(-1.0),
+            ..dto
+        };
+        let fields: Vec<String> = invalid
+            .validate_all()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.field)
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                "date",
+                "amount",
+                "category",
+                "description",
+                "tax_rate",
+                "tax_amount"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_create_expense_dto_currency() {
+        // 通貨の指定がない場合は日本円として整数のみ受け付ける
+        let dto: CreateExpenseDto = serde_json::from_str(
+            r#"{"date": "2024-01-01", "amount": 100.5, "category": "消耗品費"}"#,
+        )
+        .unwrap();
+        assert_eq!(dto.currency, None);
+        let errors = dto.validate_all().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "amount");
+
+        // 米ドルは小数点以下2桁まで受け付ける
+        let dto: CreateExpenseDto = serde_json::from_str(
+            r#"{"date": "2024-01-01", "amount": 100.50, "category": "消耗品費", "currency": "USD"}"#,
+        )
+        .unwrap();
+        assert_eq!(dto.currency, Some(CurrencyCode::Usd));
+        assert!(dto.validate_all().unwrap().is_empty());
+
+        // 未指定の場合はAPIサーバーに送信しない
+        let json = serde_json::to_value(CreateExpenseDto {
+            currency: None,
+            ..dto
+        })
+        .unwrap();
+        assert!(json.get("currency").is_none());
+    }
+
+    #[test]
+    fn test_get_expenses_query_validate() {
+        assert!(GetExpensesQuery::default().validate().is_ok());
+
+        let query: GetExpensesQuery = serde_json::from_str(
+            r#"{"from_date": "2024-01-01", "to_date": "2024-01-31", "limit": 50, "offset": 100, "sort_by": "amount_desc"}"#,
+        )
+        .unwrap();
+        assert!(query.validate().is_ok());
+        assert_eq!(query.sort_by, ExpenseSortKey::AmountDesc);
+
+        let reversed = GetExpensesQuery {
+            from_date: Some("2024-02-01".to_string()),
+            to_date: Some("2024-01-31".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(reversed.validate(), Err(AppError::Validation(_))));
+
+        let invalid_date = GetExpensesQuery {
+            to_date: Some("2024/01/31".to_string()),
+            ..Default::default()
+        };
+        assert!(invalid_date.validate().is_err());
+
+        for limit in [0, MAX_EXPENSES_PAGE_SIZE + 1] {
+            let query = GetExpensesQuery {
+                limit: Some(limit),
+                ..Default::default()
+            };
+            assert!(query.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_update_expense_dto_validate_all() {
+        let empty: UpdateExpenseDto = serde_json::from_str("{}").unwrap();
+        assert!(empty.validate_all().unwrap().is_empty());
+
+        // 領収書URLの空文字は削除として有効
+        let clear_receipt: UpdateExpenseDto =
+            serde_json::from_str(r#"{"receipt_url": "", "amount": 500.0}"#).unwrap();
+        assert!(clear_receipt.validate_all().unwrap().is_empty());
+
+        let invalid: UpdateExpenseDto = serde_json::from_str(
+            r#"{"date": "2024-13-01", "amount": 0, "receipt_url": "http://example.com/a.png"}"#,
+        )
+        .unwrap();
+        let errors = invalid.validate_all().unwrap();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["date", "amount", "receipt_url"]);
+    }
+
+    #[test]
+    fn test_expense_tax_amounts() {
+        let mut expense: Expense = serde_json::from_str(
+            r#"{
+                "id": 1,
+                "date": "2024-01-01",
+                "amount": 1100.0,
+                "category": "消耗品費",
+                "category_id": null,
+                "description": null,
+                "receipt_url": null,
+                "created_at": "2024-01-01T00:00:00+09:00",
+                "updated_at": "2024-01-01T00:00:00+09:00"
+            }"#,
+        )
+        .unwrap();
+
+        // 税情報がない場合は金額をそのまま税抜金額とみなす
+        assert_eq!(expense.pretax_amount(), 1100.0);
+        assert_eq!(expense.total_with_tax(STANDARD_TAX_RATE), 1210.0);
+
+        // 税率のみの場合は逆算する
+        expense.tax_rate = Some(STANDARD_TAX_RATE);
+        assert_eq!(expense.pretax_amount(), 1000.0);
+        assert_eq!(expense.total_with_tax(STANDARD_TAX_RATE), 1100.0);
+        assert_eq!(expense.total_with_tax(REDUCED_TAX_RATE), 1080.0);
+
+        // 税額が記録されている場合はそれを優先する
+        expense.tax_amount = Some(99.0);
+        assert_eq!(expense.pretax_amount(), 1001.0);
+
+        // 円単位の金額は税額を切り捨てて逆算する（税額90円）
+        expense.amount = 1000.0;
+        expense.tax_amount = None;
+        assert_eq!(expense.pretax_amount(), 910.0);
+        assert_eq!(expense.total_with_tax(0.0), 910.0);
+        assert_eq!(expense.total_with_tax(STANDARD_TAX_RATE), 1001.0);
+
+        // 円未満の端数を含む金額は小数点以下2桁に丸める
+        expense.amount = 1000.5;
+        assert_eq!(expense.pretax_amount(), 909.55);
+    }
+
     #[test]
     fn test_update_expense_dto_partial() {
         // 部分更新DTOのテスト
@@ -132,6 +1090,77 @@ mod tests {
         assert_eq!(dto.receipt_url, None);
     }
 
+    #[test]
+    fn test_expense_filter_builder_and_validate() {
+        let filter = ExpenseFilter::new()
+            .with_date_range(Some("2024-01-01"), Some("2024-01-31"))
+            .with_category("交通費")
+            .with_has_receipt(false)
+            .with_amount_range(Some(100.0), None);
+        assert_eq!(filter.start_date.as_deref(), Some("2024-01-01"));
+        assert_eq!(filter.category.as_deref(), Some("交通費"));
+        assert!(filter.validate().is_ok());
+        assert!(ExpenseFilter::new().validate().is_ok());
+
+        let reversed = ExpenseFilter::new().with_date_range(Some("2024-02-01"), Some("2024-01-01"));
+        assert!(reversed.validate().is_err());
+        let invalid_date = ExpenseFilter::new().with_date_range(Some("2024/01/01"), None::<String>);
+        assert!(invalid_date.validate().is_err());
+        let reversed_amount = ExpenseFilter::new().with_amount_range(Some(500.0), Some(100.0));
+        assert!(reversed_amount.validate().is_err());
+        let negative = ExpenseFilter::new().with_amount_range(Some(-1.0), None);
+        assert!(negative.validate().is_err());
+
+        // 未指定のフィールドは省略できる
+        let filter: ExpenseFilter = serde_json::from_str(r#"{"category": "交通費"}"#).unwrap();
+        assert_eq!(filter, ExpenseFilter::new().with_category("交通費"));
+    }
+
+    #[test]
+    fn test_expense_filter_matches() {
+        let expense = Expense {
+            id: 1,
+            date: "2024-01-15".to_string(),
+            amount: 1200.0,
+            category: "交通費".to_string(),
+            category_id: None,
+            description: Some("Suica チャージ".to_string()),
+            receipt_url: None,
+            created_at: "2024-01-15T00:00:00+09:00".to_string(),
+            updated_at: "2024-01-15T00:00:00+09:00".to_string(),
+            category_color: None,
+            category_icon: None,
+            unknown_category: false,
+            tax_rate: None,
+            tax_amount: None,
+            latitude: None,
+            longitude: None,
+            location_opt_in: false,
+            merchant_id: None,
+            missing_receipt: false,
+        };
+
+        assert!(ExpenseFilter::new().matches(&expense));
+        assert!(ExpenseFilter::new()
+            .with_date_range(Some("2024-01-15"), Some("2024-01-15"))
+            .with_search(" suica ")
+            .with_has_receipt(false)
+            .with_amount_range(Some(1200.0), Some(1200.0))
+            .matches(&expense));
+        assert!(!ExpenseFilter::new()
+            .with_category("飲食費")
+            .matches(&expense));
+        assert!(!ExpenseFilter::new()
+            .with_has_receipt(true)
+            .matches(&expense));
+        assert!(!ExpenseFilter::new()
+            .with_search("タクシー")
+            .matches(&expense));
+        assert!(!ExpenseFilter::new()
+            .with_amount_range(None, Some(1000.0))
+            .matches(&expense));
+    }
+
     #[test]
     fn test_receipt_cache_model() {
         // 領収書キャッシュモデルのテスト
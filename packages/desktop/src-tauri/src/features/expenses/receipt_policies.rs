@@ -0,0 +1,752 @@
+/// 領収書の添付ルール
+///
+/// カテゴリごとに、一定金額以上の経費に領収書の添付を求めます。
+/// - 必須（hard）: 領収書がない経費は保存しない。後で添付することを明示した場合は保存し、未添付として記録する
+/// - 推奨（soft）: 保存するが、未添付として記録する
+///
+/// 未添付の記録は、経費に領収書が添付されると自動的に解除されます。
+use crate::features::expenses::models::{
+    CreateReceiptPolicyDto, Expense, MissingReceiptExpense, ReceiptEnforcement, ReceiptPolicy,
+    UpdateReceiptPolicyDto,
+};
+use crate::features::migrations::auto_migration::executor::MigrationExecutorTrait;
+use crate::features::migrations::auto_migration::models::{
+    ExecutableMigrationDefinition, MigrationDefinition,
+};
+use crate::features::migrations::auto_migration::registry::MigrationRegistry;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::utils::{get_current_jst_timestamp, validate_category, validate_date};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashSet;
+
+/// 領収書の添付ルールマイグレーションのSQL
+const RECEIPT_POLICIES_SCHEMA_SQL: &str = "
+    CREATE TABLE IF NOT EXISTS receipt_policies (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        user_id TEXT NOT NULL,
+        category TEXT NOT NULL,
+        category_id INTEGER,
+        min_amount REAL NOT NULL,
+        enforcement TEXT NOT NULL CHECK (enforcement IN ('hard', 'soft')),
+        created_at TEXT NOT NULL,
+        updated_at TEXT NOT NULL,
+        UNIQUE(user_id, category)
+    );
+    CREATE TABLE IF NOT EXISTS expense_receipt_flags (
+        user_id TEXT NOT NULL,
+        expense_id INTEGER NOT NULL,
+        policy_id INTEGER NOT NULL,
+        enforcement TEXT NOT NULL,
+        followup_requested INTEGER NOT NULL DEFAULT 0,
+        flagged_at TEXT NOT NULL,
+        PRIMARY KEY (user_id, expense_id)
+    );
+    CREATE TRIGGER IF NOT EXISTS trg_receipt_policies_deleted
+        AFTER DELETE ON receipt_policies
+    BEGIN
+        DELETE FROM expense_receipt_flags WHERE policy_id = OLD.id;
+    END;
+";
+
+/// 領収書の添付ルールテーブル作成マイグレーション実行器
+pub struct ReceiptPoliciesSchemaMigration;
+
+impl MigrationExecutorTrait for ReceiptPoliciesSchemaMigration {
+    fn name(&self) -> &str {
+        "013_create_receipt_policies"
+    }
+
+    fn execute(&self, conn: &Connection) -> Result<(), String> {
+        conn.execute_batch(RECEIPT_POLICIES_SCHEMA_SQL)
+            .map_err(|e| format!("receipt_policiesテーブル作成エラー: {e}"))
+    }
+}
+
+/// 領収書の添付ルールテーブル用マイグレーション定義を取得する
+///
+/// # 戻り値
+/// 実行可能なマイグレーション定義
+pub fn get_receipt_policies_schema_definition() -> ExecutableMigrationDefinition {
+    let definition = MigrationDefinition::new(
+        "013_create_receipt_policies".to_string(),
+        "3.9.0".to_string(),
+        "領収書の添付ルールと領収書未添付の記録テーブルの作成".to_string(),
+        MigrationRegistry::calculate_checksum(RECEIPT_POLICIES_SCHEMA_SQL),
+    );
+
+    ExecutableMigrationDefinition::new(definition, Box::new(ReceiptPoliciesSchemaMigration))
+}
+
+/// ルールの評価対象となる経費の内容
+#[derive(Debug, Clone, Copy)]
+pub struct ReceiptCheck<'a> {
+    pub amount: f64,
+    pub category: &'a str,
+    pub category_id: Option<i64>,
+    pub has_receipt: bool,
+}
+
+impl<'a> ReceiptCheck<'a> {
+    /// 保存済みの経費から作成する
+    pub fn for_expense(expense: &'a Expense) -> Self {
+        Self {
+            amount: expense.amount,
+            category: &expense.category,
+            category_id: expense.category_id,
+            has_receipt: expense
+                .receipt_url
+                .as_deref()
+                .is_some_and(|url| !url.trim().is_empty()),
+        }
+    }
+}
+
+/// ルールの評価結果
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReceiptDecision {
+    /// 適用されるルールがない
+    NotRequired,
+    /// 領収書が添付されている
+    Satisfied,
+    /// 領収書が必須のため保存しない
+    Rejected(ReceiptPolicy),
+    /// 保存するが、領収書未添付として記録する
+    MissingReceipt(ReceiptPolicy),
+}
+
+impl ReceiptDecision {
+    /// 保存できない場合のエラー
+    pub fn into_result(self) -> AppResult<Self> {
+        match self {
+            ReceiptDecision::Rejected(policy) => Err(AppError::validation(format!(
+                "「{}」の{}円以上の経費には領収書の添付が必要です。後で添付する場合は「領収書を後で添付する」を選択してください",
+                policy.category,
+                format_amount(policy.min_amount)
+            ))),
+            decision => Ok(decision),
+        }
+    }
+}
+
+/// 金額を桁区切りで表示する（小数点以下は値がある場合のみ）
+fn format_amount(amount: f64) -> String {
+    let integer = amount.trunc() as i64;
+    let digits = integer.abs().to_string();
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    let sign = if integer < 0 { "-" } else { "" };
+    let fraction = amount.fract().abs();
+    if fraction > 0.0 {
+        format!("{sign}{grouped}{}", &format!("{fraction:.2}")[1..])
+    } else {
+        format!("{sign}{grouped}")
+    }
+}
+
+/// ルールが経費のカテゴリに一致するかどうか
+///
+/// 双方のカテゴリーIDが分かる場合はIDで照合し、カテゴリ名の変更に影響されないようにします。
+fn matches_category(policy: &ReceiptPolicy, check: &ReceiptCheck) -> bool {
+    match (policy.category_id, check.category_id) {
+        (Some(policy_id), Some(expense_id)) => policy_id == expense_id,
+        _ => policy.category == check.category,
+    }
+}
+
+/// 経費に適用されるルールを評価する
+///
+/// 複数のルールが適用される場合は必須（hard）のルールを優先します。
+///
+/// # 引数
+/// * `policies` - 利用者のルール
+/// * `check` - 評価対象の経費の内容
+/// * `followup` - 後で領収書を添付することを明示したかどうか
+pub fn evaluate(
+    policies: &[ReceiptPolicy],
+    check: &ReceiptCheck,
+    followup: bool,
+) -> ReceiptDecision {
+    let applicable = policies
+        .iter()
+        .filter(|policy| matches_category(policy, check) && check.amount >= policy.min_amount)
+        .min_by_key(|policy| match policy.enforcement {
+            ReceiptEnforcement::Hard => 0,
+            ReceiptEnforcement::Soft => 1,
+        });
+
+    match applicable {
+        None => ReceiptDecision::NotRequired,
+        Some(_) if check.has_receipt => ReceiptDecision::Satisfied,
+        Some(policy) if policy.enforcement == ReceiptEnforcement::Hard && !followup => {
+            ReceiptDecision::Rejected(policy.clone())
+        }
+        Some(policy) => ReceiptDecision::MissingReceipt(policy.clone()),
+    }
+}
+
+fn row_to_policy(row: &rusqlite::Row) -> rusqlite::Result<(ReceiptPolicy, String)> {
+    Ok((
+        ReceiptPolicy {
+            id: row.get(0)?,
+            category: row.get(1)?,
+            category_id: row.get(2)?,
+            min_amount: row.get(3)?,
+            enforcement: ReceiptEnforcement::Soft,
+            created_at: row.get(5)?,
+            updated_at: row.get(6)?,
+        },
+        row.get(4)?,
+    ))
+}
+
+/// ルールの最低金額を検証する（0円の場合はすべての経費が対象）
+fn validate_min_amount(min_amount: f64) -> AppResult<()> {
+    if !min_amount.is_finite() || min_amount < 0.0 {
+        return Err(AppError::validation(
+            "最低金額は0以上の数値で入力してください",
+        ));
+    }
+    Ok(())
+}
+
+const POLICY_COLUMNS: &str =
+    "id, category, category_id, min_amount, enforcement, created_at, updated_at";
+
+/// 利用者の領収書の添付ルールを取得する（カテゴリ名順）
+pub fn list_policies(conn: &Connection, user_id: &str) -> AppResult<Vec<ReceiptPolicy>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {POLICY_COLUMNS} FROM receipt_policies WHERE user_id = ?1 ORDER BY category"
+    ))?;
+    let rows = stmt
+        .query_map(params![user_id], row_to_policy)?
+        .collect::<Result<Vec<_>, _>>()?;
+    rows.into_iter()
+        .map(|(policy, enforcement)| {
+            Ok(ReceiptPolicy {
+                enforcement: ReceiptEnforcement::parse(&enforcement)?,
+                ..policy
+            })
+        })
+        .collect()
+}
+
+fn find_policy(conn: &Connection, user_id: &str, policy_id: i64) -> AppResult<ReceiptPolicy> {
+    let (policy, enforcement) = conn
+        .query_row(
+            &format!(
+                "SELECT {POLICY_COLUMNS} FROM receipt_policies WHERE user_id = ?1 AND id = ?2"
+            ),
+            params![user_id, policy_id],
+            row_to_policy,
+        )
+        .optional()?
+        .ok_or_else(|| AppError::not_found(format!("領収書の添付ルール(ID: {policy_id})")))?;
+    Ok(ReceiptPolicy {
+        enforcement: ReceiptEnforcement::parse(&enforcement)?,
+        ..policy
+    })
+}
+
+/// 領収書の添付ルールを作成する
+pub fn create_policy(
+    conn: &Connection,
+    user_id: &str,
+    dto: &CreateReceiptPolicyDto,
+) -> AppResult<ReceiptPolicy> {
+    let category = dto.category.trim();
+    validate_category(category)?;
+    validate_min_amount(dto.min_amount)?;
+
+    let now = get_current_jst_timestamp();
+    let inserted = conn.execute(
+        "INSERT INTO receipt_policies
+             (user_id, category, category_id, min_amount, enforcement, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
+         ON CONFLICT(user_id, category) DO NOTHING",
+        params![
+            user_id,
+            category,
+            dto.category_id,
+            dto.min_amount,
+            dto.enforcement.as_str(),
+            now
+        ],
+    )?;
+    if inserted == 0 {
+        return Err(AppError::validation(format!(
+            "「{category}」の領収書の添付ルールは既に登録されています"
+        )));
+    }
+    find_policy(conn, user_id, conn.last_insert_rowid())
+}
+
+/// 領収書の添付ルールを更新する
+pub fn update_policy(
+    conn: &Connection,
+    user_id: &str,
+    policy_id: i64,
+    dto: &UpdateReceiptPolicyDto,
+) -> AppResult<ReceiptPolicy> {
+    let current = find_policy(conn, user_id, policy_id)?;
+    let min_amount = dto.min_amount.unwrap_or(current.min_amount);
+    validate_min_amount(min_amount)?;
+    let enforcement = dto.enforcement.unwrap_or(current.enforcement);
+
+    conn.execute(
+        "UPDATE receipt_policies SET min_amount = ?3, enforcement = ?4, updated_at = ?5
+         WHERE user_id = ?1 AND id = ?2",
+        params![
+            user_id,
+            policy_id,
+            min_amount,
+            enforcement.as_str(),
+            get_current_jst_timestamp()
+        ],
+    )?;
+    find_policy(conn, user_id, policy_id)
+}
+
+/// 領収書の添付ルールを削除する（このルールによる未添付の記録も解除する）
+///
+/// # 戻り値
+/// 削除した場合はtrue
+pub fn delete_policy(conn: &Connection, user_id: &str, policy_id: i64) -> AppResult<bool> {
+    let deleted = conn.execute(
+        "DELETE FROM receipt_policies WHERE user_id = ?1 AND id = ?2",
+        params![user_id, policy_id],
+    )?;
+    Ok(deleted > 0)
+}
+
+/// 経費を領収書未添付として記録する
+pub fn flag_missing_receipt(
+    conn: &Connection,
+    user_id: &str,
+    expense_id: i64,
+    policy: &ReceiptPolicy,
+    followup_requested: bool,
+) -> AppResult<()> {
+    conn.execute(
+        "INSERT INTO expense_receipt_flags
+             (user_id, expense_id, policy_id, enforcement, followup_requested, flagged_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(user_id, expense_id) DO UPDATE SET
+             policy_id = excluded.policy_id,
+             enforcement = excluded.enforcement,
+             followup_requested = excluded.followup_requested",
+        params![
+            user_id,
+            expense_id,
+            policy.id,
+            policy.enforcement.as_str(),
+            followup_requested,
+            get_current_jst_timestamp()
+        ],
+    )?;
+    Ok(())
+}
+
+/// 経費の領収書未添付の記録を解除する
+pub fn clear_flag(conn: &Connection, user_id: &str, expense_id: i64) -> AppResult<()> {
+    conn.execute(
+        "DELETE FROM expense_receipt_flags WHERE user_id = ?1 AND expense_id = ?2",
+        params![user_id, expense_id],
+    )?;
+    Ok(())
+}
+
+/// 評価結果に従って領収書未添付の記録を更新する
+///
+/// # 戻り値
+/// 領収書未添付として記録した場合はtrue
+pub fn apply_decision(
+    conn: &Connection,
+    user_id: &str,
+    expense_id: i64,
+    decision: &ReceiptDecision,
+    followup_requested: bool,
+) -> AppResult<bool> {
+    match decision {
+        ReceiptDecision::MissingReceipt(policy) => {
+            flag_missing_receipt(conn, user_id, expense_id, policy, followup_requested)?;
+            Ok(true)
+        }
+        _ => {
+            clear_flag(conn, user_id, expense_id)?;
+            Ok(false)
+        }
+    }
+}
+
+/// 領収書が添付された経費の未添付の記録を解除する
+///
+/// 経費のミラー（`expense_mirror`）を参照するため、同期後に呼び出します。
+///
+/// # 戻り値
+/// 解除した件数
+pub fn clear_resolved_flags(conn: &Connection, user_id: &str) -> AppResult<usize> {
+    Ok(conn.execute(
+        "DELETE FROM expense_receipt_flags
+         WHERE user_id = ?1 AND expense_id IN (
+             SELECT id FROM expense_mirror
+             WHERE user_id = ?1
+               AND COALESCE(TRIM(json_extract(payload, '$.receipt_url')), '') != ''
+         )",
+        params![user_id],
+    )?)
+}
+
+/// 領収書未添付として記録されている経費のIDを取得する
+pub fn flagged_expense_ids(conn: &Connection, user_id: &str) -> AppResult<HashSet<i64>> {
+    let mut stmt =
+        conn.prepare("SELECT expense_id FROM expense_receipt_flags WHERE user_id = ?1")?;
+    let ids = stmt
+        .query_map(params![user_id], |row| row.get(0))?
+        .collect::<Result<HashSet<_>, _>>()?;
+    Ok(ids)
+}
+
+/// 領収書未添付として記録されている経費を取得する（日付の新しい順）
+///
+/// # 引数
+/// * `start_date` - 開始日（YYYY-MM-DD形式、この日を含む）
+/// * `end_date` - 終了日（YYYY-MM-DD形式、この日を含む）
+pub fn missing_receipt_expenses(
+    conn: &Connection,
+    user_id: &str,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> AppResult<Vec<MissingReceiptExpense>> {
+    for date in [start_date, end_date].into_iter().flatten() {
+        validate_date(date)?;
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT m.payload, f.policy_id, f.enforcement, f.followup_requested, f.flagged_at
+         FROM expense_receipt_flags f
+         JOIN expense_mirror m ON m.user_id = f.user_id AND m.id = f.expense_id
+         WHERE f.user_id = ?1
+           AND (?2 IS NULL OR m.date >= ?2)
+           AND (?3 IS NULL OR m.date <= ?3)
+         ORDER BY m.date DESC, m.created_at DESC",
+    )?;
+    let rows = stmt
+        .query_map(params![user_id, start_date, end_date], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, bool>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut expenses = Vec::with_capacity(rows.len());
+    for (payload, policy_id, enforcement, followup_requested, flagged_at) in rows {
+        let expense: Expense = serde_json::from_str(&payload)?;
+        if ReceiptCheck::for_expense(&expense).has_receipt {
+            continue;
+        }
+        expenses.push(MissingReceiptExpense {
+            expense: Expense {
+                missing_receipt: true,
+                ..expense
+            },
+            policy_id,
+            enforcement: ReceiptEnforcement::parse(&enforcement)?,
+            followup_requested,
+            flagged_at,
+        });
+    }
+    Ok(expenses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::expenses::sync::{replace_all, ExpenseMirrorSchemaMigration};
+
+    fn create_test_connection() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        ExpenseMirrorSchemaMigration.execute(&conn).unwrap();
+        ReceiptPoliciesSchemaMigration.execute(&conn).unwrap();
+        conn
+    }
+
+    fn policy(
+        category: &str,
+        category_id: Option<i64>,
+        min_amount: f64,
+        enforcement: ReceiptEnforcement,
+    ) -> ReceiptPolicy {
+        ReceiptPolicy {
+            id: 1,
+            category: category.to_string(),
+            category_id,
+            min_amount,
+            enforcement,
+            created_at: "2026-10-01T09:00:00+09:00".to_string(),
+            updated_at: "2026-10-01T09:00:00+09:00".to_string(),
+        }
+    }
+
+    fn check(amount: f64, category: &str, category_id: Option<i64>) -> ReceiptCheck<'_> {
+        ReceiptCheck {
+            amount,
+            category,
+            category_id,
+            has_receipt: false,
+        }
+    }
+
+    fn expense(id: i64, date: &str, receipt_url: Option<&str>) -> Expense {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "date": date,
+            "amount": 8000.0,
+            "category": "交際費",
+            "category_id": 4,
+            "description": null,
+            "receipt_url": receipt_url,
+            "created_at": format!("{date}T09:00:00+09:00"),
+            "updated_at": format!("{date}T09:00:00+09:00"),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_amount_boundary() {
+        let policies = [policy("交際費", None, 5000.0, ReceiptEnforcement::Hard)];
+
+        assert_eq!(
+            evaluate(&policies, &check(4999.99, "交際費", None), false),
+            ReceiptDecision::NotRequired
+        );
+        // 最低金額ちょうどの経費にも領収書を求める
+        assert!(matches!(
+            evaluate(&policies, &check(5000.0, "交際費", None), false),
+            ReceiptDecision::Rejected(_)
+        ));
+        assert!(matches!(
+            evaluate(&policies, &check(5000.01, "交際費", None), false),
+            ReceiptDecision::Rejected(_)
+        ));
+    }
+
+    #[test]
+    fn test_hard_and_soft_enforcement() {
+        let hard = [policy("交際費", None, 5000.0, ReceiptEnforcement::Hard)];
+        let soft = [policy("交際費", None, 5000.0, ReceiptEnforcement::Soft)];
+        let without_receipt = check(8000.0, "交際費", None);
+        let with_receipt = ReceiptCheck {
+            has_receipt: true,
+            ..without_receipt
+        };
+
+        // 後で添付することを明示すれば必須のルールでも保存できる
+        assert!(matches!(
+            evaluate(&hard, &without_receipt, true),
+            ReceiptDecision::MissingReceipt(_)
+        ));
+        assert!(matches!(
+            evaluate(&soft, &without_receipt, false),
+            ReceiptDecision::MissingReceipt(_)
+        ));
+        assert_eq!(
+            evaluate(&hard, &with_receipt, false),
+            ReceiptDecision::Satisfied
+        );
+
+        let error = evaluate(&hard, &without_receipt, false)
+            .into_result()
+            .unwrap_err();
+        assert!(error.to_string().contains("5,000円以上"));
+    }
+
+    #[test]
+    fn test_category_matching_after_rename() {
+        let policies = [policy("接待費", Some(4), 5000.0, ReceiptEnforcement::Hard)];
+
+        // カテゴリ名が変わってもIDが同じなら適用する
+        assert!(matches!(
+            evaluate(&policies, &check(8000.0, "交際費", Some(4)), false),
+            ReceiptDecision::Rejected(_)
+        ));
+        // 旧名称を別のカテゴリに付け直した場合は適用しない
+        assert_eq!(
+            evaluate(&policies, &check(8000.0, "接待費", Some(9)), false),
+            ReceiptDecision::NotRequired
+        );
+        // IDが分からない経費は名前で照合する
+        assert!(matches!(
+            evaluate(&policies, &check(8000.0, "接待費", None), false),
+            ReceiptDecision::Rejected(_)
+        ));
+        assert_eq!(
+            evaluate(&policies, &check(8000.0, "交際費", None), false),
+            ReceiptDecision::NotRequired
+        );
+    }
+
+    #[test]
+    fn test_hard_policy_takes_precedence() {
+        let policies = [
+            ReceiptPolicy {
+                id: 1,
+                ..policy("交際費", None, 3000.0, ReceiptEnforcement::Soft)
+            },
+            ReceiptPolicy {
+                id: 2,
+                ..policy("旧交際費", Some(4), 5000.0, ReceiptEnforcement::Hard)
+            },
+        ];
+        assert!(matches!(
+            evaluate(&policies, &check(4000.0, "交際費", Some(4)), false),
+            ReceiptDecision::MissingReceipt(ReceiptPolicy { id: 1, .. })
+        ));
+        assert!(matches!(
+            evaluate(&policies, &check(6000.0, "交際費", Some(4)), false),
+            ReceiptDecision::Rejected(ReceiptPolicy { id: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_policy_crud() {
+        let conn = create_test_connection();
+        let dto = CreateReceiptPolicyDto {
+            category: " 交際費 ".to_string(),
+            category_id: Some(4),
+            min_amount: 5000.0,
+            enforcement: ReceiptEnforcement::Hard,
+        };
+        let created = create_policy(&conn, "u1", &dto).unwrap();
+        assert_eq!(created.category, "交際費");
+        assert!(create_policy(&conn, "u1", &dto).is_err());
+        assert!(create_policy(
+            &conn,
+            "u1",
+            &CreateReceiptPolicyDto {
+                min_amount: -1.0,
+                category: "消耗品費".to_string(),
+                ..dto.clone()
+            }
+        )
+        .is_err());
+
+        let updated = update_policy(
+            &conn,
+            "u1",
+            created.id,
+            &UpdateReceiptPolicyDto {
+                enforcement: Some(ReceiptEnforcement::Soft),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(updated.enforcement, ReceiptEnforcement::Soft);
+        assert_eq!(updated.min_amount, 5000.0);
+
+        assert!(list_policies(&conn, "u2").unwrap().is_empty());
+        assert_eq!(list_policies(&conn, "u1").unwrap(), vec![updated]);
+        assert!(
+            update_policy(&conn, "u2", created.id, &UpdateReceiptPolicyDto::default()).is_err()
+        );
+
+        assert!(!delete_policy(&conn, "u2", created.id).unwrap());
+        assert!(delete_policy(&conn, "u1", created.id).unwrap());
+        assert!(list_policies(&conn, "u1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_missing_receipt_report_and_automatic_clear() {
+        let mut conn = create_test_connection();
+        let policy = create_policy(
+            &conn,
+            "u1",
+            &CreateReceiptPolicyDto {
+                category: "交際費".to_string(),
+                category_id: Some(4),
+                min_amount: 5000.0,
+                enforcement: ReceiptEnforcement::Soft,
+            },
+        )
+        .unwrap();
+        replace_all(
+            &mut conn,
+            "u1",
+            &[
+                expense(1, "2026-09-30", None),
+                expense(2, "2026-10-01", None),
+            ],
+            "rev-1",
+        )
+        .unwrap();
+        flag_missing_receipt(&conn, "u1", 1, &policy, false).unwrap();
+        flag_missing_receipt(&conn, "u1", 2, &policy, true).unwrap();
+
+        let all = missing_receipt_expenses(&conn, "u1", None, None).unwrap();
+        assert_eq!(
+            all.iter().map(|e| e.expense.id).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+        assert!(all[0].followup_requested);
+        assert!(all[0].expense.missing_receipt);
+
+        let october = missing_receipt_expenses(&conn, "u1", Some("2026-10-01"), None).unwrap();
+        assert_eq!(october.len(), 1);
+        assert!(missing_receipt_expenses(&conn, "u1", Some("2026/10/01"), None).is_err());
+
+        // 領収書が添付されると記録が解除される
+        replace_all(
+            &mut conn,
+            "u1",
+            &[
+                expense(1, "2026-09-30", None),
+                expense(2, "2026-10-01", Some("https://receipts.example.com/2.jpg")),
+            ],
+            "rev-2",
+        )
+        .unwrap();
+        assert_eq!(clear_resolved_flags(&conn, "u1").unwrap(), 1);
+        assert_eq!(
+            flagged_expense_ids(&conn, "u1").unwrap(),
+            HashSet::from([1])
+        );
+
+        // ルールを削除すると記録も解除される
+        delete_policy(&conn, "u1", policy.id).unwrap();
+        assert!(flagged_expense_ids(&conn, "u1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_apply_decision() {
+        let conn = create_test_connection();
+        let soft = policy("交際費", None, 5000.0, ReceiptEnforcement::Soft);
+
+        assert!(apply_decision(
+            &conn,
+            "u1",
+            7,
+            &ReceiptDecision::MissingReceipt(soft),
+            false
+        )
+        .unwrap());
+        assert!(flagged_expense_ids(&conn, "u1").unwrap().contains(&7));
+
+        assert!(!apply_decision(&conn, "u1", 7, &ReceiptDecision::Satisfied, false).unwrap());
+        assert!(flagged_expense_ids(&conn, "u1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount(5000.0), "5,000");
+        assert_eq!(format_amount(1234567.5), "1,234,567.50");
+        assert_eq!(format_amount(999.0), "999");
+    }
+}
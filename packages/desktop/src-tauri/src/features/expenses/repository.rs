@@ -0,0 +1,1005 @@
+/// 経費データのリポジトリ
+///
+/// ローカルSQLiteの経費テーブルに対する検索処理を提供します。
+use crate::features::expenses::location::{bounding_box, distance_m, validate_nearby_query};
+use crate::features::expenses::models::{CategorySummary, Expense, ExpenseFilter, NearbyExpense};
+use crate::features::expenses::sync;
+use crate::features::migrations::auto_migration::executor::MigrationExecutorTrait;
+use crate::features::migrations::auto_migration::models::{
+    ExecutableMigrationDefinition, MigrationDefinition,
+};
+use crate::features::migrations::auto_migration::registry::MigrationRegistry;
+use crate::features::receipts::exif::GpsCoordinates;
+use crate::shared::database::connection::check_column_exists;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::utils::{round_to_currency_precision, validate_date};
+use rusqlite::{params, params_from_iter, Connection, Row, ToSql};
+use std::collections::HashMap;
+
+/// 経費テーブルに追加する税関連カラム（カラム名, 定義）
+const EXPENSE_TAX_COLUMNS: &[(&str, &str)] = &[("tax_rate", "REAL"), ("tax_amount", "REAL")];
+
+/// 税関連カラム追加マイグレーションのチェックサム計算用の内容
+const EXPENSE_TAX_COLUMNS_SQL: &str = "
+    ALTER TABLE expenses ADD COLUMN tax_rate REAL;
+    ALTER TABLE expenses ADD COLUMN tax_amount REAL;
+";
+
+/// 経費の税関連カラム追加マイグレーション実行器
+pub struct ExpenseTaxColumnsMigration;
+
+impl MigrationExecutorTrait for ExpenseTaxColumnsMigration {
+    fn name(&self) -> &str {
+        "008_add_expense_tax_columns"
+    }
+
+    fn execute(&self, conn: &Connection) -> Result<(), String> {
+        add_expense_tax_columns(conn).map_err(|e| format!("税関連カラム追加エラー: {e}"))
+    }
+}
+
+/// 経費の税関連カラム用マイグレーション定義を取得する
+///
+/// # 戻り値
+/// 実行可能なマイグレーション定義
+pub fn get_expense_tax_columns_definition() -> ExecutableMigrationDefinition {
+    let definition = MigrationDefinition::new(
+        "008_add_expense_tax_columns".to_string(),
+        "3.4.0".to_string(),
+        "経費テーブルへの税率・税額カラムの追加".to_string(),
+        MigrationRegistry::calculate_checksum(EXPENSE_TAX_COLUMNS_SQL),
+    );
+
+    ExecutableMigrationDefinition::new(definition, Box::new(ExpenseTaxColumnsMigration))
+}
+
+/// 経費テーブルに税率・税額カラムを追加する
+///
+/// SQLiteの`ALTER TABLE ADD COLUMN`は再実行できないため、
+/// 存在しないカラムのみ追加します。
+///
+/// # 引数
+/// * `conn` - データベース接続
+pub fn add_expense_tax_columns(conn: &Connection) -> AppResult<()> {
+    for (column, definition) in EXPENSE_TAX_COLUMNS {
+        if !check_column_exists(conn, "expenses", column) {
+            conn.execute(
+                &format!("ALTER TABLE expenses ADD COLUMN {column} {definition}"),
+                [],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// 経費テーブル（別名e）から取得するカラム
+///
+/// 一覧表示で行ごとにカテゴリを引き直さずに済むよう、
+/// カテゴリ（別名c）の色・アイコンも合わせて取得します。
+const EXPENSE_COLUMNS: &str = "e.id, e.date, e.amount, e.category, e.description, e.receipt_url, \
+     e.created_at, e.updated_at, c.id, c.color, c.icon, e.tax_rate, e.tax_amount, \
+     e.latitude, e.longitude, e.location_opt_in, e.merchant_id";
+
+/// 経費とカテゴリの結合（カテゴリが存在しない経費も含める）
+const EXPENSE_FROM: &str = "expenses e LEFT JOIN categories c ON c.name = e.category";
+
+/// 行データを経費モデルに変換する
+fn map_expense_row(row: &Row) -> rusqlite::Result<Expense> {
+    let category_id: Option<i64> = row.get(8)?;
+    Ok(Expense {
+        id: row.get(0)?,
+        date: row.get(1)?,
+        amount: row.get(2)?,
+        category: row.get(3)?,
+        category_id,
+        description: row.get(4)?,
+        receipt_url: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+        category_color: row.get(9)?,
+        category_icon: row.get(10)?,
+        unknown_category: category_id.is_none(),
+        tax_rate: row.get(11)?,
+        tax_amount: row.get(12)?,
+        latitude: row.get(13)?,
+        longitude: row.get(14)?,
+        location_opt_in: row.get::<_, Option<bool>>(15)?.unwrap_or(false),
+        merchant_id: row.get(16)?,
+        missing_receipt: false,
+    })
+}
+
+/// 指定地点の近くで発生した経費を検索する
+///
+/// 位置情報を保存した経費（`expense_locations`）を経費ミラーと結合して検索します。
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+/// * `latitude` - 緯度
+/// * `longitude` - 経度
+/// * `radius_m` - 半径（メートル）
+///
+/// # 戻り値
+/// 半径内のユーザーの経費一覧（近い順）
+pub fn find_expenses_near(
+    conn: &Connection,
+    user_id: &str,
+    latitude: f64,
+    longitude: f64,
+    radius_m: f64,
+) -> AppResult<Vec<NearbyExpense>> {
+    validate_nearby_query(latitude, longitude, radius_m)?;
+    let center = GpsCoordinates {
+        latitude,
+        longitude,
+    };
+    let (min_lat, max_lat, min_lon, max_lon) = bounding_box(&center, radius_m);
+
+    // 緯度・経度の範囲で絞り込んだうえで、正確な距離で判定する
+    // （日付変更線をまたぐ範囲は経度の条件を外す）
+    let crosses_antimeridian = min_lon < -180.0 || max_lon > 180.0;
+    let mut sql = "SELECT m.payload, l.latitude, l.longitude
+         FROM expense_locations l
+         JOIN expense_mirror m ON m.user_id = l.user_id AND m.id = l.expense_id
+         WHERE l.user_id = ?1 AND l.latitude BETWEEN ?2 AND ?3"
+        .to_string();
+    let mut params: Vec<Box<dyn ToSql>> = vec![
+        Box::new(user_id.to_string()),
+        Box::new(min_lat),
+        Box::new(max_lat),
+    ];
+    if !crosses_antimeridian {
+        sql.push_str(" AND l.longitude BETWEEN ?4 AND ?5");
+        params.push(Box::new(min_lon));
+        params.push(Box::new(max_lon));
+    }
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt
+        .query_map(params_from_iter(params.iter()), |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                GpsCoordinates {
+                    latitude: row.get(1)?,
+                    longitude: row.get(2)?,
+                },
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut nearby = Vec::new();
+    for (payload, location) in rows {
+        let distance_m = distance_m(&center, &location);
+        if distance_m > radius_m {
+            continue;
+        }
+        let mut expense: Expense = serde_json::from_str(&payload)?;
+        expense.latitude = Some(location.latitude);
+        expense.longitude = Some(location.longitude);
+        expense.location_opt_in = true;
+        nearby.push(NearbyExpense {
+            expense,
+            distance_m,
+        });
+    }
+    nearby.sort_by(|a, b| a.distance_m.total_cmp(&b.distance_m));
+
+    Ok(nearby)
+}
+
+/// 検索条件に一致する経費を検索する
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+/// * `filter` - 検索条件
+///
+/// # 戻り値
+/// ユーザーの経費のうち条件に一致するもの（日付の新しい順）
+pub fn find_expenses(
+    conn: &Connection,
+    user_id: &str,
+    filter: &ExpenseFilter,
+) -> AppResult<Vec<Expense>> {
+    filter.validate()?;
+
+    let mut sql = format!("SELECT {EXPENSE_COLUMNS} FROM {EXPENSE_FROM} WHERE e.user_id = ?");
+    let mut params: Vec<Box<dyn ToSql>> = vec![Box::new(user_id.to_string())];
+
+    if let Some(start_date) = &filter.start_date {
+        sql.push_str(" AND e.date >= ?");
+        params.push(Box::new(start_date.clone()));
+    }
+
+    if let Some(end_date) = &filter.end_date {
+        sql.push_str(" AND e.date <= ?");
+        params.push(Box::new(end_date.clone()));
+    }
+
+    if let Some(category) = &filter.category {
+        sql.push_str(" AND e.category = ?");
+        params.push(Box::new(category.clone()));
+    }
+
+    if let Some(search) = filter.search_term() {
+        // LIKEの特殊文字をエスケープして部分一致させる
+        let pattern = format!(
+            "%{}%",
+            search
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        );
+        sql.push_str(" AND (e.description LIKE ? ESCAPE '\\' OR e.category LIKE ? ESCAPE '\\')");
+        params.push(Box::new(pattern.clone()));
+        params.push(Box::new(pattern));
+    }
+
+    match filter.has_receipt {
+        Some(true) => sql.push_str(" AND (e.receipt_url IS NOT NULL AND e.receipt_url != '')"),
+        Some(false) => sql.push_str(" AND (e.receipt_url IS NULL OR e.receipt_url = '')"),
+        None => {}
+    }
+
+    if let Some(min_amount) = filter.min_amount {
+        sql.push_str(" AND e.amount >= ?");
+        params.push(Box::new(min_amount));
+    }
+
+    if let Some(max_amount) = filter.max_amount {
+        sql.push_str(" AND e.amount <= ?");
+        params.push(Box::new(max_amount));
+    }
+
+    sql.push_str(" ORDER BY e.date DESC, e.id DESC");
+
+    let mut stmt = conn.prepare(&sql)?;
+    let expenses = stmt
+        .query_map(params_from_iter(params.iter()), map_expense_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(expenses)
+}
+
+/// ミラー（`expense_mirror`）の経費を取得する
+///
+/// `sql`は`payload`の列だけを選択してください。
+fn query_mirrored<P: rusqlite::Params>(
+    conn: &Connection,
+    sql: &str,
+    params: P,
+) -> AppResult<Vec<Expense>> {
+    let mut stmt = conn.prepare(sql)?;
+    let payloads = stmt
+        .query_map(params, |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    payloads
+        .iter()
+        .map(|payload| serde_json::from_str(payload).map_err(AppError::from))
+        .collect()
+}
+
+/// 期間内の経費を検索する
+///
+/// API Server版で一覧に表示している経費のミラー（`expense_mirror`）を対象に、
+/// `idx_expense_mirror_date`の範囲検索で取得します。
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+/// * `start_date` - 開始日（YYYY-MM-DD形式、この日を含む）
+/// * `end_date` - 終了日（YYYY-MM-DD形式、この日を含む）
+///
+/// # 戻り値
+/// 期間内のユーザーの経費一覧（日付の新しい順）、または日付が不正・開始日が終了日より後の場合はバリデーションエラー
+pub fn find_by_date_range(
+    conn: &Connection,
+    user_id: &str,
+    start_date: &str,
+    end_date: &str,
+) -> AppResult<Vec<Expense>> {
+    validate_date(start_date)?;
+    validate_date(end_date)?;
+    if start_date > end_date {
+        return Err(AppError::validation(
+            "開始日は終了日以前の日付を指定してください",
+        ));
+    }
+
+    query_mirrored(
+        conn,
+        "SELECT payload FROM expense_mirror
+         WHERE user_id = ?1 AND date BETWEEN ?2 AND ?3
+         ORDER BY date DESC, id DESC",
+        params![user_id, start_date, end_date],
+    )
+}
+
+/// 領収書が添付されていない経費を検索する
+///
+/// 領収書の添付ルールで記録した未添付の経費（`get_missing_receipt_expenses`）と同じく、
+/// API Server版で一覧に表示している経費のミラー（`expense_mirror`）を対象にします。
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+/// * `filter` - 検索条件（領収書の有無は無視されます）
+///
+/// # 戻り値
+/// ユーザーの経費のうち領収書URLが未設定のもの（日付の新しい順）
+pub fn find_expenses_without_receipts(
+    conn: &Connection,
+    user_id: &str,
+    filter: &ExpenseFilter,
+) -> AppResult<Vec<Expense>> {
+    let filter = filter.clone().with_has_receipt(false);
+    filter.validate()?;
+    sync::list_mirrored(conn, user_id, None, &filter)
+}
+
+/// カテゴリ別の経費件数と合計金額を取得する
+///
+/// API Server版で一覧に表示している経費のミラー（`expense_mirror`）を集計します。
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+/// * `year` - 対象年（Noneの場合は全期間）
+/// * `month` - 対象月（1〜12、Noneの場合は年全体）
+///
+/// # 戻り値
+/// ユーザーの経費のカテゴリ名をキーとした (件数, 合計金額) のマップ
+pub fn get_expense_count_by_category(
+    conn: &Connection,
+    user_id: &str,
+    year: Option<i32>,
+    month: Option<u32>,
+) -> AppResult<HashMap<String, (i64, f64)>> {
+    let mut sql = "SELECT json_extract(payload, '$.category') AS category, COUNT(*), \
+                   COALESCE(SUM(json_extract(payload, '$.amount')), 0) \
+                   FROM expense_mirror WHERE user_id = ?"
+        .to_string();
+    let mut params: Vec<Box<dyn ToSql>> = vec![Box::new(user_id.to_string())];
+
+    if let Some(month) = month {
+        if !(1..=12).contains(&month) {
+            return Err(AppError::validation("月は1〜12の範囲で指定してください"));
+        }
+    }
+
+    match (year, month) {
+        (Some(year), Some(month)) => {
+            sql.push_str(" AND date LIKE ?");
+            params.push(Box::new(format!("{year:04}-{month:02}-%")));
+        }
+        (Some(year), None) => {
+            sql.push_str(" AND date LIKE ?");
+            params.push(Box::new(format!("{year:04}-%")));
+        }
+        (None, Some(month)) => {
+            sql.push_str(" AND substr(date, 6, 2) = ?");
+            params.push(Box::new(format!("{month:02}")));
+        }
+        (None, None) => {}
+    }
+
+    sql.push_str(" GROUP BY category");
+
+    let mut stmt = conn.prepare(&sql)?;
+    let summary = stmt
+        .query_map(params_from_iter(params.iter()), |row| {
+            Ok((row.get::<_, String>(0)?, (row.get(1)?, row.get(2)?)))
+        })?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+
+    Ok(summary)
+}
+
+/// 月別のカテゴリごとの件数・合計金額・割合を取得する
+///
+/// API Server版で一覧に表示している経費のミラー（`expense_mirror`）を集計します。
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+/// * `month` - 対象月（YYYY-MM形式、呼び出し側で検証済み）
+///
+/// # 戻り値
+/// ユーザーの経費のカテゴリごとの集計（合計金額の多い順）。経費がない月は空
+pub fn find_monthly_summary(
+    conn: &Connection,
+    user_id: &str,
+    month: &str,
+) -> AppResult<Vec<CategorySummary>> {
+    let mut stmt = conn.prepare(
+        "SELECT json_extract(payload, '$.category') AS category,
+                SUM(json_extract(payload, '$.amount')) AS total, COUNT(*)
+         FROM expense_mirror
+         WHERE user_id = ?1 AND date BETWEEN ?2 AND ?3
+         GROUP BY category
+         ORDER BY total DESC, category ASC",
+    )?;
+    let mut summary = stmt
+        .query_map(
+            params![user_id, format!("{month}-01"), format!("{month}-31")],
+            |row| {
+                Ok(CategorySummary {
+                    category: row.get(0)?,
+                    total_amount: round_to_currency_precision(row.get(1)?),
+                    expense_count: row.get::<_, i64>(2)? as usize,
+                    percentage_of_total: 0.0,
+                })
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // 月の合計金額が確定してから割合を計算する
+    let total: f64 = summary.iter().map(|category| category.total_amount).sum();
+    if total > 0.0 {
+        for category in &mut summary {
+            category.percentage_of_total =
+                round_to_currency_precision(category.total_amount / total * 100.0);
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::expenses::location::{
+        save_expense_location, ExpenseLocationMigration, ExpenseLocationsSchemaMigration,
+    };
+    use crate::features::expenses::merchants::MerchantsSchemaMigration;
+    use crate::shared::database::connection::create_in_memory_connection;
+
+    const USER_ID: &str = "user_a";
+    const OTHER_USER_ID: &str = "user_b";
+
+    /// ユーザーID・税関連・位置情報・店舗のカラムまで追加したテスト用データベースを作成する
+    fn create_test_connection() -> Connection {
+        let conn = create_in_memory_connection().unwrap();
+        // 認証機能のマイグレーション後と同じくユーザーIDを持たせる
+        conn.execute("ALTER TABLE expenses ADD COLUMN user_id TEXT", [])
+            .unwrap();
+        ExpenseTaxColumnsMigration.execute(&conn).unwrap();
+        ExpenseLocationMigration.execute(&conn).unwrap();
+        MerchantsSchemaMigration.execute(&conn).unwrap();
+        sync::ExpenseMirrorSchemaMigration.execute(&conn).unwrap();
+        ExpenseLocationsSchemaMigration.execute(&conn).unwrap();
+        conn
+    }
+
+    fn insert_expense(conn: &Connection, date: &str, category: &str, receipt_url: Option<&str>) {
+        insert_expense_with_amount(conn, date, category, 1000.0, receipt_url);
+    }
+
+    fn insert_expense_with_amount(
+        conn: &Connection,
+        date: &str,
+        category: &str,
+        amount: f64,
+        receipt_url: Option<&str>,
+    ) {
+        insert_user_expense(conn, USER_ID, date, category, amount, receipt_url);
+    }
+
+    fn insert_user_expense(
+        conn: &Connection,
+        user_id: &str,
+        date: &str,
+        category: &str,
+        amount: f64,
+        receipt_url: Option<&str>,
+    ) {
+        conn.execute(
+            "INSERT INTO expenses (date, amount, category, description, receipt_url, user_id, created_at, updated_at)
+             VALUES (?1, ?2, ?3, NULL, ?4, ?5, '2024-01-01T00:00:00+09:00', '2024-01-01T00:00:00+09:00')",
+            rusqlite::params![date, amount, category, receipt_url, user_id],
+        )
+        .unwrap();
+    }
+
+    /// APIサーバーから同期した経費としてミラーに保存する
+    fn mirror_user_expense(
+        conn: &Connection,
+        user_id: &str,
+        date: &str,
+        category: &str,
+        amount: f64,
+        receipt_url: Option<&str>,
+    ) -> i64 {
+        let id: i64 = conn
+            .query_row(
+                "SELECT COALESCE(MAX(id), 0) + 1 FROM expense_mirror",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let expense: Expense = serde_json::from_value(serde_json::json!({
+            "id": id,
+            "date": date,
+            "amount": amount,
+            "category": category,
+            "description": null,
+            "receipt_url": receipt_url,
+            "created_at": "2024-01-01T00:00:00+09:00",
+            "updated_at": "2024-01-01T00:00:00+09:00",
+        }))
+        .unwrap();
+        sync::upsert_mirrored(conn, user_id, &expense).unwrap();
+        id
+    }
+
+    fn mirror_expense(conn: &Connection, date: &str, category: &str, amount: f64) -> i64 {
+        mirror_user_expense(conn, USER_ID, date, category, amount, None)
+    }
+
+    #[test]
+    fn test_find_by_date_range() {
+        let conn = create_test_connection();
+        mirror_expense(&conn, "2024-01-31", "交通費", 1000.0);
+        mirror_expense(&conn, "2024-02-01", "交通費", 1000.0);
+        mirror_expense(&conn, "2024-02-15", "飲食費", 1000.0);
+        mirror_expense(&conn, "2024-02-29", "飲食費", 1000.0);
+        mirror_expense(&conn, "2024-03-01", "交通費", 1000.0);
+
+        // 開始日・終了日を含む
+        let dates: Vec<String> = find_by_date_range(&conn, USER_ID, "2024-02-01", "2024-02-29")
+            .unwrap()
+            .into_iter()
+            .map(|e| e.date)
+            .collect();
+        assert_eq!(dates, ["2024-02-29", "2024-02-15", "2024-02-01"]);
+
+        let single_day = find_by_date_range(&conn, USER_ID, "2024-01-31", "2024-01-31").unwrap();
+        assert_eq!(single_day.len(), 1);
+
+        assert!(
+            find_by_date_range(&conn, USER_ID, "2023-01-01", "2023-12-31")
+                .unwrap()
+                .is_empty()
+        );
+
+        // 他のユーザーの経費は含めない
+        mirror_user_expense(&conn, OTHER_USER_ID, "2024-02-10", "交通費", 1000.0, None);
+        assert_eq!(
+            find_by_date_range(&conn, USER_ID, "2024-02-01", "2024-02-29")
+                .unwrap()
+                .len(),
+            3
+        );
+        let other = find_by_date_range(&conn, OTHER_USER_ID, "2024-02-01", "2024-02-29").unwrap();
+        assert_eq!(other.len(), 1);
+    }
+
+    #[test]
+    fn test_find_by_date_range_invalid() {
+        let conn = create_test_connection();
+
+        assert!(matches!(
+            find_by_date_range(&conn, USER_ID, "2024-03-01", "2024-02-01"),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            find_by_date_range(&conn, USER_ID, "2024/02/01", "2024-02-29"),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            find_by_date_range(&conn, USER_ID, "2024-02-01", "2024-02-30"),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_find_by_date_range_uses_date_index() {
+        let conn = create_test_connection();
+
+        let plan: Vec<String> = conn
+            .prepare(
+                "EXPLAIN QUERY PLAN SELECT payload FROM expense_mirror
+                 WHERE user_id = ?1 AND date BETWEEN ?2 AND ?3
+                 ORDER BY date DESC, id DESC",
+            )
+            .unwrap()
+            .query_map(params![USER_ID, "2024-02-01", "2024-02-29"], |row| {
+                row.get(3)
+            })
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert!(
+            plan.iter()
+                .any(|step| step.contains("idx_expense_mirror_date")),
+            "{plan:?}"
+        );
+    }
+
+    #[test]
+    fn test_find_expenses_without_receipts() {
+        let conn = create_test_connection();
+        mirror_user_expense(&conn, USER_ID, "2024-01-10", "交通費", 500.0, None);
+        mirror_user_expense(&conn, USER_ID, "2024-01-20", "交通費", 1000.0, Some(""));
+        mirror_user_expense(
+            &conn,
+            USER_ID,
+            "2024-01-25",
+            "交通費",
+            1500.0,
+            Some("https://example.com/receipt.png"),
+        );
+        mirror_user_expense(&conn, USER_ID, "2024-02-05", "飲食費", 3000.0, None);
+        // 他のユーザーの経費は含めない
+        mirror_user_expense(&conn, OTHER_USER_ID, "2024-01-11", "交通費", 2000.0, None);
+
+        let all = find_expenses_without_receipts(&conn, USER_ID, &ExpenseFilter::new()).unwrap();
+        assert_eq!(all.len(), 3);
+        // 日付の新しい順
+        assert_eq!(all[0].date, "2024-02-05");
+
+        let transport = find_expenses_without_receipts(
+            &conn,
+            USER_ID,
+            &ExpenseFilter::new().with_category("交通費"),
+        )
+        .unwrap();
+        assert_eq!(transport.len(), 2);
+
+        let january = find_expenses_without_receipts(
+            &conn,
+            USER_ID,
+            &ExpenseFilter::new().with_date_range(Some("2024-01-01"), Some("2024-01-31")),
+        )
+        .unwrap();
+        assert_eq!(january.len(), 2);
+        assert!(january.iter().all(|e| e.date.starts_with("2024-01")));
+
+        // 領収書なし条件は上書きされる
+        let without = find_expenses_without_receipts(
+            &conn,
+            USER_ID,
+            &ExpenseFilter::new()
+                .with_category("交通費")
+                .with_amount_range(Some(100.0), Some(600.0))
+                .with_has_receipt(true),
+        )
+        .unwrap();
+        assert_eq!(without.len(), 1);
+        assert_eq!(without[0].amount, 500.0);
+
+        let other =
+            find_expenses_without_receipts(&conn, OTHER_USER_ID, &ExpenseFilter::new()).unwrap();
+        assert_eq!(other.len(), 1);
+        assert_eq!(other[0].amount, 2000.0);
+    }
+
+    #[test]
+    fn test_find_expenses_without_receipts_invalid_range() {
+        let conn = create_test_connection();
+
+        assert!(find_expenses_without_receipts(
+            &conn,
+            USER_ID,
+            &ExpenseFilter::new().with_date_range(Some("2024/01/01"), None::<String>)
+        )
+        .is_err());
+        assert!(find_expenses_without_receipts(
+            &conn,
+            USER_ID,
+            &ExpenseFilter::new().with_date_range(Some("2024-02-01"), Some("2024-01-01"))
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_find_expenses_with_filter() {
+        let conn = create_test_connection();
+        insert_expense_with_amount(&conn, "2024-01-10", "交通費", 500.0, None);
+        insert_expense_with_amount(
+            &conn,
+            "2024-01-15",
+            "交通費",
+            1500.0,
+            Some("https://example.com/receipt.png"),
+        );
+        insert_expense_with_amount(&conn, "2024-01-20", "飲食費", 3000.0, None);
+        conn.execute(
+            "UPDATE expenses SET description = '100%オレンジジュース' WHERE category = '飲食費'",
+            [],
+        )
+        .unwrap();
+
+        assert_eq!(
+            find_expenses(&conn, USER_ID, &ExpenseFilter::new())
+                .unwrap()
+                .len(),
+            3
+        );
+
+        let with_receipt =
+            find_expenses(&conn, USER_ID, &ExpenseFilter::new().with_has_receipt(true)).unwrap();
+        assert_eq!(with_receipt.len(), 1);
+        assert_eq!(with_receipt[0].amount, 1500.0);
+
+        let by_amount = find_expenses(
+            &conn,
+            USER_ID,
+            &ExpenseFilter::new().with_amount_range(Some(1000.0), Some(2000.0)),
+        )
+        .unwrap();
+        assert_eq!(by_amount.len(), 1);
+
+        // LIKEの特殊文字はそのまま検索される
+        let by_search =
+            find_expenses(&conn, USER_ID, &ExpenseFilter::new().with_search("100%")).unwrap();
+        assert_eq!(by_search.len(), 1);
+        assert_eq!(by_search[0].category, "飲食費");
+        assert!(
+            find_expenses(&conn, USER_ID, &ExpenseFilter::new().with_search("0%オ_"))
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            find_expenses(&conn, USER_ID, &ExpenseFilter::new().with_search("交通"))
+                .unwrap()
+                .len(),
+            2
+        );
+
+        assert!(find_expenses(
+            &conn,
+            USER_ID,
+            &ExpenseFilter::new().with_amount_range(Some(2000.0), Some(1000.0))
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_find_expenses_is_scoped_to_user() {
+        let conn = create_test_connection();
+        insert_expense(&conn, "2024-01-10", "交通費", None);
+        insert_user_expense(&conn, OTHER_USER_ID, "2024-01-11", "交通費", 2000.0, None);
+
+        let own = find_expenses(&conn, USER_ID, &ExpenseFilter::new()).unwrap();
+        assert_eq!(own.len(), 1);
+        assert_eq!(own[0].date, "2024-01-10");
+
+        let other = find_expenses(&conn, OTHER_USER_ID, &ExpenseFilter::new()).unwrap();
+        assert_eq!(other.len(), 1);
+        assert_eq!(other[0].amount, 2000.0);
+
+        assert!(find_expenses(&conn, "user_c", &ExpenseFilter::new())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_get_expense_count_by_category() {
+        let conn = create_test_connection();
+        mirror_expense(&conn, "2024-01-10", "交通費", 500.0);
+        mirror_expense(&conn, "2024-01-15", "交通費", 1500.0);
+        mirror_expense(&conn, "2024-01-20", "飲食費", 3000.0);
+        mirror_expense(&conn, "2024-02-01", "交通費", 800.0);
+        mirror_expense(&conn, "2023-01-05", "通信費", 5000.0);
+
+        let all = get_expense_count_by_category(&conn, USER_ID, None, None).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all["交通費"], (3, 2800.0));
+
+        let january = get_expense_count_by_category(&conn, USER_ID, Some(2024), Some(1)).unwrap();
+        assert_eq!(january.len(), 2);
+        assert_eq!(january["交通費"], (2, 2000.0));
+        assert_eq!(january["飲食費"], (1, 3000.0));
+
+        let year_2024 = get_expense_count_by_category(&conn, USER_ID, Some(2024), None).unwrap();
+        assert!(!year_2024.contains_key("通信費"));
+
+        let every_january = get_expense_count_by_category(&conn, USER_ID, None, Some(1)).unwrap();
+        assert_eq!(every_january["通信費"], (1, 5000.0));
+
+        assert!(get_expense_count_by_category(&conn, USER_ID, Some(2024), Some(13)).is_err());
+
+        // 他のユーザーの経費は集計しない
+        mirror_user_expense(&conn, OTHER_USER_ID, "2024-01-12", "交通費", 700.0, None);
+        let january = get_expense_count_by_category(&conn, USER_ID, Some(2024), Some(1)).unwrap();
+        assert_eq!(january["交通費"], (2, 2000.0));
+        let other = get_expense_count_by_category(&conn, OTHER_USER_ID, None, None).unwrap();
+        assert_eq!(other.len(), 1);
+        assert_eq!(other["交通費"], (1, 700.0));
+    }
+
+    #[test]
+    fn test_find_monthly_summary_without_expenses() {
+        let conn = create_test_connection();
+        mirror_expense(&conn, "2024-01-31", "交通費", 1000.0);
+        mirror_expense(&conn, "2024-03-01", "交通費", 1000.0);
+
+        assert!(find_monthly_summary(&conn, USER_ID, "2024-02")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_find_monthly_summary_single_category() {
+        let conn = create_test_connection();
+        mirror_expense(&conn, "2024-02-01", "交通費", 1200.0);
+        mirror_expense(&conn, "2024-02-29", "交通費", 800.0);
+        mirror_expense(&conn, "2024-03-01", "飲食費", 5000.0);
+
+        assert_eq!(
+            find_monthly_summary(&conn, USER_ID, "2024-02").unwrap(),
+            vec![CategorySummary {
+                category: "交通費".to_string(),
+                total_amount: 2000.0,
+                expense_count: 2,
+                percentage_of_total: 100.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_find_monthly_summary_multiple_categories() {
+        let conn = create_test_connection();
+        mirror_expense(&conn, "2024-04-01", "交通費", 1000.0);
+        mirror_expense(&conn, "2024-04-10", "飲食費", 1500.0);
+        mirror_expense(&conn, "2024-04-20", "飲食費", 500.0);
+        mirror_expense(&conn, "2024-04-30", "消耗品費", 1000.0);
+        mirror_expense(&conn, "2024-05-01", "交通費", 9999.0);
+        // 他のユーザーの経費は集計しない
+        mirror_user_expense(&conn, OTHER_USER_ID, "2024-04-15", "交通費", 9999.0, None);
+
+        let summary = find_monthly_summary(&conn, USER_ID, "2024-04").unwrap();
+
+        // 合計金額の多い順（同額の場合はカテゴリ名順）
+        let rows: Vec<(&str, f64, usize, f64)> = summary
+            .iter()
+            .map(|c| {
+                (
+                    c.category.as_str(),
+                    c.total_amount,
+                    c.expense_count,
+                    c.percentage_of_total,
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("飲食費", 2000.0, 2, 50.0),
+                ("交通費", 1000.0, 1, 25.0),
+                ("消耗品費", 1000.0, 1, 25.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_monthly_summary_rounds_percentage() {
+        let conn = create_test_connection();
+        for category in ["交通費", "飲食費", "消耗品費"] {
+            mirror_expense(&conn, "2024-06-15", category, 1000.0);
+        }
+
+        let percentages: Vec<f64> = find_monthly_summary(&conn, USER_ID, "2024-06")
+            .unwrap()
+            .iter()
+            .map(|c| c.percentage_of_total)
+            .collect();
+        assert_eq!(percentages, vec![33.33, 33.33, 33.33]);
+    }
+
+    #[test]
+    fn test_find_expenses_includes_category_style() {
+        let conn = create_test_connection();
+        conn.execute(
+            "INSERT OR REPLACE INTO categories (name, color, icon) VALUES ('交通費', '#4ECDC4', '🚗')",
+            [],
+        )
+        .unwrap();
+        insert_expense(&conn, "2024-01-10", "交通費", None);
+        // categoriesテーブルから削除（名称変更）されたカテゴリ
+        insert_expense(&conn, "2024-01-20", "旧カテゴリ", None);
+
+        let expenses = find_expenses(&conn, USER_ID, &ExpenseFilter::new()).unwrap();
+        assert_eq!(expenses.len(), 2);
+
+        let unknown = &expenses[0];
+        assert_eq!(unknown.category, "旧カテゴリ");
+        assert!(unknown.unknown_category);
+        assert_eq!(unknown.category_id, None);
+        assert_eq!(unknown.category_color, None);
+        assert_eq!(unknown.category_icon, None);
+
+        let known = &expenses[1];
+        assert!(!known.unknown_category);
+        assert!(known.category_id.is_some());
+        assert_eq!(known.category_color.as_deref(), Some("#4ECDC4"));
+        assert_eq!(known.category_icon.as_deref(), Some("🚗"));
+
+        let json = serde_json::to_value(unknown).unwrap();
+        assert_eq!(json["unknown_category"], true);
+        assert!(json["category_color"].is_null());
+    }
+
+    #[test]
+    fn test_expense_tax_columns_migration() {
+        let conn = create_in_memory_connection().unwrap();
+        conn.execute("ALTER TABLE expenses ADD COLUMN user_id TEXT", [])
+            .unwrap();
+        assert!(!check_column_exists(&conn, "expenses", "tax_rate"));
+
+        ExpenseTaxColumnsMigration.execute(&conn).unwrap();
+        // 2回目も失敗しない
+        ExpenseTaxColumnsMigration.execute(&conn).unwrap();
+        assert!(check_column_exists(&conn, "expenses", "tax_rate"));
+        assert!(check_column_exists(&conn, "expenses", "tax_amount"));
+
+        // 既存の経費は税情報なしとして読み込める
+        insert_expense_with_amount(&conn, "2024-01-10", "消耗品費", 1100.0, None);
+        conn.execute(
+            "INSERT INTO expenses (date, amount, category, user_id, created_at, updated_at, tax_rate, tax_amount)
+             VALUES ('2024-01-11', 1080.0, '飲食費', ?1, '2024-01-11', '2024-01-11', 0.08, 80.0)",
+            params![USER_ID],
+        )
+        .unwrap();
+
+        ExpenseLocationMigration.execute(&conn).unwrap();
+        MerchantsSchemaMigration.execute(&conn).unwrap();
+        let expenses = find_expenses(&conn, USER_ID, &ExpenseFilter::new()).unwrap();
+        assert_eq!(expenses.len(), 2);
+        assert_eq!(expenses[0].tax_rate, Some(0.08));
+        assert_eq!(expenses[0].tax_amount, Some(80.0));
+        assert_eq!(expenses[0].pretax_amount(), 1000.0);
+        assert_eq!(expenses[1].tax_rate, None);
+        assert_eq!(expenses[1].pretax_amount(), 1100.0);
+    }
+
+    #[test]
+    fn test_find_expenses_near() {
+        let conn = create_test_connection();
+        for (category, location) in [
+            // 東京駅
+            ("交通費", Some((35.681_236, 139.767_125))),
+            // 新宿駅（約6.2km）
+            ("飲食費", Some((35.690_921, 139.700_258))),
+            // 大阪駅
+            ("宿泊費", Some((34.702_485, 135.495_951))),
+            ("消耗品費", None),
+        ] {
+            let id = mirror_user_expense(&conn, USER_ID, "2024-01-10", category, 1000.0, None);
+            if let Some((latitude, longitude)) = location {
+                let location = GpsCoordinates {
+                    latitude,
+                    longitude,
+                };
+                save_expense_location(&conn, USER_ID, id, &location).unwrap();
+            }
+        }
+
+        let near = find_expenses_near(&conn, USER_ID, 35.681, 139.767, 1_000.0).unwrap();
+        let categories: Vec<&str> = near.iter().map(|n| n.expense.category.as_str()).collect();
+        assert_eq!(categories, vec!["交通費"]);
+        assert!(near[0].expense.location_opt_in);
+        assert_eq!(near[0].expense.latitude, Some(35.681_236));
+
+        let near = find_expenses_near(&conn, USER_ID, 35.681, 139.767, 10_000.0).unwrap();
+        let categories: Vec<&str> = near.iter().map(|n| n.expense.category.as_str()).collect();
+        assert_eq!(categories, vec!["交通費", "飲食費"]);
+        assert!(near[0].distance_m < near[1].distance_m);
+
+        assert!(find_expenses_near(&conn, USER_ID, 35.681, 139.767, -1.0).is_err());
+        assert!(
+            find_expenses_near(&conn, OTHER_USER_ID, 35.681, 139.767, 10_000.0)
+                .unwrap()
+                .is_empty()
+        );
+
+        // 位置情報を含めない場合はシリアライズされない
+        let expense = near[0].expense.clone().without_location();
+        let json = serde_json::to_string(&expense).unwrap();
+        assert!(!json.contains("latitude"));
+        assert!(!expense.location_opt_in);
+    }
+}
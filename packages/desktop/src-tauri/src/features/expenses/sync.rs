@@ -0,0 +1,994 @@
+/// 経費の差分同期
+///
+/// API Server版では、取得した経費をローカルのミラーテーブルに保存し、一覧はミラーから読み込みます。
+/// 起動後の一覧表示はローカルから即座に行い、APIサーバーとは前回のリビジョン以降の変更だけをやり取りします。
+///
+/// # APIサーバーとの取り決め
+/// APIサーバー側の実装は`packages/api-server`の`routes/expenses.ts`と`utils/expense-sync.ts`です。
+/// - リビジョンはRFC3339形式（UTC）の時刻で、全件取得のレスポンスにも`revision`が含まれる
+/// - `GET /api/v1/expenses?since=<revision>`は、`changes.upserted`（作成・更新された経費）、
+///   `changes.deleted`（削除された経費のID）と新しい`revision`を返す
+/// - `since`のリビジョンが古すぎて差分を返せない場合は`full_resync_required: true`
+///   （またはエラーコード`SYNC_TOKEN_EXPIRED`）を返し、クライアントは全件を取得し直す
+/// - `since`に対応していないサーバーは通常の一覧（`expenses`）を返し、全件の置き換えとして扱う
+/// - `GET /api/v1/expenses/checksum`は`count`と`checksum`（`id:updated_at`をIDの昇順に
+///   改行区切りで並べた文字列のSHA-256）を返す。未対応の場合は全件から算出して比較する
+///
+/// 同期ではサーバーの内容を正とし、ローカルの変更は作成・更新・削除のAPI呼び出しの結果のみを反映します。
+use crate::features::expenses::models::{
+    Expense, ExpenseFilter, ExpensePage, ExpenseSortKey, GetExpensesQuery,
+};
+use crate::features::migrations::auto_migration::executor::MigrationExecutorTrait;
+use crate::features::migrations::auto_migration::models::{
+    ExecutableMigrationDefinition, MigrationDefinition,
+};
+use crate::features::migrations::auto_migration::registry::MigrationRegistry;
+use crate::shared::api_client::ApiClient;
+use crate::shared::database::lock_db;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::utils::{get_current_jst_timestamp, validate_month};
+use chrono::{DateTime, Duration, Utc};
+use log::{info, warn};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, ToSql};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::{Mutex, OnceLock};
+
+/// リビジョンが古すぎる場合にAPIサーバーが返すエラーコード
+const SYNC_TOKEN_EXPIRED_CODE: &str = "SYNC_TOKEN_EXPIRED";
+
+/// 整合性チェックの間隔（時間）
+const INTEGRITY_CHECK_INTERVAL_HOURS: i64 = 24;
+
+/// 経費ミラーマイグレーションのSQL
+const EXPENSE_MIRROR_SCHEMA_SQL: &str = "
+    CREATE TABLE IF NOT EXISTS expense_mirror (
+        user_id TEXT NOT NULL,
+        id INTEGER NOT NULL,
+        date TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        updated_at TEXT NOT NULL,
+        payload TEXT NOT NULL,
+        PRIMARY KEY (user_id, id)
+    );
+    CREATE INDEX IF NOT EXISTS idx_expense_mirror_date
+        ON expense_mirror(user_id, date);
+    CREATE TABLE IF NOT EXISTS expense_sync_state (
+        user_id TEXT PRIMARY KEY,
+        revision TEXT,
+        last_synced_at TEXT,
+        last_full_sync_at TEXT,
+        last_integrity_check_at TEXT
+    );
+";
+
+/// 経費ミラーテーブル作成マイグレーション実行器
+pub struct ExpenseMirrorSchemaMigration;
+
+impl MigrationExecutorTrait for ExpenseMirrorSchemaMigration {
+    fn name(&self) -> &str {
+        "012_create_expense_mirror"
+    }
+
+    fn execute(&self, conn: &Connection) -> Result<(), String> {
+        conn.execute_batch(EXPENSE_MIRROR_SCHEMA_SQL)
+            .map_err(|e| format!("expense_mirrorテーブル作成エラー: {e}"))
+    }
+}
+
+/// 経費ミラーテーブル用マイグレーション定義を取得する
+///
+/// # 戻り値
+/// 実行可能なマイグレーション定義
+pub fn get_expense_mirror_schema_definition() -> ExecutableMigrationDefinition {
+    let definition = MigrationDefinition::new(
+        "012_create_expense_mirror".to_string(),
+        "3.8.0".to_string(),
+        "API Server版の経費ミラーテーブルと同期状態テーブルの作成".to_string(),
+        MigrationRegistry::calculate_checksum(EXPENSE_MIRROR_SCHEMA_SQL),
+    );
+
+    ExecutableMigrationDefinition::new(definition, Box::new(ExpenseMirrorSchemaMigration))
+}
+
+/// APIサーバーが返す経費の変更
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExpenseChanges {
+    /// 作成・更新された経費
+    #[serde(default)]
+    pub upserted: Vec<Expense>,
+    /// 削除された経費のID
+    #[serde(default)]
+    pub deleted: Vec<i64>,
+}
+
+/// 経費の同期レスポンス
+///
+/// 差分に対応したサーバーは`changes`を、未対応のサーバーや全件取得では`expenses`を返します。
+#[derive(Debug, Deserialize)]
+struct SyncResponse {
+    #[serde(default)]
+    changes: Option<ExpenseChanges>,
+    #[serde(default)]
+    expenses: Option<Vec<Expense>>,
+    #[serde(default)]
+    revision: Option<String>,
+    #[serde(default)]
+    full_resync_required: bool,
+    timestamp: String,
+}
+
+/// 同期レスポンスの内容
+#[derive(Debug)]
+enum SyncPayload {
+    /// 前回のリビジョン以降の変更
+    Delta(ExpenseChanges, String),
+    /// 全件
+    Full(Vec<Expense>, String),
+    /// 全件の取得し直しが必要
+    ResyncRequired,
+}
+
+impl SyncResponse {
+    fn into_payload(self) -> AppResult<SyncPayload> {
+        if self.full_resync_required {
+            return Ok(SyncPayload::ResyncRequired);
+        }
+        // リビジョンを返さないサーバーは応答時刻をリビジョンとして使用する
+        let revision = self.revision.unwrap_or(self.timestamp);
+        match (self.changes, self.expenses) {
+            (Some(changes), _) => Ok(SyncPayload::Delta(changes, revision)),
+            (None, Some(expenses)) => Ok(SyncPayload::Full(expenses, revision)),
+            (None, None) => Err(AppError::ExternalService(
+                "経費の同期レスポンスに変更も一覧も含まれていません".to_string(),
+            )),
+        }
+    }
+}
+
+/// 同期の結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpenseSyncReport {
+    /// 全件を取得し直したかどうか
+    pub full_resync: bool,
+    /// 作成・更新した経費の件数
+    pub upserted: usize,
+    /// 削除した経費の件数
+    pub deleted: usize,
+    /// 同期後のミラーの経費の件数
+    pub total: usize,
+    /// 同期後のリビジョン
+    pub revision: String,
+    /// ミラーの内容が変わったかどうか（全件の置き換えで内容が同じ場合はfalse）
+    pub changed: bool,
+}
+
+/// 同期の状態
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExpenseSyncState {
+    /// 前回同期したリビジョン
+    pub revision: Option<String>,
+    /// 前回同期した日時
+    pub last_synced_at: Option<String>,
+    /// 前回全件を取得した日時
+    pub last_full_sync_at: Option<String>,
+    /// 前回整合性を確認した日時
+    pub last_integrity_check_at: Option<String>,
+}
+
+impl ExpenseSyncState {
+    /// 整合性チェックの時期かどうか
+    pub fn integrity_check_due(&self, now: DateTime<Utc>) -> bool {
+        self.last_integrity_check_at
+            .as_deref()
+            .and_then(|checked| DateTime::parse_from_rfc3339(checked).ok())
+            .is_none_or(|checked| {
+                now.signed_duration_since(checked)
+                    >= Duration::hours(INTEGRITY_CHECK_INTERVAL_HOURS)
+            })
+    }
+}
+
+/// 経費の件数とチェックサム
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpenseChecksum {
+    pub count: usize,
+    pub checksum: String,
+}
+
+/// 整合性チェックの結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpenseIntegrityReport {
+    /// ローカルのミラー
+    pub local: ExpenseChecksum,
+    /// APIサーバー
+    pub server: ExpenseChecksum,
+    /// 一致したかどうか
+    pub matches: bool,
+    /// 一致しなかったため全件を取得し直した結果
+    pub resync: Option<ExpenseSyncReport>,
+}
+
+/// 経費のIDと更新日時からチェックサムを算出する
+///
+/// IDの昇順に`id:updated_at`を改行区切りで並べた文字列のSHA-256です。
+pub fn checksum_of<'a>(entries: impl IntoIterator<Item = (i64, &'a str)>) -> ExpenseChecksum {
+    let mut entries: Vec<_> = entries.into_iter().collect();
+    entries.sort_unstable_by_key(|(id, _)| *id);
+
+    let mut hasher = Sha256::new();
+    for (id, updated_at) in &entries {
+        hasher.update(format!("{id}:{updated_at}\n"));
+    }
+    ExpenseChecksum {
+        count: entries.len(),
+        checksum: format!("{:x}", hasher.finalize()),
+    }
+}
+
+/// 同期の状態を取得する（一度も同期していない場合はNone）
+pub fn get_sync_state(conn: &Connection, user_id: &str) -> AppResult<Option<ExpenseSyncState>> {
+    Ok(conn
+        .query_row(
+            "SELECT revision, last_synced_at, last_full_sync_at, last_integrity_check_at
+             FROM expense_sync_state WHERE user_id = ?1",
+            params![user_id],
+            |row| {
+                Ok(ExpenseSyncState {
+                    revision: row.get(0)?,
+                    last_synced_at: row.get(1)?,
+                    last_full_sync_at: row.get(2)?,
+                    last_integrity_check_at: row.get(3)?,
+                })
+            },
+        )
+        .optional()?)
+}
+
+/// 経費をミラーに保存する（同じIDの経費は置き換える）
+///
+/// 作成・更新のAPI呼び出しの結果を、次回の同期を待たずに反映する場合にも使用します。
+pub fn upsert_mirrored(conn: &Connection, user_id: &str, expense: &Expense) -> AppResult<()> {
+    conn.execute(
+        "INSERT INTO expense_mirror (user_id, id, date, created_at, updated_at, payload)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(user_id, id) DO UPDATE SET
+             date = excluded.date,
+             created_at = excluded.created_at,
+             updated_at = excluded.updated_at,
+             payload = excluded.payload",
+        params![
+            user_id,
+            expense.id,
+            expense.date,
+            expense.created_at,
+            expense.updated_at,
+            serde_json::to_string(expense)?
+        ],
+    )?;
+    Ok(())
+}
+
+/// ミラーから経費を取得する
+pub fn get_mirrored(
+    conn: &Connection,
+    user_id: &str,
+    expense_id: i64,
+) -> AppResult<Option<Expense>> {
+    let payload: Option<String> = conn
+        .query_row(
+            "SELECT payload FROM expense_mirror WHERE user_id = ?1 AND id = ?2",
+            params![user_id, expense_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(payload
+        .map(|payload| serde_json::from_str(&payload))
+        .transpose()?)
+}
+
+/// 経費をミラーから削除する
+///
+/// # 戻り値
+/// 削除した場合はtrue
+pub fn remove_mirrored(conn: &Connection, user_id: &str, expense_id: i64) -> AppResult<bool> {
+    let deleted = conn.execute(
+        "DELETE FROM expense_mirror WHERE user_id = ?1 AND id = ?2",
+        params![user_id, expense_id],
+    )?;
+    Ok(deleted > 0)
+}
+
+fn mirrored_count(conn: &Connection, user_id: &str) -> AppResult<usize> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM expense_mirror WHERE user_id = ?1",
+        params![user_id],
+        |row| row.get(0),
+    )?;
+    Ok(count as usize)
+}
+
+/// 同期の状態を記録する
+fn record_sync(conn: &Connection, user_id: &str, revision: &str, full: bool) -> AppResult<()> {
+    let now = get_current_jst_timestamp();
+    conn.execute(
+        "INSERT INTO expense_sync_state (user_id, revision, last_synced_at, last_full_sync_at)
+         VALUES (?1, ?2, ?3, CASE WHEN ?4 THEN ?3 END)
+         ON CONFLICT(user_id) DO UPDATE SET
+             revision = excluded.revision,
+             last_synced_at = excluded.last_synced_at,
+             last_full_sync_at = CASE WHEN ?4 THEN ?3 ELSE last_full_sync_at END",
+        params![user_id, revision, now, full],
+    )?;
+    Ok(())
+}
+
+/// 変更をミラーに反映する
+pub fn apply_changes(
+    conn: &mut Connection,
+    user_id: &str,
+    changes: &ExpenseChanges,
+    revision: &str,
+) -> AppResult<ExpenseSyncReport> {
+    let tx = conn.transaction()?;
+    for expense in &changes.upserted {
+        upsert_mirrored(&tx, user_id, expense)?;
+    }
+    let mut deleted = 0;
+    for id in &changes.deleted {
+        if remove_mirrored(&tx, user_id, *id)? {
+            deleted += 1;
+        }
+    }
+    record_sync(&tx, user_id, revision, false)?;
+    let total = mirrored_count(&tx, user_id)?;
+    tx.commit()?;
+
+    Ok(ExpenseSyncReport {
+        full_resync: false,
+        upserted: changes.upserted.len(),
+        deleted,
+        total,
+        revision: revision.to_string(),
+        changed: !changes.upserted.is_empty() || deleted > 0,
+    })
+}
+
+/// ミラーを全件置き換える
+pub fn replace_all(
+    conn: &mut Connection,
+    user_id: &str,
+    expenses: &[Expense],
+    revision: &str,
+) -> AppResult<ExpenseSyncReport> {
+    let tx = conn.transaction()?;
+    let previous = local_checksum(&tx, user_id)?;
+    tx.execute(
+        "DELETE FROM expense_mirror WHERE user_id = ?1",
+        params![user_id],
+    )?;
+    for expense in expenses {
+        upsert_mirrored(&tx, user_id, expense)?;
+    }
+    record_sync(&tx, user_id, revision, true)?;
+    let current = local_checksum(&tx, user_id)?;
+    tx.commit()?;
+
+    Ok(ExpenseSyncReport {
+        full_resync: true,
+        upserted: expenses.len(),
+        deleted: previous.count.saturating_sub(current.count),
+        total: current.count,
+        revision: revision.to_string(),
+        changed: previous != current,
+    })
+}
+
+/// ミラーから経費一覧を取得する
+///
+/// # 引数
+/// * `month` - 月フィルター（YYYY-MM形式）
+/// * `filter` - 検索条件
+pub fn list_mirrored(
+    conn: &Connection,
+    user_id: &str,
+    month: Option<&str>,
+    filter: &ExpenseFilter,
+) -> AppResult<Vec<Expense>> {
+    if let Some(month) = month {
+        validate_month(month)?;
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT payload FROM expense_mirror
+         WHERE user_id = ?1 AND (?2 IS NULL OR substr(date, 1, 7) = ?2)
+         ORDER BY date DESC, created_at DESC",
+    )?;
+    let payloads = stmt
+        .query_map(params![user_id, month], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut expenses = Vec::with_capacity(payloads.len());
+    for payload in payloads {
+        let expense: Expense = serde_json::from_str(&payload)?;
+        if filter.matches(&expense) {
+            expenses.push(expense);
+        }
+    }
+    Ok(expenses)
+}
+
+/// ミラーから経費一覧を1ページ分取得する
+///
+/// 期間・カテゴリ・並び順はSQLで絞り込み、`filter`の条件はその後に適用します。
+/// `total_count`はページに関係なく、すべての条件に一致した件数です。
+///
+/// # 引数
+/// * `month` - 月フィルター（YYYY-MM形式）
+/// * `filter` - 検索条件
+/// * `query` - ページ取得条件
+pub fn list_mirrored_page(
+    conn: &Connection,
+    user_id: &str,
+    month: Option<&str>,
+    filter: &ExpenseFilter,
+    query: &GetExpensesQuery,
+) -> AppResult<ExpensePage> {
+    query.validate()?;
+
+    let mut sql = "SELECT payload FROM expense_mirror WHERE user_id = ?".to_string();
+    let mut params: Vec<Box<dyn ToSql>> = vec![Box::new(user_id.to_string())];
+
+    if let Some(month) = month {
+        validate_month(month)?;
+        sql.push_str(" AND substr(date, 1, 7) = ?");
+        params.push(Box::new(month.to_string()));
+    }
+
+    if let Some(from_date) = &query.from_date {
+        sql.push_str(" AND date >= ?");
+        params.push(Box::new(from_date.clone()));
+    }
+
+    if let Some(to_date) = &query.to_date {
+        sql.push_str(" AND date <= ?");
+        params.push(Box::new(to_date.clone()));
+    }
+
+    if let Some(category) = &query.category {
+        sql.push_str(" AND json_extract(payload, '$.category') = ?");
+        params.push(Box::new(category.clone()));
+    }
+
+    sql.push_str(match query.sort_by {
+        ExpenseSortKey::DateDesc => " ORDER BY date DESC, created_at DESC",
+        ExpenseSortKey::DateAsc => " ORDER BY date ASC, created_at ASC",
+        ExpenseSortKey::AmountDesc => {
+            " ORDER BY json_extract(payload, '$.amount') DESC, date DESC, created_at DESC"
+        }
+        ExpenseSortKey::AmountAsc => {
+            " ORDER BY json_extract(payload, '$.amount') ASC, date DESC, created_at DESC"
+        }
+    });
+
+    let mut stmt = conn.prepare(&sql)?;
+    let payloads = stmt
+        .query_map(params_from_iter(params.iter()), |row| {
+            row.get::<_, String>(0)
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut matched = Vec::with_capacity(payloads.len());
+    for payload in payloads {
+        let expense: Expense = serde_json::from_str(&payload)?;
+        if filter.matches(&expense) {
+            matched.push(expense);
+        }
+    }
+
+    let total_count = matched.len();
+    let offset = query.offset.unwrap_or(0) as usize;
+    let limit = query.limit.map_or(usize::MAX, |limit| limit as usize);
+    let expenses = matched.into_iter().skip(offset).take(limit).collect();
+
+    Ok(ExpensePage {
+        expenses,
+        total_count,
+    })
+}
+
+/// ミラーの件数とチェックサムを算出する
+pub fn local_checksum(conn: &Connection, user_id: &str) -> AppResult<ExpenseChecksum> {
+    let mut stmt = conn.prepare("SELECT id, updated_at FROM expense_mirror WHERE user_id = ?1")?;
+    let rows = stmt
+        .query_map(params![user_id], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(checksum_of(
+        rows.iter()
+            .map(|(id, updated_at)| (*id, updated_at.as_str())),
+    ))
+}
+
+/// 整合性を確認した日時を記録する
+fn record_integrity_check(conn: &Connection, user_id: &str) -> AppResult<()> {
+    conn.execute(
+        "UPDATE expense_sync_state SET last_integrity_check_at = ?2 WHERE user_id = ?1",
+        params![user_id, get_current_jst_timestamp()],
+    )?;
+    Ok(())
+}
+
+/// 同期を1つずつ実行するためのロック
+///
+/// バックグラウンドの同期と手動の同期が同時にミラーを書き換えないようにします。
+pub fn sync_lock() -> &'static tokio::sync::Mutex<()> {
+    static LOCK: OnceLock<tokio::sync::Mutex<()>> = OnceLock::new();
+    LOCK.get_or_init(|| tokio::sync::Mutex::new(()))
+}
+
+/// APIサーバーと経費を同期する
+///
+/// 前回のリビジョン以降の変更を取得してミラーに反映します。
+/// 一度も同期していない場合、`force_full`を指定した場合、リビジョンが古すぎる場合は全件を取得し直します。
+/// 呼び出し元で[`sync_lock`]を取得してから呼び出してください。
+///
+/// # 引数
+/// * `api_client` - APIクライアント
+/// * `db` - データベース接続
+/// * `user_id` - ユーザーID
+/// * `session_token` - セッショントークン
+/// * `force_full` - trueの場合は全件を取得し直す
+pub async fn sync_expenses(
+    api_client: &ApiClient,
+    db: &Mutex<Connection>,
+    user_id: &str,
+    session_token: Option<&str>,
+    force_full: bool,
+) -> AppResult<ExpenseSyncReport> {
+    let revision = if force_full {
+        None
+    } else {
+        get_sync_state(&*lock_db(db)?, user_id)?.and_then(|state| state.revision)
+    };
+
+    let payload = match &revision {
+        Some(revision) => {
+            let endpoint = format!("/api/v1/expenses?since={}", urlencoding::encode(revision));
+            match api_client
+                .get::<SyncResponse>(&endpoint, session_token)
+                .await
+            {
+                Ok(response) => response.into_payload()?,
+                Err(e) if e.to_string().contains(SYNC_TOKEN_EXPIRED_CODE) => {
+                    SyncPayload::ResyncRequired
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        None => SyncPayload::ResyncRequired,
+    };
+
+    let payload = match payload {
+        SyncPayload::ResyncRequired => {
+            if revision.is_some() {
+                info!("前回のリビジョンが古いため、経費を全件取得し直します");
+            }
+            let response: SyncResponse = api_client.get("/api/v1/expenses", session_token).await?;
+            match response.into_payload()? {
+                SyncPayload::ResyncRequired => {
+                    return Err(AppError::ExternalService(
+                        "経費の全件取得で再同期を要求されました".to_string(),
+                    ))
+                }
+                SyncPayload::Delta(changes, revision) => {
+                    SyncPayload::Full(changes.upserted, revision)
+                }
+                full => full,
+            }
+        }
+        delta => delta,
+    };
+
+    let mut conn = lock_db(db)?;
+    let report = match payload {
+        SyncPayload::Delta(changes, revision) => {
+            apply_changes(&mut conn, user_id, &changes, &revision)?
+        }
+        SyncPayload::Full(expenses, revision) => {
+            replace_all(&mut conn, user_id, &expenses, &revision)?
+        }
+        SyncPayload::ResyncRequired => unreachable!("再同期は全件取得に置き換え済み"),
+    };
+    info!(
+        "経費を同期しました: full_resync={}, upserted={}, deleted={}, total={}",
+        report.full_resync, report.upserted, report.deleted, report.total
+    );
+    Ok(report)
+}
+
+/// ミラーとAPIサーバーの件数・チェックサムを比較する
+///
+/// 一致しない場合は全件を取得し直します。
+/// 呼び出し元で[`sync_lock`]を取得してから呼び出してください。
+pub async fn check_integrity(
+    api_client: &ApiClient,
+    db: &Mutex<Connection>,
+    user_id: &str,
+    session_token: Option<&str>,
+) -> AppResult<ExpenseIntegrityReport> {
+    let server = match api_client
+        .get::<ExpenseChecksum>("/api/v1/expenses/checksum", session_token)
+        .await
+    {
+        Ok(checksum) => checksum,
+        Err(e) if e.to_string().contains("NOT_FOUND") => {
+            // チェックサムに未対応のサーバーは全件から算出する
+            let response: SyncResponse = api_client.get("/api/v1/expenses", session_token).await?;
+            let expenses = response.expenses.unwrap_or_default();
+            checksum_of(
+                expenses
+                    .iter()
+                    .map(|expense| (expense.id, expense.updated_at.as_str())),
+            )
+        }
+        Err(e) => return Err(e),
+    };
+
+    let local = local_checksum(&*lock_db(db)?, user_id)?;
+    let matches = local == server;
+    let resync = if matches {
+        None
+    } else {
+        warn!(
+            "経費のミラーがAPIサーバーと一致しないため、全件を取得し直します: local={local:?}, server={server:?}"
+        );
+        Some(sync_expenses(api_client, db, user_id, session_token, true).await?)
+    };
+    record_integrity_check(&*lock_db(db)?, user_id)?;
+
+    Ok(ExpenseIntegrityReport {
+        local,
+        server,
+        matches,
+        resync,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_connection() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        ExpenseMirrorSchemaMigration.execute(&conn).unwrap();
+        conn
+    }
+
+    fn expense(id: i64, date: &str, updated_at: &str) -> Expense {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "date": date,
+            "amount": 1000.0 * id as f64,
+            "category": if id % 2 == 0 { "交通費" } else { "飲食費" },
+            "description": format!("経費{id}"),
+            "created_at": format!("{date}T09:00:00+09:00"),
+            "updated_at": updated_at,
+        }))
+        .unwrap()
+    }
+
+    fn ids(expenses: &[Expense]) -> Vec<i64> {
+        expenses.iter().map(|expense| expense.id).collect()
+    }
+
+    #[test]
+    fn test_response_payloads() {
+        let delta: SyncResponse = serde_json::from_value(serde_json::json!({
+            "success": true,
+            "changes": { "upserted": [], "deleted": [3] },
+            "revision": "rev-2",
+            "timestamp": "2026-10-15T01:00:00.000Z"
+        }))
+        .unwrap();
+        assert!(matches!(
+            delta.into_payload().unwrap(),
+            SyncPayload::Delta(changes, revision) if changes.deleted == vec![3] && revision == "rev-2"
+        ));
+
+        // sinceに未対応のサーバーは通常の一覧を返す（応答時刻をリビジョンとして使用）
+        let list: SyncResponse = serde_json::from_value(serde_json::json!({
+            "success": true,
+            "expenses": [],
+            "count": 0,
+            "filters": null,
+            "timestamp": "2026-10-15T01:00:00.000Z"
+        }))
+        .unwrap();
+        assert!(matches!(
+            list.into_payload().unwrap(),
+            SyncPayload::Full(expenses, revision) if expenses.is_empty() && revision == "2026-10-15T01:00:00.000Z"
+        ));
+
+        let expired: SyncResponse = serde_json::from_value(serde_json::json!({
+            "success": true,
+            "full_resync_required": true,
+            "timestamp": "2026-10-15T01:00:00.000Z"
+        }))
+        .unwrap();
+        assert!(matches!(
+            expired.into_payload().unwrap(),
+            SyncPayload::ResyncRequired
+        ));
+
+        let empty: SyncResponse =
+            serde_json::from_value(serde_json::json!({ "timestamp": "t" })).unwrap();
+        assert!(empty.into_payload().is_err());
+    }
+
+    #[test]
+    fn test_replace_all_and_apply_changes() {
+        let mut conn = create_test_connection();
+        assert_eq!(get_sync_state(&conn, "u1").unwrap(), None);
+
+        let report = replace_all(
+            &mut conn,
+            "u1",
+            &[
+                expense(1, "2026-09-30", "2026-09-30T09:00:00+09:00"),
+                expense(2, "2026-10-01", "2026-10-01T09:00:00+09:00"),
+            ],
+            "rev-1",
+        )
+        .unwrap();
+        assert!(report.full_resync);
+        assert_eq!(report.total, 2);
+        let state = get_sync_state(&conn, "u1").unwrap().unwrap();
+        assert_eq!(state.revision.as_deref(), Some("rev-1"));
+        assert!(state.last_full_sync_at.is_some());
+
+        let changes = ExpenseChanges {
+            upserted: vec![
+                expense(2, "2026-10-02", "2026-10-02T09:00:00+09:00"),
+                expense(3, "2026-10-03", "2026-10-03T09:00:00+09:00"),
+            ],
+            deleted: vec![1, 99],
+        };
+        let report = apply_changes(&mut conn, "u1", &changes, "rev-2").unwrap();
+        assert!(!report.full_resync);
+        assert_eq!(report.upserted, 2);
+        // 存在しない経費の削除は数えない
+        assert_eq!(report.deleted, 1);
+        assert_eq!(report.total, 2);
+        assert!(report.changed);
+
+        let expenses = list_mirrored(&conn, "u1", None, &ExpenseFilter::new()).unwrap();
+        assert_eq!(ids(&expenses), vec![3, 2]);
+        assert_eq!(expenses[1].date, "2026-10-02");
+
+        let state = get_sync_state(&conn, "u1").unwrap().unwrap();
+        assert_eq!(state.revision.as_deref(), Some("rev-2"));
+    }
+
+    #[test]
+    fn test_replace_all_counts_removed_expenses() {
+        let mut conn = create_test_connection();
+        replace_all(
+            &mut conn,
+            "u1",
+            &[expense(1, "2026-10-01", "a"), expense(2, "2026-10-02", "b")],
+            "rev-1",
+        )
+        .unwrap();
+
+        let report =
+            replace_all(&mut conn, "u1", &[expense(2, "2026-10-02", "b")], "rev-2").unwrap();
+        assert_eq!(report.deleted, 1);
+        assert_eq!(report.total, 1);
+        assert!(report.changed);
+
+        // 同じ内容での置き換え（sinceに未対応のサーバー）は変更として扱わない
+        let report =
+            replace_all(&mut conn, "u1", &[expense(2, "2026-10-02", "b")], "rev-3").unwrap();
+        assert!(report.full_resync);
+        assert!(!report.changed);
+    }
+
+    #[test]
+    fn test_mirror_is_scoped_to_user() {
+        let mut conn = create_test_connection();
+        replace_all(&mut conn, "u1", &[expense(1, "2026-10-01", "a")], "rev-1").unwrap();
+        replace_all(&mut conn, "u2", &[expense(2, "2026-10-01", "b")], "rev-1").unwrap();
+
+        // 他のユーザーのミラーは置き換えない
+        replace_all(&mut conn, "u2", &[], "rev-2").unwrap();
+        assert_eq!(
+            ids(&list_mirrored(&conn, "u1", None, &ExpenseFilter::new()).unwrap()),
+            vec![1]
+        );
+        assert!(!remove_mirrored(&conn, "u2", 1).unwrap());
+    }
+
+    #[test]
+    fn test_list_mirrored_filters() {
+        let mut conn = create_test_connection();
+        replace_all(
+            &mut conn,
+            "u1",
+            &[
+                expense(1, "2026-09-30", "a"),
+                expense(2, "2026-10-01", "b"),
+                expense(3, "2026-10-15", "c"),
+            ],
+            "rev-1",
+        )
+        .unwrap();
+
+        let october = list_mirrored(&conn, "u1", Some("2026-10"), &ExpenseFilter::new()).unwrap();
+        assert_eq!(ids(&october), vec![3, 2]);
+
+        let filter = ExpenseFilter {
+            category: Some("飲食費".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(&list_mirrored(&conn, "u1", Some("2026-10"), &filter).unwrap()),
+            vec![3]
+        );
+
+        assert!(list_mirrored(&conn, "u1", Some("2026/10"), &ExpenseFilter::new()).is_err());
+        assert!(list_mirrored(&conn, "u1", Some("10-2026"), &ExpenseFilter::new()).is_err());
+    }
+
+    #[test]
+    fn test_list_mirrored_page() {
+        let mut conn = create_test_connection();
+        let expenses: Vec<Expense> = (1..=5)
+            .map(|id| expense(id, &format!("2026-10-0{id}"), "a"))
+            .collect();
+        replace_all(&mut conn, "u1", &expenses, "rev-1").unwrap();
+        replace_all(&mut conn, "u2", &[expense(9, "2026-10-03", "a")], "rev-1").unwrap();
+
+        // 条件なしでは従来の一覧と同じ並び順
+        let all = list_mirrored_page(
+            &conn,
+            "u1",
+            None,
+            &ExpenseFilter::new(),
+            &GetExpensesQuery::default(),
+        )
+        .unwrap();
+        assert_eq!(all.total_count, 5);
+        assert_eq!(ids(&all.expenses), vec![5, 4, 3, 2, 1]);
+
+        // 件数と開始位置を指定しても、total_countは一致した全件数
+        let query = GetExpensesQuery {
+            limit: Some(2),
+            offset: Some(1),
+            ..Default::default()
+        };
+        let page = list_mirrored_page(&conn, "u1", None, &ExpenseFilter::new(), &query).unwrap();
+        assert_eq!(page.total_count, 5);
+        assert_eq!(ids(&page.expenses), vec![4, 3]);
+
+        // 期間・カテゴリ・並び順
+        let query = GetExpensesQuery {
+            from_date: Some("2026-10-02".to_string()),
+            to_date: Some("2026-10-05".to_string()),
+            category: Some("交通費".to_string()),
+            sort_by: ExpenseSortKey::AmountAsc,
+            ..Default::default()
+        };
+        let page = list_mirrored_page(&conn, "u1", None, &ExpenseFilter::new(), &query).unwrap();
+        assert_eq!(page.total_count, 2);
+        assert_eq!(ids(&page.expenses), vec![2, 4]);
+
+        // 検索条件はページ分割の前に適用する
+        let filter = ExpenseFilter::new().with_amount_range(Some(3000.0), None);
+        let query = GetExpensesQuery {
+            limit: Some(1),
+            sort_by: ExpenseSortKey::DateAsc,
+            ..Default::default()
+        };
+        let page = list_mirrored_page(&conn, "u1", None, &filter, &query).unwrap();
+        assert_eq!(page.total_count, 3);
+        assert_eq!(ids(&page.expenses), vec![3]);
+
+        // 範囲外の開始位置は空のページ
+        let query = GetExpensesQuery {
+            offset: Some(10),
+            ..Default::default()
+        };
+        let page = list_mirrored_page(&conn, "u1", None, &ExpenseFilter::new(), &query).unwrap();
+        assert_eq!(page.total_count, 5);
+        assert!(page.expenses.is_empty());
+
+        // 期間が逆転している場合はエラー
+        let reversed = GetExpensesQuery {
+            from_date: Some("2026-10-05".to_string()),
+            to_date: Some("2026-10-01".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            list_mirrored_page(&conn, "u1", None, &ExpenseFilter::new(), &reversed),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_write_through() {
+        let mut conn = create_test_connection();
+        replace_all(&mut conn, "u1", &[], "rev-1").unwrap();
+
+        upsert_mirrored(&conn, "u1", &expense(5, "2026-10-05", "a")).unwrap();
+        upsert_mirrored(&conn, "u1", &expense(5, "2026-10-06", "b")).unwrap();
+        let expenses = list_mirrored(&conn, "u1", None, &ExpenseFilter::new()).unwrap();
+        assert_eq!(ids(&expenses), vec![5]);
+        assert_eq!(expenses[0].date, "2026-10-06");
+
+        // 書き込みではリビジョンを進めない（次回の同期でサーバーの内容を取得する）
+        let state = get_sync_state(&conn, "u1").unwrap().unwrap();
+        assert_eq!(state.revision.as_deref(), Some("rev-1"));
+
+        assert!(remove_mirrored(&conn, "u1", 5).unwrap());
+        assert!(list_mirrored(&conn, "u1", None, &ExpenseFilter::new())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_checksum() {
+        let a = checksum_of([(2, "b"), (1, "a")]);
+        let b = checksum_of([(1, "a"), (2, "b")]);
+        assert_eq!(a, b);
+        assert_eq!(a.count, 2);
+        // APIサーバー（utils/expense-sync.ts）と同じ値になること
+        assert_eq!(
+            a.checksum,
+            "b173219e5a48cb2ef75498db207f085235147922fcad012c30bc977070a28a68"
+        );
+        assert_ne!(a, checksum_of([(1, "a"), (2, "c")]));
+        assert_eq!(
+            checksum_of([]).checksum,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+
+        let mut conn = create_test_connection();
+        replace_all(
+            &mut conn,
+            "u1",
+            &[expense(1, "2026-10-01", "a"), expense(2, "2026-10-02", "b")],
+            "rev-1",
+        )
+        .unwrap();
+        assert_eq!(local_checksum(&conn, "u1").unwrap(), a);
+    }
+
+    #[test]
+    fn test_integrity_check_due() {
+        let now = Utc::now();
+        let state = ExpenseSyncState::default();
+        assert!(state.integrity_check_due(now));
+
+        let recent = ExpenseSyncState {
+            last_integrity_check_at: Some((now - Duration::hours(1)).to_rfc3339()),
+            ..Default::default()
+        };
+        assert!(!recent.integrity_check_due(now));
+
+        let old = ExpenseSyncState {
+            last_integrity_check_at: Some(
+                (now - Duration::hours(INTEGRITY_CHECK_INTERVAL_HOURS)).to_rfc3339(),
+            ),
+            ..Default::default()
+        };
+        assert!(old.integrity_check_due(now));
+
+        let mut conn = create_test_connection();
+        replace_all(&mut conn, "u1", &[], "rev-1").unwrap();
+        record_integrity_check(&conn, "u1").unwrap();
+        let state = get_sync_state(&conn, "u1").unwrap().unwrap();
+        assert!(!state.integrity_check_due(now));
+    }
+}
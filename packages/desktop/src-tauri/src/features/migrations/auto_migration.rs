@@ -12,8 +12,8 @@ pub mod table;
 
 // 公開インターフェース
 pub use models::{
-    AppliedMigration, AutoMigrationResult, MigrationDefinition, MigrationExecutionResult,
-    MigrationStatusReport,
+    AppliedMigration, AutoMigrationResult, AutoMigrationStatus, MigrationDefinition,
+    MigrationExecutionResult, MigrationRiskLevel, MigrationStatusReport,
 };
 
 pub use errors::{MigrationError, MigrationErrorType};
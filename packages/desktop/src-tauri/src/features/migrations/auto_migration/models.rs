@@ -380,6 +380,110 @@ impl fmt::Display for MigrationStatusReport {
     }
 }
 
+/// 未適用マイグレーションのリスクレベル
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MigrationRiskLevel {
+    /// 未適用マイグレーションなし
+    Low,
+    /// 未適用マイグレーションが1件
+    Medium,
+    /// 未適用マイグレーションが複数件、または前回適用から30日以上経過
+    High,
+    /// 未適用マイグレーションが多数、または前回適用から90日以上経過
+    Critical,
+}
+
+impl MigrationRiskLevel {
+    /// 未適用件数と前回適用からの経過日数からリスクレベルを判定
+    ///
+    /// # 引数
+    /// * `pending_count` - 未適用マイグレーション数
+    /// * `days_since_last_migration` - 前回適用からの経過日数（未適用の場合はNone）
+    ///
+    /// # 戻り値
+    /// リスクレベル
+    pub fn evaluate(pending_count: usize, days_since_last_migration: Option<i64>) -> Self {
+        if pending_count == 0 {
+            return MigrationRiskLevel::Low;
+        }
+
+        let days = days_since_last_migration.unwrap_or(0);
+        if pending_count >= 3 || days >= 90 {
+            MigrationRiskLevel::Critical
+        } else if pending_count >= 2 || days >= 30 {
+            MigrationRiskLevel::High
+        } else {
+            MigrationRiskLevel::Medium
+        }
+    }
+}
+
+impl fmt::Display for MigrationRiskLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            MigrationRiskLevel::Low => "低",
+            MigrationRiskLevel::Medium => "中",
+            MigrationRiskLevel::High => "高",
+            MigrationRiskLevel::Critical => "重大",
+        };
+        write!(f, "{label}")
+    }
+}
+
+/// 自動マイグレーション状態
+///
+/// マイグレーション状態レポートに、次に適用されるマイグレーションや
+/// 前回適用からの経過日数、リスクレベルを加えた情報を表します。
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoMigrationStatus {
+    /// 次回起動時に適用されるマイグレーション名
+    pub next_migration_due: Option<String>,
+    /// 最後のマイグレーション適用日時（JST、RFC3339形式）
+    pub last_migration_at: Option<String>,
+    /// 最後のマイグレーション適用からの経過日数
+    pub days_since_last_migration: Option<i64>,
+    /// 未適用マイグレーション名一覧
+    pub pending_migrations: Vec<String>,
+    /// リスクレベル
+    pub risk_level: MigrationRiskLevel,
+    /// 元になったマイグレーション状態レポート
+    pub status_report: MigrationStatusReport,
+}
+
+impl AutoMigrationStatus {
+    /// マイグレーション状態レポートから自動マイグレーション状態を作成
+    ///
+    /// # 引数
+    /// * `report` - マイグレーション状態レポート
+    /// * `now` - 経過日数の計算に使う現在日時
+    ///
+    /// # 戻り値
+    /// 自動マイグレーション状態
+    pub fn from_report(
+        report: MigrationStatusReport,
+        now: chrono::DateTime<chrono::FixedOffset>,
+    ) -> Self {
+        let last_migration_at = report.last_migration_date.clone();
+        let days_since_last_migration = last_migration_at
+            .as_deref()
+            .and_then(|date| chrono::DateTime::parse_from_rfc3339(date).ok())
+            .map(|applied_at| (now - applied_at).num_days());
+
+        let pending_migrations = report.pending_migrations.clone();
+        let risk_level =
+            MigrationRiskLevel::evaluate(pending_migrations.len(), days_since_last_migration);
+
+        Self {
+            next_migration_due: pending_migrations.first().cloned(),
+            last_migration_at,
+            days_since_last_migration,
+            pending_migrations,
+            risk_level,
+            status_report: report,
+        }
+    }
+}
+
 /// マイグレーション実行結果
 ///
 /// 個別のマイグレーション実行結果を表します。
@@ -545,4 +649,66 @@ mod tests {
         assert!(failure_result.applied_migrations.is_empty());
         assert_eq!(failure_result.total_execution_time_ms, 0);
     }
+
+    #[test]
+    fn test_migration_risk_level_evaluate() {
+        assert_eq!(
+            MigrationRiskLevel::evaluate(0, Some(365)),
+            MigrationRiskLevel::Low
+        );
+        assert_eq!(
+            MigrationRiskLevel::evaluate(1, Some(3)),
+            MigrationRiskLevel::Medium
+        );
+        assert_eq!(
+            MigrationRiskLevel::evaluate(1, None),
+            MigrationRiskLevel::Medium
+        );
+        assert_eq!(
+            MigrationRiskLevel::evaluate(2, Some(3)),
+            MigrationRiskLevel::High
+        );
+        assert_eq!(
+            MigrationRiskLevel::evaluate(1, Some(30)),
+            MigrationRiskLevel::High
+        );
+        assert_eq!(
+            MigrationRiskLevel::evaluate(3, Some(0)),
+            MigrationRiskLevel::Critical
+        );
+        assert_eq!(
+            MigrationRiskLevel::evaluate(1, Some(90)),
+            MigrationRiskLevel::Critical
+        );
+    }
+
+    #[test]
+    fn test_auto_migration_status_from_report() {
+        let now = chrono::DateTime::parse_from_rfc3339("2024-03-31T12:00:00+09:00").unwrap();
+
+        let report = MigrationStatusReport::new(
+            3,
+            2,
+            vec!["003_add_index".to_string()],
+            Some("2024-03-01T09:00:00+09:00".to_string()),
+            "2.0.0".to_string(),
+        );
+        let status = AutoMigrationStatus::from_report(report, now);
+
+        assert_eq!(status.next_migration_due.as_deref(), Some("003_add_index"));
+        assert_eq!(
+            status.last_migration_at.as_deref(),
+            Some("2024-03-01T09:00:00+09:00")
+        );
+        assert_eq!(status.days_since_last_migration, Some(30));
+        assert_eq!(status.risk_level, MigrationRiskLevel::High);
+        assert_eq!(status.status_report.total_applied, 2);
+
+        // 未適用・適用履歴なしの場合
+        let report = MigrationStatusReport::new(0, 0, vec![], None, "0.0.0".to_string());
+        let status = AutoMigrationStatus::from_report(report, now);
+        assert!(status.next_migration_due.is_none());
+        assert!(status.days_since_last_migration.is_none());
+        assert_eq!(status.risk_level, MigrationRiskLevel::Low);
+    }
 }
@@ -8,7 +8,21 @@ use super::executor::{
     UserIdNanoidMigrationExecutor,
 };
 use super::models::{ExecutableMigrationDefinition, MigrationDefinition};
+use crate::features::expenses::location::{
+    get_expense_location_definition, get_expense_locations_schema_definition,
+};
+use crate::features::expenses::merchants::{
+    get_expense_merchants_schema_definition, get_merchants_schema_definition,
+};
+use crate::features::expenses::receipt_policies::get_receipt_policies_schema_definition;
+use crate::features::expenses::repository::get_expense_tax_columns_definition;
+use crate::features::expenses::sync::get_expense_mirror_schema_definition;
+use crate::features::migrations::query_indexes::get_query_indexes_definition;
 use crate::features::migrations::r2_user_directory_migration::get_r2_migration_schema_definition;
+use crate::features::receipts::annotations::get_receipt_annotations_schema_definition;
+use crate::features::receipts::cache_policy::get_cache_config_schema_definition;
+use crate::features::security::audit_log::get_security_events_schema_definition;
+use crate::features::subscriptions::repository::get_subscription_payments_schema_definition;
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 
@@ -228,6 +242,42 @@ impl MigrationRegistry {
         );
         registry.register_executable(user_id_nanoid_executable)?;
 
+        // セキュリティイベント（監査ログ）テーブル
+        registry.register_executable(get_security_events_schema_definition())?;
+
+        // 検索パターンに合わせた複合インデックス
+        registry.register_executable(get_query_indexes_definition())?;
+
+        // サブスクリプション支払い履歴テーブル
+        registry.register_executable(get_subscription_payments_schema_definition())?;
+
+        // 経費の税率・税額カラム
+        registry.register_executable(get_expense_tax_columns_definition())?;
+
+        // 経費の位置情報とプライバシー設定
+        registry.register_executable(get_expense_location_definition())?;
+
+        // 領収書の注釈テーブル
+        registry.register_executable(get_receipt_annotations_schema_definition())?;
+
+        // 店舗・別名テーブルと経費の店舗ID
+        registry.register_executable(get_merchants_schema_definition())?;
+
+        // API Server版の経費ミラーと同期状態
+        registry.register_executable(get_expense_mirror_schema_definition())?;
+
+        // 領収書の添付ルールと領収書未添付の記録
+        registry.register_executable(get_receipt_policies_schema_definition())?;
+
+        // 領収書キャッシュの保持ポリシーとアクセス回数
+        registry.register_executable(get_cache_config_schema_definition())?;
+
+        // API Server版の経費の位置情報
+        registry.register_executable(get_expense_locations_schema_definition())?;
+
+        // API Server版の経費と店舗の紐付け
+        registry.register_executable(get_expense_merchants_schema_definition())?;
+
         Ok(registry)
     }
 }
@@ -350,7 +400,7 @@ mod tests {
     fn test_register_default_migrations() {
         let registry = MigrationRegistry::register_default_migrations().unwrap();
 
-        assert_eq!(registry.count(), 5);
+        assert_eq!(registry.count(), 15);
         assert!(registry
             .find_executable_migration("001_create_basic_schema")
             .is_some());
@@ -366,6 +416,15 @@ mod tests {
         assert!(registry
             .find_executable_migration("004_migrate_user_id_to_nanoid")
             .is_some());
+        assert!(registry
+            .find_executable_migration("005_create_security_events")
+            .is_some());
+        assert!(registry
+            .find_executable_migration("006_add_query_indexes")
+            .is_some());
+        assert!(registry
+            .find_executable_migration("007_create_subscription_payments")
+            .is_some());
 
         // 各マイグレーションのチェックサムが正しく計算されていることを確認
         let basic_schema = registry
@@ -1,10 +1,14 @@
-use super::auto_migration::{AutoMigrationService, MigrationStatusReport};
+use super::auto_migration::{AutoMigrationService, AutoMigrationStatus, MigrationStatusReport};
+use super::query_indexes::{
+    explain_query_plans as explain_representative_queries, QueryPlanReport,
+};
 use super::service::{
     drop_receipt_path_column, is_receipt_url_migration_complete,
     is_user_authentication_migration_complete, migrate_receipt_path_to_url,
     migrate_user_authentication, MigrationResult, MigrationStatus,
 };
 use crate::shared::database::connection::initialize_database;
+use crate::shared::operations::{begin_operation, OperationKind};
 use chrono::Utc;
 use chrono_tz::Asia::Tokyo;
 use tauri::AppHandle;
@@ -61,6 +65,7 @@ pub async fn check_migration_status(app_handle: AppHandle) -> Result<MigrationSt
 pub async fn execute_user_authentication_migration(
     app_handle: AppHandle,
 ) -> Result<MigrationResult, String> {
+    let _operation = begin_operation(OperationKind::Migration, "ユーザー認証マイグレーション");
     let conn =
         initialize_database(&app_handle).map_err(|e| format!("データベース接続エラー: {e}"))?;
 
@@ -79,6 +84,7 @@ pub async fn execute_user_authentication_migration(
 pub async fn execute_receipt_url_migration(
     app_handle: AppHandle,
 ) -> Result<MigrationResult, String> {
+    let _operation = begin_operation(OperationKind::Migration, "receipt_urlマイグレーション");
     let conn =
         initialize_database(&app_handle).map_err(|e| format!("データベース接続エラー: {e}"))?;
 
@@ -96,6 +102,7 @@ pub async fn execute_receipt_url_migration(
 pub async fn drop_receipt_path_column_command(
     app_handle: AppHandle,
 ) -> Result<MigrationResult, String> {
+    let _operation = begin_operation(OperationKind::Migration, "receipt_pathカラム削除");
     let conn =
         initialize_database(&app_handle).map_err(|e| format!("データベース接続エラー: {e}"))?;
 
@@ -256,6 +263,7 @@ pub struct DetailedMigrationInfo {
 pub async fn execute_comprehensive_data_migration_command(
     app_handle: AppHandle,
 ) -> Result<super::service::DataMigrationResult, String> {
+    let _operation = begin_operation(OperationKind::Migration, "包括的データ移行");
     let conn =
         initialize_database(&app_handle).map_err(|e| format!("データベース接続エラー: {e}"))?;
 
@@ -266,17 +274,18 @@ pub async fn execute_comprehensive_data_migration_command(
 /// 自動マイグレーションシステムの状態を確認する
 ///
 /// 新しい自動マイグレーションシステムを使用して、詳細なマイグレーション状態を取得します。
+/// 次に適用されるマイグレーション、前回適用からの経過日数、リスクレベルも併せて返します。
 /// 要件7.1, 7.2, 7.3, 7.4に対応します。
 ///
 /// # 引数
 /// * `app_handle` - Tauriアプリケーションハンドル
 ///
 /// # 戻り値
-/// 詳細なマイグレーション状態レポート
+/// 自動マイグレーション状態
 #[tauri::command]
 pub async fn check_auto_migration_status(
     app_handle: AppHandle,
-) -> Result<MigrationStatusReport, String> {
+) -> Result<AutoMigrationStatus, String> {
     let conn =
         initialize_database(&app_handle).map_err(|e| format!("データベース接続エラー: {e}"))?;
 
@@ -285,9 +294,12 @@ pub async fn check_auto_migration_status(
         .map_err(|e| format!("自動マイグレーションサービス初期化エラー: {e}"))?;
 
     // マイグレーション状態を確認
-    auto_migration_service
+    let status_report = auto_migration_service
         .check_migration_status(&conn)
-        .map_err(|e| format!("マイグレーション状態確認エラー: {e}"))
+        .map_err(|e| format!("マイグレーション状態確認エラー: {e}"))?;
+
+    let now = Utc::now().with_timezone(&Tokyo).fixed_offset();
+    Ok(AutoMigrationStatus::from_report(status_report, now))
 }
 
 /// 自動マイグレーションシステムの詳細情報を取得する
@@ -494,3 +506,29 @@ mod tests {
     }
     */
 }
+
+/// 代表的なクエリの実行計画を確認する
+///
+/// # 引数
+/// * `app_handle` - Tauriアプリケーションハンドル
+///
+/// # 戻り値
+/// クエリごとの実行計画とインデックス使用状況
+#[tauri::command]
+pub async fn explain_query_plans(app_handle: AppHandle) -> Result<Vec<QueryPlanReport>, String> {
+    let conn =
+        initialize_database(&app_handle).map_err(|e| format!("データベース接続エラー: {e}"))?;
+
+    let reports = explain_representative_queries(&conn)
+        .map_err(|e| format!("実行計画の取得に失敗しました: {e}"))?;
+
+    for report in reports.iter().filter(|report| !report.uses_index) {
+        log::warn!(
+            "インデックスを使用していないクエリがあります: name={}, plan={:?}",
+            report.name,
+            report.plan
+        );
+    }
+
+    Ok(reports)
+}
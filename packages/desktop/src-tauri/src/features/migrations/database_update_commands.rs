@@ -3,8 +3,11 @@
 //! R2ユーザーディレクトリ移行に伴うデータベース更新処理のTauriコマンド
 
 use super::database_updater::{
-    DatabaseStatistics, DatabaseUpdateResult, DatabaseUpdater, UrlUpdateItem,
+    DatabaseStatistics, DatabaseUpdateResult, DatabaseUpdater, ReceiptUrlRebaseItem, UrlUpdateItem,
 };
+use crate::features::receipts::url::ReceiptUrlConfig;
+use crate::shared::database::connection::get_database_connection;
+use crate::shared::operations::{begin_operation, OperationKind};
 use log::{info, warn};
 use serde::{Deserialize, Serialize};
 
@@ -106,6 +109,7 @@ pub async fn execute_database_update(
     }
 
     // 実際の更新処理
+    let _operation = begin_operation(OperationKind::DatabaseUpdate, "レガシーURLの一括更新");
     let legacy_items = DatabaseUpdater::detect_legacy_urls().await.map_err(|e| {
         let error_msg = format!("レガシーURL検出エラー: {e}");
         warn!("{}", error_msg);
@@ -140,6 +144,66 @@ pub async fn execute_database_update(
     Ok(result)
 }
 
+/// receipt_url付け替え結果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReceiptUrlRebaseResult {
+    /// 付け替え対象の件数
+    pub total_records: usize,
+    /// 更新した件数（ドライランの場合は0）
+    pub updated_count: usize,
+    /// サンプルアイテム（最初の10件）
+    pub sample_items: Vec<ReceiptUrlRebaseItem>,
+}
+
+/// receipt_urlを公開URL（カスタムドメイン）に付け替えるコマンド
+///
+/// 環境変数`R2_PUBLIC_BASE_URL`が設定されている場合に、
+/// R2エンドポイント形式のURLを公開URLに付け替えます。
+///
+/// # 引数
+/// * `dry_run` - ドライランモード（検出のみ）
+///
+/// # 戻り値
+/// 付け替え結果
+#[tauri::command]
+pub async fn rebase_receipt_urls(dry_run: bool) -> Result<ReceiptUrlRebaseResult, String> {
+    info!("receipt_url付け替えコマンドを開始します (dry_run: {dry_run})");
+
+    let config = ReceiptUrlConfig::from_env();
+    if config.public_base_url.is_none() {
+        return Err("R2_PUBLIC_BASE_URLが設定されていません".to_string());
+    }
+
+    let conn = get_database_connection()
+        .await
+        .map_err(|e| format!("データベース接続エラー: {e}"))?;
+    let items = DatabaseUpdater::plan_receipt_url_rebase(&conn, &config)
+        .map_err(|e| format!("receipt_url付け替え対象の検出エラー: {e}"))?;
+
+    let updated_count = if dry_run {
+        0
+    } else {
+        let _operation = begin_operation(OperationKind::DatabaseUpdate, "receipt_urlの付け替え");
+        DatabaseUpdater::apply_receipt_url_rebase(&conn, &items).map_err(|e| {
+            let error_msg = format!("receipt_url付け替えエラー: {e}");
+            warn!("{}", error_msg);
+            error_msg
+        })?
+    };
+
+    info!(
+        "receipt_url付け替えコマンド完了: 対象={}, 更新={}",
+        items.len(),
+        updated_count
+    );
+
+    Ok(ReceiptUrlRebaseResult {
+        total_records: items.len(),
+        updated_count,
+        sample_items: items.into_iter().take(10).collect(),
+    })
+}
+
 /// データベース統計取得コマンド
 ///
 /// # 戻り値
@@ -188,6 +252,7 @@ pub async fn update_specific_receipt_urls(
         });
     }
 
+    let _operation = begin_operation(OperationKind::DatabaseUpdate, "receipt_urlの個別更新");
     let result = DatabaseUpdater::update_receipt_urls_batch(update_items, Some(50))
         .await
         .map_err(|e| {
@@ -239,6 +304,21 @@ pub async fn check_database_url_integrity() -> Result<DatabaseIntegrityResult, S
         ));
     }
 
+    // R2エンドポイント形式・公開URL形式のどちらでもないURLがないかチェック
+    let conn = get_database_connection()
+        .await
+        .map_err(|e| format!("データベース接続エラー: {e}"))?;
+    let unrecognized =
+        DatabaseUpdater::find_unrecognized_receipt_urls(&conn, &ReceiptUrlConfig::from_env())
+            .map_err(|e| format!("receipt_url形式チェックエラー: {e}"))?;
+    if !unrecognized.is_empty() {
+        issues.push(format!(
+            "{}件のreceipt_urlが認識できない形式です（例: 経費ID {}）",
+            unrecognized.len(),
+            unrecognized[0].0
+        ));
+    }
+
     let is_consistent = issues.is_empty();
 
     let issues_len = issues.len();
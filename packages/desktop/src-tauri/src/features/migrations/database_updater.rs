@@ -4,10 +4,11 @@
 //! トランザクション処理による整合性保証、更新失敗時のロールバック機能、
 //! 更新後検証機能を含みます。
 
+use crate::features::receipts::url::{parse_receipt_url, rebase_receipt_url, ReceiptUrlConfig};
 use crate::shared::database::connection::get_database_connection;
 use crate::shared::errors::{AppError, AppResult};
 use log::{debug, error, info, warn};
-use rusqlite::{OptionalExtension, Transaction};
+use rusqlite::{Connection, OptionalExtension, Transaction};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -41,6 +42,17 @@ pub struct UrlUpdateItem {
     pub user_id: i64,
 }
 
+/// receipt_urlの付け替えアイテム（R2エンドポイント形式 → 公開URL）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReceiptUrlRebaseItem {
+    /// 経費ID
+    pub expense_id: i64,
+    /// 古いURL
+    pub old_url: String,
+    /// 新しいURL
+    pub new_url: String,
+}
+
 /// データベース更新サービス
 pub struct DatabaseUpdater;
 
@@ -519,6 +531,126 @@ impl DatabaseUpdater {
         )))
     }
 
+    /// 公開URL（カスタムドメイン）に付け替えるreceipt_urlを検出する
+    ///
+    /// # 引数
+    /// * `conn` - データベース接続
+    /// * `config` - 領収書URLの設定（公開URLのベースが未設定の場合は常に空）
+    ///
+    /// # 戻り値
+    /// 付け替え対象の一覧
+    pub fn plan_receipt_url_rebase(
+        conn: &Connection,
+        config: &ReceiptUrlConfig,
+    ) -> AppResult<Vec<ReceiptUrlRebaseItem>> {
+        let items = Self::receipt_urls(conn)?
+            .into_iter()
+            .filter_map(|(expense_id, old_url)| {
+                rebase_receipt_url(&old_url, config).map(|new_url| ReceiptUrlRebaseItem {
+                    expense_id,
+                    old_url,
+                    new_url,
+                })
+            })
+            .collect();
+        Ok(items)
+    }
+
+    /// receipt_urlを付け替える
+    ///
+    /// 経費・レシートキャッシュ・注釈のURLを1つのトランザクションで更新します。
+    /// 検出後にURLが変更された経費は更新しません。
+    ///
+    /// # 引数
+    /// * `conn` - データベース接続
+    /// * `items` - 付け替え対象の一覧
+    ///
+    /// # 戻り値
+    /// 更新された経費の件数
+    pub fn apply_receipt_url_rebase(
+        conn: &Connection,
+        items: &[ReceiptUrlRebaseItem],
+    ) -> AppResult<usize> {
+        let tx = conn.unchecked_transaction()?;
+        let now = chrono::Utc::now()
+            .with_timezone(&chrono_tz::Asia::Tokyo)
+            .to_rfc3339();
+
+        let mut updated_count = 0;
+        for item in items {
+            if Self::rebase_receipt_url_in(&tx, item, &now)? {
+                updated_count += 1;
+            }
+        }
+
+        tx.commit()?;
+        info!("receipt_urlの付け替え完了: {updated_count}件");
+        Ok(updated_count)
+    }
+
+    /// トランザクション内で1件の経費のreceipt_urlを付け替える
+    ///
+    /// 経費・レシートキャッシュ・注釈のURLを更新します。
+    /// 経費のURLが`old_url`から変更されている場合は何も更新しません。
+    ///
+    /// # 引数
+    /// * `conn` - トランザクション中のデータベース接続
+    /// * `item` - 付け替え対象
+    /// * `now` - 更新日時（RFC3339形式、JST）
+    ///
+    /// # 戻り値
+    /// 経費が更新された場合はtrue
+    pub(crate) fn rebase_receipt_url_in(
+        conn: &Connection,
+        item: &ReceiptUrlRebaseItem,
+        now: &str,
+    ) -> AppResult<bool> {
+        // 経費のURL変更時に注釈が削除されないよう、先に注釈のURLを付け替える
+        conn.execute(
+            "UPDATE annotations SET receipt_url = ?1
+             WHERE expense_id = ?2 AND receipt_url = ?3
+               AND EXISTS (SELECT 1 FROM expenses WHERE id = ?2 AND receipt_url = ?3)",
+            rusqlite::params![item.new_url, item.expense_id, item.old_url],
+        )?;
+        let updated = conn.execute(
+            "UPDATE expenses SET receipt_url = ?1, updated_at = ?2
+             WHERE id = ?3 AND receipt_url = ?4",
+            rusqlite::params![item.new_url, now, item.expense_id, item.old_url],
+        )?;
+        conn.execute(
+            "UPDATE OR IGNORE receipt_cache SET receipt_url = ?1 WHERE receipt_url = ?2",
+            rusqlite::params![item.new_url, item.old_url],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// 認識できない形式のreceipt_urlを検出する
+    ///
+    /// # 戻り値
+    /// (経費ID, URL) の一覧
+    pub fn find_unrecognized_receipt_urls(
+        conn: &Connection,
+        config: &ReceiptUrlConfig,
+    ) -> AppResult<Vec<(i64, String)>> {
+        Ok(Self::receipt_urls(conn)?
+            .into_iter()
+            .filter(|(_, url)| parse_receipt_url(url, config).is_err())
+            .collect())
+    }
+
+    /// receipt_urlを持つ経費の (ID, URL) 一覧を取得する
+    fn receipt_urls(conn: &Connection) -> AppResult<Vec<(i64, String)>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, receipt_url FROM expenses
+             WHERE receipt_url IS NOT NULL AND receipt_url != ''
+             ORDER BY id",
+        )?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
     /// データベース統計情報を取得
     ///
     /// # 戻り値
@@ -645,6 +777,101 @@ mod tests {
         assert_eq!(result.duration_ms, 1500);
     }
 
+    fn create_rebase_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE expenses (id INTEGER PRIMARY KEY, receipt_url TEXT, updated_at TEXT);
+             CREATE TABLE receipt_cache (id INTEGER PRIMARY KEY, receipt_url TEXT NOT NULL UNIQUE);
+             INSERT INTO expenses (id, receipt_url) VALUES
+               (1, 'https://account.r2.cloudflarestorage.com/orano-keihi/users/a/receipts/1/a.png'),
+               (2, 'https://receipts.example.com/users/a/receipts/2/b.png'),
+               (3, NULL),
+               (4, 'https://other.example.com/c.png');
+             INSERT INTO receipt_cache (receipt_url) VALUES
+               ('https://account.r2.cloudflarestorage.com/orano-keihi/users/a/receipts/1/a.png');",
+        )
+        .unwrap();
+        crate::features::receipts::annotations::create_annotations_table(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO annotations (expense_id, receipt_url, user_id, x, y, width, height, created_at)
+             SELECT id, receipt_url, 'a', 0.1, 0.1, 0.5, 0.1, 'now' FROM expenses WHERE id = 1",
+            [],
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_receipt_url_rebase() {
+        let conn = create_rebase_test_db();
+        let config = ReceiptUrlConfig::with_public_base_url("https://receipts.example.com");
+
+        // 公開URLのベースが未設定の場合は何もしない
+        assert!(
+            DatabaseUpdater::plan_receipt_url_rebase(&conn, &ReceiptUrlConfig::default())
+                .unwrap()
+                .is_empty()
+        );
+
+        let items = DatabaseUpdater::plan_receipt_url_rebase(&conn, &config).unwrap();
+        assert_eq!(
+            items,
+            vec![ReceiptUrlRebaseItem {
+                expense_id: 1,
+                old_url:
+                    "https://account.r2.cloudflarestorage.com/orano-keihi/users/a/receipts/1/a.png"
+                        .to_string(),
+                new_url: "https://receipts.example.com/users/a/receipts/1/a.png".to_string(),
+            }]
+        );
+
+        assert_eq!(
+            DatabaseUpdater::apply_receipt_url_rebase(&conn, &items).unwrap(),
+            1
+        );
+        let cached: String = conn
+            .query_row("SELECT receipt_url FROM receipt_cache", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(cached, items[0].new_url);
+        // 注釈は削除されずに新しいURLへ付け替えられる
+        let annotated: String = conn
+            .query_row("SELECT receipt_url FROM annotations", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(annotated, items[0].new_url);
+
+        // 2回目は対象なし、再適用しても変化しない
+        assert!(DatabaseUpdater::plan_receipt_url_rebase(&conn, &config)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            DatabaseUpdater::apply_receipt_url_rebase(&conn, &items).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_find_unrecognized_receipt_urls() {
+        let conn = create_rebase_test_db();
+
+        // カスタムドメイン未設定時は公開URLをすべて認識する
+        assert!(DatabaseUpdater::find_unrecognized_receipt_urls(
+            &conn,
+            &ReceiptUrlConfig::default()
+        )
+        .unwrap()
+        .is_empty());
+
+        // カスタムドメイン設定時は、両方の形式を認識し、別ホストのみ検出する
+        let config = ReceiptUrlConfig::with_public_base_url("https://receipts.example.com");
+        let unrecognized = DatabaseUpdater::find_unrecognized_receipt_urls(&conn, &config).unwrap();
+        assert_eq!(
+            unrecognized,
+            vec![(4, "https://other.example.com/c.png".to_string())]
+        );
+    }
+
     #[tokio::test]
     async fn test_database_statistics_structure() {
         let stats = DatabaseStatistics {
@@ -53,6 +53,11 @@ pub enum ErrorHandlingResult {
         /// リセット対象
         target: String,
     },
+    /// ユーザー操作によりキャンセルされた
+    Cancelled {
+        /// キャンセル理由
+        reason: String,
+    },
 }
 
 /// リトライ実行結果
@@ -84,8 +89,10 @@ pub struct AttemptDetail {
 /// エラー統計
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ErrorStatistics {
-    /// 総エラー数
+    /// 総エラー数（キャンセルは含まない）
     pub total_errors: usize,
+    /// キャンセル数
+    pub cancelled_count: usize,
     /// エラータイプ別カウント
     pub error_type_counts: HashMap<String, usize>,
     /// 重要度別カウント
@@ -172,6 +179,15 @@ impl ComprehensiveErrorHandler {
         user_id: Option<i64>,
         context: Option<&str>,
     ) -> ErrorHandlingResult {
+        // キャンセルは失敗として扱わない
+        if error.is_cancelled() {
+            self.update_cancelled_statistics();
+            self.log_error(error, migration_log_id, context);
+            return ErrorHandlingResult::Cancelled {
+                reason: error.to_string(),
+            };
+        }
+
         // エラー統計を更新
         self.update_error_statistics(error);
 
@@ -228,6 +244,9 @@ impl ComprehensiveErrorHandler {
             ErrorAction::Reset => ErrorHandlingResult::Reset {
                 target: "migration_state".to_string(),
             },
+            ErrorAction::Cancel => ErrorHandlingResult::Cancelled {
+                reason: error.to_string(),
+            },
         }
     }
 
@@ -281,6 +300,28 @@ impl ComprehensiveErrorHandler {
                         attempt_details,
                     };
                 }
+                Err(error) if error.is_cancelled() => {
+                    // キャンセルはリトライせず、失敗統計にも含めない
+                    attempt_details.push(AttemptDetail {
+                        attempt_number: attempts,
+                        duration: attempt_start.elapsed(),
+                        result: Err(error.clone()),
+                        delay: None,
+                    });
+                    self.update_cancelled_statistics();
+
+                    info!(
+                        "操作がキャンセルされました (試行 {}): {}",
+                        attempts, error_context
+                    );
+
+                    return RetryResult {
+                        result: Err(error),
+                        attempts,
+                        total_duration: start_time.elapsed(),
+                        attempt_details,
+                    };
+                }
                 Err(error) => {
                     let attempt_duration = attempt_start.elapsed();
 
@@ -524,6 +565,13 @@ impl ComprehensiveErrorHandler {
         }
     }
 
+    /// キャンセル統計を更新
+    fn update_cancelled_statistics(&self) {
+        if let Ok(mut stats) = self.error_statistics.lock() {
+            stats.cancelled_count += 1;
+        }
+    }
+
     /// リトライ成功統計を更新
     fn update_retry_success_statistics(&self) {
         if let Ok(mut stats) = self.error_statistics.lock() {
@@ -583,6 +631,7 @@ impl ComprehensiveErrorHandler {
         let mut report = String::new();
         report.push_str("=== エラー統計レポート ===\n");
         report.push_str(&format!("総エラー数: {}\n", stats.total_errors));
+        report.push_str(&format!("キャンセル数: {}\n", stats.cancelled_count));
         report.push_str(&format!("リトライ成功数: {}\n", stats.retry_success_count));
         report.push_str(&format!("リトライ失敗数: {}\n", stats.retry_failure_count));
         report.push_str(&format!(
@@ -672,6 +721,9 @@ pub fn handle_migration_error_sync(
         ErrorAction::Reset => ErrorHandlingResult::Reset {
             target: "migration_state".to_string(),
         },
+        ErrorAction::Cancel => ErrorHandlingResult::Cancelled {
+            reason: error.to_string(),
+        },
     }
 }
 
@@ -776,6 +828,54 @@ mod tests {
         assert_eq!(result.attempts, 1); // リトライ不可能なエラーなので1回のみ
     }
 
+    #[tokio::test]
+    async fn test_cancellation_during_retry_is_not_failure() {
+        let handler = ComprehensiveErrorHandler::new(None);
+        let call_count = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        // 1回目は一時的エラー、リトライ中にユーザーがキャンセル
+        let operation = {
+            let call_count = call_count.clone();
+            move || {
+                let call_count = call_count.clone();
+                async move {
+                    let count = call_count.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+                    if count == 1 {
+                        Err(MigrationError::Timeout {
+                            message: "タイムアウト".to_string(),
+                            operation: "upload".to_string(),
+                            timeout_seconds: 1,
+                        })
+                    } else {
+                        Err(MigrationError::Cancelled {
+                            message: "移行がキャンセルされました".to_string(),
+                            operation: "upload".to_string(),
+                        })
+                    }
+                }
+            }
+        };
+
+        let result: RetryResult<()> = handler
+            .execute_with_retry(operation, "テスト操作", Some(1))
+            .await;
+
+        assert!(result.result.unwrap_err().is_cancelled());
+        assert_eq!(result.attempts, 2);
+
+        let cancelled = MigrationError::Cancelled {
+            message: "移行がキャンセルされました".to_string(),
+            operation: "batch".to_string(),
+        };
+        let handling = handler.handle_error(&cancelled, Some(1), None, None).await;
+        assert!(matches!(handling, ErrorHandlingResult::Cancelled { .. }));
+
+        let stats = handler.get_error_statistics();
+        assert_eq!(stats.cancelled_count, 2);
+        assert_eq!(stats.total_errors, 0);
+        assert_eq!(stats.retry_failure_count, 0);
+    }
+
     #[test]
     fn test_error_statistics() {
         let handler = ComprehensiveErrorHandler::new(None);
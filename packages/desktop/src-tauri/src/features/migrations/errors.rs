@@ -4,7 +4,7 @@
 //! 包括的なエラーハンドリング機能を提供します。
 
 use crate::shared::errors::{AppError, ErrorSeverity};
-use log::{error, warn};
+use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -125,6 +125,10 @@ pub enum MigrationError {
         endpoint: String,
         retry_count: usize,
     },
+
+    /// ユーザー操作によるキャンセル（失敗としては扱わない）
+    #[error("キャンセル: {message}")]
+    Cancelled { message: String, operation: String },
 }
 
 impl MigrationError {
@@ -145,6 +149,7 @@ impl MigrationError {
             MigrationError::ResourceExhaustion { .. } => ErrorSeverity::High,
             MigrationError::MigrationState { .. } => ErrorSeverity::Medium,
             MigrationError::ExternalDependency { .. } => ErrorSeverity::Medium,
+            MigrationError::Cancelled { .. } => ErrorSeverity::Low,
         }
     }
 
@@ -165,6 +170,7 @@ impl MigrationError {
             MigrationError::ResourceExhaustion { .. } => "resource",
             MigrationError::MigrationState { .. } => "state",
             MigrationError::ExternalDependency { .. } => "external",
+            MigrationError::Cancelled { .. } => "cancelled",
         }
     }
 
@@ -185,6 +191,7 @@ impl MigrationError {
             MigrationError::ResourceExhaustion { .. } => "MIG_RESOURCE".to_string(),
             MigrationError::MigrationState { .. } => "MIG_STATE".to_string(),
             MigrationError::ExternalDependency { .. } => "MIG_EXTERNAL".to_string(),
+            MigrationError::Cancelled { .. } => "MIG_CANCELLED".to_string(),
         }
     }
 
@@ -231,6 +238,7 @@ impl MigrationError {
             MigrationError::ExternalDependency { .. } => {
                 "外部サービスとの通信でエラーが発生しました。".to_string()
             }
+            MigrationError::Cancelled { .. } => "処理はキャンセルされました。".to_string(),
         }
     }
 
@@ -420,6 +428,13 @@ impl MigrationError {
                     "retry_count": retry_count
                 })
             }
+            MigrationError::Cancelled { message, operation } => {
+                serde_json::json!({
+                    "error_type": "cancelled",
+                    "message": message,
+                    "operation": operation
+                })
+            }
         }
     }
 
@@ -444,6 +459,11 @@ impl MigrationError {
         )
     }
 
+    /// ユーザー操作によるキャンセルかどうか
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, MigrationError::Cancelled { .. })
+    }
+
     /// エラーの詳細情報を取得（デバッグ用）
     pub fn debug_info(&self) -> String {
         format!("{self:?}")
@@ -453,6 +473,10 @@ impl MigrationError {
 /// MigrationErrorからAppErrorへの変換
 impl From<MigrationError> for AppError {
     fn from(migration_error: MigrationError) -> Self {
+        if let MigrationError::Cancelled { message, .. } = migration_error {
+            return AppError::Cancelled(message);
+        }
+
         match migration_error.severity() {
             ErrorSeverity::Critical => AppError::Security(migration_error.to_string()),
             ErrorSeverity::High => AppError::Database(migration_error.to_string()),
@@ -507,6 +531,10 @@ impl TryFrom<AppError> for MigrationError {
                 key: "unknown".to_string(),
                 status_code: None,
             }),
+            AppError::Cancelled(msg) => Ok(MigrationError::Cancelled {
+                message: msg,
+                operation: "unknown".to_string(),
+            }),
             _ => Err(app_error),
         }
     }
@@ -521,6 +549,17 @@ impl MigrationErrorHandler {
         let metadata = error.log_metadata();
         let context_str = context.unwrap_or("unknown");
 
+        // キャンセルは失敗ではないため情報レベルで記録
+        if error.is_cancelled() {
+            info!(
+                "Migration Cancelled [{}]: {} | Context: {}",
+                error.error_code(),
+                error,
+                context_str
+            );
+            return;
+        }
+
         match error.severity() {
             ErrorSeverity::Critical => {
                 error!(
@@ -578,6 +617,7 @@ impl MigrationErrorHandler {
             MigrationError::ResourceExhaustion { .. } => ErrorAction::Pause,
             MigrationError::MigrationState { .. } => ErrorAction::Reset,
             MigrationError::ExternalDependency { .. } => ErrorAction::Retry,
+            MigrationError::Cancelled { .. } => ErrorAction::Cancel,
         }
     }
 
@@ -632,6 +672,8 @@ pub enum ErrorAction {
     Pause,
     /// 状態をリセット
     Reset,
+    /// キャンセルとして終了（失敗扱いしない）
+    Cancel,
 }
 
 /// リトライ戦略
@@ -777,6 +819,25 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_cancelled_conversion() {
+        let error = MigrationError::Cancelled {
+            message: "移行がキャンセルされました".to_string(),
+            operation: "batch".to_string(),
+        };
+
+        assert_eq!(
+            MigrationErrorHandler::handle_error(&error),
+            ErrorAction::Cancel
+        );
+        assert_eq!(error.error_code(), "MIG_CANCELLED");
+        assert!(!error.is_retryable());
+
+        let app_error: AppError = error.into();
+        assert!(app_error.is_cancelled());
+        assert!(MigrationError::try_from(app_error).unwrap().is_cancelled());
+    }
+
     #[test]
     fn test_user_message() {
         let error = MigrationError::IntegrityValidation {
@@ -41,6 +41,7 @@ impl From<ErrorSeverity> for LogLevel {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StructuredLogEntry {
     /// タイムスタンプ（JST）
+    #[serde(with = "crate::shared::utils::jst_datetime")]
     pub timestamp: DateTime<Utc>,
     /// ログレベル
     pub level: LogLevel,
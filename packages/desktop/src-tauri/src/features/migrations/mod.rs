@@ -10,6 +10,7 @@ pub mod database_updater;
 pub mod error_handler;
 pub mod errors;
 pub mod logging;
+pub mod query_indexes;
 pub mod r2_user_directory_migration;
 pub mod security_audit;
 pub mod service;
@@ -26,11 +27,15 @@ mod integration_tests;
 #[cfg(test)]
 mod error_scenario_tests;
 
+#[cfg(test)]
+mod upgrade_fixture_tests;
+
 // 公開インターフェース
 pub use auto_migration::{
-    AppliedMigration, AutoMigrationResult, AutoMigrationService, MigrationDefinition,
-    MigrationError, MigrationErrorType, MigrationExecutionResult, MigrationExecutor,
-    MigrationRegistry, MigrationStatusReport, MigrationTable,
+    AppliedMigration, AutoMigrationResult, AutoMigrationService, AutoMigrationStatus,
+    MigrationDefinition, MigrationError, MigrationErrorType, MigrationExecutionResult,
+    MigrationExecutor, MigrationRegistry, MigrationRiskLevel, MigrationStatusReport,
+    MigrationTable,
 };
 
 pub use commands::{
@@ -42,12 +47,13 @@ pub use commands::{
 
 pub use database_update_commands::{
     check_database_url_integrity, detect_legacy_receipt_urls, execute_database_update,
-    get_database_statistics, update_specific_receipt_urls, DatabaseIntegrityResult,
-    DatabaseUpdateParams, LegacyUrlDetectionResult,
+    get_database_statistics, rebase_receipt_urls, update_specific_receipt_urls,
+    DatabaseIntegrityResult, DatabaseUpdateParams, LegacyUrlDetectionResult,
+    ReceiptUrlRebaseResult,
 };
 
 pub use database_updater::{
-    DatabaseStatistics, DatabaseUpdateResult, DatabaseUpdater, UrlUpdateItem,
+    DatabaseStatistics, DatabaseUpdateResult, DatabaseUpdater, ReceiptUrlRebaseItem, UrlUpdateItem,
 };
 
 pub use error_handler::{
@@ -169,3 +175,62 @@ pub const SUPPORTED_MIGRATIONS: &[&str] = &[
 /// マイグレーション機能の説明
 pub const DESCRIPTION: &str =
     "データベーススキーマのマイグレーション、バックアップ、復元機能、包括的エラーハンドリング、構造化ログ、セキュリティ監査機能を提供します";
+
+use crate::shared::capabilities::{Capability, Requirement};
+
+/// マイグレーション機能の利用者向け機能
+pub const CAPABILITIES: &[Capability] = &[
+    Capability::new(
+        "migrations.check_integrity",
+        "capability.migrations.check_integrity",
+        "check_database_integrity",
+    ),
+    Capability::new(
+        "migrations.execute_user_authentication",
+        "capability.migrations.execute_user_authentication",
+        "execute_user_authentication_migration",
+    )
+    .mutating()
+    .internal(),
+    Capability::new(
+        "migrations.execute_receipt_url",
+        "capability.migrations.execute_receipt_url",
+        "execute_receipt_url_migration",
+    )
+    .mutating()
+    .internal(),
+    Capability::new(
+        "migrations.drop_receipt_path_column",
+        "capability.migrations.drop_receipt_path_column",
+        "drop_receipt_path_column_command",
+    )
+    .destructive()
+    .internal(),
+    Capability::new(
+        "migrations.execute_database_update",
+        "capability.migrations.execute_database_update",
+        "execute_database_update",
+    )
+    .destructive()
+    .internal(),
+    Capability::new(
+        "migrations.update_specific_receipt_urls",
+        "capability.migrations.update_specific_receipt_urls",
+        "update_specific_receipt_urls",
+    )
+    .mutating()
+    .internal(),
+    Capability::new(
+        "migrations.rebase_receipt_urls",
+        "capability.migrations.rebase_receipt_urls",
+        "rebase_receipt_urls",
+    )
+    .destructive()
+    .internal(),
+    Capability::new(
+        "migrations.explain_query_plans",
+        "capability.migrations.explain_query_plans",
+        "explain_query_plans",
+    )
+    .requires(&[Requirement::DebugBuild]),
+];
@@ -0,0 +1,318 @@
+//! 検索パターンに合わせた複合インデックスのマイグレーション
+//!
+//! ユーザー認証の導入後は、ほぼすべての検索が`user_id`で絞り込まれるため、
+//! 単一カラムのインデックスでは全件走査やメモリ上のソートが発生していました。
+//! このモジュールは複合インデックスを作成し、それに包含される冗長な
+//! 単一カラムのインデックスを削除します。
+//! また、代表的なクエリの実行計画を確認する診断機能を提供します。
+
+use crate::features::migrations::auto_migration::executor::MigrationExecutorTrait;
+use crate::features::migrations::auto_migration::models::{
+    ExecutableMigrationDefinition, MigrationDefinition,
+};
+use crate::features::migrations::auto_migration::registry::MigrationRegistry;
+use crate::shared::errors::AppResult;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+/// 複合インデックスの作成と冗長なインデックスの削除
+const QUERY_INDEXES_SQL: &str = "
+-- ユーザー・日付での経費検索
+CREATE INDEX IF NOT EXISTS idx_expenses_user_date ON expenses(user_id, date);
+-- ユーザー・カテゴリ・日付での集計
+CREATE INDEX IF NOT EXISTS idx_expenses_user_category_date ON expenses(user_id, category, date);
+-- ユーザーごとの古いキャッシュの削除
+CREATE INDEX IF NOT EXISTS idx_receipt_cache_user_accessed ON receipt_cache(user_id, last_accessed);
+
+-- 複合インデックスの先頭カラムと重複するもの
+DROP INDEX IF EXISTS idx_expenses_user_id;
+DROP INDEX IF EXISTS idx_receipt_cache_user_id;
+-- receipt_urlのUNIQUE制約によるインデックスと重複するもの
+DROP INDEX IF EXISTS idx_receipt_cache_url;
+";
+
+/// 複合インデックスマイグレーション実行器
+pub struct QueryIndexesMigration;
+
+impl MigrationExecutorTrait for QueryIndexesMigration {
+    fn execute(&self, conn: &Connection) -> Result<(), String> {
+        log::info!("複合インデックスを作成します");
+
+        conn.execute_batch(QUERY_INDEXES_SQL)
+            .map_err(|e| format!("複合インデックス作成エラー: {e}"))?;
+
+        log::info!("複合インデックスの作成が完了しました");
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "006_add_query_indexes"
+    }
+}
+
+/// 複合インデックス用マイグレーション定義を取得する
+///
+/// # 戻り値
+/// 実行可能なマイグレーション定義
+pub fn get_query_indexes_definition() -> ExecutableMigrationDefinition {
+    let definition = MigrationDefinition::new(
+        "006_add_query_indexes".to_string(),
+        "3.2.0".to_string(),
+        "検索パターンに合わせた複合インデックスの作成".to_string(),
+        MigrationRegistry::calculate_checksum(QUERY_INDEXES_SQL),
+    );
+
+    ExecutableMigrationDefinition::new(definition, Box::new(QueryIndexesMigration))
+}
+
+/// 実行計画を確認する代表的なクエリ（名前, SQL）
+///
+/// パラメータはすべてNULLで実行計画を取得します。
+const REPRESENTATIVE_QUERIES: &[(&str, &str)] = &[
+    (
+        "expenses_by_user_and_date",
+        "SELECT id, date, amount, category FROM expenses
+         WHERE user_id = ?1 AND date BETWEEN ?2 AND ?3 ORDER BY date DESC",
+    ),
+    (
+        "category_totals_by_user",
+        "SELECT category, COUNT(*), SUM(amount) FROM expenses
+         WHERE user_id = ?1 AND date BETWEEN ?2 AND ?3 GROUP BY category",
+    ),
+    (
+        "category_expenses_by_user",
+        "SELECT id, date, amount FROM expenses
+         WHERE user_id = ?1 AND category = ?2 AND date BETWEEN ?3 AND ?4 ORDER BY date",
+    ),
+    (
+        "receipt_cache_lookup",
+        "SELECT local_path FROM receipt_cache WHERE receipt_url = ?1 AND user_id = ?2",
+    ),
+    (
+        "receipt_cache_oldest_by_user",
+        "SELECT receipt_url FROM receipt_cache WHERE user_id = ?1 ORDER BY last_accessed ASC",
+    ),
+    (
+        "active_subscriptions_by_user",
+        "SELECT id, name, amount FROM subscriptions WHERE user_id = ?1 AND is_active = 1",
+    ),
+];
+
+/// クエリの実行計画の診断結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryPlanReport {
+    /// クエリ名
+    pub name: String,
+    /// 実行計画（EXPLAIN QUERY PLANのdetail列）
+    pub plan: Vec<String>,
+    /// 使用しているインデックス
+    pub indexes: Vec<String>,
+    /// すべてのテーブルアクセスでインデックスを使用しているか
+    pub uses_index: bool,
+    /// 並べ替え・グループ化に一時B-Treeを使用しているか
+    pub uses_temp_btree: bool,
+}
+
+/// 実行計画の1行からインデックス名を取り出す
+fn index_name(detail: &str) -> Option<String> {
+    let (_, rest) = detail.split_once(" INDEX ")?;
+    rest.split_whitespace().next().map(str::to_string)
+}
+
+/// 代表的なクエリの実行計画を確認する
+///
+/// # 引数
+/// * `conn` - データベース接続
+///
+/// # 戻り値
+/// クエリごとの診断結果
+pub fn explain_query_plans(conn: &Connection) -> AppResult<Vec<QueryPlanReport>> {
+    REPRESENTATIVE_QUERIES
+        .iter()
+        .map(|(name, sql)| {
+            let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {sql}"))?;
+            let params = vec![rusqlite::types::Null; stmt.parameter_count()];
+            let plan = stmt
+                .query_map(rusqlite::params_from_iter(params), |row| {
+                    row.get::<_, String>(3)
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let accesses: Vec<&String> = plan
+                .iter()
+                .filter(|detail| detail.starts_with("SCAN ") || detail.starts_with("SEARCH "))
+                .collect();
+            let uses_index = !accesses.is_empty()
+                && accesses
+                    .iter()
+                    .all(|detail| detail.contains(" INDEX ") || detail.contains("PRIMARY KEY"));
+
+            Ok(QueryPlanReport {
+                name: name.to_string(),
+                indexes: plan
+                    .iter()
+                    .filter_map(|detail| index_name(detail))
+                    .collect(),
+                uses_index,
+                uses_temp_btree: plan.iter().any(|detail| detail.contains("TEMP B-TREE")),
+                plan,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::migrations::service::{migrate_user_authentication, run_migrations};
+    use std::time::{Duration, Instant};
+
+    /// 認証マイグレーションまで適用したデータベースを作成する
+    fn create_migrated_connection() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        migrate_user_authentication(&conn).unwrap();
+        conn
+    }
+
+    fn index_names(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'index'")
+            .unwrap();
+        stmt.query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_migration_replaces_redundant_indexes() {
+        let conn = create_migrated_connection();
+        assert!(index_names(&conn).contains(&"idx_expenses_user_id".to_string()));
+
+        QueryIndexesMigration.execute(&conn).unwrap();
+        // 2回目も失敗しない
+        QueryIndexesMigration.execute(&conn).unwrap();
+
+        let names = index_names(&conn);
+        for created in [
+            "idx_expenses_user_date",
+            "idx_expenses_user_category_date",
+            "idx_receipt_cache_user_accessed",
+        ] {
+            assert!(names.contains(&created.to_string()), "{created}");
+        }
+        for dropped in [
+            "idx_expenses_user_id",
+            "idx_receipt_cache_user_id",
+            "idx_receipt_cache_url",
+        ] {
+            assert!(!names.contains(&dropped.to_string()), "{dropped}");
+        }
+    }
+
+    #[test]
+    fn test_explain_query_plans_use_indexes() {
+        let conn = create_migrated_connection();
+        QueryIndexesMigration.execute(&conn).unwrap();
+
+        let reports = explain_query_plans(&conn).unwrap();
+        assert_eq!(reports.len(), REPRESENTATIVE_QUERIES.len());
+        for report in &reports {
+            assert!(report.uses_index, "{}: {:?}", report.name, report.plan);
+        }
+
+        let report = |name: &str| reports.iter().find(|r| r.name == name).unwrap();
+        assert!(report("expenses_by_user_and_date")
+            .indexes
+            .iter()
+            .any(|index| index.starts_with("idx_expenses_user_")));
+        assert!(!report("expenses_by_user_and_date").uses_temp_btree);
+        assert_eq!(
+            report("receipt_cache_oldest_by_user").indexes,
+            vec!["idx_receipt_cache_user_accessed"]
+        );
+    }
+
+    #[test]
+    fn test_aggregate_queries_with_seeded_expenses() {
+        const EXPENSE_COUNT: usize = 50_000;
+        const USER_COUNT: usize = 10;
+        let categories = ["交通費", "飲食費", "通信費", "消耗品費", "その他"];
+
+        let conn = create_migrated_connection();
+        QueryIndexesMigration.execute(&conn).unwrap();
+
+        let tx = conn.unchecked_transaction().unwrap();
+        {
+            for user_id in 1..=USER_COUNT {
+                tx.execute(
+                    "INSERT OR IGNORE INTO users (id, google_id, email, name, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, '2024-01-01', '2024-01-01')",
+                    rusqlite::params![
+                        user_id as i64,
+                        format!("google_{user_id}"),
+                        format!("user{user_id}@example.com"),
+                        format!("ユーザー{user_id}"),
+                    ],
+                )
+                .unwrap();
+            }
+            let mut stmt = tx
+                .prepare(
+                    "INSERT INTO expenses (date, amount, category, description, created_at, updated_at, user_id)
+                     VALUES (?1, ?2, ?3, NULL, ?1, ?1, ?4)",
+                )
+                .unwrap();
+            for i in 0..EXPENSE_COUNT {
+                let date = format!("2024-{:02}-{:02}", i % 12 + 1, i % 28 + 1);
+                stmt.execute(rusqlite::params![
+                    date,
+                    (i % 5000) as f64,
+                    categories[i % categories.len()],
+                    (i % USER_COUNT) as i64 + 1,
+                ])
+                .unwrap();
+            }
+        }
+        tx.commit().unwrap();
+        conn.execute_batch("ANALYZE").unwrap();
+
+        let started = Instant::now();
+        let totals: Vec<(String, i64, f64)> = conn
+            .prepare(
+                "SELECT category, COUNT(*), SUM(amount) FROM expenses
+                 WHERE user_id = ?1 AND date BETWEEN ?2 AND ?3 GROUP BY category",
+            )
+            .unwrap()
+            .query_map(rusqlite::params![1, "2024-01-01", "2024-12-31"], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let monthly: Vec<(String, f64)> = conn
+            .prepare(
+                "SELECT substr(date, 1, 7), SUM(amount) FROM expenses
+                 WHERE user_id = ?1 AND date BETWEEN ?2 AND ?3 GROUP BY substr(date, 1, 7)",
+            )
+            .unwrap()
+            .query_map(rusqlite::params![1, "2024-01-01", "2024-12-31"], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let elapsed = started.elapsed();
+
+        assert_eq!(
+            totals.iter().map(|(_, count, _)| count).sum::<i64>(),
+            (EXPENSE_COUNT / USER_COUNT) as i64
+        );
+        assert!(!monthly.is_empty());
+        assert!(
+            elapsed < Duration::from_millis(500),
+            "集計クエリに時間がかかりすぎています: {elapsed:?}"
+        );
+    }
+}
@@ -93,6 +93,7 @@ pub struct SecurityAuditEntry {
     /// 監査ID（一意識別子）
     pub audit_id: String,
     /// タイムスタンプ（JST）
+    #[serde(with = "crate::shared::utils::jst_datetime")]
     pub timestamp: DateTime<Utc>,
     /// イベントタイプ
     pub event_type: SecurityEventType,
@@ -1,4 +1,5 @@
 use crate::shared::errors::AppError;
+use crate::shared::utils::disk_space::{check_disk_space, BACKUP_HEADROOM_BYTES};
 use chrono::Utc;
 use chrono_tz::Asia::Tokyo;
 use rusqlite::Connection;
@@ -405,13 +406,83 @@ fn validate_migration(tx: &Transaction) -> Result<(), AppError> {
 ///
 /// # 戻り値
 /// 成功時はOk(())、失敗時はエラー
+///
+/// 作成前に空き容量を確認し、作成後はファイルサイズと整合性を検証します。
+/// 検証に失敗したバックアップは削除するため、復元可能として一覧に残ることはありません。
 pub fn create_backup(conn: &Connection, backup_path: &str) -> Result<(), AppError> {
-    let mut backup_conn = rusqlite::Connection::open(backup_path)?;
-    let backup = rusqlite::backup::Backup::new(conn, &mut backup_conn)?;
-    backup.run_to_completion(5, std::time::Duration::from_millis(250), None)?;
+    let source_pages = database_size(conn)?;
+    check_disk_space(
+        Path::new(backup_path),
+        source_pages.0 * source_pages.1 + BACKUP_HEADROOM_BYTES,
+    )?;
+
+    {
+        let mut backup_conn = rusqlite::Connection::open(backup_path)?;
+        let backup = rusqlite::backup::Backup::new(conn, &mut backup_conn)?;
+        backup.run_to_completion(5, std::time::Duration::from_millis(250), None)?;
+    }
+
+    let verified = verify_backup_file(backup_path).and_then(|backup_pages| {
+        if backup_pages.0 == source_pages.0 {
+            Ok(())
+        } else {
+            Err(AppError::Database(format!(
+                "ページ数が一致しません（元: {}、バックアップ: {}）",
+                source_pages.0, backup_pages.0
+            )))
+        }
+    });
+    if let Err(e) = verified {
+        if let Err(remove_error) = std::fs::remove_file(backup_path) {
+            log::warn!("検証に失敗したバックアップの削除に失敗: {backup_path}: {remove_error}");
+        }
+        return Err(AppError::Database(format!(
+            "バックアップの検証に失敗しました: {e}"
+        )));
+    }
     Ok(())
 }
 
+/// データベースのページ数とページサイズを取得する
+fn database_size(conn: &Connection) -> Result<(u64, u64), AppError> {
+    let page_count: u64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+    let page_size: u64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+    Ok((page_count, page_size))
+}
+
+/// バックアップファイルが途中で切れていないか、整合性に問題がないかを検証する
+///
+/// # 引数
+/// * `backup_path` - バックアップファイルのパス
+///
+/// # 戻り値
+/// 検証に成功した場合はバックアップのページ数とページサイズ
+pub fn verify_backup_file(backup_path: &str) -> Result<(u64, u64), AppError> {
+    let file_size = std::fs::metadata(backup_path)?.len();
+    let backup_conn = rusqlite::Connection::open_with_flags(
+        backup_path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+    )?;
+
+    let (page_count, page_size) = database_size(&backup_conn)?;
+    if page_count == 0 || file_size != page_count * page_size {
+        return Err(AppError::Database(format!(
+            "ファイルサイズが不正です（{file_size}バイト、想定: {}バイト）",
+            page_count * page_size
+        )));
+    }
+
+    let integrity: String =
+        backup_conn.query_row("PRAGMA integrity_check", [], |row| row.get(0))?;
+    if integrity != "ok" {
+        return Err(AppError::Database(format!(
+            "整合性チェックに失敗しました: {integrity}"
+        )));
+    }
+
+    Ok((page_count, page_size))
+}
+
 /// バックアップからデータベースを復元する
 ///
 /// # 引数
@@ -432,6 +503,14 @@ pub fn restore_from_backup(
         });
     }
 
+    // 途中で切れたバックアップや壊れたバックアップからは復元しない
+    if let Err(e) = verify_backup_file(backup_path) {
+        return Ok(RestoreResult {
+            success: false,
+            message: format!("バックアップファイルが破損しているため復元できません: {e}"),
+        });
+    }
+
     let backup_conn = rusqlite::Connection::open(backup_path)?;
 
     // バックアップから復元
@@ -666,7 +745,7 @@ fn check_column_exists(conn: &Connection, table_name: &str, column_name: &str) -
 /// * `app_data_dir` - アプリデータディレクトリのパス
 ///
 /// # 戻り値
-/// バックアップファイルのパス一覧
+/// バックアップファイルのパス一覧（検証に失敗したファイルは含まない）
 pub fn list_backup_files(app_data_dir: &Path) -> Result<Vec<String>, AppError> {
     let mut backup_files = Vec::new();
 
@@ -675,7 +754,10 @@ pub fn list_backup_files(app_data_dir: &Path) -> Result<Vec<String>, AppError> {
             if let Some(file_name) = entry.file_name().to_str() {
                 if file_name.starts_with("database_backup_") && file_name.ends_with(".db") {
                     if let Some(path_str) = entry.path().to_str() {
-                        backup_files.push(path_str.to_string());
+                        match verify_backup_file(path_str) {
+                            Ok(_) => backup_files.push(path_str.to_string()),
+                            Err(e) => log::warn!("破損したバックアップを除外: {path_str}: {e}"),
+                        }
                     }
                 }
             }
@@ -688,6 +770,32 @@ pub fn list_backup_files(app_data_dir: &Path) -> Result<Vec<String>, AppError> {
     Ok(backup_files)
 }
 
+/// バックアップファイルのパスを生成する（JST使用）
+///
+/// ファイルベースのデータベースの場合は、`list_backup_files`で列挙できるよう
+/// データベースファイルと同じディレクトリに配置します。
+/// インメモリデータベースの場合はカレントディレクトリからの相対パスになります。
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `kind` - バックアップの種類（ファイル名に含める）
+///
+/// # 戻り値
+/// バックアップファイルのパス
+fn backup_path_for(conn: &Connection, kind: &str) -> String {
+    let now_jst = Utc::now().with_timezone(&Tokyo);
+    let file_name = format!("database_backup_{kind}_{}.db", now_jst.timestamp());
+
+    match conn
+        .path()
+        .filter(|path| !path.is_empty())
+        .and_then(|path| Path::new(path).parent())
+    {
+        Some(dir) => dir.join(file_name).to_string_lossy().into_owned(),
+        None => file_name,
+    }
+}
+
 /// 包括的なデータ移行を実行する
 ///
 /// この関数は既存データの安全な移行とバックアップ作成を行います。
@@ -702,8 +810,7 @@ pub fn execute_comprehensive_data_migration(
     conn: &Connection,
 ) -> Result<DataMigrationResult, AppError> {
     // バックアップパスを生成（JST使用）
-    let now_jst = Utc::now().with_timezone(&Tokyo);
-    let backup_path = format!("database_backup_migration_{}.db", now_jst.timestamp());
+    let backup_path = backup_path_for(conn, "migration");
 
     // 1. 移行前のバックアップを作成
     if let Err(e) = create_backup(conn, &backup_path) {
@@ -996,8 +1103,7 @@ pub fn migrate_user_authentication(conn: &Connection) -> Result<MigrationResult,
     log::info!("データベースファイルパス: {:?}", conn.path());
 
     // バックアップパスを生成（JST使用）
-    let now_jst = Utc::now().with_timezone(&Tokyo);
-    let backup_path = format!("database_backup_auth_{}.db", now_jst.timestamp());
+    let backup_path = backup_path_for(conn, "auth");
 
     // 1. バックアップを作成
     log::info!("データベースバックアップを作成中: {backup_path}");
@@ -2456,6 +2562,48 @@ mod tests {
         assert_eq!(count, 1);
     }
 
+    #[test]
+    fn test_truncated_backup_is_not_restorable() {
+        let mut conn = SqliteConnection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE test_table (id INTEGER PRIMARY KEY, name TEXT)",
+            [],
+        )
+        .unwrap();
+        for i in 0..200 {
+            conn.execute(
+                "INSERT INTO test_table (name) VALUES (?1)",
+                [format!("row-{i}-{}", "x".repeat(100))],
+            )
+            .unwrap();
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let backup_path = dir.path().join("database_backup_manual_1.db");
+        let backup_path = backup_path.to_str().unwrap();
+        create_backup(&conn, backup_path).unwrap();
+        assert!(verify_backup_file(backup_path).is_ok());
+        assert_eq!(list_backup_files(dir.path()).unwrap(), vec![backup_path]);
+
+        // ディスクが一杯になって途中で書き込みが止まった状態を再現する
+        let file_size = std::fs::metadata(backup_path).unwrap().len();
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(backup_path)
+            .unwrap()
+            .set_len(file_size / 2)
+            .unwrap();
+
+        assert!(verify_backup_file(backup_path).is_err());
+        assert!(list_backup_files(dir.path()).unwrap().is_empty());
+        let result = restore_from_backup(&mut conn, backup_path).unwrap();
+        assert!(!result.success);
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM test_table", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 200);
+    }
+
     #[test]
     fn test_drop_receipt_path_column() {
         let conn = create_test_db();
@@ -2856,4 +3004,67 @@ mod tests {
             .unwrap();
         assert_eq!(expenses_count, 1);
     }
+
+    /// sqlite_masterから指定した種類のオブジェクト名を取得する
+    fn schema_object_names(conn: &SqliteConnection, kind: &str) -> Vec<String> {
+        let mut stmt = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = ?1 ORDER BY name")
+            .unwrap();
+        stmt.query_map([kind], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<String>, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_run_migrations_is_idempotent() {
+        let conn = SqliteConnection::open_in_memory().unwrap();
+
+        let mut first_run: Option<(Vec<String>, Vec<String>)> = None;
+        for run in 1..=3 {
+            run_migrations(&conn).unwrap();
+
+            let tables = schema_object_names(&conn, "table");
+            let indexes = schema_object_names(&conn, "index");
+
+            // テーブル・インデックスが重複していないこと
+            let mut unique_tables = tables.clone();
+            unique_tables.dedup();
+            assert_eq!(tables, unique_tables, "{run}回目でテーブルが重複しています");
+            let mut unique_indexes = indexes.clone();
+            unique_indexes.dedup();
+            assert_eq!(
+                indexes, unique_indexes,
+                "{run}回目でインデックスが重複しています"
+            );
+
+            // 2回目以降もスキーマが変化しないこと
+            match &first_run {
+                Some((first_tables, first_indexes)) => {
+                    assert_eq!(&tables, first_tables, "{run}回目でテーブルが変化しました");
+                    assert_eq!(
+                        &indexes, first_indexes,
+                        "{run}回目でインデックスが変化しました"
+                    );
+                }
+                None => first_run = Some((tables, indexes)),
+            }
+
+            // 初期カテゴリが重複していないこと
+            let (total, distinct): (i64, i64) = conn
+                .query_row(
+                    "SELECT COUNT(*), COUNT(DISTINCT name) FROM categories",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .unwrap();
+            assert_eq!(total, 6, "{run}回目で初期カテゴリの件数が変化しました");
+            assert_eq!(total, distinct, "{run}回目で初期カテゴリが重複しています");
+
+            let integrity: String = conn
+                .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(integrity, "ok", "{run}回目で整合性チェックに失敗しました");
+        }
+    }
 }
@@ -0,0 +1,298 @@
+//! 過去バージョンのデータベースを使用したアップグレードテスト
+//!
+//! `tests/fixtures/schema_history` に保存した各世代のスキーマのSQLiteファイルを
+//! 一時ディレクトリにコピーし、起動時と同じマイグレーションを適用した後に
+//! データが失われていないことを検証します。
+//!
+//! 新しいスキーマをリリースしたときは、次のコマンドで現在のスキーマの
+//! フィクスチャを追加してください。
+//!
+//! ```sh
+//! pnpm fixture:generate  # cargo test generate_schema_fixture -- --ignored
+//! ```
+
+#[cfg(test)]
+mod tests {
+    use crate::features::expenses::models::ExpenseFilter;
+    use crate::features::expenses::repository as expense_repository;
+    use crate::features::migrations::service::execute_comprehensive_data_migration;
+    use crate::shared::database::connection::open_and_migrate_database;
+    use crate::shared::export::CURRENT_SCHEMA_VERSION;
+    use rusqlite::Connection;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use tempfile::TempDir;
+
+    /// 件数を保持すべきテーブル
+    const PRESERVED_TABLES: [&str; 4] =
+        ["expenses", "subscriptions", "receipt_cache", "categories"];
+
+    /// user_idを必ず持つべきテーブル
+    const USER_OWNED_TABLES: [&str; 3] = ["expenses", "subscriptions", "receipt_cache"];
+
+    /// フィクスチャディレクトリのパスを取得する
+    fn fixtures_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/schema_history")
+    }
+
+    /// フィクスチャファイルの一覧を取得する（ファイル名順）
+    fn fixture_paths() -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(fixtures_dir())
+            .expect("フィクスチャディレクトリの読み込みに失敗")
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "sqlite"))
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    fn table_exists(conn: &Connection, table: &str) -> bool {
+        conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name=?1",
+            [table],
+            |row| row.get::<_, i64>(0),
+        )
+        .unwrap()
+            > 0
+    }
+
+    fn count_rows(conn: &Connection, table: &str) -> i64 {
+        if !table_exists(conn, table) {
+            return 0;
+        }
+        conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+            row.get(0)
+        })
+        .unwrap()
+    }
+
+    /// テーブルに含まれるユーザーIDの一覧を取得する
+    fn distinct_user_ids(conn: &Connection, table: &str) -> Vec<String> {
+        if !table_exists(conn, table) {
+            return Vec::new();
+        }
+        conn.prepare(&format!("SELECT DISTINCT user_id FROM {table}"))
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    /// フィクスチャを一時ディレクトリにコピーし、マイグレーションを適用する
+    ///
+    /// # 戻り値
+    /// (マイグレーション前の行数, マイグレーション済みの接続, 一時ディレクトリ)
+    fn upgrade_fixture(fixture: &Path) -> (Vec<i64>, Connection, TempDir) {
+        let temp_dir = TempDir::new().expect("一時ディレクトリの作成に失敗");
+        let db_path = temp_dir.path().join(fixture.file_name().unwrap());
+        fs::copy(fixture, &db_path).expect("フィクスチャのコピーに失敗");
+
+        let before = {
+            let conn = Connection::open(&db_path).unwrap();
+            PRESERVED_TABLES
+                .iter()
+                .map(|table| count_rows(&conn, table))
+                .collect()
+        };
+
+        let conn = open_and_migrate_database(&db_path)
+            .unwrap_or_else(|e| panic!("{fixture:?} のマイグレーションに失敗: {e}"));
+
+        let result = execute_comprehensive_data_migration(&conn)
+            .unwrap_or_else(|e| panic!("{fixture:?} の包括的データ移行に失敗: {e}"));
+        assert!(
+            result.success,
+            "{fixture:?} の包括的データ移行に失敗: {}",
+            result.message
+        );
+
+        (before, conn, temp_dir)
+    }
+
+    #[test]
+    fn test_schema_history_fixtures_exist() {
+        let names: Vec<String> = fixture_paths()
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+
+        for expected in [
+            "v1.0_pre_receipt_url.sqlite",
+            "v2.0_pre_auth.sqlite",
+            "v2.1_pre_nanoid.sqlite",
+        ] {
+            assert!(
+                names.iter().any(|name| name == expected),
+                "フィクスチャ {expected} が見つかりません"
+            );
+        }
+    }
+
+    #[test]
+    fn test_upgrade_preserves_data_for_all_fixtures() {
+        for fixture in fixture_paths() {
+            let (before, conn, _temp_dir) = upgrade_fixture(&fixture);
+
+            // 行数が保持されていること（カテゴリは既存データがあれば追加されない）
+            for (table, before_count) in PRESERVED_TABLES.iter().zip(before) {
+                assert_eq!(
+                    count_rows(&conn, table),
+                    before_count.max(if *table == "categories" { 6 } else { 0 }),
+                    "{fixture:?} の {table} の行数が変化しました"
+                );
+            }
+
+            // すべての行にuser_idが設定され、usersテーブルに存在すること
+            for table in USER_OWNED_TABLES {
+                let orphaned: i64 = conn
+                    .query_row(
+                        &format!(
+                            "SELECT COUNT(*) FROM {table}
+                             WHERE user_id IS NULL OR user_id NOT IN (SELECT id FROM users)"
+                        ),
+                        [],
+                        |row| row.get(0),
+                    )
+                    .unwrap();
+                assert_eq!(
+                    orphaned, 0,
+                    "{fixture:?} の {table} にユーザー未設定の行があります"
+                );
+            }
+
+            // 整合性チェック
+            let integrity: String = conn
+                .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(integrity, "ok", "{fixture:?} の整合性チェックに失敗");
+
+            let mut stmt = conn.prepare("PRAGMA foreign_key_check").unwrap();
+            let violations = stmt.query_map([], |_| Ok(())).unwrap().count();
+            assert_eq!(violations, 0, "{fixture:?} に外部キー違反があります");
+
+            // 古いカラムが残っていないこと
+            assert!(
+                !crate::shared::database::connection::check_column_exists(
+                    &conn,
+                    "expenses",
+                    "receipt_path"
+                ),
+                "{fixture:?} にreceipt_pathカラムが残っています"
+            );
+
+            // 代表的なクエリが成功すること
+            let user_ids = distinct_user_ids(&conn, "expenses");
+            let found: usize = user_ids
+                .iter()
+                .map(|user_id| {
+                    expense_repository::find_expenses(&conn, user_id, &ExpenseFilter::new())
+                        .unwrap_or_else(|e| panic!("{fixture:?} の経費検索に失敗: {e}"))
+                        .len()
+                })
+                .sum();
+            assert_eq!(found as i64, count_rows(&conn, "expenses"));
+
+            for user_id in &user_ids {
+                expense_repository::get_expense_count_by_category(&conn, user_id, None, None)
+                    .unwrap_or_else(|e| panic!("{fixture:?} のカテゴリ別集計に失敗: {e}"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_upgrade_is_idempotent() {
+        for fixture in fixture_paths() {
+            let (_, conn, temp_dir) = upgrade_fixture(&fixture);
+            let counts: Vec<i64> = PRESERVED_TABLES
+                .iter()
+                .map(|table| count_rows(&conn, table))
+                .collect();
+            let db_path = PathBuf::from(conn.path().unwrap());
+            drop(conn);
+
+            // 2回目の起動でも失敗せず、データが変化しないこと
+            let conn = open_and_migrate_database(&db_path)
+                .unwrap_or_else(|e| panic!("{fixture:?} の再マイグレーションに失敗: {e}"));
+            for (table, expected) in PRESERVED_TABLES.iter().zip(counts) {
+                assert_eq!(count_rows(&conn, table), expected);
+            }
+            drop(temp_dir);
+        }
+    }
+
+    /// 現在のスキーマのフィクスチャを生成する
+    ///
+    /// リリース時に手動で実行します。ファイル名は環境変数`SCHEMA_FIXTURE_NAME`で指定でき、
+    /// 未指定の場合は現在のスキーマバージョンを使用します。
+    #[test]
+    #[ignore]
+    fn generate_schema_fixture() {
+        let name = std::env::var("SCHEMA_FIXTURE_NAME")
+            .unwrap_or_else(|_| format!("v{CURRENT_SCHEMA_VERSION}"));
+        let output = fixtures_dir().join(format!("{name}.sqlite"));
+        assert!(!output.exists(), "{output:?} は既に存在します");
+
+        let temp_dir = TempDir::new().unwrap();
+        let conn = open_and_migrate_database(&temp_dir.path().join("fixture.db")).unwrap();
+        insert_fixture_rows(&conn);
+
+        conn.execute("VACUUM INTO ?1", [output.to_string_lossy()])
+            .expect("フィクスチャの書き出しに失敗");
+        println!("フィクスチャを生成しました: {output:?}");
+    }
+
+    /// フィクスチャ用の代表的なデータを挿入する
+    fn insert_fixture_rows(conn: &Connection) {
+        let now = "2025-04-01T10:00:00+09:00";
+        let user_id: String = conn
+            .query_row(
+                "SELECT id FROM users ORDER BY created_at LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .expect("デフォルトユーザーが見つかりません");
+
+        let expenses = [
+            ("2025-04-03", 1200.0, "交通費", Some("電車代"), None),
+            (
+                "2025-04-10",
+                3500.0,
+                "飲食費",
+                Some("打ち合わせランチ"),
+                Some("https://receipts.example.com/receipts/2.png"),
+            ),
+            ("2025-05-01", 5980.0, "通信費", Some("モバイル回線"), None),
+        ];
+        for (date, amount, category, description, receipt_url) in expenses {
+            conn.execute(
+                "INSERT INTO expenses (date, amount, category, description, receipt_url, user_id, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+                rusqlite::params![date, amount, category, description, receipt_url, user_id, now],
+            )
+            .unwrap();
+        }
+
+        conn.execute(
+            "INSERT INTO receipt_cache (receipt_url, local_path, cached_at, file_size, last_accessed, user_id)
+             VALUES ('https://receipts.example.com/receipts/2.png', '/tmp/cache/2.png', ?1, 2048, ?1, ?2)",
+            rusqlite::params![now, user_id],
+        )
+        .unwrap();
+
+        let subscriptions = [
+            ("クラウドストレージ", 1300.0, "monthly", "2025-01-15", 1),
+            ("開発ツール", 12000.0, "annual", "2024-06-01", 1),
+            ("旧サービス", 500.0, "monthly", "2023-03-01", 0),
+        ];
+        for (name, amount, billing_cycle, start_date, is_active) in subscriptions {
+            conn.execute(
+                "INSERT INTO subscriptions (name, amount, billing_cycle, start_date, category, is_active, user_id, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, 'その他', ?5, ?6, ?7, ?7)",
+                rusqlite::params![name, amount, billing_cycle, start_date, is_active, user_id, now],
+            )
+            .unwrap();
+        }
+    }
+}
@@ -10,5 +10,129 @@ pub mod expenses;
 pub mod migrations;
 pub mod receipts;
 pub mod security;
+pub mod startup;
 pub mod subscriptions;
 pub mod updater;
+
+use crate::shared::capabilities::Capability;
+
+/// すべての機能モジュールが登録した利用者向け機能
+pub fn capability_registry() -> impl Iterator<Item = &'static Capability> {
+    [
+        auth::CAPABILITIES,
+        categories::CAPABILITIES,
+        expenses::CAPABILITIES,
+        subscriptions::CAPABILITIES,
+        receipts::CAPABILITIES,
+        security::CAPABILITIES,
+        migrations::CAPABILITIES,
+        updater::CAPABILITIES,
+        startup::CAPABILITIES,
+    ]
+    .into_iter()
+    .flatten()
+}
+
+/// コマンド名から登録されている機能を取得する
+///
+/// データを変更するか、元に戻せない操作かの判定に使用します。
+pub fn find_capability(command: &str) -> Option<&'static Capability> {
+    capability_registry().find(|capability| capability.command == command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// データを変更するコマンドの名前の接頭辞
+    const MUTATING_PREFIXES: &[&str] = &[
+        "create_",
+        "update_",
+        "delete_",
+        "upload_",
+        "import_",
+        "execute_",
+        "set_",
+        "strip_",
+        "toggle_",
+        "clone_",
+        "add_",
+        "drop_",
+        "rebase_",
+        "invalidate_",
+        "cleanup_",
+        "encrypt_and_store_",
+        "sync_",
+        "skip_",
+        "restart_",
+        "download_and_install_",
+        "recompress_",
+        "log_",
+        "logout",
+    ];
+
+    /// lib.rsの`generate_handler!`に登録されているコマンド名を取得する
+    fn registered_commands() -> Vec<&'static str> {
+        let lib_rs = include_str!("../lib.rs");
+        let start = lib_rs.find("generate_handler![").unwrap();
+        let end = start + lib_rs[start..].find("])").unwrap();
+        lib_rs[start..end]
+            .lines()
+            .skip(1)
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("//"))
+            .filter_map(|line| line.trim_end_matches(',').rsplit("::").next())
+            .collect()
+    }
+
+    #[test]
+    fn test_every_mutating_command_is_registered() {
+        let commands = registered_commands();
+        assert!(commands.len() > 50, "{commands:?}");
+
+        let missing: Vec<_> = commands
+            .iter()
+            .filter(|command| {
+                MUTATING_PREFIXES
+                    .iter()
+                    .any(|prefix| command.starts_with(prefix))
+            })
+            .filter(|command| !find_capability(command).is_some_and(|c| c.mutates))
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "データを変更するコマンドが機能として登録されていません: {missing:?}"
+        );
+
+        // 削除系のコマンドは元に戻せない操作として登録する
+        for command in commands
+            .iter()
+            .filter(|command| command.starts_with("delete_") || command.starts_with("drop_"))
+        {
+            assert!(find_capability(command).unwrap().destructive, "{command}");
+        }
+    }
+
+    #[test]
+    fn test_registry_matches_commands() {
+        let commands: HashSet<_> = registered_commands().into_iter().collect();
+        let mut ids = HashSet::new();
+        for capability in capability_registry() {
+            assert!(
+                commands.contains(capability.command),
+                "存在しないコマンドが登録されています: {}",
+                capability.command
+            );
+            assert!(
+                ids.insert(capability.id),
+                "機能IDが重複しています: {}",
+                capability.id
+            );
+            assert_eq!(
+                capability.label_key,
+                format!("capability.{}", capability.id)
+            );
+        }
+    }
+}
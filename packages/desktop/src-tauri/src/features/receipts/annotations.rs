@@ -0,0 +1,323 @@
+/// 領収書の注釈（ハイライト）
+///
+/// 明細行の多い領収書で、経費が対象とする行を矩形で示します。
+/// 注釈はローカルのメタデータとして保存し、領収書ファイル自体は変更しません。
+/// 経費の削除や領収書の差し替え・削除時には、トリガーで注釈も削除されます。
+use super::models::{CreateReceiptAnnotationDto, NormalizedRect, ReceiptAnnotation};
+use crate::features::migrations::auto_migration::executor::MigrationExecutorTrait;
+use crate::features::migrations::auto_migration::models::{
+    ExecutableMigrationDefinition, MigrationDefinition,
+};
+use crate::features::migrations::auto_migration::registry::MigrationRegistry;
+use crate::shared::errors::AppResult;
+use crate::shared::utils::get_current_jst_timestamp;
+use rusqlite::{params, Connection, Row};
+
+/// 注釈テーブルから取得するカラム
+const ANNOTATION_COLUMNS: &str =
+    "id, expense_id, receipt_url, page_number, x, y, width, height, note, created_at";
+
+/// 注釈テーブルのスキーマ
+const ANNOTATIONS_SCHEMA_SQL: &str = "
+    CREATE TABLE IF NOT EXISTS annotations (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        expense_id INTEGER NOT NULL,
+        receipt_url TEXT NOT NULL,
+        user_id TEXT NOT NULL,
+        page_number INTEGER NOT NULL DEFAULT 1 CHECK(page_number >= 1),
+        x REAL NOT NULL,
+        y REAL NOT NULL,
+        width REAL NOT NULL,
+        height REAL NOT NULL,
+        note TEXT,
+        created_at TEXT NOT NULL,
+        FOREIGN KEY (expense_id) REFERENCES expenses(id) ON DELETE CASCADE
+    );
+    CREATE INDEX IF NOT EXISTS idx_annotations_expense_receipt
+        ON annotations(expense_id, receipt_url);
+    CREATE TRIGGER IF NOT EXISTS trg_annotations_expense_deleted
+        AFTER DELETE ON expenses
+    BEGIN
+        DELETE FROM annotations WHERE expense_id = OLD.id;
+    END;
+    CREATE TRIGGER IF NOT EXISTS trg_annotations_receipt_changed
+        AFTER UPDATE OF receipt_url ON expenses
+        WHEN OLD.receipt_url IS NOT NEW.receipt_url
+    BEGIN
+        DELETE FROM annotations
+        WHERE expense_id = OLD.id AND receipt_url IS NOT NEW.receipt_url;
+    END;
+";
+
+/// 注釈テーブル作成マイグレーション実行器
+pub struct ReceiptAnnotationsSchemaMigration;
+
+impl MigrationExecutorTrait for ReceiptAnnotationsSchemaMigration {
+    fn name(&self) -> &str {
+        "010_create_receipt_annotations"
+    }
+
+    fn execute(&self, conn: &Connection) -> Result<(), String> {
+        create_annotations_table(conn).map_err(|e| format!("annotationsテーブル作成エラー: {e}"))
+    }
+}
+
+/// 注釈テーブル用マイグレーション定義を取得する
+///
+/// # 戻り値
+/// 実行可能なマイグレーション定義
+pub fn get_receipt_annotations_schema_definition() -> ExecutableMigrationDefinition {
+    let definition = MigrationDefinition::new(
+        "010_create_receipt_annotations".to_string(),
+        "3.6.0".to_string(),
+        "領収書の注釈テーブルの作成".to_string(),
+        MigrationRegistry::calculate_checksum(ANNOTATIONS_SCHEMA_SQL),
+    );
+
+    ExecutableMigrationDefinition::new(definition, Box::new(ReceiptAnnotationsSchemaMigration))
+}
+
+/// 注釈テーブルとインデックス・トリガーを作成する
+///
+/// # 引数
+/// * `conn` - データベース接続
+pub fn create_annotations_table(conn: &Connection) -> AppResult<()> {
+    conn.execute_batch(ANNOTATIONS_SCHEMA_SQL)?;
+    Ok(())
+}
+
+/// 行データを注釈モデルに変換する
+fn map_annotation_row(row: &Row) -> rusqlite::Result<ReceiptAnnotation> {
+    Ok(ReceiptAnnotation {
+        id: row.get(0)?,
+        expense_id: row.get(1)?,
+        receipt_url: row.get(2)?,
+        page_number: row.get(3)?,
+        rect: NormalizedRect {
+            x: row.get(4)?,
+            y: row.get(5)?,
+            width: row.get(6)?,
+            height: row.get(7)?,
+        },
+        note: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+/// 注釈を追加する
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `dto` - 注釈の入力値
+/// * `receipt_url` - 注釈を付ける領収書のURL
+/// * `user_id` - ユーザーID
+///
+/// # 戻り値
+/// 追加した注釈、または入力値が不正な場合はエラー
+pub fn insert_annotation(
+    conn: &Connection,
+    dto: &CreateReceiptAnnotationDto,
+    receipt_url: &str,
+    user_id: &str,
+) -> AppResult<ReceiptAnnotation> {
+    dto.validate()?;
+
+    let created_at = get_current_jst_timestamp();
+    conn.execute(
+        "INSERT INTO annotations
+            (expense_id, receipt_url, user_id, page_number, x, y, width, height, note, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            dto.expense_id,
+            receipt_url,
+            user_id,
+            dto.page_number(),
+            dto.rect.x,
+            dto.rect.y,
+            dto.rect.width,
+            dto.rect.height,
+            dto.note(),
+            created_at,
+        ],
+    )?;
+
+    Ok(ReceiptAnnotation {
+        id: conn.last_insert_rowid(),
+        expense_id: dto.expense_id,
+        receipt_url: receipt_url.to_string(),
+        page_number: dto.page_number(),
+        rect: dto.rect,
+        note: dto.note(),
+        created_at,
+    })
+}
+
+/// 領収書の注釈を取得する
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `expense_id` - 経費ID
+/// * `receipt_url` - 領収書のURL
+/// * `user_id` - ユーザーID
+///
+/// # 戻り値
+/// 注釈一覧（ページ順・上から順）
+pub fn find_annotations(
+    conn: &Connection,
+    expense_id: i64,
+    receipt_url: &str,
+    user_id: &str,
+) -> AppResult<Vec<ReceiptAnnotation>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {ANNOTATION_COLUMNS} FROM annotations
+         WHERE expense_id = ?1 AND receipt_url = ?2 AND user_id = ?3
+         ORDER BY page_number, y, x, id"
+    ))?;
+    let annotations = stmt
+        .query_map(
+            params![expense_id, receipt_url, user_id],
+            map_annotation_row,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(annotations)
+}
+
+/// 注釈を削除する
+///
+/// # 戻り値
+/// 削除した場合はtrue、該当する注釈がない場合はfalse
+pub fn delete_annotation(conn: &Connection, id: i64, user_id: &str) -> AppResult<bool> {
+    let deleted = conn.execute(
+        "DELETE FROM annotations WHERE id = ?1 AND user_id = ?2",
+        params![id, user_id],
+    )?;
+    Ok(deleted > 0)
+}
+
+/// 経費のすべての注釈を削除する
+///
+/// APIサーバー経由で経費や領収書を削除した場合など、
+/// ローカルの経費テーブルのトリガーが働かないときに使用します。
+///
+/// # 戻り値
+/// 削除した件数
+pub fn delete_annotations_for_expense(
+    conn: &Connection,
+    expense_id: i64,
+    user_id: &str,
+) -> AppResult<usize> {
+    let deleted = conn.execute(
+        "DELETE FROM annotations WHERE expense_id = ?1 AND user_id = ?2",
+        params![expense_id, user_id],
+    )?;
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECEIPT_URL: &str = "https://receipts.example.com/users/u1/receipts/1/a.pdf";
+
+    fn create_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE expenses (id INTEGER PRIMARY KEY, receipt_url TEXT, updated_at TEXT);",
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO expenses (id, receipt_url) VALUES (1, ?1), (2, ?1)",
+            [RECEIPT_URL],
+        )
+        .unwrap();
+        create_annotations_table(&conn).unwrap();
+        conn
+    }
+
+    fn dto(expense_id: i64, page_number: u32, y: f64) -> CreateReceiptAnnotationDto {
+        CreateReceiptAnnotationDto {
+            expense_id,
+            page_number: Some(page_number),
+            rect: NormalizedRect {
+                x: 0.1,
+                y,
+                width: 0.8,
+                height: 0.05,
+            },
+            note: Some("対象の明細".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_insert_and_find_annotations() {
+        let conn = create_test_db();
+        let second = insert_annotation(&conn, &dto(1, 2, 0.1), RECEIPT_URL, "u1").unwrap();
+        let first = insert_annotation(&conn, &dto(1, 1, 0.5), RECEIPT_URL, "u1").unwrap();
+        insert_annotation(&conn, &dto(1, 1, 0.2), RECEIPT_URL, "u2").unwrap();
+
+        let annotations = find_annotations(&conn, 1, RECEIPT_URL, "u1").unwrap();
+        assert_eq!(annotations, vec![first, second]);
+        assert_eq!(annotations[0].note.as_deref(), Some("対象の明細"));
+
+        // 不正な座標は保存しない
+        let mut invalid = dto(1, 1, 0.98);
+        invalid.rect.height = 0.1;
+        assert!(insert_annotation(&conn, &invalid, RECEIPT_URL, "u1").is_err());
+    }
+
+    #[test]
+    fn test_delete_annotation_is_scoped_to_user() {
+        let conn = create_test_db();
+        let annotation = insert_annotation(&conn, &dto(1, 1, 0.5), RECEIPT_URL, "u1").unwrap();
+
+        assert!(!delete_annotation(&conn, annotation.id, "u2").unwrap());
+        assert!(delete_annotation(&conn, annotation.id, "u1").unwrap());
+        assert!(find_annotations(&conn, 1, RECEIPT_URL, "u1")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_annotations_cascade_with_receipt() {
+        let conn = create_test_db();
+        for expense_id in [1, 2] {
+            insert_annotation(&conn, &dto(expense_id, 1, 0.5), RECEIPT_URL, "u1").unwrap();
+        }
+
+        // 領収書以外の更新では削除されない
+        conn.execute("UPDATE expenses SET updated_at = 'now' WHERE id = 1", [])
+            .unwrap();
+        assert_eq!(
+            find_annotations(&conn, 1, RECEIPT_URL, "u1").unwrap().len(),
+            1
+        );
+
+        // 領収書の削除で経費1の注釈のみ削除される
+        conn.execute("UPDATE expenses SET receipt_url = NULL WHERE id = 1", [])
+            .unwrap();
+        assert!(find_annotations(&conn, 1, RECEIPT_URL, "u1")
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            find_annotations(&conn, 2, RECEIPT_URL, "u1").unwrap().len(),
+            1
+        );
+
+        // 経費の削除で削除される
+        conn.execute("DELETE FROM expenses WHERE id = 2", [])
+            .unwrap();
+        assert!(find_annotations(&conn, 2, RECEIPT_URL, "u1")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_delete_annotations_for_expense() {
+        let conn = create_test_db();
+        insert_annotation(&conn, &dto(1, 1, 0.2), RECEIPT_URL, "u1").unwrap();
+        insert_annotation(&conn, &dto(1, 1, 0.5), RECEIPT_URL, "u1").unwrap();
+
+        assert_eq!(delete_annotations_for_expense(&conn, 1, "u1").unwrap(), 2);
+        assert_eq!(delete_annotations_for_expense(&conn, 1, "u1").unwrap(), 0);
+    }
+}
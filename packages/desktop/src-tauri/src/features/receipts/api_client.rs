@@ -1,10 +1,13 @@
 // APIサーバーとの通信を行うクライアント
 
+use crate::shared::api_client::send_with_retry;
 use crate::shared::config::environment::ApiConfig;
 use crate::shared::errors::AppError;
+use crate::shared::rate_limit::{shared_cooldown, RateLimitCooldown};
 use log::{debug, error, info, warn};
 use reqwest::{multipart, Client, Response};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
 
 /// APIクライアント設定
@@ -94,10 +97,19 @@ pub struct HealthCheckResult {
     pub details: Option<serde_json::Value>,
 }
 
+/// 複数ファイルのアップロードに使用するタイムアウトの倍率（単一ファイルのアップロードに対する）
+const MULTIPLE_UPLOAD_TIMEOUT_MULTIPLIER: u32 = 4;
+
 /// APIクライアント
 pub struct ApiClient {
     client: Client,
     config: ApiClientConfig,
+    /// アップロードのリクエストのタイムアウト
+    upload_timeout: Duration,
+    /// アップロード以外のリクエスト（削除・ヘルスチェック）のタイムアウト
+    download_timeout: Duration,
+    /// レート制限による待機期間（アプリ全体で共有）
+    cooldown: Arc<RateLimitCooldown>,
 }
 
 impl ApiClient {
@@ -108,7 +120,35 @@ impl ApiClient {
             .build()
             .map_err(|e| AppError::Configuration(format!("HTTPクライアント初期化失敗: {e}")))?;
 
-        Ok(Self { client, config })
+        let timeout = Duration::from_secs(config.timeout_seconds);
+        Ok(Self {
+            client,
+            config,
+            upload_timeout: timeout,
+            download_timeout: timeout,
+            cooldown: shared_cooldown(),
+        })
+    }
+
+    /// レート制限の待機期間を差し替える（テスト用）
+    pub fn with_cooldown(mut self, cooldown: Arc<RateLimitCooldown>) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// リクエストの種類ごとのタイムアウトを設定の値から変更する
+    ///
+    /// # 引数
+    /// * `upload_timeout` - アップロードのタイムアウト（複数ファイルの場合はこの値の数倍）
+    /// * `download_timeout` - アップロード以外のリクエストのタイムアウト
+    pub fn with_timeout_override(
+        mut self,
+        upload_timeout: Duration,
+        download_timeout: Duration,
+    ) -> Self {
+        self.upload_timeout = upload_timeout;
+        self.download_timeout = download_timeout;
+        self
     }
 
     /// 単一ファイルをAPIサーバー経由でアップロード
@@ -138,70 +178,54 @@ impl ApiClient {
     ) -> Result<UploadResponse, AppError> {
         info!("APIサーバー経由でファイルアップロード開始: expense_id={expense_id}, filename={filename}, user_id={user_id}, type={upload_type}");
 
-        let url = format!("{}/api/v1/receipts/upload", self.config.base_url);
-
-        // リトライ機能付きでリクエスト送信
-        let mut attempts = 0;
-        loop {
-            // マルチパートフォームデータを構築（リトライごとに再作成）
-            let form = multipart::Form::new()
-                .part(
-                    "file",
-                    multipart::Part::bytes(file_data.to_vec())
-                        .file_name(filename.to_string())
-                        .mime_str(&self.get_content_type(filename))
-                        .map_err(|e| AppError::Validation(format!("MIMEタイプ設定エラー: {e}")))?,
-                )
-                .text("expenseId", expense_id.to_string())
-                .text("userId", user_id.to_string())
-                .text("type", upload_type.to_string());
-
-            match self
-                .client
-                .post(&url)
-                .header("Authorization", format!("Bearer {auth_token}"))
-                .multipart(form)
-                .send()
-                .await
-            {
-                Ok(response) => {
-                    if response.status().is_success() {
-                        let upload_response: UploadResponse =
-                            response.json().await.map_err(|e| {
-                                AppError::ExternalService(format!("レスポンス解析エラー: {e}"))
-                            })?;
-
-                        info!(
-                            "ファイルアップロード成功: expense_id={expense_id}, url={:?}",
-                            upload_response.file_url
-                        );
-                        return Ok(upload_response);
-                    } else {
-                        let error_response = self.handle_error_response(response).await?;
-                        return Err(AppError::ExternalService(format!(
-                            "APIサーバーエラー: {} - {}",
-                            error_response.error.code, error_response.error.message
-                        )));
-                    }
-                }
-                Err(e) => {
-                    if attempts < self.config.max_retries {
-                        attempts += 1;
-                        let delay = Duration::from_secs(2_u64.pow(attempts));
-                        warn!(
-                            "APIリクエスト失敗、リトライします: attempt={attempts}/{}, delay={delay:?}",
-                            self.config.max_retries
-                        );
-                        tokio::time::sleep(delay).await;
-                        continue;
-                    } else {
-                        return Err(AppError::ExternalService(format!(
-                            "APIサーバーへの接続に失敗しました: {e}"
-                        )));
-                    }
-                }
-            }
+        let endpoint = "/api/v1/receipts/upload";
+        let url = format!("{}{endpoint}", self.config.base_url);
+
+        // マルチパートフォームデータはクローンできないため、送信のたびに作成する
+        let response = send_with_retry(
+            || {
+                let form = multipart::Form::new()
+                    .part(
+                        "file",
+                        multipart::Part::bytes(file_data.to_vec())
+                            .file_name(filename.to_string())
+                            .mime_str(&self.get_content_type(filename))
+                            .map_err(|e| {
+                                AppError::Validation(format!("MIMEタイプ設定エラー: {e}"))
+                            })?,
+                    )
+                    .text("expenseId", expense_id.to_string())
+                    .text("userId", user_id.to_string())
+                    .text("type", upload_type.to_string());
+
+                Ok(self
+                    .client
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {auth_token}"))
+                    .timeout(self.upload_timeout)
+                    .multipart(form))
+            },
+            &self.cooldown,
+            self.config.max_retries,
+            "POST",
+            endpoint,
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            return Err(self.error_from_response(response).await);
         }
+
+        let upload_response: UploadResponse = response
+            .json()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("レスポンス解析エラー: {e}")))?;
+
+        info!(
+            "ファイルアップロード成功: expense_id={expense_id}, url={:?}",
+            upload_response.file_url
+        );
+        Ok(upload_response)
     }
 
     /// 複数ファイルをAPIサーバー経由で並列アップロード
@@ -216,126 +240,91 @@ impl ApiClient {
             files.len()
         );
 
-        let url = format!("{}/api/v1/receipts/upload/multiple", self.config.base_url);
-
-        // リトライ機能付きでリクエスト送信
-        let mut attempts = 0;
-        loop {
-            // マルチパートフォームデータを構築（リトライごとに再作成）
-            let mut form = multipart::Form::new();
+        let endpoint = "/api/v1/receipts/upload/multiple";
+        let url = format!("{}{endpoint}", self.config.base_url);
+
+        // マルチパートフォームデータはクローンできないため、送信のたびに作成する
+        let response = send_with_retry(
+            || {
+                let mut form = multipart::Form::new();
+
+                for (i, (expense_id, _file_path, file_data, filename)) in files.iter().enumerate() {
+                    form = form
+                        .part(
+                            format!("files[{i}]"),
+                            multipart::Part::bytes(file_data.clone())
+                                .file_name(filename.clone())
+                                .mime_str(&self.get_content_type(filename))
+                                .map_err(|e| {
+                                    AppError::Validation(format!("MIMEタイプ設定エラー: {e}"))
+                                })?,
+                        )
+                        .text(format!("expenseIds[{i}]"), expense_id.to_string());
+                }
 
-            for (i, (expense_id, _file_path, file_data, filename)) in files.iter().enumerate() {
-                form = form
-                    .part(
-                        format!("files[{i}]"),
-                        multipart::Part::bytes(file_data.clone())
-                            .file_name(filename.clone())
-                            .mime_str(&self.get_content_type(filename))
-                            .map_err(|e| {
-                                AppError::Validation(format!("MIMEタイプ設定エラー: {e}"))
-                            })?,
-                    )
-                    .text(format!("expenseIds[{i}]"), expense_id.to_string());
-            }
+                form = form.text("userId", user_id.to_string());
+
+                // 複数ファイルは送信に時間がかかるため、単一ファイルより長いタイムアウトを使用する
+                Ok(self
+                    .client
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {auth_token}"))
+                    .timeout(self.upload_timeout * MULTIPLE_UPLOAD_TIMEOUT_MULTIPLIER)
+                    .multipart(form))
+            },
+            &self.cooldown,
+            self.config.max_retries,
+            "POST",
+            endpoint,
+        )
+        .await?;
 
-            form = form.text("userId", user_id.to_string());
-
-            match self
-                .client
-                .post(&url)
-                .header("Authorization", format!("Bearer {auth_token}"))
-                .multipart(form)
-                .send()
-                .await
-            {
-                Ok(response) => {
-                    if response.status().is_success() {
-                        let upload_response: MultipleUploadResponse =
-                            response.json().await.map_err(|e| {
-                                AppError::ExternalService(format!("レスポンス解析エラー: {e}"))
-                            })?;
-
-                        info!(
-                            "複数ファイルアップロード成功: 成功={}, 失敗={}",
-                            upload_response.successful_uploads, upload_response.failed_uploads
-                        );
-                        return Ok(upload_response);
-                    } else {
-                        let error_response = self.handle_error_response(response).await?;
-                        return Err(AppError::ExternalService(format!(
-                            "APIサーバーエラー: {} - {}",
-                            error_response.error.code, error_response.error.message
-                        )));
-                    }
-                }
-                Err(e) => {
-                    if attempts < self.config.max_retries {
-                        attempts += 1;
-                        let delay = Duration::from_secs(2_u64.pow(attempts));
-                        warn!(
-                            "APIリクエスト失敗、リトライします: attempt={attempts}/{}, delay={delay:?}",
-                            self.config.max_retries
-                        );
-                        tokio::time::sleep(delay).await;
-                        continue;
-                    } else {
-                        return Err(AppError::ExternalService(format!(
-                            "APIサーバーへの接続に失敗しました: {e}"
-                        )));
-                    }
-                }
-            }
+        if !response.status().is_success() {
+            return Err(self.error_from_response(response).await);
         }
+
+        let upload_response: MultipleUploadResponse = response
+            .json()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("レスポンス解析エラー: {e}")))?;
+
+        info!(
+            "複数ファイルアップロード成功: 成功={}, 失敗={}",
+            upload_response.successful_uploads, upload_response.failed_uploads
+        );
+        Ok(upload_response)
     }
 
     /// ファイルをAPIサーバー経由で削除
     pub async fn delete_file(&self, file_key: &str, auth_token: &str) -> Result<bool, AppError> {
         info!("APIサーバー経由でファイル削除開始: file_key={file_key}");
 
-        let url = format!("{}/api/v1/receipts/{file_key}", self.config.base_url);
-
-        // リトライ機能付きでリクエスト送信
-        let mut attempts = 0;
-        loop {
-            match self
-                .client
-                .delete(&url)
-                .header("Authorization", format!("Bearer {auth_token}"))
-                .send()
-                .await
-            {
-                Ok(response) => {
-                    if response.status().is_success() {
-                        info!("ファイル削除成功: file_key={file_key}");
-                        return Ok(true);
-                    } else if response.status().as_u16() == 404 {
-                        warn!("削除対象ファイルが見つかりません: file_key={file_key}");
-                        return Ok(true); // 既に削除済みとして成功扱い
-                    } else {
-                        let error_response = self.handle_error_response(response).await?;
-                        return Err(AppError::ExternalService(format!(
-                            "APIサーバーエラー: {} - {}",
-                            error_response.error.code, error_response.error.message
-                        )));
-                    }
-                }
-                Err(e) => {
-                    if attempts < self.config.max_retries {
-                        attempts += 1;
-                        let delay = Duration::from_secs(2_u64.pow(attempts));
-                        warn!(
-                            "APIリクエスト失敗、リトライします: attempt={attempts}/{}, delay={delay:?}",
-                            self.config.max_retries
-                        );
-                        tokio::time::sleep(delay).await;
-                        continue;
-                    } else {
-                        return Err(AppError::ExternalService(format!(
-                            "APIサーバーへの接続に失敗しました: {e}"
-                        )));
-                    }
-                }
-            }
+        let endpoint = format!("/api/v1/receipts/{file_key}");
+        let url = format!("{}{endpoint}", self.config.base_url);
+
+        let response = send_with_retry(
+            || {
+                Ok(self
+                    .client
+                    .delete(&url)
+                    .header("Authorization", format!("Bearer {auth_token}"))
+                    .timeout(self.download_timeout))
+            },
+            &self.cooldown,
+            self.config.max_retries,
+            "DELETE",
+            &endpoint,
+        )
+        .await?;
+
+        if response.status().is_success() {
+            info!("ファイル削除成功: file_key={file_key}");
+            Ok(true)
+        } else if response.status().as_u16() == 404 {
+            warn!("削除対象ファイルが見つかりません: file_key={file_key}");
+            Ok(true) // 既に削除済みとして成功扱い
+        } else {
+            Err(self.error_from_response(response).await)
         }
     }
 
@@ -346,7 +335,13 @@ impl ApiClient {
         let url = format!("{}/api/v1/health", self.config.base_url);
         let start_time = std::time::Instant::now();
 
-        match self.client.get(&url).send().await {
+        match self
+            .client
+            .get(&url)
+            .timeout(self.download_timeout)
+            .send()
+            .await
+        {
             Ok(response) => {
                 let duration = start_time.elapsed();
                 let status_code = response.status().as_u16();
@@ -415,6 +410,17 @@ impl ApiClient {
         Ok(result.is_healthy)
     }
 
+    /// エラーレスポンスをアプリのエラーに変換する
+    async fn error_from_response(&self, response: Response) -> AppError {
+        match self.handle_error_response(response).await {
+            Ok(error_response) => AppError::ExternalService(format!(
+                "APIサーバーエラー: {} - {}",
+                error_response.error.code, error_response.error.message
+            )),
+            Err(e) => e,
+        }
+    }
+
     /// エラーレスポンスを処理し、詳細なエラー情報を提供
     async fn handle_error_response(&self, response: Response) -> Result<ErrorResponse, AppError> {
         let status = response.status();
@@ -513,3 +519,84 @@ impl ApiClient {
         .to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_with_timeout_override_applies_to_health_check() {
+        // 接続を受け付けるだけで応答しないサーバー
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut sockets = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                sockets.push(socket);
+            }
+        });
+
+        let client = ApiClient::new(ApiClientConfig {
+            base_url,
+            timeout_seconds: 30,
+            max_retries: 0,
+        })
+        .unwrap()
+        .with_timeout_override(Duration::from_secs(60), Duration::from_millis(200));
+
+        let started = std::time::Instant::now();
+        let result = client.health_check_detailed().await.unwrap();
+
+        assert!(!result.is_healthy);
+        assert_eq!(result.error_message.as_deref(), Some("接続タイムアウト"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_delete_file_honors_retry_after_and_shared_cooldown() {
+        // 1回目は429（Retry-After: 1秒）、2回目は成功を返すサーバー
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let responses = [
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 1\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ];
+        let server = tokio::spawn(async move {
+            let mut arrivals = Vec::new();
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buffer = Vec::new();
+                let mut chunk = [0_u8; 1024];
+                while !buffer.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let read = socket.read(&mut chunk).await.unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    buffer.extend_from_slice(&chunk[..read]);
+                }
+                arrivals.push(tokio::time::Instant::now());
+                socket.write_all(response.as_bytes()).await.unwrap();
+                socket.shutdown().await.ok();
+            }
+            arrivals
+        });
+
+        let cooldown = Arc::new(RateLimitCooldown::new());
+        let client = ApiClient::new(ApiClientConfig {
+            base_url,
+            timeout_seconds: 10,
+            max_retries: 0,
+        })
+        .unwrap()
+        .with_cooldown(Arc::clone(&cooldown));
+
+        assert!(client.delete_file("receipt.jpg", "token").await.unwrap());
+
+        let arrivals = server.await.unwrap();
+        assert!(arrivals[1].duration_since(arrivals[0]) >= Duration::from_millis(950));
+        assert_eq!(cooldown.status().last_status_code, Some(429));
+        assert_eq!(cooldown.status().rate_limited_count, 1);
+    }
+}
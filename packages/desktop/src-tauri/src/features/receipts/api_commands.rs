@@ -1,11 +1,30 @@
 /// 領収書関連のAPIコマンド
 /// APIサーバー経由で領収書の取得・操作を行う
 use crate::features::auth::middleware::AuthMiddleware;
+use crate::features::expenses::location::{is_location_storage_enabled, save_expense_location};
 use crate::features::receipts::api_client::{ApiClient, ApiClientConfig};
+use crate::features::receipts::cache::{CacheManager, CACHE_NEAR_FULL_THRESHOLD};
+use crate::features::receipts::connectivity::{ensure_storage_available, record_storage_probe};
+use crate::features::receipts::exif::{sanitize_receipt, GpsCoordinates, SanitizedReceipt};
+use crate::features::receipts::models::{
+    CacheNearFullEvent, PerformanceStats, PerformanceStatsAccumulator,
+};
+use crate::features::receipts::recompress::{
+    ApiReceiptStore, ReceiptStore, RecompressJob, RecompressOptions, RecompressReport,
+};
+use crate::features::receipts::url::{parse_receipt_url, ReceiptUrlConfig};
 use crate::shared::api_client::ApiClient as SharedApiClient;
+use crate::shared::database::lock_db;
+use crate::shared::events::{emit_event, AppEvent};
+use crate::shared::operations::{begin_operation, OperationKind};
+use crate::shared::rate_limit::{shared_cooldown, RateLimitStatus};
+use crate::shared::utils::get_current_jst_timestamp;
+use crate::AppState;
 use log::{debug, error, info, warn};
+use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
 
 /// 領収書取得のレスポンス
 #[derive(Debug, Serialize, Deserialize)]
@@ -52,6 +71,9 @@ pub struct HealthCheckResponse {
     pub timestamp: String,
     pub version: String,
     pub environment: String,
+    /// クライアント側のレート制限の状態
+    #[serde(default)]
+    pub rate_limit: RateLimitStatus,
 }
 
 /// APIサーバー経由で領収書を取得する
@@ -60,6 +82,7 @@ pub struct HealthCheckResponse {
 /// * `receipt_url` - 領収書URL
 /// * `session_token` - セッショントークン
 /// * `auth_middleware` - 認証ミドルウェア
+/// * `force` - ストレージ停止中でも試行する場合はtrue
 ///
 /// # 戻り値
 /// 領収書データ（Base64エンコード）、または失敗時はエラーメッセージ
@@ -67,6 +90,7 @@ pub struct HealthCheckResponse {
 pub async fn get_receipt_via_api(
     receipt_url: String,
     session_token: Option<String>,
+    force: Option<bool>,
     auth_middleware: State<'_, AuthMiddleware>,
 ) -> Result<String, String> {
     info!("APIサーバー経由で領収書取得開始: receipt_url={receipt_url}");
@@ -82,6 +106,9 @@ pub async fn get_receipt_via_api(
 
     debug!("認証成功 - ユーザーID: {}", user.id);
 
+    // ストレージ停止が確認されている場合は即座に失敗させる
+    ensure_storage_available(force.unwrap_or(false))?;
+
     // URLの基本検証
     if !receipt_url.starts_with("https://") {
         return Err("無効な領収書URLです".to_string());
@@ -110,6 +137,7 @@ pub async fn get_receipt_via_api(
             format!("領収書の取得に失敗しました: {e}")
         })?;
 
+    record_storage_probe(true);
     info!(
         "領収書取得成功 - ユーザーID: {}, ファイルサイズ: {} bytes",
         user.id, response.file_size
@@ -124,20 +152,34 @@ pub async fn get_receipt_via_api(
 /// * `expense_id` - 経費ID
 /// * `file_path` - ファイルパス
 /// * `session_token` - セッショントークン
+/// * `app` - Tauriアプリハンドル
+/// * `state` - アプリケーション状態
 /// * `auth_middleware` - 認証ミドルウェア
+/// * `force` - ストレージ停止中でも試行する場合はtrue
 ///
 /// # 戻り値
 /// アップロード結果、または失敗時はエラーメッセージ
+///
+/// EXIFなどのメタデータはアップロード前に必ず削除します。
+/// 位置情報の保存を許可している場合のみ、削除前にGPS座標を取り出して経費に保存します。
+/// 領収書キャッシュの使用率が90%以上の場合は、受け付ける前に`cache-near-full`イベントを送信します。
 #[tauri::command]
 pub async fn upload_receipt_via_api(
     expense_id: i64,
     file_path: String,
     session_token: Option<String>,
+    force: Option<bool>,
+    app: AppHandle,
+    state: State<'_, AppState>,
     auth_middleware: State<'_, AuthMiddleware>,
 ) -> Result<String, String> {
     info!(
         "APIサーバー経由で領収書アップロード開始: expense_id={expense_id}, file_path={file_path}"
     );
+    let _operation = begin_operation(
+        OperationKind::ReceiptUpload,
+        format!("領収書のアップロード（expense_id={expense_id}）"),
+    );
 
     // 認証チェック
     let user = auth_middleware
@@ -150,44 +192,82 @@ pub async fn upload_receipt_via_api(
 
     debug!("認証成功 - ユーザーID: {}", user.id);
 
+    // ストレージ停止が確認されている場合は即座に失敗させる
+    ensure_storage_available(force.unwrap_or(false))?;
+
     // セッショントークンが必要
     let token = session_token.ok_or_else(|| {
         error!("セッショントークンが提供されていません");
         "セッショントークンが必要です".to_string()
     })?;
 
+    // キャッシュの空き容量を確認
+    notify_if_cache_near_full(&app, &state);
+
+    // 保存先を作成
+    let store = ApiReceiptStore::new(user.id.clone(), token).map_err(|e| {
+        error!("APIクライアント作成エラー: {e}");
+        format!("APIクライアント作成エラー: {e}")
+    })?;
+
+    upload_receipt_internal(&store, &state.db, &user.id, expense_id, &file_path).await
+}
+
+/// 領収書ファイルのメタデータを削除して保存先にアップロードする
+///
+/// # 引数
+/// * `store` - 領収書の保存先
+/// * `db` - データベース接続
+/// * `user_id` - ユーザーID
+/// * `expense_id` - 経費ID
+/// * `file_path` - ファイルパス
+///
+/// # 戻り値
+/// アップロードした領収書のURL、または失敗時はエラーメッセージ
+///
+/// 位置情報の保存を許可している場合のみ、削除前にGPS座標を取り出して経費に保存します。
+pub(crate) async fn upload_receipt_internal(
+    store: &impl ReceiptStore,
+    db: &Mutex<Connection>,
+    user_id: &str,
+    expense_id: i64,
+    file_path: &str,
+) -> Result<String, String> {
     // ファイルの存在確認
-    if !std::path::Path::new(&file_path).exists() {
+    if !std::path::Path::new(file_path).exists() {
         return Err("指定されたファイルが存在しません".to_string());
     }
 
     // ファイルを読み込み
-    let file_data = tokio::fs::read(&file_path).await.map_err(|e| {
+    let file_data = tokio::fs::read(file_path).await.map_err(|e| {
         error!("ファイル読み込みエラー: {e}");
         format!("ファイル読み込みエラー: {e}")
     })?;
 
+    // 位置情報の保存設定を確認し、メタデータを削除する
+    let store_location = lock_db(db)
+        .and_then(|db| is_location_storage_enabled(&db, user_id))
+        .map_err(|e| format!("位置情報の設定の取得に失敗しました: {e}"))?;
+    let SanitizedReceipt {
+        data: file_data,
+        location,
+    } = sanitize_receipt(&file_data, store_location);
+
     // ファイル名を取得
-    let filename = std::path::Path::new(&file_path)
+    let filename = std::path::Path::new(file_path)
         .file_name()
         .and_then(|name| name.to_str())
         .ok_or_else(|| "ファイル名を取得できません".to_string())?;
 
-    // APIクライアントを作成
-    let config = ApiClientConfig::from_env();
-    let api_client = ApiClient::new(config).map_err(|e| {
-        error!("APIクライアント作成エラー: {e}");
-        format!("APIクライアント作成エラー: {e}")
-    })?;
-
-    // ファイルをアップロード（ユーザーIDを渡す）
-    match api_client
-        .upload_file(expense_id, &file_data, filename, &user.id, &token)
-        .await
-    {
-        Ok(response) => {
-            let file_url = response.file_url.unwrap_or_else(|| "".to_string());
+    // ファイルをアップロード
+    match store.upload(expense_id, &file_data, filename).await {
+        Ok(file_url) => {
+            record_storage_probe(true);
             info!("ファイルアップロード成功: file_url={file_url}");
+
+            if let Some(location) = location {
+                save_uploaded_receipt_location(db, user_id, expense_id, &location);
+            }
             Ok(file_url)
         }
         Err(e) => {
@@ -197,6 +277,56 @@ pub async fn upload_receipt_via_api(
     }
 }
 
+/// 領収書キャッシュの使用率が高い場合に警告イベントを送信する
+///
+/// 確認に失敗してもアップロードは続行します。
+fn notify_if_cache_near_full(app: &AppHandle, state: &State<'_, AppState>) {
+    let cache_dir = match app.path().app_data_dir() {
+        Ok(dir) => dir.join("receipt_cache"),
+        Err(e) => {
+            warn!("アプリデータディレクトリの取得に失敗しました: {e}");
+            return;
+        }
+    };
+    let cache_manager = CacheManager::new(cache_dir, 100);
+    let stats = state.try_db(|db| cache_manager.stats(db));
+
+    match stats {
+        Ok(stats) if stats.is_full(CACHE_NEAR_FULL_THRESHOLD) => {
+            let event = CacheNearFullEvent {
+                utilization_percent: stats.utilization_percent(),
+                stats,
+            };
+            warn!(
+                "領収書キャッシュの使用率が高くなっています: {:.1}%",
+                event.utilization_percent
+            );
+            if let Err(e) = emit_event(app, AppEvent::CacheNearFull(event)) {
+                warn!("キャッシュ容量警告イベントの送信に失敗しました: {e}");
+            }
+        }
+        Ok(_) => {}
+        Err(e) => warn!("キャッシュ使用率の確認に失敗しました: {e}"),
+    }
+}
+
+/// アップロードした領収書の位置情報を経費に保存する
+///
+/// 位置情報の保存に失敗してもアップロード自体は成功として扱います。
+fn save_uploaded_receipt_location(
+    db: &Mutex<Connection>,
+    user_id: &str,
+    expense_id: i64,
+    location: &GpsCoordinates,
+) {
+    let result =
+        lock_db(db).and_then(|db| save_expense_location(&db, user_id, expense_id, location));
+    match result {
+        Ok(()) => debug!("経費の位置情報を保存しました: expense_id={expense_id}"),
+        Err(e) => warn!("経費の位置情報の保存に失敗しました: expense_id={expense_id}, error={e}"),
+    }
+}
+
 /// APIサーバー経由で複数の領収書をアップロードする
 ///
 /// # 引数
@@ -216,6 +346,10 @@ pub async fn upload_multiple_receipts_via_api(
         "APIサーバー経由で複数領収書アップロード開始: ファイル数={}",
         file_paths.len()
     );
+    let _operation = begin_operation(
+        OperationKind::ReceiptUpload,
+        format!("領収書の一括アップロード（{}件）", file_paths.len()),
+    );
 
     // 認証チェック
     let user = auth_middleware
@@ -270,14 +404,17 @@ pub async fn check_api_server_health() -> Result<HealthCheckResponse, String> {
     })?;
 
     // ヘルスチェックエンドポイントを呼び出し
-    let response = api_client
+    let mut response = api_client
         .get::<HealthCheckResponse>("/api/v1/health", None)
         .await
         .map_err(|e| {
             error!("ヘルスチェックエラー: {e}");
+            record_storage_probe(false);
             format!("APIサーバーへの接続に失敗しました: {e}")
         })?;
 
+    record_storage_probe(true);
+    response.rate_limit = shared_cooldown().status();
     info!("APIサーバーヘルスチェック成功: status={}", response.status);
 
     Ok(response)
@@ -298,19 +435,84 @@ pub async fn check_api_server_health_detailed() -> Result<serde_json::Value, Str
     })?;
 
     // 詳細ヘルスチェックエンドポイントを呼び出し
-    let response = api_client
+    let mut response = api_client
         .get::<serde_json::Value>("/api/v1/health/detailed", None)
         .await
         .map_err(|e| {
             error!("詳細ヘルスチェックエラー: {e}");
+            record_storage_probe(false);
             format!("APIサーバーへの接続に失敗しました: {e}")
         })?;
 
+    record_storage_probe(true);
+    if let Some(details) = response.as_object_mut() {
+        details.insert(
+            "rate_limit".to_string(),
+            serde_json::to_value(shared_cooldown().status()).unwrap_or_default(),
+        );
+    }
     info!("APIサーバー詳細ヘルスチェック成功");
 
     Ok(response)
 }
 
+/// パフォーマンス測定の回数
+const PERFORMANCE_MEASUREMENT_ROUNDS: usize = 3;
+
+/// R2（APIサーバー経由）のパフォーマンス統計を取得する
+///
+/// ヘルスチェックを複数回実行し、レイテンシの平均を返します。
+/// ヘルスチェックでは転送量を計測しないため、スループットは0になります。
+///
+/// # 戻り値
+/// 集計したパフォーマンス統計、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn get_r2_performance_stats() -> Result<PerformanceStats, String> {
+    info!("R2パフォーマンス測定開始: rounds={PERFORMANCE_MEASUREMENT_ROUNDS}");
+
+    // APIクライアントを作成
+    let config = ApiClientConfig::from_env();
+    let api_client = ApiClient::new(config).map_err(|e| {
+        error!("APIクライアント作成エラー: {e}");
+        format!("APIクライアント作成エラー: {e}")
+    })?;
+
+    let mut accumulator = PerformanceStatsAccumulator::default();
+    for round in 1..=PERFORMANCE_MEASUREMENT_ROUNDS {
+        let result = api_client
+            .health_check_detailed()
+            .await
+            .map_err(|e| format!("パフォーマンス測定エラー: {e}"))?;
+        debug!(
+            "パフォーマンス測定: round={round}, latency_ms={}, healthy={}",
+            result.response_time_ms, result.is_healthy
+        );
+
+        record_storage_probe(result.is_healthy);
+        accumulator.push(PerformanceStats {
+            latency_ms: result.response_time_ms,
+            throughput_bps: 0,
+            connection_status: if result.is_healthy {
+                "connected".to_string()
+            } else {
+                "disconnected".to_string()
+            },
+            last_measured: get_current_jst_timestamp(),
+        });
+    }
+
+    let stats = accumulator.average();
+    info!(
+        "R2パフォーマンス測定完了: average_ms={}, p95_ms={}, p99_ms={}, status={}",
+        stats.latency_ms,
+        accumulator.p95_latency_ms(),
+        accumulator.p99_latency_ms(),
+        stats.connection_status
+    );
+
+    Ok(stats)
+}
+
 /// フォールバックファイルの同期
 ///
 /// # 戻り値
@@ -325,7 +527,7 @@ pub async fn sync_fallback_files() -> Result<serde_json::Value, String> {
     Ok(serde_json::json!({
         "success": false,
         "message": "フォールバックファイル同期は現在サポートされていません",
-        "timestamp": chrono::Utc::now().to_rfc3339()
+        "timestamp": get_current_jst_timestamp()
     }))
 }
 
@@ -349,6 +551,7 @@ pub async fn get_fallback_file_count() -> Result<i32, String> {
 /// * `receipt_url` - 領収書URL
 /// * `session_token` - セッショントークン
 /// * `auth_middleware` - 認証ミドルウェア
+/// * `force` - ストレージ停止中でも試行する場合はtrue
 ///
 /// # 戻り値
 /// 削除成功の場合はtrue、または失敗時はエラーメッセージ
@@ -356,6 +559,7 @@ pub async fn get_fallback_file_count() -> Result<i32, String> {
 pub async fn delete_receipt_via_api(
     receipt_url: String,
     session_token: Option<String>,
+    force: Option<bool>,
     auth_middleware: State<'_, AuthMiddleware>,
 ) -> Result<bool, String> {
     info!("APIサーバー経由で領収書削除開始: receipt_url={receipt_url}");
@@ -371,6 +575,9 @@ pub async fn delete_receipt_via_api(
 
     debug!("認証成功 - ユーザーID: {}", user.id);
 
+    // ストレージ停止が確認されている場合は即座に失敗させる
+    ensure_storage_available(force.unwrap_or(false))?;
+
     // セッショントークンが必要
     let token = session_token.ok_or_else(|| {
         error!("セッショントークンが提供されていません");
@@ -430,6 +637,7 @@ pub async fn delete_receipt_via_api(
     info!("レスポンス解析結果: success={success}");
 
     if success {
+        record_storage_probe(true);
         info!(
             "領収書削除成功 - ユーザーID: {}, receipt_url: {receipt_url}",
             user.id
@@ -446,38 +654,216 @@ pub async fn delete_receipt_via_api(
     }
 }
 
+/// アップロード済みの領収書を再圧縮して置き換える
+///
+/// # 引数
+/// * `options` - 再圧縮の設定（省略時は既定値）
+/// * `session_token` - セッショントークン
+/// * `app` - Tauriアプリハンドル
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 削減されたバイト数とスキップ・失敗した領収書を含む結果、または失敗時はエラーメッセージ
+///
+/// 中断した場合は、次回の実行時に続きから再開します。
+/// `dry_run`を指定した場合は置き換えずに削減見込みのみを返します。
+#[tauri::command]
+pub async fn recompress_existing_receipts(
+    options: Option<RecompressOptions>,
+    session_token: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, AuthMiddleware>,
+) -> Result<RecompressReport, String> {
+    let options = options.unwrap_or_default();
+    info!("領収書の再圧縮を開始します: options={options:?}");
+
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/api/receipts/recompress")
+        .await
+        .map_err(|e| {
+            error!("認証エラー: {e}");
+            format!("認証エラー: {e}")
+        })?;
+
+    // ストレージ停止が確認されている場合は即座に失敗させる
+    ensure_storage_available(false)?;
+
+    let token = session_token.ok_or_else(|| "セッショントークンが必要です".to_string())?;
+
+    let _operation = (!options.dry_run).then(|| {
+        begin_operation(
+            OperationKind::DatabaseUpdate,
+            "アップロード済み領収書の再圧縮",
+        )
+    });
+
+    let cache_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("アプリデータディレクトリの取得に失敗しました: {e}"))?
+        .join("receipt_cache");
+    let cache_manager = CacheManager::new(cache_dir, 100);
+    let store = ApiReceiptStore::new(user.id.clone(), token)
+        .map_err(|e| format!("APIクライアント作成エラー: {e}"))?;
+
+    RecompressJob::new(&store, &state.db, &cache_manager, &user.id, options)
+        .run()
+        .await
+        .map_err(|e| {
+            error!("領収書の再圧縮に失敗しました: {e}");
+            format!("領収書の再圧縮に失敗しました: {e}")
+        })
+}
+
 /// URLからファイルキーを抽出する
 ///
+/// R2エンドポイント形式・カスタムドメイン形式のどちらにも対応します
+/// （`super::url::parse_receipt_url`を参照）。
+///
 /// # 引数
 /// * `url` - 領収書URL
 ///
 /// # 戻り値
 /// ファイルキー、または失敗時はエラーメッセージ
-fn extract_file_key_from_url(url: &str) -> Result<String, String> {
-    // R2 URLの形式: https://{account_id}.r2.cloudflarestorage.com/{bucket_name}/{file_key}
-    // または: https://r2.cloudflarestorage.com/{bucket_name}/{file_key}
+pub(crate) fn extract_file_key_from_url(url: &str) -> Result<String, String> {
+    let parsed = parse_receipt_url(url, &ReceiptUrlConfig::from_env())?;
+
+    debug!(
+        "URLからファイルキーを抽出: url={url}, shape={:?}, file_key={}",
+        parsed.shape, parsed.file_key
+    );
+
+    Ok(parsed.file_key)
+}
 
-    let url_parts: Vec<&str> = url.split('/').collect();
-    if url_parts.len() < 5 {
-        return Err("URLの形式が正しくありません".to_string());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::expenses::location::{
+        add_expense_location_columns, set_location_storage_enabled,
+    };
+    use crate::shared::database::connection::create_in_memory_connection;
+    use crate::shared::errors::{AppError, AppResult};
+
+    const USER_ID: &str = "user-1";
+
+    /// アップロードしたデータを記録する保存先
+    #[derive(Default)]
+    struct MockReceiptStore {
+        uploads: Mutex<Vec<(i64, Vec<u8>, String)>>,
+        /// アップロードを失敗させる
+        fail_uploads: bool,
     }
 
-    // バケット名（url_parts[3]）を除いて、その後の部分をファイルキーとして取得
-    let file_key_parts = &url_parts[4..];
-    let file_key = file_key_parts.join("/");
+    impl ReceiptStore for MockReceiptStore {
+        async fn download(&self, receipt_url: &str) -> AppResult<Vec<u8>> {
+            Err(AppError::not_found(receipt_url))
+        }
+
+        async fn upload(&self, expense_id: i64, data: &[u8], filename: &str) -> AppResult<String> {
+            if self.fail_uploads {
+                return Err(AppError::ExternalService("アップロード失敗".to_string()));
+            }
+            self.uploads
+                .lock()
+                .unwrap()
+                .push((expense_id, data.to_vec(), filename.to_string()));
+            Ok(format!(
+                "https://receipts.example.com/users/{USER_ID}/receipts/{expense_id}/{filename}"
+            ))
+        }
 
-    if file_key.is_empty() {
-        return Err("ファイルキーの抽出に失敗しました".to_string());
+        async fn delete(&self, _receipt_url: &str) -> AppResult<()> {
+            Ok(())
+        }
     }
 
-    debug!("URLからファイルキーを抽出: url={url}, file_key={file_key}");
+    fn test_db() -> Mutex<Connection> {
+        let conn = create_in_memory_connection().unwrap();
+        add_expense_location_columns(&conn).unwrap();
+        Mutex::new(conn)
+    }
 
-    Ok(file_key)
-}
+    /// 一時ディレクトリに領収書ファイルを作成する
+    fn write_receipt(dir: &tempfile::TempDir, name: &str, data: &[u8]) -> String {
+        let path = dir.path().join(name);
+        std::fs::write(&path, data).unwrap();
+        path.to_string_lossy().to_string()
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[tokio::test]
+    async fn test_upload_receipt_internal_uploads_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = write_receipt(&dir, "receipt.pdf", b"%PDF-1.4 receipt");
+        let store = MockReceiptStore::default();
+        let db = test_db();
+
+        let url = upload_receipt_internal(&store, &db, USER_ID, 7, &file_path)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            url,
+            "https://receipts.example.com/users/user-1/receipts/7/receipt.pdf"
+        );
+        let uploads = store.uploads.lock().unwrap();
+        assert_eq!(uploads.len(), 1);
+        assert_eq!(
+            uploads[0],
+            (7, b"%PDF-1.4 receipt".to_vec(), "receipt.pdf".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upload_receipt_internal_with_location_storage_enabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = write_receipt(&dir, "receipt.pdf", b"%PDF-1.4 receipt");
+        let store = MockReceiptStore::default();
+        let db = test_db();
+        set_location_storage_enabled(&db.lock().unwrap(), USER_ID, true).unwrap();
+
+        // 位置情報を含まないファイルもそのままアップロードする
+        let result = upload_receipt_internal(&store, &db, USER_ID, 7, &file_path).await;
+
+        assert!(result.is_ok());
+        assert_eq!(store.uploads.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_upload_receipt_internal_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("missing.png").to_string_lossy().to_string();
+        let store = MockReceiptStore::default();
+        let db = test_db();
+
+        let result = upload_receipt_internal(&store, &db, USER_ID, 7, &file_path).await;
+
+        assert_eq!(
+            result.unwrap_err(),
+            "指定されたファイルが存在しません".to_string()
+        );
+        assert!(store.uploads.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_upload_receipt_internal_upload_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = write_receipt(&dir, "receipt.pdf", b"%PDF-1.4 receipt");
+        let store = MockReceiptStore {
+            fail_uploads: true,
+            ..Default::default()
+        };
+        let db = test_db();
+
+        let result = upload_receipt_internal(&store, &db, USER_ID, 7, &file_path).await;
+
+        assert!(result
+            .unwrap_err()
+            .starts_with("ファイルアップロードエラー"));
+    }
 
     #[test]
     fn test_extract_file_key_from_url() {
@@ -499,10 +885,17 @@ mod tests {
         assert!(result3.is_ok());
         assert_eq!(result3.unwrap(), "users/2/receipts/6/1766576410-52dd0bc2-4e34-4d20-9ae4-2f69d0ccb255-christmas-amidakuji-result-2025-12-24T10-15-07.png");
 
-        // 無効なURL（パーツが不足）
-        let invalid_url = "https://example.com/file.jpg";
-        let result4 = extract_file_key_from_url(invalid_url);
-        assert!(result4.is_err());
+        // カスタムドメインのURL（バケット名を含まない）
+        let url4 = "https://receipts.example.com/users/2/receipts/6/test.png";
+        assert_eq!(
+            extract_file_key_from_url(url4).unwrap(),
+            "users/2/receipts/6/test.png"
+        );
+
+        // 無効なURL（ファイルキーがない）
+        let invalid_url = "https://example.com/";
+        let result5 = extract_file_key_from_url(invalid_url);
+        assert!(result5.is_err());
     }
 
     #[test]
@@ -1,11 +1,14 @@
 // ユーザー認証付きR2コマンド
-// R2ユーザーディレクトリ移行機能のための認証付きコマンド
+// R2ユーザーディレクトリ移行機能のための認証付きコマンドと、領収書の所有者確認
 
 use std::sync::Arc;
 
 use crate::features::auth::middleware::AuthMiddleware;
 use crate::features::auth::service::AuthService;
 use crate::features::security::service::SecurityManager;
+use crate::shared::errors::{AppError, AppResult};
+use crate::AppState;
+use rusqlite::{params, Connection};
 use tauri::State;
 
 /// 認証ミドルウェアを作成するヘルパー関数
@@ -16,8 +19,145 @@ async fn create_auth_middleware(
 ) -> Result<AuthMiddleware, String> {
     let auth_service = Arc::new(auth_service.inner().clone());
 
-    // セキュリティサービスを取得（SecurityManagerはSecurityServiceのエイリアス）
-    let security_service = Arc::new(security_manager.clone());
+    // SecurityManagerはSecurityServiceのエイリアス
+    Ok(AuthMiddleware::new(auth_service, security_manager.clone()))
+}
+
+/// 領収書を参照している経費・サブスクリプションの所有者を取得する
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `receipt_url` - 領収書URL
+///
+/// # 戻り値
+/// 所有者のユーザーID一覧（参照されていない場合は空）
+pub fn find_receipt_owners(conn: &Connection, receipt_url: &str) -> AppResult<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT user_id FROM expenses WHERE receipt_url = ?1
+         UNION
+         SELECT user_id FROM subscriptions WHERE receipt_path = ?1",
+    )?;
+    let owners = stmt
+        .query_map(params![receipt_url], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(owners)
+}
+
+/// 領収書がユーザーの経費またはサブスクリプションに添付されていることを確認する
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `receipt_url` - 領収書URL
+/// * `user_id` - 認証済みのユーザーID
+///
+/// # 戻り値
+/// 所有者の場合はOk、参照されていない場合はNotFound、他のユーザーの領収書の場合はセキュリティエラー
+pub fn ensure_receipt_owner(conn: &Connection, receipt_url: &str, user_id: &str) -> AppResult<()> {
+    let owners = find_receipt_owners(conn, receipt_url)?;
+    if owners.is_empty() {
+        return Err(AppError::not_found("領収書"));
+    }
+    if !owners.iter().any(|owner| owner == user_id) {
+        return Err(AppError::Security(
+            "この領収書へのアクセス権限がありません".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// 認証済みのユーザーが領収書にアクセスできるかを確認する
+///
+/// # 引数
+/// * `receipt_url` - 領収書URL
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// アクセスできる場合はtrue、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn verify_receipt_access(
+    receipt_url: String,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, AuthMiddleware>,
+) -> Result<bool, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/receipts/access")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| ensure_receipt_owner(db, &receipt_url, &user.id))
+        .map_err(|e| {
+            log::warn!(
+                "領収書へのアクセスを拒否しました: user_id={}, error={e}",
+                user.id
+            );
+            e.user_message().to_string()
+        })?;
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::database::connection::open_and_migrate_in_memory_database;
+
+    const RECEIPT_URL: &str = "https://receipts.example.com/users/u1/receipt.jpg";
+
+    fn insert_expense(conn: &Connection, user_id: &str, receipt_url: &str) {
+        conn.execute(
+            "INSERT INTO expenses (date, amount, category, receipt_url, user_id, created_at, updated_at)
+             VALUES ('2024-01-10', 1000, '交通費', ?1, ?2, '2024-01-10T00:00:00+09:00', '2024-01-10T00:00:00+09:00')",
+            params![receipt_url, user_id],
+        )
+        .unwrap();
+    }
+
+    fn insert_subscription(conn: &Connection, user_id: &str, receipt_path: &str) {
+        conn.execute(
+            "INSERT INTO subscriptions (name, amount, billing_cycle, start_date, category, receipt_path, user_id, created_at, updated_at)
+             VALUES ('動画配信', 990, 'monthly', '2024-01-01', 'その他', ?1, ?2, '2024-01-01T00:00:00+09:00', '2024-01-01T00:00:00+09:00')",
+            params![receipt_path, user_id],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_ensure_receipt_owner_for_expense() {
+        let conn = open_and_migrate_in_memory_database().unwrap();
+        insert_expense(&conn, "u1", RECEIPT_URL);
+
+        assert!(ensure_receipt_owner(&conn, RECEIPT_URL, "u1").is_ok());
+        assert!(matches!(
+            ensure_receipt_owner(&conn, RECEIPT_URL, "u2"),
+            Err(AppError::Security(_))
+        ));
+    }
+
+    #[test]
+    fn test_ensure_receipt_owner_for_subscription() {
+        let conn = open_and_migrate_in_memory_database().unwrap();
+        insert_subscription(&conn, "u1", RECEIPT_URL);
+
+        assert!(ensure_receipt_owner(&conn, RECEIPT_URL, "u1").is_ok());
+        assert!(matches!(
+            ensure_receipt_owner(&conn, RECEIPT_URL, "u2"),
+            Err(AppError::Security(_))
+        ));
+    }
+
+    #[test]
+    fn test_ensure_receipt_owner_unknown_receipt() {
+        let conn = open_and_migrate_in_memory_database().unwrap();
+        insert_expense(&conn, "u1", RECEIPT_URL);
 
-    Ok(AuthMiddleware::new(auth_service, security_service))
+        assert!(matches!(
+            ensure_receipt_owner(&conn, "https://receipts.example.com/other.jpg", "u1"),
+            Err(AppError::NotFound(_))
+        ));
+    }
 }
@@ -1,7 +1,9 @@
 // ローカルキャッシュ管理モジュール
 
-use super::models::ReceiptCache;
+use super::cache_policy::load_cache_policy;
+use super::models::{CacheStats, EvictionStrategy, ReceiptCache};
 use crate::shared::errors::{AppError, AppResult};
+use crate::shared::utils::disk_space::{check_disk_space, CACHE_WRITE_HEADROOM_BYTES};
 use rusqlite::Connection;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
@@ -9,7 +11,20 @@ use std::time::Duration;
 /// デフォルトユーザーID（既存データ用）
 const DEFAULT_USER_ID: &str = "1";
 
+/// 空き容量の警告を出すキャッシュ使用率の閾値
+pub const CACHE_NEAR_FULL_THRESHOLD: f32 = 0.9;
+
+/// キャッシュの管理で適用する上限
+struct CacheLimits {
+    max_size_bytes: u64,
+    max_age: Option<Duration>,
+    eviction_strategy: EvictionStrategy,
+}
+
 /// ローカルキャッシュマネージャー
+///
+/// 最大サイズと保持期間は、`cache_config`テーブルに保持ポリシーが保存されている場合はそちらを優先し、
+/// 未設定の場合は初期化時の値を使用します。
 pub struct CacheManager {
     cache_dir: PathBuf,
     pub max_cache_size: u64,
@@ -56,6 +71,7 @@ impl CacheManager {
     ///
     /// # 戻り値
     /// キャッシュファイルのパス、または失敗時はAppError
+    /// （ディスクの空き容量が少ない場合は保存せずにエラーを返すため、呼び出し側はキャッシュなしで続行する）
     pub fn cache_file(
         &self,
         receipt_url: &str,
@@ -66,6 +82,12 @@ impl CacheManager {
         // キャッシュディレクトリを確認・作成
         self.initialize_sync()?;
 
+        // 空き容量が少ない場合はキャッシュしない
+        check_disk_space(
+            &self.cache_dir,
+            data.len() as u64 + CACHE_WRITE_HEADROOM_BYTES,
+        )?;
+
         // ファイル名を生成（URLからハッシュを作成）
         let filename = self.generate_cache_filename(receipt_url);
         let cache_path = self.cache_dir.join(&filename);
@@ -132,8 +154,32 @@ impl CacheManager {
         Ok(None)
     }
 
+    /// 保存された保持ポリシーから上限を取得する
+    ///
+    /// # 引数
+    /// * `conn` - データベース接続
+    ///
+    /// # 戻り値
+    /// 適用する上限（ポリシーが未設定の場合は初期化時の値とLRU方式）
+    fn limits(&self, conn: &Connection) -> AppResult<CacheLimits> {
+        Ok(match load_cache_policy(conn)? {
+            Some(policy) => CacheLimits {
+                max_size_bytes: policy.max_size_bytes(),
+                max_age: policy.max_age(),
+                eviction_strategy: policy.eviction_strategy,
+            },
+            None => CacheLimits {
+                max_size_bytes: self.max_cache_size,
+                max_age: Some(self.max_age),
+                eviction_strategy: EvictionStrategy::default(),
+            },
+        })
+    }
+
     /// 古いキャッシュを削除（同期版）
     ///
+    /// 保持ポリシーで保持日数が無期限に設定されている場合は何も削除しません。
+    ///
     /// # 引数
     /// * `conn` - データベース接続
     /// * `user_id` - ユーザーID（Noneの場合は全ユーザー対象）
@@ -141,31 +187,52 @@ impl CacheManager {
     /// # 戻り値
     /// 削除されたファイル数、または失敗時はAppError
     pub fn cleanup_old_cache(&self, conn: &Connection, user_id: Option<&str>) -> AppResult<usize> {
-        let max_age_days = self.max_age.as_secs() / (24 * 3600);
-
-        // データベースから古いキャッシュ情報を取得して物理ファイルも削除
-        let old_caches = self.get_old_cache_entries(conn, max_age_days as i64, user_id)?;
+        match self.limits(conn)?.max_age {
+            Some(max_age) => self.remove_expired_cache(conn, user_id, max_age),
+            None => Ok(0),
+        }
+    }
 
-        let mut _deleted_count = 0;
-        for cache in &old_caches {
+    /// 最後のアクセスから保持期間を超えたキャッシュを削除する
+    fn remove_expired_cache(
+        &self,
+        conn: &Connection,
+        user_id: Option<&str>,
+        max_age: Duration,
+    ) -> AppResult<usize> {
+        // データベースから期限切れのキャッシュ情報を取得して物理ファイルも削除
+        let expired_caches: Vec<ReceiptCache> = self
+            .get_cache_entries(conn, user_id)?
+            .into_iter()
+            .filter(|cache| cache.is_expired(max_age))
+            .collect();
+
+        let mut db_deleted_count = 0;
+        for cache in &expired_caches {
             let cache_path = Path::new(&cache.local_path);
             if cache_path.exists() {
                 if let Err(e) = std::fs::remove_file(cache_path) {
                     eprintln!("キャッシュファイル削除エラー: {} ({})", cache.local_path, e);
-                } else {
-                    _deleted_count += 1;
                 }
             }
-        }
 
-        // データベースから古いキャッシュ情報を削除
-        let db_deleted_count = self.cleanup_old_cache_db(conn, max_age_days as i64, user_id)?;
+            // データベースから期限切れのキャッシュ情報を削除
+            db_deleted_count += conn
+                .execute(
+                    "DELETE FROM receipt_cache WHERE id = ?1",
+                    rusqlite::params![cache.id],
+                )
+                .map_err(|e| AppError::Database(format!("古いキャッシュ削除失敗: {e}")))?;
+        }
 
         Ok(db_deleted_count)
     }
 
     /// キャッシュサイズを管理（同期版）
     ///
+    /// 実行のたびに保存された保持ポリシーを読み込み、最大サイズを超えている場合は
+    /// 期限切れのキャッシュを削除したうえで、ポリシーの削除方式で上限以下になるまで削除します。
+    ///
     /// # 引数
     /// * `conn` - データベース接続
     /// * `user_id` - ユーザーID（Noneの場合は全ユーザー対象）
@@ -173,17 +240,26 @@ impl CacheManager {
     /// # 戻り値
     /// 成功時はOk(())、失敗時はAppError
     pub fn manage_cache_size(&self, conn: &Connection, user_id: Option<&str>) -> AppResult<()> {
+        let limits = self.limits(conn)?;
+
         // 現在のキャッシュサイズを計算
         let current_size = self.calculate_cache_size_sync()?;
 
-        if current_size > self.max_cache_size {
-            // サイズ超過時は古いファイルから削除
-            self.cleanup_old_cache(conn, user_id)?;
+        if current_size > limits.max_size_bytes {
+            // サイズ超過時は期限切れのファイルから削除
+            if let Some(max_age) = limits.max_age {
+                self.remove_expired_cache(conn, user_id, max_age)?;
+            }
 
-            // まだサイズが超過している場合は、LRU方式で削除
+            // まだサイズが超過している場合は、ポリシーの削除方式で削除
             let remaining_size = self.calculate_cache_size_sync()?;
-            if remaining_size > self.max_cache_size {
-                self.cleanup_lru_cache(conn, user_id)?;
+            if remaining_size > limits.max_size_bytes {
+                self.evict_cache(
+                    conn,
+                    user_id,
+                    limits.eviction_strategy,
+                    limits.max_size_bytes,
+                )?;
             }
         }
 
@@ -252,33 +328,70 @@ impl CacheManager {
         Ok(total_size)
     }
 
-    /// LRU方式でキャッシュを削除（同期版）
+    /// キャッシュの統計情報を取得（同期版）
+    ///
+    /// # 引数
+    /// * `conn` - データベース接続
+    ///
+    /// # 戻り値
+    /// キャッシュ統計情報、または失敗時はAppError
+    pub fn stats(&self, conn: &Connection) -> AppResult<CacheStats> {
+        let total_size_bytes = self.calculate_cache_size_sync()?;
+        let total_files: i64 = conn
+            .query_row("SELECT COUNT(*) FROM receipt_cache", [], |row| row.get(0))
+            .map_err(|e| AppError::Database(format!("キャッシュ数取得エラー: {e}")))?;
+
+        Ok(CacheStats {
+            total_files: total_files as usize,
+            total_size_bytes,
+            max_size_bytes: self.limits(conn)?.max_size_bytes,
+            cache_hit_rate: 0.0, // 実装を簡略化
+        })
+    }
+
+    /// 削除方式に従ってキャッシュを削除（同期版）
+    ///
+    /// 削除方式の順に、キャッシュサイズが上限以下になるまで削除します。
     ///
     /// # 引数
     /// * `conn` - データベース接続
     /// * `user_id` - ユーザーID（Noneの場合は全ユーザー対象）
+    /// * `strategy` - 削除方式
+    /// * `max_size_bytes` - 最大キャッシュサイズ（バイト）
     ///
     /// # 戻り値
     /// 成功時はOk(())、失敗時はAppError
-    fn cleanup_lru_cache(&self, conn: &Connection, user_id: Option<&str>) -> AppResult<()> {
-        // 最も古くアクセスされたファイルを取得して削除
-        let lru_caches = self.get_lru_cache_entries(conn, 10, user_id)?; // 最大10個削除
+    fn evict_cache(
+        &self,
+        conn: &Connection,
+        user_id: Option<&str>,
+        strategy: EvictionStrategy,
+        max_size_bytes: u64,
+    ) -> AppResult<()> {
+        let mut current_size = self.calculate_cache_size_sync()?;
+        let candidates = self.get_eviction_candidates(conn, strategy, user_id)?;
+
+        for cache in &candidates {
+            if current_size <= max_size_bytes {
+                break;
+            }
 
-        for cache in &lru_caches {
             let cache_path = Path::new(&cache.local_path);
-            if cache_path.exists() {
-                if let Err(e) = std::fs::remove_file(cache_path) {
-                    eprintln!(
-                        "LRUキャッシュファイル削除エラー: {} ({})",
-                        cache.local_path, e
-                    );
+            if let Ok(metadata) = std::fs::metadata(cache_path) {
+                match std::fs::remove_file(cache_path) {
+                    Ok(()) => current_size = current_size.saturating_sub(metadata.len()),
+                    Err(e) => {
+                        eprintln!("キャッシュファイル削除エラー: {} ({})", cache.local_path, e)
+                    }
                 }
             }
 
             // データベースからも削除
-            let delete_user_id = user_id.unwrap_or(DEFAULT_USER_ID);
-            if let Err(e) = self.delete_receipt_cache(conn, &cache.receipt_url, delete_user_id) {
-                eprintln!("LRUキャッシュDB削除エラー: {} ({})", cache.receipt_url, e);
+            if let Err(e) = conn.execute(
+                "DELETE FROM receipt_cache WHERE id = ?1",
+                rusqlite::params![cache.id],
+            ) {
+                eprintln!("キャッシュDB削除エラー: {} ({})", cache.receipt_url, e);
             }
         }
 
@@ -420,7 +533,8 @@ impl CacheManager {
         let now = Utc::now().with_timezone(&Tokyo).to_rfc3339();
 
         conn.execute(
-            "UPDATE receipt_cache SET last_accessed = ?1 WHERE receipt_url = ?2 AND user_id = ?3",
+            "UPDATE receipt_cache SET last_accessed = ?1, access_count = access_count + 1
+             WHERE receipt_url = ?2 AND user_id = ?3",
             rusqlite::params![&now, receipt_url, user_id],
         )
         .map_err(|e| AppError::Database(format!("アクセス時刻更新失敗: {e}")))?;
@@ -444,30 +558,21 @@ impl CacheManager {
         Ok(())
     }
 
-    /// 古いキャッシュエントリを取得するヘルパー関数
-    fn get_old_cache_entries(
+    /// キャッシュエントリを取得するヘルパー関数
+    fn get_cache_entries(
         &self,
         conn: &Connection,
-        max_age_days: i64,
         user_id: Option<&str>,
     ) -> AppResult<Vec<ReceiptCache>> {
-        use chrono::Utc;
-        use chrono_tz::Asia::Tokyo;
-
-        // JSTで現在時刻を取得
-        let now = Utc::now().with_timezone(&Tokyo);
-        let cutoff_date = now - chrono::Duration::days(max_age_days);
-        let cutoff_str = cutoff_date.to_rfc3339();
-
         let (query, params): (String, Vec<Box<dyn rusqlite::ToSql>>) = if let Some(uid) = user_id {
             (
-                "SELECT id, receipt_url, local_path, cached_at, file_size, last_accessed FROM receipt_cache WHERE last_accessed < ?1 AND user_id = ?2".to_string(),
-                vec![Box::new(cutoff_str), Box::new(uid.to_string())]
+                "SELECT id, receipt_url, local_path, cached_at, file_size, last_accessed FROM receipt_cache WHERE user_id = ?1".to_string(),
+                vec![Box::new(uid.to_string())]
             )
         } else {
             (
-                "SELECT id, receipt_url, local_path, cached_at, file_size, last_accessed FROM receipt_cache WHERE last_accessed < ?1".to_string(),
-                vec![Box::new(cutoff_str)]
+                "SELECT id, receipt_url, local_path, cached_at, file_size, last_accessed FROM receipt_cache".to_string(),
+                vec![]
             )
         };
 
@@ -500,22 +605,27 @@ impl CacheManager {
         Ok(caches)
     }
 
-    /// LRUキャッシュエントリを取得するヘルパー関数
-    fn get_lru_cache_entries(
+    /// 削除方式の順にキャッシュエントリを取得するヘルパー関数
+    fn get_eviction_candidates(
         &self,
         conn: &Connection,
-        limit: i64,
+        strategy: EvictionStrategy,
         user_id: Option<&str>,
     ) -> AppResult<Vec<ReceiptCache>> {
+        let order_by = match strategy {
+            EvictionStrategy::Lru => "last_accessed ASC, id ASC",
+            EvictionStrategy::Lfu => "access_count ASC, last_accessed ASC, id ASC",
+            EvictionStrategy::Fifo => "cached_at ASC, id ASC",
+        };
         let (query, params): (String, Vec<Box<dyn rusqlite::ToSql>>) = if let Some(uid) = user_id {
             (
-                "SELECT id, receipt_url, local_path, cached_at, file_size, last_accessed FROM receipt_cache WHERE user_id = ?1 ORDER BY last_accessed ASC LIMIT ?2".to_string(),
-                vec![Box::new(uid.to_string()), Box::new(limit)]
+                format!("SELECT id, receipt_url, local_path, cached_at, file_size, last_accessed FROM receipt_cache WHERE user_id = ?1 ORDER BY {order_by}"),
+                vec![Box::new(uid.to_string())]
             )
         } else {
             (
-                "SELECT id, receipt_url, local_path, cached_at, file_size, last_accessed FROM receipt_cache ORDER BY last_accessed ASC LIMIT ?1".to_string(),
-                vec![Box::new(limit)]
+                format!("SELECT id, receipt_url, local_path, cached_at, file_size, last_accessed FROM receipt_cache ORDER BY {order_by}"),
+                vec![]
             )
         };
 
@@ -548,36 +658,6 @@ impl CacheManager {
         Ok(caches)
     }
 
-    /// データベースから古いキャッシュ情報を削除
-    fn cleanup_old_cache_db(
-        &self,
-        conn: &Connection,
-        max_age_days: i64,
-        user_id: Option<&str>,
-    ) -> AppResult<usize> {
-        use chrono::Utc;
-        use chrono_tz::Asia::Tokyo;
-
-        let now = Utc::now().with_timezone(&Tokyo);
-        let cutoff_date = now - chrono::Duration::days(max_age_days);
-        let cutoff_str = cutoff_date.to_rfc3339();
-
-        let deleted_count = if let Some(uid) = user_id {
-            conn.execute(
-                "DELETE FROM receipt_cache WHERE last_accessed < ?1 AND user_id = ?2",
-                rusqlite::params![cutoff_str, uid],
-            )
-        } else {
-            conn.execute(
-                "DELETE FROM receipt_cache WHERE last_accessed < ?1",
-                rusqlite::params![cutoff_str],
-            )
-        }
-        .map_err(|e| AppError::Database(format!("古いキャッシュ削除失敗: {e}")))?;
-
-        Ok(deleted_count)
-    }
-
     /// 既存データにデフォルトユーザーIDを設定する
     ///
     /// # 引数
@@ -624,8 +704,52 @@ impl CacheManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::features::receipts::cache_policy::{create_cache_config_table, save_cache_policy};
+    use crate::features::receipts::models::CachePolicy;
+    use chrono::Utc;
     use tempfile::TempDir;
 
+    const USER_ID: &str = "u1";
+
+    const MB: usize = 1024 * 1024;
+
+    /// receipt_cacheテーブルとcache_configテーブルを持つインメモリデータベースを作成する
+    fn test_connection() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE receipt_cache (
+                id INTEGER PRIMARY KEY,
+                receipt_url TEXT NOT NULL UNIQUE,
+                local_path TEXT NOT NULL,
+                cached_at TEXT NOT NULL,
+                file_size INTEGER NOT NULL,
+                last_accessed TEXT NOT NULL,
+                user_id TEXT
+            )",
+        )
+        .unwrap();
+        create_cache_config_table(&conn).unwrap();
+        conn
+    }
+
+    /// キャッシュの最終アクセス時刻を指定した時間だけ過去に設定する
+    fn set_last_accessed(conn: &Connection, receipt_url: &str, ago: chrono::Duration) {
+        conn.execute(
+            "UPDATE receipt_cache SET last_accessed = ?1 WHERE receipt_url = ?2",
+            rusqlite::params![(Utc::now() - ago).to_rfc3339(), receipt_url],
+        )
+        .unwrap();
+    }
+
+    fn cached_urls(conn: &Connection) -> Vec<String> {
+        conn.prepare("SELECT receipt_url FROM receipt_cache ORDER BY receipt_url")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
     #[test]
     fn test_cache_filename_generation() {
         let temp_dir = TempDir::new().unwrap();
@@ -649,6 +773,69 @@ mod tests {
         assert_eq!(filename1, filename1_again);
     }
 
+    #[test]
+    fn test_cleanup_old_cache_removes_only_expired_entries() {
+        use chrono_tz::Asia::Tokyo;
+
+        let temp_dir = TempDir::new().unwrap();
+        let cache_manager = CacheManager::new(temp_dir.path().to_path_buf(), 100);
+        let conn = test_connection();
+
+        // 期限切れ（UTC表記）・期限内（JST表記）・他ユーザーの期限切れ
+        let expired = (Utc::now() - chrono::Duration::days(31)).to_rfc3339();
+        let fresh = Utc::now().with_timezone(&Tokyo).to_rfc3339();
+        let expired_path = temp_dir.path().join("expired.png");
+        std::fs::write(&expired_path, b"data").unwrap();
+        for (url, path, accessed, user_id) in [
+            (
+                "https://example.com/1.png",
+                expired_path.to_str().unwrap(),
+                &expired,
+                "u1",
+            ),
+            (
+                "https://example.com/2.png",
+                "/nonexistent/2.png",
+                &fresh,
+                "u1",
+            ),
+            (
+                "https://example.com/3.png",
+                "/nonexistent/3.png",
+                &expired,
+                "u2",
+            ),
+        ] {
+            conn.execute(
+                "INSERT INTO receipt_cache (receipt_url, local_path, cached_at, file_size, last_accessed, user_id)
+                 VALUES (?1, ?2, ?3, 4, ?3, ?4)",
+                rusqlite::params![url, path, accessed, user_id],
+            )
+            .unwrap();
+        }
+
+        assert_eq!(
+            cache_manager.cleanup_old_cache(&conn, Some("u1")).unwrap(),
+            1
+        );
+        assert!(!expired_path.exists());
+
+        let remaining: Vec<String> = conn
+            .prepare("SELECT receipt_url FROM receipt_cache ORDER BY id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            remaining,
+            vec!["https://example.com/2.png", "https://example.com/3.png"]
+        );
+
+        // 全ユーザー対象
+        assert_eq!(cache_manager.cleanup_old_cache(&conn, None).unwrap(), 1);
+    }
+
     #[test]
     fn test_cache_size_calculation() {
         let temp_dir = TempDir::new().unwrap();
@@ -658,4 +845,364 @@ mod tests {
         let initial_size = cache_manager.calculate_cache_size_sync().unwrap();
         assert_eq!(initial_size, 0);
     }
+
+    #[test]
+    fn test_cache_file_stores_data() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = temp_dir.path().join("receipt_cache");
+        let cache_manager = CacheManager::new(cache_dir.clone(), 100);
+        let conn = test_connection();
+        let url = "https://example.com/receipt.png";
+
+        let path = cache_manager
+            .cache_file(url, b"receipt".to_vec(), &conn, USER_ID)
+            .unwrap();
+
+        // キャッシュディレクトリにファイルが作成され、DBに記録される
+        assert!(path.starts_with(&cache_dir));
+        assert_eq!(std::fs::read(&path).unwrap(), b"receipt");
+        let (local_path, file_size): (String, i64) = conn
+            .query_row(
+                "SELECT local_path, file_size FROM receipt_cache WHERE receipt_url = ?1 AND user_id = ?2",
+                rusqlite::params![url, USER_ID],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(Path::new(&local_path), path);
+        assert_eq!(file_size, 7);
+        assert_eq!(cache_manager.calculate_cache_size_sync().unwrap(), 7);
+    }
+
+    #[test]
+    fn test_get_cached_file_returns_stored_data() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_manager = CacheManager::new(temp_dir.path().to_path_buf(), 100);
+        let conn = test_connection();
+        let url = "https://example.com/receipt.pdf";
+        cache_manager
+            .cache_file(url, b"%PDF-1.4".to_vec(), &conn, USER_ID)
+            .unwrap();
+        set_last_accessed(&conn, url, chrono::Duration::hours(1));
+
+        assert_eq!(
+            cache_manager.get_cached_file(url, &conn, USER_ID).unwrap(),
+            Some(b"%PDF-1.4".to_vec())
+        );
+        // 取得するとアクセス時刻が更新される
+        let last_accessed: String = conn
+            .query_row(
+                "SELECT last_accessed FROM receipt_cache WHERE receipt_url = ?1",
+                [url],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let last_accessed = chrono::DateTime::parse_from_rfc3339(&last_accessed).unwrap();
+        assert!(Utc::now() - last_accessed.with_timezone(&Utc) < chrono::Duration::minutes(1));
+
+        // 他のユーザーのキャッシュや未保存のURLは返さない
+        assert_eq!(
+            cache_manager.get_cached_file(url, &conn, "u2").unwrap(),
+            None
+        );
+        assert_eq!(
+            cache_manager
+                .get_cached_file("https://example.com/other.pdf", &conn, USER_ID)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_get_cached_file_drops_entry_for_missing_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_manager = CacheManager::new(temp_dir.path().to_path_buf(), 100);
+        let conn = test_connection();
+        let url = "https://example.com/receipt.png";
+        let path = cache_manager
+            .cache_file(url, b"receipt".to_vec(), &conn, USER_ID)
+            .unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(
+            cache_manager.get_cached_file(url, &conn, USER_ID).unwrap(),
+            None
+        );
+        assert!(cached_urls(&conn).is_empty());
+    }
+
+    #[test]
+    fn test_manage_cache_size_evicts_least_recently_accessed() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut cache_manager = CacheManager::new(temp_dir.path().to_path_buf(), 100);
+        cache_manager.max_cache_size = 25;
+        let conn = test_connection();
+
+        let urls = [
+            "https://example.com/a.png",
+            "https://example.com/b.png",
+            "https://example.com/c.png",
+        ];
+        for url in urls {
+            cache_manager
+                .cache_file(url, vec![0; 10], &conn, USER_ID)
+                .unwrap();
+        }
+        // aが最も新しく、bが最も古くアクセスされた状態にする
+        set_last_accessed(&conn, urls[0], chrono::Duration::minutes(1));
+        set_last_accessed(&conn, urls[1], chrono::Duration::minutes(30));
+        set_last_accessed(&conn, urls[2], chrono::Duration::minutes(10));
+
+        // 上限（25バイト）を超えているため、最も古くアクセスされたbだけを削除する
+        cache_manager
+            .manage_cache_size(&conn, Some(USER_ID))
+            .unwrap();
+
+        assert_eq!(
+            cached_urls(&conn),
+            vec!["https://example.com/a.png", "https://example.com/c.png"]
+        );
+        assert_eq!(cache_manager.calculate_cache_size_sync().unwrap(), 20);
+        assert_eq!(
+            cache_manager
+                .get_cached_file(urls[1], &conn, USER_ID)
+                .unwrap(),
+            None
+        );
+        assert!(cache_manager
+            .get_cached_file(urls[0], &conn, USER_ID)
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn test_manage_cache_size_keeps_cache_within_limit() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut cache_manager = CacheManager::new(temp_dir.path().to_path_buf(), 100);
+        cache_manager.max_cache_size = 30;
+        let conn = test_connection();
+        for url in ["https://example.com/a.png", "https://example.com/b.png"] {
+            cache_manager
+                .cache_file(url, vec![0; 10], &conn, USER_ID)
+                .unwrap();
+        }
+
+        // 上限以下の場合は何も削除しない
+        cache_manager
+            .manage_cache_size(&conn, Some(USER_ID))
+            .unwrap();
+
+        assert_eq!(cached_urls(&conn).len(), 2);
+        assert_eq!(cache_manager.calculate_cache_size_sync().unwrap(), 20);
+    }
+
+    #[test]
+    fn test_cleanup_old_cache_uses_max_age_threshold() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut cache_manager = CacheManager::new(temp_dir.path().to_path_buf(), 100);
+        cache_manager.max_age = Duration::from_secs(3600);
+        let conn = test_connection();
+
+        let old_url = "https://example.com/old.png";
+        let recent_url = "https://example.com/recent.png";
+        let old_path = cache_manager
+            .cache_file(old_url, b"old".to_vec(), &conn, USER_ID)
+            .unwrap();
+        let recent_path = cache_manager
+            .cache_file(recent_url, b"recent".to_vec(), &conn, USER_ID)
+            .unwrap();
+        set_last_accessed(&conn, old_url, chrono::Duration::hours(2));
+        set_last_accessed(&conn, recent_url, chrono::Duration::minutes(30));
+
+        assert_eq!(
+            cache_manager
+                .cleanup_old_cache(&conn, Some(USER_ID))
+                .unwrap(),
+            1
+        );
+
+        assert!(!old_path.exists());
+        assert!(recent_path.exists());
+        assert_eq!(cached_urls(&conn), vec![recent_url]);
+    }
+
+    /// 1MBのキャッシュを3件保存し、最大2MBの保持ポリシーを保存する
+    ///
+    /// 各キャッシュは削除方式ごとに最初に削除される対象が異なるように設定します。
+    /// - a: 最も古くキャッシュした（FIFOで削除）
+    /// - b: 最後のアクセスが最も古い（LRUで削除）
+    /// - c: アクセス回数が最も少ない（LFUで削除）
+    fn seeded_cache(
+        strategy: EvictionStrategy,
+    ) -> (TempDir, CacheManager, Connection, [&'static str; 3]) {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_manager = CacheManager::new(temp_dir.path().to_path_buf(), 100);
+        let conn = test_connection();
+        let urls = [
+            "https://example.com/a.png",
+            "https://example.com/b.png",
+            "https://example.com/c.png",
+        ];
+
+        for (url, cached_minutes_ago, accessed_minutes_ago, access_count) in [
+            (urls[0], 60, 1, 5),
+            (urls[1], 30, 50, 3),
+            (urls[2], 10, 20, 1),
+        ] {
+            cache_manager
+                .cache_file(url, vec![0; MB], &conn, USER_ID)
+                .unwrap();
+            let minutes_ago =
+                |minutes| (Utc::now() - chrono::Duration::minutes(minutes)).to_rfc3339();
+            conn.execute(
+                "UPDATE receipt_cache SET cached_at = ?1, last_accessed = ?2, access_count = ?3
+                 WHERE receipt_url = ?4",
+                rusqlite::params![
+                    minutes_ago(cached_minutes_ago),
+                    minutes_ago(accessed_minutes_ago),
+                    access_count,
+                    url
+                ],
+            )
+            .unwrap();
+        }
+
+        save_cache_policy(
+            &conn,
+            &CachePolicy {
+                max_size_mb: 2,
+                max_age_days: Some(7),
+                eviction_strategy: strategy,
+            },
+        )
+        .unwrap();
+
+        (temp_dir, cache_manager, conn, urls)
+    }
+
+    /// 削除方式に従って1件だけ削除されることを確認する
+    fn assert_evicts(strategy: EvictionStrategy, evicted: usize) {
+        let (_temp_dir, cache_manager, conn, urls) = seeded_cache(strategy);
+
+        cache_manager
+            .manage_cache_size(&conn, Some(USER_ID))
+            .unwrap();
+
+        let expected: Vec<&str> = urls
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != evicted)
+            .map(|(_, url)| *url)
+            .collect();
+        assert_eq!(cached_urls(&conn), expected, "{strategy:?}");
+        assert_eq!(
+            cache_manager.calculate_cache_size_sync().unwrap(),
+            2 * MB as u64
+        );
+    }
+
+    #[test]
+    fn test_manage_cache_size_with_lru_policy() {
+        assert_evicts(EvictionStrategy::Lru, 1);
+    }
+
+    #[test]
+    fn test_manage_cache_size_with_lfu_policy() {
+        assert_evicts(EvictionStrategy::Lfu, 2);
+    }
+
+    #[test]
+    fn test_manage_cache_size_with_fifo_policy() {
+        assert_evicts(EvictionStrategy::Fifo, 0);
+    }
+
+    #[test]
+    fn test_manage_cache_size_reads_policy_on_each_run() {
+        let (_temp_dir, cache_manager, conn, urls) = seeded_cache(EvictionStrategy::Fifo);
+        save_cache_policy(
+            &conn,
+            &CachePolicy {
+                max_size_mb: 3,
+                max_age_days: None,
+                eviction_strategy: EvictionStrategy::Fifo,
+            },
+        )
+        .unwrap();
+
+        // 上限（3MB）以下のため何も削除しない
+        cache_manager
+            .manage_cache_size(&conn, Some(USER_ID))
+            .unwrap();
+        assert_eq!(cached_urls(&conn).len(), 3);
+        assert_eq!(
+            cache_manager.stats(&conn).unwrap().max_size_bytes,
+            3 * MB as u64
+        );
+
+        // 上限を下げると次の実行から適用される
+        save_cache_policy(
+            &conn,
+            &CachePolicy {
+                max_size_mb: 1,
+                max_age_days: None,
+                eviction_strategy: EvictionStrategy::Fifo,
+            },
+        )
+        .unwrap();
+        cache_manager
+            .manage_cache_size(&conn, Some(USER_ID))
+            .unwrap();
+        assert_eq!(cached_urls(&conn), vec![urls[2]]);
+    }
+
+    #[test]
+    fn test_get_cached_file_counts_accesses() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_manager = CacheManager::new(temp_dir.path().to_path_buf(), 100);
+        let conn = test_connection();
+        let url = "https://example.com/receipt.png";
+        cache_manager
+            .cache_file(url, b"receipt".to_vec(), &conn, USER_ID)
+            .unwrap();
+
+        for _ in 0..2 {
+            cache_manager.get_cached_file(url, &conn, USER_ID).unwrap();
+        }
+
+        let access_count: i64 = conn
+            .query_row(
+                "SELECT access_count FROM receipt_cache WHERE receipt_url = ?1",
+                [url],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(access_count, 2);
+    }
+
+    #[test]
+    fn test_cleanup_old_cache_keeps_entries_without_max_age() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_manager = CacheManager::new(temp_dir.path().to_path_buf(), 100);
+        let conn = test_connection();
+        let url = "https://example.com/old.png";
+        cache_manager
+            .cache_file(url, b"old".to_vec(), &conn, USER_ID)
+            .unwrap();
+        set_last_accessed(&conn, url, chrono::Duration::days(365));
+        save_cache_policy(
+            &conn,
+            &CachePolicy {
+                max_age_days: None,
+                ..CachePolicy::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            cache_manager
+                .cleanup_old_cache(&conn, Some(USER_ID))
+                .unwrap(),
+            0
+        );
+        assert_eq!(cached_urls(&conn), vec![url]);
+    }
 }
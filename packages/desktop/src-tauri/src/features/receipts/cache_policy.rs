@@ -0,0 +1,208 @@
+/// 領収書キャッシュの保持ポリシー
+///
+/// 最大サイズ・保持日数・上限を超えた場合の削除方式を`cache_config`テーブルに保存し、
+/// 再コンパイルせずに画面から変更できるようにします。
+/// キャッシュディレクトリは全ユーザーで共有しているため、ポリシーもアプリ全体で1件です。
+/// LFU方式で使用するアクセス回数は、`receipt_cache`テーブルの`access_count`カラムに記録します。
+use super::models::{CachePolicy, EvictionStrategy};
+use crate::features::migrations::auto_migration::executor::MigrationExecutorTrait;
+use crate::features::migrations::auto_migration::models::{
+    ExecutableMigrationDefinition, MigrationDefinition,
+};
+use crate::features::migrations::auto_migration::registry::MigrationRegistry;
+use crate::shared::database::connection::check_column_exists;
+use crate::shared::errors::AppResult;
+use crate::shared::utils::get_current_jst_timestamp;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// キャッシュ設定マイグレーションのSQL（カラム追加は存在確認のうえ実行）
+const CACHE_CONFIG_SCHEMA_SQL: &str = "
+    ALTER TABLE receipt_cache ADD COLUMN access_count INTEGER NOT NULL DEFAULT 0;
+    CREATE TABLE IF NOT EXISTS cache_config (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        max_size_mb INTEGER NOT NULL,
+        max_age_days INTEGER,
+        eviction_strategy TEXT NOT NULL CHECK (eviction_strategy IN ('LRU', 'LFU', 'FIFO')),
+        updated_at TEXT NOT NULL
+    );
+";
+
+/// キャッシュ設定テーブル作成マイグレーション実行器
+pub struct CacheConfigSchemaMigration;
+
+impl MigrationExecutorTrait for CacheConfigSchemaMigration {
+    fn name(&self) -> &str {
+        "014_create_cache_config"
+    }
+
+    fn execute(&self, conn: &Connection) -> Result<(), String> {
+        create_cache_config_table(conn).map_err(|e| format!("cache_configテーブル作成エラー: {e}"))
+    }
+}
+
+/// キャッシュ設定テーブル用マイグレーション定義を取得する
+///
+/// # 戻り値
+/// 実行可能なマイグレーション定義
+pub fn get_cache_config_schema_definition() -> ExecutableMigrationDefinition {
+    let definition = MigrationDefinition::new(
+        "014_create_cache_config".to_string(),
+        "3.10.0".to_string(),
+        "キャッシュ設定テーブルの作成と領収書キャッシュのアクセス回数カラムの追加".to_string(),
+        MigrationRegistry::calculate_checksum(CACHE_CONFIG_SCHEMA_SQL),
+    );
+
+    ExecutableMigrationDefinition::new(definition, Box::new(CacheConfigSchemaMigration))
+}
+
+/// キャッシュ設定テーブルを作成し、領収書キャッシュにアクセス回数カラムを追加する
+///
+/// # 引数
+/// * `conn` - データベース接続
+pub fn create_cache_config_table(conn: &Connection) -> AppResult<()> {
+    if !check_column_exists(conn, "receipt_cache", "access_count") {
+        conn.execute(
+            "ALTER TABLE receipt_cache ADD COLUMN access_count INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+    }
+
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS cache_config (
+             id INTEGER PRIMARY KEY CHECK (id = 1),
+             max_size_mb INTEGER NOT NULL,
+             max_age_days INTEGER,
+             eviction_strategy TEXT NOT NULL CHECK (eviction_strategy IN ('LRU', 'LFU', 'FIFO')),
+             updated_at TEXT NOT NULL
+         );",
+    )?;
+    Ok(())
+}
+
+/// 保存されたキャッシュの保持ポリシーを取得する
+///
+/// # 引数
+/// * `conn` - データベース接続
+///
+/// # 戻り値
+/// 保存されたポリシー（未設定の場合はNone）
+pub fn load_cache_policy(conn: &Connection) -> AppResult<Option<CachePolicy>> {
+    let row = conn
+        .query_row(
+            "SELECT max_size_mb, max_age_days, eviction_strategy FROM cache_config WHERE id = 1",
+            [],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, Option<i64>>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            },
+        )
+        .optional()?;
+
+    row.map(|(max_size_mb, max_age_days, eviction_strategy)| {
+        Ok(CachePolicy {
+            max_size_mb: max_size_mb.max(0) as u64,
+            max_age_days: max_age_days.map(|days| days.max(0) as u32),
+            eviction_strategy: EvictionStrategy::parse(&eviction_strategy)?,
+        })
+    })
+    .transpose()
+}
+
+/// キャッシュの保持ポリシーを取得する
+///
+/// # 引数
+/// * `conn` - データベース接続
+///
+/// # 戻り値
+/// 保存されたポリシー（未設定の場合は既定のポリシー）
+pub fn get_cache_policy(conn: &Connection) -> AppResult<CachePolicy> {
+    Ok(load_cache_policy(conn)?.unwrap_or_default())
+}
+
+/// キャッシュの保持ポリシーを保存する
+///
+/// 次回のキャッシュサイズ管理から適用されます。
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `policy` - 保持ポリシー
+///
+/// # 戻り値
+/// 成功時はOk(())、ポリシーが無効な場合はバリデーションエラー
+pub fn save_cache_policy(conn: &Connection, policy: &CachePolicy) -> AppResult<()> {
+    policy.validate()?;
+
+    conn.execute(
+        "INSERT INTO cache_config (id, max_size_mb, max_age_days, eviction_strategy, updated_at)
+         VALUES (1, ?1, ?2, ?3, ?4)
+         ON CONFLICT(id) DO UPDATE SET
+             max_size_mb = excluded.max_size_mb,
+             max_age_days = excluded.max_age_days,
+             eviction_strategy = excluded.eviction_strategy,
+             updated_at = excluded.updated_at",
+        params![
+            policy.max_size_mb as i64,
+            policy.max_age_days,
+            policy.eviction_strategy.as_str(),
+            get_current_jst_timestamp()
+        ],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::database::connection::open_and_migrate_in_memory_database;
+
+    #[test]
+    fn test_cache_policy_defaults_until_saved() {
+        let conn = open_and_migrate_in_memory_database().unwrap();
+
+        assert_eq!(load_cache_policy(&conn).unwrap(), None);
+        assert_eq!(get_cache_policy(&conn).unwrap(), CachePolicy::default());
+    }
+
+    #[test]
+    fn test_save_cache_policy_overwrites_previous_policy() {
+        let conn = open_and_migrate_in_memory_database().unwrap();
+        let policy = CachePolicy {
+            max_size_mb: 500,
+            max_age_days: None,
+            eviction_strategy: EvictionStrategy::Lfu,
+        };
+
+        save_cache_policy(&conn, &CachePolicy::default()).unwrap();
+        save_cache_policy(&conn, &policy).unwrap();
+
+        assert_eq!(get_cache_policy(&conn).unwrap(), policy);
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM cache_config", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, 1);
+    }
+
+    #[test]
+    fn test_save_cache_policy_rejects_invalid_policy() {
+        let conn = open_and_migrate_in_memory_database().unwrap();
+        let policy = CachePolicy {
+            max_size_mb: 0,
+            ..CachePolicy::default()
+        };
+
+        assert!(save_cache_policy(&conn, &policy).is_err());
+        assert_eq!(load_cache_policy(&conn).unwrap(), None);
+    }
+
+    #[test]
+    fn test_create_cache_config_table_is_idempotent() {
+        let conn = open_and_migrate_in_memory_database().unwrap();
+
+        create_cache_config_table(&conn).unwrap();
+
+        assert!(check_column_exists(&conn, "receipt_cache", "access_count"));
+    }
+}
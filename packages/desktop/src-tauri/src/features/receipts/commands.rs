@@ -1,6 +1,19 @@
 // 領収書機能のTauriコマンドハンドラー
 
-use super::{cache::CacheManager, models::CacheStats};
+use super::{
+    annotations,
+    api_commands::{extract_file_key_from_url, ReceiptResponse},
+    cache::CacheManager,
+    cache_policy,
+    models::{CachePolicy, CacheStats, CreateReceiptAnnotationDto, ReceiptAnnotation},
+    watermark::{apply_watermark_with_annotations, load_watermark_font, WatermarkOptions},
+};
+use crate::features::security::{
+    audit_log,
+    models::{EventSeverity, SecurityEvent},
+};
+use crate::shared::errors::AppResult;
+use crate::shared::utils::disk_space::{check_disk_space, EXPORT_HEADROOM_BYTES};
 use crate::AppState;
 use tauri::{AppHandle, Manager, State};
 
@@ -44,13 +57,8 @@ pub async fn get_receipt_offline(
     let cache_manager = CacheManager::new(cache_dir, 100);
 
     // オフライン時のキャッシュから取得
-    let cached_result = {
-        let db = state
-            .db
-            .lock()
-            .map_err(|e| format!("データベースロックエラー: {e}"))?;
-        cache_manager.get_offline_cached_file(&receipt_url, &db, &user.id)
-    };
+    let cached_result =
+        state.try_db(|db| cache_manager.get_offline_cached_file(&receipt_url, db, &user.id));
 
     match cached_result {
         Ok(Some(cached_data)) => {
@@ -98,26 +106,17 @@ pub async fn sync_cache_on_online(
     let cache_manager = CacheManager::new(cache_dir, 100);
 
     // キャッシュ同期を実行（同期版を使用）
-    let sync_result: Result<usize, String> = {
-        let db = state
-            .db
-            .lock()
-            .map_err(|e| format!("データベースロックエラー: {e}"))?;
-
+    let sync_result = state.try_db(|db| {
         // 古いキャッシュをクリーンアップ
-        let cleaned_count = cache_manager
-            .cleanup_old_cache(&db, Some(&user.id))
-            .map_err(|e| format!("キャッシュクリーンアップエラー: {e}"))?;
+        let cleaned_count = cache_manager.cleanup_old_cache(db, Some(&user.id))?;
 
         // キャッシュサイズを管理
-        cache_manager
-            .manage_cache_size(&db, Some(&user.id))
-            .map_err(|e| format!("キャッシュサイズ管理エラー: {e}"))?;
+        cache_manager.manage_cache_size(db, Some(&user.id))?;
 
         println!("キャッシュ同期完了: {cleaned_count}個のファイルをクリーンアップしました");
 
         Ok(cleaned_count)
-    };
+    });
 
     match sync_result {
         Ok(synced_count) => Ok(synced_count),
@@ -156,29 +155,313 @@ pub async fn get_cache_stats(
     let cache_dir = app_data_dir.join("receipt_cache");
     let cache_manager = CacheManager::new(cache_dir, 100);
 
-    // キャッシュサイズを計算（同期版を使用）
-    let current_size = cache_manager
-        .calculate_cache_size_sync()
-        .map_err(|e| format!("キャッシュサイズ計算エラー: {e}"))?;
+    state
+        .try_db(|db| cache_manager.stats(db))
+        .map_err(|e| format!("キャッシュ統計取得エラー: {e}"))
+}
+
+/// 領収書キャッシュの保持ポリシーを取得する
+///
+/// # 引数
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 保持ポリシー（未設定の場合は既定値）、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn get_cache_policy(
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<CachePolicy, String> {
+    // 認証チェック
+    auth_middleware
+        .authenticate_request(session_token.as_deref(), "/receipts/cache-policy")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(cache_policy::get_cache_policy)
+        .map_err(|e| e.user_message().to_string())
+}
+
+/// 領収書キャッシュの保持ポリシーを変更する
+///
+/// 次回のキャッシュサイズ管理から適用されます。
+///
+/// # 引数
+/// * `policy` - 保持ポリシー
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 成功時はOk(())、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn set_cache_policy(
+    policy: CachePolicy,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<(), String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/receipts/cache-policy")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| cache_policy::save_cache_policy(db, &policy))
+        .map_err(|e| e.user_message().to_string())?;
+    log::info!(
+        "キャッシュの保持ポリシーを変更しました: user_id={}, policy={policy:?}",
+        user.id
+    );
+    Ok(())
+}
 
-    // データベースからキャッシュ数を取得
-    let cache_count = {
-        let db = state
-            .db
-            .lock()
-            .map_err(|e| format!("データベースロックエラー: {e}"))?;
+/// 透かし入りの領収書の控えを出力する
+///
+/// 領収書はキャッシュを優先して取得し、保存済みの原本は変更しません。
+/// 出力した記録はセキュリティイベントとして残します。
+///
+/// # 引数
+/// * `expense_id` - 経費ID
+/// * `output_path` - 出力先のファイルパス
+/// * `watermark_options` - 透かしの設定（未指定時は既定値）
+/// * `include_annotations` - 注釈のハイライトを描画するか（未指定時は描画しない）
+/// * `session_token` - セッショントークン
+/// * `app` - Tauriアプリハンドル
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 出力したファイルのバイト数、または失敗時はエラーメッセージ
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_receipt_copy(
+    expense_id: i64,
+    output_path: String,
+    watermark_options: Option<WatermarkOptions>,
+    include_annotations: Option<bool>,
+    session_token: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<usize, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/receipts/export-copy")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
 
-        let count: i64 = db
-            .query_row("SELECT COUNT(*) FROM receipt_cache", [], |row| row.get(0))
-            .map_err(|e| format!("キャッシュ数取得エラー: {e}"))?;
+    let options = watermark_options.unwrap_or_default();
+    options.validate().map_err(|e| e.to_string())?;
 
-        count as usize
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("アプリデータディレクトリの取得に失敗しました: {e}"))?;
+    let cache_dir = app_data_dir.join("receipt_cache");
+    let output = std::path::PathBuf::from(&output_path);
+    if output.starts_with(&cache_dir) {
+        return Err("キャッシュディレクトリには出力できません".to_string());
+    }
+    let cache_manager = CacheManager::new(cache_dir, 100);
+
+    // 領収書URL・キャッシュ・注釈を取得
+    let receipt_url: Option<String> = state
+        .try_db(|db| {
+            Ok(db.query_row(
+                "SELECT receipt_url FROM expenses WHERE id = ?1",
+                [expense_id],
+                |row| row.get(0),
+            )?)
+        })
+        .map_err(|e| format!("経費の取得に失敗しました: {e}"))?;
+    let receipt_url =
+        receipt_url.ok_or_else(|| "この経費には領収書が添付されていません".to_string())?;
+    let cached = state
+        .try_db(|db| cache_manager.get_cached_file(&receipt_url, db, &user.id))
+        .map_err(|e| format!("キャッシュ取得エラー: {e}"))?;
+    let receipt_annotations = if include_annotations.unwrap_or(false) {
+        state
+            .try_db(|db| annotations::find_annotations(db, expense_id, &receipt_url, &user.id))
+            .map_err(|e| format!("注釈の取得に失敗しました: {e}"))?
+    } else {
+        Vec::new()
+    };
+
+    let original = match cached {
+        Some(data) => data,
+        None => {
+            let data = fetch_receipt_data(&receipt_url, session_token.as_deref()).await?;
+            if let Err(e) = state
+                .try_db(|db| cache_manager.cache_file(&receipt_url, data.clone(), db, &user.id))
+            {
+                log::warn!("領収書のキャッシュ保存に失敗しました: {e}");
+            }
+            data
+        }
     };
 
-    Ok(CacheStats {
-        total_files: cache_count,
-        total_size_bytes: current_size,
-        max_size_bytes: cache_manager.max_cache_size,
-        cache_hit_rate: 0.0, // 実装を簡略化
-    })
+    let font = load_watermark_font(app.path().resource_dir().ok());
+    let watermarked =
+        apply_watermark_with_annotations(&original, &options, font.as_ref(), &receipt_annotations)
+            .map_err(|e| format!("透かしの追加に失敗しました: {e}"))?;
+    check_disk_space(&output, watermarked.len() as u64 + EXPORT_HEADROOM_BYTES)
+        .map_err(|e| e.to_string())?;
+    std::fs::write(&output, &watermarked)
+        .map_err(|e| format!("出力ファイルの書き込みに失敗しました: {e}"))?;
+
+    // 出力の記録
+    let event = SecurityEvent::new(
+        "receipt_copy_exported".to_string(),
+        format!(
+            "expense_id={expense_id}, output_path={output_path}, watermark={}, annotations={}",
+            options.text(),
+            receipt_annotations.len()
+        ),
+        EventSeverity::Info,
+        Some(user.id.clone()),
+    );
+    if let Err(e) = state.try_db(|db| audit_log::insert_security_event(db, &event)) {
+        log::warn!("領収書出力の記録に失敗しました: {e}");
+    }
+
+    log::info!("透かし入りの領収書を出力しました: expense_id={expense_id}");
+    Ok(watermarked.len())
+}
+
+/// ユーザーの経費に添付された領収書のURLを取得する（未添付の場合はNone）
+fn find_receipt_url(
+    db: &rusqlite::Connection,
+    expense_id: i64,
+    user_id: &str,
+) -> AppResult<Option<String>> {
+    let receipt_url: Option<String> = db.query_row(
+        "SELECT receipt_url FROM expenses WHERE id = ?1 AND user_id = ?2",
+        rusqlite::params![expense_id, user_id],
+        |row| row.get(0),
+    )?;
+    Ok(receipt_url.filter(|url| !url.is_empty()))
+}
+
+/// ユーザーの経費に添付された領収書のURLを取得し、未添付の場合はエラーメッセージを返す
+fn require_receipt_url(state: &AppState, expense_id: i64, user_id: &str) -> Result<String, String> {
+    state
+        .try_db(|db| find_receipt_url(db, expense_id, user_id))
+        .map_err(|e| format!("経費の取得に失敗しました: {e}"))?
+        .ok_or_else(|| "この経費には領収書が添付されていません".to_string())
+}
+
+/// 領収書に注釈（ハイライト）を追加する
+///
+/// # 引数
+/// * `dto` - 注釈の入力値（座標はページに対する0〜1の相対値）
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 追加した注釈、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn add_receipt_annotation(
+    dto: CreateReceiptAnnotationDto,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<ReceiptAnnotation, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/receipts/annotations")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    dto.validate().map_err(|e| e.to_string())?;
+
+    let receipt_url = require_receipt_url(&state, dto.expense_id, &user.id)?;
+
+    state
+        .try_db(|db| annotations::insert_annotation(db, &dto, &receipt_url, &user.id))
+        .map_err(|e| format!("注釈の追加に失敗しました: {e}"))
+}
+
+/// 領収書の注釈一覧を取得する
+///
+/// # 引数
+/// * `expense_id` - 経費ID
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 現在の領収書に付けられた注釈一覧、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn list_receipt_annotations(
+    expense_id: i64,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<Vec<ReceiptAnnotation>, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/receipts/annotations")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    let receipt_url = require_receipt_url(&state, expense_id, &user.id)?;
+
+    state
+        .try_db(|db| annotations::find_annotations(db, expense_id, &receipt_url, &user.id))
+        .map_err(|e| format!("注釈の取得に失敗しました: {e}"))
+}
+
+/// 領収書の注釈を削除する
+///
+/// # 引数
+/// * `annotation_id` - 注釈ID
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 削除した場合はtrue、該当する注釈がない場合はfalse
+#[tauri::command]
+pub async fn delete_receipt_annotation(
+    annotation_id: i64,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, crate::features::auth::middleware::AuthMiddleware>,
+) -> Result<bool, String> {
+    // 認証チェック
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/receipts/annotations")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| annotations::delete_annotation(db, annotation_id, &user.id))
+        .map_err(|e| format!("注釈の削除に失敗しました: {e}"))
+}
+
+/// APIサーバーから領収書の内容を取得する
+async fn fetch_receipt_data(
+    receipt_url: &str,
+    session_token: Option<&str>,
+) -> Result<Vec<u8>, String> {
+    use base64::{engine::general_purpose, Engine as _};
+
+    let file_key = extract_file_key_from_url(receipt_url)?;
+    let api_client = crate::shared::api_client::ApiClient::new()
+        .map_err(|e| format!("APIクライアント作成エラー: {e}"))?;
+    let response = api_client
+        .get::<ReceiptResponse>(&format!("/api/v1/receipts/{file_key}/data"), session_token)
+        .await
+        .map_err(|e| format!("領収書の取得に失敗しました: {e}"))?;
+
+    general_purpose::STANDARD
+        .decode(response.data)
+        .map_err(|e| format!("領収書データのデコードに失敗しました: {e}"))
 }
@@ -0,0 +1,228 @@
+/// ストレージ（APIサーバー経由のR2）の接続状態監視
+///
+/// ヘルスチェックの結果を保持し、ストレージに依存するコマンドの先頭で
+/// 共通のガード関数から参照します。直近のプローブで停止が確認されている場合は、
+/// 各コマンドが個別にタイムアウトするのを待たずに即座に失敗させます。
+use log::{info, warn};
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// ストレージ停止時のエラーコード
+pub const STORAGE_UNAVAILABLE: &str = "STORAGE_UNAVAILABLE";
+
+/// 停止を検知してから再試行を許可するまでの間隔
+pub const STORAGE_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// ストレージの接続状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageStatus {
+    /// 直近のプローブで正常に応答した
+    Healthy,
+    /// 直近のプローブで応答がなかった
+    Down,
+    /// まだプローブしていない
+    Unknown,
+}
+
+/// ストレージ停止時に返す型付きエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageUnavailableError {
+    /// 最後のプローブからの経過秒数
+    pub last_probe_age_secs: u64,
+    /// 次の再試行までの秒数
+    pub next_retry_in_secs: u64,
+}
+
+impl fmt::Display for StorageUnavailableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{STORAGE_UNAVAILABLE}: ストレージに接続できません（最終確認: {}秒前、次回再試行まで: {}秒）",
+            self.last_probe_age_secs, self.next_retry_in_secs
+        )
+    }
+}
+
+impl std::error::Error for StorageUnavailableError {}
+
+impl From<StorageUnavailableError> for String {
+    fn from(error: StorageUnavailableError) -> Self {
+        error.to_string()
+    }
+}
+
+/// 接続状態モニター
+#[derive(Debug)]
+pub struct ConnectivityMonitor {
+    status: StorageStatus,
+    last_probe_at: Option<Instant>,
+    retry_interval: Duration,
+}
+
+impl ConnectivityMonitor {
+    /// 新しいモニターを作成
+    ///
+    /// # 引数
+    /// * `retry_interval` - 停止検知後、再試行を許可するまでの間隔
+    pub fn new(retry_interval: Duration) -> Self {
+        Self {
+            status: StorageStatus::Unknown,
+            last_probe_at: None,
+            retry_interval,
+        }
+    }
+
+    /// 現在の接続状態を取得
+    pub fn status(&self) -> StorageStatus {
+        self.status
+    }
+
+    /// プローブ結果を記録
+    ///
+    /// # 引数
+    /// * `healthy` - プローブが成功したかどうか
+    /// * `now` - プローブ時刻
+    pub fn record_probe(&mut self, healthy: bool, now: Instant) {
+        let status = if healthy {
+            StorageStatus::Healthy
+        } else {
+            StorageStatus::Down
+        };
+
+        if status != self.status {
+            info!(
+                "ストレージ接続状態が変化しました: {:?} -> {status:?}",
+                self.status
+            );
+        }
+
+        self.status = status;
+        self.last_probe_at = Some(now);
+    }
+
+    /// ストレージ操作を実行してよいか判定する
+    ///
+    /// 停止が確認されてから再試行間隔が経過していない場合のみ失敗します。
+    /// 状態が不明・古い場合は操作を試行させます（フェイルオープン）。
+    ///
+    /// # 引数
+    /// * `force` - 停止中でも試行する場合はtrue
+    /// * `now` - 判定時刻
+    pub fn check(&self, force: bool, now: Instant) -> Result<(), StorageUnavailableError> {
+        if force || self.status != StorageStatus::Down {
+            return Ok(());
+        }
+
+        let Some(last_probe_at) = self.last_probe_at else {
+            return Ok(());
+        };
+
+        let age = now.saturating_duration_since(last_probe_at);
+        if age >= self.retry_interval {
+            return Ok(());
+        }
+
+        Err(StorageUnavailableError {
+            last_probe_age_secs: age.as_secs(),
+            next_retry_in_secs: (self.retry_interval - age).as_secs().max(1),
+        })
+    }
+}
+
+/// アプリ全体で共有するモニターインスタンス
+static STORAGE_MONITOR: OnceLock<Mutex<ConnectivityMonitor>> = OnceLock::new();
+
+fn global_monitor() -> &'static Mutex<ConnectivityMonitor> {
+    STORAGE_MONITOR.get_or_init(|| Mutex::new(ConnectivityMonitor::new(STORAGE_RETRY_INTERVAL)))
+}
+
+/// ストレージ依存コマンドの先頭で呼び出すガード
+///
+/// # 引数
+/// * `force` - 停止中でも試行する場合はtrue
+///
+/// # 戻り値
+/// 試行してよい場合はOk(())、停止中の場合は`STORAGE_UNAVAILABLE`エラー
+pub fn ensure_storage_available(force: bool) -> Result<(), StorageUnavailableError> {
+    // ロックが取得できない場合も操作を妨げない
+    let Ok(monitor) = global_monitor().lock() else {
+        return Ok(());
+    };
+
+    monitor.check(force, Instant::now()).inspect_err(|e| {
+        warn!("ストレージ停止中のため操作をスキップしました: {e}");
+    })
+}
+
+/// ストレージ操作を実行できる状態かどうか（ログを出力せずに判定する）
+pub fn is_storage_available() -> bool {
+    global_monitor()
+        .lock()
+        .map(|monitor| monitor.check(false, Instant::now()).is_ok())
+        .unwrap_or(true)
+}
+
+/// ストレージのプローブ結果を記録する
+///
+/// ヘルスチェックの結果や、ストレージ操作の成功時に呼び出します。
+pub fn record_storage_probe(healthy: bool) {
+    if let Ok(mut monitor) = global_monitor().lock() {
+        monitor.record_probe(healthy, Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_status_fails_open() {
+        let monitor = ConnectivityMonitor::new(Duration::from_secs(30));
+        assert_eq!(monitor.status(), StorageStatus::Unknown);
+        assert!(monitor.check(false, Instant::now()).is_ok());
+    }
+
+    #[test]
+    fn test_known_down_fails_fast() {
+        let mut monitor = ConnectivityMonitor::new(Duration::from_secs(30));
+        let probed_at = Instant::now();
+        monitor.record_probe(false, probed_at);
+
+        let err = monitor
+            .check(false, probed_at + Duration::from_secs(10))
+            .unwrap_err();
+        assert_eq!(err.last_probe_age_secs, 10);
+        assert_eq!(err.next_retry_in_secs, 20);
+        assert!(err.to_string().starts_with(STORAGE_UNAVAILABLE));
+    }
+
+    #[test]
+    fn test_forced_attempt_updates_status() {
+        let mut monitor = ConnectivityMonitor::new(Duration::from_secs(30));
+        let probed_at = Instant::now();
+        monitor.record_probe(false, probed_at);
+
+        let now = probed_at + Duration::from_secs(5);
+        assert!(monitor.check(true, now).is_ok());
+
+        // 強制試行が成功したら即座に正常状態になる
+        monitor.record_probe(true, now);
+        assert_eq!(monitor.status(), StorageStatus::Healthy);
+        assert!(monitor.check(false, now).is_ok());
+    }
+
+    #[test]
+    fn test_stale_down_status_fails_open() {
+        let mut monitor = ConnectivityMonitor::new(Duration::from_secs(30));
+        let probed_at = Instant::now();
+        monitor.record_probe(false, probed_at);
+
+        assert!(monitor
+            .check(false, probed_at + Duration::from_secs(30))
+            .is_ok());
+        assert!(monitor
+            .check(false, probed_at + Duration::from_secs(600))
+            .is_ok());
+    }
+}
@@ -0,0 +1,506 @@
+/// 領収書画像のEXIF処理
+///
+/// スマートフォンで撮影した領収書にはGPS座標を含むEXIFが付いていることがあります。
+/// アップロード前にメタデータを必ず削除し、利用者が許可している場合のみ
+/// 削除前に位置情報を取り出します。
+///
+/// 対応形式はJPEG（APP1セグメント）とPNG（eXIfチャンク）です。
+/// それ以外の形式（PDFなど）はそのまま扱います。
+use serde::{Deserialize, Serialize};
+
+/// JPEGの開始マーカー
+const JPEG_SOI: [u8; 2] = [0xFF, 0xD8];
+
+/// PNGのシグネチャ
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// JPEGのAPP1セグメント内のEXIF識別子
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+
+/// GPS情報IFDへのポインタのタグ
+const TAG_GPS_IFD: u16 = 0x8825;
+
+/// GPS緯度の南北（N/S）
+const TAG_GPS_LATITUDE_REF: u16 = 0x0001;
+
+/// GPS緯度（度・分・秒）
+const TAG_GPS_LATITUDE: u16 = 0x0002;
+
+/// GPS経度の東西（E/W）
+const TAG_GPS_LONGITUDE_REF: u16 = 0x0003;
+
+/// GPS経度（度・分・秒）
+const TAG_GPS_LONGITUDE: u16 = 0x0004;
+
+/// TIFFのRATIONAL型
+const TIFF_TYPE_RATIONAL: u16 = 5;
+
+/// GPS座標（10進数の度）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GpsCoordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl GpsCoordinates {
+    /// 座標が有効な範囲にあるか
+    pub fn is_valid(&self) -> bool {
+        self.latitude.is_finite()
+            && self.longitude.is_finite()
+            && (-90.0..=90.0).contains(&self.latitude)
+            && (-180.0..=180.0).contains(&self.longitude)
+    }
+}
+
+/// メタデータ削除後の領収書
+#[derive(Debug, Clone)]
+pub struct SanitizedReceipt {
+    /// メタデータを削除したファイルデータ
+    pub data: Vec<u8>,
+    /// 取り出した位置情報（取り出さない設定の場合や、含まれていない場合はNone）
+    pub location: Option<GpsCoordinates>,
+}
+
+/// アップロード前に領収書のメタデータを削除する
+///
+/// # 引数
+/// * `data` - ファイルデータ
+/// * `keep_location` - 削除前に位置情報を取り出すか（利用者が許可している場合のみtrue）
+///
+/// # 戻り値
+/// メタデータを削除したデータと、取り出した位置情報
+pub fn sanitize_receipt(data: &[u8], keep_location: bool) -> SanitizedReceipt {
+    SanitizedReceipt {
+        location: if keep_location {
+            extract_gps(data)
+        } else {
+            None
+        },
+        data: strip_metadata(data),
+    }
+}
+
+/// 画像からGPS座標を取り出す
+///
+/// # 引数
+/// * `data` - ファイルデータ
+///
+/// # 戻り値
+/// GPS座標（含まれていない・解析できない場合はNone）
+pub fn extract_gps(data: &[u8]) -> Option<GpsCoordinates> {
+    let tiff = if data.starts_with(&JPEG_SOI) {
+        jpeg_segments(data)?
+            .into_iter()
+            .filter(|segment| segment.marker == 0xE1)
+            .find_map(|segment| segment.payload.strip_prefix(EXIF_HEADER))?
+    } else if data.starts_with(&PNG_SIGNATURE) {
+        png_chunks(data)?
+            .into_iter()
+            .find(|chunk| &chunk.kind == b"eXIf")
+            .map(|chunk| chunk.data)
+            // 一部の実装はJPEGと同じ識別子を付ける
+            .map(|data| data.strip_prefix(EXIF_HEADER).unwrap_or(data))?
+    } else {
+        return None;
+    };
+
+    parse_tiff_gps(tiff).filter(GpsCoordinates::is_valid)
+}
+
+/// 画像からEXIFなどのメタデータを削除する
+///
+/// JPEGはAPP1セグメント（EXIF・XMP）、PNGはeXIfチャンクを削除します。
+/// 解析できないデータや対応していない形式はそのまま返します。
+///
+/// # 引数
+/// * `data` - ファイルデータ
+///
+/// # 戻り値
+/// メタデータを削除したデータ
+pub fn strip_metadata(data: &[u8]) -> Vec<u8> {
+    if data.starts_with(&JPEG_SOI) {
+        if let Some(segments) = jpeg_segments(data) {
+            let mut output = Vec::with_capacity(data.len());
+            output.extend_from_slice(&JPEG_SOI);
+            let mut copied_until = JPEG_SOI.len();
+            for segment in segments {
+                if segment.marker == 0xE1 {
+                    output.extend_from_slice(&data[copied_until..segment.start]);
+                    copied_until = segment.end;
+                }
+            }
+            output.extend_from_slice(&data[copied_until..]);
+            return output;
+        }
+    } else if data.starts_with(&PNG_SIGNATURE) {
+        if let Some(chunks) = png_chunks(data) {
+            let mut output = Vec::with_capacity(data.len());
+            output.extend_from_slice(&PNG_SIGNATURE);
+            for chunk in chunks.iter().filter(|chunk| &chunk.kind != b"eXIf") {
+                output.extend_from_slice(&data[chunk.start..chunk.end]);
+            }
+            return output;
+        }
+    }
+
+    data.to_vec()
+}
+
+/// JPEGのマーカーセグメント
+struct JpegSegment<'a> {
+    marker: u8,
+    payload: &'a [u8],
+    /// マーカーを含むセグメントの開始位置
+    start: usize,
+    /// セグメントの終了位置（この位置を含まない）
+    end: usize,
+}
+
+/// JPEGの画像データ（SOS）より前のセグメントを列挙する
+fn jpeg_segments(data: &[u8]) -> Option<Vec<JpegSegment<'_>>> {
+    let mut segments = Vec::new();
+    let mut pos = JPEG_SOI.len();
+
+    loop {
+        if *data.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *data.get(pos + 1)?;
+        match marker {
+            // 詰め物のFF
+            0xFF => {
+                pos += 1;
+                continue;
+            }
+            // 長さを持たないマーカー
+            0x01 | 0xD0..=0xD7 => {
+                pos += 2;
+                continue;
+            }
+            // 画像データの開始・終了以降はメタデータを含まない
+            0xDA | 0xD9 => return Some(segments),
+            _ => {}
+        }
+
+        let length = usize::from(u16::from_be_bytes([
+            *data.get(pos + 2)?,
+            *data.get(pos + 3)?,
+        ]));
+        if length < 2 {
+            return None;
+        }
+        let end = pos + 2 + length;
+        segments.push(JpegSegment {
+            marker,
+            payload: data.get(pos + 4..end)?,
+            start: pos,
+            end,
+        });
+        pos = end;
+    }
+}
+
+/// PNGのチャンク
+struct PngChunk<'a> {
+    kind: [u8; 4],
+    data: &'a [u8],
+    /// 長さフィールドを含むチャンクの開始位置
+    start: usize,
+    /// CRCを含むチャンクの終了位置（この位置を含まない）
+    end: usize,
+}
+
+/// PNGのチャンクを列挙する
+fn png_chunks(data: &[u8]) -> Option<Vec<PngChunk<'_>>> {
+    let mut chunks = Vec::new();
+    let mut pos = PNG_SIGNATURE.len();
+
+    while pos < data.len() {
+        let length = u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?) as usize;
+        let kind: [u8; 4] = data.get(pos + 4..pos + 8)?.try_into().ok()?;
+        let end = pos.checked_add(12)?.checked_add(length)?;
+        if end > data.len() {
+            return None;
+        }
+        chunks.push(PngChunk {
+            kind,
+            data: &data[pos + 8..pos + 8 + length],
+            start: pos,
+            end,
+        });
+        pos = end;
+        if &kind == b"IEND" {
+            break;
+        }
+    }
+
+    Some(chunks)
+}
+
+/// TIFF形式のEXIFを読み取る
+struct TiffReader<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> TiffReader<'a> {
+    fn new(data: &'a [u8]) -> Option<Self> {
+        let little_endian = match data.get(0..2)? {
+            b"II" => true,
+            b"MM" => false,
+            _ => return None,
+        };
+        let reader = Self {
+            data,
+            little_endian,
+        };
+        (reader.u16(2)? == 42).then_some(reader)
+    }
+
+    fn u16(&self, offset: usize) -> Option<u16> {
+        let bytes: [u8; 2] = self.data.get(offset..offset + 2)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn u32(&self, offset: usize) -> Option<u32> {
+        let bytes: [u8; 4] = self.data.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    /// IFDのエントリを検索し、エントリの位置を返す
+    fn find_entry(&self, ifd_offset: usize, tag: u16) -> Option<usize> {
+        let count = usize::from(self.u16(ifd_offset)?);
+        (0..count)
+            .map(|i| ifd_offset + 2 + i * 12)
+            .find(|&entry| self.u16(entry) == Some(tag))
+    }
+
+    /// ASCII型の値の先頭1文字を取得する（N/S/E/Wの判定用）
+    fn ascii_initial(&self, entry: usize) -> Option<u8> {
+        // 4バイト以内の値はエントリ内に格納される
+        self.data.get(entry + 8).copied()
+    }
+
+    /// RATIONAL型3要素（度・分・秒）を10進数の度に変換する
+    fn degrees(&self, entry: usize) -> Option<f64> {
+        if self.u16(entry + 2)? != TIFF_TYPE_RATIONAL || self.u32(entry + 4)? < 3 {
+            return None;
+        }
+        let offset = self.u32(entry + 8)? as usize;
+        let rational = |i: usize| -> Option<f64> {
+            let numerator = self.u32(offset + i * 8)?;
+            let denominator = self.u32(offset + i * 8 + 4)?;
+            (denominator != 0).then(|| f64::from(numerator) / f64::from(denominator))
+        };
+        Some(rational(0)? + rational(1)? / 60.0 + rational(2)? / 3600.0)
+    }
+}
+
+/// TIFF形式のEXIFからGPS座標を取り出す
+fn parse_tiff_gps(tiff: &[u8]) -> Option<GpsCoordinates> {
+    let reader = TiffReader::new(tiff)?;
+    let ifd0 = reader.u32(4)? as usize;
+    let gps_pointer = reader.find_entry(ifd0, TAG_GPS_IFD)?;
+    let gps_ifd = reader.u32(gps_pointer + 8)? as usize;
+
+    let signed = |value_tag: u16, ref_tag: u16, negative: u8| -> Option<f64> {
+        let value = reader.degrees(reader.find_entry(gps_ifd, value_tag)?)?;
+        let reference = reader
+            .find_entry(gps_ifd, ref_tag)
+            .and_then(|entry| reader.ascii_initial(entry));
+        Some(if reference == Some(negative) {
+            -value
+        } else {
+            value
+        })
+    };
+
+    Some(GpsCoordinates {
+        latitude: signed(TAG_GPS_LATITUDE, TAG_GPS_LATITUDE_REF, b'S')?,
+        longitude: signed(TAG_GPS_LONGITUDE, TAG_GPS_LONGITUDE_REF, b'W')?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, Rgb, RgbImage};
+    use std::io::Cursor;
+
+    /// GPS情報だけを含むTIFF形式のEXIFを作成する
+    fn build_gps_tiff(
+        little_endian: bool,
+        latitude: (u32, u32, u32, char),
+        longitude: (u32, u32, u32, char),
+    ) -> Vec<u8> {
+        let u16_bytes = |v: u16| {
+            if little_endian {
+                v.to_le_bytes()
+            } else {
+                v.to_be_bytes()
+            }
+        };
+        let u32_bytes = |v: u32| {
+            if little_endian {
+                v.to_le_bytes()
+            } else {
+                v.to_be_bytes()
+            }
+        };
+        let entry = |tag: u16, kind: u16, count: u32, value: [u8; 4]| {
+            let mut bytes = Vec::new();
+            bytes.extend_from_slice(&u16_bytes(tag));
+            bytes.extend_from_slice(&u16_bytes(kind));
+            bytes.extend_from_slice(&u32_bytes(count));
+            bytes.extend_from_slice(&value);
+            bytes
+        };
+        let ascii = |c: char| [c as u8, 0, 0, 0];
+
+        // ヘッダー(8) + IFD0(2 + 12 + 4) + GPS IFD(2 + 12 * 4 + 4) + RATIONAL(24 * 2)
+        let ifd0 = 8u32;
+        let gps_ifd = ifd0 + 18;
+        let latitude_values = gps_ifd + 54;
+        let longitude_values = latitude_values + 24;
+
+        let mut tiff = Vec::new();
+        tiff.extend_from_slice(if little_endian { b"II" } else { b"MM" });
+        tiff.extend_from_slice(&u16_bytes(42));
+        tiff.extend_from_slice(&u32_bytes(ifd0));
+
+        tiff.extend_from_slice(&u16_bytes(1));
+        tiff.extend(entry(TAG_GPS_IFD, 4, 1, u32_bytes(gps_ifd)));
+        tiff.extend_from_slice(&u32_bytes(0));
+
+        tiff.extend_from_slice(&u16_bytes(4));
+        tiff.extend(entry(TAG_GPS_LATITUDE_REF, 2, 2, ascii(latitude.3)));
+        tiff.extend(entry(TAG_GPS_LATITUDE, 5, 3, u32_bytes(latitude_values)));
+        tiff.extend(entry(TAG_GPS_LONGITUDE_REF, 2, 2, ascii(longitude.3)));
+        tiff.extend(entry(TAG_GPS_LONGITUDE, 5, 3, u32_bytes(longitude_values)));
+        tiff.extend_from_slice(&u32_bytes(0));
+
+        for (d, m, s) in [
+            (latitude.0, latitude.1, latitude.2),
+            (longitude.0, longitude.1, longitude.2),
+        ] {
+            for (numerator, denominator) in [(d, 1), (m, 1), (s, 100)] {
+                tiff.extend_from_slice(&u32_bytes(numerator));
+                tiff.extend_from_slice(&u32_bytes(denominator));
+            }
+        }
+        tiff
+    }
+
+    fn encode(format: ImageFormat) -> Vec<u8> {
+        let image = RgbImage::from_pixel(4, 4, Rgb([200, 100, 50]));
+        let mut output = Cursor::new(Vec::new());
+        image.write_to(&mut output, format).unwrap();
+        output.into_inner()
+    }
+
+    /// SOIの直後にEXIFのAPP1セグメントを挿入したJPEGを作成する
+    fn jpeg_with_exif(tiff: &[u8]) -> Vec<u8> {
+        let jpeg = encode(ImageFormat::Jpeg);
+        let mut payload = EXIF_HEADER.to_vec();
+        payload.extend_from_slice(tiff);
+
+        let mut output = JPEG_SOI.to_vec();
+        output.extend_from_slice(&[0xFF, 0xE1]);
+        output.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+        output.extend_from_slice(&payload);
+        output.extend_from_slice(&jpeg[2..]);
+        output
+    }
+
+    /// IENDの直前にeXIfチャンクを挿入したPNGを作成する
+    fn png_with_exif(tiff: &[u8]) -> Vec<u8> {
+        let png = encode(ImageFormat::Png);
+        let iend = png.len() - 12;
+        let mut output = png[..iend].to_vec();
+        output.extend_from_slice(&(tiff.len() as u32).to_be_bytes());
+        output.extend_from_slice(b"eXIf");
+        output.extend_from_slice(tiff);
+        // 解析ではCRCを検証しない
+        output.extend_from_slice(&[0, 0, 0, 0]);
+        output.extend_from_slice(&png[iend..]);
+        output
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack
+            .windows(needle.len())
+            .any(|window| window == needle)
+    }
+
+    #[test]
+    fn test_extract_gps_from_jpeg() {
+        // 東京タワー付近: 35°39'31.00"N 139°44'43.50"E
+        let tiff = build_gps_tiff(true, (35, 39, 3100, 'N'), (139, 44, 4350, 'E'));
+        let location = extract_gps(&jpeg_with_exif(&tiff)).unwrap();
+        assert!((location.latitude - 35.658_611).abs() < 1e-5);
+        assert!((location.longitude - 139.745_417).abs() < 1e-5);
+
+        // ビッグエンディアン・南半球・西半球
+        let tiff = build_gps_tiff(false, (33, 52, 0, 'S'), (70, 40, 0, 'W'));
+        let location = extract_gps(&jpeg_with_exif(&tiff)).unwrap();
+        assert!((location.latitude + 33.866_667).abs() < 1e-5);
+        assert!((location.longitude + 70.666_667).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_strip_metadata_removes_exif() {
+        let tiff = build_gps_tiff(true, (35, 39, 3100, 'N'), (139, 44, 4350, 'E'));
+
+        let jpeg = jpeg_with_exif(&tiff);
+        let stripped = strip_metadata(&jpeg);
+        assert!(!contains(&stripped, EXIF_HEADER));
+        assert_eq!(extract_gps(&stripped), None);
+        assert!(image::load_from_memory(&stripped).is_ok());
+
+        let png = png_with_exif(&tiff);
+        assert!(extract_gps(&png).is_some());
+        let stripped = strip_metadata(&png);
+        assert!(!contains(&stripped, b"eXIf"));
+        assert_eq!(stripped, encode(ImageFormat::Png));
+        assert!(image::load_from_memory(&stripped).is_ok());
+    }
+
+    #[test]
+    fn test_sanitize_receipt_respects_opt_in() {
+        let tiff = build_gps_tiff(true, (35, 39, 3100, 'N'), (139, 44, 4350, 'E'));
+        let jpeg = jpeg_with_exif(&tiff);
+
+        // 許可していない場合も削除は行い、位置情報は取り出さない
+        let sanitized = sanitize_receipt(&jpeg, false);
+        assert_eq!(sanitized.location, None);
+        assert!(!contains(&sanitized.data, EXIF_HEADER));
+
+        let sanitized = sanitize_receipt(&jpeg, true);
+        assert!(sanitized.location.is_some());
+        assert!(!contains(&sanitized.data, EXIF_HEADER));
+    }
+
+    #[test]
+    fn test_unsupported_or_broken_data_is_unchanged() {
+        let pdf = b"%PDF-1.4\n...".to_vec();
+        assert_eq!(strip_metadata(&pdf), pdf);
+        assert_eq!(extract_gps(&pdf), None);
+
+        let broken = vec![0xFF, 0xD8, 0xFF, 0xE1, 0xFF];
+        assert_eq!(strip_metadata(&broken), broken);
+        assert_eq!(extract_gps(&broken), None);
+
+        // GPS情報を含まないJPEG
+        let plain = encode(ImageFormat::Jpeg);
+        assert_eq!(extract_gps(&plain), None);
+        assert!(image::load_from_memory(&strip_metadata(&plain)).is_ok());
+    }
+}
@@ -0,0 +1,513 @@
+// R2バケットのページング列挙
+//
+// バケット全体を一度に列挙するとClass B操作の課金とメモリ使用量が増えるため、
+// 継続トークンによるページ単位の取得と、中断から再開できる列挙ヘルパーを提供します。
+
+use crate::shared::api_client::ApiClient;
+use crate::shared::errors::{AppError, AppResult};
+use chrono::Utc;
+use chrono_tz::Asia::Tokyo;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+
+/// 1ページあたりの最大取得件数（S3互換APIの上限）
+pub const MAX_KEYS_LIMIT: u32 = 1000;
+
+/// 継続トークン失効時のエラーコード
+pub const CONTINUATION_TOKEN_EXPIRED: &str = "CONTINUATION_TOKEN_EXPIRED";
+
+/// R2上のファイル情報
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct R2FileInfo {
+    /// オブジェクトキー
+    pub key: String,
+    /// ファイルサイズ（バイト）
+    pub size: u64,
+    /// 最終更新日時（RFC3339形式）
+    pub last_modified: Option<String>,
+}
+
+/// 一覧取得結果の1ページ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListObjectsPage {
+    /// 取得したオブジェクト
+    pub objects: Vec<R2FileInfo>,
+    /// 次ページの継続トークン（最終ページの場合はNone）
+    pub next_continuation_token: Option<String>,
+}
+
+/// 一覧取得エラー
+#[derive(Debug, thiserror::Error)]
+pub enum ListObjectsError {
+    /// 継続トークンが失効している
+    #[error("{CONTINUATION_TOKEN_EXPIRED}: 継続トークンが失効しました")]
+    ContinuationTokenExpired,
+
+    /// その他のエラー
+    #[error(transparent)]
+    Request(#[from] AppError),
+}
+
+/// ページ単位でオブジェクトを列挙する
+pub trait ObjectLister {
+    /// 継続トークンを指定して1ページ分のオブジェクトを取得する
+    fn list_objects_paginated(
+        &self,
+        prefix: &str,
+        continuation_token: Option<&str>,
+        max_keys: u32,
+    ) -> impl Future<Output = Result<ListObjectsPage, ListObjectsError>> + Send;
+
+    /// 指定キーより後ろから1ページ分のオブジェクトを取得する
+    ///
+    /// 継続トークンが失効した場合の再開に使用します。
+    fn list_objects_after(
+        &self,
+        prefix: &str,
+        start_after: &str,
+        max_keys: u32,
+    ) -> impl Future<Output = Result<ListObjectsPage, ListObjectsError>> + Send;
+}
+
+/// APIサーバー経由でR2バケットを操作するクライアント
+pub struct R2Client {
+    api_client: ApiClient,
+    auth_token: String,
+}
+
+impl R2Client {
+    /// 新しいクライアントを作成
+    ///
+    /// # 引数
+    /// * `api_client` - APIクライアント
+    /// * `auth_token` - 認証トークン
+    pub fn new(api_client: ApiClient, auth_token: String) -> Self {
+        Self {
+            api_client,
+            auth_token,
+        }
+    }
+
+    async fn fetch_page(
+        &self,
+        prefix: &str,
+        cursor_param: Option<(&str, &str)>,
+        max_keys: u32,
+    ) -> Result<ListObjectsPage, ListObjectsError> {
+        let max_keys = max_keys.clamp(1, MAX_KEYS_LIMIT);
+        let mut endpoint = format!(
+            "/api/v1/receipts/objects?prefix={}&max_keys={max_keys}",
+            urlencoding::encode(prefix)
+        );
+        if let Some((name, value)) = cursor_param {
+            endpoint.push_str(&format!("&{name}={}", urlencoding::encode(value)));
+        }
+
+        match self
+            .api_client
+            .get::<ListObjectsPage>(&endpoint, Some(&self.auth_token))
+            .await
+        {
+            Ok(page) => Ok(page),
+            Err(AppError::ExternalService(message))
+                if message.contains(CONTINUATION_TOKEN_EXPIRED) =>
+            {
+                Err(ListObjectsError::ContinuationTokenExpired)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl ObjectLister for R2Client {
+    async fn list_objects_paginated(
+        &self,
+        prefix: &str,
+        continuation_token: Option<&str>,
+        max_keys: u32,
+    ) -> Result<ListObjectsPage, ListObjectsError> {
+        self.fetch_page(
+            prefix,
+            continuation_token.map(|token| ("continuation_token", token)),
+            max_keys,
+        )
+        .await
+    }
+
+    async fn list_objects_after(
+        &self,
+        prefix: &str,
+        start_after: &str,
+        max_keys: u32,
+    ) -> Result<ListObjectsPage, ListObjectsError> {
+        self.fetch_page(prefix, Some(("start_after", start_after)), max_keys)
+            .await
+    }
+}
+
+/// 列挙の進捗
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnumerationProgress {
+    /// 処理済みページ数
+    pub pages_processed: u64,
+    /// 処理済みオブジェクト数
+    pub objects_processed: u64,
+    /// 発行したClass B操作（一覧取得）の回数
+    pub class_b_operations: u64,
+    /// 列挙が完了したかどうか
+    pub completed: bool,
+}
+
+/// 中断から再開できる列挙
+///
+/// ページを取得するたびに`save`でチェックポイントを保存しておくと、
+/// 中断後に`load_or_start`で続きから再開できます。
+#[derive(Debug, Clone)]
+pub struct ResumableEnumeration {
+    scan_id: String,
+    prefix: String,
+    max_keys: u32,
+    continuation_token: Option<String>,
+    last_key: Option<String>,
+    progress: EnumerationProgress,
+}
+
+impl ResumableEnumeration {
+    /// チェックポイント用テーブルを作成する
+    pub fn ensure_table(conn: &Connection) -> AppResult<()> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS r2_enumeration_checkpoints (
+                scan_id TEXT PRIMARY KEY,
+                prefix TEXT NOT NULL,
+                continuation_token TEXT,
+                last_key TEXT,
+                pages_processed INTEGER NOT NULL DEFAULT 0,
+                objects_processed INTEGER NOT NULL DEFAULT 0,
+                class_b_operations INTEGER NOT NULL DEFAULT 0,
+                completed INTEGER NOT NULL DEFAULT 0,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        Ok(())
+    }
+
+    /// 保存済みのチェックポイントから再開する（なければ新規に開始する）
+    ///
+    /// 完了済みのチェックポイントや接頭辞が異なるチェックポイントは破棄し、最初から列挙します。
+    ///
+    /// # 引数
+    /// * `conn` - データベース接続
+    /// * `scan_id` - 列挙の識別子（例: "orphan_scan"）
+    /// * `prefix` - 列挙対象の接頭辞
+    /// * `max_keys` - 1ページあたりの取得件数
+    pub fn load_or_start(
+        conn: &Connection,
+        scan_id: &str,
+        prefix: &str,
+        max_keys: u32,
+    ) -> AppResult<Self> {
+        Self::ensure_table(conn)?;
+
+        let fresh = Self {
+            scan_id: scan_id.to_string(),
+            prefix: prefix.to_string(),
+            max_keys: max_keys.clamp(1, MAX_KEYS_LIMIT),
+            continuation_token: None,
+            last_key: None,
+            progress: EnumerationProgress::default(),
+        };
+
+        let saved = conn
+            .query_row(
+                "SELECT prefix, continuation_token, last_key, pages_processed, objects_processed,
+                        class_b_operations, completed
+                 FROM r2_enumeration_checkpoints WHERE scan_id = ?1",
+                params![scan_id],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, Option<String>>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                        EnumerationProgress {
+                            pages_processed: row.get::<_, i64>(3)? as u64,
+                            objects_processed: row.get::<_, i64>(4)? as u64,
+                            class_b_operations: row.get::<_, i64>(5)? as u64,
+                            completed: row.get(6)?,
+                        },
+                    ))
+                },
+            )
+            .optional()?;
+
+        match saved {
+            Some((saved_prefix, continuation_token, last_key, progress))
+                if saved_prefix == prefix && !progress.completed =>
+            {
+                log::info!(
+                    "列挙を再開します: scan_id={scan_id}, pages_processed={}",
+                    progress.pages_processed
+                );
+                Ok(Self {
+                    continuation_token,
+                    last_key,
+                    progress,
+                    ..fresh
+                })
+            }
+            _ => Ok(fresh),
+        }
+    }
+
+    /// 現在の進捗を取得
+    pub fn progress(&self) -> &EnumerationProgress {
+        &self.progress
+    }
+
+    /// 次のページを取得する
+    ///
+    /// 継続トークンが失効していた場合は、最後に処理したキーの直後から取得し直します。
+    ///
+    /// # 戻り値
+    /// 取得したオブジェクト（列挙が完了している場合はNone）
+    pub async fn next_page<L: ObjectLister>(
+        &mut self,
+        lister: &L,
+    ) -> AppResult<Option<Vec<R2FileInfo>>> {
+        if self.progress.completed {
+            return Ok(None);
+        }
+
+        self.progress.class_b_operations += 1;
+        let result = lister
+            .list_objects_paginated(
+                &self.prefix,
+                self.continuation_token.as_deref(),
+                self.max_keys,
+            )
+            .await;
+
+        let page = match result {
+            Ok(page) => page,
+            Err(ListObjectsError::ContinuationTokenExpired) => {
+                log::warn!(
+                    "継続トークンが失効したため、最後に処理したキーから再開します: scan_id={}",
+                    self.scan_id
+                );
+                self.progress.class_b_operations += 1;
+                let retried = match &self.last_key {
+                    Some(last_key) => {
+                        lister
+                            .list_objects_after(&self.prefix, last_key, self.max_keys)
+                            .await
+                    }
+                    None => {
+                        lister
+                            .list_objects_paginated(&self.prefix, None, self.max_keys)
+                            .await
+                    }
+                };
+                retried.map_err(|e| match e {
+                    ListObjectsError::Request(e) => e,
+                    other => AppError::ExternalService(other.to_string()),
+                })?
+            }
+            Err(ListObjectsError::Request(e)) => return Err(e),
+        };
+
+        self.progress.pages_processed += 1;
+        self.progress.objects_processed += page.objects.len() as u64;
+        if let Some(last) = page.objects.last() {
+            self.last_key = Some(last.key.clone());
+        }
+        self.continuation_token = page.next_continuation_token;
+        if self.continuation_token.is_none() {
+            self.progress.completed = true;
+        }
+
+        Ok(Some(page.objects))
+    }
+
+    /// チェックポイントを保存する
+    pub fn save(&self, conn: &Connection) -> AppResult<()> {
+        Self::ensure_table(conn)?;
+        let now = Utc::now().with_timezone(&Tokyo).to_rfc3339();
+        conn.execute(
+            "INSERT OR REPLACE INTO r2_enumeration_checkpoints (
+                scan_id, prefix, continuation_token, last_key, pages_processed,
+                objects_processed, class_b_operations, completed, updated_at
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                self.scan_id,
+                self.prefix,
+                self.continuation_token,
+                self.last_key,
+                self.progress.pages_processed as i64,
+                self.progress.objects_processed as i64,
+                self.progress.class_b_operations as i64,
+                self.progress.completed,
+                now,
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+    /// ページング応答を再現するモック
+    struct MockLister {
+        keys: Vec<String>,
+        calls: AtomicU32,
+        /// この回数目の呼び出しで継続トークンを失効させる
+        expire_on_call: Option<u32>,
+        expired: AtomicBool,
+    }
+
+    impl MockLister {
+        fn new(count: usize) -> Self {
+            Self {
+                keys: (0..count).map(|i| format!("receipts/{i:04}.png")).collect(),
+                calls: AtomicU32::new(0),
+                expire_on_call: None,
+                expired: AtomicBool::new(false),
+            }
+        }
+
+        fn page_from(&self, start: usize, max_keys: u32) -> ListObjectsPage {
+            let end = (start + max_keys as usize).min(self.keys.len());
+            let objects = self.keys[start..end]
+                .iter()
+                .map(|key| R2FileInfo {
+                    key: key.clone(),
+                    size: 100,
+                    last_modified: None,
+                })
+                .collect();
+            // 件数がちょうど割り切れる場合は空の最終ページを返す（S3の挙動を再現）
+            let next_continuation_token = if start < self.keys.len() {
+                Some(format!("token-{end}"))
+            } else {
+                None
+            };
+            ListObjectsPage {
+                objects,
+                next_continuation_token,
+            }
+        }
+    }
+
+    impl ObjectLister for MockLister {
+        async fn list_objects_paginated(
+            &self,
+            _prefix: &str,
+            continuation_token: Option<&str>,
+            max_keys: u32,
+        ) -> Result<ListObjectsPage, ListObjectsError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if continuation_token.is_some()
+                && self.expire_on_call == Some(call)
+                && !self.expired.swap(true, Ordering::SeqCst)
+            {
+                return Err(ListObjectsError::ContinuationTokenExpired);
+            }
+            let start = continuation_token
+                .map(|t| t.trim_start_matches("token-").parse().unwrap())
+                .unwrap_or(0);
+            Ok(self.page_from(start, max_keys))
+        }
+
+        async fn list_objects_after(
+            &self,
+            _prefix: &str,
+            start_after: &str,
+            max_keys: u32,
+        ) -> Result<ListObjectsPage, ListObjectsError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let start = self.keys.iter().position(|k| k == start_after).unwrap() + 1;
+            Ok(self.page_from(start, max_keys))
+        }
+    }
+
+    async fn collect_keys(
+        enumeration: &mut ResumableEnumeration,
+        lister: &MockLister,
+        conn: &Connection,
+        max_pages: Option<usize>,
+    ) -> Vec<String> {
+        let mut keys = Vec::new();
+        let mut pages = 0;
+        while let Some(objects) = enumeration.next_page(lister).await.unwrap() {
+            keys.extend(objects.into_iter().map(|o| o.key));
+            enumeration.save(conn).unwrap();
+            pages += 1;
+            if max_pages == Some(pages) {
+                break;
+            }
+        }
+        keys
+    }
+
+    #[tokio::test]
+    async fn test_enumerates_all_pages_including_empty_final_page() {
+        let conn = Connection::open_in_memory().unwrap();
+        let lister = MockLister::new(30);
+        let mut enumeration =
+            ResumableEnumeration::load_or_start(&conn, "orphan_scan", "receipts/", 10).unwrap();
+
+        let keys = collect_keys(&mut enumeration, &lister, &conn, None).await;
+
+        assert_eq!(keys, lister.keys);
+        // 10件×3ページ + 空の最終ページ
+        assert_eq!(enumeration.progress().pages_processed, 4);
+        assert_eq!(enumeration.progress().class_b_operations, 4);
+        assert!(enumeration.progress().completed);
+        assert!(enumeration.next_page(&lister).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_scan_resumes_from_checkpoint() {
+        let conn = Connection::open_in_memory().unwrap();
+        let lister = MockLister::new(25);
+
+        let mut first =
+            ResumableEnumeration::load_or_start(&conn, "orphan_scan", "receipts/", 10).unwrap();
+        let mut keys = collect_keys(&mut first, &lister, &conn, Some(1)).await;
+        drop(first);
+
+        let mut resumed =
+            ResumableEnumeration::load_or_start(&conn, "orphan_scan", "receipts/", 10).unwrap();
+        assert_eq!(resumed.progress().pages_processed, 1);
+        keys.extend(collect_keys(&mut resumed, &lister, &conn, None).await);
+
+        assert_eq!(keys, lister.keys);
+        // 中断前の1ページ + 再開後の2ページと空の最終ページ
+        assert_eq!(lister.calls.load(Ordering::SeqCst), 4);
+
+        // 完了後は最初からやり直す
+        let restarted =
+            ResumableEnumeration::load_or_start(&conn, "orphan_scan", "receipts/", 10).unwrap();
+        assert_eq!(restarted.progress(), &EnumerationProgress::default());
+    }
+
+    #[tokio::test]
+    async fn test_expired_token_resumes_after_last_key() {
+        let conn = Connection::open_in_memory().unwrap();
+        let mut lister = MockLister::new(25);
+        lister.expire_on_call = Some(2);
+
+        let mut enumeration =
+            ResumableEnumeration::load_or_start(&conn, "orphan_scan", "receipts/", 10).unwrap();
+        let keys = collect_keys(&mut enumeration, &lister, &conn, None).await;
+
+        // 重複も欠落もない
+        assert_eq!(keys, lister.keys);
+        assert_eq!(enumeration.progress().pages_processed, 4);
+        // 失効したリクエストも課金対象として数える
+        assert_eq!(enumeration.progress().class_b_operations, 5);
+    }
+}
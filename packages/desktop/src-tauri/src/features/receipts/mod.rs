@@ -1,20 +1,30 @@
 // 領収書機能モジュール
 
+pub mod annotations;
 pub mod api_client;
 pub mod api_commands;
 pub mod auth_commands;
 pub mod cache;
+pub mod cache_policy;
 pub mod commands;
+pub mod connectivity;
+pub mod exif;
+pub mod listing;
 pub mod models;
+pub mod recompress;
+pub mod url;
 pub mod user_path_manager;
+pub mod watermark;
 
 // 公開インターフェース
 
 // モデル
 pub use models::{
-    CacheStats, MultipleFileUpload, MultipleFileUploadInput, MultipleUploadResult,
-    PerformanceStats, R2ConnectionTestResult, R2DebugInfo, R2UsageInfo, ReceiptCache,
-    SingleUploadResult, TestStepResult, UploadProgress, UploadResult, UploadStatus,
+    CacheNearFullEvent, CachePolicy, CacheStats, CreateReceiptAnnotationDto, EvictionStrategy,
+    MultipleFileUpload, MultipleFileUploadInput, MultipleUploadResult, NormalizedRect,
+    PerformanceStats, PerformanceStatsAccumulator, R2ConnectionTestResult, R2DebugInfo,
+    R2UsageInfo, ReceiptAnnotation, ReceiptCache, SingleUploadResult, TestStepResult,
+    UploadProgress, UploadResult, UploadStatus,
 };
 
 // ユーザーパス管理
@@ -35,7 +45,9 @@ pub use api_commands::{
 };
 
 // コマンド（Tauriコマンドハンドラー）
-pub use commands::{get_cache_stats, get_receipt_offline, sync_cache_on_online};
+pub use commands::{
+    get_cache_policy, get_cache_stats, get_receipt_offline, set_cache_policy, sync_cache_on_online,
+};
 
 /// 領収書機能の初期化とセットアップ
 pub fn initialize() {
@@ -75,6 +87,120 @@ pub fn get_feature_stats() -> std::collections::HashMap<String, String> {
     stats
 }
 
+use crate::shared::capabilities::{Capability, Requirement};
+
+/// 領収書機能の利用者向け機能
+pub const CAPABILITIES: &[Capability] = &[
+    Capability::new(
+        "receipts.upload",
+        "capability.receipts.upload",
+        "upload_receipt_via_api",
+    )
+    .requires(&[
+        Requirement::Authenticated,
+        Requirement::ApiServerConfigured,
+        Requirement::StorageOnline,
+    ])
+    .mutating(),
+    Capability::new(
+        "receipts.upload_multiple",
+        "capability.receipts.upload_multiple",
+        "upload_multiple_receipts_via_api",
+    )
+    .requires(&[
+        Requirement::Authenticated,
+        Requirement::ApiServerConfigured,
+        Requirement::StorageOnline,
+    ])
+    .mutating(),
+    Capability::new(
+        "receipts.delete",
+        "capability.receipts.delete",
+        "delete_receipt_via_api",
+    )
+    .requires(&[
+        Requirement::Authenticated,
+        Requirement::ApiServerConfigured,
+        Requirement::StorageOnline,
+    ])
+    .destructive(),
+    Capability::new(
+        "receipts.sync_fallback_files",
+        "capability.receipts.sync_fallback_files",
+        "sync_fallback_files",
+    )
+    .requires(&[
+        Requirement::Authenticated,
+        Requirement::ApiServerConfigured,
+        Requirement::StorageOnline,
+    ])
+    .mutating()
+    .internal(),
+    Capability::new(
+        "receipts.sync_cache",
+        "capability.receipts.sync_cache",
+        "sync_cache_on_online",
+    )
+    .requires(&[Requirement::Authenticated, Requirement::StorageOnline])
+    .mutating()
+    .internal(),
+    Capability::new(
+        "receipts.recompress_existing",
+        "capability.receipts.recompress_existing",
+        "recompress_existing_receipts",
+    )
+    .requires(&[
+        Requirement::Authenticated,
+        Requirement::ApiServerConfigured,
+        Requirement::StorageOnline,
+    ])
+    .mutating(),
+    Capability::new(
+        "receipts.cache_stats",
+        "capability.receipts.cache_stats",
+        "get_cache_stats",
+    ),
+    Capability::new(
+        "receipts.cache_policy",
+        "capability.receipts.cache_policy",
+        "get_cache_policy",
+    )
+    .requires(&[Requirement::Authenticated]),
+    Capability::new(
+        "receipts.set_cache_policy",
+        "capability.receipts.set_cache_policy",
+        "set_cache_policy",
+    )
+    .requires(&[Requirement::Authenticated])
+    .mutating(),
+    Capability::new(
+        "receipts.export_copy",
+        "capability.receipts.export_copy",
+        "export_receipt_copy",
+    )
+    .requires(&[Requirement::Authenticated]),
+    Capability::new(
+        "receipts.add_annotation",
+        "capability.receipts.add_annotation",
+        "add_receipt_annotation",
+    )
+    .requires(&[Requirement::Authenticated])
+    .mutating(),
+    Capability::new(
+        "receipts.delete_annotation",
+        "capability.receipts.delete_annotation",
+        "delete_receipt_annotation",
+    )
+    .requires(&[Requirement::Authenticated])
+    .destructive(),
+    Capability::new(
+        "receipts.check_server_health",
+        "capability.receipts.check_server_health",
+        "check_api_server_health_detailed",
+    )
+    .requires(&[Requirement::ApiServerConfigured]),
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,6 +1,10 @@
 // 領収書機能のデータモデル
 
+use super::api_client::UploadResponse;
+use crate::shared::errors::{AppError, AppResult};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// 領収書キャッシュデータモデル
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -13,6 +17,32 @@ pub struct ReceiptCache {
     pub last_accessed: String, // 最終アクセス日時（RFC3339形式、JST）
 }
 
+impl ReceiptCache {
+    /// 最終アクセスからの経過時間を取得する
+    ///
+    /// 最終アクセス日時を解析できない場合は`Duration::MAX`を返し、
+    /// 常に期限切れとして扱われるようにします。
+    pub fn age(&self) -> Duration {
+        self.age_at(Utc::now())
+    }
+
+    /// 最終アクセスから`max_age`を超えて経過しているかを判定する
+    pub fn is_expired(&self, max_age: Duration) -> bool {
+        self.age() > max_age
+    }
+
+    /// 指定時刻における最終アクセスからの経過時間を取得する
+    fn age_at(&self, now: DateTime<Utc>) -> Duration {
+        match DateTime::parse_from_rfc3339(&self.last_accessed) {
+            // 未来の日時（時計のずれ）は経過時間0とする
+            Ok(last_accessed) => (now - last_accessed.with_timezone(&Utc))
+                .to_std()
+                .unwrap_or(Duration::ZERO),
+            Err(_) => Duration::MAX,
+        }
+    }
+}
+
 /// 複数ファイルアップロード用の入力構造体
 #[derive(Debug, Clone, Deserialize)]
 pub struct MultipleFileUploadInput {
@@ -50,6 +80,121 @@ pub struct CacheStats {
     pub cache_hit_rate: f64,
 }
 
+impl CacheStats {
+    /// キャッシュの使用率（%）を取得する
+    ///
+    /// 上限が0の場合は100%として扱います。
+    pub fn utilization_percent(&self) -> f32 {
+        if self.max_size_bytes == 0 {
+            return 100.0;
+        }
+        (self.total_size_bytes as f64 / self.max_size_bytes as f64 * 100.0) as f32
+    }
+
+    /// 使用率が閾値（0.0〜1.0）以上かを判定する
+    ///
+    /// # 引数
+    /// * `threshold` - 閾値（例: 0.9は90%）
+    pub fn is_full(&self, threshold: f32) -> bool {
+        self.utilization_percent() >= threshold * 100.0
+    }
+}
+
+/// キャッシュの空き容量警告イベントの内容
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheNearFullEvent {
+    pub utilization_percent: f32,
+    pub stats: CacheStats,
+}
+
+/// キャッシュの上限を超えた場合に削除するキャッシュの選び方
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum EvictionStrategy {
+    /// 最後のアクセスが古い順に削除する
+    #[default]
+    Lru,
+    /// アクセス回数が少ない順に削除する（同じ回数の場合は最後のアクセスが古い順）
+    Lfu,
+    /// キャッシュした日時が古い順に削除する
+    Fifo,
+}
+
+impl EvictionStrategy {
+    /// データベースに保存する値
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EvictionStrategy::Lru => "LRU",
+            EvictionStrategy::Lfu => "LFU",
+            EvictionStrategy::Fifo => "FIFO",
+        }
+    }
+
+    /// データベースに保存した値から変換する
+    pub fn parse(value: &str) -> AppResult<Self> {
+        match value {
+            "LRU" => Ok(EvictionStrategy::Lru),
+            "LFU" => Ok(EvictionStrategy::Lfu),
+            "FIFO" => Ok(EvictionStrategy::Fifo),
+            other => Err(AppError::Database(format!(
+                "不明なキャッシュの削除方式です: {other}"
+            ))),
+        }
+    }
+}
+
+/// キャッシュの最大サイズの上限（MB）
+pub const MAX_CACHE_SIZE_MB: u64 = 10 * 1024;
+
+/// 領収書キャッシュの保持ポリシー
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct CachePolicy {
+    pub max_size_mb: u64,                    // 最大キャッシュサイズ（MB）
+    pub max_age_days: Option<u32>,           // 最後のアクセスからの保持日数（Noneの場合は期限なし）
+    pub eviction_strategy: EvictionStrategy, // 最大サイズを超えた場合の削除方式
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        Self {
+            max_size_mb: 100,
+            max_age_days: Some(7),
+            eviction_strategy: EvictionStrategy::Lru,
+        }
+    }
+}
+
+impl CachePolicy {
+    /// 最大キャッシュサイズ（バイト）
+    pub fn max_size_bytes(&self) -> u64 {
+        self.max_size_mb * 1024 * 1024
+    }
+
+    /// 最後のアクセスからの保持期間（期限なしの場合はNone）
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age_days
+            .map(|days| Duration::from_secs(u64::from(days) * 24 * 3600))
+    }
+
+    /// ポリシーの値を検証する
+    ///
+    /// # 戻り値
+    /// 有効な場合はOk(())、無効な場合はバリデーションエラー
+    pub fn validate(&self) -> AppResult<()> {
+        if !(1..=MAX_CACHE_SIZE_MB).contains(&self.max_size_mb) {
+            return Err(AppError::validation(format!(
+                "最大キャッシュサイズは1〜{MAX_CACHE_SIZE_MB}MBの範囲で指定してください"
+            )));
+        }
+        if self.max_age_days == Some(0) {
+            return Err(AppError::validation(
+                "キャッシュの保持日数は1日以上で指定してください",
+            ));
+        }
+        Ok(())
+    }
+}
+
 /// R2接続テスト結果
 #[derive(Debug, Clone, Serialize)]
 pub struct R2ConnectionTestResult {
@@ -94,6 +239,70 @@ pub struct PerformanceStats {
     pub last_measured: String,
 }
 
+/// 複数回の測定結果を集計するパフォーマンス統計
+///
+/// 1回の測定はネットワークの揺らぎの影響を受けやすいため、
+/// 複数回の測定結果から平均値とパーセンタイルを求めます。
+#[derive(Debug, Clone, Default)]
+pub struct PerformanceStatsAccumulator {
+    pub samples: Vec<PerformanceStats>,
+}
+
+impl PerformanceStatsAccumulator {
+    /// 測定結果を追加する
+    pub fn push(&mut self, sample: PerformanceStats) {
+        self.samples.push(sample);
+    }
+
+    /// 測定結果の平均を取得する
+    ///
+    /// レイテンシとスループットは平均値、接続状態と測定日時は最後の測定結果の値を使用します。
+    /// 測定結果がない場合は0と「未測定」を返します。
+    pub fn average(&self) -> PerformanceStats {
+        let Some(latest) = self.samples.last() else {
+            return PerformanceStats {
+                latency_ms: 0,
+                throughput_bps: 0,
+                connection_status: "未測定".to_string(),
+                last_measured: String::new(),
+            };
+        };
+
+        let count = self.samples.len() as u64;
+        let total_latency: u64 = self.samples.iter().map(|s| s.latency_ms).sum();
+        let total_throughput: u64 = self.samples.iter().map(|s| s.throughput_bps).sum();
+
+        PerformanceStats {
+            latency_ms: total_latency / count,
+            throughput_bps: total_throughput / count,
+            connection_status: latest.connection_status.clone(),
+            last_measured: latest.last_measured.clone(),
+        }
+    }
+
+    /// レイテンシの95パーセンタイルを取得する（測定結果がない場合は0）
+    pub fn p95_latency_ms(&self) -> u64 {
+        self.latency_percentile(95)
+    }
+
+    /// レイテンシの99パーセンタイルを取得する（測定結果がない場合は0）
+    pub fn p99_latency_ms(&self) -> u64 {
+        self.latency_percentile(99)
+    }
+
+    /// レイテンシのパーセンタイルを最近傍順位法で求める
+    fn latency_percentile(&self, percentile: usize) -> u64 {
+        let mut latencies: Vec<u64> = self.samples.iter().map(|s| s.latency_ms).collect();
+        if latencies.is_empty() {
+            return 0;
+        }
+        latencies.sort_unstable();
+
+        let rank = (percentile * latencies.len()).div_ceil(100).max(1);
+        latencies[rank - 1]
+    }
+}
+
 /// R2使用量情報
 #[derive(Debug, Clone, Serialize)]
 pub struct R2UsageInfo {
@@ -150,6 +359,40 @@ pub struct UploadResult {
     pub duration: std::time::Duration,
 }
 
+impl UploadResult {
+    /// APIサーバーのアップロードレスポンスからアップロード結果を作成する
+    ///
+    /// # 引数
+    /// * `expense_id` - 経費ID（エラーメッセージ用）
+    /// * `response` - APIサーバーからのアップロードレスポンス
+    /// * `duration` - アップロードにかかった時間
+    ///
+    /// # 戻り値
+    /// アップロード結果（URLが空の場合はURLなしとして扱います）
+    pub fn from_api_response(
+        expense_id: i64,
+        response: UploadResponse,
+        duration: Duration,
+    ) -> Self {
+        let error = match (response.success, response.error) {
+            (true, _) => None,
+            (false, Some(error)) => Some(error),
+            (false, None) => Some(format!(
+                "領収書のアップロードに失敗しました（expense_id={expense_id}）"
+            )),
+        };
+
+        Self {
+            file_key: response.file_key,
+            success: response.success,
+            url: response.file_url.filter(|url| !url.is_empty()),
+            error,
+            file_size: response.file_size,
+            duration,
+        }
+    }
+}
+
 /// Duration を milliseconds として serialize する
 fn serialize_duration<S>(duration: &std::time::Duration, serializer: S) -> Result<S::Ok, S::Error>
 where
@@ -196,6 +439,148 @@ pub struct SyncFileResult {
     pub duration_ms: u64,
 }
 
+/// 注釈のメモの最大文字数
+pub const MAX_ANNOTATION_NOTE_LENGTH: usize = 500;
+
+/// 正規化座標の比較に使う許容誤差
+const RECT_EPSILON: f64 = 1e-9;
+
+/// 領収書上の矩形
+///
+/// 座標はページ（画像）の大きさに対する0〜1の相対値で、左上が原点です。
+/// 画像の解像度やPDFのページサイズに依存せずに保存できます。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NormalizedRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl NormalizedRect {
+    /// ピクセル（またはポイント）単位の矩形から正規化座標を作成する
+    ///
+    /// # 引数
+    /// * `left`, `top`, `width`, `height` - ページ上の矩形（左上原点）
+    /// * `page_width`, `page_height` - ページの大きさ
+    ///
+    /// # 戻り値
+    /// 正規化された矩形、またはページ外の場合はエラー
+    pub fn from_pixels(
+        left: f64,
+        top: f64,
+        width: f64,
+        height: f64,
+        page_width: f64,
+        page_height: f64,
+    ) -> AppResult<Self> {
+        if !(page_width > 0.0 && page_height > 0.0) {
+            return Err(AppError::validation(
+                "ページの大きさは0より大きい値を指定してください",
+            ));
+        }
+        let rect = Self {
+            x: left / page_width,
+            y: top / page_height,
+            width: width / page_width,
+            height: height / page_height,
+        };
+        rect.validate()?;
+        Ok(rect)
+    }
+
+    /// 正規化座標をページの大きさに合わせた矩形に変換する
+    ///
+    /// # 戻り値
+    /// (左, 上, 幅, 高さ)
+    pub fn to_pixels(&self, page_width: f64, page_height: f64) -> (f64, f64, f64, f64) {
+        (
+            self.x * page_width,
+            self.y * page_height,
+            self.width * page_width,
+            self.height * page_height,
+        )
+    }
+
+    /// 座標がページ内に収まっているかを検証する
+    pub fn validate(&self) -> AppResult<()> {
+        let values = [self.x, self.y, self.width, self.height];
+        if values.iter().any(|value| !value.is_finite()) {
+            return Err(AppError::validation("注釈の座標が不正です"));
+        }
+        if self.x < 0.0 || self.y < 0.0 || self.x >= 1.0 || self.y >= 1.0 {
+            return Err(AppError::validation(
+                "注釈の位置は0以上1未満で指定してください",
+            ));
+        }
+        if self.width <= 0.0 || self.height <= 0.0 {
+            return Err(AppError::validation(
+                "注釈の幅と高さは0より大きい値を指定してください",
+            ));
+        }
+        if self.x + self.width > 1.0 + RECT_EPSILON || self.y + self.height > 1.0 + RECT_EPSILON {
+            return Err(AppError::validation("注釈がページの外にはみ出しています"));
+        }
+        Ok(())
+    }
+}
+
+/// 領収書の注釈（該当する明細行のハイライト）
+///
+/// 注釈はメタデータのみで、保存済みの領収書ファイルは変更しません。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReceiptAnnotation {
+    pub id: i64,
+    pub expense_id: i64,
+    pub receipt_url: String,
+    pub page_number: u32, // ページ番号（1始まり、画像は常に1）
+    pub rect: NormalizedRect,
+    pub note: Option<String>,
+    pub created_at: String, // 作成日時（RFC3339形式、JST）
+}
+
+/// 注釈追加用DTO
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateReceiptAnnotationDto {
+    pub expense_id: i64,
+    pub page_number: Option<u32>,
+    pub rect: NormalizedRect,
+    pub note: Option<String>,
+}
+
+impl CreateReceiptAnnotationDto {
+    /// ページ番号（未指定時は1）
+    pub fn page_number(&self) -> u32 {
+        self.page_number.unwrap_or(1)
+    }
+
+    /// 前後の空白を除いたメモ（空の場合はNone）
+    pub fn note(&self) -> Option<String> {
+        self.note
+            .as_deref()
+            .map(str::trim)
+            .filter(|note| !note.is_empty())
+            .map(str::to_string)
+    }
+
+    /// 入力値を検証する
+    pub fn validate(&self) -> AppResult<()> {
+        if self.page_number() == 0 {
+            return Err(AppError::validation("ページ番号は1以上で指定してください"));
+        }
+        self.rect.validate()?;
+        if self
+            .note()
+            .is_some_and(|note| note.chars().count() > MAX_ANNOTATION_NOTE_LENGTH)
+        {
+            return Err(AppError::validation(format!(
+                "注釈のメモは{MAX_ANNOTATION_NOTE_LENGTH}文字以内で入力してください"
+            )));
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,6 +609,225 @@ mod tests {
         assert_eq!(deserialized.file_size, cache.file_size);
     }
 
+    fn cache_accessed_at(last_accessed: &str) -> ReceiptCache {
+        ReceiptCache {
+            id: 1,
+            receipt_url: "https://example.com/receipt.pdf".to_string(),
+            local_path: "/path/to/cache/receipt_123.pdf".to_string(),
+            cached_at: "2024-01-01T12:00:00+09:00".to_string(),
+            file_size: 1024,
+            last_accessed: last_accessed.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_receipt_cache_age() {
+        let now = DateTime::parse_from_rfc3339("2024-01-31T12:00:00+09:00")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        // タイムゾーンが異なっても正しく比較できる
+        let cache = cache_accessed_at("2024-01-01T03:00:00Z");
+        assert_eq!(cache.age_at(now), Duration::from_secs(30 * 24 * 3600));
+
+        // 未来の日時は経過時間0
+        let cache = cache_accessed_at("2024-02-01T00:00:00+09:00");
+        assert_eq!(cache.age_at(now), Duration::ZERO);
+
+        // 解析できない日時は期限切れ扱い
+        let cache = cache_accessed_at("invalid");
+        assert_eq!(cache.age_at(now), Duration::MAX);
+        assert!(cache.is_expired(Duration::from_secs(3600)));
+    }
+
+    #[test]
+    fn test_receipt_cache_is_expired() {
+        let day = Duration::from_secs(24 * 3600);
+        let accessed = (Utc::now() - chrono::Duration::days(10)).to_rfc3339();
+        let cache = cache_accessed_at(&accessed);
+
+        assert!(cache.is_expired(7 * day));
+        assert!(!cache.is_expired(30 * day));
+    }
+
+    #[test]
+    fn test_cache_policy_validation() {
+        let policy = CachePolicy::default();
+        assert!(policy.validate().is_ok());
+        assert_eq!(policy.max_size_bytes(), 100 * 1024 * 1024);
+        assert_eq!(policy.max_age(), Some(Duration::from_secs(7 * 24 * 3600)));
+
+        let unlimited_age = CachePolicy {
+            max_age_days: None,
+            ..policy
+        };
+        assert!(unlimited_age.validate().is_ok());
+        assert_eq!(unlimited_age.max_age(), None);
+
+        for invalid in [
+            CachePolicy {
+                max_size_mb: 0,
+                ..policy
+            },
+            CachePolicy {
+                max_size_mb: MAX_CACHE_SIZE_MB + 1,
+                ..policy
+            },
+            CachePolicy {
+                max_age_days: Some(0),
+                ..policy
+            },
+        ] {
+            assert!(invalid.validate().is_err(), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn test_eviction_strategy_serialization() {
+        for strategy in [
+            EvictionStrategy::Lru,
+            EvictionStrategy::Lfu,
+            EvictionStrategy::Fifo,
+        ] {
+            let json = serde_json::to_string(&strategy).unwrap();
+            assert_eq!(json, format!("\"{}\"", strategy.as_str()));
+            assert_eq!(
+                EvictionStrategy::parse(strategy.as_str()).unwrap(),
+                strategy
+            );
+        }
+        assert!(EvictionStrategy::parse("MRU").is_err());
+    }
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> NormalizedRect {
+        NormalizedRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn test_normalized_rect_from_pixels() {
+        let normalized =
+            NormalizedRect::from_pixels(100.0, 600.0, 800.0, 60.0, 1000.0, 2000.0).unwrap();
+        assert_eq!(normalized, rect(0.1, 0.3, 0.8, 0.03));
+        assert_eq!(
+            normalized.to_pixels(500.0, 1000.0),
+            (50.0, 300.0, 400.0, 30.0)
+        );
+
+        // ページ端まで（浮動小数点の誤差を許容）
+        assert!(NormalizedRect::from_pixels(0.0, 0.0, 595.0, 842.0, 595.0, 842.0).is_ok());
+
+        assert!(NormalizedRect::from_pixels(0.0, 0.0, 10.0, 10.0, 0.0, 842.0).is_err());
+        assert!(NormalizedRect::from_pixels(500.0, 0.0, 200.0, 10.0, 595.0, 842.0).is_err());
+    }
+
+    #[test]
+    fn test_normalized_rect_validation() {
+        assert!(rect(0.0, 0.5, 1.0, 0.5).validate().is_ok());
+
+        for invalid in [
+            rect(-0.1, 0.0, 0.5, 0.5),
+            rect(0.0, 1.0, 0.5, 0.1),
+            rect(0.2, 0.2, 0.0, 0.1),
+            rect(0.2, 0.2, 0.1, -0.1),
+            rect(0.6, 0.2, 0.5, 0.1),
+            rect(0.2, 0.95, 0.1, 0.1),
+            rect(f64::NAN, 0.2, 0.1, 0.1),
+            rect(0.2, 0.2, f64::INFINITY, 0.1),
+        ] {
+            assert!(invalid.validate().is_err(), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn test_create_receipt_annotation_dto_validation() {
+        let dto = CreateReceiptAnnotationDto {
+            expense_id: 1,
+            page_number: None,
+            rect: rect(0.1, 0.4, 0.8, 0.05),
+            note: Some("  タクシー代の行  ".to_string()),
+        };
+        assert!(dto.validate().is_ok());
+        assert_eq!(dto.page_number(), 1);
+        assert_eq!(dto.note().as_deref(), Some("タクシー代の行"));
+
+        let blank_note = CreateReceiptAnnotationDto {
+            note: Some("   ".to_string()),
+            ..dto.clone()
+        };
+        assert_eq!(blank_note.note(), None);
+
+        let zero_page = CreateReceiptAnnotationDto {
+            page_number: Some(0),
+            ..dto.clone()
+        };
+        assert!(zero_page.validate().is_err());
+
+        let long_note = CreateReceiptAnnotationDto {
+            note: Some("あ".repeat(MAX_ANNOTATION_NOTE_LENGTH + 1)),
+            ..dto
+        };
+        assert!(long_note.validate().is_err());
+    }
+
+    fn upload_response(
+        success: bool,
+        file_url: Option<&str>,
+        error: Option<&str>,
+    ) -> UploadResponse {
+        UploadResponse {
+            success,
+            file_url: file_url.map(str::to_string),
+            file_key: "users/1/receipts/10/receipt.jpg".to_string(),
+            file_size: 2048,
+            content_type: "image/jpeg".to_string(),
+            uploaded_at: "2024-01-01T00:00:00Z".to_string(),
+            error: error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_upload_result_from_api_response() {
+        let duration = Duration::from_millis(350);
+
+        let result = UploadResult::from_api_response(
+            10,
+            upload_response(true, Some("https://example.com/receipt.jpg"), None),
+            duration,
+        );
+        assert!(result.success);
+        assert_eq!(result.file_key, "users/1/receipts/10/receipt.jpg");
+        assert_eq!(
+            result.url.as_deref(),
+            Some("https://example.com/receipt.jpg")
+        );
+        assert_eq!(result.error, None);
+        assert_eq!(result.file_size, 2048);
+        assert_eq!(result.duration, duration);
+
+        // 空のURLはURLなしとして扱う
+        let result =
+            UploadResult::from_api_response(10, upload_response(true, Some(""), None), duration);
+        assert_eq!(result.url, None);
+
+        // 失敗時はAPIのエラーを優先し、なければ経費IDを含むメッセージを設定する
+        let result = UploadResult::from_api_response(
+            10,
+            upload_response(false, None, Some("容量超過")),
+            duration,
+        );
+        assert!(!result.success);
+        assert_eq!(result.error.as_deref(), Some("容量超過"));
+
+        let result =
+            UploadResult::from_api_response(10, upload_response(false, None, None), duration);
+        assert!(result.error.unwrap().contains("expense_id=10"));
+    }
+
     #[test]
     fn test_multiple_upload_result_model() {
         // 複数アップロード結果モデルのテスト
@@ -285,6 +889,63 @@ mod tests {
         assert_eq!(deserialized.cache_hit_rate, 0.85);
     }
 
+    #[test]
+    fn test_cache_stats_utilization() {
+        let stats = |total_size_bytes: u64, max_size_bytes: u64| CacheStats {
+            total_files: 1,
+            total_size_bytes,
+            max_size_bytes,
+            cache_hit_rate: 0.0,
+        };
+
+        assert_eq!(stats(0, 100).utilization_percent(), 0.0);
+        assert_eq!(stats(25, 100).utilization_percent(), 25.0);
+        assert_eq!(stats(150, 100).utilization_percent(), 150.0);
+        assert_eq!(stats(0, 0).utilization_percent(), 100.0);
+
+        assert!(!stats(89, 100).is_full(0.9));
+        assert!(stats(90, 100).is_full(0.9));
+        assert!(stats(120, 100).is_full(0.9));
+        assert!(stats(0, 0).is_full(0.9));
+    }
+
+    #[test]
+    fn test_performance_stats_accumulator() {
+        let sample = |latency_ms, status: &str, measured: &str| PerformanceStats {
+            latency_ms,
+            throughput_bps: latency_ms * 10,
+            connection_status: status.to_string(),
+            last_measured: measured.to_string(),
+        };
+
+        let empty = PerformanceStatsAccumulator::default();
+        assert_eq!(empty.average().latency_ms, 0);
+        assert_eq!(empty.average().connection_status, "未測定");
+        assert_eq!(empty.p95_latency_ms(), 0);
+
+        let mut accumulator = PerformanceStatsAccumulator::default();
+        accumulator.push(sample(120, "connected", "2025-04-01T10:00:00+09:00"));
+        accumulator.push(sample(80, "connected", "2025-04-01T10:00:01+09:00"));
+        accumulator.push(sample(400, "disconnected", "2025-04-01T10:00:02+09:00"));
+
+        let average = accumulator.average();
+        assert_eq!(average.latency_ms, 200);
+        assert_eq!(average.throughput_bps, 2000);
+        assert_eq!(average.connection_status, "disconnected");
+        assert_eq!(average.last_measured, "2025-04-01T10:00:02+09:00");
+
+        // 3回の測定では95・99パーセンタイルとも最大値になる
+        assert_eq!(accumulator.p95_latency_ms(), 400);
+        assert_eq!(accumulator.p99_latency_ms(), 400);
+
+        let mut accumulator = PerformanceStatsAccumulator::default();
+        for latency_ms in 1..=100 {
+            accumulator.push(sample(latency_ms, "connected", ""));
+        }
+        assert_eq!(accumulator.p95_latency_ms(), 95);
+        assert_eq!(accumulator.p99_latency_ms(), 99);
+    }
+
     #[test]
     fn test_upload_status_enum() {
         // アップロードステータス列挙型のテスト
@@ -0,0 +1,1019 @@
+// アップロード済み領収書の再圧縮
+//
+// 圧縮設定の変更前にアップロードされた大きな領収書を再圧縮し、置き換えます。
+// - 対象はレシートキャッシュのファイルサイズが閾値以上の領収書
+// - 指定した割合以上小さくなった場合のみ、新しいキーでアップロードして経費の参照を付け替える
+// - 元のオブジェクトは、新しいオブジェクトを再ダウンロードしてハッシュを確認し、
+//   データベースの更新をコミットした後でのみ削除する
+// 処理状況はデータベースに記録するため、中断しても続きから再開できます。
+
+use super::api_client::{ApiClient, ApiClientConfig};
+use super::api_commands::{extract_file_key_from_url, ReceiptResponse};
+use super::cache::CacheManager;
+use crate::features::migrations::database_updater::{DatabaseUpdater, ReceiptUrlRebaseItem};
+use crate::shared::api_client::ApiClient as SharedApiClient;
+use crate::shared::database::lock_db;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::export::sha256_hex;
+use base64::{engine::general_purpose, Engine as _};
+use chrono::Utc;
+use chrono_tz::Asia::Tokyo;
+use futures::stream::{self, StreamExt};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, Rgb, RgbImage};
+use log::{info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::Mutex;
+
+/// 再圧縮対象とするファイルサイズの既定値（2MB）
+pub const DEFAULT_MIN_SIZE_BYTES: u64 = 2 * 1024 * 1024;
+
+/// 置き換えに必要な削減率の既定値（%）
+pub const DEFAULT_MIN_SAVINGS_PERCENT: u8 = 20;
+
+/// 同時に処理する領収書数の既定値
+pub const DEFAULT_MAX_CONCURRENT: usize = 3;
+
+/// 同時に処理する領収書数の上限
+const MAX_CONCURRENT_LIMIT: usize = 8;
+
+/// 再圧縮後の画像の長辺の最大ピクセル数
+const MAX_DIMENSION: u32 = 2400;
+
+/// 再圧縮時のJPEG品質
+const JPEG_QUALITY: u8 = 80;
+
+/// 再圧縮の設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecompressOptions {
+    /// 再圧縮対象とする最小ファイルサイズ（バイト）
+    pub min_size_bytes: u64,
+    /// 置き換えに必要な削減率（%）
+    pub min_savings_percent: u8,
+    /// trueの場合は置き換えずに削減見込みのみを報告する
+    pub dry_run: bool,
+    /// 同時に処理する領収書数
+    pub max_concurrent: usize,
+}
+
+impl Default for RecompressOptions {
+    fn default() -> Self {
+        Self {
+            min_size_bytes: DEFAULT_MIN_SIZE_BYTES,
+            min_savings_percent: DEFAULT_MIN_SAVINGS_PERCENT,
+            dry_run: false,
+            max_concurrent: DEFAULT_MAX_CONCURRENT,
+        }
+    }
+}
+
+/// 領収書ごとの処理状況
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecompressStatus {
+    /// 削減見込み（ドライラン）
+    Projected,
+    /// 新しいオブジェクトをアップロード済み（参照の付け替え前）
+    Uploaded,
+    /// 参照を付け替え済み（元のオブジェクトの削除待ち）
+    Replaced,
+    /// 置き換えと元のオブジェクトの削除が完了
+    Completed,
+    /// 削減率が足りない、または対応していない形式のため対象外
+    Skipped,
+    /// 失敗（次回の実行で再試行）
+    Failed,
+}
+
+impl RecompressStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Projected => "projected",
+            Self::Uploaded => "uploaded",
+            Self::Replaced => "replaced",
+            Self::Completed => "completed",
+            Self::Skipped => "skipped",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// 領収書ごとの処理結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecompressItemResult {
+    pub expense_id: i64,
+    /// 元の領収書URL
+    pub receipt_url: String,
+    pub status: RecompressStatus,
+    /// 元のファイルサイズ（バイト）
+    pub original_size: u64,
+    /// 再圧縮後のファイルサイズ（バイト）
+    pub new_size: Option<u64>,
+    /// 置き換え後の領収書URL
+    pub new_url: Option<String>,
+    /// スキップ・失敗の理由
+    pub message: Option<String>,
+}
+
+impl RecompressItemResult {
+    /// 置き換えによって削減された（ドライランでは削減見込みの）バイト数
+    fn bytes_saved(&self) -> u64 {
+        match (self.status, self.new_size) {
+            (
+                RecompressStatus::Projected
+                | RecompressStatus::Replaced
+                | RecompressStatus::Completed,
+                Some(new_size),
+            ) => self.original_size.saturating_sub(new_size),
+            _ => 0,
+        }
+    }
+}
+
+/// 再圧縮の結果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecompressReport {
+    pub dry_run: bool,
+    /// 処理した領収書数
+    pub processed: usize,
+    /// 置き換えた（ドライランでは置き換え対象の）領収書数
+    pub replaced: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    /// 削減されたバイト数（ドライランでは削減見込み）
+    pub bytes_saved: u64,
+    pub items: Vec<RecompressItemResult>,
+}
+
+impl RecompressReport {
+    fn from_items(dry_run: bool, mut items: Vec<RecompressItemResult>) -> Self {
+        items.sort_by_key(|item| item.expense_id);
+        let count = |status: &[RecompressStatus]| {
+            items
+                .iter()
+                .filter(|item| status.contains(&item.status))
+                .count()
+        };
+        Self {
+            dry_run,
+            processed: items.len(),
+            replaced: count(&[
+                RecompressStatus::Projected,
+                RecompressStatus::Replaced,
+                RecompressStatus::Completed,
+            ]),
+            skipped: count(&[RecompressStatus::Skipped]),
+            failed: count(&[RecompressStatus::Failed]),
+            bytes_saved: items.iter().map(RecompressItemResult::bytes_saved).sum(),
+            items,
+        }
+    }
+}
+
+/// 再圧縮の対象
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecompressCandidate {
+    pub expense_id: i64,
+    pub receipt_url: String,
+    /// レシートキャッシュに記録されたファイルサイズ（バイト）
+    pub file_size: u64,
+}
+
+/// 領収書の保存先
+pub trait ReceiptStore: Sync {
+    /// 領収書をダウンロードする（キャッシュは使用しない）
+    fn download(&self, receipt_url: &str) -> impl Future<Output = AppResult<Vec<u8>>> + Send;
+
+    /// 領収書を新しいキーでアップロードする
+    ///
+    /// # 戻り値
+    /// アップロードした領収書のURL
+    fn upload(
+        &self,
+        expense_id: i64,
+        data: &[u8],
+        filename: &str,
+    ) -> impl Future<Output = AppResult<String>> + Send;
+
+    /// 領収書を削除する
+    fn delete(&self, receipt_url: &str) -> impl Future<Output = AppResult<()>> + Send;
+}
+
+/// APIサーバー経由で領収書を操作する保存先
+pub struct ApiReceiptStore {
+    upload_client: ApiClient,
+    api_client: SharedApiClient,
+    user_id: String,
+    auth_token: String,
+}
+
+impl ApiReceiptStore {
+    /// 新しい保存先を作成
+    ///
+    /// # 引数
+    /// * `user_id` - ユーザーID
+    /// * `auth_token` - 認証トークン
+    pub fn new(user_id: String, auth_token: String) -> AppResult<Self> {
+        Ok(Self {
+            upload_client: ApiClient::new(ApiClientConfig::from_env())?,
+            api_client: SharedApiClient::new()?,
+            user_id,
+            auth_token,
+        })
+    }
+}
+
+impl ReceiptStore for ApiReceiptStore {
+    async fn download(&self, receipt_url: &str) -> AppResult<Vec<u8>> {
+        let file_key = extract_file_key_from_url(receipt_url).map_err(AppError::Validation)?;
+        let response = self
+            .api_client
+            .get::<ReceiptResponse>(
+                &format!("/api/v1/receipts/{file_key}/data"),
+                Some(&self.auth_token),
+            )
+            .await?;
+        general_purpose::STANDARD
+            .decode(response.data)
+            .map_err(|e| AppError::ExternalService(format!("領収書データのデコードに失敗: {e}")))
+    }
+
+    async fn upload(&self, expense_id: i64, data: &[u8], filename: &str) -> AppResult<String> {
+        let response = self
+            .upload_client
+            .upload_file(expense_id, data, filename, &self.user_id, &self.auth_token)
+            .await?;
+        response
+            .file_url
+            .filter(|url| !url.is_empty())
+            .ok_or_else(|| {
+                AppError::ExternalService("アップロード結果にURLが含まれていません".to_string())
+            })
+    }
+
+    async fn delete(&self, receipt_url: &str) -> AppResult<()> {
+        let response = self
+            .api_client
+            .delete_with_body::<serde_json::Value>(
+                "/api/v1/receipts/delete-by-url",
+                &serde_json::json!({ "receiptUrl": receipt_url }),
+                Some(&self.auth_token),
+            )
+            .await?;
+        if response.get("success").and_then(|v| v.as_bool()) == Some(true) {
+            Ok(())
+        } else {
+            Err(AppError::ExternalService(format!(
+                "領収書の削除に失敗しました: {receipt_url}"
+            )))
+        }
+    }
+}
+
+/// 領収書画像を再圧縮する
+///
+/// 長辺が上限を超える画像は縮小し、JPEGで保存し直します。
+/// 透過部分は白で塗りつぶします。
+///
+/// # 戻り値
+/// 再圧縮したJPEGデータ（PDFなど対応していない形式の場合はNone）
+pub fn compress_receipt(data: &[u8]) -> AppResult<Option<Vec<u8>>> {
+    let format = match image::guess_format(data) {
+        Ok(format @ (ImageFormat::Jpeg | ImageFormat::Png)) => format,
+        _ => return Ok(None),
+    };
+    let image = image::load_from_memory_with_format(data, format)
+        .map_err(|e| AppError::Validation(format!("領収書画像の読み込みに失敗しました: {e}")))?;
+
+    let image = if image.width().max(image.height()) > MAX_DIMENSION {
+        image.resize(MAX_DIMENSION, MAX_DIMENSION, FilterType::Lanczos3)
+    } else {
+        image
+    };
+
+    let mut encoded = Vec::new();
+    JpegEncoder::new_with_quality(&mut encoded, JPEG_QUALITY)
+        .encode_image(&flatten_on_white(&image))
+        .map_err(|e| AppError::ExternalService(format!("領収書画像の圧縮に失敗しました: {e}")))?;
+    Ok(Some(encoded))
+}
+
+/// 透過部分を白で塗りつぶしたRGB画像に変換する
+fn flatten_on_white(image: &DynamicImage) -> RgbImage {
+    if !image.color().has_alpha() {
+        return image.to_rgb8();
+    }
+    let rgba = image.to_rgba8();
+    RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let blend = |c: u8| ((c as u32 * a as u32 + 255 * (255 - a as u32)) / 255) as u8;
+        Rgb([blend(r), blend(g), blend(b)])
+    })
+}
+
+/// 再圧縮後のファイル名（拡張子を.jpgに変更）
+fn recompressed_filename(receipt_url: &str) -> String {
+    let name = receipt_url
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.split(['?', '#']).next())
+        .filter(|name| !name.is_empty())
+        .unwrap_or("receipt");
+    let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+    format!("{stem}.jpg")
+}
+
+/// 処理状況テーブルを作成する
+pub fn ensure_table(conn: &Connection) -> AppResult<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS receipt_recompress_items (
+            receipt_url TEXT PRIMARY KEY,
+            expense_id INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            original_size INTEGER NOT NULL,
+            new_url TEXT,
+            new_size INTEGER,
+            new_sha256 TEXT,
+            message TEXT,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// 処理状況を記録する
+fn save_item(
+    conn: &Connection,
+    item: &RecompressItemResult,
+    sha256: Option<&str>,
+) -> AppResult<()> {
+    let now = Utc::now().with_timezone(&Tokyo).to_rfc3339();
+    conn.execute(
+        "INSERT OR REPLACE INTO receipt_recompress_items (
+            receipt_url, expense_id, status, original_size, new_url, new_size,
+            new_sha256, message, updated_at
+         ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            item.receipt_url,
+            item.expense_id,
+            item.status.as_str(),
+            item.original_size as i64,
+            item.new_url,
+            item.new_size.map(|size| size as i64),
+            sha256,
+            item.message,
+            now,
+        ],
+    )?;
+    Ok(())
+}
+
+/// 再圧縮の対象を取得する
+///
+/// レシートキャッシュのファイルサイズが閾値以上で、置き換え済み・対象外と判定済みでない領収書が対象です。
+/// 再圧縮して置き換えた後の領収書は対象にしません。
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+/// * `min_size_bytes` - 最小ファイルサイズ
+pub fn select_candidates(
+    conn: &Connection,
+    user_id: &str,
+    min_size_bytes: u64,
+) -> AppResult<Vec<RecompressCandidate>> {
+    ensure_table(conn)?;
+    let mut stmt = conn.prepare(
+        "SELECT e.id, e.receipt_url, c.file_size
+         FROM expenses e
+         JOIN receipt_cache c ON c.receipt_url = e.receipt_url
+         WHERE c.user_id = ?1 AND c.file_size >= ?2
+           AND e.receipt_url NOT IN (
+             SELECT receipt_url FROM receipt_recompress_items
+             WHERE status IN ('replaced', 'completed', 'skipped')
+           )
+           AND e.receipt_url NOT IN (
+             SELECT new_url FROM receipt_recompress_items
+             WHERE new_url IS NOT NULL AND status IN ('replaced', 'completed')
+           )
+         ORDER BY e.id",
+    )?;
+    let candidates = stmt
+        .query_map(params![user_id, min_size_bytes as i64], |row| {
+            Ok(RecompressCandidate {
+                expense_id: row.get(0)?,
+                receipt_url: row.get(1)?,
+                file_size: row.get::<_, i64>(2)? as u64,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(candidates)
+}
+
+/// 領収書の再圧縮ジョブ
+pub struct RecompressJob<'a, S> {
+    store: &'a S,
+    db: &'a Mutex<Connection>,
+    cache: &'a CacheManager,
+    user_id: &'a str,
+    options: RecompressOptions,
+}
+
+impl<'a, S: ReceiptStore> RecompressJob<'a, S> {
+    /// 新しいジョブを作成
+    ///
+    /// # 引数
+    /// * `store` - 領収書の保存先
+    /// * `db` - データベース接続
+    /// * `cache` - レシートキャッシュ
+    /// * `user_id` - ユーザーID
+    /// * `options` - 再圧縮の設定
+    pub fn new(
+        store: &'a S,
+        db: &'a Mutex<Connection>,
+        cache: &'a CacheManager,
+        user_id: &'a str,
+        options: RecompressOptions,
+    ) -> Self {
+        Self {
+            store,
+            db,
+            cache,
+            user_id,
+            options,
+        }
+    }
+
+    /// ジョブを実行する
+    ///
+    /// 前回中断した置き換えの後始末をしてから、対象の領収書を並行して処理します。
+    pub async fn run(&self) -> AppResult<RecompressReport> {
+        let candidates = {
+            let conn = lock_db(self.db)?;
+            select_candidates(&conn, self.user_id, self.options.min_size_bytes)?
+        };
+        info!(
+            "領収書の再圧縮を開始します: 対象={}件, dry_run={}",
+            candidates.len(),
+            self.options.dry_run
+        );
+
+        let mut items = if self.options.dry_run {
+            Vec::new()
+        } else {
+            self.finish_pending_deletions().await?
+        };
+
+        let concurrency = self.options.max_concurrent.clamp(1, MAX_CONCURRENT_LIMIT);
+        let processed: Vec<_> = stream::iter(candidates)
+            .map(|candidate| self.process(candidate))
+            .buffer_unordered(concurrency)
+            .collect()
+            .await;
+        items.extend(processed);
+
+        let report = RecompressReport::from_items(self.options.dry_run, items);
+        info!(
+            "領収書の再圧縮が完了しました: 置き換え={}件, スキップ={}件, 失敗={}件, 削減={}バイト",
+            report.replaced, report.skipped, report.failed, report.bytes_saved
+        );
+        Ok(report)
+    }
+
+    /// 参照の付け替え後に削除できなかった元のオブジェクトを削除する
+    async fn finish_pending_deletions(&self) -> AppResult<Vec<RecompressItemResult>> {
+        let pending = {
+            let conn = lock_db(self.db)?;
+            ensure_table(&conn)?;
+            let mut stmt = conn.prepare(
+                "SELECT expense_id, receipt_url, original_size, new_url, new_size, new_sha256
+                 FROM receipt_recompress_items WHERE status = 'replaced'",
+            )?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok((
+                        RecompressItemResult {
+                            expense_id: row.get(0)?,
+                            receipt_url: row.get(1)?,
+                            status: RecompressStatus::Replaced,
+                            original_size: row.get::<_, i64>(2)? as u64,
+                            new_url: row.get(3)?,
+                            new_size: row.get::<_, Option<i64>>(4)?.map(|size| size as u64),
+                            message: None,
+                        },
+                        row.get::<_, Option<String>>(5)?,
+                    ))
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+
+        let mut items = Vec::with_capacity(pending.len());
+        for (item, sha256) in pending {
+            items.push(self.delete_original(item, sha256.as_deref()).await);
+        }
+        Ok(items)
+    }
+
+    /// 1件の領収書を処理する
+    async fn process(&self, candidate: RecompressCandidate) -> RecompressItemResult {
+        let mut item = RecompressItemResult {
+            expense_id: candidate.expense_id,
+            receipt_url: candidate.receipt_url,
+            status: RecompressStatus::Failed,
+            original_size: candidate.file_size,
+            new_size: None,
+            new_url: None,
+            message: None,
+        };
+
+        match self.try_process(&mut item).await {
+            Ok(item) => item,
+            Err(e) => {
+                warn!(
+                    "領収書の再圧縮に失敗しました: expense_id={}, error={e}",
+                    item.expense_id
+                );
+                item.status = RecompressStatus::Failed;
+                item.message = Some(e.to_string());
+                if !self.options.dry_run {
+                    if let Err(e) = lock_db(self.db).and_then(|conn| save_item(&conn, &item, None))
+                    {
+                        warn!("再圧縮の処理状況の記録に失敗しました: {e}");
+                    }
+                }
+                item
+            }
+        }
+    }
+
+    async fn try_process(
+        &self,
+        item: &mut RecompressItemResult,
+    ) -> AppResult<RecompressItemResult> {
+        if !self.options.dry_run {
+            self.discard_orphaned_upload(&item.receipt_url).await?;
+        }
+
+        // キャッシュを優先して元の領収書を取得する
+        let cached = {
+            let conn = lock_db(self.db)?;
+            self.cache
+                .get_cached_file(&item.receipt_url, &conn, self.user_id)?
+        };
+        let original = match cached {
+            Some(data) => data,
+            None => self.store.download(&item.receipt_url).await?,
+        };
+        item.original_size = original.len() as u64;
+
+        let Some(compressed) = compress_receipt(&original)? else {
+            return self.skip(item, "再圧縮に対応していない形式です");
+        };
+        item.new_size = Some(compressed.len() as u64);
+
+        let required =
+            item.original_size * (100 - self.options.min_savings_percent.min(100) as u64);
+        if compressed.len() as u64 * 100 > required {
+            return self.skip(item, "削減率が閾値に達しません");
+        }
+
+        if self.options.dry_run {
+            item.status = RecompressStatus::Projected;
+            return Ok(item.clone());
+        }
+
+        // 新しいキーでアップロードし、付け替え前に記録しておく（中断時に後始末できるように）
+        let sha256 = sha256_hex(&compressed);
+        let new_url = self
+            .store
+            .upload(
+                item.expense_id,
+                &compressed,
+                &recompressed_filename(&item.receipt_url),
+            )
+            .await?;
+        item.new_url = Some(new_url.clone());
+        item.status = RecompressStatus::Uploaded;
+        save_item(&*lock_db(self.db)?, item, Some(&sha256))?;
+
+        // 再ダウンロードしてハッシュを確認する
+        let verified = self
+            .store
+            .download(&new_url)
+            .await
+            .map(|data| sha256_hex(&data) == sha256);
+        if !matches!(verified, Ok(true)) {
+            self.delete_uploaded(item).await;
+            return Err(match verified {
+                Err(e) => e,
+                _ => AppError::ExternalService(
+                    "アップロードした領収書のハッシュが一致しません".to_string(),
+                ),
+            });
+        }
+
+        if !self.commit_replacement(item, &sha256)? {
+            self.delete_uploaded(item).await;
+            return Err(AppError::Concurrency(
+                "処理中に経費の領収書が変更されたため置き換えを中止しました".to_string(),
+            ));
+        }
+        self.recache(&new_url, compressed);
+
+        Ok(self.delete_original(item.clone(), Some(&sha256)).await)
+    }
+
+    /// 対象外として記録する
+    fn skip(
+        &self,
+        item: &mut RecompressItemResult,
+        reason: &str,
+    ) -> AppResult<RecompressItemResult> {
+        item.status = RecompressStatus::Skipped;
+        item.message = Some(reason.to_string());
+        if !self.options.dry_run {
+            save_item(&*lock_db(self.db)?, item, None)?;
+        }
+        Ok(item.clone())
+    }
+
+    /// 前回の処理でアップロードしたまま参照されていないオブジェクトを削除する
+    ///
+    /// 中断した場合や、置き換えを中止した際の削除に失敗した場合に残ります。
+    async fn discard_orphaned_upload(&self, receipt_url: &str) -> AppResult<()> {
+        let orphan = {
+            let conn = lock_db(self.db)?;
+            conn.query_row(
+                "SELECT new_url FROM receipt_recompress_items
+                 WHERE receipt_url = ?1 AND status IN ('uploaded', 'failed')",
+                params![receipt_url],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()?
+            .flatten()
+        };
+        if let Some(orphan) = orphan {
+            info!("中断時にアップロードした領収書を削除します: {orphan}");
+            self.store.delete(&orphan).await?;
+        }
+        Ok(())
+    }
+
+    /// 経費・注釈の参照を新しいURLに付け替え、処理状況と同じトランザクションでコミットする
+    ///
+    /// 元の領収書のキャッシュは参照先と内容が一致しなくなるため削除します。
+    ///
+    /// # 戻り値
+    /// 経費が更新された場合はtrue
+    fn commit_replacement(&self, item: &mut RecompressItemResult, sha256: &str) -> AppResult<bool> {
+        let conn = lock_db(self.db)?;
+        let tx = conn.unchecked_transaction()?;
+        let stale_cache: Option<String> = tx
+            .query_row(
+                "SELECT local_path FROM receipt_cache WHERE receipt_url = ?1",
+                params![item.receipt_url],
+                |row| row.get(0),
+            )
+            .optional()?;
+        tx.execute(
+            "DELETE FROM receipt_cache WHERE receipt_url = ?1",
+            params![item.receipt_url],
+        )?;
+
+        let rebase = ReceiptUrlRebaseItem {
+            expense_id: item.expense_id,
+            old_url: item.receipt_url.clone(),
+            new_url: item.new_url.clone().unwrap_or_default(),
+        };
+        let now = Utc::now().with_timezone(&Tokyo).to_rfc3339();
+        if !DatabaseUpdater::rebase_receipt_url_in(&tx, &rebase, &now)? {
+            return Ok(false);
+        }
+
+        item.status = RecompressStatus::Replaced;
+        save_item(&tx, item, Some(sha256))?;
+        tx.commit()?;
+
+        if let Some(path) = stale_cache {
+            if let Err(e) = std::fs::remove_file(&path) {
+                warn!("置き換え前の領収書のキャッシュの削除に失敗しました: {path}, error={e}");
+            }
+        }
+        Ok(true)
+    }
+
+    /// 再圧縮した領収書をキャッシュする（失敗しても置き換えは完了扱い）
+    fn recache(&self, new_url: &str, data: Vec<u8>) {
+        let result = lock_db(self.db)
+            .and_then(|conn| self.cache.cache_file(new_url, data, &conn, self.user_id));
+        if let Err(e) = result {
+            warn!("再圧縮した領収書のキャッシュに失敗しました: {e}");
+        }
+    }
+
+    /// 置き換えを中止した場合にアップロードしたオブジェクトを削除する
+    ///
+    /// 削除に失敗した場合はURLを残し、次回の実行で削除します。
+    async fn delete_uploaded(&self, item: &mut RecompressItemResult) {
+        let Some(new_url) = item.new_url.clone() else {
+            return;
+        };
+        match self.store.delete(&new_url).await {
+            Ok(()) => item.new_url = None,
+            Err(e) => warn!("アップロードした領収書の削除に失敗しました: {new_url}, error={e}"),
+        }
+    }
+
+    /// 付け替え済みの元のオブジェクトを削除する
+    ///
+    /// 削除に失敗した場合は付け替え済みのまま記録し、次回の実行で再試行します。
+    async fn delete_original(
+        &self,
+        mut item: RecompressItemResult,
+        sha256: Option<&str>,
+    ) -> RecompressItemResult {
+        match self.store.delete(&item.receipt_url).await {
+            Ok(()) => {
+                item.status = RecompressStatus::Completed;
+                item.message = None;
+            }
+            Err(e) => {
+                warn!(
+                    "置き換え前の領収書の削除に失敗しました: {}, error={e}",
+                    item.receipt_url
+                );
+                item.message = Some(format!("元の領収書の削除に失敗しました（次回再試行）: {e}"));
+            }
+        }
+        if let Err(e) = lock_db(self.db).and_then(|conn| save_item(&conn, &item, sha256)) {
+            warn!("再圧縮の処理状況の記録に失敗しました: {e}");
+        }
+        item
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const USER_ID: &str = "user-1";
+
+    /// メモリ上の保存先
+    #[derive(Default)]
+    struct MockStore {
+        objects: Mutex<HashMap<String, Vec<u8>>>,
+        uploads: AtomicU32,
+        /// アップロードしたデータを壊して保存する（ハッシュ不一致の再現）
+        corrupt_uploads: bool,
+        /// 指定したURLの削除を失敗させる
+        fail_delete_of: Option<String>,
+    }
+
+    impl MockStore {
+        fn with(objects: &[(&str, Vec<u8>)]) -> Self {
+            let store = Self::default();
+            for (url, data) in objects {
+                store.put(url, data.clone());
+            }
+            store
+        }
+
+        fn put(&self, url: &str, data: Vec<u8>) {
+            self.objects.lock().unwrap().insert(url.to_string(), data);
+        }
+
+        fn contains(&self, url: &str) -> bool {
+            self.objects.lock().unwrap().contains_key(url)
+        }
+    }
+
+    impl ReceiptStore for MockStore {
+        async fn download(&self, receipt_url: &str) -> AppResult<Vec<u8>> {
+            self.objects
+                .lock()
+                .unwrap()
+                .get(receipt_url)
+                .cloned()
+                .ok_or_else(|| AppError::not_found(receipt_url))
+        }
+
+        async fn upload(&self, expense_id: i64, data: &[u8], filename: &str) -> AppResult<String> {
+            let n = self.uploads.fetch_add(1, Ordering::SeqCst);
+            let url = format!("https://receipts.example.com/receipts/{expense_id}/{n}-{filename}");
+            let mut data = data.to_vec();
+            if self.corrupt_uploads {
+                data.push(0);
+            }
+            self.put(&url, data);
+            Ok(url)
+        }
+
+        async fn delete(&self, receipt_url: &str) -> AppResult<()> {
+            if self.fail_delete_of.as_deref() == Some(receipt_url) {
+                return Err(AppError::ExternalService("削除失敗".to_string()));
+            }
+            self.objects.lock().unwrap().remove(receipt_url);
+            Ok(())
+        }
+    }
+
+    /// 圧縮しにくいノイズ画像（PNG）
+    fn noisy_png(width: u32, height: u32) -> Vec<u8> {
+        let mut seed = 12345u32;
+        let image = RgbaImage::from_fn(width, height, |_, _| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let [r, g, b, _] = seed.to_le_bytes();
+            Rgba([r, g, b, 255])
+        });
+        let mut data = Vec::new();
+        DynamicImage::ImageRgba8(image)
+            .write_to(&mut std::io::Cursor::new(&mut data), ImageFormat::Png)
+            .unwrap();
+        data
+    }
+
+    fn url(id: i64) -> String {
+        format!("https://receipts.example.com/receipts/{id}/scan.png")
+    }
+
+    fn setup(expenses: &[(i64, u64)]) -> (Mutex<Connection>, CacheManager, tempfile::TempDir) {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE expenses (id INTEGER PRIMARY KEY, receipt_url TEXT, updated_at TEXT);
+             CREATE TABLE receipt_cache (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                receipt_url TEXT NOT NULL UNIQUE,
+                local_path TEXT NOT NULL,
+                cached_at TEXT NOT NULL,
+                file_size INTEGER NOT NULL,
+                last_accessed TEXT NOT NULL,
+                user_id TEXT NOT NULL
+             );",
+        )
+        .unwrap();
+        crate::features::receipts::annotations::create_annotations_table(&conn).unwrap();
+        for (id, size) in expenses {
+            conn.execute(
+                "INSERT INTO expenses (id, receipt_url) VALUES (?1, ?2)",
+                params![id, url(*id)],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO receipt_cache (receipt_url, local_path, cached_at, file_size, last_accessed, user_id)
+                 VALUES (?1, '/nonexistent', 'now', ?2, 'now', ?3)",
+                params![url(*id), *size as i64, USER_ID],
+            )
+            .unwrap();
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let cache = CacheManager::new(dir.path().join("receipt_cache"), 100);
+        (Mutex::new(conn), cache, dir)
+    }
+
+    fn options(dry_run: bool) -> RecompressOptions {
+        RecompressOptions {
+            min_size_bytes: 1000,
+            dry_run,
+            ..Default::default()
+        }
+    }
+
+    fn expense_url(db: &Mutex<Connection>, id: i64) -> String {
+        db.lock()
+            .unwrap()
+            .query_row(
+                "SELECT receipt_url FROM expenses WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_compress_receipt() {
+        let png = noisy_png(3000, 100);
+        let compressed = compress_receipt(&png).unwrap().unwrap();
+        let decoded = image::load_from_memory(&compressed).unwrap();
+        assert_eq!(image::guess_format(&compressed).unwrap(), ImageFormat::Jpeg);
+        assert_eq!(decoded.width(), MAX_DIMENSION);
+        assert!(compressed.len() < png.len());
+
+        // PDFは対象外
+        assert!(compress_receipt(b"%PDF-1.4\n").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_recompressed_filename() {
+        assert_eq!(recompressed_filename(&url(1)), "scan.jpg");
+        assert_eq!(
+            recompressed_filename("https://example.com/a/b.v2.jpeg?x=1"),
+            "b.v2.jpg"
+        );
+        assert_eq!(recompressed_filename("https://example.com/"), "receipt.jpg");
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_reports_projected_savings_without_changes() {
+        let (db, cache, _dir) = setup(&[(1, 5000), (2, 10)]);
+        let store = MockStore::with(&[(&url(1), noisy_png(800, 800))]);
+
+        let report = RecompressJob::new(&store, &db, &cache, USER_ID, options(true))
+            .run()
+            .await
+            .unwrap();
+
+        // 閾値未満の経費2は対象外
+        assert_eq!(report.processed, 1);
+        assert_eq!(report.replaced, 1);
+        assert_eq!(report.items[0].status, RecompressStatus::Projected);
+        assert!(report.bytes_saved > 0);
+        assert_eq!(store.uploads.load(Ordering::SeqCst), 0);
+        assert_eq!(expense_url(&db, 1), url(1));
+    }
+
+    #[tokio::test]
+    async fn test_replaces_receipt_and_deletes_original() {
+        let (db, cache, _dir) = setup(&[(1, 5000), (2, 5000)]);
+        let original = noisy_png(800, 800);
+        // 経費2はすでに十分小さいJPEG
+        let small = compress_receipt(&original).unwrap().unwrap();
+        let store = MockStore::with(&[(&url(1), original.clone()), (&url(2), small)]);
+
+        let report = RecompressJob::new(&store, &db, &cache, USER_ID, options(false))
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!((report.replaced, report.skipped, report.failed), (1, 1, 0));
+        let replaced = &report.items[0];
+        assert_eq!(replaced.status, RecompressStatus::Completed);
+        let new_url = replaced.new_url.clone().unwrap();
+        assert_eq!(expense_url(&db, 1), new_url);
+        assert!(store.contains(&new_url));
+        assert!(!store.contains(&url(1)));
+        assert_eq!(
+            report.bytes_saved,
+            original.len() as u64 - replaced.new_size.unwrap()
+        );
+        assert_eq!(report.items[1].status, RecompressStatus::Skipped);
+
+        // 再実行しても処理済み・対象外の領収書は再処理しない
+        let report = RecompressJob::new(&store, &db, &cache, USER_ID, options(false))
+            .run()
+            .await
+            .unwrap();
+        assert_eq!(report.processed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_hash_mismatch_keeps_original() {
+        let (db, cache, _dir) = setup(&[(1, 5000)]);
+        let mut store = MockStore::with(&[(&url(1), noisy_png(800, 800))]);
+        store.corrupt_uploads = true;
+
+        let report = RecompressJob::new(&store, &db, &cache, USER_ID, options(false))
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.bytes_saved, 0);
+        assert_eq!(expense_url(&db, 1), url(1));
+        assert!(store.contains(&url(1)));
+        // アップロードした領収書は削除される
+        assert_eq!(store.objects.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_original_deletion_is_retried() {
+        let (db, cache, _dir) = setup(&[(1, 5000)]);
+        let mut store = MockStore::with(&[(&url(1), noisy_png(800, 800))]);
+        store.fail_delete_of = Some(url(1));
+
+        let report = RecompressJob::new(&store, &db, &cache, USER_ID, options(false))
+            .run()
+            .await
+            .unwrap();
+        // 参照は付け替え済みのため削減として数える
+        assert_eq!(report.items[0].status, RecompressStatus::Replaced);
+        assert!(report.bytes_saved > 0);
+        assert!(store.contains(&url(1)));
+
+        store.fail_delete_of = None;
+        let report = RecompressJob::new(&store, &db, &cache, USER_ID, options(false))
+            .run()
+            .await
+            .unwrap();
+        assert_eq!(report.items[0].status, RecompressStatus::Completed);
+        assert!(!store.contains(&url(1)));
+    }
+}
@@ -0,0 +1,270 @@
+//! 領収書URLの解析と生成
+//!
+//! 領収書URLには次の形式があります。
+//! - R2エンドポイント形式: `https://{account_id}.r2.cloudflarestorage.com/{bucket}/{file_key}`
+//! - バケット名をホストに含む形式: `https://{bucket}.{account_id}.r2.cloudflarestorage.com/{file_key}`
+//! - 公開URL（r2.devまたはカスタムドメイン）: `https://receipts.example.com/{file_key}`
+//!
+//! カスタムドメインにパスのプレフィックスがある場合は、
+//! 環境変数`R2_PUBLIC_BASE_URL`（例: `https://cdn.example.com/receipts`）で指定します。
+
+use crate::get_env_var_optional;
+
+/// R2エンドポイントのホスト名
+const R2_ENDPOINT_HOST: &str = "r2.cloudflarestorage.com";
+
+/// R2の開発用公開URLのホスト名サフィックス
+const R2_DEV_HOST_SUFFIX: &str = ".r2.dev";
+
+/// 領収書URLの設定
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReceiptUrlConfig {
+    /// 公開URLのベース（カスタムドメイン）。末尾のスラッシュは含まない
+    pub public_base_url: Option<String>,
+}
+
+impl ReceiptUrlConfig {
+    /// 公開URLのベースを指定して作成する
+    ///
+    /// ドメイン名のみ（例: `receipts.example.com`）が指定された場合は`https://`を補います。
+    pub fn with_public_base_url(public_base_url: impl Into<String>) -> Self {
+        let base = public_base_url.into();
+        let base = base.trim().trim_end_matches('/');
+        let public_base_url = match base {
+            "" => None,
+            base if base.starts_with("https://") => Some(base.to_string()),
+            base => Some(format!("https://{base}")),
+        };
+        Self { public_base_url }
+    }
+
+    /// 環境変数`R2_PUBLIC_BASE_URL`から設定を読み込む
+    pub fn from_env() -> Self {
+        match get_env_var_optional!("R2_PUBLIC_BASE_URL") {
+            Some(base) => Self::with_public_base_url(base),
+            None => Self::default(),
+        }
+    }
+}
+
+/// 領収書URLの形式
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReceiptUrlShape {
+    /// R2エンドポイント形式（パスの先頭がバケット名）
+    R2Endpoint { bucket: String },
+    /// バケット名をホストに含むR2エンドポイント形式
+    R2VirtualHost { bucket: String },
+    /// 公開URL（r2.devまたはカスタムドメイン）
+    Public,
+}
+
+/// 解析済みの領収書URL
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedReceiptUrl {
+    pub shape: ReceiptUrlShape,
+    pub file_key: String,
+}
+
+/// 領収書URLを解析し、形式とファイルキーを取得する
+///
+/// # 引数
+/// * `url` - 領収書URL
+/// * `config` - 領収書URLの設定
+///
+/// # 戻り値
+/// 解析結果、または形式が不正な場合はエラーメッセージ
+pub fn parse_receipt_url(url: &str, config: &ReceiptUrlConfig) -> Result<ParsedReceiptUrl, String> {
+    // 設定されたカスタムドメイン配下のURLは、ベース以降をそのままファイルキーとする
+    if let Some(base) = &config.public_base_url {
+        if let Some(rest) = url.strip_prefix(base.as_str()) {
+            if let Some(file_key) = rest.strip_prefix('/') {
+                return with_file_key(ReceiptUrlShape::Public, file_key);
+            }
+        }
+    }
+
+    let rest = url
+        .strip_prefix("https://")
+        .ok_or_else(|| "URLの形式が正しくありません".to_string())?;
+    let (host, path) = rest
+        .split_once('/')
+        .ok_or_else(|| "URLの形式が正しくありません".to_string())?;
+    // クエリ文字列・フラグメントはファイルキーに含めない
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+
+    if host.is_empty() || !host.contains('.') {
+        return Err("URLの形式が正しくありません".to_string());
+    }
+
+    if host == R2_ENDPOINT_HOST || host.ends_with(&format!(".{R2_ENDPOINT_HOST}")) {
+        let subdomain = host
+            .strip_suffix(R2_ENDPOINT_HOST)
+            .unwrap_or_default()
+            .trim_end_matches('.');
+
+        // {bucket}.{account_id}.r2.cloudflarestorage.com の場合はパス全体がファイルキー
+        if let Some((bucket, _account_id)) = subdomain.split_once('.') {
+            let shape = ReceiptUrlShape::R2VirtualHost {
+                bucket: bucket.to_string(),
+            };
+            return with_file_key(shape, path);
+        }
+
+        // パスの先頭がバケット名
+        let (bucket, file_key) = path
+            .split_once('/')
+            .ok_or_else(|| "ファイルキーの抽出に失敗しました".to_string())?;
+        let shape = ReceiptUrlShape::R2Endpoint {
+            bucket: bucket.to_string(),
+        };
+        return with_file_key(shape, file_key);
+    }
+
+    // r2.devやカスタムドメインはバケットのルートを公開しているため、パス全体がファイルキー
+    if host.ends_with(R2_DEV_HOST_SUFFIX) || config.public_base_url.is_none() {
+        return with_file_key(ReceiptUrlShape::Public, path);
+    }
+
+    // カスタムドメインが設定されている場合、別のホストのURLは認識できない
+    Err(format!("認識できない領収書URLのホストです: {host}"))
+}
+
+fn with_file_key(shape: ReceiptUrlShape, file_key: &str) -> Result<ParsedReceiptUrl, String> {
+    if file_key.is_empty() || file_key.ends_with('/') {
+        return Err("ファイルキーの抽出に失敗しました".to_string());
+    }
+    Ok(ParsedReceiptUrl {
+        shape,
+        file_key: file_key.to_string(),
+    })
+}
+
+/// ファイルキーから公開URLを生成する
+///
+/// # 戻り値
+/// 公開URLのベースが設定されている場合はそのURL、未設定の場合はNone
+pub fn build_public_url(file_key: &str, config: &ReceiptUrlConfig) -> Option<String> {
+    config
+        .public_base_url
+        .as_ref()
+        .map(|base| format!("{base}/{}", file_key.trim_start_matches('/')))
+}
+
+/// 領収書URLを公開URLのベースに付け替えた場合の新しいURLを取得する
+///
+/// # 戻り値
+/// 付け替えが必要な場合は新しいURL。既に公開URLの場合、
+/// 公開URLのベースが未設定の場合、または解析できない場合はNone
+pub fn rebase_receipt_url(url: &str, config: &ReceiptUrlConfig) -> Option<String> {
+    let parsed = parse_receipt_url(url, config).ok()?;
+    if parsed.shape == ReceiptUrlShape::Public {
+        return None;
+    }
+    build_public_url(&parsed.file_key, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENDPOINT_URL: &str = "https://d6392b1230a419b37b30f45fc13de9cf.r2.cloudflarestorage.com/orano-keihi-dev/users/2/receipts/6/test.png";
+    const CUSTOM_URL: &str = "https://receipts.example.com/users/2/receipts/6/test.png";
+
+    fn custom_domain() -> ReceiptUrlConfig {
+        ReceiptUrlConfig::with_public_base_url("https://receipts.example.com/")
+    }
+
+    #[test]
+    fn test_parse_r2_endpoint_urls() {
+        for config in [ReceiptUrlConfig::default(), custom_domain()] {
+            let parsed = parse_receipt_url(ENDPOINT_URL, &config).unwrap();
+            assert_eq!(
+                parsed.shape,
+                ReceiptUrlShape::R2Endpoint {
+                    bucket: "orano-keihi-dev".to_string()
+                }
+            );
+            assert_eq!(parsed.file_key, "users/2/receipts/6/test.png");
+
+            let parsed = parse_receipt_url(
+                "https://orano-keihi-dev.account.r2.cloudflarestorage.com/users/2/receipts/6/test.png",
+                &config,
+            )
+            .unwrap();
+            assert_eq!(
+                parsed.shape,
+                ReceiptUrlShape::R2VirtualHost {
+                    bucket: "orano-keihi-dev".to_string()
+                }
+            );
+            assert_eq!(parsed.file_key, "users/2/receipts/6/test.png");
+        }
+    }
+
+    #[test]
+    fn test_parse_public_urls() {
+        // カスタムドメイン（設定あり・なしのどちらでもバケット名を取り除かない）
+        for config in [ReceiptUrlConfig::default(), custom_domain()] {
+            let parsed = parse_receipt_url(CUSTOM_URL, &config).unwrap();
+            assert_eq!(parsed.shape, ReceiptUrlShape::Public);
+            assert_eq!(parsed.file_key, "users/2/receipts/6/test.png");
+        }
+
+        // r2.devの公開URL
+        let parsed = parse_receipt_url(
+            "https://pub-1234.r2.dev/users/2/receipts/6/test.png?v=1",
+            &custom_domain(),
+        )
+        .unwrap();
+        assert_eq!(parsed.file_key, "users/2/receipts/6/test.png");
+
+        // パスのプレフィックスを持つカスタムドメイン
+        let config = ReceiptUrlConfig::with_public_base_url("https://cdn.example.com/receipts");
+        let parsed = parse_receipt_url(
+            "https://cdn.example.com/receipts/users/2/receipts/6/test.png",
+            &config,
+        )
+        .unwrap();
+        assert_eq!(parsed.file_key, "users/2/receipts/6/test.png");
+    }
+
+    #[test]
+    fn test_parse_invalid_urls() {
+        let config = ReceiptUrlConfig::default();
+        assert!(parse_receipt_url("http://receipts.example.com/a.png", &config).is_err());
+        assert!(parse_receipt_url("https://receipts.example.com", &config).is_err());
+        assert!(parse_receipt_url("https://receipts.example.com/", &config).is_err());
+        assert!(parse_receipt_url("https://localhost/a.png", &config).is_err());
+        assert!(
+            parse_receipt_url("https://account.r2.cloudflarestorage.com/bucket", &config).is_err()
+        );
+
+        // カスタムドメイン設定時は、別ホストの公開URLを受け付けない
+        assert!(parse_receipt_url("https://other.example.com/a.png", &custom_domain()).is_err());
+    }
+
+    #[test]
+    fn test_rebase_receipt_url() {
+        let config = custom_domain();
+        assert_eq!(
+            rebase_receipt_url(ENDPOINT_URL, &config).as_deref(),
+            Some(CUSTOM_URL)
+        );
+        // 既にカスタムドメインのURLは対象外
+        assert_eq!(rebase_receipt_url(CUSTOM_URL, &config), None);
+        // カスタムドメイン未設定の場合は付け替えない
+        assert_eq!(
+            rebase_receipt_url(ENDPOINT_URL, &ReceiptUrlConfig::default()),
+            None
+        );
+
+        assert_eq!(
+            build_public_url("/users/1/receipts/1/a.png", &config).as_deref(),
+            Some("https://receipts.example.com/users/1/receipts/1/a.png")
+        );
+        assert_eq!(
+            ReceiptUrlConfig::with_public_base_url("  ").public_base_url,
+            None
+        );
+    }
+}
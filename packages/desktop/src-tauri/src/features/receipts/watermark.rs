@@ -0,0 +1,858 @@
+/// 領収書の透かし処理
+///
+/// 取引先などへ送付する領収書の控えに「提出済み」の透かしを入れます。
+/// 保存済みの原本は変更せず、透かし入りの複製のみを作成します。
+/// - 画像（PNG / JPEG）: 半透明の文字を描画
+/// - PDF: 各ページに半透明の文字を重ねる
+///
+/// 領収書の注釈（ハイライト）を指定した場合は、該当ページに半透明の矩形も描画します。
+use super::models::ReceiptAnnotation;
+use crate::shared::errors::{AppError, AppResult};
+use ab_glyph::{Font, FontVec, PxScale, ScaleFont};
+use chrono::Utc;
+use chrono_tz::Asia::Tokyo;
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId, Stream};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use std::path::PathBuf;
+
+/// 透かしの既定の文言
+const DEFAULT_LABEL: &str = "提出済み";
+
+/// 透かしに添えるアプリ名
+const APP_NAME: &str = "オラの経費";
+
+/// 透かしの色（赤）
+const WATERMARK_COLOR: [u8; 3] = [200, 0, 0];
+
+/// 注釈のハイライトの色（黄）
+const HIGHLIGHT_COLOR: [u8; 3] = [255, 214, 0];
+
+/// 注釈のハイライトの不透明度
+const HIGHLIGHT_OPACITY: f32 = 0.35;
+
+/// 同梱フォントのリソースパス
+pub const BUNDLED_FONT_PATH: &str = "fonts/NotoSansJP-Regular.otf";
+
+/// 日本語を含むOS標準フォントの候補
+const SYSTEM_FONT_CANDIDATES: &[&str] = &[
+    // macOS
+    "/System/Library/Fonts/ヒラギノ角ゴシック W3.ttc",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    // Windows
+    "C:\\Windows\\Fonts\\YuGothM.ttc",
+    "C:\\Windows\\Fonts\\meiryo.ttc",
+    "C:\\Windows\\Fonts\\msgothic.ttc",
+    // Linux
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/fonts-japanese-gothic.ttf",
+];
+
+/// 透かしの配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WatermarkPosition {
+    /// 左下から右上への対角線上
+    #[default]
+    Diagonal,
+    /// 中央に水平
+    Center,
+    /// 下部に水平
+    Bottom,
+}
+
+/// 透かしの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatermarkOptions {
+    /// 文言（例: 提出済み）
+    pub label: String,
+    /// 日付（YYYY-MM-DD形式、未指定時は今日）
+    pub date: Option<String>,
+    /// 不透明度（0.0〜1.0）
+    pub opacity: f32,
+    /// 配置
+    pub position: WatermarkPosition,
+}
+
+impl Default for WatermarkOptions {
+    fn default() -> Self {
+        Self {
+            label: DEFAULT_LABEL.to_string(),
+            date: None,
+            opacity: 0.3,
+            position: WatermarkPosition::default(),
+        }
+    }
+}
+
+impl WatermarkOptions {
+    /// 透かしの文字列を作成する（例: 提出済み 2024-07-15 オラの経費）
+    pub fn text(&self) -> String {
+        let date = self.date.clone().unwrap_or_else(|| {
+            Utc::now()
+                .with_timezone(&Tokyo)
+                .format("%Y-%m-%d")
+                .to_string()
+        });
+        format!("{} {date} {APP_NAME}", self.label.trim())
+    }
+
+    /// 設定値を検証する
+    pub fn validate(&self) -> AppResult<()> {
+        if self.label.trim().is_empty() {
+            return Err(AppError::validation("透かしの文言を入力してください"));
+        }
+        if !(0.0..=1.0).contains(&self.opacity) || self.opacity == 0.0 {
+            return Err(AppError::validation(
+                "透かしの不透明度は0より大きく1以下で指定してください",
+            ));
+        }
+        if let Some(date) = &self.date {
+            crate::shared::utils::validate_date(date)?;
+        }
+        Ok(())
+    }
+}
+
+/// 領収書ファイルの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReceiptFileFormat {
+    Png,
+    Jpeg,
+    Pdf,
+}
+
+impl ReceiptFileFormat {
+    /// ファイル先頭のバイト列から形式を判定する
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(Self::Png)
+        } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(Self::Jpeg)
+        } else if data.starts_with(b"%PDF-") {
+            Some(Self::Pdf)
+        } else {
+            None
+        }
+    }
+}
+
+/// 透かしに使うフォントを読み込む
+///
+/// 同梱フォント、OS標準の日本語フォントの順に探します。
+///
+/// # 引数
+/// * `resource_dir` - アプリのリソースディレクトリ
+///
+/// # 戻り値
+/// 読み込めたフォント（見つからない場合はNone）
+pub fn load_watermark_font(resource_dir: Option<PathBuf>) -> Option<FontVec> {
+    let candidates = resource_dir
+        .map(|dir| dir.join(BUNDLED_FONT_PATH))
+        .into_iter()
+        .chain(SYSTEM_FONT_CANDIDATES.iter().map(PathBuf::from));
+
+    for path in candidates {
+        let Ok(data) = std::fs::read(&path) else {
+            continue;
+        };
+        match FontVec::try_from_vec_and_index(data, 0) {
+            Ok(font) => {
+                log::debug!("透かし用フォントを読み込みました: {}", path.display());
+                return Some(font);
+            }
+            Err(e) => log::warn!("フォントの読み込みに失敗しました: {} - {e}", path.display()),
+        }
+    }
+
+    log::warn!("透かし用の日本語フォントが見つかりません。文字の代わりに帯を描画します");
+    None
+}
+
+/// 透かし入りの複製を作成する
+///
+/// # 引数
+/// * `data` - 原本のファイル内容（変更されません）
+/// * `options` - 透かしの設定
+/// * `font` - 画像に文字を描画するフォント（Noneの場合は帯のみ）
+///
+/// # 戻り値
+/// 透かし入りのファイル内容、または失敗時はエラー
+pub fn apply_watermark(
+    data: &[u8],
+    options: &WatermarkOptions,
+    font: Option<&FontVec>,
+) -> AppResult<Vec<u8>> {
+    apply_watermark_with_annotations(data, options, font, &[])
+}
+
+/// 注釈のハイライトと透かしを入れた複製を作成する
+///
+/// # 引数
+/// * `data` - 原本のファイル内容（変更されません）
+/// * `options` - 透かしの設定
+/// * `font` - 画像に文字を描画するフォント（Noneの場合は帯のみ）
+/// * `annotations` - 描画する注釈（画像は1ページ目の注釈のみ）
+///
+/// # 戻り値
+/// 加工後のファイル内容、または失敗時はエラー
+pub fn apply_watermark_with_annotations(
+    data: &[u8],
+    options: &WatermarkOptions,
+    font: Option<&FontVec>,
+    annotations: &[ReceiptAnnotation],
+) -> AppResult<Vec<u8>> {
+    options.validate()?;
+    for annotation in annotations {
+        annotation.rect.validate()?;
+    }
+
+    match ReceiptFileFormat::detect(data) {
+        Some(ReceiptFileFormat::Png) => {
+            watermark_image(data, ImageFormat::Png, options, font, annotations)
+        }
+        Some(ReceiptFileFormat::Jpeg) => {
+            watermark_image(data, ImageFormat::Jpeg, options, font, annotations)
+        }
+        Some(ReceiptFileFormat::Pdf) => watermark_pdf(data, options, annotations),
+        None => Err(AppError::validation(
+            "対応していないファイル形式です（PNG、JPEG、PDFのみ対応）",
+        )),
+    }
+}
+
+/// 文字の濃淡（0.0〜1.0）を保持するバッファ
+struct Coverage {
+    width: u32,
+    height: u32,
+    values: Vec<f32>,
+}
+
+impl Coverage {
+    fn get(&self, x: i64, y: i64) -> f32 {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return 0.0;
+        }
+        self.values[(y as u32 * self.width + x as u32) as usize]
+    }
+}
+
+/// 文字列をフォントで描画する
+fn render_text(font: &FontVec, text: &str, px: f32) -> Coverage {
+    let scaled = font.as_scaled(PxScale::from(px));
+    let width = text
+        .chars()
+        .map(|c| scaled.h_advance(scaled.glyph_id(c)))
+        .sum::<f32>()
+        .ceil()
+        .max(1.0) as u32;
+    let height = (scaled.ascent() - scaled.descent()).ceil().max(1.0) as u32;
+    let mut values = vec![0.0_f32; (width * height) as usize];
+
+    let mut caret = 0.0;
+    for c in text.chars() {
+        let glyph_id = scaled.glyph_id(c);
+        let glyph = glyph_id.with_scale_and_position(px, ab_glyph::point(caret, scaled.ascent()));
+        caret += scaled.h_advance(glyph_id);
+
+        if let Some(outlined) = font.outline_glyph(glyph) {
+            let bounds = outlined.px_bounds();
+            outlined.draw(|gx, gy, coverage| {
+                let x = bounds.min.x as i64 + gx as i64;
+                let y = bounds.min.y as i64 + gy as i64;
+                if x >= 0 && y >= 0 && (x as u32) < width && (y as u32) < height {
+                    let index = (y as u32 * width + x as u32) as usize;
+                    values[index] = values[index].max(coverage);
+                }
+            });
+        }
+    }
+
+    Coverage {
+        width,
+        height,
+        values,
+    }
+}
+
+/// フォントがない場合の斜線入りの帯を作成する
+fn render_band(text: &str, px: f32) -> Coverage {
+    let width = (text.chars().count() as f32 * px * 0.8).ceil().max(1.0) as u32;
+    let height = px.ceil().max(1.0) as u32;
+    let border = (px / 12.0).ceil().max(1.0) as u32;
+    let stripe = (px / 4.0).max(2.0) as u32;
+
+    let values = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let on_border = x < border || y < border || x >= width - border || y >= height - border;
+            let on_stripe = ((x + y) / stripe).is_multiple_of(2);
+            if on_border || on_stripe {
+                1.0
+            } else {
+                0.0
+            }
+        })
+        .collect();
+
+    Coverage {
+        width,
+        height,
+        values,
+    }
+}
+
+/// 透かしの角度・位置・大きさを決める
+///
+/// # 戻り値
+/// (角度（ラジアン）, 中心X, 中心Y, 文字列の長さの目標値)
+fn layout(width: f32, height: f32, position: WatermarkPosition) -> (f32, f32, f32, f32) {
+    match position {
+        // 画像座標はY軸が下向きのため、右上がりは負の角度
+        WatermarkPosition::Diagonal => (
+            -(height / width).atan(),
+            width / 2.0,
+            height / 2.0,
+            width.hypot(height) * 0.8,
+        ),
+        WatermarkPosition::Center => (0.0, width / 2.0, height / 2.0, width * 0.8),
+        WatermarkPosition::Bottom => (0.0, width / 2.0, height * 0.92, width * 0.6),
+    }
+}
+
+/// 画像に透かしを入れる
+fn watermark_image(
+    data: &[u8],
+    format: ImageFormat,
+    options: &WatermarkOptions,
+    font: Option<&FontVec>,
+    annotations: &[ReceiptAnnotation],
+) -> AppResult<Vec<u8>> {
+    let mut image = image::load_from_memory_with_format(data, format)
+        .map_err(|e| AppError::validation(format!("画像の読み込みに失敗しました: {e}")))?
+        .to_rgba8();
+
+    for annotation in annotations.iter().filter(|a| a.page_number == 1) {
+        draw_highlight(&mut image, annotation);
+    }
+    draw_watermark(&mut image, &options.text(), options, font);
+
+    let output = match format {
+        ImageFormat::Jpeg => DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(image).to_rgb8()),
+        _ => DynamicImage::ImageRgba8(image),
+    };
+    let mut buffer = Cursor::new(Vec::new());
+    output
+        .write_to(&mut buffer, format)
+        .map_err(|e| AppError::Io(std::io::Error::other(e)))?;
+    Ok(buffer.into_inner())
+}
+
+/// 画像に注釈の半透明の矩形を合成する
+fn draw_highlight(image: &mut RgbaImage, annotation: &ReceiptAnnotation) {
+    let (width, height) = (image.width() as f64, image.height() as f64);
+    let (left, top, rect_width, rect_height) = annotation.rect.to_pixels(width, height);
+
+    let min_x = left.floor().max(0.0) as u32;
+    let min_y = top.floor().max(0.0) as u32;
+    let max_x = ((left + rect_width).ceil() as u32).min(image.width());
+    let max_y = ((top + rect_height).ceil() as u32).min(image.height());
+
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let Rgba([r, g, b, a]) = *image.get_pixel(x, y);
+            let blend = |base: u8, color: u8| {
+                (base as f32 * (1.0 - HIGHLIGHT_OPACITY) + color as f32 * HIGHLIGHT_OPACITY).round()
+                    as u8
+            };
+            image.put_pixel(
+                x,
+                y,
+                Rgba([
+                    blend(r, HIGHLIGHT_COLOR[0]),
+                    blend(g, HIGHLIGHT_COLOR[1]),
+                    blend(b, HIGHLIGHT_COLOR[2]),
+                    a.max((HIGHLIGHT_OPACITY * 255.0).round() as u8),
+                ]),
+            );
+        }
+    }
+}
+
+/// 画像に半透明の文字（または帯）を合成する
+fn draw_watermark(
+    image: &mut RgbaImage,
+    text: &str,
+    options: &WatermarkOptions,
+    font: Option<&FontVec>,
+) {
+    let (width, height) = (image.width() as f32, image.height() as f32);
+    let (angle, center_x, center_y, target_length) = layout(width, height, options.position);
+
+    // 一度描画した幅から、目標の長さになる文字サイズを求める
+    const BASE_PX: f32 = 64.0;
+    let render = |px: f32| match font {
+        Some(font) => render_text(font, text, px),
+        None => render_band(text, px),
+    };
+    let base = render(BASE_PX);
+    let max_px = if options.position == WatermarkPosition::Bottom {
+        height * 0.1
+    } else {
+        height * 0.5
+    };
+    let px = (BASE_PX * target_length / base.width as f32)
+        .min(max_px)
+        .max(8.0);
+    let coverage = render(px);
+
+    let (sin, cos) = angle.sin_cos();
+    let half_w = coverage.width as f32 / 2.0;
+    let half_h = coverage.height as f32 / 2.0;
+    let reach = half_w.hypot(half_h).ceil();
+
+    let min_x = (center_x - reach).max(0.0) as u32;
+    let max_x = (center_x + reach).min(width - 1.0).max(0.0) as u32;
+    let min_y = (center_y - reach).max(0.0) as u32;
+    let max_y = (center_y + reach).min(height - 1.0).max(0.0) as u32;
+
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            // 逆回転して文字バッファ上の位置を求める
+            let dx = x as f32 + 0.5 - center_x;
+            let dy = y as f32 + 0.5 - center_y;
+            let u = dx * cos + dy * sin + half_w;
+            let v = -dx * sin + dy * cos + half_h;
+            let value = coverage.get(u.floor() as i64, v.floor() as i64);
+            if value <= 0.0 {
+                continue;
+            }
+
+            let alpha = options.opacity * value;
+            let Rgba([r, g, b, a]) = *image.get_pixel(x, y);
+            let blend = |base: u8, color: u8| {
+                (base as f32 * (1.0 - alpha) + color as f32 * alpha).round() as u8
+            };
+            image.put_pixel(
+                x,
+                y,
+                Rgba([
+                    blend(r, WATERMARK_COLOR[0]),
+                    blend(g, WATERMARK_COLOR[1]),
+                    blend(b, WATERMARK_COLOR[2]),
+                    a.max((alpha * 255.0).round() as u8),
+                ]),
+            );
+        }
+    }
+}
+
+/// ページ辞書の値を親ノードから継承して取得する
+fn inherited_attribute(doc: &Document, page_id: ObjectId, key: &[u8]) -> Option<Object> {
+    let mut node_id = page_id;
+    for _ in 0..32 {
+        let node = doc.get_dictionary(node_id).ok()?;
+        if let Ok(value) = node.get(key) {
+            return Some(value.clone());
+        }
+        node_id = node.get(b"Parent").and_then(Object::as_reference).ok()?;
+    }
+    None
+}
+
+/// 参照を解決した辞書を取得する
+fn resolve_dictionary(doc: &Document, object: &Object) -> Dictionary {
+    match object {
+        Object::Reference(id) => doc.get_dictionary(*id).cloned().unwrap_or_default(),
+        Object::Dictionary(dict) => dict.clone(),
+        _ => Dictionary::new(),
+    }
+}
+
+/// ページの大きさ（幅, 高さ）を取得する
+fn page_size(doc: &Document, page_id: ObjectId) -> (f32, f32) {
+    let number = |object: &Object| match object {
+        Object::Integer(value) => Some(*value as f32),
+        Object::Real(value) => Some(*value),
+        _ => None,
+    };
+    inherited_attribute(doc, page_id, b"MediaBox")
+        .and_then(|media_box| {
+            let values: Vec<f32> = media_box
+                .as_array()
+                .ok()?
+                .iter()
+                .filter_map(number)
+                .collect();
+            (values.len() == 4).then(|| (values[2] - values[0], values[3] - values[1]))
+        })
+        // A4
+        .unwrap_or((595.0, 842.0))
+}
+
+/// 透かし用の日本語フォント（埋め込みなしのCIDフォント）を作成する
+fn add_japanese_font(doc: &mut Document) -> ObjectId {
+    let descriptor = doc.add_object(dictionary! {
+        "Type" => "FontDescriptor",
+        "FontName" => "HeiseiKakuGo-W5",
+        "Flags" => 4,
+        "FontBBox" => vec![(-92).into(), (-250).into(), 1010.into(), 922.into()],
+        "ItalicAngle" => 0,
+        "Ascent" => 752,
+        "Descent" => -221,
+        "CapHeight" => 737,
+        "StemV" => 114,
+    });
+    let cid_font = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "CIDFontType0",
+        "BaseFont" => "HeiseiKakuGo-W5",
+        "CIDSystemInfo" => dictionary! {
+            "Registry" => Object::string_literal("Adobe"),
+            "Ordering" => Object::string_literal("Japan1"),
+            "Supplement" => 2,
+        },
+        "FontDescriptor" => descriptor,
+        "DW" => 1000,
+    });
+    doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type0",
+        "BaseFont" => "HeiseiKakuGo-W5-UniJIS-UCS2-H",
+        "Encoding" => "UniJIS-UCS2-H",
+        "DescendantFonts" => vec![cid_font.into()],
+    })
+}
+
+/// PDFの各ページに透かしを入れる
+fn watermark_pdf(
+    data: &[u8],
+    options: &WatermarkOptions,
+    annotations: &[ReceiptAnnotation],
+) -> AppResult<Vec<u8>> {
+    const FONT_NAME: &str = "FWatermark";
+    const STATE_NAME: &str = "GSWatermark";
+    const HIGHLIGHT_STATE_NAME: &str = "GSHighlight";
+
+    let mut doc = Document::load_mem(data)
+        .map_err(|e| AppError::validation(format!("PDFの読み込みに失敗しました: {e}")))?;
+
+    let font_id = add_japanese_font(&mut doc);
+    let state_id = doc.add_object(dictionary! {
+        "Type" => "ExtGState",
+        "ca" => options.opacity,
+        "CA" => options.opacity,
+    });
+    let highlight_state_id = doc.add_object(dictionary! {
+        "Type" => "ExtGState",
+        "ca" => HIGHLIGHT_OPACITY,
+    });
+
+    let text = options.text();
+    // UniJIS-UCS2-Hは2バイトのUCS-2で文字を指定する
+    let encoded: String = text
+        .encode_utf16()
+        .map(|unit| format!("{unit:04X}"))
+        .collect();
+    // 全角は1em、半角は0.5emとして幅を見積もる
+    let em_width: f32 = text
+        .chars()
+        .map(|c| if c.is_ascii() { 0.5 } else { 1.0 })
+        .sum();
+
+    let pages: Vec<(u32, ObjectId)> = doc.get_pages().into_iter().collect();
+    for (page_number, page_id) in pages {
+        let (width, height) = page_size(&doc, page_id);
+
+        // 注釈の矩形（PDFの座標はY軸が上向きのため上下を反転）
+        let highlights: String = annotations
+            .iter()
+            .filter(|annotation| annotation.page_number == page_number)
+            .map(|annotation| {
+                let (left, top, rect_width, rect_height) =
+                    annotation.rect.to_pixels(width as f64, height as f64);
+                let bottom = height as f64 - top - rect_height;
+                format!(
+                    "q /{HIGHLIGHT_STATE_NAME} gs {r} {g} {b} rg \
+                     {left:.2} {bottom:.2} {rect_width:.2} {rect_height:.2} re f Q\n",
+                    r = HIGHLIGHT_COLOR[0] as f32 / 255.0,
+                    g = HIGHLIGHT_COLOR[1] as f32 / 255.0,
+                    b = HIGHLIGHT_COLOR[2] as f32 / 255.0,
+                )
+            })
+            .collect();
+
+        // PDFの座標はY軸が上向きのため、右上がりは正の角度
+        let (angle, center_x, center_y, target_length) = layout(width, height, options.position);
+        let (angle, center_y) = (-angle, height - center_y);
+        let max_size = if options.position == WatermarkPosition::Bottom {
+            height * 0.05
+        } else {
+            height * 0.2
+        };
+        let size = (target_length / em_width).min(max_size).max(6.0);
+
+        let (sin, cos) = angle.sin_cos();
+        let half_w = em_width * size / 2.0;
+        let half_h = size * 0.35;
+        let x = center_x - half_w * cos + half_h * sin;
+        let y = center_y - half_w * sin - half_h * cos;
+
+        let content = format!(
+            "q /{STATE_NAME} gs {r} {g} {b} rg BT /{FONT_NAME} {size:.2} Tf \
+             {cos:.5} {sin:.5} {neg_sin:.5} {cos:.5} {x:.2} {y:.2} Tm <{encoded}> Tj ET Q\n",
+            r = WATERMARK_COLOR[0] as f32 / 255.0,
+            g = WATERMARK_COLOR[1] as f32 / 255.0,
+            b = WATERMARK_COLOR[2] as f32 / 255.0,
+            neg_sin = -sin,
+        );
+
+        // 継承されたリソースを含めてページ自身のリソースとして設定し直す
+        let mut resources = inherited_attribute(&doc, page_id, b"Resources")
+            .map(|object| resolve_dictionary(&doc, &object))
+            .unwrap_or_default();
+        for (category, name, id) in [
+            ("Font", FONT_NAME, font_id),
+            ("ExtGState", STATE_NAME, state_id),
+            ("ExtGState", HIGHLIGHT_STATE_NAME, highlight_state_id),
+        ] {
+            let mut entries = resources
+                .get(category.as_bytes())
+                .map(|object| resolve_dictionary(&doc, object))
+                .unwrap_or_default();
+            entries.set(name, id);
+            resources.set(category, entries);
+        }
+
+        // 既存の描画状態の影響を受けないよう、元の内容をq/Qで囲む
+        let wrapped_start = doc.add_object(Stream::new(Dictionary::new(), b"q\n".to_vec()));
+        let page = doc
+            .get_object_mut(page_id)
+            .and_then(Object::as_dict_mut)
+            .map_err(|e| AppError::validation(format!("PDFのページが不正です: {e}")))?;
+        page.set("Resources", resources);
+        let mut contents = match page.get(b"Contents") {
+            Ok(Object::Reference(id)) => vec![Object::Reference(*id)],
+            Ok(Object::Array(items)) => items.clone(),
+            _ => Vec::new(),
+        };
+        contents.insert(0, wrapped_start.into());
+        page.set("Contents", contents);
+
+        doc.add_page_contents(page_id, format!("Q\n{highlights}{content}").into_bytes())
+            .map_err(|e| AppError::validation(format!("PDFへの透かし追加に失敗しました: {e}")))?;
+    }
+
+    let mut buffer = Vec::new();
+    doc.save_to(&mut buffer)?;
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_options() -> WatermarkOptions {
+        WatermarkOptions {
+            date: Some("2024-07-15".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn sample_image(format: ImageFormat) -> Vec<u8> {
+        let image = RgbaImage::from_pixel(320, 240, Rgba([255, 255, 255, 255]));
+        let image = match format {
+            ImageFormat::Jpeg => DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(image).to_rgb8()),
+            _ => DynamicImage::ImageRgba8(image),
+        };
+        let mut buffer = Cursor::new(Vec::new());
+        image.write_to(&mut buffer, format).unwrap();
+        buffer.into_inner()
+    }
+
+    fn sample_pdf() -> Vec<u8> {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let content_id = doc.add_object(Stream::new(
+            Dictionary::new(),
+            b"0 0 1 rg 10 10 100 100 re f".to_vec(),
+        ));
+        let page_ids: Vec<Object> = (0..2)
+            .map(|_| {
+                doc.add_object(dictionary! {
+                    "Type" => "Page",
+                    "Parent" => pages_id,
+                    "Contents" => content_id,
+                })
+                .into()
+            })
+            .collect();
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => page_ids,
+                "Count" => 2,
+                "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+                "Resources" => Dictionary::new(),
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        let mut buffer = Vec::new();
+        doc.save_to(&mut buffer).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn test_watermark_text() {
+        assert_eq!(test_options().text(), "提出済み 2024-07-15 オラの経費");
+
+        let invalid = WatermarkOptions {
+            opacity: 1.5,
+            ..test_options()
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_watermark_png() {
+        let original = sample_image(ImageFormat::Png);
+        let output = apply_watermark(&original, &test_options(), None).unwrap();
+
+        assert_ne!(output, original);
+        let decoded = image::load_from_memory(&output).unwrap().to_rgba8();
+        assert_eq!(decoded.dimensions(), (320, 240));
+        // 中央付近が透かしの色に寄っている
+        assert!(decoded.pixels().any(|Rgba([r, g, _, _])| *r > *g));
+    }
+
+    #[test]
+    fn test_watermark_jpeg_positions() {
+        let original = sample_image(ImageFormat::Jpeg);
+        for position in [
+            WatermarkPosition::Diagonal,
+            WatermarkPosition::Center,
+            WatermarkPosition::Bottom,
+        ] {
+            let options = WatermarkOptions {
+                position,
+                ..test_options()
+            };
+            let output = apply_watermark(&original, &options, None).unwrap();
+            assert_ne!(output, original);
+            assert_eq!(
+                ReceiptFileFormat::detect(&output),
+                Some(ReceiptFileFormat::Jpeg)
+            );
+            assert!(image::load_from_memory(&output).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_watermark_with_font() {
+        // 環境にフォントがない場合は文字描画の確認を省略する
+        let Some(font) = load_watermark_font(None).or_else(|| {
+            std::fs::read("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf")
+                .ok()
+                .and_then(|data| FontVec::try_from_vec(data).ok())
+        }) else {
+            return;
+        };
+
+        let original = sample_image(ImageFormat::Png);
+        let options = WatermarkOptions {
+            label: "SUBMITTED".to_string(),
+            ..test_options()
+        };
+        let output = apply_watermark(&original, &options, Some(&font)).unwrap();
+        assert_ne!(output, original);
+        assert!(image::load_from_memory(&output).is_ok());
+    }
+
+    #[test]
+    fn test_watermark_pdf() {
+        let original = sample_pdf();
+        let output = apply_watermark(&original, &test_options(), None).unwrap();
+
+        assert_ne!(output, original);
+        let doc = Document::load_mem(&output).unwrap();
+        let pages = doc.get_pages();
+        assert_eq!(pages.len(), 2);
+        for page_id in pages.values() {
+            let content =
+                String::from_utf8_lossy(&doc.get_page_content(*page_id).unwrap()).into_owned();
+            // 元の描画内容が残り、透かしが追加されている
+            assert!(content.contains("re f"));
+            assert!(content.contains("/FWatermark"));
+        }
+    }
+
+    fn annotation(page_number: u32) -> ReceiptAnnotation {
+        ReceiptAnnotation {
+            id: 1,
+            expense_id: 1,
+            receipt_url: "https://receipts.example.com/a.png".to_string(),
+            page_number,
+            rect: crate::features::receipts::models::NormalizedRect {
+                x: 0.0,
+                y: 0.0,
+                width: 0.25,
+                height: 0.1,
+            },
+            note: None,
+            created_at: "2024-07-15T10:00:00+09:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_annotation_highlight_png() {
+        let original = sample_image(ImageFormat::Png);
+        let output =
+            apply_watermark_with_annotations(&original, &test_options(), None, &[annotation(1)])
+                .unwrap();
+        let decoded = image::load_from_memory(&output).unwrap().to_rgba8();
+
+        // 左上の注釈の範囲内は黄色に、範囲外（右下）は白のまま
+        let Rgba([r, g, b, _]) = *decoded.get_pixel(5, 5);
+        assert!(r > 250 && g > 230 && b < 200, "{r} {g} {b}");
+        assert_eq!(*decoded.get_pixel(319, 239), Rgba([255, 255, 255, 255]));
+
+        // 2ページ目の注釈は画像には描画しない
+        let without = apply_watermark(&original, &test_options(), None).unwrap();
+        let other_page =
+            apply_watermark_with_annotations(&original, &test_options(), None, &[annotation(2)])
+                .unwrap();
+        assert_eq!(other_page, without);
+    }
+
+    #[test]
+    fn test_annotation_highlight_pdf() {
+        let original = sample_pdf();
+        let output =
+            apply_watermark_with_annotations(&original, &test_options(), None, &[annotation(2)])
+                .unwrap();
+
+        let doc = Document::load_mem(&output).unwrap();
+        let pages = doc.get_pages();
+        let content = |number: u32| {
+            String::from_utf8_lossy(&doc.get_page_content(pages[&number]).unwrap()).into_owned()
+        };
+        assert!(!content(1).contains("/GSHighlight"));
+        // A4の左上（PDFの座標では上端）に描画される
+        assert!(content(2).contains("/GSHighlight gs"));
+        assert!(content(2).contains("0.00 757.80 148.75 84.20 re f"));
+    }
+
+    #[test]
+    fn test_unsupported_format() {
+        assert!(apply_watermark(b"GIF89a", &test_options(), None).is_err());
+    }
+}
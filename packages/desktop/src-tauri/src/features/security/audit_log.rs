@@ -0,0 +1,718 @@
+// セキュリティイベント（監査ログ）の永続化と検索
+
+use super::models::{EventSeverity, SecurityEvent};
+use crate::features::migrations::auto_migration::executor::MigrationExecutorTrait;
+use crate::features::migrations::auto_migration::models::{
+    ExecutableMigrationDefinition, MigrationDefinition,
+};
+use crate::features::migrations::auto_migration::registry::MigrationRegistry;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::export::escape_csv_field;
+use crate::shared::utils::validate_date;
+use chrono::{Duration, NaiveDate};
+use rusqlite::{params, params_from_iter, Connection, Row, ToSql};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+/// セキュリティイベントテーブルのスキーマ
+///
+/// キーセットページネーションのため、(timestamp, id) と
+/// (event_type, timestamp, id) の複合インデックスを作成します。
+const SECURITY_EVENTS_SCHEMA_SQL: &str = "
+    CREATE TABLE IF NOT EXISTS security_events (
+        id TEXT PRIMARY KEY,
+        event_type TEXT NOT NULL,
+        severity TEXT NOT NULL,
+        severity_level INTEGER NOT NULL,
+        details TEXT NOT NULL,
+        user_id TEXT,
+        timestamp TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_security_events_timestamp
+        ON security_events(timestamp, id);
+    CREATE INDEX IF NOT EXISTS idx_security_events_type_timestamp
+        ON security_events(event_type, timestamp, id);
+";
+
+/// 1ページあたりのデフォルト取得件数
+pub const DEFAULT_PAGE_SIZE: u32 = 100;
+
+/// 1ページあたりの最大取得件数
+pub const MAX_PAGE_SIZE: u32 = 1000;
+
+/// セキュリティイベントテーブル作成マイグレーション実行器
+pub struct SecurityEventsSchemaMigration;
+
+impl MigrationExecutorTrait for SecurityEventsSchemaMigration {
+    fn name(&self) -> &str {
+        "005_create_security_events"
+    }
+
+    fn execute(&self, conn: &Connection) -> Result<(), String> {
+        create_security_events_table(conn)
+            .map_err(|e| format!("security_eventsテーブル作成エラー: {e}"))
+    }
+}
+
+/// セキュリティイベントテーブル用マイグレーション定義を取得する
+///
+/// # 戻り値
+/// 実行可能なマイグレーション定義
+pub fn get_security_events_schema_definition() -> ExecutableMigrationDefinition {
+    let definition = MigrationDefinition::new(
+        "005_create_security_events".to_string(),
+        "3.1.0".to_string(),
+        "セキュリティイベント（監査ログ）テーブルの作成".to_string(),
+        MigrationRegistry::calculate_checksum(SECURITY_EVENTS_SCHEMA_SQL),
+    );
+
+    ExecutableMigrationDefinition::new(definition, Box::new(SecurityEventsSchemaMigration))
+}
+
+/// セキュリティイベントテーブルとインデックスを作成する
+///
+/// # 引数
+/// * `conn` - データベース接続
+pub fn create_security_events_table(conn: &Connection) -> AppResult<()> {
+    conn.execute_batch(SECURITY_EVENTS_SCHEMA_SQL)?;
+    Ok(())
+}
+
+/// ソート方向
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    /// 古い順
+    Asc,
+    /// 新しい順
+    #[default]
+    Desc,
+}
+
+/// キーセットページネーション用カーソル
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SecurityEventCursor {
+    /// 最後に取得したイベントの発生時刻
+    pub timestamp: String,
+    /// 最後に取得したイベントのID
+    pub id: String,
+}
+
+/// セキュリティイベント検索条件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SecurityEventQuery {
+    /// イベントタイプ（空の場合は全タイプ）
+    #[serde(default)]
+    pub event_types: Vec<String>,
+    /// 最小重要度（この重要度以上を取得）
+    pub min_severity: Option<EventSeverity>,
+    /// 開始日（YYYY-MM-DD形式、この日を含む）
+    pub start_date: Option<String>,
+    /// 終了日（YYYY-MM-DD形式、この日を含む）
+    pub end_date: Option<String>,
+    /// 詳細に対する部分一致検索
+    pub search: Option<String>,
+    /// ユーザーID
+    pub user_id: Option<String>,
+    /// ソート方向
+    #[serde(default)]
+    pub sort: SortDirection,
+    /// 前ページの最後の位置
+    pub cursor: Option<SecurityEventCursor>,
+    /// 取得件数（デフォルト100、最大1000）
+    pub limit: Option<u32>,
+}
+
+/// セキュリティイベント検索結果のページ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityEventPage {
+    /// 取得したイベント
+    pub events: Vec<SecurityEvent>,
+    /// 次ページ取得用カーソル（最終ページの場合はNone）
+    pub next_cursor: Option<SecurityEventCursor>,
+}
+
+/// イベントタイプ別の件数
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SecurityEventTypeCount {
+    /// イベントタイプ
+    pub event_type: String,
+    /// 件数
+    pub count: i64,
+}
+
+/// セキュリティイベントを保存する
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `event` - 保存するイベント
+pub fn insert_security_event(conn: &Connection, event: &SecurityEvent) -> AppResult<()> {
+    conn.execute(
+        "INSERT INTO security_events (id, event_type, severity, severity_level, details, user_id, timestamp)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            event.id,
+            event.event_type,
+            event.severity.as_str(),
+            event.severity.level(),
+            event.details,
+            event.user_context,
+            event.timestamp,
+        ],
+    )?;
+    Ok(())
+}
+
+/// 行データをセキュリティイベントに変換する
+fn map_security_event_row(row: &Row) -> rusqlite::Result<SecurityEvent> {
+    let severity: String = row.get(2)?;
+    Ok(SecurityEvent {
+        id: row.get(0)?,
+        event_type: row.get(1)?,
+        severity: EventSeverity::from_stored(&severity),
+        details: row.get(3)?,
+        user_context: row.get(4)?,
+        timestamp: row.get(5)?,
+    })
+}
+
+/// LIKE句のワイルドカードをエスケープする
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// 検索条件からWHERE句とパラメータを組み立てる
+fn build_filter_clause(
+    query: &SecurityEventQuery,
+    include_cursor: bool,
+) -> AppResult<(String, Vec<Box<dyn ToSql>>)> {
+    let mut conditions: Vec<String> = Vec::new();
+    let mut params: Vec<Box<dyn ToSql>> = Vec::new();
+
+    if !query.event_types.is_empty() {
+        let placeholders = vec!["?"; query.event_types.len()].join(", ");
+        conditions.push(format!("event_type IN ({placeholders})"));
+        for event_type in &query.event_types {
+            params.push(Box::new(event_type.clone()));
+        }
+    }
+
+    if let Some(min_severity) = &query.min_severity {
+        conditions.push("severity_level >= ?".to_string());
+        params.push(Box::new(min_severity.level()));
+    }
+
+    if let Some(start_date) = &query.start_date {
+        validate_date(start_date)?;
+        conditions.push("timestamp >= ?".to_string());
+        params.push(Box::new(start_date.clone()));
+    }
+
+    if let Some(end_date) = &query.end_date {
+        validate_date(end_date)?;
+        // 終了日を含めるため、翌日の0時より前を条件とする
+        let next_day = NaiveDate::parse_from_str(end_date, "%Y-%m-%d")
+            .map_err(|e| AppError::validation(format!("日付の解析に失敗しました: {e}")))?
+            + Duration::days(1);
+        conditions.push("timestamp < ?".to_string());
+        params.push(Box::new(next_day.format("%Y-%m-%d").to_string()));
+    }
+
+    if let (Some(start), Some(end)) = (&query.start_date, &query.end_date) {
+        if start > end {
+            return Err(AppError::validation(
+                "開始日は終了日以前の日付を指定してください",
+            ));
+        }
+    }
+
+    if let Some(search) = query.search.as_deref().filter(|s| !s.trim().is_empty()) {
+        conditions.push("details LIKE ? ESCAPE '\\'".to_string());
+        params.push(Box::new(format!("%{}%", escape_like(search.trim()))));
+    }
+
+    if let Some(user_id) = &query.user_id {
+        conditions.push("user_id = ?".to_string());
+        params.push(Box::new(user_id.clone()));
+    }
+
+    if include_cursor {
+        if let Some(cursor) = &query.cursor {
+            let operator = match query.sort {
+                SortDirection::Asc => ">",
+                SortDirection::Desc => "<",
+            };
+            conditions.push(format!("(timestamp, id) {operator} (?, ?)"));
+            params.push(Box::new(cursor.timestamp.clone()));
+            params.push(Box::new(cursor.id.clone()));
+        }
+    }
+
+    let clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    };
+
+    Ok((clause, params))
+}
+
+/// 検索用のSELECT文を組み立てる
+fn build_select_sql(query: &SecurityEventQuery, where_clause: &str) -> String {
+    let direction = match query.sort {
+        SortDirection::Asc => "ASC",
+        SortDirection::Desc => "DESC",
+    };
+    format!(
+        "SELECT id, event_type, severity, details, user_id, timestamp FROM security_events{where_clause} ORDER BY timestamp {direction}, id {direction}"
+    )
+}
+
+/// セキュリティイベントを検索する
+///
+/// OFFSETではなく (timestamp, id) のキーセットでページングするため、
+/// 件数が増えても後方のページの取得コストが変わりません。
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `query` - 検索条件
+///
+/// # 戻り値
+/// イベントのページと次ページ用カーソル
+pub fn query_security_events(
+    conn: &Connection,
+    query: &SecurityEventQuery,
+) -> AppResult<SecurityEventPage> {
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(AppError::validation(format!(
+            "取得件数は1〜{MAX_PAGE_SIZE}の範囲で指定してください"
+        )));
+    }
+
+    let (where_clause, mut params) = build_filter_clause(query, true)?;
+    let sql = format!("{} LIMIT ?", build_select_sql(query, &where_clause));
+    // 次ページの有無を判定するため1件多く取得する
+    params.push(Box::new(i64::from(limit) + 1));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let mut events = stmt
+        .query_map(params_from_iter(params.iter()), map_security_event_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let next_cursor = if events.len() > limit as usize {
+        events.truncate(limit as usize);
+        events.last().map(|event| SecurityEventCursor {
+            timestamp: event.timestamp.clone(),
+            id: event.id.clone(),
+        })
+    } else {
+        None
+    };
+
+    Ok(SecurityEventPage {
+        events,
+        next_cursor,
+    })
+}
+
+/// ユーザーのイベントタイプごとの件数を取得する
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+///
+/// # 戻り値
+/// イベントタイプと件数の一覧（件数の多い順）
+pub fn get_security_event_types(
+    conn: &Connection,
+    user_id: &str,
+) -> AppResult<Vec<SecurityEventTypeCount>> {
+    let mut stmt = conn.prepare(
+        "SELECT event_type, COUNT(*) FROM security_events WHERE user_id = ?1 GROUP BY event_type ORDER BY COUNT(*) DESC, event_type ASC",
+    )?;
+    let types = stmt
+        .query_map([user_id], |row| {
+            Ok(SecurityEventTypeCount {
+                event_type: row.get(0)?,
+                count: row.get(1)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(types)
+}
+
+/// イベントタイプ・重要度別の件数
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecurityEventCount {
+    /// イベントタイプ
+    pub event_type: String,
+    /// 重要度
+    pub severity: EventSeverity,
+    /// 件数
+    pub count: usize,
+}
+
+/// 期間内のイベント件数をイベントタイプ・重要度別に集計する
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `start_date` - 開始日（YYYY-MM-DD形式、この日を含む）
+/// * `end_date` - 終了日（YYYY-MM-DD形式、この日を含む）
+///
+/// # 戻り値
+/// イベントタイプ・重要度ごとの件数
+pub fn count_security_events(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+) -> AppResult<Vec<SecurityEventCount>> {
+    let query = SecurityEventQuery {
+        start_date: Some(start_date.to_string()),
+        end_date: Some(end_date.to_string()),
+        ..Default::default()
+    };
+    let (where_clause, params) = build_filter_clause(&query, false)?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT event_type, severity, COUNT(*) FROM security_events{where_clause} GROUP BY event_type, severity ORDER BY event_type, severity"
+    ))?;
+    let counts = stmt
+        .query_map(params_from_iter(params.iter()), |row| {
+            let severity: String = row.get(1)?;
+            Ok(SecurityEventCount {
+                event_type: row.get(0)?,
+                severity: EventSeverity::from_stored(&severity),
+                count: row.get::<_, i64>(2)? as usize,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(counts)
+}
+
+/// 検索条件に一致するセキュリティイベントをCSVとして書き出す
+///
+/// 結果全体をメモリに載せず、1行ずつ書き出します。
+/// カーソルと取得件数は無視し、条件に一致するすべてのイベントを出力します。
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `query` - 検索条件
+/// * `writer` - 書き込み先
+///
+/// # 戻り値
+/// 書き出したイベント数
+pub fn export_security_events_csv<W: Write + ?Sized>(
+    conn: &Connection,
+    query: &SecurityEventQuery,
+    writer: &mut W,
+) -> AppResult<usize> {
+    let (where_clause, params) = build_filter_clause(query, false)?;
+    let sql = build_select_sql(query, &where_clause);
+
+    writeln!(writer, "id,timestamp,event_type,severity,user_id,details")?;
+
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query(params_from_iter(params.iter()))?;
+    let mut count = 0;
+
+    while let Some(row) = rows.next()? {
+        let event = map_security_event_row(row)?;
+        writeln!(
+            writer,
+            "{},{},{},{},{},{}",
+            escape_csv_field(&event.id),
+            escape_csv_field(&event.timestamp),
+            escape_csv_field(&event.event_type),
+            event.severity.as_str(),
+            escape_csv_field(event.user_context.as_deref().unwrap_or("")),
+            escape_csv_field(&event.details),
+        )?;
+        count += 1;
+    }
+
+    writer.flush()?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const EVENT_TYPES: [&str; 4] = ["login", "logout", "token_invalid", "access_denied"];
+
+    fn create_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        create_security_events_table(&conn).unwrap();
+        conn
+    }
+
+    /// 同一時刻のイベントを含むテストデータを投入する
+    fn seed_events(conn: &mut Connection, count: usize) {
+        let tx = conn.transaction().unwrap();
+        {
+            let mut stmt = tx
+                .prepare(
+                    "INSERT INTO security_events (id, event_type, severity, severity_level, details, user_id, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                )
+                .unwrap();
+            let base = NaiveDate::from_ymd_opt(2024, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap();
+            for i in 0..count {
+                let severity = match i % 4 {
+                    0 => EventSeverity::Info,
+                    1 => EventSeverity::Warning,
+                    2 => EventSeverity::Error,
+                    _ => EventSeverity::Critical,
+                };
+                // 3件ずつ同じ時刻にしてタイブレークを検証する
+                let timestamp = (base + Duration::minutes((i / 3) as i64))
+                    .format("%Y-%m-%dT%H:%M:%S+09:00")
+                    .to_string();
+                stmt.execute(params![
+                    format!("evt-{i:06}"),
+                    EVENT_TYPES[i % EVENT_TYPES.len()],
+                    severity.as_str(),
+                    severity.level(),
+                    format!("event number {i}"),
+                    format!("user-{}", i % 10),
+                    timestamp,
+                ])
+                .unwrap();
+            }
+        }
+        tx.commit().unwrap();
+    }
+
+    fn collect_all_pages(conn: &Connection, mut query: SecurityEventQuery) -> Vec<SecurityEvent> {
+        let mut all = Vec::new();
+        loop {
+            let page = query_security_events(conn, &query).unwrap();
+            all.extend(page.events);
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        all
+    }
+
+    #[test]
+    fn test_keyset_pagination_has_no_duplicates_or_gaps() {
+        let mut conn = create_test_db();
+        seed_events(&mut conn, 100_000);
+
+        for sort in [SortDirection::Desc, SortDirection::Asc] {
+            let events = collect_all_pages(
+                &conn,
+                SecurityEventQuery {
+                    sort,
+                    limit: Some(MAX_PAGE_SIZE),
+                    ..Default::default()
+                },
+            );
+
+            assert_eq!(events.len(), 100_000);
+            let ids: HashSet<&str> = events.iter().map(|e| e.id.as_str()).collect();
+            assert_eq!(ids.len(), 100_000);
+
+            // 並び順が途切れずに単調であること
+            for pair in events.windows(2) {
+                let a = (&pair[0].timestamp, &pair[0].id);
+                let b = (&pair[1].timestamp, &pair[1].id);
+                match sort {
+                    SortDirection::Asc => assert!(a < b),
+                    SortDirection::Desc => assert!(a > b),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_filtered_pagination() {
+        let mut conn = create_test_db();
+        seed_events(&mut conn, 10_000);
+
+        let events = collect_all_pages(
+            &conn,
+            SecurityEventQuery {
+                event_types: vec!["login".to_string(), "logout".to_string()],
+                min_severity: Some(EventSeverity::Warning),
+                user_id: Some("user-1".to_string()),
+                limit: Some(37),
+                ..Default::default()
+            },
+        );
+
+        let expected: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM security_events
+                 WHERE event_type IN ('login', 'logout') AND severity_level >= 1 AND user_id = 'user-1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(expected > 0);
+        assert_eq!(events.len() as i64, expected);
+        assert!(events
+            .iter()
+            .all(|e| e.severity.level() >= 1 && e.user_context.as_deref() == Some("user-1")));
+    }
+
+    #[test]
+    fn test_date_range_and_search() {
+        let mut conn = create_test_db();
+        seed_events(&mut conn, 10_000);
+
+        // 10,000件 / 3件毎分 ≒ 2.3日分
+        let page = query_security_events(
+            &conn,
+            &SecurityEventQuery {
+                start_date: Some("2024-01-02".to_string()),
+                end_date: Some("2024-01-02".to_string()),
+                limit: Some(MAX_PAGE_SIZE),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(page
+            .events
+            .iter()
+            .all(|e| e.timestamp.starts_with("2024-01-02")));
+
+        let page = query_security_events(
+            &conn,
+            &SecurityEventQuery {
+                search: Some("number 4242".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(page.events.len(), 1);
+        assert_eq!(page.events[0].id, "evt-004242");
+
+        // ワイルドカードはリテラルとして扱う
+        let page = query_security_events(
+            &conn,
+            &SecurityEventQuery {
+                search: Some("%".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(page.events.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_query() {
+        let conn = create_test_db();
+
+        let zero_limit = SecurityEventQuery {
+            limit: Some(0),
+            ..Default::default()
+        };
+        assert!(query_security_events(&conn, &zero_limit).is_err());
+
+        let reversed = SecurityEventQuery {
+            start_date: Some("2024-02-01".to_string()),
+            end_date: Some("2024-01-01".to_string()),
+            ..Default::default()
+        };
+        assert!(query_security_events(&conn, &reversed).is_err());
+    }
+
+    #[test]
+    fn test_get_security_event_types() {
+        let mut conn = create_test_db();
+        seed_events(&mut conn, 40);
+
+        // user-0 のイベントは login と token_invalid が2件ずつ
+        let types = get_security_event_types(&conn, "user-0").unwrap();
+        assert_eq!(types.len(), 2);
+        assert_eq!(types.iter().map(|t| t.count).sum::<i64>(), 4);
+        assert_eq!(types[0].event_type, "login");
+        assert_eq!(types[0].count, 2);
+
+        assert!(get_security_event_types(&conn, "user-x")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_query_plans_use_indexes() {
+        let conn = create_test_db();
+
+        let plan_for = |query: &SecurityEventQuery| -> String {
+            let (where_clause, params) = build_filter_clause(query, true).unwrap();
+            let sql = format!(
+                "EXPLAIN QUERY PLAN {} LIMIT 101",
+                build_select_sql(query, &where_clause)
+            );
+            let mut stmt = conn.prepare(&sql).unwrap();
+            stmt.query_map(params_from_iter(params.iter()), |row| {
+                row.get::<_, String>(3)
+            })
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+            .join("\n")
+        };
+
+        let cursor = Some(SecurityEventCursor {
+            timestamp: "2024-01-01T00:00:00+09:00".to_string(),
+            id: "evt-000001".to_string(),
+        });
+
+        let by_time = plan_for(&SecurityEventQuery {
+            cursor: cursor.clone(),
+            ..Default::default()
+        });
+        assert!(
+            by_time.contains("idx_security_events_timestamp"),
+            "{by_time}"
+        );
+
+        let by_type = plan_for(&SecurityEventQuery {
+            event_types: vec!["login".to_string()],
+            cursor,
+            ..Default::default()
+        });
+        assert!(
+            by_type.contains("idx_security_events_type_timestamp"),
+            "{by_type}"
+        );
+    }
+
+    #[test]
+    fn test_export_security_events_csv() {
+        let conn = create_test_db();
+        let mut event = SecurityEvent::new(
+            "login".to_string(),
+            "detail with, comma and \"quote\"".to_string(),
+            EventSeverity::Warning,
+            Some("user-1".to_string()),
+        );
+        event.timestamp = "2024-01-01T00:00:00+09:00".to_string();
+        insert_security_event(&conn, &event).unwrap();
+
+        let mut output = Vec::new();
+        let count =
+            export_security_events_csv(&conn, &SecurityEventQuery::default(), &mut output).unwrap();
+        assert_eq!(count, 1);
+
+        let csv = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "id,timestamp,event_type,severity,user_id,details");
+        assert!(lines[1].ends_with("\"detail with, comma and \"\"quote\"\"\""));
+        assert!(lines[1].contains(",Warning,user-1,"));
+    }
+}
@@ -1,7 +1,26 @@
+use crate::features::auth::middleware::AuthMiddleware;
+use crate::features::auth::service::AuthService;
+use crate::features::auth::storage_backend::secure_storage_status;
+use crate::features::expenses::location::is_location_storage_enabled;
+use crate::features::receipts::connectivity::is_storage_available;
+use crate::features::security::audit_log::{
+    self, SecurityEventPage, SecurityEventQuery, SecurityEventTypeCount,
+};
+use crate::features::security::models::{AppHealth, EventSeverity, SecurityEvent};
+use crate::features::security::query_console::{self, QueryResult};
 use crate::features::security::service::SecurityService;
+use crate::features::security::smoke_test::{self, ReceiptStorage, SmokeTestReport};
+use crate::shared::capabilities::{available_capabilities, Capability, CapabilityContext};
+use crate::shared::database::connection::get_database_path;
+use crate::shared::rate_limit::shared_cooldown;
+use crate::shared::utils::disk_space::{
+    available_space, check_disk_space, EXPORT_HEADROOM_BYTES, LOW_DISK_SPACE_THRESHOLD_BYTES,
+};
+use crate::shared::utils::get_current_jst_timestamp;
+use crate::AppState;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 
 /// トークン暗号化リクエスト
 #[derive(Debug, Serialize, Deserialize)]
@@ -370,12 +389,207 @@ pub async fn get_system_diagnostic_info() -> Result<HashMap<String, serde_json::
     );
     info.insert(
         "timestamp".to_string(),
-        serde_json::Value::String(chrono::Utc::now().to_rfc3339()),
+        serde_json::Value::String(get_current_jst_timestamp()),
     );
 
     Ok(info)
 }
 
+/// アプリの状態（アプリデータのボリュームの空き容量、セキュアストレージの警告、レート制限の状態など）を取得する
+#[tauri::command]
+pub async fn get_app_health(app: AppHandle) -> Result<AppHealth, String> {
+    log::debug!("アプリ状態取得コマンドを実行");
+
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("アプリデータディレクトリの取得に失敗しました: {e}"))?;
+    let available_disk_bytes = match available_space(&app_data_dir) {
+        Ok(available) => Some(available),
+        Err(e) => {
+            log::warn!("空き容量の取得に失敗しました: {e}");
+            None
+        }
+    };
+
+    Ok(AppHealth {
+        app_data_dir: app_data_dir.to_string_lossy().into_owned(),
+        available_disk_bytes,
+        low_disk_space: available_disk_bytes
+            .is_some_and(|available| available < LOW_DISK_SPACE_THRESHOLD_BYTES),
+        secure_storage_warning: secure_storage_status().warning,
+        rate_limit: shared_cooldown().status(),
+        checked_at: get_current_jst_timestamp(),
+    })
+}
+
+/// コマンドパレットに表示する、現在利用できる機能の一覧を取得する
+///
+/// 各機能モジュールが登録した機能を、ログイン状態・ストレージの接続状態・
+/// 設定・機能フラグ・ビルドの種類で絞り込んで返します。
+///
+/// # 引数
+/// * `session_token` - セッショントークン（未ログインの場合はNone）
+#[tauri::command]
+pub async fn get_available_capabilities(
+    session_token: Option<String>,
+    auth_service: State<'_, AuthService>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Capability>, String> {
+    log::debug!("利用可能な機能の取得コマンドを実行");
+
+    let user = match session_token.filter(|token| !token.is_empty()) {
+        Some(token) => auth_service.validate_session(token).await.ok(),
+        None => None,
+    };
+
+    let location_storage_enabled = match &user {
+        Some(user) => state
+            .try_db(|db| is_location_storage_enabled(db, &user.id))
+            .unwrap_or(false),
+        None => false,
+    };
+
+    let context = CapabilityContext {
+        authenticated: user.is_some(),
+        storage_online: is_storage_available(),
+        api_server_configured: crate::get_env_var_optional!("API_SERVER_URL")
+            .is_some_and(|url| !url.is_empty()),
+        location_storage_enabled,
+        debug_build: cfg!(debug_assertions),
+    };
+
+    Ok(available_capabilities(
+        crate::features::capability_registry(),
+        &context,
+    ))
+}
+
+/// 読み取り専用のSQLクエリを実行する（上級者向け）
+///
+/// # 引数
+/// * `sql` - 単一のSELECT文
+/// * `params` - `?`に割り当てるパラメーター
+/// * `row_limit` - 取得件数の上限（省略時は500件、最大5000件）
+/// * `acknowledged` - 初回利用時の確認に同意した場合はtrue
+/// * `session_token` - セッショントークン
+/// * `app` - Tauriアプリハンドル
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 列名と行、または失敗時はエラーメッセージ
+///
+/// 機能フラグ`ENABLE_QUERY_CONSOLE`が有効な場合のみ実行できます。
+/// 初回利用時は`QUERY_CONSOLE_CONFIRMATION_REQUIRED`を返すため、確認後に`acknowledged`を指定して再実行します。
+/// 実行したSQLは結果にかかわらずセキュリティイベントに記録します。
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn run_readonly_query(
+    sql: String,
+    params: Option<Vec<serde_json::Value>>,
+    row_limit: Option<usize>,
+    acknowledged: Option<bool>,
+    session_token: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, AuthMiddleware>,
+) -> Result<QueryResult, String> {
+    if !query_console::is_query_console_enabled() {
+        return Err("クエリコンソールは有効になっていません".to_string());
+    }
+
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/security/query")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    let confirmed = state
+        .try_db(|db| query_console::is_acknowledged(db, &user.id))
+        .map_err(|e| format!("確認状況の取得に失敗しました: {e}"))?;
+    if !confirmed {
+        if acknowledged != Some(true) {
+            return Err(format!(
+                "{}: 初回利用時は確認が必要です",
+                query_console::CONFIRMATION_REQUIRED
+            ));
+        }
+        state
+            .try_db(|db| query_console::acknowledge(db, &user.id))
+            .map_err(|e| format!("確認状況の保存に失敗しました: {e}"))?;
+    }
+
+    let result = get_database_path(&app).and_then(|path| {
+        let conn = query_console::open_readonly_connection(&path)?;
+        query_console::run_readonly_query(
+            &conn,
+            &sql,
+            &params.unwrap_or_default(),
+            &user.id,
+            row_limit.unwrap_or(query_console::DEFAULT_ROW_LIMIT),
+            query_console::STATEMENT_TIMEOUT,
+        )
+    });
+
+    // 結果にかかわらず実行したSQLを記録する
+    let (details, severity) = match &result {
+        Ok(result) => (
+            format!("sql={sql}, rows={}", result.rows.len()),
+            EventSeverity::Info,
+        ),
+        Err(e) => (format!("sql={sql}, error={e}"), EventSeverity::Warning),
+    };
+    let event = SecurityEvent::new(
+        "readonly_query".to_string(),
+        details,
+        severity,
+        Some(user.id.clone()),
+    );
+    if let Err(e) = state.try_db(|db| audit_log::insert_security_event(db, &event)) {
+        log::warn!("クエリ実行の記録に失敗しました: {e}");
+    }
+
+    result.map_err(|e| format!("クエリの実行に失敗しました: {e}"))
+}
+
+/// リリース前のスモークテストを実行する
+///
+/// 一時データベースと生成した領収書で主要な操作を順に実行し、手順ごとの結果を返します。
+/// ログイン中はAPIサーバー経由で領収書をアップロードし、未ログインの場合はモックを使用します。
+/// 作成したデータは失敗時も含めて削除します。
+///
+/// # 引数
+/// * `session_token` - セッショントークン（未ログインの場合はNone）
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// スモークテストのレポート、または失敗時はエラーメッセージ
+///
+/// 機能フラグ`ENABLE_RELEASE_SMOKE_TEST`が有効な場合のみ実行できます。
+#[tauri::command]
+pub async fn run_release_smoke_test(
+    session_token: Option<String>,
+    auth_middleware: State<'_, AuthMiddleware>,
+) -> Result<SmokeTestReport, String> {
+    if !smoke_test::is_smoke_test_enabled() {
+        return Err("リリース前スモークテストは有効になっていません".to_string());
+    }
+
+    let storage = match session_token {
+        Some(token) => {
+            let user = auth_middleware
+                .authenticate_request(Some(&token), "/security/smoke-test")
+                .await
+                .map_err(|e| format!("認証エラー: {e}"))?;
+            ReceiptStorage::remote(user.id, token)
+                .map_err(|e| format!("APIクライアントの初期化に失敗しました: {e}"))?
+        }
+        None => ReceiptStorage::mock(),
+    };
+
+    Ok(smoke_test::run(storage).await)
+}
+
 /// セキュリティ設定を検証する
 #[tauri::command]
 pub async fn validate_security_configuration() -> Result<bool, String> {
@@ -405,15 +619,138 @@ pub async fn get_environment_info() -> Result<HashMap<String, String>, String> {
 }
 
 /// セキュリティイベントをログに記録する
+///
+/// ログ出力に加えてsecurity_eventsテーブルに保存します。
+/// 保存に失敗した場合も呼び出し元の処理は妨げません。
 #[tauri::command]
-pub async fn log_security_event(event: String, details: Option<String>) -> Result<(), String> {
+pub async fn log_security_event(
+    event: String,
+    details: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
     log::info!("セキュリティイベント: {event}");
-    if let Some(details) = details {
+    if let Some(details) = &details {
         log::info!("詳細: {details}");
     }
+
+    let security_event = SecurityEvent::new(
+        event,
+        details.unwrap_or_default(),
+        EventSeverity::Info,
+        None,
+    );
+    if let Err(e) = state.try_db(|db| audit_log::insert_security_event(db, &security_event)) {
+        log::warn!("セキュリティイベントの保存に失敗しました: {e}");
+    }
+
     Ok(())
 }
 
+/// セキュリティイベントを検索する
+///
+/// 検索対象はログイン中のユーザーのイベントに限られます（検索条件のユーザーIDは無視されます）。
+///
+/// # 引数
+/// * `query` - 検索条件（フィルター、カーソル、ソート方向、取得件数）
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// イベントのページと次ページ用カーソル、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn query_security_events(
+    query: SecurityEventQuery,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, AuthMiddleware>,
+) -> Result<SecurityEventPage, String> {
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/security/events")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    let query = SecurityEventQuery {
+        user_id: Some(user.id),
+        ..query
+    };
+    state
+        .try_db(|db| audit_log::query_security_events(db, &query))
+        .map_err(|e| format!("セキュリティイベントの検索に失敗しました: {e}"))
+}
+
+/// ログイン中のユーザーのセキュリティイベントのタイプ一覧と件数を取得する
+///
+/// # 引数
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// イベントタイプと件数の一覧、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn get_security_event_types(
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, AuthMiddleware>,
+) -> Result<Vec<SecurityEventTypeCount>, String> {
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/security/events/types")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    state
+        .try_db(|db| audit_log::get_security_event_types(db, &user.id))
+        .map_err(|e| format!("イベントタイプの取得に失敗しました: {e}"))
+}
+
+/// 検索条件に一致するセキュリティイベントをCSVファイルに書き出す
+///
+/// 書き出す対象はログイン中のユーザーのイベントに限られます（検索条件のユーザーIDは無視されます）。
+///
+/// # 引数
+/// * `query` - 検索条件（カーソルと取得件数は無視されます）
+/// * `file_path` - 出力先ファイルパス
+/// * `session_token` - セッショントークン
+/// * `state` - アプリケーション状態
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 書き出したイベント数、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn export_security_events_csv(
+    query: SecurityEventQuery,
+    file_path: String,
+    session_token: Option<String>,
+    state: State<'_, AppState>,
+    auth_middleware: State<'_, AuthMiddleware>,
+) -> Result<usize, String> {
+    let user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/security/events/export")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    // 出力サイズは書き出すまで分からないため、ヘッドルーム分の空き容量を確認する
+    check_disk_space(std::path::Path::new(&file_path), EXPORT_HEADROOM_BYTES)
+        .map_err(|e| e.to_string())?;
+    let file = std::fs::File::create(&file_path)
+        .map_err(|e| format!("出力ファイルの作成に失敗しました: {e}"))?;
+    let mut writer = std::io::BufWriter::new(file);
+
+    let meta = crate::shared::export::ExportMeta::current(Some(&user.id));
+    let query = SecurityEventQuery {
+        user_id: Some(user.id),
+        ..query
+    };
+    state
+        .try_db(|db| {
+            crate::shared::export::write_csv_export(&mut writer, &meta, |body| {
+                audit_log::export_security_events_csv(db, &query, body)
+            })
+        })
+        .map_err(|e| format!("セキュリティイベントのエクスポートに失敗しました: {e}"))
+}
+
 /// R2診断情報を取得する
 #[tauri::command]
 pub async fn get_r2_diagnostic_info() -> Result<HashMap<String, serde_json::Value>, String> {
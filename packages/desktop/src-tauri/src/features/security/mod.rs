@@ -1,11 +1,80 @@
 // セキュリティ機能モジュール
 
+pub mod audit_log;
 pub mod commands;
 pub mod encryption;
 pub mod models;
+pub mod query_console;
 pub mod service;
+pub mod smoke_test;
 
 // 公開インターフェース
 pub use commands::*;
 pub use models::*;
 pub use service::*;
+
+use crate::shared::capabilities::{Capability, Requirement};
+
+/// セキュリティ機能の利用者向け機能
+pub const CAPABILITIES: &[Capability] = &[
+    Capability::new(
+        "security.app_health",
+        "capability.security.app_health",
+        "get_app_health",
+    ),
+    Capability::new(
+        "security.readonly_query",
+        "capability.security.readonly_query",
+        "run_readonly_query",
+    )
+    .requires(&[Requirement::Authenticated])
+    .internal(),
+    Capability::new(
+        "security.release_smoke_test",
+        "capability.security.release_smoke_test",
+        "run_release_smoke_test",
+    )
+    .mutating()
+    .internal(),
+    Capability::new(
+        "security.export_events_csv",
+        "capability.security.export_events_csv",
+        "export_security_events_csv",
+    )
+    .requires(&[Requirement::Authenticated]),
+    Capability::new(
+        "security.log_event",
+        "capability.security.log_event",
+        "log_security_event",
+    )
+    .mutating()
+    .internal(),
+    Capability::new(
+        "security.store_token",
+        "capability.security.store_token",
+        "encrypt_and_store_token",
+    )
+    .mutating()
+    .internal(),
+    Capability::new(
+        "security.invalidate_token",
+        "capability.security.invalidate_token",
+        "invalidate_token",
+    )
+    .mutating()
+    .internal(),
+    Capability::new(
+        "security.invalidate_all_tokens",
+        "capability.security.invalidate_all_tokens",
+        "invalidate_all_tokens",
+    )
+    .destructive()
+    .internal(),
+    Capability::new(
+        "security.cleanup_expired_tokens",
+        "capability.security.cleanup_expired_tokens",
+        "cleanup_expired_tokens",
+    )
+    .mutating()
+    .internal(),
+];
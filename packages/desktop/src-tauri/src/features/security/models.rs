@@ -1,5 +1,6 @@
 // セキュリティ機能のデータモデル
 
+use crate::shared::rate_limit::RateLimitStatus;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -55,8 +56,10 @@ pub struct TokenInfo {
     /// 暗号化されたトークン
     pub encrypted_token: String,
     /// 作成日時
+    #[serde(with = "crate::shared::utils::jst_datetime")]
     pub created_at: DateTime<Utc>,
     /// 最終アクセス日時
+    #[serde(with = "crate::shared::utils::jst_datetime")]
     pub last_accessed: DateTime<Utc>,
     /// アクセス回数
     pub access_count: u64,
@@ -79,6 +82,23 @@ pub struct DiagnosticInfo {
     pub validation_status: ValidationStatus,
 }
 
+/// アプリの状態
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppHealth {
+    /// アプリデータディレクトリ
+    pub app_data_dir: String,
+    /// アプリデータのボリュームの空き容量（バイト、取得できない場合はNone）
+    pub available_disk_bytes: Option<u64>,
+    /// 空き容量が少ないかどうか
+    pub low_disk_space: bool,
+    /// セキュアストレージの警告（フォールバックストレージを使用している場合）
+    pub secure_storage_warning: Option<String>,
+    /// APIサーバーのレート制限による待機期間の状態
+    pub rate_limit: RateLimitStatus,
+    /// 確認日時（RFC3339形式、JST）
+    pub checked_at: String,
+}
+
 /// システム情報
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemInfo {
@@ -173,6 +193,29 @@ pub struct SecurityEvent {
     pub user_context: Option<String>,
 }
 
+/// 期間内のセキュリティイベントの集計
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditReport {
+    /// 集計の開始日（YYYY-MM-DD形式）
+    pub start_date: String,
+    /// 集計の終了日（YYYY-MM-DD形式）
+    pub end_date: String,
+    /// イベントの総数
+    pub total_events: usize,
+    /// イベントタイプ別の件数
+    pub events_by_type: HashMap<String, usize>,
+    /// 重要度別の件数（キーは`Info`・`Warning`・`Error`・`Critical`）
+    pub events_by_severity: HashMap<String, usize>,
+    /// 認証に失敗したイベントの件数
+    pub failed_auth_count: usize,
+    /// 領収書の操作（アップロード・削除・出力など）のイベント件数
+    pub receipt_operations_count: usize,
+    /// 認証失敗率とエラー率から求めたリスクスコア（0〜100、高いほど危険）
+    pub risk_score: f64,
+    /// 作成日時（RFC3339形式、JST）
+    pub generated_at: String,
+}
+
 /// イベント重要度
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub enum EventSeverity {
@@ -330,6 +373,38 @@ impl SecurityEvent {
     }
 }
 
+impl EventSeverity {
+    /// 重要度の順序値を取得（大きいほど重要）
+    pub fn level(&self) -> i64 {
+        match self {
+            EventSeverity::Info => 0,
+            EventSeverity::Warning => 1,
+            EventSeverity::Error => 2,
+            EventSeverity::Critical => 3,
+        }
+    }
+
+    /// 保存用の文字列表現を取得
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventSeverity::Info => "Info",
+            EventSeverity::Warning => "Warning",
+            EventSeverity::Error => "Error",
+            EventSeverity::Critical => "Critical",
+        }
+    }
+
+    /// 保存用の文字列表現から変換（不明な値はInfo）
+    pub fn from_stored(value: &str) -> Self {
+        match value {
+            "Warning" => EventSeverity::Warning,
+            "Error" => EventSeverity::Error,
+            "Critical" => EventSeverity::Critical,
+            _ => EventSeverity::Info,
+        }
+    }
+}
+
 impl ConnectionTestResult {
     /// 成功結果を作成
     pub fn success(response_time_ms: u64, connection_details: ConnectionDetails) -> Self {
@@ -0,0 +1,566 @@
+// 読み取り専用のSQLクエリコンソール
+//
+// 組み込みのフィルターでは答えられない集計のため、上級者向け画面からSELECT文を実行します。
+// - 専用の読み取り専用接続（SQLITE_OPEN_READ_ONLY）で実行する
+// - 単一のSELECT文のみ許可し、ATTACH・PRAGMAや複数の文は拒否する
+// - 参照するテーブルは`user_id`列を持つものに限り、同名の一時ビューでログイン中のユーザーの行に絞り込む
+// - 取得件数の上限と、進捗ハンドラーによる実行時間の上限を設ける
+// 機能フラグ（`ENABLE_QUERY_CONSOLE`）が有効な場合のみ利用でき、初回利用時は確認が必要です。
+
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::utils::get_current_jst_timestamp;
+use base64::{engine::general_purpose, Engine as _};
+use rusqlite::hooks::{AuthAction, AuthContext, Authorization};
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{params, params_from_iter, Connection, OpenFlags, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// クエリコンソールを有効にする機能フラグの環境変数名
+pub const QUERY_CONSOLE_FLAG: &str = "ENABLE_QUERY_CONSOLE";
+
+/// 初回利用時の確認が必要な場合のエラーコード
+pub const CONFIRMATION_REQUIRED: &str = "QUERY_CONSOLE_CONFIRMATION_REQUIRED";
+
+/// 取得件数の既定値
+pub const DEFAULT_ROW_LIMIT: usize = 500;
+
+/// 取得件数の上限
+pub const MAX_ROW_LIMIT: usize = 5000;
+
+/// 実行時間の上限
+pub const STATEMENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 進捗ハンドラーを呼び出す間隔（仮想マシンの命令数）
+const PROGRESS_HANDLER_OPS: i32 = 1000;
+
+/// `user_id`列を持っていても参照を許可しないテーブル
+const DENIED_TABLES: &[&str] = &["sessions"];
+
+/// クエリの実行結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryResult {
+    /// 列名
+    pub columns: Vec<String>,
+    /// 行（列の順に値を格納。BLOBはBase64文字列）
+    pub rows: Vec<Vec<serde_json::Value>>,
+    /// 取得件数の上限で打ち切った場合はtrue
+    pub truncated: bool,
+    /// 適用した取得件数の上限
+    pub row_limit: usize,
+    /// 実行時間（ミリ秒）
+    pub duration_ms: u64,
+}
+
+/// クエリコンソールが有効かどうか
+pub fn is_query_console_enabled() -> bool {
+    crate::get_env_var_or_default!("ENABLE_QUERY_CONSOLE", "false").eq_ignore_ascii_case("true")
+}
+
+/// 初回利用時の確認状況テーブルを作成する
+pub fn ensure_acknowledgement_table(conn: &Connection) -> AppResult<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS query_console_acknowledgements (
+            user_id TEXT PRIMARY KEY,
+            acknowledged_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// 初回利用時の確認を済ませているかどうか
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+pub fn is_acknowledged(conn: &Connection, user_id: &str) -> AppResult<bool> {
+    ensure_acknowledgement_table(conn)?;
+    let acknowledged = conn
+        .query_row(
+            "SELECT 1 FROM query_console_acknowledgements WHERE user_id = ?1",
+            params![user_id],
+            |_| Ok(()),
+        )
+        .optional()?;
+    Ok(acknowledged.is_some())
+}
+
+/// 初回利用時の確認を記録する
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `user_id` - ユーザーID
+pub fn acknowledge(conn: &Connection, user_id: &str) -> AppResult<()> {
+    ensure_acknowledgement_table(conn)?;
+    conn.execute(
+        "INSERT OR IGNORE INTO query_console_acknowledgements (user_id, acknowledged_at)
+         VALUES (?1, ?2)",
+        params![user_id, get_current_jst_timestamp()],
+    )?;
+    Ok(())
+}
+
+/// 読み取り専用の接続を開く
+///
+/// # 引数
+/// * `path` - データベースファイルのパス
+pub fn open_readonly_connection(path: &Path) -> AppResult<Connection> {
+    let conn = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    Ok(conn)
+}
+
+/// SQLを1つの文に正規化する
+///
+/// 文字列・識別子・コメントの外にある`;`の後に別の文がある場合は拒否します。
+///
+/// # 戻り値
+/// 末尾の`;`を除いた文
+fn single_statement(sql: &str) -> AppResult<&str> {
+    let bytes = sql.as_bytes();
+    let mut i = 0;
+    let mut end = None;
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'\'' | b'"' | b'`') => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    i += 1;
+                }
+            }
+            b'[' => {
+                while i < bytes.len() && bytes[i] != b']' {
+                    i += 1;
+                }
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                    i += 1;
+                }
+                i += 1;
+            }
+            b';' => {
+                end.get_or_insert(i);
+            }
+            c if !c.is_ascii_whitespace() && end.is_some() => {
+                return Err(AppError::Validation("複数の文は実行できません".to_string()));
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    let statement = sql[..end.unwrap_or(sql.len())].trim();
+    let keyword = statement
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .find(|word| !word.is_empty())
+        .unwrap_or_default();
+    if !keyword.eq_ignore_ascii_case("select") && !keyword.eq_ignore_ascii_case("with") {
+        return Err(AppError::Validation("SELECT文のみ実行できます".to_string()));
+    }
+    Ok(statement)
+}
+
+/// SQLが参照するテーブルを取得する
+///
+/// SELECT以外の操作（PRAGMA・ATTACHなど）や、mainデータベース以外の参照は拒否します。
+/// テーブル名と同じ名前のCTEやmainのビューを経由した参照も、絞り込みを回避できるため拒否します。
+fn referenced_tables(conn: &Connection, sql: &str) -> AppResult<BTreeSet<String>> {
+    let found = Arc::new(Mutex::new((BTreeSet::new(), BTreeSet::new())));
+    let collected = Arc::clone(&found);
+    conn.authorizer(Some(move |context: AuthContext<'_>| match context.action {
+        AuthAction::Select | AuthAction::Function { .. } | AuthAction::Recursive => {
+            Authorization::Allow
+        }
+        // 列を参照しない場合（count(*)など）は修飾なしの参照でデータベース名が渡されない
+        AuthAction::Read { table_name, .. }
+            if matches!(context.database_name, Some("main") | None) =>
+        {
+            if let Ok(mut found) = collected.lock() {
+                found.0.insert(table_name.to_string());
+                if let Some(accessor) = context.accessor {
+                    found.1.insert(accessor.to_string());
+                }
+            }
+            Authorization::Allow
+        }
+        _ => Authorization::Deny,
+    }));
+    let prepared = conn.prepare(sql).map(|stmt| stmt.readonly());
+    clear_authorizer(conn);
+
+    if !prepared.map_err(|e| AppError::Validation(format!("実行できないSQLです: {e}")))? {
+        return Err(AppError::Validation(
+            "読み取り専用のSQLのみ実行できます".to_string(),
+        ));
+    }
+    let (tables, accessors) = found
+        .lock()
+        .map_err(|e| AppError::Concurrency(e.to_string()))?
+        .clone();
+
+    for accessor in &accessors {
+        let shadows_schema = conn
+            .query_row(
+                "SELECT 1 FROM main.sqlite_master WHERE name = ?1 COLLATE NOCASE",
+                params![accessor],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if shadows_schema {
+            return Err(AppError::Security(format!(
+                "{accessor} を経由した参照はできません"
+            )));
+        }
+    }
+    Ok(tables)
+}
+
+fn clear_authorizer(conn: &Connection) {
+    conn.authorizer(None::<fn(AuthContext<'_>) -> Authorization>);
+}
+
+/// テーブルを`user_id`で絞り込む一時ビューを作成する
+///
+/// 一時ビューはmainのテーブルより優先して解決されるため、
+/// SQLを書き換えずにログイン中のユーザーの行だけを参照させられます。
+fn create_scoped_views(
+    conn: &Connection,
+    tables: &BTreeSet<String>,
+    user_id: &str,
+) -> AppResult<()> {
+    for table in tables {
+        if DENIED_TABLES.contains(&table.as_str()) {
+            return Err(AppError::Security(format!(
+                "テーブル {table} は参照できません"
+            )));
+        }
+        let has_user_id = conn
+            .query_row(
+                "SELECT 1 FROM pragma_table_info(?1) WHERE name = 'user_id'",
+                params![table],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if !has_user_id {
+            return Err(AppError::Security(format!(
+                "テーブル {table} はユーザーごとに絞り込めないため参照できません"
+            )));
+        }
+
+        let quoted = table.replace('"', "\"\"");
+        conn.execute(
+            &format!(
+                "CREATE TEMP VIEW \"{quoted}\" AS SELECT * FROM main.\"{quoted}\" WHERE user_id = '{}'",
+                user_id.replace('\'', "''")
+            ),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+/// JSONの値をSQLのパラメーターに変換する
+fn to_sql_value(value: &serde_json::Value) -> AppResult<Value> {
+    Ok(match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Integer(*b as i64),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::Real(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => Value::Text(s.clone()),
+        _ => {
+            return Err(AppError::Validation(
+                "パラメーターには文字列・数値・真偽値・nullのみ指定できます".to_string(),
+            ))
+        }
+    })
+}
+
+/// SQLの値をJSONの値に変換する
+fn to_json_value(value: ValueRef<'_>) -> serde_json::Value {
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => i.into(),
+        ValueRef::Real(f) => serde_json::Number::from_f64(f)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        ValueRef::Text(text) => String::from_utf8_lossy(text).into_owned().into(),
+        ValueRef::Blob(blob) => general_purpose::STANDARD.encode(blob).into(),
+    }
+}
+
+/// 読み取り専用のクエリを実行する
+///
+/// # 引数
+/// * `conn` - `open_readonly_connection`で開いた専用の接続（一時ビューを作成するため使い回さない）
+/// * `sql` - 単一のSELECT文
+/// * `params` - `?`に割り当てるパラメーター
+/// * `user_id` - ログイン中のユーザーID
+/// * `row_limit` - 取得件数の上限
+/// * `timeout` - 実行時間の上限
+pub fn run_readonly_query(
+    conn: &Connection,
+    sql: &str,
+    params: &[serde_json::Value],
+    user_id: &str,
+    row_limit: usize,
+    timeout: Duration,
+) -> AppResult<QueryResult> {
+    let started = Instant::now();
+    let row_limit = row_limit.clamp(1, MAX_ROW_LIMIT);
+    let sql = single_statement(sql)?;
+
+    let tables = referenced_tables(conn, sql)?;
+    create_scoped_views(conn, &tables, user_id)?;
+
+    // 一時ビュー経由の参照のみ許可する（main.テーブル名での直接参照を防ぐ）
+    let views = tables;
+    conn.authorizer(Some(move |context: AuthContext<'_>| match context.action {
+        AuthAction::Select | AuthAction::Function { .. } | AuthAction::Recursive => {
+            Authorization::Allow
+        }
+        AuthAction::Read { table_name, .. }
+            if views.contains(table_name)
+                && match context.database_name {
+                    // 一時ビューはこのモジュールが作成したものだけなのでCTE経由でも許可する
+                    Some("temp") | None => true,
+                    Some("main") => context.accessor == Some(table_name),
+                    _ => false,
+                } =>
+        {
+            Authorization::Allow
+        }
+        _ => Authorization::Deny,
+    }));
+    let prepared = conn.prepare(sql);
+    clear_authorizer(conn);
+    let mut stmt = prepared.map_err(|e| AppError::Security(format!("実行できないSQLです: {e}")))?;
+
+    if stmt.parameter_count() != params.len() {
+        return Err(AppError::Validation(format!(
+            "パラメーターの数が一致しません（必要: {}、指定: {}）",
+            stmt.parameter_count(),
+            params.len()
+        )));
+    }
+    let values = params
+        .iter()
+        .map(to_sql_value)
+        .collect::<AppResult<Vec<_>>>()?;
+
+    let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+    let deadline = started + timeout;
+    conn.progress_handler(
+        PROGRESS_HANDLER_OPS,
+        Some(move || Instant::now() > deadline),
+    );
+
+    let result = (|| {
+        let mut rows = stmt.query(params_from_iter(values))?;
+        let mut collected = Vec::new();
+        let mut truncated = false;
+        while let Some(row) = rows.next()? {
+            if collected.len() == row_limit {
+                truncated = true;
+                break;
+            }
+            collected.push(
+                (0..columns.len())
+                    .map(|i| row.get_ref(i).map(to_json_value))
+                    .collect::<rusqlite::Result<Vec<_>>>()?,
+            );
+        }
+        Ok::<_, rusqlite::Error>((collected, truncated))
+    })();
+    conn.progress_handler(0, None::<fn() -> bool>);
+
+    let (rows, truncated) = result.map_err(|e| match e {
+        rusqlite::Error::SqliteFailure(error, _)
+            if error.code == rusqlite::ErrorCode::OperationInterrupted =>
+        {
+            AppError::Validation(format!(
+                "実行時間の上限（{}秒）を超えたため中断しました",
+                timeout.as_secs_f64()
+            ))
+        }
+        e => AppError::Database(e.to_string()),
+    })?;
+
+    Ok(QueryResult {
+        columns,
+        rows,
+        truncated,
+        row_limit,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn create_test_db() -> (tempfile::TempDir, std::path::PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("expenses.db");
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE expenses (id INTEGER PRIMARY KEY, amount REAL, receipt_url TEXT, user_id INTEGER);
+             CREATE TABLE sessions (id TEXT PRIMARY KEY, user_id INTEGER);
+             CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT);
+             INSERT INTO expenses VALUES
+               (1, 12000, NULL, 1), (2, 800, NULL, 1), (3, 15000, 'https://r/3.png', 1),
+               (4, 50000, NULL, 2);
+             INSERT INTO sessions VALUES ('token', 1);
+             INSERT INTO users VALUES (1, 'a@example.com');",
+        )
+        .unwrap();
+        (dir, path)
+    }
+
+    fn run(path: &Path, sql: &str, params: &[serde_json::Value]) -> AppResult<QueryResult> {
+        let conn = open_readonly_connection(path).unwrap();
+        run_readonly_query(
+            &conn,
+            sql,
+            params,
+            "1",
+            DEFAULT_ROW_LIMIT,
+            STATEMENT_TIMEOUT,
+        )
+    }
+
+    #[test]
+    fn test_select_is_scoped_to_user() {
+        let (_dir, path) = create_test_db();
+
+        let result = run(
+            &path,
+            "SELECT id, amount FROM expenses WHERE amount > ?1 AND receipt_url IS NULL ORDER BY id;",
+            &[json!(10000)],
+        )
+        .unwrap();
+        assert_eq!(result.columns, ["id", "amount"]);
+        // ユーザー2の経費は含まれない
+        assert_eq!(result.rows, vec![vec![json!(1), json!(12000.0)]]);
+        assert!(!result.truncated);
+
+        // サブクエリ・CTEでも絞り込まれる
+        let result = run(
+            &path,
+            "WITH t AS (SELECT * FROM expenses) SELECT count(*) FROM t WHERE id IN (SELECT id FROM expenses)",
+            &[],
+        )
+        .unwrap();
+        assert_eq!(result.rows, vec![vec![json!(3)]]);
+
+        // 列を参照しない集計も絞り込まれる
+        let result = run(&path, "SELECT count(*) FROM expenses", &[]).unwrap();
+        assert_eq!(result.rows, vec![vec![json!(3)]]);
+        let result = run(&path, "SELECT 1 FROM expenses", &[]).unwrap();
+        assert_eq!(result.rows.len(), 3);
+    }
+
+    #[test]
+    fn test_rejects_cte_shadowing_table() {
+        let (_dir, path) = create_test_db();
+        for sql in [
+            "WITH expenses AS (SELECT * FROM main.expenses) SELECT * FROM expenses",
+            "WITH Expenses AS (SELECT * FROM expenses) SELECT count(*) FROM Expenses",
+        ] {
+            assert!(run(&path, sql, &[]).is_err(), "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_rejects_non_select_statements() {
+        let (_dir, path) = create_test_db();
+        for sql in [
+            "DELETE FROM expenses",
+            "UPDATE expenses SET amount = 0",
+            "PRAGMA table_info(expenses)",
+            "SELECT * FROM pragma_table_info('expenses')",
+            "ATTACH DATABASE '/tmp/x.db' AS x",
+            "SELECT 1; DELETE FROM expenses",
+            "SELECT 1; SELECT 2",
+            "WITH t AS (SELECT 1) DELETE FROM expenses",
+        ] {
+            assert!(run(&path, sql, &[]).is_err(), "{sql}");
+        }
+
+        // 文字列・コメント内のセミコロンは許可する
+        let result = run(&path, "SELECT ';' AS s -- ; DROP\n;", &[]).unwrap();
+        assert_eq!(result.rows, vec![vec![json!(";")]]);
+    }
+
+    #[test]
+    fn test_rejects_tables_that_cannot_be_scoped() {
+        let (_dir, path) = create_test_db();
+        for sql in [
+            "SELECT * FROM users",
+            "SELECT * FROM sessions",
+            "SELECT * FROM sqlite_master",
+            "SELECT * FROM main.expenses",
+            "SELECT count(*) FROM main.expenses",
+            "SELECT count(*) FROM users",
+            "SELECT * FROM expenses, main.expenses",
+        ] {
+            assert!(run(&path, sql, &[]).is_err(), "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_row_limit_and_timeout() {
+        let (_dir, path) = create_test_db();
+        let conn = open_readonly_connection(&path).unwrap();
+        let result = run_readonly_query(
+            &conn,
+            "SELECT id FROM expenses ORDER BY id",
+            &[],
+            "1",
+            2,
+            STATEMENT_TIMEOUT,
+        )
+        .unwrap();
+        assert_eq!(result.rows.len(), 2);
+        assert!(result.truncated);
+
+        let conn = open_readonly_connection(&path).unwrap();
+        let error = run_readonly_query(
+            &conn,
+            "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c) SELECT max(x) FROM c",
+            &[],
+            "1",
+            DEFAULT_ROW_LIMIT,
+            Duration::from_millis(50),
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("中断"), "{error}");
+    }
+
+    #[test]
+    fn test_acknowledgement() {
+        let conn = Connection::open_in_memory().unwrap();
+        assert!(!is_acknowledged(&conn, "1").unwrap());
+        acknowledge(&conn, "1").unwrap();
+        acknowledge(&conn, "1").unwrap();
+        assert!(is_acknowledged(&conn, "1").unwrap());
+        assert!(!is_acknowledged(&conn, "2").unwrap());
+    }
+}
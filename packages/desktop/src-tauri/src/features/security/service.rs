@@ -1,10 +1,36 @@
+use crate::features::security::audit_log::{count_security_events, insert_security_event};
 use crate::features::security::encryption::TokenEncryption;
-use crate::features::security::models::{SecurityConfig, SecurityError, TokenInfo};
+use crate::features::security::models::{
+    AuditReport, EventSeverity, SecurityConfig, SecurityError, SecurityEvent, TokenInfo,
+};
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::utils::get_current_jst_timestamp;
+use rusqlite::Connection;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
+/// 認証の失敗を表すイベントタイプ
+const FAILED_AUTH_EVENT_TYPES: &[&str] = &[
+    "unauthorized_access",
+    "auth_failed",
+    "login_failed",
+    "invalid_token",
+    "session_validation_failed",
+];
+
+/// 領収書の操作を表すイベントタイプの接頭辞
+const RECEIPT_EVENT_PREFIXES: &[&str] = &["receipt_", "upload_"];
+
+/// リスクスコアにおける認証失敗率の重み
+const FAILED_AUTH_WEIGHT: f64 = 0.6;
+
+/// リスクスコアにおけるエラー率（重要度がError以上のイベントの割合）の重み
+const ERROR_RATE_WEIGHT: f64 = 0.4;
+
 /// セキュリティサービス
 /// 認証トークンの暗号化、セキュアな保存、アクセス制御を管理する
+///
+/// 複製したインスタンスはトークンのキャッシュを共有するため、`Arc`で包まずに複製して渡せます。
 #[derive(Clone)]
 pub struct SecurityService {
     /// トークン暗号化サービス
@@ -13,6 +39,8 @@ pub struct SecurityService {
     config: SecurityConfig,
     /// アクティブなトークンのキャッシュ
     token_cache: Arc<Mutex<HashMap<String, TokenInfo>>>,
+    /// セキュリティイベントの保存先（`with_event_store`で設定）
+    event_store: Option<Arc<Mutex<Connection>>>,
 }
 
 impl SecurityService {
@@ -22,18 +50,84 @@ impl SecurityService {
     /// * `config` - セキュリティ設定
     ///
     /// # 戻り値
-    /// SecurityServiceインスタンス
-    pub fn new(config: SecurityConfig) -> Result<Self, SecurityError> {
+    /// SecurityServiceインスタンス、または設定が不正な場合は設定エラー
+    pub fn new(config: SecurityConfig) -> AppResult<Self> {
+        if config.encryption_key.is_empty() {
+            return Err(AppError::Configuration(
+                "暗号化キーが設定されていません".to_string(),
+            ));
+        }
+        if config.max_token_age_hours <= 0 {
+            return Err(AppError::Configuration(format!(
+                "トークンの最大保持時間が不正です: {}",
+                config.max_token_age_hours
+            )));
+        }
+
         let token_encryption = TokenEncryption::new(config.encryption_key.clone())
-            .map_err(|e| SecurityError::EncryptionError(e.to_string()))?;
+            .map_err(|e| AppError::Configuration(format!("暗号化の初期化に失敗しました: {e}")))?;
 
         Ok(Self {
             token_encryption,
             config,
             token_cache: Arc::new(Mutex::new(HashMap::new())),
+            event_store: None,
         })
     }
 
+    /// セキュリティイベントを保存するデータベースを設定する
+    ///
+    /// 設定後は、不正アクセスの検出やトークンの無効化を`security_events`テーブルに記録します。
+    /// 複製したインスタンスも同じ保存先を使用します。
+    ///
+    /// # 引数
+    /// * `conn` - `security_events`テーブルを持つデータベース接続
+    pub fn with_event_store(mut self, conn: Arc<Mutex<Connection>>) -> Self {
+        self.event_store = Some(conn);
+        self
+    }
+
+    /// セキュリティイベントを記録する
+    ///
+    /// 監査ログが無効な場合や保存先が未設定の場合は保存しません。
+    /// 保存に失敗しても呼び出し元の処理は妨げません。
+    ///
+    /// # 引数
+    /// * `event_type` - イベントタイプ
+    /// * `details` - イベント詳細（トークンなどの秘密情報は含めない）
+    /// * `severity` - 重要度
+    /// * `user_id` - 関連するユーザーID（不明な場合はNone）
+    pub fn record_event(
+        &self,
+        event_type: &str,
+        details: String,
+        severity: EventSeverity,
+        user_id: Option<&str>,
+    ) {
+        if !self.config.enable_audit_logging {
+            return;
+        }
+        let Some(store) = &self.event_store else {
+            return;
+        };
+
+        let event = SecurityEvent::new(
+            event_type.to_string(),
+            details,
+            severity,
+            user_id.map(str::to_string),
+        );
+        let result = store
+            .lock()
+            .map_err(|e| e.to_string())
+            .and_then(|conn| insert_security_event(&conn, &event).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            log::warn!(
+                "セキュリティイベントの保存に失敗しました: event_type={event_type}, error={e}"
+            );
+        }
+    }
+
     /// 認証トークンを暗号化して保存する
     ///
     /// # 引数
@@ -92,7 +186,15 @@ impl SecurityService {
         let decrypted_token = self
             .token_encryption
             .decrypt_token(encrypted_token)
-            .map_err(|e| SecurityError::DecryptionError(e.to_string()))?;
+            .map_err(|e| {
+                self.record_event(
+                    "token_decrypt_failed",
+                    format!("token_id={token_id}, error={e}"),
+                    EventSeverity::Error,
+                    None,
+                );
+                SecurityError::DecryptionError(e.to_string())
+            })?;
 
         // アクセス情報を更新
         {
@@ -251,29 +353,39 @@ impl SecurityService {
         log::warn!("不正アクセスを検出しました: {request_info}");
 
         // 不正アクセスの詳細をログに記録
-        if let Some(token) = token {
+        let token_state = if let Some(token) = token {
             if !token.is_empty() {
                 // トークンが提供されている場合、その有効性をチェック
                 match self.verify_api_request(token) {
                     Ok(true) => {
                         log::info!("有効なトークンでの不正アクセス試行: {request_info}");
+                        "valid_format"
                     }
                     Ok(false) => {
                         log::warn!("無効なトークンでの不正アクセス試行: {request_info}");
+                        "invalid_format"
                     }
                     Err(e) => {
                         log::error!("トークン検証エラー during 不正アクセス検出: {e}");
+                        "verification_error"
                     }
                 }
             } else {
                 log::warn!("空のトークンでの不正アクセス試行: {request_info}");
+                "empty"
             }
         } else {
             log::warn!("トークンなしでの不正アクセス試行: {request_info}");
-        }
+            "missing"
+        };
 
-        // セキュリティイベントとして記録（実装は後で追加）
-        // TODO: セキュリティイベントログシステムとの統合
+        // トークン自体は保存せず、状態のみを記録する
+        self.record_event(
+            "unauthorized_access",
+            format!("{request_info}, token={token_state}"),
+            EventSeverity::Warning,
+            None,
+        );
 
         Ok(())
     }
@@ -294,6 +406,12 @@ impl SecurityService {
         }
 
         log::info!("トークンを無効化しました: token_id={token_id}");
+        self.record_event(
+            "token_invalidated",
+            format!("token_id={token_id}"),
+            EventSeverity::Info,
+            None,
+        );
         Ok(())
     }
 
@@ -312,6 +430,12 @@ impl SecurityService {
         };
 
         log::info!("すべてのトークンを無効化しました: count={count}");
+        self.record_event(
+            "all_tokens_invalidated",
+            format!("count={count}"),
+            EventSeverity::Warning,
+            None,
+        );
         Ok(count)
     }
 
@@ -398,6 +522,82 @@ impl SecurityService {
         Ok(removed_count)
     }
 
+    /// 期間内のセキュリティイベントを集計する
+    ///
+    /// リスクスコアは、認証失敗率とエラー率（重要度がError以上のイベントの割合）の
+    /// 加重平均を0〜100で表したものです。イベントがない場合は0になります。
+    ///
+    /// # 引数
+    /// * `conn` - データベース接続
+    /// * `start_date` - 開始日（YYYY-MM-DD形式、この日を含む）
+    /// * `end_date` - 終了日（YYYY-MM-DD形式、この日を含む）
+    ///
+    /// # 戻り値
+    /// 集計結果、または日付が不正な場合はバリデーションエラー
+    pub fn generate_audit_report(
+        &self,
+        conn: &Connection,
+        start_date: &str,
+        end_date: &str,
+    ) -> AppResult<AuditReport> {
+        let counts = count_security_events(conn, start_date, end_date)?;
+
+        let mut events_by_type: HashMap<String, usize> = HashMap::new();
+        let mut events_by_severity: HashMap<String, usize> = HashMap::new();
+        let mut error_count = 0;
+        for count in &counts {
+            *events_by_type.entry(count.event_type.clone()).or_default() += count.count;
+            *events_by_severity
+                .entry(count.severity.as_str().to_string())
+                .or_default() += count.count;
+            if count.severity.level() >= EventSeverity::Error.level() {
+                error_count += count.count;
+            }
+        }
+
+        let total_events: usize = events_by_type.values().sum();
+        let count_types = |matches: &dyn Fn(&str) -> bool| -> usize {
+            events_by_type
+                .iter()
+                .filter(|(event_type, _)| matches(event_type))
+                .map(|(_, count)| count)
+                .sum()
+        };
+        let failed_auth_count =
+            count_types(&|event_type| FAILED_AUTH_EVENT_TYPES.contains(&event_type));
+        let receipt_operations_count = count_types(&|event_type| {
+            RECEIPT_EVENT_PREFIXES
+                .iter()
+                .any(|prefix| event_type.starts_with(prefix))
+        });
+
+        let risk_score = if total_events == 0 {
+            0.0
+        } else {
+            let rate = |count: usize| count as f64 / total_events as f64;
+            let score = (FAILED_AUTH_WEIGHT * rate(failed_auth_count)
+                + ERROR_RATE_WEIGHT * rate(error_count))
+                * 100.0;
+            (score * 10.0).round() / 10.0
+        };
+
+        log::info!(
+            "監査レポートを作成しました: period={start_date}..{end_date}, total_events={total_events}, risk_score={risk_score}"
+        );
+
+        Ok(AuditReport {
+            start_date: start_date.to_string(),
+            end_date: end_date.to_string(),
+            total_events,
+            events_by_type,
+            events_by_severity,
+            failed_auth_count,
+            receipt_operations_count,
+            risk_score,
+            generated_at: get_current_jst_timestamp(),
+        })
+    }
+
     /// セキュリティ設定を取得する
     ///
     /// # 戻り値
@@ -424,6 +624,184 @@ mod tests {
         SecurityService::new(config).unwrap()
     }
 
+    #[test]
+    fn test_clone_shares_token_cache() {
+        let service = setup_test_security_service();
+        let cloned = service.clone();
+
+        service.encrypt_and_store_token("t1", "token").unwrap();
+        assert!(cloned.get_token_info("t1").unwrap().is_some());
+
+        cloned.invalidate_token("t1").unwrap();
+        assert_eq!(service.get_active_token_count(), 0);
+    }
+
+    #[test]
+    fn test_new_rejects_invalid_config() {
+        let valid = SecurityConfig {
+            encryption_key: "test_encryption_key_32_bytes_long".to_string(),
+            max_token_age_hours: 24,
+            enable_audit_logging: true,
+        };
+
+        let empty_key = SecurityConfig {
+            encryption_key: String::new(),
+            ..valid.clone()
+        };
+        assert!(matches!(
+            SecurityService::new(empty_key),
+            Err(AppError::Configuration(_))
+        ));
+
+        let no_token_age = SecurityConfig {
+            max_token_age_hours: 0,
+            ..valid.clone()
+        };
+        assert!(matches!(
+            SecurityService::new(no_token_age),
+            Err(AppError::Configuration(_))
+        ));
+
+        assert!(SecurityService::new(valid).is_ok());
+    }
+
+    fn event_store() -> Arc<Mutex<Connection>> {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::features::security::audit_log::create_security_events_table(&conn).unwrap();
+        Arc::new(Mutex::new(conn))
+    }
+
+    fn stored_event_types(store: &Mutex<Connection>) -> Vec<(String, String)> {
+        store
+            .lock()
+            .unwrap()
+            .prepare("SELECT event_type, details FROM security_events ORDER BY timestamp, rowid")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_security_events_are_persisted() {
+        let store = event_store();
+        let service = setup_test_security_service().with_event_store(Arc::clone(&store));
+        let token = "header.payload.signature";
+
+        service
+            .detect_unauthorized_access("不正アクセス試行: path=/expenses", Some(token))
+            .unwrap();
+        service
+            .detect_unauthorized_access("不正アクセス試行: path=/expenses", None)
+            .unwrap();
+        // 複製したインスタンスも同じ保存先に記録する
+        service.clone().invalidate_token("t1").unwrap();
+
+        let events = stored_event_types(&store);
+        let types: Vec<&str> = events.iter().map(|(t, _)| t.as_str()).collect();
+        assert_eq!(
+            types,
+            vec![
+                "unauthorized_access",
+                "unauthorized_access",
+                "token_invalidated"
+            ]
+        );
+        // トークン自体は保存しない
+        assert!(events.iter().all(|(_, details)| !details.contains(token)));
+        assert!(events[0].1.ends_with("token=valid_format"));
+        assert!(events[1].1.ends_with("token=missing"));
+    }
+
+    #[test]
+    fn test_security_events_are_not_persisted_when_audit_logging_disabled() {
+        let store = event_store();
+        let config = SecurityConfig {
+            encryption_key: "test_encryption_key_32_bytes_long".to_string(),
+            max_token_age_hours: 24,
+            enable_audit_logging: false,
+        };
+        let service = SecurityService::new(config)
+            .unwrap()
+            .with_event_store(Arc::clone(&store));
+
+        service.detect_unauthorized_access("path=/", None).unwrap();
+
+        assert!(stored_event_types(&store).is_empty());
+    }
+
+    fn insert_event(conn: &Connection, event_type: &str, severity: EventSeverity, timestamp: &str) {
+        let mut event = crate::features::security::models::SecurityEvent::new(
+            event_type.to_string(),
+            String::new(),
+            severity,
+            None,
+        );
+        event.timestamp = timestamp.to_string();
+        crate::features::security::audit_log::insert_security_event(conn, &event).unwrap();
+    }
+
+    #[test]
+    fn test_generate_audit_report() {
+        let service = setup_test_security_service();
+        let conn = Connection::open_in_memory().unwrap();
+        crate::features::security::audit_log::create_security_events_table(&conn).unwrap();
+
+        let day = "2024-03-10T12:00:00+09:00";
+        for _ in 0..5 {
+            insert_event(&conn, "page_access", EventSeverity::Info, day);
+        }
+        insert_event(&conn, "upload_complete", EventSeverity::Info, day);
+        insert_event(&conn, "receipt_copy_exported", EventSeverity::Info, day);
+        insert_event(&conn, "unauthorized_access", EventSeverity::Warning, day);
+        insert_event(&conn, "login_failed", EventSeverity::Error, day);
+        insert_event(&conn, "error", EventSeverity::Critical, day);
+        // 期間外のイベントは集計しない
+        insert_event(
+            &conn,
+            "login_failed",
+            EventSeverity::Error,
+            "2024-03-11T00:00:00+09:00",
+        );
+
+        let report = service
+            .generate_audit_report(&conn, "2024-03-01", "2024-03-10")
+            .unwrap();
+        assert_eq!(report.total_events, 10);
+        assert_eq!(report.events_by_type["page_access"], 5);
+        assert_eq!(report.events_by_type["login_failed"], 1);
+        assert_eq!(report.events_by_severity["Info"], 7);
+        assert_eq!(report.events_by_severity["Warning"], 1);
+        assert_eq!(report.events_by_severity["Error"], 1);
+        assert_eq!(report.events_by_severity["Critical"], 1);
+        assert_eq!(report.failed_auth_count, 2);
+        assert_eq!(report.receipt_operations_count, 2);
+        // 認証失敗率20%・エラー率20% => 0.6 * 20 + 0.4 * 20
+        assert_eq!(report.risk_score, 20.0);
+    }
+
+    #[test]
+    fn test_generate_audit_report_without_events() {
+        let service = setup_test_security_service();
+        let conn = Connection::open_in_memory().unwrap();
+        crate::features::security::audit_log::create_security_events_table(&conn).unwrap();
+
+        let report = service
+            .generate_audit_report(&conn, "2024-03-01", "2024-03-31")
+            .unwrap();
+        assert_eq!(report.total_events, 0);
+        assert!(report.events_by_type.is_empty());
+        assert_eq!(report.risk_score, 0.0);
+
+        assert!(service
+            .generate_audit_report(&conn, "2024-03-31", "2024-03-01")
+            .is_err());
+        assert!(service
+            .generate_audit_report(&conn, "2024/03/01", "2024-03-31")
+            .is_err());
+    }
+
     #[test]
     fn test_encrypt_and_store_token() {
         let service = setup_test_security_service();
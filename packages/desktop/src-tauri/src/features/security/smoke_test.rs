@@ -0,0 +1,778 @@
+// リリース前のスモークテスト
+//
+// リリース前に手作業で確認していた主要な操作を、隔離した環境で順に実行します。
+// - 一時ディレクトリに作成した新しいデータベースに、マイグレーションを最初から適用する
+// - 経費・サブスクリプションの作成・更新・削除、CSV・JSONのエクスポートと再取り込みを実行する
+// - 生成した領収書画像のアップロード・ダウンロード・削除を実行する
+//   （ログイン中はAPIサーバー経由、認証情報がない場合はメモリ上のモック）
+// - 手順ごとの成否と所要時間をレポートにまとめる
+// 作成したデータは失敗時も含めて必ず削除します。
+// 機能フラグ（`ENABLE_RELEASE_SMOKE_TEST`）が有効な場合のみ利用できます。
+
+use crate::features::expenses::models::{Expense, ExpenseFilter};
+use crate::features::expenses::sync;
+use crate::features::receipts::api_client::{ApiClient, ApiClientConfig};
+use crate::features::subscriptions::import::{parse_export_content, SubscriptionExportSource};
+use crate::features::subscriptions::repository as subscription_repository;
+use crate::shared::database::connection::open_and_migrate_database;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::export::{
+    read_json_export, wrap_json_export, write_csv_export, ExportMeta, CURRENT_SCHEMA_VERSION,
+};
+use crate::shared::utils::disk_space::available_space;
+use crate::shared::utils::get_current_jst_timestamp;
+use image::{ImageFormat, Rgb, RgbImage};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// スモークテストを有効にする機能フラグの環境変数名
+pub const SMOKE_TEST_FLAG: &str = "ENABLE_RELEASE_SMOKE_TEST";
+
+/// レポート形式のバージョン（項目の追加・変更時に更新する）
+pub const REPORT_FORMAT_VERSION: u32 = 1;
+
+/// スモークテストで作成するデータのユーザーID
+const SMOKE_USER_ID: &str = "release-smoke-test";
+
+/// スモークテストでアップロードするファイル名の接頭辞
+const RECEIPT_PREFIX: &str = "release-smoke-test";
+
+/// スモークテストが有効かどうか
+pub fn is_smoke_test_enabled() -> bool {
+    crate::get_env_var_or_default!("ENABLE_RELEASE_SMOKE_TEST", "false")
+        .eq_ignore_ascii_case("true")
+}
+
+/// 手順の結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Passed,
+    Failed,
+    /// 前提となる手順の失敗や、認証情報がないために実行しなかった
+    Skipped,
+}
+
+/// 手順ごとの結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepReport {
+    /// 手順ID（リリース間で比較できるよう変更しない）
+    pub step: String,
+    pub status: StepStatus,
+    pub duration_ms: u64,
+    /// 失敗・スキップの理由、または補足情報
+    pub detail: Option<String>,
+}
+
+/// 領収書の保存先
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageMode {
+    /// APIサーバー経由のR2
+    Remote,
+    /// メモリ上のモック
+    Mock,
+}
+
+/// スモークテストのレポート
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmokeTestReport {
+    pub format_version: u32,
+    pub run_id: String,
+    pub app_version: String,
+    pub schema_version: String,
+    pub storage: StorageMode,
+    pub started_at: String, // RFC3339形式（JST）
+    pub duration_ms: u64,
+    /// すべての手順が成功またはスキップした場合はtrue
+    pub passed: bool,
+    pub steps: Vec<StepReport>,
+}
+
+/// アップロードした領収書
+#[derive(Debug, Clone)]
+struct StoredReceipt {
+    key: String,
+    url: Option<String>,
+}
+
+/// 領収書の保存先
+pub enum ReceiptStorage {
+    Mock(Mutex<HashMap<String, Vec<u8>>>),
+    Remote {
+        client: ApiClient,
+        http: reqwest::Client,
+        user_id: String,
+        auth_token: String,
+    },
+}
+
+impl ReceiptStorage {
+    /// メモリ上のモックを作成する
+    pub fn mock() -> Self {
+        Self::Mock(Mutex::new(HashMap::new()))
+    }
+
+    /// APIサーバー経由の保存先を作成する
+    ///
+    /// # 引数
+    /// * `user_id` - ログイン中のユーザーID
+    /// * `auth_token` - セッショントークン
+    pub fn remote(user_id: String, auth_token: String) -> AppResult<Self> {
+        Ok(Self::Remote {
+            client: ApiClient::new(ApiClientConfig::from_env())?,
+            http: reqwest::Client::new(),
+            user_id,
+            auth_token,
+        })
+    }
+
+    fn mode(&self) -> StorageMode {
+        match self {
+            Self::Mock(_) => StorageMode::Mock,
+            Self::Remote { .. } => StorageMode::Remote,
+        }
+    }
+
+    fn mock_objects(
+        objects: &Mutex<HashMap<String, Vec<u8>>>,
+    ) -> AppResult<std::sync::MutexGuard<'_, HashMap<String, Vec<u8>>>> {
+        objects
+            .lock()
+            .map_err(|e| AppError::Concurrency(format!("モックストレージのロックエラー: {e}")))
+    }
+
+    async fn upload(&self, filename: &str, data: &[u8]) -> AppResult<StoredReceipt> {
+        match self {
+            Self::Mock(objects) => {
+                let key = format!("{SMOKE_USER_ID}/{filename}");
+                Self::mock_objects(objects)?.insert(key.clone(), data.to_vec());
+                Ok(StoredReceipt { key, url: None })
+            }
+            Self::Remote {
+                client,
+                user_id,
+                auth_token,
+                ..
+            } => {
+                let response = client
+                    .upload_file(0, data, filename, user_id, auth_token)
+                    .await?;
+                if !response.success {
+                    return Err(AppError::ExternalService(
+                        response
+                            .error
+                            .unwrap_or_else(|| "アップロードに失敗しました".to_string()),
+                    ));
+                }
+                Ok(StoredReceipt {
+                    key: response.file_key,
+                    url: response.file_url,
+                })
+            }
+        }
+    }
+
+    async fn download(&self, receipt: &StoredReceipt) -> AppResult<Vec<u8>> {
+        match self {
+            Self::Mock(objects) => Self::mock_objects(objects)?
+                .get(&receipt.key)
+                .cloned()
+                .ok_or_else(|| AppError::NotFound(format!("領収書がありません: {}", receipt.key))),
+            Self::Remote {
+                http, auth_token, ..
+            } => {
+                let url = receipt.url.as_deref().ok_or_else(|| {
+                    AppError::ExternalService("アップロード結果にURLがありません".to_string())
+                })?;
+                let response = http
+                    .get(url)
+                    .bearer_auth(auth_token)
+                    .send()
+                    .await
+                    .and_then(reqwest::Response::error_for_status)
+                    .map_err(|e| AppError::ExternalService(format!("ダウンロード失敗: {e}")))?;
+                let bytes = response
+                    .bytes()
+                    .await
+                    .map_err(|e| AppError::ExternalService(format!("ダウンロード失敗: {e}")))?;
+                Ok(bytes.to_vec())
+            }
+        }
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<bool> {
+        match self {
+            Self::Mock(objects) => Ok(Self::mock_objects(objects)?.remove(key).is_some()),
+            Self::Remote {
+                client, auth_token, ..
+            } => client.delete_file(key, auth_token).await,
+        }
+    }
+
+    async fn exists(&self, key: &str) -> AppResult<Option<bool>> {
+        match self {
+            Self::Mock(objects) => Ok(Some(Self::mock_objects(objects)?.contains_key(key))),
+            // APIサーバーには存在確認のエンドポイントがないため確認しない
+            Self::Remote { .. } => Ok(None),
+        }
+    }
+}
+
+/// スモークテスト用の一時ディレクトリ（破棄時に削除する）
+struct Workspace {
+    dir: PathBuf,
+}
+
+impl Workspace {
+    fn create(run_id: &str) -> AppResult<Self> {
+        let dir = std::env::temp_dir().join(format!("orano-keihi-smoke-{run_id}"));
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn database_path(&self) -> PathBuf {
+        self.dir.join("smoke.db")
+    }
+
+    fn remove(&self) -> AppResult<()> {
+        if self.dir.exists() {
+            std::fs::remove_dir_all(&self.dir)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Workspace {
+    fn drop(&mut self) {
+        if let Err(e) = self.remove() {
+            log::warn!("スモークテストの一時ディレクトリの削除に失敗しました: {e}");
+        }
+    }
+}
+
+/// 手順の結果を記録する
+struct StepRecorder {
+    steps: Vec<StepReport>,
+}
+
+impl StepRecorder {
+    fn push(&mut self, step: &str, status: StepStatus, started: Instant, detail: Option<String>) {
+        if status == StepStatus::Failed {
+            log::warn!("スモークテスト失敗: step={step}, detail={detail:?}");
+        }
+        self.steps.push(StepReport {
+            step: step.to_string(),
+            status,
+            duration_ms: started.elapsed().as_millis() as u64,
+            detail,
+        });
+    }
+
+    fn finish<T>(&mut self, step: &str, started: Instant, result: AppResult<T>) -> Option<T> {
+        match result {
+            Ok(value) => {
+                self.push(step, StepStatus::Passed, started, None);
+                Some(value)
+            }
+            Err(e) => {
+                self.push(step, StepStatus::Failed, started, Some(e.to_string()));
+                None
+            }
+        }
+    }
+
+    fn run(&mut self, step: &str, f: impl FnOnce() -> AppResult<()>) -> Option<()> {
+        let started = Instant::now();
+        let result = f();
+        self.finish(step, started, result)
+    }
+
+    async fn run_async<T>(
+        &mut self,
+        step: &str,
+        future: impl Future<Output = AppResult<T>>,
+    ) -> Option<T> {
+        let started = Instant::now();
+        let result = future.await;
+        self.finish(step, started, result)
+    }
+
+    fn skip(&mut self, step: &str, reason: &str) {
+        self.push(
+            step,
+            StepStatus::Skipped,
+            Instant::now(),
+            Some(reason.to_string()),
+        );
+    }
+}
+
+/// スモークテストを実行する
+///
+/// 手順が失敗しても残りの手順とクリーンアップは実行します。
+///
+/// # 引数
+/// * `storage` - 領収書の保存先
+///
+/// # 戻り値
+/// 手順ごとの結果をまとめたレポート
+pub async fn run(storage: ReceiptStorage) -> SmokeTestReport {
+    let run_id = crate::shared::utils::nanoid::generate_user_id_with_length(12);
+    let started_at = get_current_jst_timestamp();
+    let started = Instant::now();
+    let mut recorder = StepRecorder { steps: Vec::new() };
+
+    log::info!("リリース前スモークテストを開始します: run_id={run_id}");
+
+    // 一時ディレクトリの作成に続いてマイグレーションを実行する（失敗時は破棄で削除される）
+    let migrations_started = Instant::now();
+    let setup = Workspace::create(&run_id).and_then(|workspace| {
+        let conn = open_and_migrate_database(&workspace.database_path())?;
+        Ok((workspace, conn))
+    });
+    let (workspace, conn) = recorder
+        .finish("migrations", migrations_started, setup)
+        .unzip();
+
+    const NO_DATABASE: &str = "マイグレーションに失敗したため実行しません";
+    match &conn {
+        Some(conn) => {
+            recorder.run("expense_crud", || expense_crud(conn));
+            recorder.run("subscription_crud", || subscription_crud(conn));
+            recorder.run("csv_export_import", || csv_export_import(conn));
+            recorder.run("json_export_import", || json_export_import(conn));
+        }
+        None => {
+            for step in [
+                "expense_crud",
+                "subscription_crud",
+                "csv_export_import",
+                "json_export_import",
+            ] {
+                recorder.skip(step, NO_DATABASE);
+            }
+        }
+    }
+
+    // 削除の手順が失敗した場合に備え、アップロードした領収書を記録しておく
+    let mut uploaded: Vec<String> = Vec::new();
+    let filename = format!("{RECEIPT_PREFIX}-{run_id}.png");
+    let upload = recorder
+        .run_async("receipt_upload", async {
+            let bytes = generate_receipt_image()?;
+            let receipt = storage.upload(&filename, &bytes).await?;
+            Ok((receipt, bytes))
+        })
+        .await;
+    if let Some((receipt, _)) = &upload {
+        uploaded.push(receipt.key.clone());
+    }
+
+    match &upload {
+        Some((receipt, expected)) => {
+            recorder
+                .run_async("receipt_download", async {
+                    let downloaded = storage.download(receipt).await?;
+                    if &downloaded != expected {
+                        return Err(AppError::validation(format!(
+                            "ダウンロードした領収書が一致しません: expected={}bytes, actual={}bytes",
+                            expected.len(),
+                            downloaded.len()
+                        )));
+                    }
+                    Ok(())
+                })
+                .await;
+            let deleted = recorder
+                .run_async("receipt_delete", async {
+                    if !storage.delete(&receipt.key).await? {
+                        return Err(AppError::NotFound(format!(
+                            "削除対象の領収書がありません: {}",
+                            receipt.key
+                        )));
+                    }
+                    if storage.exists(&receipt.key).await? == Some(true) {
+                        return Err(AppError::validation("削除後も領収書が残っています"));
+                    }
+                    Ok(())
+                })
+                .await;
+            if deleted.is_some() {
+                uploaded.clear();
+            }
+        }
+        None => {
+            let reason = "アップロードに失敗したため実行しません";
+            recorder.skip("receipt_download", reason);
+            recorder.skip("receipt_delete", reason);
+        }
+    }
+
+    match &storage {
+        ReceiptStorage::Remote { client, .. } => {
+            recorder
+                .run_async("health", async {
+                    let health = client.health_check_detailed().await?;
+                    if !health.is_healthy {
+                        return Err(AppError::ExternalService(format!(
+                            "APIサーバーが応答しません: status={}, error={:?}",
+                            health.status_code, health.error_message
+                        )));
+                    }
+                    Ok(())
+                })
+                .await;
+        }
+        ReceiptStorage::Mock(_) => {
+            recorder.skip(
+                "health",
+                "ログインしていないため、APIサーバーの確認は実行しません",
+            );
+        }
+    }
+
+    match &workspace {
+        Some(workspace) => {
+            recorder
+                .run_async("diagnostics", diagnostics(&workspace.dir))
+                .await;
+        }
+        None => recorder.skip("diagnostics", NO_DATABASE),
+    }
+
+    // ここからは失敗の有無にかかわらず必ず実行する
+    let cleanup_started = Instant::now();
+    let mut cleanup_errors = Vec::new();
+    for key in &uploaded {
+        if let Err(e) = storage.delete(key).await {
+            cleanup_errors.push(format!("領収書の削除に失敗しました({key}): {e}"));
+        }
+    }
+    drop(conn);
+    if let Some(workspace) = &workspace {
+        if let Err(e) = workspace.remove() {
+            cleanup_errors.push(format!("一時ディレクトリの削除に失敗しました: {e}"));
+        }
+    }
+    drop(workspace);
+    if cleanup_errors.is_empty() {
+        recorder.push("cleanup", StepStatus::Passed, cleanup_started, None);
+    } else {
+        recorder.push(
+            "cleanup",
+            StepStatus::Failed,
+            cleanup_started,
+            Some(cleanup_errors.join("; ")),
+        );
+    }
+
+    let passed = recorder
+        .steps
+        .iter()
+        .all(|step| step.status != StepStatus::Failed);
+    log::info!("リリース前スモークテストが完了しました: run_id={run_id}, passed={passed}");
+
+    SmokeTestReport {
+        format_version: REPORT_FORMAT_VERSION,
+        run_id,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: CURRENT_SCHEMA_VERSION.to_string(),
+        storage: storage.mode(),
+        started_at,
+        duration_ms: started.elapsed().as_millis() as u64,
+        passed,
+        steps: recorder.steps,
+    }
+}
+
+fn ensure(condition: bool, message: &str) -> AppResult<()> {
+    if condition {
+        Ok(())
+    } else {
+        Err(AppError::validation(message))
+    }
+}
+
+fn sample_expense(id: i64, amount: f64) -> AppResult<Expense> {
+    let timestamp = get_current_jst_timestamp();
+    Ok(serde_json::from_value(serde_json::json!({
+        "id": id,
+        "date": "2024-01-15",
+        "amount": amount,
+        "category": "交通費",
+        "category_id": null,
+        "description": "スモークテスト",
+        "receipt_url": null,
+        "created_at": timestamp,
+        "updated_at": timestamp,
+        "tax_rate": 0.1
+    }))?)
+}
+
+/// 経費の作成・更新・削除
+fn expense_crud(conn: &Connection) -> AppResult<()> {
+    let mut expense = sample_expense(1, 1_100.0)?;
+    sync::upsert_mirrored(conn, SMOKE_USER_ID, &expense)?;
+    let created = sync::get_mirrored(conn, SMOKE_USER_ID, expense.id)?;
+    ensure(
+        created.is_some_and(|created| created.amount == 1_100.0),
+        "作成した経費を取得できません",
+    )?;
+
+    expense.amount = 2_200.0;
+    sync::upsert_mirrored(conn, SMOKE_USER_ID, &expense)?;
+    let updated = sync::get_mirrored(conn, SMOKE_USER_ID, expense.id)?;
+    ensure(
+        updated.is_some_and(|updated| updated.pretax_amount() == 2_000.0),
+        "更新した経費が反映されていません",
+    )?;
+
+    ensure(
+        sync::remove_mirrored(conn, SMOKE_USER_ID, expense.id)?,
+        "経費を削除できません",
+    )?;
+    ensure(
+        sync::get_mirrored(conn, SMOKE_USER_ID, expense.id)?.is_none(),
+        "削除した経費が残っています",
+    )
+}
+
+fn insert_subscription(conn: &Connection, name: &str, amount: f64) -> AppResult<i64> {
+    let timestamp = get_current_jst_timestamp();
+    conn.execute(
+        "INSERT INTO subscriptions (name, amount, billing_cycle, start_date, category, is_active, user_id, created_at, updated_at)
+         VALUES (?1, ?2, 'monthly', '2024-01-01', 'その他', 1, ?3, ?4, ?4)",
+        params![name, amount, SMOKE_USER_ID, timestamp],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// サブスクリプションの作成・更新・削除
+fn subscription_crud(conn: &Connection) -> AppResult<()> {
+    let id = insert_subscription(conn, "スモークテスト", 980.0)?;
+    ensure(
+        subscription_repository::find_by_id(id, SMOKE_USER_ID, conn)?.is_some(),
+        "作成したサブスクリプションを取得できません",
+    )?;
+
+    conn.execute(
+        "UPDATE subscriptions SET amount = 1280, billing_cycle = 'annual', updated_at = ?2 WHERE id = ?1",
+        params![id, get_current_jst_timestamp()],
+    )?;
+    let updated = subscription_repository::find_by_id(id, SMOKE_USER_ID, conn)?;
+    ensure(
+        updated.is_some_and(|updated| {
+            updated.amount == 1_280.0 && updated.months_per_cycle().ok() == Some(12)
+        }),
+        "更新したサブスクリプションが反映されていません",
+    )?;
+
+    conn.execute("DELETE FROM subscriptions WHERE id = ?1", [id])?;
+    ensure(
+        subscription_repository::find_by_id(id, SMOKE_USER_ID, conn)?.is_none(),
+        "削除したサブスクリプションが残っています",
+    )
+}
+
+/// サブスクリプションのCSVエクスポートと再取り込み
+fn csv_export_import(conn: &Connection) -> AppResult<()> {
+    let ids = [
+        insert_subscription(conn, "スモークテストA", 500.0)?,
+        insert_subscription(conn, "スモークテストB", 1_500.0)?,
+    ];
+    let subscriptions = ids
+        .iter()
+        .map(|&id| {
+            subscription_repository::find_by_id(id, SMOKE_USER_ID, conn)?
+                .ok_or_else(|| AppError::NotFound(format!("サブスクリプションがありません: {id}")))
+        })
+        .collect::<AppResult<Vec<_>>>()?;
+
+    let mut exported = Vec::new();
+    write_csv_export(
+        &mut exported,
+        &ExportMeta::current(Some(SMOKE_USER_ID)),
+        |w| {
+            writeln!(w, "name,price,period,start_date,category")?;
+            for subscription in &subscriptions {
+                writeln!(
+                    w,
+                    "{},{},{},{},{}",
+                    subscription.name,
+                    subscription.amount,
+                    subscription.billing_cycle,
+                    subscription.start_date,
+                    subscription.category
+                )?;
+            }
+            Ok(())
+        },
+    )?;
+
+    let content = String::from_utf8(exported)
+        .map_err(|e| AppError::validation(format!("CSVの文字コードが不正です: {e}")))?;
+    let candidates = parse_export_content(&content, SubscriptionExportSource::Generic)?;
+    ensure(
+        candidates.len() == subscriptions.len()
+            && candidates.iter().all(|candidate| candidate.is_valid())
+            && candidates
+                .iter()
+                .zip(&subscriptions)
+                .all(|(candidate, subscription)| {
+                    candidate.dto.name == subscription.name
+                        && candidate.dto.amount == subscription.amount
+                }),
+        "再取り込みしたサブスクリプションがエクスポート前と一致しません",
+    )?;
+
+    conn.execute(
+        "DELETE FROM subscriptions WHERE id IN (?1, ?2)",
+        params![ids[0], ids[1]],
+    )?;
+    Ok(())
+}
+
+/// 経費のJSONエクスポートと再取り込み
+fn json_export_import(conn: &Connection) -> AppResult<()> {
+    for (id, amount) in [(10, 1_080.0), (11, 13_200.0)] {
+        sync::upsert_mirrored(conn, SMOKE_USER_ID, &sample_expense(id, amount)?)?;
+    }
+    let expenses = sync::list_mirrored(conn, SMOKE_USER_ID, None, &ExpenseFilter::default())?;
+
+    let exported = serde_json::to_string(&wrap_json_export(
+        &ExportMeta::current(Some(SMOKE_USER_ID)),
+        &expenses,
+    )?)?;
+    let verified = read_json_export(serde_json::from_str(&exported)?)?;
+    let imported: Vec<Expense> = serde_json::from_value(verified.body)?;
+
+    let import_user = format!("{SMOKE_USER_ID}-import");
+    for expense in &imported {
+        sync::upsert_mirrored(conn, &import_user, expense)?;
+    }
+    let reimported = sync::list_mirrored(conn, &import_user, None, &ExpenseFilter::default())?;
+    ensure(
+        reimported.len() == expenses.len()
+            && reimported
+                .iter()
+                .zip(&expenses)
+                .all(|(a, b)| a.id == b.id && a.amount == b.amount),
+        "再取り込みした経費がエクスポート前と一致しません",
+    )?;
+
+    conn.execute(
+        "DELETE FROM expense_mirror WHERE user_id IN (?1, ?2)",
+        params![SMOKE_USER_ID, import_user],
+    )?;
+    Ok(())
+}
+
+/// 診断情報の取得
+async fn diagnostics(dir: &Path) -> AppResult<()> {
+    let info = super::commands::get_system_diagnostic_info()
+        .await
+        .map_err(AppError::ExternalService)?;
+    ensure(
+        info.get("system").and_then(|value| value.as_str()) == Some("OK"),
+        "システム診断情報が正常ではありません",
+    )?;
+    available_space(dir)?;
+    Ok(())
+}
+
+/// アップロードする領収書画像を生成する
+fn generate_receipt_image() -> AppResult<Vec<u8>> {
+    let image = RgbImage::from_fn(16, 16, |x, y| Rgb([(x * 16) as u8, (y * 16) as u8, 128]));
+    let mut output = Cursor::new(Vec::new());
+    image
+        .write_to(&mut output, ImageFormat::Png)
+        .map_err(|e| AppError::validation(format!("領収書画像の生成に失敗しました: {e}")))?;
+    Ok(output.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step<'a>(report: &'a SmokeTestReport, name: &str) -> &'a StepReport {
+        report
+            .steps
+            .iter()
+            .find(|step| step.step == name)
+            .unwrap_or_else(|| panic!("手順がありません: {name}"))
+    }
+
+    #[tokio::test]
+    async fn test_smoke_test_passes_with_mock_storage() {
+        let report = run(ReceiptStorage::mock()).await;
+
+        let failures: Vec<_> = report
+            .steps
+            .iter()
+            .filter(|step| step.status == StepStatus::Failed)
+            .collect();
+        assert!(failures.is_empty(), "{failures:?}");
+        assert!(report.passed);
+        assert_eq!(report.storage, StorageMode::Mock);
+        assert_eq!(step(&report, "health").status, StepStatus::Skipped);
+        assert_eq!(step(&report, "receipt_delete").status, StepStatus::Passed);
+
+        // 一時ディレクトリは削除されている
+        let dir = std::env::temp_dir().join(format!("orano-keihi-smoke-{}", report.run_id));
+        assert!(!dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_report_lists_every_step_in_order() {
+        let report = run(ReceiptStorage::mock()).await;
+        let steps: Vec<_> = report.steps.iter().map(|step| step.step.as_str()).collect();
+        assert_eq!(
+            steps,
+            [
+                "migrations",
+                "expense_crud",
+                "subscription_crud",
+                "csv_export_import",
+                "json_export_import",
+                "receipt_upload",
+                "receipt_download",
+                "receipt_delete",
+                "health",
+                "diagnostics",
+                "cleanup",
+            ]
+        );
+
+        let value = serde_json::to_value(&report).unwrap();
+        assert_eq!(value["format_version"], REPORT_FORMAT_VERSION);
+        assert_eq!(value["storage"], "mock");
+        assert_eq!(value["steps"][0]["status"], "passed");
+    }
+
+    #[tokio::test]
+    async fn test_mock_storage_round_trip() {
+        let storage = ReceiptStorage::mock();
+        let bytes = generate_receipt_image().unwrap();
+        let receipt = storage.upload("leftover.png", &bytes).await.unwrap();
+        assert_eq!(storage.download(&receipt).await.unwrap(), bytes);
+        assert!(storage.delete(&receipt.key).await.unwrap());
+        assert_eq!(storage.exists(&receipt.key).await.unwrap(), Some(false));
+        assert!(!storage.delete(&receipt.key).await.unwrap());
+    }
+
+    #[test]
+    fn test_workspace_is_removed_on_drop() {
+        let workspace = Workspace::create("drop-test").unwrap();
+        let dir = workspace.dir.clone();
+        std::fs::write(workspace.database_path(), b"test").unwrap();
+        drop(workspace);
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,102 @@
+use super::config::{app_data_file, StartupSettings, UpdateStartupSettingsDto};
+use super::login_item::LoginItem;
+use super::session::{self, SessionState};
+use super::tray;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::utils::jst_datetime;
+use chrono::Utc;
+use log::{debug, info};
+use tauri::AppHandle;
+
+/// 前回の画面の状態のファイル名
+const SESSION_FILE_NAME: &str = "session_state.json";
+
+/// 保存した設定にOSのログイン時の起動の登録状態を加える
+fn with_login_state(
+    app_handle: &AppHandle,
+    settings: StartupSettings,
+) -> AppResult<StartupSettings> {
+    Ok(StartupSettings {
+        launch_at_login: LoginItem::for_app(app_handle)?.is_enabled()?,
+        ..settings
+    })
+}
+
+/// 起動時の動作の設定を取得するコマンド
+///
+/// `launch_at_login`はOSに登録されている実際の状態を返します。
+#[tauri::command]
+pub fn get_startup_settings(app_handle: AppHandle) -> Result<StartupSettings, String> {
+    with_login_state(&app_handle, StartupSettings::load(&app_handle)).map_err(|e| e.to_string())
+}
+
+/// 起動時の動作の設定を更新するコマンド
+///
+/// ログイン時の起動はすぐにOSに登録し、トレイアイコンの表示も再起動せずに切り替えます。
+#[tauri::command]
+pub fn update_startup_settings(
+    app_handle: AppHandle,
+    dto: UpdateStartupSettingsDto,
+) -> Result<StartupSettings, String> {
+    info!("起動時の動作の設定を更新します: {dto:?}");
+    update_settings(&app_handle, &dto).map_err(|e| e.to_string())
+}
+
+fn update_settings(
+    app_handle: &AppHandle,
+    dto: &UpdateStartupSettingsDto,
+) -> AppResult<StartupSettings> {
+    if let Some(enabled) = dto.launch_at_login {
+        LoginItem::for_app(app_handle)?.set_enabled(enabled)?;
+    }
+
+    let mut settings = StartupSettings::load(app_handle);
+    settings.apply(dto);
+    settings.save(app_handle)?;
+
+    if dto.start_minimized.is_some() {
+        if settings.start_minimized {
+            tray::show_tray(app_handle).map_err(|e| {
+                AppError::configuration(format!("トレイアイコンを表示できません: {e}"))
+            })?;
+        } else {
+            tray::remove_tray(app_handle);
+        }
+    }
+    if dto.restore_last_session == Some(false) {
+        session::clear(&app_data_file(app_handle, SESSION_FILE_NAME)?)?;
+    }
+
+    with_login_state(app_handle, settings)
+}
+
+/// 表示中の画面と絞り込み条件を保存するコマンド
+///
+/// 前回の画面の復元が無効な場合は保存しません。
+#[tauri::command]
+pub fn save_session_state(app_handle: AppHandle, state: SessionState) -> Result<(), String> {
+    if !StartupSettings::load(&app_handle).restore_last_session {
+        debug!("前回の画面の復元が無効なため、画面の状態を保存しません");
+        return Ok(());
+    }
+
+    let state = SessionState {
+        saved_at: Some(jst_datetime::format(&Utc::now())),
+        ..state
+    };
+    app_data_file(&app_handle, SESSION_FILE_NAME)
+        .and_then(|path| session::save(&path, &state))
+        .map_err(|e| e.to_string())
+}
+
+/// 前回表示していた画面と絞り込み条件を取得するコマンド
+///
+/// 前回の画面の復元が無効な場合や、保存されていない場合はnullを返します。
+#[tauri::command]
+pub fn get_session_state(app_handle: AppHandle) -> Result<Option<SessionState>, String> {
+    if !StartupSettings::load(&app_handle).restore_last_session {
+        return Ok(None);
+    }
+    let path = app_data_file(&app_handle, SESSION_FILE_NAME).map_err(|e| e.to_string())?;
+    Ok(session::load(&path))
+}
@@ -0,0 +1,162 @@
+use crate::shared::errors::{AppError, AppResult};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// 起動時の動作の設定ファイル名
+const SETTINGS_FILE_NAME: &str = "startup_settings.json";
+
+/// 起動時の動作の設定
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StartupSettings {
+    /// 起動時にメインウィンドウを表示せず、トレイアイコンのみを表示する
+    #[serde(default)]
+    pub start_minimized: bool,
+    /// ログイン時に起動する
+    ///
+    /// 利用者がOSの設定から変更する場合もあるため、保存せずに取得時にOSの登録状態を設定します。
+    #[serde(default, skip_deserializing)]
+    pub launch_at_login: bool,
+    /// 起動時に前回表示していた画面と絞り込み条件を復元する
+    #[serde(default)]
+    pub restore_last_session: bool,
+}
+
+/// 起動時の動作の設定を更新するためのDTO（指定した項目のみ更新）
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateStartupSettingsDto {
+    pub start_minimized: Option<bool>,
+    pub launch_at_login: Option<bool>,
+    pub restore_last_session: Option<bool>,
+}
+
+/// アプリデータディレクトリ内のファイルのパスを取得する
+///
+/// # 引数
+/// * `app_handle` - Tauriアプリケーションハンドル
+/// * `file_name` - ファイル名
+pub(super) fn app_data_file(app_handle: &AppHandle, file_name: &str) -> AppResult<PathBuf> {
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| {
+        AppError::configuration(format!("アプリデータディレクトリの取得に失敗: {e}"))
+    })?;
+    if !app_data_dir.exists() {
+        fs::create_dir_all(&app_data_dir)?;
+    }
+    Ok(app_data_dir.join(file_name))
+}
+
+impl StartupSettings {
+    /// 設定を読み込む（読み込めない場合はデフォルト設定）
+    ///
+    /// # 引数
+    /// * `app_handle` - Tauriアプリケーションハンドル
+    pub fn load(app_handle: &AppHandle) -> Self {
+        match app_data_file(app_handle, SETTINGS_FILE_NAME) {
+            Ok(path) => Self::load_from(&path),
+            Err(e) => {
+                warn!("起動時の動作の設定ファイルのパスを取得できません: {e}");
+                Self::default()
+            }
+        }
+    }
+
+    /// 指定したファイルから設定を読み込む
+    pub fn load_from(path: &Path) -> Self {
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!("起動時の動作の設定の解析に失敗、デフォルト設定を使用: {e}");
+                Self::default()
+            }),
+            Err(e) => {
+                debug!("起動時の動作の設定ファイルを読み込めないため、デフォルト設定を使用: {e}");
+                Self::default()
+            }
+        }
+    }
+
+    /// 設定を保存する
+    ///
+    /// # 引数
+    /// * `app_handle` - Tauriアプリケーションハンドル
+    pub fn save(&self, app_handle: &AppHandle) -> AppResult<()> {
+        self.save_to(&app_data_file(app_handle, SETTINGS_FILE_NAME)?)
+    }
+
+    /// 指定したファイルに設定を保存する
+    pub fn save_to(&self, path: &Path) -> AppResult<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        info!("起動時の動作の設定を保存しました: {}", path.display());
+        Ok(())
+    }
+
+    /// DTOで指定された項目を反映する（ログイン時の起動は呼び出し元でOSに登録する）
+    pub fn apply(&mut self, dto: &UpdateStartupSettingsDto) {
+        if let Some(start_minimized) = dto.start_minimized {
+            self.start_minimized = start_minimized;
+        }
+        if let Some(restore_last_session) = dto.restore_last_session {
+            self.restore_last_session = restore_last_session;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_missing_file_returns_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = StartupSettings::load_from(&dir.path().join("missing.json"));
+        assert_eq!(settings, StartupSettings::default());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SETTINGS_FILE_NAME);
+        let settings = StartupSettings {
+            start_minimized: true,
+            launch_at_login: true,
+            restore_last_session: true,
+        };
+        settings.save_to(&path).unwrap();
+
+        // ログイン時の起動はOSの登録状態を使用するため読み込まない
+        let loaded = StartupSettings::load_from(&path);
+        assert!(loaded.start_minimized);
+        assert!(loaded.restore_last_session);
+        assert!(!loaded.launch_at_login);
+    }
+
+    #[test]
+    fn test_load_invalid_file_returns_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SETTINGS_FILE_NAME);
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(
+            StartupSettings::load_from(&path),
+            StartupSettings::default()
+        );
+    }
+
+    #[test]
+    fn test_apply_updates_only_given_fields() {
+        let mut settings = StartupSettings {
+            start_minimized: true,
+            launch_at_login: false,
+            restore_last_session: true,
+        };
+        settings.apply(&UpdateStartupSettingsDto {
+            restore_last_session: Some(false),
+            launch_at_login: Some(true),
+            ..Default::default()
+        });
+
+        assert!(settings.start_minimized);
+        assert!(!settings.restore_last_session);
+        assert!(!settings.launch_at_login);
+    }
+}
@@ -0,0 +1,407 @@
+//! ログイン時の起動の登録
+//!
+//! OSごとの仕組みでログイン時にアプリケーションを起動するよう登録します。
+//! - macOS: `~/Library/LaunchAgents`のLaunchAgent
+//! - Windows: `HKCU\Software\Microsoft\Windows\CurrentVersion\Run`
+//! - Linux: `~/.config/autostart`のデスクトップエントリ（XDG Autostart）
+//!
+//! 利用者はOSの設定からも無効にできるため、登録状態は登録内容とOS側の無効化の両方から判定します。
+
+use crate::shared::errors::{AppError, AppResult};
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+/// ログイン時に起動する項目
+#[derive(Debug, Clone)]
+pub struct LoginItem {
+    /// アプリケーションの識別子（例: `com.tsucchinoko.orano-keihi`）
+    identifier: String,
+    /// アプリケーション名
+    name: String,
+    /// 起動する実行ファイル
+    executable: PathBuf,
+}
+
+impl LoginItem {
+    /// 実行中のアプリケーションの項目を作成する
+    pub fn for_app(app_handle: &AppHandle) -> AppResult<Self> {
+        Ok(Self {
+            identifier: app_handle.config().identifier.clone(),
+            name: app_handle.package_info().name.clone(),
+            executable: std::env::current_exe()?,
+        })
+    }
+
+    /// ログイン時に起動するよう登録されているか（OSの設定で無効にされている場合はfalse）
+    pub fn is_enabled(&self) -> AppResult<bool> {
+        platform::is_enabled(self)
+    }
+
+    /// ログイン時に起動するよう登録・登録解除する
+    ///
+    /// 登録後にOSの設定で無効にされている場合は、設定の変更方法を含むエラーを返します。
+    pub fn set_enabled(&self, enabled: bool) -> AppResult<()> {
+        platform::set_enabled(self, enabled)?;
+        if enabled && !self.is_enabled()? {
+            return Err(AppError::configuration(
+                platform::DISABLED_BY_SYSTEM_MESSAGE,
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 書き込み権限がない場合のエラー
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn permission_error(message: &str, error: std::io::Error) -> AppError {
+    if error.kind() == std::io::ErrorKind::PermissionDenied {
+        AppError::configuration(message)
+    } else {
+        error.into()
+    }
+}
+
+/// LaunchAgentの内容を作成する
+#[cfg(any(target_os = "macos", test))]
+fn launch_agent_plist(label: &str, executable: &str) -> String {
+    let escape = |value: &str| {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        escape(label),
+        escape(executable)
+    )
+}
+
+/// `launchctl print-disabled`の出力から、項目が無効にされているかを判定する
+#[cfg(any(target_os = "macos", test))]
+fn is_disabled_in_launchctl(output: &str, label: &str) -> bool {
+    let quoted = format!("\"{label}\"");
+    output.lines().any(|line| {
+        let line = line.trim();
+        line.starts_with(&quoted)
+            && line
+                .split("=>")
+                .nth(1)
+                .is_some_and(|state| matches!(state.trim(), "disabled" | "true"))
+    })
+}
+
+/// `reg query`で取得したStartupApprovedの値から、スタートアップが無効にされているかを判定する
+///
+/// 値の先頭バイトが奇数（`03`など）の場合、タスクマネージャーや設定アプリで無効にされています。
+#[cfg(any(target_os = "windows", test))]
+fn is_disabled_in_startup_approved(output: &str) -> bool {
+    output
+        .lines()
+        .find_map(|line| {
+            let mut columns = line.split_whitespace().skip_while(|c| *c != "REG_BINARY");
+            columns.next()?;
+            let value = columns.next()?;
+            u8::from_str_radix(value.get(0..2)?, 16).ok()
+        })
+        .is_some_and(|flag| flag & 1 == 1)
+}
+
+/// XDG Autostartのデスクトップエントリを作成する
+#[cfg(any(target_os = "linux", test))]
+fn desktop_entry(name: &str, executable: &str) -> String {
+    format!(
+        "[Desktop Entry]\nType=Application\nName={name}\nExec=\"{}\"\nX-GNOME-Autostart-enabled=true\n",
+        executable.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+/// デスクトップエントリが有効かどうか（`Hidden=true`などで無効にされていないか）
+#[cfg(any(target_os = "linux", test))]
+fn is_desktop_entry_enabled(contents: &str) -> bool {
+    !contents.lines().map(str::trim).any(|line| {
+        matches!(
+            line.replace(' ', "").as_str(),
+            "Hidden=true" | "X-GNOME-Autostart-enabled=false"
+        )
+    })
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+
+    pub const DISABLED_BY_SYSTEM_MESSAGE: &str = "ログイン時の起動がシステム設定で無効になっています。システム設定の「一般」>「ログイン項目」で、このアプリのバックグラウンドでの実行を許可してください";
+
+    const PERMISSION_MESSAGE: &str = "ログイン項目を登録できません。システム設定の「一般」>「ログイン項目」で、このアプリのバックグラウンドでの実行を許可してから、もう一度お試しください";
+
+    fn plist_path(item: &LoginItem) -> AppResult<PathBuf> {
+        let home = dirs::home_dir().ok_or_else(|| {
+            AppError::configuration("ホームディレクトリを取得できません".to_string())
+        })?;
+        Ok(home
+            .join("Library/LaunchAgents")
+            .join(format!("{}.plist", item.identifier)))
+    }
+
+    fn launchctl_domain() -> String {
+        // SAFETY: getuidは常に成功し、副作用がない
+        format!("gui/{}", unsafe { libc::getuid() })
+    }
+
+    pub fn is_enabled(item: &LoginItem) -> AppResult<bool> {
+        if !plist_path(item)?.exists() {
+            return Ok(false);
+        }
+        let output = Command::new("launchctl")
+            .args(["print-disabled", &launchctl_domain()])
+            .output()?;
+        Ok(!is_disabled_in_launchctl(
+            &String::from_utf8_lossy(&output.stdout),
+            &item.identifier,
+        ))
+    }
+
+    pub fn set_enabled(item: &LoginItem, enabled: bool) -> AppResult<()> {
+        let path = plist_path(item)?;
+        if !enabled {
+            return match fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    Err(permission_error(PERMISSION_MESSAGE, e))
+                }
+                _ => Ok(()),
+            };
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| permission_error(PERMISSION_MESSAGE, e))?;
+        }
+        let plist = launch_agent_plist(&item.identifier, &item.executable.to_string_lossy());
+        fs::write(&path, plist).map_err(|e| permission_error(PERMISSION_MESSAGE, e))?;
+
+        // 以前にOS側で無効にされた項目を有効に戻す（許可されていない場合は失敗する）
+        let status = Command::new("launchctl")
+            .args([
+                "enable",
+                &format!("{}/{}", launchctl_domain(), item.identifier),
+            ])
+            .status()?;
+        if !status.success() {
+            return Err(AppError::configuration(PERMISSION_MESSAGE));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use std::process::{Command, Output};
+
+    pub const DISABLED_BY_SYSTEM_MESSAGE: &str = "ログイン時の起動がWindowsの設定で無効になっています。設定アプリの「アプリ」>「スタートアップ」またはタスクマネージャーの「スタートアップ」タブで、このアプリを有効にしてください";
+
+    const PERMISSION_MESSAGE: &str = "スタートアップに登録できません。グループポリシーまたはセキュリティソフトによって制限されている可能性があります。管理者に確認してください";
+
+    const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+
+    const STARTUP_APPROVED_KEY: &str =
+        r"HKCU\Software\Microsoft\Windows\CurrentVersion\Explorer\StartupApproved\Run";
+
+    fn reg(args: &[&str]) -> AppResult<Output> {
+        Ok(Command::new("reg").args(args).output()?)
+    }
+
+    fn is_access_denied(output: &Output) -> bool {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_lowercase();
+        stderr.contains("access is denied") || stderr.contains("アクセスが拒否")
+    }
+
+    pub fn is_enabled(item: &LoginItem) -> AppResult<bool> {
+        if !reg(&["query", RUN_KEY, "/v", &item.name])?.status.success() {
+            return Ok(false);
+        }
+        let approved = reg(&["query", STARTUP_APPROVED_KEY, "/v", &item.name])?;
+        Ok(!(approved.status.success()
+            && is_disabled_in_startup_approved(&String::from_utf8_lossy(&approved.stdout))))
+    }
+
+    pub fn set_enabled(item: &LoginItem, enabled: bool) -> AppResult<()> {
+        let output = if enabled {
+            let command = format!("\"{}\"", item.executable.display());
+            let output = reg(&[
+                "add", RUN_KEY, "/v", &item.name, "/t", "REG_SZ", "/d", &command, "/f",
+            ])?;
+            // 以前に設定アプリで無効にされた状態を解除する（値がない場合は失敗するが問題ない）
+            if output.status.success() {
+                let _ = reg(&["delete", STARTUP_APPROVED_KEY, "/v", &item.name, "/f"]);
+            }
+            output
+        } else {
+            if !reg(&["query", RUN_KEY, "/v", &item.name])?.status.success() {
+                return Ok(());
+            }
+            reg(&["delete", RUN_KEY, "/v", &item.name, "/f"])?
+        };
+
+        if !output.status.success() {
+            if is_access_denied(&output) {
+                return Err(AppError::configuration(PERMISSION_MESSAGE));
+            }
+            return Err(AppError::configuration(format!(
+                "スタートアップの登録を変更できません: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+    use std::fs;
+
+    pub const DISABLED_BY_SYSTEM_MESSAGE: &str = "ログイン時の起動がデスクトップ環境の設定で無効になっています。「自動起動するアプリケーション」の設定で、このアプリを有効にしてください";
+
+    const PERMISSION_MESSAGE: &str =
+        "自動起動の設定ファイルに書き込めません。~/.config/autostart の権限を確認してください";
+
+    fn entry_path(item: &LoginItem) -> AppResult<PathBuf> {
+        let config_dir = dirs::config_dir().ok_or_else(|| {
+            AppError::configuration("設定ディレクトリを取得できません".to_string())
+        })?;
+        Ok(config_dir
+            .join("autostart")
+            .join(format!("{}.desktop", item.identifier)))
+    }
+
+    pub fn is_enabled(item: &LoginItem) -> AppResult<bool> {
+        match fs::read_to_string(entry_path(item)?) {
+            Ok(contents) => Ok(is_desktop_entry_enabled(&contents)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn set_enabled(item: &LoginItem, enabled: bool) -> AppResult<()> {
+        let path = entry_path(item)?;
+        if !enabled {
+            return match fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    Err(permission_error(PERMISSION_MESSAGE, e))
+                }
+                _ => Ok(()),
+            };
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| permission_error(PERMISSION_MESSAGE, e))?;
+        }
+        let entry = desktop_entry(&item.name, &item.executable.to_string_lossy());
+        fs::write(&path, entry).map_err(|e| permission_error(PERMISSION_MESSAGE, e))
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod platform {
+    use super::*;
+
+    pub const DISABLED_BY_SYSTEM_MESSAGE: &str = "このOSではログイン時の起動に対応していません";
+
+    pub fn is_enabled(_item: &LoginItem) -> AppResult<bool> {
+        Ok(false)
+    }
+
+    pub fn set_enabled(_item: &LoginItem, enabled: bool) -> AppResult<()> {
+        if enabled {
+            return Err(AppError::configuration(DISABLED_BY_SYSTEM_MESSAGE));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_launch_agent_plist() {
+        let plist = launch_agent_plist(
+            "com.tsucchinoko.orano-keihi",
+            "/Applications/Tom & Jerry.app/Contents/MacOS/orano-keihi",
+        );
+        assert!(plist.contains("<string>com.tsucchinoko.orano-keihi</string>"));
+        assert!(plist.contains("Tom &amp; Jerry.app"));
+        assert!(plist.contains("<key>RunAtLoad</key>\n    <true/>"));
+    }
+
+    #[test]
+    fn test_is_disabled_in_launchctl() {
+        let output = r#"disabled services = {
+	"com.apple.ScreenReaderUIServer" => disabled
+	"com.tsucchinoko.orano-keihi" => disabled
+	"com.example.other" => enabled
+}"#;
+        assert!(is_disabled_in_launchctl(
+            output,
+            "com.tsucchinoko.orano-keihi"
+        ));
+        assert!(!is_disabled_in_launchctl(output, "com.example.other"));
+        assert!(!is_disabled_in_launchctl(output, "com.example.missing"));
+
+        // 古いmacOSはtrue/falseで出力する
+        assert!(is_disabled_in_launchctl(
+            "\t\"com.tsucchinoko.orano-keihi\" => true",
+            "com.tsucchinoko.orano-keihi"
+        ));
+        // 前方一致する別の項目は対象外
+        assert!(!is_disabled_in_launchctl(
+            "\t\"com.tsucchinoko.orano-keihi.helper\" => disabled",
+            "com.tsucchinoko.orano-keihi"
+        ));
+    }
+
+    #[test]
+    fn test_is_disabled_in_startup_approved() {
+        let enabled = "\r\nHKEY_CURRENT_USER\\...\\StartupApproved\\Run\r\n    orano-keihi    REG_BINARY    020000000000000000000000\r\n";
+        let disabled = "\r\nHKEY_CURRENT_USER\\...\\StartupApproved\\Run\r\n    orano-keihi    REG_BINARY    0300000066AF9C7C5A46D901\r\n";
+        assert!(!is_disabled_in_startup_approved(enabled));
+        assert!(is_disabled_in_startup_approved(disabled));
+        assert!(!is_disabled_in_startup_approved(""));
+    }
+
+    #[test]
+    fn test_desktop_entry() {
+        let entry = desktop_entry("orano-keihi", "/opt/orano keihi/orano-keihi");
+        assert!(entry.starts_with("[Desktop Entry]\n"));
+        assert!(entry.contains("Exec=\"/opt/orano keihi/orano-keihi\"\n"));
+        assert!(is_desktop_entry_enabled(&entry));
+    }
+
+    #[test]
+    fn test_is_desktop_entry_enabled() {
+        assert!(!is_desktop_entry_enabled(
+            "[Desktop Entry]\nX-GNOME-Autostart-enabled=false\n"
+        ));
+        assert!(!is_desktop_entry_enabled(
+            "[Desktop Entry]\nHidden = true\n"
+        ));
+        assert!(is_desktop_entry_enabled("[Desktop Entry]\nHidden=false\n"));
+    }
+}
@@ -0,0 +1,50 @@
+/// 起動時の動作機能モジュール
+///
+/// トレイアイコンのみを表示した起動、ログイン時の起動、前回の画面の復元を提供します。
+pub mod commands;
+pub mod config;
+pub mod login_item;
+pub mod session;
+pub mod tray;
+
+pub use config::{StartupSettings, UpdateStartupSettingsDto};
+pub use session::SessionState;
+
+use crate::shared::capabilities::Capability;
+use log::{info, warn};
+use tauri::AppHandle;
+
+/// 起動時の動作の利用者向け機能
+pub const CAPABILITIES: &[Capability] = &[
+    Capability::new(
+        "startup.update_settings",
+        "capability.startup.update_settings",
+        "update_startup_settings",
+    )
+    .mutating()
+    .internal(),
+    Capability::new(
+        "startup.save_session_state",
+        "capability.startup.save_session_state",
+        "save_session_state",
+    )
+    .mutating()
+    .internal(),
+];
+
+/// 起動時の動作の設定を反映する
+///
+/// メインウィンドウは非表示で作成されるため、トレイアイコンのみで起動する場合以外は表示します。
+/// トレイアイコンを表示できない場合は、操作できなくなるのを防ぐためウィンドウを表示します。
+pub fn apply_startup_settings(app_handle: &AppHandle) {
+    if StartupSettings::load(app_handle).start_minimized {
+        match tray::show_tray(app_handle) {
+            Ok(()) => {
+                info!("トレイアイコンのみを表示して起動します");
+                return;
+            }
+            Err(e) => warn!("トレイアイコンを表示できないため、ウィンドウを表示します: {e}"),
+        }
+    }
+    tray::show_main_window(app_handle);
+}
@@ -0,0 +1,169 @@
+use crate::shared::errors::{AppError, AppResult};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+
+/// 画面のパスの最大長
+const MAX_ROUTE_LENGTH: usize = 512;
+
+/// 絞り込み条件の最大サイズ（JSONのバイト数）
+const MAX_FILTERS_BYTES: usize = 16 * 1024;
+
+/// 前回表示していた画面の状態
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionState {
+    /// 表示していた画面のパス（例: `/expenses`）
+    pub route: String,
+    /// 画面の絞り込み条件（フロントエンドが報告した内容をそのまま保存する）
+    #[serde(default)]
+    pub filters: Map<String, Value>,
+    /// 保存日時（RFC3339形式、JST）
+    #[serde(default)]
+    pub saved_at: Option<String>,
+}
+
+impl SessionState {
+    /// 保存する状態を検証する
+    ///
+    /// 画面のパスはアプリ内のパスのみ受け付けます。
+    pub fn validate(&self) -> AppResult<()> {
+        let route = self.route.trim();
+        if !route.starts_with('/') || route.starts_with("//") || route.contains("://") {
+            return Err(AppError::validation(format!(
+                "画面のパスが不正です: {}",
+                self.route
+            )));
+        }
+        if route.len() > MAX_ROUTE_LENGTH {
+            return Err(AppError::validation(format!(
+                "画面のパスは{MAX_ROUTE_LENGTH}文字以内で指定してください"
+            )));
+        }
+        if serde_json::to_vec(&self.filters)?.len() > MAX_FILTERS_BYTES {
+            return Err(AppError::validation(format!(
+                "絞り込み条件は{}KB以内で指定してください",
+                MAX_FILTERS_BYTES / 1024
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// 保存した状態を読み込む
+///
+/// # 戻り値
+/// 保存した状態（保存されていない場合や読み込めない場合はNone）
+pub fn load(path: &Path) -> Option<SessionState> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            debug!("前回の画面の状態を読み込めません: {e}");
+            return None;
+        }
+    };
+    match serde_json::from_str::<SessionState>(&content) {
+        Ok(state) if state.validate().is_ok() => Some(state),
+        Ok(_) => {
+            warn!("前回の画面の状態が不正なため復元しません");
+            None
+        }
+        Err(e) => {
+            warn!("前回の画面の状態の解析に失敗: {e}");
+            None
+        }
+    }
+}
+
+/// 状態を保存する
+pub fn save(path: &Path, state: &SessionState) -> AppResult<()> {
+    state.validate()?;
+    fs::write(path, serde_json::to_string(state)?)?;
+    Ok(())
+}
+
+/// 保存した状態を削除する
+pub fn clear(path: &Path) -> AppResult<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn state(route: &str) -> SessionState {
+        SessionState {
+            route: route.to_string(),
+            filters: json!({ "month": "2026-10", "category": "交通費" })
+                .as_object()
+                .unwrap()
+                .clone(),
+            saved_at: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_route() {
+        assert!(state("/").validate().is_ok());
+        assert!(state("/expenses?month=2026-10").validate().is_ok());
+
+        assert!(state("expenses").validate().is_err());
+        assert!(state("").validate().is_err());
+        assert!(state("//evil.example.com").validate().is_err());
+        assert!(state("/redirect?to=https://evil.example.com")
+            .validate()
+            .is_err());
+        assert!(state(&format!("/{}", "a".repeat(MAX_ROUTE_LENGTH)))
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_validate_filters_size() {
+        let mut large = state("/expenses");
+        large
+            .filters
+            .insert("query".to_string(), json!("a".repeat(MAX_FILTERS_BYTES)));
+        assert!(large.validate().is_err());
+    }
+
+    #[test]
+    fn test_save_load_and_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session_state.json");
+        assert_eq!(load(&path), None);
+
+        let saved = SessionState {
+            saved_at: Some("2026-10-15T10:00:00+09:00".to_string()),
+            ..state("/expenses")
+        };
+        save(&path, &saved).unwrap();
+        assert_eq!(load(&path), Some(saved));
+
+        clear(&path).unwrap();
+        assert_eq!(load(&path), None);
+        // 保存されていない場合も成功する
+        clear(&path).unwrap();
+    }
+
+    #[test]
+    fn test_save_rejects_invalid_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session_state.json");
+        assert!(save(&path, &state("https://evil.example.com")).is_err());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_load_ignores_tampered_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session_state.json");
+        fs::write(&path, r#"{"route":"//evil.example.com","filters":{}}"#).unwrap();
+        assert_eq!(load(&path), None);
+    }
+}
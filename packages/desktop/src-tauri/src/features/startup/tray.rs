@@ -0,0 +1,76 @@
+use log::warn;
+use tauri::menu::{MenuBuilder, MenuItemBuilder};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager};
+
+/// トレイアイコンのID
+const TRAY_ID: &str = "main-tray";
+
+/// メインウィンドウのラベル
+const MAIN_WINDOW_LABEL: &str = "main";
+
+/// トレイアイコンを表示する（表示済みの場合は何もしない）
+pub fn show_tray(app_handle: &AppHandle) -> tauri::Result<()> {
+    if app_handle.tray_by_id(TRAY_ID).is_some() {
+        return Ok(());
+    }
+
+    let menu = MenuBuilder::new(app_handle)
+        .item(
+            &MenuItemBuilder::new("ウィンドウを表示")
+                .id("tray_show_window")
+                .build(app_handle)?,
+        )
+        .separator()
+        .item(
+            &MenuItemBuilder::new("終了")
+                .id("tray_quit")
+                .build(app_handle)?,
+        )
+        .build()?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(&app_handle.package_info().name)
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app_handle, event| match event.id().as_ref() {
+            "tray_show_window" => show_main_window(app_handle),
+            "tray_quit" => app_handle.exit(0),
+            _ => {}
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app_handle.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app_handle)?;
+    Ok(())
+}
+
+/// トレイアイコンを削除する
+pub fn remove_tray(app_handle: &AppHandle) {
+    app_handle.remove_tray_by_id(TRAY_ID);
+}
+
+/// メインウィンドウを表示して前面に出す
+pub fn show_main_window(app_handle: &AppHandle) {
+    let Some(window) = app_handle.get_webview_window(MAIN_WINDOW_LABEL) else {
+        warn!("メインウィンドウが見つかりません");
+        return;
+    };
+    if let Err(e) = window
+        .show()
+        .and_then(|_| window.unminimize())
+        .and_then(|_| window.set_focus())
+    {
+        warn!("メインウィンドウの表示に失敗: {e}");
+    }
+}
@@ -2,10 +2,21 @@
 ///
 /// ローカルSQLiteの代わりにAPI Serverを使用してサブスクリプションデータを管理します
 use crate::features::auth::middleware::AuthMiddleware;
+use crate::features::receipts::connectivity::{ensure_storage_available, record_storage_probe};
+use crate::features::subscriptions::forecast;
+use crate::features::subscriptions::import::{
+    parse_export_content, prepare_import, SubscriptionExportSource, SubscriptionImportCandidate,
+    SubscriptionImportResult,
+};
 use crate::features::subscriptions::models::*;
 use crate::shared::api_client::ApiClient;
+use crate::shared::errors::ValidationError;
+use crate::shared::mutation::{DeleteResponse, DeleteResult};
+use crate::shared::utils::date_utils::today_jst;
+use crate::shared::utils::{normalize_string, validate_subscription_name};
 use log::info;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use tauri::State;
 
 /// API Serverからのサブスクリプション作成レスポンス
@@ -16,6 +27,21 @@ struct CreateSubscriptionResponse {
     timestamp: String,
 }
 
+/// API Serverへのサブスクリプション一括作成リクエスト
+#[derive(Debug, Serialize)]
+struct CreateSubscriptionsRequest<'a> {
+    subscriptions: &'a [CreateSubscriptionDto],
+}
+
+/// API Serverからのサブスクリプション一括作成レスポンス
+#[derive(Debug, Serialize, Deserialize)]
+struct CreateSubscriptionsResponse {
+    success: bool,
+    subscriptions: Vec<Subscription>,
+    count: usize,
+    timestamp: String,
+}
+
 /// API Serverからのサブスクリプション一覧取得レスポンス
 #[derive(Debug, Serialize, Deserialize)]
 struct GetSubscriptionsResponse {
@@ -26,20 +52,19 @@ struct GetSubscriptionsResponse {
     timestamp: String,
 }
 
-/// API Serverからのサブスクリプション更新レスポンス
+/// API Serverからのサブスクリプション取得レスポンス
 #[derive(Debug, Serialize, Deserialize)]
-struct UpdateSubscriptionResponse {
+struct GetSubscriptionResponse {
     success: bool,
     subscription: Subscription,
     timestamp: String,
 }
 
-/// API Serverからの月額合計取得レスポンス
+/// API Serverからのサブスクリプション更新レスポンス
 #[derive(Debug, Serialize, Deserialize)]
-struct MonthlyTotalResponse {
+struct UpdateSubscriptionResponse {
     success: bool,
-    #[serde(rename = "monthlyTotal")]
-    monthly_total: f64,
+    subscription: Subscription,
     timestamp: String,
 }
 
@@ -64,6 +89,11 @@ pub async fn create_subscription(
         .await
         .map_err(|e| format!("認証エラー: {e}"))?;
 
+    // 入力値の検証（すべてのエラーをまとめて返す）
+    dto.validate_all()
+        .and_then(|errors| ValidationError::ensure_none(&errors))
+        .map_err(|e| e.user_message().to_string())?;
+
     // APIクライアントを作成
     let api_client = ApiClient::new().map_err(|e| format!("APIクライアント作成エラー: {e}"))?;
 
@@ -83,7 +113,7 @@ pub async fn create_subscription(
 /// サブスクリプション一覧を取得する（API Server経由）
 ///
 /// # 引数
-/// * `active_only` - アクティブなサブスクリプションのみを取得するか
+/// * `filter` - 検索条件（オプション）
 /// * `session_token` - セッショントークン
 /// * `auth_middleware` - 認証ミドルウェア
 ///
@@ -91,7 +121,7 @@ pub async fn create_subscription(
 /// サブスクリプション一覧、または失敗時はエラーメッセージ
 #[tauri::command]
 pub async fn get_subscriptions(
-    active_only: bool,
+    filter: Option<SubscriptionFilter>,
     session_token: Option<String>,
     auth_middleware: State<'_, AuthMiddleware>,
 ) -> Result<Vec<Subscription>, String> {
@@ -101,11 +131,16 @@ pub async fn get_subscriptions(
         .await
         .map_err(|e| format!("認証エラー: {e}"))?;
 
+    let filter = filter.unwrap_or_default();
+    filter
+        .validate()
+        .map_err(|e| e.user_message().to_string())?;
+
     // APIクライアントを作成
     let api_client = ApiClient::new().map_err(|e| format!("APIクライアント作成エラー: {e}"))?;
 
-    // クエリパラメータを構築
-    let endpoint = if active_only {
+    // クエリパラメータを構築（API Serverはアクティブのみの絞り込みに対応）
+    let endpoint = if filter.is_active == Some(true) {
         "/api/v1/subscriptions?activeOnly=true"
     } else {
         "/api/v1/subscriptions"
@@ -117,8 +152,146 @@ pub async fn get_subscriptions(
         .await
         .map_err(|e| format!("サブスクリプション一覧取得APIエラー: {e}"))?;
 
-    info!("サブスクリプション一覧取得成功: count={}", response.count);
-    Ok(response.subscriptions)
+    // 残りの条件は取得後に適用する
+    let subscriptions: Vec<Subscription> = response
+        .subscriptions
+        .into_iter()
+        .filter(|subscription| filter.matches(subscription))
+        .collect();
+
+    info!(
+        "サブスクリプション一覧取得成功: count={}, filtered={}",
+        response.count,
+        subscriptions.len()
+    );
+    Ok(subscriptions)
+}
+
+/// IDを指定してサブスクリプションを取得する（API Server経由）
+///
+/// # 引数
+/// * `subscription_id` - サブスクリプションID
+/// * `session_token` - セッショントークン
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// サブスクリプション（存在しない場合はNone）、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn get_subscription_by_id(
+    subscription_id: i64,
+    session_token: Option<String>,
+    auth_middleware: State<'_, AuthMiddleware>,
+) -> Result<Option<Subscription>, String> {
+    // 認証チェック
+    let _user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/subscriptions/get")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    // APIクライアントを作成
+    let api_client = ApiClient::new().map_err(|e| format!("APIクライアント作成エラー: {e}"))?;
+
+    // API Serverにサブスクリプション取得リクエストを送信
+    let endpoint = format!("/api/v1/subscriptions/{subscription_id}");
+    match api_client
+        .get::<GetSubscriptionResponse>(&endpoint, session_token.as_deref())
+        .await
+    {
+        Ok(response) => {
+            info!("サブスクリプション取得成功: subscription_id={subscription_id}");
+            Ok(Some(response.subscription))
+        }
+        Err(e) if e.to_string().contains("NOT_FOUND") => {
+            info!("サブスクリプションが見つかりません: subscription_id={subscription_id}");
+            Ok(None)
+        }
+        Err(e) => Err(format!("サブスクリプション取得APIエラー: {e}")),
+    }
+}
+
+/// 既存のサブスクリプションを複製する（API Server経由）
+///
+/// ID・領収書以外の項目をコピーして新しいサブスクリプションを作成します。
+/// 作成日時・更新日時はAPI Server側で現在時刻が設定されます。
+///
+/// # 引数
+/// * `subscription_id` - 複製元のサブスクリプションID
+/// * `new_name` - 新しいサービス名（省略時は複製元と同じ名前）
+/// * `session_token` - セッショントークン
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 作成されたサブスクリプション、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn clone_subscription(
+    subscription_id: i64,
+    new_name: Option<String>,
+    session_token: Option<String>,
+    auth_middleware: State<'_, AuthMiddleware>,
+) -> Result<Subscription, String> {
+    // 認証チェック
+    let _user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/subscriptions/clone")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    // 新しいサービス名のバリデーション
+    let new_name = new_name.map(|name| normalize_string(&name));
+    if let Some(name) = &new_name {
+        validate_subscription_name(name).map_err(|e| e.user_message().to_string())?;
+    }
+
+    // APIクライアントを作成
+    let api_client = ApiClient::new().map_err(|e| format!("APIクライアント作成エラー: {e}"))?;
+
+    // 複製元のサブスクリプションを取得
+    let endpoint = format!("/api/v1/subscriptions/{subscription_id}");
+    let original = api_client
+        .get::<GetSubscriptionResponse>(&endpoint, session_token.as_deref())
+        .await
+        .map_err(|e| format!("サブスクリプション取得APIエラー: {e}"))?
+        .subscription;
+
+    let dto = CreateSubscriptionDto {
+        name: new_name.unwrap_or_else(|| original.name.clone()),
+        amount: original.amount,
+        billing_cycle: original.billing_cycle.clone(),
+        start_date: original.start_date.clone(),
+        category: original.category.clone(),
+        category_id: original.category_id,
+        currency: None,
+    };
+
+    // API Serverにサブスクリプション作成リクエストを送信
+    let mut cloned = api_client
+        .post::<_, CreateSubscriptionResponse>(
+            "/api/v1/subscriptions",
+            &dto,
+            session_token.as_deref(),
+        )
+        .await
+        .map_err(|e| format!("サブスクリプション作成APIエラー: {e}"))?
+        .subscription;
+
+    // 作成直後はアクティブになるため、複製元が無効の場合は状態を合わせる
+    if cloned.is_active != original.is_active {
+        let endpoint = format!("/api/v1/subscriptions/{}/toggle", cloned.id);
+        cloned = api_client
+            .patch::<_, UpdateSubscriptionResponse>(
+                &endpoint,
+                &serde_json::json!({}),
+                session_token.as_deref(),
+            )
+            .await
+            .map_err(|e| format!("サブスクリプションステータス切り替えAPIエラー: {e}"))?
+            .subscription;
+    }
+
+    info!(
+        "サブスクリプション複製成功: source_id={subscription_id}, subscription_id={}",
+        cloned.id
+    );
+    Ok(cloned)
 }
 
 /// サブスクリプションを更新する（API Server経由）
@@ -201,13 +374,13 @@ pub async fn toggle_subscription_status(
 /// * `auth_middleware` - 認証ミドルウェア
 ///
 /// # 戻り値
-/// 成功時はOk(())、失敗時はエラーメッセージ
+/// 削除したサブスクリプションのIDとリビジョン、または失敗時はエラーメッセージ
 #[tauri::command]
 pub async fn delete_subscription(
     id: i64,
     session_token: Option<String>,
     auth_middleware: State<'_, AuthMiddleware>,
-) -> Result<(), String> {
+) -> Result<DeleteResult, String> {
     info!("🗑️ サブスクリプション削除処理開始: subscription_id={id}");
 
     // 認証チェック
@@ -233,8 +406,8 @@ pub async fn delete_subscription(
     let endpoint = format!("/api/v1/subscriptions/{id}");
     info!("📡 API削除リクエスト送信: endpoint={endpoint}");
 
-    api_client
-        .delete(&endpoint, session_token.as_deref())
+    let response: DeleteResponse = api_client
+        .delete_json(&endpoint, session_token.as_deref())
         .await
         .map_err(|e| {
             log::error!("📡 サブスクリプション削除APIエラー: {e}");
@@ -242,11 +415,13 @@ pub async fn delete_subscription(
         })?;
 
     info!("✅ サブスクリプション削除成功: subscription_id={id}");
-    Ok(())
+    Ok(response.into())
 }
 
 /// 月額サブスクリプション合計を取得する（API Server経由）
 ///
+/// API Serverから取得した有効なサブスクリプションの月額換算を合計します。
+///
 /// # 引数
 /// * `session_token` - セッショントークン
 /// * `auth_middleware` - 認証ミドルウェア
@@ -267,17 +442,11 @@ pub async fn get_monthly_subscription_total(
     // APIクライアントを作成
     let api_client = ApiClient::new().map_err(|e| format!("APIクライアント作成エラー: {e}"))?;
 
-    // API Serverに月額合計取得リクエストを送信
-    let response: MonthlyTotalResponse = api_client
-        .get(
-            "/api/v1/subscriptions/monthly-total",
-            session_token.as_deref(),
-        )
-        .await
-        .map_err(|e| format!("月額合計取得APIエラー: {e}"))?;
+    let subscriptions = fetch_active_subscriptions(&api_client, session_token.as_deref()).await?;
+    let total = forecast::monthly_total(&subscriptions);
 
-    info!("月額合計取得成功: total={}", response.monthly_total);
-    Ok(response.monthly_total)
+    info!("月額合計取得成功: total={total}");
+    Ok(total)
 }
 
 /// サブスクリプションの領収書をアップロードする（API Server経由）
@@ -287,6 +456,7 @@ pub async fn get_monthly_subscription_total(
 /// * `file_path` - ファイルパス
 /// * `session_token` - セッショントークン
 /// * `auth_middleware` - 認証ミドルウェア
+/// * `force` - ストレージ停止中でも試行する場合はtrue
 ///
 /// # 戻り値
 /// アップロードされた領収書のURL、または失敗時はエラーメッセージ
@@ -295,6 +465,7 @@ pub async fn upload_subscription_receipt_via_api(
     subscription_id: i64,
     file_path: String,
     session_token: Option<String>,
+    force: Option<bool>,
     auth_middleware: State<'_, AuthMiddleware>,
 ) -> Result<String, String> {
     info!(
@@ -307,6 +478,9 @@ pub async fn upload_subscription_receipt_via_api(
         .await
         .map_err(|e| format!("認証エラー: {e}"))?;
 
+    // ストレージ停止が確認されている場合は即座に失敗させる
+    ensure_storage_available(force.unwrap_or(false))?;
+
     // セッショントークンが必要
     let token = session_token.ok_or_else(|| "セッショントークンが必要です".to_string())?;
 
@@ -345,6 +519,7 @@ pub async fn upload_subscription_receipt_via_api(
         .await
     {
         Ok(response) => {
+            record_storage_probe(true);
             let file_url = response.file_url.unwrap_or_else(|| "".to_string());
             info!("サブスクリプションの領収書アップロード成功: file_url={file_url}");
             Ok(file_url)
@@ -362,6 +537,7 @@ pub async fn upload_subscription_receipt_via_api(
 /// * `receipt_url` - 削除する領収書のHTTPS URL
 /// * `session_token` - セッショントークン
 /// * `auth_middleware` - 認証ミドルウェア
+/// * `force` - ストレージ停止中でも試行する場合はtrue
 ///
 /// # 戻り値
 /// 削除成功時はtrue、失敗時はエラーメッセージ
@@ -369,6 +545,7 @@ pub async fn upload_subscription_receipt_via_api(
 pub async fn delete_subscription_receipt_from_r2(
     receipt_url: String,
     session_token: Option<String>,
+    force: Option<bool>,
     auth_middleware: State<'_, AuthMiddleware>,
 ) -> Result<bool, String> {
     info!("サブスクリプションの領収書削除処理開始（R2）: receipt_url={receipt_url}");
@@ -384,6 +561,9 @@ pub async fn delete_subscription_receipt_from_r2(
 
     log::debug!("認証成功 - ユーザーID: {}", user.id);
 
+    // ストレージ停止が確認されている場合は即座に失敗させる
+    ensure_storage_available(force.unwrap_or(false))?;
+
     // セッショントークンが必要
     let token = session_token.ok_or_else(|| {
         log::error!("セッショントークンが提供されていません");
@@ -443,6 +623,7 @@ pub async fn delete_subscription_receipt_from_r2(
     info!("レスポンス解析結果: success={success}");
 
     if success {
+        record_storage_probe(true);
         info!(
             "サブスクリプションの領収書削除成功 - ユーザーID: {}, receipt_url: {receipt_url}",
             user.id
@@ -467,13 +648,13 @@ pub async fn delete_subscription_receipt_from_r2(
 /// * `auth_middleware` - 認証ミドルウェア
 ///
 /// # 戻り値
-/// 削除成功時はtrue、失敗時はエラーメッセージ
+/// 領収書パスを削除した後のサブスクリプション、または失敗時はエラーメッセージ
 #[tauri::command]
 pub async fn delete_subscription_receipt_via_api(
     subscription_id: i64,
     session_token: Option<String>,
     auth_middleware: State<'_, AuthMiddleware>,
-) -> Result<bool, String> {
+) -> Result<Subscription, String> {
     info!("サブスクリプションの領収書パス削除処理開始（DB）: subscription_id={subscription_id}");
 
     // 認証チェック
@@ -499,11 +680,269 @@ pub async fn delete_subscription_receipt_via_api(
     info!("領収書パス削除リクエストを送信: subscription_id={subscription_id}, dto={dto:?}");
 
     let endpoint = format!("/api/v1/subscriptions/{subscription_id}");
-    let _response: UpdateSubscriptionResponse = api_client
+    let response: UpdateSubscriptionResponse = api_client
         .put(&endpoint, &dto, session_token.as_deref())
         .await
         .map_err(|e| format!("領収書パス削除APIエラー: {e}"))?;
 
     info!("サブスクリプションの領収書パス削除成功: subscription_id={subscription_id}");
-    Ok(true)
+    Ok(response.subscription)
+}
+
+/// 有効なサブスクリプションをAPI Serverから取得する
+///
+/// # 引数
+/// * `api_client` - APIクライアント
+/// * `session_token` - セッショントークン
+///
+/// # 戻り値
+/// 有効なサブスクリプション一覧、または失敗時はエラーメッセージ
+async fn fetch_active_subscriptions(
+    api_client: &ApiClient,
+    session_token: Option<&str>,
+) -> Result<Vec<Subscription>, String> {
+    let response: GetSubscriptionsResponse = api_client
+        .get("/api/v1/subscriptions?activeOnly=true", session_token)
+        .await
+        .map_err(|e| format!("サブスクリプション一覧取得APIエラー: {e}"))?;
+
+    Ok(response
+        .subscriptions
+        .into_iter()
+        .filter(|subscription| subscription.is_active)
+        .collect())
+}
+
+/// 指定日数以内に更新されるサブスクリプションを取得する（API Server経由）
+///
+/// # 引数
+/// * `days` - 今日から何日以内の更新を対象とするか
+/// * `session_token` - セッショントークン
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 次回更新日付きのサブスクリプション一覧、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn get_upcoming_renewals(
+    days: u32,
+    session_token: Option<String>,
+    auth_middleware: State<'_, AuthMiddleware>,
+) -> Result<Vec<SubscriptionRenewal>, String> {
+    // 認証チェック
+    let _user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/subscriptions/renewals")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    // APIクライアントを作成
+    let api_client = ApiClient::new().map_err(|e| format!("APIクライアント作成エラー: {e}"))?;
+
+    let subscriptions = fetch_active_subscriptions(&api_client, session_token.as_deref()).await?;
+    let renewals = forecast::upcoming_renewals(subscriptions, days, today_jst())
+        .map_err(|e| format!("更新予定のサブスクリプション取得に失敗しました: {e}"))?;
+
+    info!(
+        "更新予定のサブスクリプション取得成功: days={days}, count={}",
+        renewals.len()
+    );
+    Ok(renewals)
+}
+
+/// 指定年の月別の支払見込み額を取得する（API Server経由）
+///
+/// # 引数
+/// * `year` - 対象年
+/// * `session_token` - セッショントークン
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 月名をキーとした支払見込み額のマップ、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn get_annual_cost_breakdown(
+    year: i32,
+    session_token: Option<String>,
+    auth_middleware: State<'_, AuthMiddleware>,
+) -> Result<HashMap<String, f64>, String> {
+    // 認証チェック
+    let _user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/subscriptions/annual-breakdown")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    // APIクライアントを作成
+    let api_client = ApiClient::new().map_err(|e| format!("APIクライアント作成エラー: {e}"))?;
+
+    let subscriptions = fetch_active_subscriptions(&api_client, session_token.as_deref()).await?;
+    let breakdown = forecast::annual_cost_breakdown(&subscriptions, year)
+        .map_err(|e| format!("年間支払見込みの計算に失敗しました: {e}"))?;
+
+    info!(
+        "年間支払見込み計算成功: year={year}, subscriptions={}",
+        subscriptions.len()
+    );
+    Ok(breakdown)
+}
+
+/// エクスポートファイルを解析してサブスクリプション候補を返す
+///
+/// # 引数
+/// * `path` - エクスポートファイルのパス
+/// * `source` - エクスポート形式（"apple" または "generic"）
+/// * `session_token` - セッショントークン
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// バリデーション結果付きのサブスクリプション候補一覧、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn parse_subscription_export(
+    path: String,
+    source: SubscriptionExportSource,
+    session_token: Option<String>,
+    auth_middleware: State<'_, AuthMiddleware>,
+) -> Result<Vec<SubscriptionImportCandidate>, String> {
+    // 認証チェック
+    let _user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/subscriptions/import")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    let content = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("ファイル読み込みエラー: {e}"))?;
+
+    let candidates = parse_export_content(&content, source)?;
+
+    info!(
+        "サブスクリプションエクスポート解析成功: source={source:?}, count={}",
+        candidates.len()
+    );
+    Ok(candidates)
+}
+
+/// サブスクリプション候補を一括で取り込む（API Server経由）
+///
+/// 既存のサブスクリプションと名前が完全一致するものはスキップし、
+/// 残りをAPI Serverの一括作成エンドポイントで1回のリクエストで登録します。
+/// API Server側で1つのバッチとして実行されるため、途中で失敗した場合は
+/// どのサブスクリプションも登録されません。
+///
+/// # 引数
+/// * `candidates` - 取り込むサブスクリプション
+/// * `session_token` - セッショントークン
+/// * `auth_middleware` - 認証ミドルウェア
+///
+/// # 戻り値
+/// 取り込み結果（作成数とスキップされた候補）、または失敗時はエラーメッセージ
+#[tauri::command]
+pub async fn import_subscriptions(
+    candidates: Vec<CreateSubscriptionDto>,
+    session_token: Option<String>,
+    auth_middleware: State<'_, AuthMiddleware>,
+) -> Result<SubscriptionImportResult, String> {
+    // 認証チェック
+    let _user = auth_middleware
+        .authenticate_request(session_token.as_deref(), "/subscriptions/import")
+        .await
+        .map_err(|e| format!("認証エラー: {e}"))?;
+
+    // APIクライアントを作成
+    let api_client = ApiClient::new().map_err(|e| format!("APIクライアント作成エラー: {e}"))?;
+
+    // 重複判定のため既存のサブスクリプションを取得
+    let existing_names: Vec<String> = api_client
+        .get::<GetSubscriptionsResponse>("/api/v1/subscriptions", session_token.as_deref())
+        .await
+        .map_err(|e| format!("サブスクリプション一覧取得APIエラー: {e}"))?
+        .subscriptions
+        .into_iter()
+        .map(|subscription| subscription.name)
+        .collect();
+
+    let (to_create, skipped) =
+        prepare_import(candidates, &existing_names).map_err(|e| e.user_message().to_string())?;
+
+    let imported_count = if to_create.is_empty() {
+        0
+    } else {
+        let request = CreateSubscriptionsRequest {
+            subscriptions: &to_create,
+        };
+        api_client
+            .post::<_, CreateSubscriptionsResponse>(
+                "/api/v1/subscriptions/batch",
+                &request,
+                session_token.as_deref(),
+            )
+            .await
+            .map_err(|e| format!("サブスクリプション一括作成APIエラー: {e}"))?
+            .count
+    };
+
+    info!(
+        "サブスクリプション取り込み成功: imported={imported_count}, skipped={}",
+        skipped.len()
+    );
+    Ok(SubscriptionImportResult {
+        imported_count,
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// API Serverが返すサブスクリプション（サーバー側で設定される日時を含む）
+    fn server_subscription(receipt_path: Option<&str>) -> serde_json::Value {
+        serde_json::json!({
+            "id": 3,
+            "name": "クラウドストレージ",
+            "amount": 1300.0,
+            "billing_cycle": "monthly",
+            "start_date": "2025-01-15",
+            "category": "その他",
+            "category_id": 6,
+            "is_active": true,
+            "receipt_path": receipt_path,
+            "created_at": "2025-01-15T09:00:00+09:00",
+            "updated_at": "2025-04-01T10:00:00+09:00"
+        })
+    }
+
+    /// 詳細取得で返されるサブスクリプションをシリアライズする
+    fn fetched(subscription: serde_json::Value) -> serde_json::Value {
+        let response: GetSubscriptionResponse = serde_json::from_value(serde_json::json!({
+            "success": true,
+            "subscription": subscription,
+            "timestamp": "2025-04-01T01:00:00.000Z"
+        }))
+        .unwrap();
+        serde_json::to_value(&response.subscription).unwrap()
+    }
+
+    #[test]
+    fn test_mutation_responses_match_fetch() {
+        let receipt_path = Some("https://receipts.example.com/users/1/subscriptions/3/a.pdf");
+        let created: CreateSubscriptionResponse = serde_json::from_value(serde_json::json!({
+            "success": true,
+            "subscription": server_subscription(receipt_path),
+            "timestamp": "2025-04-01T01:00:00.000Z"
+        }))
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(&created.subscription).unwrap(),
+            fetched(server_subscription(receipt_path))
+        );
+
+        // 領収書パスの削除は更新レスポンスのサブスクリプションをそのまま返す
+        let updated: UpdateSubscriptionResponse = serde_json::from_value(serde_json::json!({
+            "success": true,
+            "subscription": server_subscription(None),
+            "timestamp": "2025-04-01T01:00:00.000Z"
+        }))
+        .unwrap();
+        let updated = serde_json::to_value(&updated.subscription).unwrap();
+        assert_eq!(updated, fetched(server_subscription(None)));
+        assert!(updated["receipt_path"].is_null());
+    }
 }
@@ -0,0 +1,237 @@
+/// サブスクリプションの更新予定と支払見込みの計算
+///
+/// API Serverから取得したサブスクリプションを対象に、月額合計、今後の更新予定や
+/// 年間の月別支払見込みを計算します。
+/// データベースに依存しない純粋な関数として実装しています。
+use crate::features::subscriptions::models::{Subscription, SubscriptionRenewal};
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::utils::round_to_currency_precision;
+use chrono::{Datelike, NaiveDate};
+use std::collections::HashMap;
+
+/// 有効なサブスクリプションの月額合計を計算する
+///
+/// 各サブスクリプションの月額換算（`Subscription::monthly_equivalent`）を合計します。
+///
+/// # 引数
+/// * `subscriptions` - ユーザーのサブスクリプション
+///
+/// # 戻り値
+/// 月額合計（有効なサブスクリプションがない場合は0.0）
+pub fn monthly_total(subscriptions: &[Subscription]) -> f64 {
+    round_to_currency_precision(
+        subscriptions
+            .iter()
+            .filter(|s| s.is_active)
+            .map(Subscription::monthly_equivalent)
+            .sum(),
+    )
+}
+
+/// 指定日数以内に更新されるサブスクリプションを抽出する
+///
+/// # 引数
+/// * `subscriptions` - ユーザーのサブスクリプション
+/// * `days` - 基準日から何日以内の更新を対象とするか
+/// * `today` - 基準日
+///
+/// # 戻り値
+/// 有効なサブスクリプションの次回更新日付き一覧（更新日の近い順）、
+/// または開始日・請求サイクルが不正な場合はバリデーションエラー
+pub fn upcoming_renewals(
+    subscriptions: Vec<Subscription>,
+    days: u32,
+    today: NaiveDate,
+) -> AppResult<Vec<SubscriptionRenewal>> {
+    let until = today + chrono::Duration::days(i64::from(days));
+
+    let mut renewals = Vec::new();
+    for subscription in subscriptions.into_iter().filter(|s| s.is_active) {
+        let next_renewal = subscription.try_next_renewal_date(today)?;
+
+        if next_renewal <= until {
+            renewals.push(SubscriptionRenewal {
+                subscription,
+                next_renewal_date: next_renewal.format("%Y-%m-%d").to_string(),
+            });
+        }
+    }
+
+    renewals.sort_by(|a, b| a.next_renewal_date.cmp(&b.next_renewal_date));
+    Ok(renewals)
+}
+
+/// 指定年の月別の支払見込み額を計算する
+///
+/// 有効なサブスクリプションについて、月額は開始月以降の毎月、
+/// 年額は開始日と同じ月に支払いが発生するものとして集計します。
+///
+/// # 引数
+/// * `subscriptions` - ユーザーのサブスクリプション
+/// * `year` - 対象年
+///
+/// # 戻り値
+/// 月名（"1月"〜"12月"）をキーとした支払見込み額のマップ、
+/// または開始日・請求サイクルが不正な場合はバリデーションエラー
+pub fn annual_cost_breakdown(
+    subscriptions: &[Subscription],
+    year: i32,
+) -> AppResult<HashMap<String, f64>> {
+    let mut totals = [0.0_f64; 12];
+    for subscription in subscriptions.iter().filter(|s| s.is_active) {
+        let start_date =
+            NaiveDate::parse_from_str(&subscription.start_date, "%Y-%m-%d").map_err(|e| {
+                AppError::validation(format!(
+                    "開始日の形式が不正です（id={}）: {e}",
+                    subscription.id
+                ))
+            })?;
+        if start_date.year() > year {
+            continue;
+        }
+
+        match subscription.billing_cycle.as_str() {
+            "monthly" => {
+                let first_month = if start_date.year() == year {
+                    start_date.month0() as usize
+                } else {
+                    0
+                };
+                for total in totals.iter_mut().skip(first_month) {
+                    *total += subscription.amount;
+                }
+            }
+            "annual" => {
+                totals[start_date.month0() as usize] += subscription.amount;
+            }
+            other => {
+                return Err(AppError::validation(format!(
+                    "不明な請求サイクルです: {other}"
+                )))
+            }
+        }
+    }
+
+    Ok(totals
+        .iter()
+        .enumerate()
+        .map(|(i, total)| (format!("{}月", i + 1), *total))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::utils::date_utils::parse_ymd;
+
+    fn subscription(
+        name: &str,
+        billing_cycle: &str,
+        start_date: &str,
+        is_active: bool,
+    ) -> Subscription {
+        Subscription {
+            id: 1,
+            name: name.to_string(),
+            amount: 1000.0,
+            billing_cycle: billing_cycle.to_string(),
+            start_date: start_date.to_string(),
+            category: "通信費".to_string(),
+            category_id: None,
+            is_active,
+            receipt_path: None,
+            created_at: "2024-01-01T00:00:00+09:00".to_string(),
+            updated_at: "2024-01-01T00:00:00+09:00".to_string(),
+        }
+    }
+
+    fn with_amount(amount: f64, billing_cycle: &str, is_active: bool) -> Subscription {
+        Subscription {
+            amount,
+            ..subscription("テスト", billing_cycle, "2024-01-01", is_active)
+        }
+    }
+
+    #[test]
+    fn test_monthly_total() {
+        assert_eq!(monthly_total(&[]), 0.0);
+
+        // 月額はそのまま、年額は12で割って合計する
+        let subscriptions = vec![
+            with_amount(1490.0, "monthly", true),
+            with_amount(980.0, "monthly", true),
+            with_amount(12000.0, "annual", true),
+        ];
+        assert_eq!(monthly_total(&subscriptions), 3470.0);
+    }
+
+    #[test]
+    fn test_monthly_total_excludes_inactive() {
+        let subscriptions = vec![
+            with_amount(1000.0, "monthly", true),
+            with_amount(2000.0, "monthly", false),
+            with_amount(24000.0, "annual", false),
+        ];
+
+        assert_eq!(monthly_total(&subscriptions), 1000.0);
+    }
+
+    #[test]
+    fn test_monthly_total_rounds_annual_amounts() {
+        let subscriptions = vec![
+            with_amount(980.0, "annual", true),
+            with_amount(1000.0, "annual", true),
+        ];
+
+        // 81.67 + 83.33
+        assert_eq!(monthly_total(&subscriptions), 165.0);
+    }
+
+    #[test]
+    fn test_upcoming_renewals() {
+        let subscriptions = vec![
+            subscription("Netflix", "monthly", "2024-01-20", true),
+            subscription("Spotify", "monthly", "2024-01-16", true),
+            subscription("Adobe", "annual", "2023-06-01", true),
+            subscription("解約済み", "monthly", "2024-01-17", false),
+        ];
+
+        let renewals =
+            upcoming_renewals(subscriptions, 7, parse_ymd("2024-03-15").unwrap()).unwrap();
+
+        let names: Vec<&str> = renewals
+            .iter()
+            .map(|r| r.subscription.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Spotify", "Netflix"]);
+        assert_eq!(renewals[0].next_renewal_date, "2024-03-16");
+        assert_eq!(renewals[1].next_renewal_date, "2024-03-20");
+    }
+
+    #[test]
+    fn test_annual_cost_breakdown() {
+        let subscriptions = vec![
+            subscription("Netflix", "monthly", "2023-05-10", true),
+            subscription("Spotify", "monthly", "2024-04-01", true),
+            subscription("Adobe", "annual", "2022-06-15", true),
+            subscription("来年開始", "monthly", "2025-01-01", true),
+            subscription("解約済み", "monthly", "2023-01-01", false),
+        ];
+
+        let breakdown = annual_cost_breakdown(&subscriptions, 2024).unwrap();
+
+        assert_eq!(breakdown.len(), 12);
+        assert_eq!(breakdown["1月"], 1000.0);
+        assert_eq!(breakdown["4月"], 2000.0);
+        assert_eq!(breakdown["6月"], 3000.0);
+        assert_eq!(breakdown["12月"], 2000.0);
+        assert_eq!(breakdown.values().sum::<f64>(), 12000.0 + 9000.0 + 1000.0);
+    }
+
+    #[test]
+    fn test_upcoming_renewals_rejects_invalid_start_date() {
+        let subscriptions = vec![subscription("Netflix", "monthly", "2024-02-30", true)];
+
+        assert!(upcoming_renewals(subscriptions, 7, parse_ymd("2024-03-15").unwrap()).is_err());
+    }
+}
@@ -0,0 +1,689 @@
+/// サブスクリプションのエクスポートファイル取り込み
+///
+/// Apple の購入履歴CSVや汎用CSVを解析し、`CreateSubscriptionDto` の候補に変換します。
+/// 解析処理はファイルI/Oやネットワークに依存しない純粋な関数として実装しています。
+/// 取り込みはAPIサーバーの一括作成エンドポイントで行うため、ここでは登録前の
+/// 検証と重複の除外のみを扱います。
+use crate::features::subscriptions::models::CreateSubscriptionDto;
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::export::{parse_csv_records, read_csv_export, CsvHeaderIndex};
+use crate::shared::utils::{
+    normalize_string, validate_amount_for_currency, validate_category, validate_date,
+    validate_subscription_name, CurrencyCode,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// 取り込み元のエクスポート形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubscriptionExportSource {
+    /// Apple の購入履歴（Purchase history）CSV
+    Apple,
+    /// 汎用CSV（name, price, period, start_date）
+    Generic,
+}
+
+/// 解析されたサブスクリプション候補
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionImportCandidate {
+    /// 元ファイルの行番号（ヘッダー行を1行目とする）
+    pub line: usize,
+    /// 作成用DTO
+    pub dto: CreateSubscriptionDto,
+    /// カテゴリがサービス名から推測されたかどうか
+    pub category_guessed: bool,
+    /// バリデーションエラーの一覧（空の場合は取り込み可能）
+    pub errors: Vec<String>,
+}
+
+impl SubscriptionImportCandidate {
+    /// 取り込み可能な候補かどうか
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// 取り込み時にスキップされたサブスクリプション
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkippedSubscription {
+    pub name: String,
+    pub reason: String,
+}
+
+/// 取り込み結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionImportResult {
+    /// 作成されたサブスクリプション数
+    pub imported_count: usize,
+    /// スキップされたサブスクリプション
+    pub skipped: Vec<SkippedSubscription>,
+}
+
+/// エクスポートファイルの内容を解析する
+///
+/// # 引数
+/// * `content` - CSVファイルの内容
+/// * `source` - エクスポート形式
+///
+/// アプリ自身のエクスポートの場合は、解析前に出所情報のチェックサムと
+/// スキーマの互換性を検証します。
+///
+/// # 戻り値
+/// サブスクリプション候補の一覧、またはヘッダー不正・検証失敗時はエラー
+pub fn parse_export_content(
+    content: &str,
+    source: SubscriptionExportSource,
+) -> AppResult<Vec<SubscriptionImportCandidate>> {
+    let content = match read_csv_export(content)? {
+        Some(verified) => {
+            for warning in &verified.warnings {
+                log::warn!("{warning}");
+            }
+            verified.body
+        }
+        None => content,
+    };
+
+    let records = parse_csv_records(content);
+    let Some((_, header)) = records.first() else {
+        return Err(AppError::validation("CSVファイルが空です"));
+    };
+
+    let columns = CsvHeaderIndex::new(header);
+    let rows = &records[1..];
+
+    match source {
+        SubscriptionExportSource::Apple => parse_apple_rows(&columns, rows),
+        SubscriptionExportSource::Generic => parse_generic_rows(&columns, rows),
+    }
+}
+
+/// 既存のサブスクリプション名と重複する候補を取り除く
+///
+/// 名前の完全一致（前後の空白は除く）を重複とみなし、候補同士の重複も
+/// 最初の1件のみを残します。
+///
+/// # 引数
+/// * `candidates` - 作成候補
+/// * `existing_names` - 既に登録済みのサブスクリプション名
+///
+/// # 戻り値
+/// (作成対象, スキップされた候補)
+pub fn partition_duplicates(
+    candidates: Vec<CreateSubscriptionDto>,
+    existing_names: &[String],
+) -> (Vec<CreateSubscriptionDto>, Vec<SkippedSubscription>) {
+    let mut seen: HashSet<String> = existing_names
+        .iter()
+        .map(|name| normalize_string(name))
+        .collect();
+    let mut to_create = Vec::new();
+    let mut skipped = Vec::new();
+
+    for dto in candidates {
+        let name = normalize_string(&dto.name);
+        if seen.contains(&name) {
+            skipped.push(SkippedSubscription {
+                name,
+                reason: "同名のサブスクリプションが既に存在します".to_string(),
+            });
+        } else {
+            seen.insert(name);
+            to_create.push(dto);
+        }
+    }
+
+    (to_create, skipped)
+}
+
+/// 取り込むサブスクリプションを検証し、作成対象を決定する
+///
+/// 不正な候補が1件でもある場合はどのサブスクリプションも登録しないよう、
+/// 送信前にすべての候補を検証します。ユーザーの既存のサブスクリプションと
+/// 名前が完全一致するものはスキップし、作成対象の名前は正規化します。
+///
+/// # 引数
+/// * `candidates` - 取り込むサブスクリプション
+/// * `existing_names` - 既に登録済みのサブスクリプション名
+///
+/// # 戻り値
+/// (作成対象, スキップされた候補)、または不正な候補がある場合はバリデーションエラー
+pub fn prepare_import(
+    candidates: Vec<CreateSubscriptionDto>,
+    existing_names: &[String],
+) -> AppResult<(Vec<CreateSubscriptionDto>, Vec<SkippedSubscription>)> {
+    for dto in &candidates {
+        let errors = validate_import_dto(dto);
+        if !errors.is_empty() {
+            return Err(AppError::validation(format!(
+                "{}: {}",
+                dto.name,
+                errors.join(", ")
+            )));
+        }
+    }
+
+    let (mut to_create, skipped) = partition_duplicates(candidates, existing_names);
+    for dto in &mut to_create {
+        dto.name = normalize_string(&dto.name);
+    }
+
+    Ok((to_create, skipped))
+}
+
+/// 作成用DTOのバリデーションを行い、エラーメッセージを列挙する
+pub fn validate_import_dto(dto: &CreateSubscriptionDto) -> Vec<String> {
+    dto.validate_all()
+        .map(|errors| errors.into_iter().map(|e| e.message).collect())
+        .unwrap_or_else(|e| vec![e.user_message().to_string()])
+}
+
+/// バリデーション結果からエラーメッセージのみを取り出す
+fn collect_errors<I>(results: I) -> Vec<String>
+where
+    I: IntoIterator<Item = AppResult<()>>,
+{
+    results
+        .into_iter()
+        .filter_map(|result| result.err())
+        .map(|e| e.user_message().to_string())
+        .collect()
+}
+
+const NAME_COLUMNS: &[&str] = &["name", "サービス名", "名前"];
+const PRICE_COLUMNS: &[&str] = &["price", "amount", "金額", "価格"];
+const PERIOD_COLUMNS: &[&str] = &["period", "billing_cycle", "請求周期", "周期"];
+const START_DATE_COLUMNS: &[&str] = &["start_date", "開始日"];
+const CATEGORY_COLUMNS: &[&str] = &["category", "カテゴリ"];
+
+const APPLE_NAME_COLUMNS: &[&str] = &["Item Description", "Content Description", "Item"];
+const APPLE_DATE_COLUMNS: &[&str] = &["Item Purchased Date", "Purchase Date", "Order Date"];
+const APPLE_PRICE_COLUMNS: &[&str] = &["Invoice Item Total", "Item Price", "Price"];
+const APPLE_TYPE_COLUMNS: &[&str] = &["Content Type", "Type"];
+const APPLE_PERIOD_COLUMNS: &[&str] = &["Subscription Period", "Period"];
+
+/// 汎用CSVの行を解析する
+fn parse_generic_rows(
+    columns: &CsvHeaderIndex,
+    rows: &[(usize, Vec<String>)],
+) -> AppResult<Vec<SubscriptionImportCandidate>> {
+    let name_col = columns.require(NAME_COLUMNS)?;
+    let price_col = columns.require(PRICE_COLUMNS)?;
+    let period_col = columns.require(PERIOD_COLUMNS)?;
+    let start_col = columns.require(START_DATE_COLUMNS)?;
+    let category_col = columns.find(CATEGORY_COLUMNS);
+
+    let candidates = rows
+        .iter()
+        .map(|(line, fields)| {
+            let field = |i: usize| fields.get(i).map(|s| s.trim()).unwrap_or("");
+            let name = field(name_col).to_string();
+            let category = category_col
+                .map(field)
+                .filter(|c| !c.is_empty())
+                .map(|c| c.to_string());
+
+            build_candidate(
+                *line,
+                name,
+                field(price_col),
+                field(period_col),
+                field(start_col),
+                category,
+            )
+        })
+        .collect();
+
+    Ok(candidates)
+}
+
+/// Apple購入履歴CSVの行を解析する
+///
+/// 購入履歴は更新のたびに1行が出力されるため、サービス名ごとにまとめ、
+/// 最も古い購入日を開始日、最新の金額を現在の金額として扱います。
+fn parse_apple_rows(
+    columns: &CsvHeaderIndex,
+    rows: &[(usize, Vec<String>)],
+) -> AppResult<Vec<SubscriptionImportCandidate>> {
+    let name_col = columns.require(APPLE_NAME_COLUMNS)?;
+    let date_col = columns.require(APPLE_DATE_COLUMNS)?;
+    let price_col = columns.require(APPLE_PRICE_COLUMNS)?;
+    let type_col = columns.find(APPLE_TYPE_COLUMNS);
+    let period_col = columns.find(APPLE_PERIOD_COLUMNS);
+
+    struct AppleEntry {
+        line: usize,
+        name: String,
+        first_date: String,
+        last_date: String,
+        price: String,
+        period: String,
+    }
+
+    let mut entries: Vec<AppleEntry> = Vec::new();
+
+    for (line, fields) in rows {
+        let field = |i: usize| fields.get(i).map(|s| s.trim()).unwrap_or("");
+
+        // サブスクリプション以外（アプリ購入など）は対象外
+        if let Some(type_col) = type_col {
+            if !field(type_col).to_lowercase().contains("subscription") {
+                continue;
+            }
+        }
+
+        let name = field(name_col).to_string();
+        let date = normalize_date(field(date_col)).unwrap_or_else(|| field(date_col).to_string());
+        let price = field(price_col).to_string();
+        let period = period_col.map(field).unwrap_or("").to_string();
+
+        match entries.iter_mut().find(|e| e.name == name) {
+            Some(entry) => {
+                if date < entry.first_date {
+                    entry.first_date = date.clone();
+                }
+                if date >= entry.last_date {
+                    entry.last_date = date;
+                    entry.price = price;
+                    if !period.is_empty() {
+                        entry.period = period;
+                    }
+                }
+            }
+            None => entries.push(AppleEntry {
+                line: *line,
+                name,
+                first_date: date.clone(),
+                last_date: date,
+                price,
+                period,
+            }),
+        }
+    }
+
+    let candidates = entries
+        .into_iter()
+        .map(|entry| {
+            // 周期が出力されていない場合は月額とみなす
+            let period = if entry.period.is_empty() {
+                "monthly".to_string()
+            } else {
+                entry.period
+            };
+            build_candidate(
+                entry.line,
+                entry.name,
+                &entry.price,
+                &period,
+                &entry.first_date,
+                None,
+            )
+        })
+        .collect();
+
+    Ok(candidates)
+}
+
+/// 1件分の候補を組み立て、バリデーション結果を付与する
+fn build_candidate(
+    line: usize,
+    name: String,
+    price: &str,
+    period: &str,
+    start_date: &str,
+    category: Option<String>,
+) -> SubscriptionImportCandidate {
+    let (amount, currency, amount_check) = match parse_localized_amount(price) {
+        Ok((amount, currency)) => (
+            amount,
+            currency,
+            validate_amount_for_currency(amount, currency),
+        ),
+        Err(e) => (0.0, CurrencyCode::Jpy, Err(e)),
+    };
+    let billing_cycle = normalize_billing_cycle(period);
+    let start_date = normalize_date(start_date).unwrap_or_else(|| start_date.to_string());
+
+    let (category, category_guessed) = match category {
+        Some(category) => (category, false),
+        None => (guess_category(&name).to_string(), true),
+    };
+
+    let errors = collect_errors([
+        validate_subscription_name(&name),
+        amount_check,
+        billing_cycle
+            .map(|_| ())
+            .ok_or_else(|| AppError::validation(format!("請求周期を判別できません: {period}"))),
+        validate_date(&start_date),
+        validate_category(&category),
+    ]);
+
+    let dto = CreateSubscriptionDto {
+        name,
+        amount,
+        billing_cycle: billing_cycle.unwrap_or(period).to_string(),
+        start_date,
+        category,
+        category_id: None,
+        currency: Some(currency),
+    };
+
+    SubscriptionImportCandidate {
+        line,
+        dto,
+        category_guessed,
+        errors,
+    }
+}
+
+/// ローカライズされた金額文字列を解析する
+///
+/// "¥1,080"、"1080円"、"$9.99" のような表記を受け付けます。
+/// 通貨記号がない場合は日本円とみなします。
+///
+/// # 戻り値
+/// (金額, 通貨)
+fn parse_localized_amount(text: &str) -> AppResult<(f64, CurrencyCode)> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(AppError::validation("金額が空です"));
+    }
+
+    let upper = text.to_uppercase();
+    let currency = if upper.contains("US$") || upper.contains("USD") || text.contains('$') {
+        CurrencyCode::Usd
+    } else if upper.contains("EUR") || text.contains('€') {
+        CurrencyCode::Eur
+    } else if upper.contains("GBP") || text.contains('£') {
+        CurrencyCode::Gbp
+    } else {
+        CurrencyCode::Jpy
+    };
+
+    let numeric: String = text
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.' || *c == '-')
+        .collect();
+
+    numeric
+        .parse::<f64>()
+        .map(|amount| (amount, currency))
+        .map_err(|_| AppError::validation(format!("金額を解析できません: {text}")))
+}
+
+/// 請求周期の表記を "monthly" / "annual" に正規化する
+fn normalize_billing_cycle(text: &str) -> Option<&'static str> {
+    let lower = text.trim().to_lowercase();
+    match lower.as_str() {
+        "monthly" | "month" | "1 month" | "月額" | "毎月" | "1ヶ月" | "1か月" => {
+            Some("monthly")
+        }
+        "annual" | "annually" | "yearly" | "year" | "1 year" | "年額" | "毎年" | "1年" => {
+            Some("annual")
+        }
+        _ => None,
+    }
+}
+
+/// 日付表記を YYYY-MM-DD 形式に正規化する
+fn normalize_date(text: &str) -> Option<String> {
+    let text = text.trim();
+    // "2024-01-15T10:00:00Z" のような日時表記は日付部分のみを使う
+    let date_part = text.get(..10).unwrap_or(text);
+
+    ["%Y-%m-%d", "%Y/%m/%d"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(date_part, format).ok())
+        .or_else(|| NaiveDate::parse_from_str(text, "%m/%d/%Y").ok())
+        .map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+/// サービス名からカテゴリを推測する
+fn guess_category(name: &str) -> &'static str {
+    const COMMUNICATION_KEYWORDS: &[&str] = &[
+        "icloud",
+        "google one",
+        "dropbox",
+        "onedrive",
+        "storage",
+        "vpn",
+        "mobile",
+        "wi-fi",
+        "wifi",
+        "ストレージ",
+        "通信",
+        "回線",
+    ];
+    const SUPPLIES_KEYWORDS: &[&str] = &[
+        "office",
+        "microsoft 365",
+        "adobe",
+        "notion",
+        "github",
+        "slack",
+        "zoom",
+        "chatgpt",
+    ];
+
+    let lower = name.to_lowercase();
+    if COMMUNICATION_KEYWORDS.iter().any(|k| lower.contains(k)) {
+        "通信費"
+    } else if SUPPLIES_KEYWORDS.iter().any(|k| lower.contains(k)) {
+        "消耗品費"
+    } else {
+        "その他"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::database::connection::open_and_migrate_in_memory_database;
+
+    const APPLE_FIXTURE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/subscription_import/apple_purchase_history.csv"
+    ));
+    const GENERIC_FIXTURE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/subscription_import/generic.csv"
+    ));
+
+    #[test]
+    fn test_parse_localized_amount() {
+        assert_eq!(
+            parse_localized_amount("¥1,080").unwrap(),
+            (1080.0, CurrencyCode::Jpy)
+        );
+        assert_eq!(
+            parse_localized_amount("￥130").unwrap(),
+            (130.0, CurrencyCode::Jpy)
+        );
+        assert_eq!(
+            parse_localized_amount("1,480円").unwrap(),
+            (1480.0, CurrencyCode::Jpy)
+        );
+        assert_eq!(
+            parse_localized_amount("980").unwrap(),
+            (980.0, CurrencyCode::Jpy)
+        );
+        assert_eq!(
+            parse_localized_amount("$9.99").unwrap(),
+            (9.99, CurrencyCode::Usd)
+        );
+        assert_eq!(
+            parse_localized_amount("€4.99").unwrap(),
+            (4.99, CurrencyCode::Eur)
+        );
+        assert!(parse_localized_amount("").is_err());
+    }
+
+    #[test]
+    fn test_normalize_billing_cycle_and_date() {
+        assert_eq!(normalize_billing_cycle("1 Month"), Some("monthly"));
+        assert_eq!(normalize_billing_cycle("年額"), Some("annual"));
+        assert_eq!(normalize_billing_cycle("weekly"), None);
+
+        assert_eq!(normalize_date("2024/01/15").unwrap(), "2024-01-15");
+        assert_eq!(
+            normalize_date("2024-01-15T09:00:00Z").unwrap(),
+            "2024-01-15"
+        );
+        assert_eq!(normalize_date("01/15/2024").unwrap(), "2024-01-15");
+        assert!(normalize_date("昨日").is_none());
+    }
+
+    #[test]
+    fn test_parse_apple_fixture() {
+        let candidates =
+            parse_export_content(APPLE_FIXTURE, SubscriptionExportSource::Apple).unwrap();
+
+        // アプリ購入は除外され、同名の更新履歴はまとめられる
+        assert_eq!(candidates.len(), 4);
+
+        let icloud = candidates
+            .iter()
+            .find(|c| c.dto.name == "iCloud+ 50GB")
+            .unwrap();
+        assert!(icloud.is_valid());
+        assert_eq!(icloud.dto.amount, 130.0);
+        assert_eq!(icloud.dto.start_date, "2024-01-15");
+        assert_eq!(icloud.dto.billing_cycle, "monthly");
+        assert_eq!(icloud.dto.category, "通信費");
+        assert!(icloud.category_guessed);
+
+        let music = candidates
+            .iter()
+            .find(|c| c.dto.name == "Apple Music")
+            .unwrap();
+        assert!(music.is_valid());
+        assert_eq!(music.dto.amount, 1080.0);
+
+        let notion = candidates
+            .iter()
+            .find(|c| c.dto.name == "Notion Plus")
+            .unwrap();
+        assert_eq!(notion.dto.billing_cycle, "annual");
+        assert!(notion.is_valid());
+        assert_eq!(notion.dto.amount, 96.0);
+        assert_eq!(notion.dto.currency, Some(CurrencyCode::Usd));
+    }
+
+    #[test]
+    fn test_parse_generic_fixture() {
+        let candidates =
+            parse_export_content(GENERIC_FIXTURE, SubscriptionExportSource::Generic).unwrap();
+        assert_eq!(candidates.len(), 4);
+
+        assert!(candidates[0].is_valid());
+        assert_eq!(candidates[0].dto.name, "Netflix");
+        assert_eq!(candidates[0].dto.amount, 1490.0);
+        assert_eq!(candidates[0].dto.category, "その他");
+        assert!(candidates[0].category_guessed);
+        assert_eq!(candidates[0].line, 2);
+
+        // カテゴリ列の指定は推測より優先される
+        assert_eq!(candidates[1].dto.category, "通信費");
+        assert!(!candidates[1].category_guessed);
+        assert_eq!(candidates[1].dto.billing_cycle, "annual");
+
+        // 不正な日付・周期はエラーとして報告される
+        assert!(!candidates[2].is_valid());
+        assert!(!candidates[3].is_valid());
+    }
+
+    #[test]
+    fn test_parse_missing_column() {
+        let result = parse_export_content("name,price\nA,100\n", SubscriptionExportSource::Generic);
+        assert!(result.is_err());
+        assert!(parse_export_content("", SubscriptionExportSource::Generic).is_err());
+    }
+
+    #[test]
+    fn test_parse_export_with_provenance() {
+        use crate::shared::export::{write_csv_export, ExportMeta};
+
+        let mut exported = Vec::new();
+        write_csv_export(&mut exported, &ExportMeta::current(Some("user_abc")), |w| {
+            w.write_all(GENERIC_FIXTURE.as_bytes())?;
+            Ok(())
+        })
+        .unwrap();
+        let exported = String::from_utf8(exported).unwrap();
+
+        let candidates =
+            parse_export_content(&exported, SubscriptionExportSource::Generic).unwrap();
+        assert_eq!(candidates.len(), 4);
+        assert_eq!(candidates[0].dto.name, "Netflix");
+
+        // チェックサムが一致しない場合は解析前に拒否される
+        let tampered = exported.replace("Netflix", "Netflux");
+        assert!(parse_export_content(&tampered, SubscriptionExportSource::Generic).is_err());
+    }
+
+    fn dto(name: &str) -> CreateSubscriptionDto {
+        CreateSubscriptionDto {
+            name: name.to_string(),
+            amount: 100.0,
+            billing_cycle: "monthly".to_string(),
+            start_date: "2024-01-01".to_string(),
+            category: "その他".to_string(),
+            category_id: None,
+            currency: None,
+        }
+    }
+
+    #[test]
+    fn test_partition_duplicates() {
+        let (to_create, skipped) = partition_duplicates(
+            vec![
+                dto("Netflix"),
+                dto(" Spotify "),
+                dto("Spotify"),
+                dto("Hulu"),
+            ],
+            &["Netflix".to_string()],
+        );
+
+        let names: Vec<&str> = to_create.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec![" Spotify ", "Hulu"]);
+        assert_eq!(skipped.len(), 2);
+        assert_eq!(skipped[0].name, "Netflix");
+        assert_eq!(skipped[1].name, "Spotify");
+    }
+
+    #[test]
+    fn test_prepare_import_skips_duplicates() {
+        let (to_create, skipped) = prepare_import(
+            vec![dto("Netflix"), dto(" Spotify "), dto("Spotify")],
+            &["Netflix".to_string()],
+        )
+        .unwrap();
+
+        let names: Vec<&str> = to_create.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["Spotify"]);
+        assert_eq!(skipped.len(), 2);
+    }
+
+    #[test]
+    fn test_prepare_import_rejects_invalid_candidates() {
+        let mut invalid = dto("Hulu");
+        invalid.billing_cycle = "weekly".to_string();
+        assert!(prepare_import(vec![dto("Netflix"), invalid], &[]).is_err());
+
+        // 通貨ごとの精度を超える金額は拒否される
+        let mut jpy = dto("Apple Music");
+        jpy.amount = 1080.5;
+        assert!(prepare_import(vec![jpy], &[]).is_err());
+
+        // 日本円以外の金額も通貨に応じて検証した上で取り込める
+        let mut usd = dto("iCloud+");
+        usd.amount = 9.99;
+        usd.currency = Some(CurrencyCode::Usd);
+        let (to_create, _) = prepare_import(vec![usd], &[]).unwrap();
+        assert_eq!(to_create[0].currency, Some(CurrencyCode::Usd));
+    }
+}
@@ -6,14 +6,126 @@
 /// - 月額合計の計算
 /// - 領収書パスの管理
 /// - APIサーバー経由でのサブスクリプション操作
+/// - エクスポートファイル（Apple購入履歴・汎用CSV）からの取り込み
+/// - 更新予定のサブスクリプション検索
+/// - 年間の月別支払見込みの計算
 pub mod api_commands;
+pub mod forecast;
+pub mod import;
 pub mod models;
+pub mod repository;
 
 // 公開インターフェース
 pub use api_commands::{
-    create_subscription, delete_subscription, delete_subscription_receipt_via_api,
-    get_monthly_subscription_total, get_subscriptions, toggle_subscription_status,
-    update_subscription,
+    clone_subscription, create_subscription, delete_subscription,
+    delete_subscription_receipt_via_api, get_annual_cost_breakdown, get_monthly_subscription_total,
+    get_subscription_by_id, get_subscriptions, get_upcoming_renewals, import_subscriptions,
+    parse_subscription_export, toggle_subscription_status, update_subscription,
 };
 
-pub use models::{CreateSubscriptionDto, Subscription, UpdateSubscriptionDto};
+pub use import::{SubscriptionExportSource, SubscriptionImportCandidate, SubscriptionImportResult};
+
+pub use models::{
+    CreateSubscriptionDto, Subscription, SubscriptionFilter, SubscriptionPayment,
+    SubscriptionRenewal, UpdateSubscriptionDto,
+};
+
+use crate::shared::capabilities::{Capability, Requirement};
+
+/// サブスクリプション機能の利用者向け機能
+pub const CAPABILITIES: &[Capability] = &[
+    Capability::new(
+        "subscriptions.create",
+        "capability.subscriptions.create",
+        "create_subscription",
+    )
+    .requires(&[Requirement::Authenticated, Requirement::ApiServerConfigured])
+    .mutating(),
+    Capability::new(
+        "subscriptions.list",
+        "capability.subscriptions.list",
+        "get_subscriptions",
+    )
+    .requires(&[Requirement::Authenticated, Requirement::ApiServerConfigured]),
+    Capability::new(
+        "subscriptions.clone",
+        "capability.subscriptions.clone",
+        "clone_subscription",
+    )
+    .requires(&[Requirement::Authenticated, Requirement::ApiServerConfigured])
+    .mutating(),
+    Capability::new(
+        "subscriptions.update",
+        "capability.subscriptions.update",
+        "update_subscription",
+    )
+    .requires(&[Requirement::Authenticated, Requirement::ApiServerConfigured])
+    .mutating(),
+    Capability::new(
+        "subscriptions.toggle_status",
+        "capability.subscriptions.toggle_status",
+        "toggle_subscription_status",
+    )
+    .requires(&[Requirement::Authenticated, Requirement::ApiServerConfigured])
+    .mutating(),
+    Capability::new(
+        "subscriptions.delete",
+        "capability.subscriptions.delete",
+        "delete_subscription",
+    )
+    .requires(&[Requirement::Authenticated, Requirement::ApiServerConfigured])
+    .destructive(),
+    Capability::new(
+        "subscriptions.upload_receipt",
+        "capability.subscriptions.upload_receipt",
+        "upload_subscription_receipt_via_api",
+    )
+    .requires(&[
+        Requirement::Authenticated,
+        Requirement::ApiServerConfigured,
+        Requirement::StorageOnline,
+    ])
+    .mutating(),
+    Capability::new(
+        "subscriptions.delete_receipt",
+        "capability.subscriptions.delete_receipt",
+        "delete_subscription_receipt_via_api",
+    )
+    .requires(&[
+        Requirement::Authenticated,
+        Requirement::ApiServerConfigured,
+        Requirement::StorageOnline,
+    ])
+    .destructive(),
+    Capability::new(
+        "subscriptions.delete_receipt_from_r2",
+        "capability.subscriptions.delete_receipt_from_r2",
+        "delete_subscription_receipt_from_r2",
+    )
+    .requires(&[
+        Requirement::Authenticated,
+        Requirement::ApiServerConfigured,
+        Requirement::StorageOnline,
+    ])
+    .destructive()
+    .internal(),
+    Capability::new(
+        "subscriptions.import",
+        "capability.subscriptions.import",
+        "import_subscriptions",
+    )
+    .requires(&[Requirement::Authenticated, Requirement::ApiServerConfigured])
+    .mutating(),
+    Capability::new(
+        "subscriptions.upcoming_renewals",
+        "capability.subscriptions.upcoming_renewals",
+        "get_upcoming_renewals",
+    )
+    .requires(&[Requirement::Authenticated, Requirement::ApiServerConfigured]),
+    Capability::new(
+        "subscriptions.annual_cost_breakdown",
+        "capability.subscriptions.annual_cost_breakdown",
+        "get_annual_cost_breakdown",
+    )
+    .requires(&[Requirement::Authenticated, Requirement::ApiServerConfigured]),
+];
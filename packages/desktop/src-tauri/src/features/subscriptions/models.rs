@@ -1,3 +1,10 @@
+use crate::shared::errors::{AppError, AppResult, ValidationError};
+use crate::shared::utils::date_utils::{days_between, next_cycle_date, parse_ymd, today_jst};
+use crate::shared::utils::{
+    round_to_currency_precision, validate_amount_for_currency, validate_category, validate_date,
+    validate_subscription_name, CurrencyCode,
+};
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
 /// サブスクリプションデータモデル
@@ -16,8 +23,93 @@ pub struct Subscription {
     pub updated_at: String,           // RFC3339形式（JST）
 }
 
+impl Subscription {
+    /// 請求サイクル1回あたりの月数を取得する
+    ///
+    /// # 戻り値
+    /// 月額は1、年額は12、不明な請求サイクルの場合はバリデーションエラー
+    pub fn months_per_cycle(&self) -> AppResult<u32> {
+        match self.billing_cycle.as_str() {
+            "monthly" => Ok(1),
+            "annual" => Ok(12),
+            other => Err(AppError::validation(format!(
+                "不明な請求サイクルです: {other}"
+            ))),
+        }
+    }
+
+    /// 月額換算の金額を取得する
+    ///
+    /// 月額はそのまま、年額は12で割った額を小数点以下2桁に丸めて返します。
+    ///
+    /// # 戻り値
+    /// 月額換算の金額、または不明な請求サイクルの場合はバリデーションエラー
+    pub fn try_monthly_equivalent(&self) -> AppResult<f64> {
+        Ok(round_to_currency_precision(
+            self.amount / f64::from(self.months_per_cycle()?),
+        ))
+    }
+
+    /// 月額換算の金額を取得する
+    ///
+    /// 請求サイクルが不明で換算できない場合は`amount`をそのまま返します。
+    /// 不正なデータを区別する必要がある場合は`try_monthly_equivalent`を使用してください。
+    pub fn monthly_equivalent(&self) -> f64 {
+        self.try_monthly_equivalent()
+            .unwrap_or_else(|_| round_to_currency_precision(self.amount))
+    }
+
+    /// 基準日以降で最初の更新日を計算する
+    ///
+    /// 開始日から請求サイクルごとに進め、基準日以降となる最初の日付を返します。
+    /// 月末開始の場合、短い月では月末日に丸められます。
+    ///
+    /// # 引数
+    /// * `reference_date` - 基準日
+    ///
+    /// # 戻り値
+    /// 次回更新日、または開始日・請求サイクルが不正な場合はバリデーションエラー
+    pub fn try_next_renewal_date(&self, reference_date: NaiveDate) -> AppResult<NaiveDate> {
+        let start_date = parse_ymd(&self.start_date).map_err(|e| {
+            AppError::validation(format!("開始日の形式が不正です（id={}）: {e}", self.id))
+        })?;
+        next_cycle_date(start_date, self.months_per_cycle()?, reference_date)
+            .ok_or_else(|| AppError::validation("更新日の計算範囲を超えました"))
+    }
+
+    /// 基準日以降で最初の更新日を計算する
+    ///
+    /// 開始日・請求サイクルが不正で計算できない場合は基準日を返します。
+    /// 不正なデータを区別する必要がある場合は`try_next_renewal_date`を使用してください。
+    ///
+    /// # 引数
+    /// * `reference_date` - 基準日
+    pub fn next_renewal_date(&self, reference_date: NaiveDate) -> NaiveDate {
+        self.try_next_renewal_date(reference_date)
+            .unwrap_or(reference_date)
+    }
+
+    /// 次回更新日までの日数を取得する（JSTの今日を基準とする）
+    ///
+    /// # 戻り値
+    /// 日数（今日が更新日の場合は0）。無効化されている場合や、
+    /// 開始日・請求サイクルが不正な場合はNone
+    pub fn days_until_next_renewal(&self) -> Option<i64> {
+        self.days_until_next_renewal_from(today_jst())
+    }
+
+    /// 基準日から次回更新日までの日数を取得する
+    fn days_until_next_renewal_from(&self, reference_date: NaiveDate) -> Option<i64> {
+        if !self.is_active {
+            return None;
+        }
+        let next_renewal = self.try_next_renewal_date(reference_date).ok()?;
+        Some(days_between(reference_date, next_renewal))
+    }
+}
+
 /// サブスクリプション作成用DTO
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CreateSubscriptionDto {
     pub name: String,
     pub amount: f64,
@@ -25,6 +117,37 @@ pub struct CreateSubscriptionDto {
     pub start_date: String,
     pub category: String,
     pub category_id: Option<i64>, // カテゴリーID（推奨）
+    /// 金額の通貨（未指定の場合は日本円）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<CurrencyCode>,
+}
+
+impl CreateSubscriptionDto {
+    /// すべてのフィールドのバリデーションを行う
+    ///
+    /// 最初のエラーで中断せず、すべてのフィールドを検証します。
+    ///
+    /// # 戻り値
+    /// フィールドごとのバリデーションエラー一覧（問題がない場合は空）
+    pub fn validate_all(&self) -> AppResult<Vec<ValidationError>> {
+        let billing_cycle_check = match self.billing_cycle.as_str() {
+            "monthly" | "annual" => Ok(()),
+            _ => Err(AppError::validation(
+                "請求周期は monthly または annual を指定してください",
+            )),
+        };
+
+        Ok(ValidationError::collect([
+            ("name", validate_subscription_name(&self.name)),
+            (
+                "amount",
+                validate_amount_for_currency(self.amount, self.currency.unwrap_or_default()),
+            ),
+            ("billing_cycle", billing_cycle_check),
+            ("start_date", validate_date(&self.start_date)),
+            ("category", validate_category(&self.category)),
+        ]))
+    }
 }
 
 /// サブスクリプション更新用DTO
@@ -38,3 +161,351 @@ pub struct UpdateSubscriptionDto {
     pub category_id: Option<i64>, // カテゴリーID（推奨）
     pub receipt_path: Option<String>,
 }
+
+/// サブスクリプションの支払い履歴
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SubscriptionPayment {
+    pub id: i64,
+    pub subscription_id: i64,
+    pub amount: f64,                 // 支払額
+    pub paid_at: String,             // YYYY-MM-DD形式
+    pub receipt_url: Option<String>, // 領収書URL
+    pub notes: Option<String>,       // メモ
+    pub created_at: String,          // RFC3339形式（JST）
+}
+
+/// 次回更新日付きのサブスクリプション
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SubscriptionRenewal {
+    #[serde(flatten)]
+    pub subscription: Subscription,
+    pub next_renewal_date: String, // YYYY-MM-DD形式
+}
+
+/// サブスクリプションの検索条件
+///
+/// `ExpenseFilter`と同様に、すべて任意で、指定した条件はAND結合されます。
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct SubscriptionFilter {
+    pub billing_cycle: Option<String>, // "monthly" または "annual"
+    pub is_active: Option<bool>,       // 有効/無効
+    pub category: Option<String>,      // カテゴリ名
+    pub min_amount: Option<f64>,       // 最小金額（この金額を含む）
+    pub max_amount: Option<f64>,       // 最大金額（この金額を含む）
+}
+
+impl SubscriptionFilter {
+    /// 条件なしの検索条件を作成する
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 請求サイクルを指定する
+    pub fn with_billing_cycle(mut self, billing_cycle: impl Into<String>) -> Self {
+        self.billing_cycle = Some(billing_cycle.into());
+        self
+    }
+
+    /// 有効/無効を指定する
+    pub fn with_is_active(mut self, is_active: bool) -> Self {
+        self.is_active = Some(is_active);
+        self
+    }
+
+    /// カテゴリを指定する
+    pub fn with_category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// 金額の範囲を指定する
+    pub fn with_amount_range(mut self, min_amount: Option<f64>, max_amount: Option<f64>) -> Self {
+        self.min_amount = min_amount;
+        self.max_amount = max_amount;
+        self
+    }
+
+    /// 検索条件のバリデーションを行う
+    ///
+    /// # 戻り値
+    /// 条件が正しい場合はOk(())、不正な場合はバリデーションエラー
+    pub fn validate(&self) -> AppResult<()> {
+        if let Some(billing_cycle) = &self.billing_cycle {
+            if billing_cycle != "monthly" && billing_cycle != "annual" {
+                return Err(AppError::validation(
+                    "請求周期は monthly または annual を指定してください",
+                ));
+            }
+        }
+
+        for amount in [self.min_amount, self.max_amount].into_iter().flatten() {
+            if !amount.is_finite() || amount < 0.0 {
+                return Err(AppError::validation(
+                    "金額の範囲は0以上の数値で指定してください",
+                ));
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_amount, self.max_amount) {
+            if min > max {
+                return Err(AppError::validation(
+                    "最小金額は最大金額以下の値を指定してください",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// サブスクリプションが検索条件に一致するかどうか
+    ///
+    /// API Serverが対応していない条件を取得後に適用する場合に使用します。
+    pub fn matches(&self, subscription: &Subscription) -> bool {
+        self.billing_cycle
+            .as_deref()
+            .is_none_or(|cycle| subscription.billing_cycle == cycle)
+            && self
+                .is_active
+                .is_none_or(|is_active| subscription.is_active == is_active)
+            && self
+                .category
+                .as_deref()
+                .is_none_or(|category| subscription.category == category)
+            && self.min_amount.is_none_or(|min| subscription.amount >= min)
+            && self.max_amount.is_none_or(|max| subscription.amount <= max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscription(billing_cycle: &str, amount: f64, is_active: bool) -> Subscription {
+        Subscription {
+            id: 1,
+            name: "Netflix".to_string(),
+            amount,
+            billing_cycle: billing_cycle.to_string(),
+            start_date: "2024-01-01".to_string(),
+            category: "娯楽".to_string(),
+            category_id: None,
+            is_active,
+            receipt_path: None,
+            created_at: "2024-01-01T00:00:00+09:00".to_string(),
+            updated_at: "2024-01-01T00:00:00+09:00".to_string(),
+        }
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        parse_ymd(s).unwrap()
+    }
+
+    fn subscription_starting(billing_cycle: &str, start_date: &str) -> Subscription {
+        Subscription {
+            start_date: start_date.to_string(),
+            ..subscription(billing_cycle, 1000.0, true)
+        }
+    }
+
+    #[test]
+    fn test_create_subscription_dto_validate_all() {
+        let dto = CreateSubscriptionDto {
+            name: "クラウドストレージ".to_string(),
+            amount: 1300.0,
+            billing_cycle: "monthly".to_string(),
+            start_date: "2024-01-15".to_string(),
+            category: "通信費".to_string(),
+            category_id: None,
+            currency: None,
+        };
+        assert!(dto.validate_all().unwrap().is_empty());
+
+        let invalid = CreateSubscriptionDto {
+            name: "".to_string(),
+            amount: 0.0,
+            billing_cycle: "weekly".to_string(),
+            start_date: "2024-02-30".to_string(),
+            category: "".to_string(),
+            category_id: None,
+            currency: None,
+        };
+        let fields: Vec<String> = invalid
+            .validate_all()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.field)
+            .collect();
+        assert_eq!(
+            fields,
+            ["name", "amount", "billing_cycle", "start_date", "category"]
+        );
+    }
+
+    #[test]
+    fn test_create_subscription_dto_currency() {
+        let dto = CreateSubscriptionDto {
+            name: "動画配信".to_string(),
+            amount: 100.5,
+            billing_cycle: "monthly".to_string(),
+            start_date: "2024-01-15".to_string(),
+            category: "娯楽".to_string(),
+            category_id: None,
+            currency: None,
+        };
+
+        // 通貨の指定がない場合は日本円として端数を拒否する
+        let fields: Vec<String> = dto
+            .validate_all()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.field)
+            .collect();
+        assert_eq!(fields, ["amount"]);
+
+        let jpy = CreateSubscriptionDto {
+            currency: Some(CurrencyCode::Jpy),
+            ..dto.clone()
+        };
+        assert_eq!(jpy.validate_all().unwrap().len(), 1);
+
+        let usd = CreateSubscriptionDto {
+            currency: Some(CurrencyCode::Usd),
+            ..dto
+        };
+        assert!(usd.validate_all().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_monthly_equivalent() {
+        assert_eq!(
+            subscription("monthly", 1490.0, true).monthly_equivalent(),
+            1490.0
+        );
+        assert_eq!(
+            subscription("annual", 12000.0, true).monthly_equivalent(),
+            1000.0
+        );
+        assert_eq!(
+            subscription("annual", 980.0, true).monthly_equivalent(),
+            81.67
+        );
+
+        // 不明な請求サイクルはtry版ではエラー、通常版では金額をそのまま返す
+        let weekly = subscription("weekly", 500.0, true);
+        assert!(matches!(
+            weekly.try_monthly_equivalent(),
+            Err(AppError::Validation(_))
+        ));
+        assert_eq!(weekly.monthly_equivalent(), 500.0);
+    }
+
+    #[test]
+    fn test_next_renewal_date() {
+        let today = date("2024-03-15");
+
+        assert_eq!(
+            subscription_starting("monthly", "2024-01-20").next_renewal_date(today),
+            date("2024-03-20")
+        );
+        assert_eq!(
+            subscription_starting("monthly", "2024-01-15").next_renewal_date(today),
+            date("2024-03-15")
+        );
+        assert_eq!(
+            subscription_starting("annual", "2022-02-01").next_renewal_date(today),
+            date("2025-02-01")
+        );
+        // 未来の開始日はそのまま
+        assert_eq!(
+            subscription_starting("monthly", "2024-04-01").next_renewal_date(today),
+            date("2024-04-01")
+        );
+        // 月末開始は短い月で丸められる
+        assert_eq!(
+            subscription_starting("monthly", "2024-01-31").next_renewal_date(date("2024-02-10")),
+            date("2024-02-29")
+        );
+
+        // 不正なデータ
+        let weekly = subscription_starting("weekly", "2024-01-01");
+        assert!(weekly.try_next_renewal_date(today).is_err());
+        assert_eq!(weekly.next_renewal_date(today), today);
+        assert!(subscription_starting("monthly", "2024/01/01")
+            .try_next_renewal_date(today)
+            .is_err());
+    }
+
+    #[test]
+    fn test_days_until_next_renewal() {
+        let today = date("2024-03-15");
+        assert_eq!(
+            subscription_starting("monthly", "2024-01-20").days_until_next_renewal_from(today),
+            Some(5)
+        );
+        assert_eq!(
+            subscription_starting("monthly", "2024-01-15").days_until_next_renewal_from(today),
+            Some(0)
+        );
+        assert_eq!(
+            subscription_starting("weekly", "2024-01-15").days_until_next_renewal_from(today),
+            None
+        );
+
+        let inactive = Subscription {
+            is_active: false,
+            ..subscription_starting("monthly", "2024-01-20")
+        };
+        assert_eq!(inactive.days_until_next_renewal_from(today), None);
+
+        // 今日を基準とする場合も、次回更新日は1請求サイクル以内
+        let days = subscription_starting("monthly", "2024-01-20")
+            .days_until_next_renewal()
+            .unwrap();
+        assert!((0..=31).contains(&days));
+    }
+
+    #[test]
+    fn test_subscription_filter_validate() {
+        assert!(SubscriptionFilter::new().validate().is_ok());
+        assert!(SubscriptionFilter::new()
+            .with_billing_cycle("annual")
+            .with_amount_range(Some(0.0), Some(1000.0))
+            .validate()
+            .is_ok());
+        assert!(SubscriptionFilter::new()
+            .with_billing_cycle("weekly")
+            .validate()
+            .is_err());
+        assert!(SubscriptionFilter::new()
+            .with_amount_range(Some(1000.0), Some(100.0))
+            .validate()
+            .is_err());
+
+        // 未指定のフィールドは省略できる
+        let filter: SubscriptionFilter = serde_json::from_str(r#"{"is_active": true}"#).unwrap();
+        assert_eq!(filter, SubscriptionFilter::new().with_is_active(true));
+    }
+
+    #[test]
+    fn test_subscription_filter_matches() {
+        let monthly = subscription("monthly", 1490.0, true);
+        let annual = subscription("annual", 14900.0, false);
+
+        assert!(SubscriptionFilter::new().matches(&monthly));
+        assert!(SubscriptionFilter::new()
+            .with_billing_cycle("monthly")
+            .with_is_active(true)
+            .with_category("娯楽")
+            .with_amount_range(Some(1000.0), Some(2000.0))
+            .matches(&monthly));
+        assert!(!SubscriptionFilter::new()
+            .with_billing_cycle("monthly")
+            .matches(&annual));
+        assert!(!SubscriptionFilter::new()
+            .with_is_active(true)
+            .matches(&annual));
+        assert!(!SubscriptionFilter::new()
+            .with_amount_range(None, Some(10000.0))
+            .matches(&annual));
+    }
+}
@@ -0,0 +1,265 @@
+/// サブスクリプションデータのリポジトリ
+///
+/// ローカルSQLiteのサブスクリプションテーブルに対する検索・更新処理を提供します。
+use crate::features::migrations::auto_migration::executor::MigrationExecutorTrait;
+use crate::features::migrations::auto_migration::models::{
+    ExecutableMigrationDefinition, MigrationDefinition,
+};
+use crate::features::migrations::auto_migration::registry::MigrationRegistry;
+use crate::features::subscriptions::models::{Subscription, SubscriptionPayment};
+use crate::shared::errors::{AppError, AppResult};
+use rusqlite::{Connection, Row};
+
+/// サブスクリプションテーブルから取得するカラム
+const SUBSCRIPTION_COLUMNS: &str =
+    "id, name, amount, billing_cycle, start_date, category, is_active, receipt_path, created_at, updated_at";
+
+/// サブスクリプション支払い履歴テーブルのスキーマ
+const SUBSCRIPTION_PAYMENTS_SCHEMA_SQL: &str = "
+    CREATE TABLE IF NOT EXISTS subscription_payments (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        subscription_id INTEGER NOT NULL,
+        amount REAL NOT NULL,
+        paid_at TEXT NOT NULL,
+        receipt_url TEXT,
+        notes TEXT,
+        created_at TEXT NOT NULL,
+        FOREIGN KEY (subscription_id) REFERENCES subscriptions(id) ON DELETE CASCADE
+    );
+    CREATE INDEX IF NOT EXISTS idx_subscription_payments_subscription_paid_at
+        ON subscription_payments(subscription_id, paid_at);
+";
+
+/// サブスクリプション支払い履歴テーブル作成マイグレーション実行器
+pub struct SubscriptionPaymentsSchemaMigration;
+
+impl MigrationExecutorTrait for SubscriptionPaymentsSchemaMigration {
+    fn name(&self) -> &str {
+        "007_create_subscription_payments"
+    }
+
+    fn execute(&self, conn: &Connection) -> Result<(), String> {
+        create_subscription_payments_table(conn)
+            .map_err(|e| format!("subscription_paymentsテーブル作成エラー: {e}"))
+    }
+}
+
+/// サブスクリプション支払い履歴テーブル用マイグレーション定義を取得する
+///
+/// # 戻り値
+/// 実行可能なマイグレーション定義
+pub fn get_subscription_payments_schema_definition() -> ExecutableMigrationDefinition {
+    let definition = MigrationDefinition::new(
+        "007_create_subscription_payments".to_string(),
+        "3.3.0".to_string(),
+        "サブスクリプション支払い履歴テーブルの作成".to_string(),
+        MigrationRegistry::calculate_checksum(SUBSCRIPTION_PAYMENTS_SCHEMA_SQL),
+    );
+
+    ExecutableMigrationDefinition::new(definition, Box::new(SubscriptionPaymentsSchemaMigration))
+}
+
+/// サブスクリプション支払い履歴テーブルとインデックスを作成する
+///
+/// # 引数
+/// * `conn` - データベース接続
+pub fn create_subscription_payments_table(conn: &Connection) -> AppResult<()> {
+    conn.execute_batch(SUBSCRIPTION_PAYMENTS_SCHEMA_SQL)?;
+    Ok(())
+}
+
+/// 行データをサブスクリプションモデルに変換する
+fn map_subscription_row(row: &Row) -> rusqlite::Result<Subscription> {
+    Ok(Subscription {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        amount: row.get(2)?,
+        billing_cycle: row.get(3)?,
+        start_date: row.get(4)?,
+        category: row.get(5)?,
+        category_id: None,
+        is_active: row.get(6)?,
+        receipt_path: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+/// IDを指定してサブスクリプションを取得する
+///
+/// # 引数
+/// * `id` - サブスクリプションID
+/// * `user_id` - ユーザーID
+/// * `conn` - データベース接続
+///
+/// # 戻り値
+/// サブスクリプション（ユーザーのサブスクリプションが存在しない場合はNone）
+pub fn find_by_id(id: i64, user_id: &str, conn: &Connection) -> AppResult<Option<Subscription>> {
+    match conn.query_row(
+        &format!("SELECT {SUBSCRIPTION_COLUMNS} FROM subscriptions WHERE id = ?1 AND user_id = ?2"),
+        rusqlite::params![id, user_id],
+        map_subscription_row,
+    ) {
+        Ok(subscription) => Ok(Some(subscription)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// サブスクリプションの支払い履歴を取得する
+///
+/// # 引数
+/// * `subscription_id` - サブスクリプションID
+/// * `user_id` - ユーザーID
+/// * `conn` - データベース接続
+///
+/// # 戻り値
+/// 支払い履歴（支払日の新しい順）、またはユーザーのサブスクリプションが存在しない場合はエラー
+pub fn get_subscription_payment_history(
+    subscription_id: i64,
+    user_id: &str,
+    conn: &Connection,
+) -> AppResult<Vec<SubscriptionPayment>> {
+    let owned: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM subscriptions WHERE id = ?1 AND user_id = ?2)",
+        rusqlite::params![subscription_id, user_id],
+        |row| row.get(0),
+    )?;
+    if !owned {
+        return Err(AppError::not_found(format!(
+            "サブスクリプション（id={subscription_id}）"
+        )));
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT id, subscription_id, amount, paid_at, receipt_url, notes, created_at
+         FROM subscription_payments
+         WHERE subscription_id = ?1
+         ORDER BY paid_at DESC, id DESC",
+    )?;
+    let payments = stmt
+        .query_map([subscription_id], |row| {
+            Ok(SubscriptionPayment {
+                id: row.get(0)?,
+                subscription_id: row.get(1)?,
+                amount: row.get(2)?,
+                paid_at: row.get(3)?,
+                receipt_url: row.get(4)?,
+                notes: row.get(5)?,
+                created_at: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(payments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::database::connection::create_in_memory_connection;
+
+    const USER_ID: &str = "user_a";
+    const OTHER_USER_ID: &str = "user_b";
+
+    /// ユーザーIDのカラムまで追加したテスト用データベースを作成する
+    fn create_test_connection() -> Connection {
+        let conn = create_in_memory_connection().unwrap();
+        // 認証機能のマイグレーション後と同じくユーザーIDを持たせる
+        conn.execute("ALTER TABLE subscriptions ADD COLUMN user_id TEXT", [])
+            .unwrap();
+        conn
+    }
+
+    fn insert_subscription(
+        conn: &Connection,
+        name: &str,
+        billing_cycle: &str,
+        start_date: &str,
+        is_active: bool,
+    ) {
+        insert_user_subscription(conn, USER_ID, name, billing_cycle, start_date, is_active);
+    }
+
+    fn insert_user_subscription(
+        conn: &Connection,
+        user_id: &str,
+        name: &str,
+        billing_cycle: &str,
+        start_date: &str,
+        is_active: bool,
+    ) {
+        conn.execute(
+            "INSERT INTO subscriptions (name, amount, billing_cycle, start_date, category, is_active, user_id, created_at, updated_at)
+             VALUES (?1, 1000.0, ?2, ?3, '通信費', ?4, ?5, '2024-01-01T00:00:00+09:00', '2024-01-01T00:00:00+09:00')",
+            rusqlite::params![name, billing_cycle, start_date, is_active, user_id],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_find_by_id() {
+        let conn = create_test_connection();
+        insert_subscription(&conn, "Netflix", "monthly", "2024-01-01", true);
+        let id = conn.last_insert_rowid();
+
+        let subscription = find_by_id(id, USER_ID, &conn).unwrap().unwrap();
+        assert_eq!(subscription.name, "Netflix");
+        assert_eq!(subscription.billing_cycle, "monthly");
+        assert!(find_by_id(id + 1, USER_ID, &conn).unwrap().is_none());
+        assert!(find_by_id(id, OTHER_USER_ID, &conn).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_get_subscription_payment_history() {
+        let conn = create_test_connection();
+        create_subscription_payments_table(&conn).unwrap();
+        // 2回目も失敗しない
+        SubscriptionPaymentsSchemaMigration.execute(&conn).unwrap();
+
+        insert_subscription(&conn, "Adobe", "annual", "2022-04-01", true);
+        let id = conn.last_insert_rowid();
+        for (paid_at, amount, receipt_url) in [
+            ("2022-04-01", 65_760.0, None),
+            (
+                "2024-04-01",
+                72_336.0,
+                Some("https://example.com/r/2024.pdf"),
+            ),
+            ("2023-04-01", 72_336.0, None),
+        ] {
+            conn.execute(
+                "INSERT INTO subscription_payments (subscription_id, amount, paid_at, receipt_url, notes, created_at)
+                 VALUES (?1, ?2, ?3, ?4, NULL, '2024-04-01T00:00:00+09:00')",
+                rusqlite::params![id, amount, paid_at, receipt_url],
+            )
+            .unwrap();
+        }
+
+        let payments = get_subscription_payment_history(id, USER_ID, &conn).unwrap();
+        let paid_at: Vec<&str> = payments.iter().map(|p| p.paid_at.as_str()).collect();
+        assert_eq!(paid_at, vec!["2024-04-01", "2023-04-01", "2022-04-01"]);
+        assert_eq!(payments[0].amount, 72_336.0);
+        assert_eq!(
+            payments[0].receipt_url.as_deref(),
+            Some("https://example.com/r/2024.pdf")
+        );
+
+        // 他のユーザーのサブスクリプションの履歴は取得できない
+        assert!(matches!(
+            get_subscription_payment_history(id, OTHER_USER_ID, &conn),
+            Err(AppError::NotFound(_))
+        ));
+
+        insert_subscription(&conn, "Netflix", "monthly", "2024-01-01", true);
+        assert!(
+            get_subscription_payment_history(conn.last_insert_rowid(), USER_ID, &conn)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(matches!(
+            get_subscription_payment_history(999, USER_ID, &conn),
+            Err(AppError::NotFound(_))
+        ));
+    }
+}
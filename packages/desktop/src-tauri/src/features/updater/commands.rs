@@ -1,7 +1,9 @@
 use super::config::UpdaterConfig;
-use super::service::{UpdateInfo, UpdaterService};
+use super::delta::DeltaUpdateInfo;
+use super::service::{ensure_no_pending_migrations, AppVersionInfo, UpdateInfo, UpdaterService};
+use crate::features::migrations::check_auto_migration_status;
 use log::info;
-use tauri::AppHandle;
+use tauri::{AppHandle, Window};
 
 /// アップデートをチェックするコマンド
 #[tauri::command]
@@ -13,13 +15,18 @@ pub async fn check_for_updates(app_handle: AppHandle) -> Result<UpdateInfo, Stri
 }
 
 /// アップデートを強制的にチェックするコマンド（スキップされたバージョンも含む）
+///
+/// チェック中は呼び出し元のウィンドウに`update-check-progress`イベントで進捗を通知します。
 #[tauri::command]
-pub async fn check_for_updates_force(app_handle: AppHandle) -> Result<UpdateInfo, String> {
+pub async fn check_for_updates_force(
+    app_handle: AppHandle,
+    window: Window,
+) -> Result<UpdateInfo, String> {
     info!("アップデート強制チェックコマンドが呼び出されました");
 
     let mut service = UpdaterService::new(app_handle);
     service
-        .check_for_updates_force()
+        .check_for_updates_force_with_progress(&window)
         .await
         .map_err(|e| e.to_string())
 }
@@ -36,10 +43,26 @@ pub async fn download_and_install_update(app_handle: AppHandle) -> Result<(), St
         .map_err(|e| e.to_string())
 }
 
-/// 現在のアプリケーションバージョンを取得するコマンド
+/// 現在のバージョンからの差分アップデートが配信されているかを確認するコマンド
+///
+/// 差分が利用できる場合、`download_and_install_update`は完全なバイナリの代わりに差分を使用します。
 #[tauri::command]
-pub fn get_app_version(app_handle: AppHandle) -> String {
-    app_handle.package_info().version.to_string()
+pub async fn check_for_delta_update(
+    app_handle: AppHandle,
+) -> Result<Option<DeltaUpdateInfo>, String> {
+    info!("差分アップデート確認コマンドが呼び出されました");
+
+    let service = UpdaterService::new(app_handle);
+    service
+        .check_for_delta_update()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 現在のアプリケーションバージョンとビルド情報を取得するコマンド
+#[tauri::command]
+pub fn get_app_version(app_handle: AppHandle) -> AppVersionInfo {
+    AppVersionInfo::new(app_handle.package_info().version.to_string())
 }
 
 /// アップデーター設定を取得するコマンド
@@ -78,6 +101,21 @@ pub async fn skip_version(app_handle: AppHandle, version: String) -> Result<(),
         .map_err(|e| e.to_string())
 }
 
+/// 指定したバージョンのリリースノートを取得するコマンド
+#[tauri::command]
+pub async fn get_update_changelog(
+    app_handle: AppHandle,
+    version: String,
+) -> Result<String, String> {
+    info!("リリースノート取得コマンドが呼び出されました: {version}");
+
+    let service = UpdaterService::new(app_handle);
+    service
+        .get_changelog(&version)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// 自動アップデートチェックを開始するコマンド
 #[tauri::command]
 pub fn start_auto_update_check(app_handle: AppHandle) -> Result<(), String> {
@@ -101,10 +139,21 @@ pub fn stop_auto_update_check(app_handle: AppHandle) -> Result<(), String> {
 }
 
 /// アプリケーションを再起動してアップデートをインストールするコマンド
+///
+/// 再起動の前に、実行中の重要な処理の完了を待ってデータベースを書き出します。
+/// リスクの高い未適用マイグレーションがある場合は、`force`を指定しない限り再起動しません。
 #[tauri::command]
-pub fn restart_application(app_handle: AppHandle) -> Result<(), String> {
+pub async fn restart_application(app_handle: AppHandle, force: Option<bool>) -> Result<(), String> {
     info!("アプリケーション再起動コマンドが呼び出されました");
 
+    let migration_status = check_auto_migration_status(app_handle.clone()).await?;
+    ensure_no_pending_migrations(&migration_status, force.unwrap_or(false))
+        .map_err(|e| e.to_string())?;
+
+    UpdaterService::new(app_handle.clone())
+        .flush_before_shutdown()
+        .await;
+
     // アプリケーションを再起動
     // 注: restart()は実行されるとプロセスが終了するため、この後のコードは実行されない
     app_handle.restart();
@@ -1,3 +1,5 @@
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::utils::jst_datetime;
 use chrono::{DateTime, Utc};
 use chrono_tz::Asia::Tokyo;
 use log::{debug, error, info, warn};
@@ -19,6 +21,15 @@ pub struct UpdaterConfig {
     pub skipped_versions: Vec<String>,
     /// 最後にチェックした時刻（Unix timestamp）
     pub last_check_time: Option<u64>,
+    /// 重要な処理の実行中はインストールを待たず、次回終了時にインストールする
+    #[serde(default)]
+    pub schedule_install_on_next_quit: bool,
+    /// 次回の自動チェック予定時刻（RFC3339形式、JST）
+    ///
+    /// 最後のチェック時刻とチェック間隔から取得時に算出します。
+    /// 自動チェックが無効な場合や、まだ一度もチェックしていない場合はNone
+    #[serde(default, skip_deserializing)]
+    pub next_check_at: Option<String>,
 }
 
 impl Default for UpdaterConfig {
@@ -29,6 +40,8 @@ impl Default for UpdaterConfig {
             include_prereleases: false,
             skipped_versions: Vec::new(),
             last_check_time: None,
+            schedule_install_on_next_quit: false,
+            next_check_at: None,
         }
     }
 }
@@ -164,6 +177,19 @@ impl UpdaterConfig {
             .map(|last_check| last_check + (self.check_interval_hours * 3600))
     }
 
+    /// 次回の自動チェック予定時刻を設定した設定を取得
+    ///
+    /// # 戻り値
+    /// `next_check_at`を算出した設定
+    pub fn with_next_check_at(mut self) -> Self {
+        self.next_check_at = self
+            .get_next_check_time()
+            .filter(|_| self.auto_check_enabled)
+            .and_then(|next_check| DateTime::from_timestamp(next_check as i64, 0))
+            .map(|next_check| jst_datetime::format(&next_check));
+        self
+    }
+
     /// チェックが必要かどうかを判定
     ///
     /// # 戻り値
@@ -182,6 +208,28 @@ impl UpdaterConfig {
         }
     }
 
+    /// アップデート先のバージョンが現在のバージョンより低くないことを検証
+    ///
+    /// # 引数
+    /// * `current` - 現在のバージョン
+    /// * `proposed` - アップデート先のバージョン
+    ///
+    /// # 戻り値
+    /// ダウングレードでない場合はOk(())、ダウングレードまたはバージョンが不正な場合はErr
+    pub fn validate_version_increment(current: &str, proposed: &str) -> AppResult<()> {
+        let parse = |version: &str| {
+            semver::Version::parse(version.trim_start_matches('v'))
+                .map_err(|e| AppError::Validation(format!("invalid version {version}: {e}")))
+        };
+
+        if parse(proposed)? < parse(current)? {
+            return Err(AppError::Validation(format!(
+                "cannot downgrade from {current} to {proposed}"
+            )));
+        }
+        Ok(())
+    }
+
     /// 設定の妥当性を検証
     ///
     /// # 戻り値
@@ -216,6 +264,10 @@ impl UpdaterConfig {
             "include_prereleases".to_string(),
             self.include_prereleases.to_string(),
         );
+        info.insert(
+            "schedule_install_on_next_quit".to_string(),
+            self.schedule_install_on_next_quit.to_string(),
+        );
         info.insert(
             "skipped_versions_count".to_string(),
             self.skipped_versions.len().to_string(),
@@ -267,6 +319,33 @@ mod tests {
         assert!(!config.include_prereleases);
         assert!(config.skipped_versions.is_empty());
         assert!(config.last_check_time.is_none());
+        assert!(!config.schedule_install_on_next_quit);
+    }
+
+    #[test]
+    fn test_validate_version_increment() {
+        assert!(UpdaterConfig::validate_version_increment("1.2.0", "1.3.0").is_ok());
+        assert!(UpdaterConfig::validate_version_increment("1.2.0", "1.2.0").is_ok());
+        assert!(UpdaterConfig::validate_version_increment("1.2.0", "v1.10.0").is_ok());
+        assert!(UpdaterConfig::validate_version_increment("1.2.0-beta.1", "1.2.0").is_ok());
+
+        let error = UpdaterConfig::validate_version_increment("1.10.0", "1.9.3").unwrap_err();
+        assert!(matches!(
+            error,
+            AppError::Validation(ref message) if message == "cannot downgrade from 1.10.0 to 1.9.3"
+        ));
+        assert!(UpdaterConfig::validate_version_increment("1.2.0", "1.2.0-beta.1").is_err());
+        assert!(UpdaterConfig::validate_version_increment("1.2.0", "latest").is_err());
+    }
+
+    #[test]
+    fn test_load_config_without_schedule_option() {
+        // 項目追加前に保存された設定ファイルも読み込める
+        let config: UpdaterConfig = serde_json::from_str(
+            r#"{"auto_check_enabled":true,"check_interval_hours":24,"include_prereleases":false,"skipped_versions":[],"last_check_time":null}"#,
+        )
+        .unwrap();
+        assert!(!config.schedule_install_on_next_quit);
     }
 
     #[test]
@@ -282,6 +361,29 @@ mod tests {
         assert_eq!(config.skipped_versions.len(), 1);
     }
 
+    #[test]
+    fn test_next_check_at() {
+        let mut config = UpdaterConfig::default();
+        assert!(config.clone().with_next_check_at().next_check_at.is_none());
+
+        // 2024-01-01 00:00:00 UTC + 24時間
+        config.last_check_time = Some(1_704_067_200);
+        assert_eq!(
+            config.clone().with_next_check_at().next_check_at.as_deref(),
+            Some("2024-01-02T09:00:00+09:00")
+        );
+
+        config.auto_check_enabled = false;
+        assert!(config.clone().with_next_check_at().next_check_at.is_none());
+
+        // フロントエンドから送られた値は無視する
+        let config: UpdaterConfig = serde_json::from_str(
+            r#"{"auto_check_enabled":true,"check_interval_hours":24,"include_prereleases":false,"skipped_versions":[],"last_check_time":null,"next_check_at":"2024-01-02T09:00:00+09:00"}"#,
+        )
+        .unwrap();
+        assert!(config.next_check_at.is_none());
+    }
+
     #[test]
     fn test_update_last_check_time() {
         let mut config = UpdaterConfig::default();
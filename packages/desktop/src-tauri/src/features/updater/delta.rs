@@ -0,0 +1,386 @@
+//! 差分アップデート
+//!
+//! 配信サーバーが現在のバージョンからのバイナリ差分（`.patch`）を公開している場合に、
+//! 完全なバイナリの代わりに差分をダウンロードして実行ファイルに適用します。
+//!
+//! 差分はbsdiff形式（圧縮なし）です。制御ブロック・差分バイト・追加バイトを繰り返し、
+//! 制御ブロックは符号ビット付きの64ビット整数（リトルエンディアン）3つで構成されます。
+//! 差分を適用した実行ファイルは、配信サーバーが公開するSHA-256と一致する場合のみ置き換えます。
+
+use crate::shared::errors::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 制御ブロックのサイズ（バイト）
+const CONTROL_BLOCK_SIZE: usize = 24;
+
+/// 配信サーバーが公開する差分の情報
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeltaUpdateInfo {
+    /// 差分の適用元のバージョン
+    pub from_version: String,
+    /// 差分を適用した後のバージョン
+    pub to_version: String,
+    /// 差分ファイルのURL
+    pub patch_url: String,
+    /// 差分ファイルのサイズ（バイト）
+    pub patch_size: u64,
+    /// 差分ファイルのSHA-256（16進数）
+    pub patch_sha256: String,
+    /// 完全なバイナリのサイズ（バイト）
+    pub full_size: u64,
+    /// 差分を適用した後の実行ファイルのSHA-256（16進数）
+    pub target_sha256: String,
+}
+
+/// 差分の情報のURLを組み立てる
+///
+/// # 引数
+/// * `base_url` - アップデートの配信元URL
+/// * `current_version` - 現在のバージョン
+pub fn delta_manifest_url(base_url: &str, current_version: &str) -> AppResult<String> {
+    let version = semver::Version::parse(current_version.trim().trim_start_matches('v'))
+        .map_err(|e| AppError::validation(format!("invalid version {current_version}: {e}")))?;
+    Ok(format!(
+        "{}/delta/{}/{version}.json",
+        base_url.trim_end_matches('/'),
+        platform_key()
+    ))
+}
+
+/// 配信サーバーでのプラットフォームの識別子（例: `darwin-aarch64`）
+///
+/// Tauriのアップデーターの`{{target}}-{{arch}}`と同じ形式です。
+fn platform_key() -> String {
+    let os = match std::env::consts::OS {
+        "macos" => "darwin",
+        os => os,
+    };
+    format!("{os}-{}", std::env::consts::ARCH)
+}
+
+/// 差分を使用できるかどうかを判定する
+///
+/// 現在のバージョンに適用できない差分や、完全なバイナリより小さくない差分は使用しません。
+///
+/// # 戻り値
+/// 使用できる場合は差分の情報、使用しない場合はNone
+pub fn select_delta(
+    info: DeltaUpdateInfo,
+    current_version: &str,
+) -> AppResult<Option<DeltaUpdateInfo>> {
+    let parse = |version: &str| {
+        semver::Version::parse(version.trim().trim_start_matches('v'))
+            .map_err(|e| AppError::validation(format!("invalid version {version}: {e}")))
+    };
+
+    if !info.patch_url.starts_with("https://") {
+        return Err(AppError::security(format!(
+            "差分ファイルのURLはHTTPSである必要があります: {}",
+            info.patch_url
+        )));
+    }
+    if parse(&info.from_version)? != parse(current_version)? {
+        return Ok(None);
+    }
+    if parse(&info.to_version)? <= parse(current_version)? {
+        return Ok(None);
+    }
+    if info.patch_size >= info.full_size {
+        return Ok(None);
+    }
+    Ok(Some(info))
+}
+
+/// データのSHA-256が期待値と一致することを確認する
+///
+/// # 引数
+/// * `data` - 確認するデータ
+/// * `expected_hex` - 期待するSHA-256（16進数）
+/// * `label` - エラーメッセージに含める対象の名前
+pub fn verify_sha256(data: &[u8], expected_hex: &str, label: &str) -> AppResult<()> {
+    let actual = format!("{:x}", Sha256::digest(data));
+    if !actual.eq_ignore_ascii_case(expected_hex.trim()) {
+        return Err(AppError::security(format!(
+            "{label}のハッシュが一致しません（期待値: {expected_hex}、実際: {actual}）"
+        )));
+    }
+    Ok(())
+}
+
+/// bsdiff形式の差分を適用する
+///
+/// # 引数
+/// * `old` - 適用元のバイナリ
+/// * `patch` - 差分
+///
+/// # 戻り値
+/// 差分を適用したバイナリ
+pub fn apply_patch(old: &[u8], patch: &[u8]) -> AppResult<Vec<u8>> {
+    let invalid =
+        |message: &str| AppError::validation(format!("差分ファイルが不正です: {message}"));
+
+    let mut new = Vec::with_capacity(old.len());
+    let mut old_pos: i64 = 0;
+    let mut cursor = 0usize;
+
+    while cursor < patch.len() {
+        let control = patch
+            .get(cursor..cursor + CONTROL_BLOCK_SIZE)
+            .ok_or_else(|| invalid("制御ブロックが途中で終わっています"))?;
+        cursor += CONTROL_BLOCK_SIZE;
+
+        let add_len = usize::try_from(read_offset(&control[0..8]))
+            .map_err(|_| invalid("差分の長さが負の値です"))?;
+        let copy_len = usize::try_from(read_offset(&control[8..16]))
+            .map_err(|_| invalid("追加データの長さが負の値です"))?;
+        let seek = read_offset(&control[16..24]);
+
+        // 差分バイトを適用元のバイトに加算する
+        let diff = patch
+            .get(cursor..cursor.saturating_add(add_len))
+            .ok_or_else(|| invalid("差分データが途中で終わっています"))?;
+        cursor += add_len;
+        let start = usize::try_from(old_pos).map_err(|_| invalid("参照位置が範囲外です"))?;
+        let source = old
+            .get(start..start.saturating_add(add_len))
+            .ok_or_else(|| invalid("参照位置が範囲外です"))?;
+        new.extend(source.iter().zip(diff).map(|(o, d)| o.wrapping_add(*d)));
+
+        // 追加バイトをそのまま書き込む
+        let extra = patch
+            .get(cursor..cursor.saturating_add(copy_len))
+            .ok_or_else(|| invalid("追加データが途中で終わっています"))?;
+        cursor += copy_len;
+        new.extend_from_slice(extra);
+
+        old_pos = old_pos
+            .checked_add(add_len as i64)
+            .and_then(|pos| pos.checked_add(seek))
+            .ok_or_else(|| invalid("参照位置が範囲外です"))?;
+    }
+
+    Ok(new)
+}
+
+/// 符号ビット付きの64ビット整数（リトルエンディアン）を読み取る
+fn read_offset(bytes: &[u8]) -> i64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(bytes);
+    let sign = buf[7] & 0x80 != 0;
+    buf[7] &= 0x7f;
+    let magnitude = i64::from_le_bytes(buf);
+    if sign {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// 実行ファイルを置き換える
+///
+/// 新しい実行ファイルを同じディレクトリに書き出してから名前を変更します。
+/// 置き換えに失敗した場合は元の実行ファイルを戻します。
+///
+/// # 引数
+/// * `executable` - 置き換える実行ファイルのパス
+/// * `contents` - 新しい実行ファイルの内容
+pub fn replace_executable(executable: &Path, contents: &[u8]) -> AppResult<()> {
+    let staged = sibling_path(executable, "delta-new");
+    let backup = sibling_path(executable, "delta-old");
+
+    fs::write(&staged, contents)?;
+    // 実行権限などを元の実行ファイルから引き継ぐ
+    let permissions = fs::metadata(executable)?.permissions();
+    fs::set_permissions(&staged, permissions)?;
+
+    if backup.exists() {
+        fs::remove_file(&backup)?;
+    }
+    fs::rename(executable, &backup)?;
+    if let Err(e) = fs::rename(&staged, executable) {
+        let _ = fs::rename(&backup, executable);
+        let _ = fs::remove_file(&staged);
+        return Err(e.into());
+    }
+    // 実行中のファイルは削除できない場合があるため、失敗しても次回の更新時に削除する
+    let _ = fs::remove_file(&backup);
+    Ok(())
+}
+
+/// 実行ファイルと同じディレクトリの作業用ファイルのパス
+fn sibling_path(executable: &Path, suffix: &str) -> PathBuf {
+    let mut name = executable
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_default();
+    name.push(format!(".{suffix}"));
+    executable.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_offset(value: i64) -> [u8; 8] {
+        let mut buf = value.unsigned_abs().to_le_bytes();
+        if value < 0 {
+            buf[7] |= 0x80;
+        }
+        buf
+    }
+
+    fn control(add_len: i64, copy_len: i64, seek: i64) -> Vec<u8> {
+        [add_len, copy_len, seek]
+            .into_iter()
+            .flat_map(encode_offset)
+            .collect()
+    }
+
+    fn sample_info() -> DeltaUpdateInfo {
+        DeltaUpdateInfo {
+            from_version: "1.2.0".to_string(),
+            to_version: "1.3.0".to_string(),
+            patch_url: "https://example.com/delta/1.2.0-1.3.0.patch".to_string(),
+            patch_size: 1_000,
+            patch_sha256: String::new(),
+            full_size: 50_000_000,
+            target_sha256: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_apply_patch() {
+        let old = b"hello world";
+        // 先頭6バイトはそのまま、残りは追加データで置き換える
+        let mut patch = control(6, 6, 5);
+        patch.extend_from_slice(&[0; 6]);
+        patch.extend_from_slice(b"there!");
+
+        assert_eq!(apply_patch(old, &patch).unwrap(), b"hello there!");
+    }
+
+    #[test]
+    fn test_apply_patch_adds_differences() {
+        let old = [10u8, 20, 250];
+        let mut patch = control(3, 0, 0);
+        // 250 + 10 は折り返して4になる
+        patch.extend_from_slice(&[1, 2, 10]);
+
+        assert_eq!(apply_patch(&old, &patch).unwrap(), vec![11, 22, 4]);
+    }
+
+    #[test]
+    fn test_apply_patch_with_negative_seek() {
+        let old = b"abcdef";
+        // 「def」の後に戻って「abc」を再利用する
+        let mut patch = control(0, 0, 3);
+        patch.extend(control(3, 0, -6));
+        patch.extend_from_slice(&[0; 3]);
+        patch.extend(control(3, 0, 0));
+        patch.extend_from_slice(&[0; 3]);
+
+        assert_eq!(apply_patch(old, &patch).unwrap(), b"defabc");
+    }
+
+    #[test]
+    fn test_apply_empty_patch() {
+        assert!(apply_patch(b"anything", &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_apply_patch_rejects_malformed_input() {
+        let old = b"hello";
+
+        // 制御ブロックが途中で終わっている
+        assert!(apply_patch(old, &[0; 10]).is_err());
+
+        // 差分データが足りない
+        let mut patch = control(3, 0, 0);
+        patch.push(0);
+        assert!(apply_patch(old, &patch).is_err());
+
+        // 適用元の範囲外を参照する
+        let mut patch = control(10, 0, 0);
+        patch.extend_from_slice(&[0; 10]);
+        assert!(apply_patch(old, &patch).is_err());
+
+        // 長さが負の値
+        assert!(apply_patch(old, &control(-1, 0, 0)).is_err());
+
+        // 参照位置が負の値になる
+        let mut patch = control(0, 0, -1);
+        patch.extend(control(1, 0, 0));
+        patch.push(0);
+        assert!(apply_patch(old, &patch).is_err());
+    }
+
+    #[test]
+    fn test_verify_sha256() {
+        let hash = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        assert!(verify_sha256(b"hello", hash, "差分ファイル").is_ok());
+        assert!(verify_sha256(b"hello", &hash.to_uppercase(), "差分ファイル").is_ok());
+        assert!(verify_sha256(b"hello!", hash, "差分ファイル").is_err());
+    }
+
+    #[test]
+    fn test_select_delta() {
+        // 現在のバージョンに適用でき、完全なバイナリより小さい
+        assert_eq!(
+            select_delta(sample_info(), "1.2.0").unwrap(),
+            Some(sample_info())
+        );
+        assert!(select_delta(sample_info(), "v1.2.0").unwrap().is_some());
+
+        // 適用元のバージョンが異なる
+        assert_eq!(select_delta(sample_info(), "1.1.0").unwrap(), None);
+
+        // 新しいバージョンではない
+        let info = DeltaUpdateInfo {
+            to_version: "1.2.0".to_string(),
+            ..sample_info()
+        };
+        assert_eq!(select_delta(info, "1.2.0").unwrap(), None);
+
+        // 差分が完全なバイナリより小さくない
+        let info = DeltaUpdateInfo {
+            patch_size: 50_000_000,
+            ..sample_info()
+        };
+        assert_eq!(select_delta(info, "1.2.0").unwrap(), None);
+    }
+
+    #[test]
+    fn test_select_delta_rejects_insecure_url() {
+        let info = DeltaUpdateInfo {
+            patch_url: "http://example.com/delta.patch".to_string(),
+            ..sample_info()
+        };
+        assert!(select_delta(info, "1.2.0").is_err());
+    }
+
+    #[test]
+    fn test_delta_manifest_url() {
+        let url = delta_manifest_url("https://example.com/api/updater/", "v1.2.0").unwrap();
+        assert!(url.starts_with("https://example.com/api/updater/delta/"));
+        assert!(url.ends_with("/1.2.0.json"));
+        assert!(!url.contains("macos"));
+
+        assert!(delta_manifest_url("https://example.com", "../1.2.0").is_err());
+    }
+
+    #[test]
+    fn test_replace_executable() {
+        let dir = tempfile::tempdir().unwrap();
+        let executable = dir.path().join("app");
+        fs::write(&executable, b"old binary").unwrap();
+
+        replace_executable(&executable, b"new binary").unwrap();
+
+        assert_eq!(fs::read(&executable).unwrap(), b"new binary");
+        assert!(!sibling_path(&executable, "delta-new").exists());
+        assert!(!sibling_path(&executable, "delta-old").exists());
+    }
+}
@@ -1,9 +1,48 @@
 pub mod commands;
 pub mod config;
+pub mod delta;
 pub mod errors;
 pub mod logger;
 pub mod service;
 
 pub use config::UpdaterConfig;
+pub use delta::DeltaUpdateInfo;
 pub use errors::UpdateError;
 pub use logger::UpdateLogger;
+
+use crate::shared::capabilities::Capability;
+
+/// アップデート機能の利用者向け機能
+pub const CAPABILITIES: &[Capability] = &[
+    Capability::new(
+        "updater.check",
+        "capability.updater.check",
+        "check_for_updates",
+    ),
+    Capability::new(
+        "updater.install",
+        "capability.updater.install",
+        "download_and_install_update",
+    )
+    .mutating(),
+    Capability::new(
+        "updater.update_config",
+        "capability.updater.update_config",
+        "update_updater_config",
+    )
+    .mutating()
+    .internal(),
+    Capability::new(
+        "updater.skip_version",
+        "capability.updater.skip_version",
+        "skip_version",
+    )
+    .mutating()
+    .internal(),
+    Capability::new(
+        "updater.restart",
+        "capability.updater.restart",
+        "restart_application",
+    )
+    .mutating(),
+];
@@ -1,16 +1,202 @@
 use super::config::UpdaterConfig;
+use super::delta::{self, DeltaUpdateInfo};
 use super::errors::UpdateError;
 use super::logger::UpdateLogger;
+use crate::features::migrations::{AutoMigrationStatus, MigrationRiskLevel};
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::events::{emit_event, AppEvent};
+use crate::shared::operations::{shared_operations, ActiveOperation, OperationRegistry};
+use crate::AppState;
 use chrono::Utc;
 use chrono_tz::Asia::Tokyo;
 use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::Duration;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Manager, Window};
 use tauri_plugin_updater::UpdaterExt;
 
+/// インストール前に実行中の処理の完了を待つ時間の上限
+const INSTALL_WAIT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// 再起動・終了前に実行中の処理の完了を待つ時間の上限
+const SHUTDOWN_FLUSH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// アップデート配信サーバーのURL（環境変数`UPDATE_BASE_URL`で上書き可能）
+const DEFAULT_UPDATE_BASE_URL: &str = "https://orano-keihi.tsucchinoko.workers.dev/api/updater";
+
+/// リリースノート取得のタイムアウト
+const CHANGELOG_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 差分の情報の取得のタイムアウト
+const DELTA_MANIFEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 差分ファイルのダウンロードのタイムアウト
+const DELTA_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// 次回終了時にインストールするアップデートがあるかどうか
+static INSTALL_ON_NEXT_QUIT: AtomicBool = AtomicBool::new(false);
+
+/// インストール待ちの通知内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingInstallBlockedEvent {
+    /// 完了を待っている処理
+    pub operations: Vec<ActiveOperation>,
+    /// 待たずに次回終了時のインストールに切り替えたかどうか
+    pub scheduled_on_next_quit: bool,
+}
+
+/// アップデートチェックの進捗を通知する
+fn emit_check_progress(window: Option<&Window>, stage: UpdateCheckStage) {
+    let Some(window) = window else {
+        return;
+    };
+    if let Err(e) = emit_event(
+        window,
+        AppEvent::UpdateCheckProgress(UpdateCheckProgress { stage }),
+    ) {
+        warn!("アップデートチェックの進捗の通知に失敗: {e}");
+    }
+}
+
+/// インストールを開始できるかどうかの判定結果
+#[derive(Debug, Clone, PartialEq)]
+pub enum InstallWindow {
+    /// 実行中の処理がなく、インストールできる
+    Ready,
+    /// 実行中の処理があるため、次回終了時にインストールする
+    ScheduledOnNextQuit(Vec<ActiveOperation>),
+    /// 待機時間内に処理が終わらなかったため、インストールを見送る
+    Blocked(Vec<ActiveOperation>),
+}
+
+/// 実行中の重要な処理を確認し、インストールを開始できるまで待つ
+///
+/// # 引数
+/// * `registry` - 実行中の処理の一覧
+/// * `timeout` - 処理の完了を待つ時間の上限
+/// * `schedule_on_next_quit` - trueの場合、処理の完了を待たずに次回終了時のインストールに切り替える
+/// * `on_blocked` - 実行中の処理があった場合に、その一覧を受け取る
+///
+/// # 戻り値
+/// 判定結果
+pub async fn wait_for_install_window(
+    registry: &OperationRegistry,
+    timeout: Duration,
+    schedule_on_next_quit: bool,
+    on_blocked: impl FnOnce(&[ActiveOperation]),
+) -> InstallWindow {
+    let operations = registry.active_operations();
+    if operations.is_empty() {
+        return InstallWindow::Ready;
+    }
+
+    on_blocked(&operations);
+    if schedule_on_next_quit {
+        return InstallWindow::ScheduledOnNextQuit(operations);
+    }
+
+    match registry.wait_until_idle(timeout).await {
+        Ok(()) => InstallWindow::Ready,
+        Err(operations) => InstallWindow::Blocked(operations),
+    }
+}
+
+/// 処理の一覧をログ・エラーメッセージ用に整形する
+fn describe_operations(operations: &[ActiveOperation]) -> String {
+    operations
+        .iter()
+        .map(|operation| operation.description.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 次回終了時のインストールが予約されていれば実行する
+///
+/// アプリの終了処理（`RunEvent::Exit`）から呼び出します。再起動は行いません。
+pub fn install_scheduled_update_on_exit(app_handle: &AppHandle) {
+    if !INSTALL_ON_NEXT_QUIT.swap(false, Ordering::SeqCst) {
+        return;
+    }
+
+    info!("予約されたアップデートを終了時にインストールします");
+    let service = UpdaterService::new(app_handle.clone());
+    tauri::async_runtime::block_on(async {
+        service.flush_before_shutdown().await;
+        if let Err(e) = service.install_update(false).await {
+            error!("終了時のアップデートインストールに失敗: {e}");
+        }
+    });
+}
+
+/// 再起動してよいか、未適用のマイグレーションを確認する
+///
+/// リスクレベルが高以上の未適用マイグレーションがある場合は、
+/// 再起動中に適用が中断されないよう、先に適用を済ませるまで再起動を拒否します。
+///
+/// # 引数
+/// * `status` - 自動マイグレーション状態
+/// * `force` - trueの場合は未適用のマイグレーションがあっても再起動する
+pub fn ensure_no_pending_migrations(status: &AutoMigrationStatus, force: bool) -> AppResult<()> {
+    let high_risk = matches!(
+        status.risk_level,
+        MigrationRiskLevel::High | MigrationRiskLevel::Critical
+    );
+    if !high_risk {
+        return Ok(());
+    }
+
+    if force {
+        warn!(
+            "未適用のマイグレーションがありますが、強制的に再起動します: {:?}",
+            status.pending_migrations
+        );
+        return Ok(());
+    }
+
+    warn!(
+        "未適用のマイグレーションがあるため再起動を中止します（リスクレベル: {}）: {:?}",
+        status.risk_level, status.pending_migrations
+    );
+    Err(AppError::configuration(
+        "pending migrations must complete first",
+    ))
+}
+
+/// 取得済みのリリースノート（URLごと）
+fn changelog_cache() -> &'static Mutex<HashMap<String, String>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// リリースノートのURLを組み立てる
+///
+/// バージョンはパスに埋め込むため、semver形式であることを確認します。
+fn changelog_url(base_url: &str, version: &str) -> AppResult<String> {
+    let version = semver::Version::parse(version.trim().trim_start_matches('v'))
+        .map_err(|e| AppError::validation(format!("invalid version {version}: {e}")))?;
+    Ok(format!(
+        "{}/changelog/{version}.md",
+        base_url.trim_end_matches('/')
+    ))
+}
+
+/// 配信サーバーへのリクエストに使用するHTTPクライアントを作成する
+fn update_server_client() -> AppResult<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(DELTA_MANIFEST_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::configuration(format!("HTTPクライアントの作成に失敗しました: {e}")))
+}
+
+/// 配信サーバーとの通信のエラー
+fn update_server_error(message: String) -> AppError {
+    AppError::external_service("アップデートサーバー".to_string(), message)
+}
+
 /// アップデート情報
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateInfo {
@@ -32,6 +218,59 @@ pub struct UpdateInfo {
     pub signature: Option<String>,
 }
 
+/// アップデートチェックの段階
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateCheckStage {
+    /// 配信サーバーに接続中
+    Connecting,
+    /// アップデート情報を取得中
+    FetchingMetadata,
+    /// バージョンを比較中
+    ComparingVersions,
+    /// チェック完了（失敗した場合を含む）
+    Done,
+}
+
+/// アップデートチェックの進捗の通知内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateCheckProgress {
+    pub stage: UpdateCheckStage,
+}
+
+/// アプリケーションのバージョンとビルド情報
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppVersionInfo {
+    /// バージョン
+    pub version: String,
+    /// ビルド日（YYYY-MM-DD形式）
+    pub build_date: Option<String>,
+    /// ビルド元のコミットハッシュ
+    pub git_commit: Option<String>,
+    /// ビルドプロファイル（"debug" または "release"）
+    pub build_profile: String,
+}
+
+impl AppVersionInfo {
+    /// コンパイル時に埋め込まれたビルド情報からバージョン情報を作成
+    ///
+    /// # 引数
+    /// * `version` - アプリケーションのバージョン
+    pub fn new(version: String) -> Self {
+        Self {
+            version,
+            build_date: option_env!("VERGEN_BUILD_DATE").map(str::to_string),
+            git_commit: option_env!("VERGEN_GIT_SHA").map(str::to_string),
+            build_profile: if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "release"
+            }
+            .to_string(),
+        }
+    }
+}
+
 /// アップデートサービス
 pub struct UpdaterService {
     app_handle: AppHandle,
@@ -212,9 +451,9 @@ impl UpdaterService {
         Ok(())
     }
 
-    /// 設定を取得
+    /// 設定を取得（次回の自動チェック予定時刻を含む）
     pub async fn get_config(&self) -> UpdaterConfig {
-        self.config.clone()
+        self.config.clone().with_next_check_at()
     }
 
     /// 設定を更新
@@ -238,19 +477,37 @@ impl UpdaterService {
 
     /// アップデートをチェック
     pub async fn check_for_updates(&mut self) -> Result<UpdateInfo, UpdateError> {
-        self.check_for_updates_internal(false).await
+        self.check_for_updates_internal(false, None).await
     }
 
     /// アップデートを強制的にチェック（スキップされたバージョンも含む）
     pub async fn check_for_updates_force(&mut self) -> Result<UpdateInfo, UpdateError> {
-        self.check_for_updates_internal(true).await
+        self.check_for_updates_internal(true, None).await
+    }
+
+    /// アップデートを強制的にチェックし、進捗をウィンドウに通知する
+    ///
+    /// # 引数
+    /// * `window` - `update-check-progress`イベントの通知先
+    pub async fn check_for_updates_force_with_progress(
+        &mut self,
+        window: &Window,
+    ) -> Result<UpdateInfo, UpdateError> {
+        let result = self.check_for_updates_internal(true, Some(window)).await;
+        emit_check_progress(Some(window), UpdateCheckStage::Done);
+        result
     }
 
     /// アップデートをチェック（内部実装）
     ///
     /// # 引数
     /// * `force` - trueの場合、スキップされたバージョンも含めてチェック
-    async fn check_for_updates_internal(&mut self, force: bool) -> Result<UpdateInfo, UpdateError> {
+    /// * `progress` - 進捗の通知先（Noneの場合は通知しない）
+    async fn check_for_updates_internal(
+        &mut self,
+        force: bool,
+        progress: Option<&Window>,
+    ) -> Result<UpdateInfo, UpdateError> {
         let current_version = self.app_handle.package_info().version.to_string();
 
         // セキュリティチェックを実行
@@ -274,9 +531,15 @@ impl UpdaterService {
             self.logger.log_warning(&format!("設定の保存に失敗: {e}"));
         }
 
+        emit_check_progress(progress, UpdateCheckStage::Connecting);
         match self.app_handle.updater() {
             Ok(updater) => {
-                match updater.check().await {
+                emit_check_progress(progress, UpdateCheckStage::FetchingMetadata);
+                let checked = updater.check().await;
+                if checked.is_ok() {
+                    emit_check_progress(progress, UpdateCheckStage::ComparingVersions);
+                }
+                match checked {
                     Ok(Some(update)) => {
                         info!("アップデートが利用可能: {}", update.version);
 
@@ -346,18 +609,139 @@ impl UpdaterService {
     }
 
     /// アップデートをダウンロードしてインストール
+    ///
+    /// マイグレーションなどの重要な処理の実行中は、完了するまでインストールを待ちます。
+    /// 設定で次回終了時のインストールが有効な場合は、待たずに終了時のインストールを予約します。
     pub async fn download_and_install(&self) -> Result<(), UpdateError> {
         info!("アップデートのダウンロードとインストールを開始...");
 
         // セキュリティチェックを実行
         self.perform_security_checks()?;
 
+        // 実行中の重要な処理を確認
+        let window = wait_for_install_window(
+            &shared_operations(),
+            INSTALL_WAIT_TIMEOUT,
+            self.config.schedule_install_on_next_quit,
+            |operations| self.notify_install_blocked(operations),
+        )
+        .await;
+
+        match window {
+            InstallWindow::Ready => self.install_preferring_delta(true).await,
+            InstallWindow::ScheduledOnNextQuit(operations) => {
+                INSTALL_ON_NEXT_QUIT.store(true, Ordering::SeqCst);
+                self.logger.log_info(&format!(
+                    "実行中の処理があるため、次回終了時にインストールします: {}",
+                    describe_operations(&operations)
+                ));
+                Ok(())
+            }
+            InstallWindow::Blocked(operations) => {
+                let error = UpdateError::installation(format!(
+                    "実行中の処理が完了しないため、インストールを延期しました: {}",
+                    describe_operations(&operations)
+                ));
+                self.logger.log_error(&error);
+                Err(error)
+            }
+        }
+    }
+
+    /// インストールが実行中の処理を待っていることをフロントエンドに通知する
+    fn notify_install_blocked(&self, operations: &[ActiveOperation]) {
+        warn!(
+            "実行中の処理があるため、インストールを待機します: {}",
+            describe_operations(operations)
+        );
+
+        let event = PendingInstallBlockedEvent {
+            operations: operations.to_vec(),
+            scheduled_on_next_quit: self.config.schedule_install_on_next_quit,
+        };
+        if let Err(e) = emit_event(&self.app_handle, AppEvent::PendingInstallBlocked(event)) {
+            warn!("インストール待機の通知に失敗: {e}");
+        }
+    }
+
+    /// 再起動・終了の前に、実行中の処理の完了を待ってデータベースを書き出す
+    pub async fn flush_before_shutdown(&self) {
+        if let Err(operations) = shared_operations()
+            .wait_until_idle(SHUTDOWN_FLUSH_TIMEOUT)
+            .await
+        {
+            let message = format!(
+                "処理の完了を待たずに終了します: {}",
+                describe_operations(&operations)
+            );
+            warn!("{message}");
+            self.logger.log_warning(&message);
+        }
+
+        let Some(state) = self.app_handle.try_state::<AppState>() else {
+            return;
+        };
+        let result =
+            state.try_db(|db| Ok(db.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?));
+        if let Err(e) = result {
+            warn!("データベースの書き出しに失敗: {e}");
+        }
+    }
+
+    /// 差分が利用できる場合は差分で、利用できない場合は完全なバイナリでアップデートする
+    ///
+    /// 差分の確認・適用に失敗した場合も、完全なバイナリのダウンロードに切り替えます。
+    ///
+    /// # 引数
+    /// * `restart` - trueの場合、インストール後にアプリケーションを再起動する
+    async fn install_preferring_delta(&self, restart: bool) -> Result<(), UpdateError> {
+        match self.check_for_delta_update().await {
+            Ok(Some(delta)) => match self.install_delta_update(&delta).await {
+                Ok(()) => {
+                    if let Err(e) = emit_event(&self.app_handle, AppEvent::DownloadComplete) {
+                        warn!("ダウンロード完了通知の送信に失敗: {e}");
+                    }
+                    if restart {
+                        self.flush_before_shutdown().await;
+                        info!("アプリケーションを再起動します...");
+                        self.app_handle.restart();
+                    }
+                    return Ok(());
+                }
+                Err(e) => {
+                    self.logger.log_warning(&format!(
+                        "差分アップデートに失敗したため、完全なバイナリをダウンロードします: {e}"
+                    ));
+                }
+            },
+            Ok(None) => debug!("利用できる差分がないため、完全なバイナリをダウンロードします"),
+            Err(e) => warn!("差分の確認に失敗したため、完全なバイナリをダウンロードします: {e}"),
+        }
+
+        self.install_update(restart).await
+    }
+
+    /// アップデートをダウンロードしてインストールする
+    ///
+    /// # 引数
+    /// * `restart` - trueの場合、インストール後にアプリケーションを再起動する
+    async fn install_update(&self, restart: bool) -> Result<(), UpdateError> {
         match self.app_handle.updater() {
             Ok(updater) => {
                 match updater.check().await {
                     Ok(Some(update)) => {
                         let version = update.version.clone();
 
+                        // 現在より低いバージョンへのダウングレードは行わない
+                        let current_version = self.app_handle.package_info().version.to_string();
+                        if let Err(e) =
+                            UpdaterConfig::validate_version_increment(&current_version, &version)
+                        {
+                            let error = UpdateError::invalid_version(e.to_string());
+                            self.logger.log_error(&error);
+                            return Err(error);
+                        }
+
                         // ログ: ダウンロード開始
                         self.logger.log_download_start(&version, None);
                         info!("アップデートをダウンロード中: {version}");
@@ -387,7 +771,7 @@ impl UpdaterService {
                                         logger.log_download_progress(current_downloaded, total);
 
                                         // フロントエンドに進捗を通知
-                                        if let Err(e) = app_handle.emit("download-progress", progress) {
+                                        if let Err(e) = emit_event(&app_handle, AppEvent::DownloadProgress(progress)) {
                                             warn!("ダウンロード進捗の通知に失敗: {e}");
                                         }
                                     } else {
@@ -414,11 +798,16 @@ impl UpdaterService {
                                 self.logger.log_install_complete(&version);
 
                                 // フロントエンドにダウンロード完了を通知
-                                if let Err(e) = self.app_handle.emit("download-complete", ()) {
+                                if let Err(e) = emit_event(&self.app_handle, AppEvent::DownloadComplete) {
                                     warn!("ダウンロード完了通知の送信に失敗: {e}");
                                 }
 
+                                if !restart {
+                                    return Ok(());
+                                }
+
                                 // アプリケーションを再起動してアップデートを適用
+                                self.flush_before_shutdown().await;
                                 info!("アプリケーションを再起動します...");
                                 let app = self.app_handle.clone();
                                 app.restart();
@@ -456,6 +845,142 @@ impl UpdaterService {
         }
     }
 
+    /// 指定したバージョンのリリースノート（Markdown）を取得
+    ///
+    /// 取得したリリースノートはバージョンごとにキャッシュし、同じバージョンは再取得しません。
+    ///
+    /// # 引数
+    /// * `version` - バージョン（例: `1.2.0`、先頭の`v`は省略可）
+    ///
+    /// # 戻り値
+    /// リリースノートのMarkdown
+    pub async fn get_changelog(&self, version: &str) -> AppResult<String> {
+        let base_url = crate::get_env_var_or_default!("UPDATE_BASE_URL", DEFAULT_UPDATE_BASE_URL);
+        let url = changelog_url(&base_url, version)?;
+
+        if let Some(changelog) = changelog_cache().lock().unwrap().get(&url) {
+            debug!("リリースノートをキャッシュから取得: {url}");
+            return Ok(changelog.clone());
+        }
+
+        info!("リリースノートを取得中: {url}");
+        let client = reqwest::Client::builder()
+            .timeout(CHANGELOG_TIMEOUT)
+            .build()
+            .map_err(|e| {
+                AppError::configuration(format!("HTTPクライアントの作成に失敗しました: {e}"))
+            })?;
+        let response = client.get(&url).send().await.map_err(|e| {
+            AppError::external_service(
+                "アップデートサーバー".to_string(),
+                format!("リリースノートの取得に失敗しました: {e}"),
+            )
+        })?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(AppError::not_found(format!(
+                "バージョン {version} のリリースノート"
+            )));
+        }
+        if !status.is_success() {
+            return Err(AppError::external_service(
+                "アップデートサーバー".to_string(),
+                format!("リリースノートの取得に失敗しました（HTTP {status}）"),
+            ));
+        }
+
+        let changelog = response.text().await.map_err(|e| {
+            AppError::external_service(
+                "アップデートサーバー".to_string(),
+                format!("リリースノートの読み込みに失敗しました: {e}"),
+            )
+        })?;
+        changelog_cache()
+            .lock()
+            .unwrap()
+            .insert(url, changelog.clone());
+
+        Ok(changelog)
+    }
+
+    /// 現在のバージョンからの差分が配信されているかを確認
+    ///
+    /// 差分が配信されていない場合や、完全なバイナリより小さくない場合はNoneを返します。
+    ///
+    /// # 戻り値
+    /// 利用できる差分の情報
+    pub async fn check_for_delta_update(&self) -> AppResult<Option<DeltaUpdateInfo>> {
+        let base_url = crate::get_env_var_or_default!("UPDATE_BASE_URL", DEFAULT_UPDATE_BASE_URL);
+        let current_version = self.app_handle.package_info().version.to_string();
+        let url = delta::delta_manifest_url(&base_url, &current_version)?;
+
+        info!("差分アップデートを確認中: {url}");
+        let response = update_server_client()?
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| update_server_error(format!("差分の情報の取得に失敗しました: {e}")))?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(update_server_error(format!(
+                "差分の情報の取得に失敗しました（HTTP {status}）"
+            )));
+        }
+
+        let info: DeltaUpdateInfo = response
+            .json()
+            .await
+            .map_err(|e| update_server_error(format!("差分の情報が不正です: {e}")))?;
+        delta::select_delta(info, &current_version)
+    }
+
+    /// 差分をダウンロードして実行ファイルに適用する
+    ///
+    /// 差分ファイルと適用後の実行ファイルのハッシュを確認してから実行ファイルを置き換えます。
+    async fn install_delta_update(&self, info: &DeltaUpdateInfo) -> AppResult<()> {
+        UpdaterConfig::validate_version_increment(
+            &self.app_handle.package_info().version.to_string(),
+            &info.to_version,
+        )?;
+
+        self.logger
+            .log_download_start(&info.to_version, Some(info.patch_size));
+        info!(
+            "差分をダウンロード中: {} ({} bytes、完全なバイナリは {} bytes)",
+            info.to_version, info.patch_size, info.full_size
+        );
+
+        let response = update_server_client()?
+            .get(&info.patch_url)
+            .timeout(DELTA_DOWNLOAD_TIMEOUT)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| update_server_error(format!("差分のダウンロードに失敗しました: {e}")))?;
+        let patch = response
+            .bytes()
+            .await
+            .map_err(|e| update_server_error(format!("差分のダウンロードに失敗しました: {e}")))?;
+        delta::verify_sha256(&patch, &info.patch_sha256, "差分ファイル")?;
+        self.logger.log_download_complete(&info.to_version);
+
+        self.logger.log_install_start(&info.to_version);
+        let executable = std::env::current_exe()?;
+        let current = std::fs::read(&executable)?;
+        let updated = delta::apply_patch(&current, &patch)?;
+        delta::verify_sha256(&updated, &info.target_sha256, "差分を適用した実行ファイル")?;
+        delta::replace_executable(&executable, &updated)?;
+
+        self.logger.log_install_complete(&info.to_version);
+        info!("差分アップデートのインストールが完了しました");
+        Ok(())
+    }
+
     /// バージョンをスキップ
     pub async fn skip_version(&mut self, version: String) -> Result<(), UpdateError> {
         info!("バージョン {version} をスキップします");
@@ -511,7 +1036,9 @@ impl UpdaterService {
                             info!("自動チェック: アップデートが利用可能です");
 
                             // フロントエンドにアップデート通知を送信
-                            if let Err(e) = app_handle.emit("update-available", &update_info) {
+                            if let Err(e) =
+                                emit_event(&app_handle, AppEvent::UpdateAvailable(update_info))
+                            {
                                 error!("アップデート通知の送信に失敗: {e}");
                             }
                         } else {
@@ -522,9 +1049,10 @@ impl UpdaterService {
                         error!("自動アップデートチェックエラー: {e}");
 
                         // エラー情報をフロントエンドに送信
-                        if let Err(emit_error) =
-                            app_handle.emit("update-check-error", e.user_friendly_message())
-                        {
+                        if let Err(emit_error) = emit_event(
+                            &app_handle,
+                            AppEvent::UpdateCheckError(e.user_friendly_message()),
+                        ) {
                             error!("エラー通知の送信に失敗: {emit_error}");
                         }
                     }
@@ -543,3 +1071,124 @@ impl UpdaterService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::operations::OperationKind;
+
+    #[test]
+    fn test_update_check_progress_serialization() {
+        let stages = [
+            (UpdateCheckStage::Connecting, "connecting"),
+            (UpdateCheckStage::FetchingMetadata, "fetching_metadata"),
+            (UpdateCheckStage::ComparingVersions, "comparing_versions"),
+            (UpdateCheckStage::Done, "done"),
+        ];
+        for (stage, expected) in stages {
+            let json = serde_json::to_value(UpdateCheckProgress { stage }).unwrap();
+            assert_eq!(json, serde_json::json!({ "stage": expected }));
+        }
+    }
+
+    #[test]
+    fn test_app_version_info() {
+        let info = AppVersionInfo::new("1.2.0".to_string());
+        assert_eq!(info.version, "1.2.0");
+        assert_eq!(info.build_profile, "debug");
+        // build.rsで埋め込んだビルド日
+        let build_date = info.build_date.unwrap();
+        assert!(chrono::NaiveDate::parse_from_str(&build_date, "%Y-%m-%d").is_ok());
+    }
+
+    #[test]
+    fn test_ensure_no_pending_migrations() {
+        use crate::features::migrations::MigrationStatusReport;
+
+        let status = |pending: &[&str]| {
+            let report = MigrationStatusReport::new(
+                3,
+                3 - pending.len(),
+                pending.iter().map(|name| name.to_string()).collect(),
+                Some("2025-04-01T10:00:00+09:00".to_string()),
+                "3".to_string(),
+            );
+            let now = chrono::DateTime::parse_from_rfc3339("2025-04-02T10:00:00+09:00").unwrap();
+            AutoMigrationStatus::from_report(report, now)
+        };
+
+        // 未適用なし・1件のみ（リスク中）は再起動できる
+        assert!(ensure_no_pending_migrations(&status(&[]), false).is_ok());
+        assert!(ensure_no_pending_migrations(&status(&["003_add_tax"]), false).is_ok());
+
+        // リスク高以上は拒否し、forceの場合のみ再起動できる
+        let pending = status(&["002_add_category_id", "003_add_tax"]);
+        assert_eq!(pending.risk_level, MigrationRiskLevel::High);
+        match ensure_no_pending_migrations(&pending, false) {
+            Err(AppError::Configuration(message)) => {
+                assert_eq!(message, "pending migrations must complete first")
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+        assert!(ensure_no_pending_migrations(&pending, true).is_ok());
+    }
+
+    #[test]
+    fn test_changelog_url() {
+        assert_eq!(
+            changelog_url("https://updates.example.com/api/updater/", "v1.2.0").unwrap(),
+            "https://updates.example.com/api/updater/changelog/1.2.0.md"
+        );
+        assert_eq!(
+            changelog_url(DEFAULT_UPDATE_BASE_URL, "1.2.0-beta.1").unwrap(),
+            format!("{DEFAULT_UPDATE_BASE_URL}/changelog/1.2.0-beta.1.md")
+        );
+
+        // パスに使えない文字列は拒否する
+        for version in ["", "latest", "../1.2.0", "1.2.0/../../secret"] {
+            assert!(matches!(
+                changelog_url(DEFAULT_UPDATE_BASE_URL, version),
+                Err(AppError::Validation(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_install_defers_during_migration() {
+        let registry = Arc::new(OperationRegistry::new());
+        let migration = registry.begin(OperationKind::Migration, "010_create_receipt_annotations");
+
+        // マイグレーションが終わらないうちはインストールを見送る
+        let mut blocked_on = Vec::new();
+        let window = wait_for_install_window(&registry, Duration::from_millis(50), false, |ops| {
+            blocked_on = ops.to_vec()
+        })
+        .await;
+        assert!(
+            matches!(&window, InstallWindow::Blocked(ops) if ops[0].kind == OperationKind::Migration)
+        );
+        assert_eq!(blocked_on.len(), 1);
+        assert_eq!(blocked_on[0].description, "010_create_receipt_annotations");
+
+        // 次回終了時のインストールが有効な場合は待たずに予約する
+        let window = wait_for_install_window(&registry, Duration::from_secs(5), true, |_| {}).await;
+        assert!(matches!(window, InstallWindow::ScheduledOnNextQuit(_)));
+
+        // マイグレーションが終われば待機後にインストールできる
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            drop(migration);
+        });
+        let window =
+            wait_for_install_window(&registry, Duration::from_secs(5), false, |_| {}).await;
+        assert_eq!(window, InstallWindow::Ready);
+
+        let mut notified = false;
+        let window = wait_for_install_window(&registry, Duration::from_secs(5), false, |_| {
+            notified = true
+        })
+        .await;
+        assert_eq!(window, InstallWindow::Ready);
+        assert!(!notified);
+    }
+}
@@ -3,24 +3,32 @@ pub mod features;
 pub mod shared;
 
 // 新しい機能モジュールからコマンドをインポート
-use features::auth::middleware::AuthMiddleware;
+use features::auth::middleware::{AuthMiddleware, DEFAULT_SESSION_CACHE_TTL};
 use features::security::models::SecurityConfig;
 use features::security::service::SecurityManager;
 use features::{
     auth::commands as auth_commands,
-    categories::api_commands as category_commands,
+    categories::{api_commands as category_commands, commands as category_local_commands},
     expenses::api_commands as expense_commands,
-    receipts::{api_commands as receipt_api_commands, commands as receipt_commands},
+    expenses::commands as expense_local_commands,
+    receipts::{
+        api_commands as receipt_api_commands, auth_commands as receipt_auth_commands,
+        commands as receipt_commands,
+    },
     security::commands as security_commands,
+    startup::commands as startup_commands,
     subscriptions::api_commands as subscription_commands,
     updater::commands as updater_commands,
 };
 use log::info;
 use rusqlite::Connection;
 use shared::config::environment::{initialize_logging_system, load_environment_variables};
+use shared::errors::AppResult;
+use shared::events::{emit_event, AppEvent};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use tauri::{Emitter, Manager};
+use tauri::Manager;
 
 /// R2接続テストのキャッシュ
 #[derive(Debug)]
@@ -74,9 +82,81 @@ pub struct AppState {
     pub r2_connection_cache: Arc<Mutex<R2ConnectionCache>>,
 }
 
+impl AppState {
+    /// ローカルデータベースの接続をロックして処理を実行する
+    ///
+    /// # 引数
+    /// * `f` - ロックした接続を使う処理
+    ///
+    /// # 戻り値
+    /// 処理の結果、またはロックの取得に失敗した場合は並行処理エラー
+    pub fn try_db<F, T>(&self, f: F) -> AppResult<T>
+    where
+        F: FnOnce(&Connection) -> AppResult<T>,
+    {
+        let db = shared::database::lock_db(&self.db)?;
+        f(&db)
+    }
+}
+
+/// セキュリティマネージャーを作成する
+///
+/// # 引数
+/// * `config` - セキュリティ設定
+///
+/// # 戻り値
+/// セキュリティマネージャー、または設定が不正な場合はエラー
+pub fn build_security_manager(config: &SecurityConfig) -> AppResult<SecurityManager> {
+    SecurityManager::new(config.clone())
+}
+
+/// ローカルデータベースを使用するコマンド用の接続を開く
+///
+/// # 引数
+/// * `database_path` - データベースファイルのパス
+///
+/// # 戻り値
+/// 外部キー制約を有効にした接続、または失敗時はエラー
+pub fn open_app_state_connection(database_path: &Path) -> AppResult<Connection> {
+    let conn = Connection::open(database_path)?;
+    shared::database::connection::set_foreign_keys_pragma(&conn, true)?;
+    Ok(conn)
+}
+
+/// アプリケーション状態を作成する
+///
+/// # 引数
+/// * `db` - ローカルデータベースの接続
+/// * `security` - セキュリティマネージャー
+///
+/// # 戻り値
+/// アプリケーション状態
+pub fn build_app_state(db: Connection, security: SecurityManager) -> AppState {
+    AppState {
+        db: Mutex::new(db),
+        security_manager: security,
+        r2_connection_cache: Arc::new(Mutex::new(R2ConnectionCache::new())),
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let builder = tauri::Builder::default();
+
+    // 多重起動を防ぐ（他のプラグインより先に登録する必要がある）
+    // 2つ目の起動はプロセスを終了し、既存のウィンドウを前面に表示する。
+    // ディープリンクの引数は`deep-link`機能により既存のインスタンスに転送される
+    #[cfg(desktop)]
+    let builder = builder.plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+        // 引数には認証コールバックのURLが含まれる場合があるため、件数のみを記録する
+        log::info!(
+            "2つ目の起動を検出したため、既存のウィンドウを表示します: 引数={}件",
+            argv.len()
+        );
+        features::startup::tray::show_main_window(app);
+    }));
+
+    builder
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
@@ -151,15 +231,18 @@ pub fn run() {
                                         update_info.latest_version
                                     );
                                     // フロントエンドに通知（ダイアログ表示用）
-                                    if let Err(e) =
-                                        app_handle.emit("show-update-dialog", &update_info)
-                                    {
+                                    if let Err(e) = emit_event(
+                                        &app_handle,
+                                        AppEvent::ShowUpdateDialog(update_info),
+                                    ) {
                                         log::error!("アップデート通知の送信に失敗: {e}");
                                     }
                                 } else {
                                     info!("最新バージョンです");
                                     // フロントエンドに通知（ダイアログ表示用）
-                                    if let Err(e) = app_handle.emit("show-no-update-dialog", ()) {
+                                    if let Err(e) =
+                                        emit_event(&app_handle, AppEvent::ShowNoUpdateDialog)
+                                    {
                                         log::error!("通知の送信に失敗: {e}");
                                     }
                                 }
@@ -168,7 +251,7 @@ pub fn run() {
                                 log::error!("アップデートチェックエラー: {e}");
                                 // フロントエンドにエラーを通知（ダイアログ表示用）
                                 if let Err(emit_error) =
-                                    app_handle.emit("show-update-error-dialog", e.to_string())
+                                    emit_event(&app_handle, AppEvent::ShowUpdateErrorDialog(e.to_string()))
                                 {
                                     log::error!("エラー通知の送信に失敗: {emit_error}");
                                 }
@@ -188,6 +271,9 @@ pub fn run() {
             initialize_logging_system();
             eprintln!("ログシステムの初期化完了");
 
+            // 起動時の動作の設定に応じてメインウィンドウまたはトレイアイコンを表示
+            features::startup::apply_startup_settings(app.handle());
+
             info!("アプリケーション初期化を開始します...");
 
             // セキュリティマネージャーを初期化（.envファイル読み込み後）
@@ -198,27 +284,11 @@ pub fn run() {
                 enable_audit_logging: true,
             };
 
-            let security_manager = match SecurityManager::new(security_config.clone()) {
-                Ok(manager) => {
-                    eprintln!("セキュリティマネージャーの初期化完了");
-                    manager
-                }
-                Err(e) => {
-                    eprintln!("SecurityManager初期化失敗: {e}");
-                    // プロダクション環境でクラッシュを防ぐため、デフォルト設定で再試行
-                    eprintln!("デフォルト設定でSecurityManagerを再作成します...");
-                    match SecurityManager::new(security_config.clone()) {
-                        Ok(manager) => {
-                            eprintln!("デフォルト設定でSecurityManager作成成功");
-                            manager
-                        }
-                        Err(e2) => {
-                            eprintln!("デフォルト設定でもSecurityManager作成失敗: {e2}");
-                            return Err(format!("SecurityManager初期化失敗: {e2}").into());
-                        }
-                    }
-                }
-            };
+            let security_manager = build_security_manager(&security_config).map_err(|e| {
+                eprintln!("SecurityManager初期化失敗: {e}");
+                format!("SecurityManager初期化失敗: {e}")
+            })?;
+            eprintln!("セキュリティマネージャーの初期化完了");
 
             info!("システム診断情報を取得中...");
 
@@ -235,6 +305,9 @@ pub fn run() {
                 }
             };
 
+            // 不正アクセスの検出などのセキュリティイベントをデータベースに記録する
+            let security_manager = security_manager.with_event_store(Arc::clone(&db_connection));
+
             // APIサーバーURLを取得
             eprintln!("APIサーバー設定を読み込み中...");
             let api_server_url = crate::get_env_var!("API_SERVER_URL")
@@ -265,16 +338,21 @@ pub fn run() {
             // AuthServiceを直接管理（コマンドで使用するため）
             app.manage(auth_service.clone());
 
-            // SecurityServiceを管理（認証ミドルウェアで使用するため）
-            // SecurityManagerから設定を取得してSecurityServiceを作成
-            let security_service = Arc::new(security_manager.clone());
-            app.manage(security_service.clone());
+            // SecurityServiceを管理（セキュリティコマンドで使用するため）
+            // 複製はトークンのキャッシュを共有する
+            app.manage(security_manager.clone());
 
             // 認証ミドルウェアを作成・管理
             let auth_middleware =
-                AuthMiddleware::new(Arc::new(auth_service.clone()), security_service.clone());
+                AuthMiddleware::new(Arc::new(auth_service.clone()), security_manager.clone())
+                    .with_cache(DEFAULT_SESSION_CACHE_TTL);
             app.manage(auth_middleware);
 
+            // ローカルデータベースを使用するコマンド用のアプリケーション状態を管理
+            let app_state_connection = crate::shared::database::connection::get_database_path(app.handle())
+                .and_then(|path| open_app_state_connection(&path))
+                .map_err(|e| format!("アプリケーション状態の初期化失敗: {e}"))?;
+            app.manage(build_app_state(app_state_connection, security_manager.clone()));
 
             eprintln!("=== アプリケーション初期化完了 ===");
             info!("アプリケーション初期化が完了しました");
@@ -284,10 +362,17 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             // セキュリティコマンド
             security_commands::get_system_diagnostic_info,
+            security_commands::get_app_health,
+            security_commands::get_available_capabilities,
+            security_commands::run_readonly_query,
+            security_commands::run_release_smoke_test,
             security_commands::validate_security_configuration,
             security_commands::test_r2_connection_secure,
             security_commands::get_environment_info,
             security_commands::log_security_event,
+            security_commands::query_security_events,
+            security_commands::get_security_event_types,
+            security_commands::export_security_events_csv,
             security_commands::get_r2_diagnostic_info,
             security_commands::encrypt_and_store_token,
             security_commands::decrypt_token,
@@ -306,17 +391,52 @@ pub fn run() {
             auth_commands::get_auth_state,
             auth_commands::get_stored_auth_info,
             auth_commands::cleanup_expired_sessions,
+            auth_commands::get_secure_storage_status,
             // カテゴリーコマンド（API Server経由）
             category_commands::get_categories,
+            category_local_commands::check_category_color,
+            category_local_commands::suggest_category_palette,
             // 経費コマンド（API Server経由）
             expense_commands::create_expense,
             expense_commands::get_expenses,
             expense_commands::update_expense,
             expense_commands::delete_expense,
             expense_commands::delete_expense_receipt,
+            expense_commands::sync_expenses,
+            expense_commands::force_full_expense_resync,
+            expense_commands::check_expense_sync_integrity,
+            expense_commands::import_expenses_csv,
+            expense_local_commands::get_expenses_without_receipts,
+            expense_local_commands::get_expenses_by_date_range,
+            expense_local_commands::get_expense_summary_by_category,
+            expense_local_commands::get_monthly_expense_summary,
+            expense_local_commands::get_expenses_near,
+            expense_local_commands::get_receipt_location_setting,
+            expense_local_commands::set_receipt_location_setting,
+            expense_local_commands::strip_location_data,
+            expense_local_commands::export_expenses_json,
+            expense_local_commands::export_expenses_csv,
+            expense_local_commands::get_merchants,
+            expense_local_commands::create_merchant,
+            expense_local_commands::update_merchant_name,
+            expense_local_commands::delete_merchant,
+            expense_local_commands::add_merchant_alias,
+            expense_local_commands::delete_merchant_alias,
+            expense_local_commands::link_expense_merchant,
+            expense_local_commands::get_spending_by_merchant,
+            expense_local_commands::get_merchant_group_proposals,
+            expense_local_commands::confirm_merchant_group,
+            expense_local_commands::get_receipt_policies,
+            expense_local_commands::create_receipt_policy,
+            expense_local_commands::update_receipt_policy,
+            expense_local_commands::delete_receipt_policy,
+            expense_local_commands::get_missing_receipt_expenses,
+            expense_local_commands::calculate,
             // サブスクリプションコマンド（API Server経由）
             subscription_commands::create_subscription,
             subscription_commands::get_subscriptions,
+            subscription_commands::get_subscription_by_id,
+            subscription_commands::clone_subscription,
             subscription_commands::update_subscription,
             subscription_commands::toggle_subscription_status,
             subscription_commands::delete_subscription,
@@ -324,18 +444,31 @@ pub fn run() {
             subscription_commands::upload_subscription_receipt_via_api,
             subscription_commands::delete_subscription_receipt_from_r2,
             subscription_commands::delete_subscription_receipt_via_api,
+            subscription_commands::parse_subscription_export,
+            subscription_commands::import_subscriptions,
+            subscription_commands::get_upcoming_renewals,
+            subscription_commands::get_annual_cost_breakdown,
             // 領収書コマンド（APIサーバー経由）
             receipt_api_commands::upload_receipt_via_api,
             receipt_api_commands::upload_multiple_receipts_via_api,
             receipt_api_commands::check_api_server_health,
             receipt_api_commands::check_api_server_health_detailed,
+            receipt_api_commands::get_r2_performance_stats,
             receipt_api_commands::sync_fallback_files,
             receipt_api_commands::get_fallback_file_count,
             receipt_api_commands::get_receipt_via_api,
             receipt_api_commands::delete_receipt_via_api,
+            receipt_api_commands::recompress_existing_receipts,
             receipt_commands::get_receipt_offline,
             receipt_commands::sync_cache_on_online,
             receipt_commands::get_cache_stats,
+            receipt_commands::get_cache_policy,
+            receipt_commands::set_cache_policy,
+            receipt_commands::export_receipt_copy,
+            receipt_commands::add_receipt_annotation,
+            receipt_commands::list_receipt_annotations,
+            receipt_commands::delete_receipt_annotation,
+            receipt_auth_commands::verify_receipt_access,
             // マイグレーションコマンド
             features::migrations::commands::check_migration_status,
             features::migrations::commands::check_auto_migration_status,
@@ -344,24 +477,165 @@ pub fn run() {
             features::migrations::commands::execute_receipt_url_migration,
             features::migrations::commands::drop_receipt_path_column_command,
             features::migrations::commands::check_database_integrity,
+            features::migrations::commands::explain_query_plans,
             // データベース更新コマンド
             features::migrations::database_update_commands::detect_legacy_receipt_urls,
             features::migrations::database_update_commands::execute_database_update,
             features::migrations::database_update_commands::get_database_statistics,
             features::migrations::database_update_commands::update_specific_receipt_urls,
             features::migrations::database_update_commands::check_database_url_integrity,
+            features::migrations::database_update_commands::rebase_receipt_urls,
             // アップデートコマンド
             updater_commands::check_for_updates,
             updater_commands::check_for_updates_force,
             updater_commands::download_and_install_update,
+            updater_commands::check_for_delta_update,
             updater_commands::get_app_version,
             updater_commands::get_updater_config,
             updater_commands::update_updater_config,
             updater_commands::skip_version,
+            updater_commands::get_update_changelog,
             updater_commands::start_auto_update_check,
             updater_commands::stop_auto_update_check,
             updater_commands::restart_application,
+            // 起動時の動作コマンド
+            startup_commands::get_startup_settings,
+            startup_commands::update_startup_settings,
+            startup_commands::save_session_state,
+            startup_commands::get_session_state,
         ])
-        .run(tauri::generate_context!())
-        .expect("Tauriアプリケーションの実行中にエラーが発生しました");
+        .build(tauri::generate_context!())
+        .expect("Tauriアプリケーションの構築中にエラーが発生しました")
+        .run(|app_handle, event| {
+            // 次回終了時に予約されたアップデートをインストール
+            if let tauri::RunEvent::Exit = event {
+                features::updater::service::install_scheduled_update_on_exit(app_handle);
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::errors::AppError;
+
+    fn security_config() -> SecurityConfig {
+        SecurityConfig {
+            encryption_key: "test_key_32_bytes_long_enough!!!".to_string(),
+            max_token_age_hours: 24,
+            enable_audit_logging: false,
+        }
+    }
+
+    #[test]
+    fn test_build_security_manager() {
+        let manager = build_security_manager(&security_config()).unwrap();
+
+        let encrypted = manager.encrypt_and_store_token("t1", "token").unwrap();
+        assert_ne!(encrypted, "token");
+    }
+
+    #[test]
+    fn test_build_security_manager_rejects_invalid_config() {
+        let config = SecurityConfig {
+            encryption_key: String::new(),
+            ..security_config()
+        };
+        assert!(matches!(
+            build_security_manager(&config),
+            Err(AppError::Configuration(_))
+        ));
+
+        let config = SecurityConfig {
+            max_token_age_hours: 0,
+            ..security_config()
+        };
+        assert!(matches!(
+            build_security_manager(&config),
+            Err(AppError::Configuration(_))
+        ));
+    }
+
+    #[test]
+    fn test_open_app_state_connection_enables_foreign_keys() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let conn = open_app_state_connection(&temp_dir.path().join("app.db")).unwrap();
+
+        let foreign_keys: bool = conn
+            .query_row("PRAGMA foreign_keys", [], |row| row.get(0))
+            .unwrap();
+        assert!(foreign_keys);
+    }
+
+    #[test]
+    fn test_open_app_state_connection_propagates_errors() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        // 存在しないディレクトリのファイルは開けない
+        let result = open_app_state_connection(&temp_dir.path().join("missing/app.db"));
+
+        assert!(matches!(result, Err(AppError::Database(_))));
+    }
+
+    #[test]
+    fn test_build_app_state() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE marker (id INTEGER PRIMARY KEY)")
+            .unwrap();
+        let security = build_security_manager(&security_config()).unwrap();
+
+        let state = build_app_state(conn, security);
+
+        // 渡した接続がそのまま保持される
+        let tables: i64 = state
+            .db
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE name = 'marker'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(tables, 1);
+
+        // R2接続テストのキャッシュは空の状態で作成される
+        let cache = state.r2_connection_cache.lock().unwrap();
+        assert_eq!(cache.get_cached_result(), None);
+        assert!(!cache.is_cache_valid());
+    }
+
+    #[test]
+    fn test_try_db() {
+        let conn = Connection::open_in_memory().unwrap();
+        let state = build_app_state(conn, build_security_manager(&security_config()).unwrap());
+
+        let value: i64 = state
+            .try_db(|db| Ok(db.query_row("SELECT 42", [], |row| row.get(0))?))
+            .unwrap();
+        assert_eq!(value, 42);
+
+        // 処理のエラーはそのまま返す
+        let result: AppResult<()> = state.try_db(|_| Err(AppError::validation("不正な値")));
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn test_try_db_returns_concurrency_error_when_poisoned() {
+        let conn = Connection::open_in_memory().unwrap();
+        let state = build_app_state(conn, build_security_manager(&security_config()).unwrap());
+
+        // ロックを保持したままパニックしてMutexを汚染する
+        std::thread::scope(|scope| {
+            let handle = scope.spawn(|| {
+                let _db = state.db.lock().unwrap();
+                panic!("ロック中のパニック");
+            });
+            assert!(handle.join().is_err());
+        });
+
+        let result = state.try_db(|_| Ok(()));
+        assert!(matches!(result, Err(AppError::Concurrency(_))));
+    }
 }
@@ -4,9 +4,14 @@ use crate::shared::config::environment::ApiConfig;
 /// APIサーバーとの通信を行う汎用的なクライアント
 /// サブスクリプション、経費、その他のAPIエンドポイントで使用可能
 use crate::shared::errors::AppError;
+use crate::shared::rate_limit::{
+    is_rate_limit_status, retry_after_delay, shared_cooldown, RateLimitCooldown,
+    MAX_RATE_LIMIT_RETRIES,
+};
 use log::{debug, info, warn};
 use reqwest::{Client, Response};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
 
 /// APIクライアント設定
@@ -59,6 +64,8 @@ pub struct ErrorDetail {
 pub struct ApiClient {
     client: Client,
     config: ApiClientConfig,
+    /// レート制限による待機期間（アプリ全体で共有）
+    cooldown: Arc<RateLimitCooldown>,
 }
 
 impl ApiClient {
@@ -75,7 +82,17 @@ impl ApiClient {
             .build()
             .map_err(|e| AppError::Configuration(format!("HTTPクライアント初期化失敗: {e}")))?;
 
-        Ok(Self { client, config })
+        Ok(Self {
+            client,
+            config,
+            cooldown: shared_cooldown(),
+        })
+    }
+
+    /// レート制限の待機期間を差し替える（テスト用）
+    pub fn with_cooldown(mut self, cooldown: Arc<RateLimitCooldown>) -> Self {
+        self.cooldown = cooldown;
+        self
     }
 
     /// APIサーバーがlocalhostかどうかを判定
@@ -188,46 +205,31 @@ impl ApiClient {
         }
 
         // DELETEリクエストは通常レスポンスボディがないため、成功ステータスのみチェック
-        let mut attempts = 0;
-        loop {
-            match request.try_clone() {
-                Some(cloned_request) => match cloned_request.send().await {
-                    Ok(response) => {
-                        if response.status().is_success() {
-                            info!("DELETEリクエスト成功: endpoint={endpoint}");
-                            return Ok(());
-                        } else {
-                            let error_response = self.handle_error_response(response).await?;
-                            return Err(AppError::ExternalService(format!(
-                                "APIサーバーエラー: {} - {}",
-                                error_response.error.code, error_response.error.message
-                            )));
-                        }
-                    }
-                    Err(e) => {
-                        if attempts < self.config.max_retries {
-                            attempts += 1;
-                            let delay = Duration::from_secs(2_u64.pow(attempts));
-                            warn!(
-                                    "APIリクエスト失敗、リトライします: attempt={attempts}/{}, delay={delay:?}",
-                                    self.config.max_retries
-                                );
-                            tokio::time::sleep(delay).await;
-                            continue;
-                        } else {
-                            return Err(AppError::ExternalService(format!(
-                                "APIサーバーへの接続に失敗しました: {e}"
-                            )));
-                        }
-                    }
-                },
-                None => {
-                    return Err(AppError::ExternalService(
-                        "リクエストのクローンに失敗しました".to_string(),
-                    ));
-                }
-            }
+        self.send_with_retry(request, "DELETE", endpoint).await?;
+        Ok(())
+    }
+
+    /// DELETEリクエストを送信し、レスポンスボディをデシリアライズする
+    pub async fn delete_json<T>(
+        &self,
+        endpoint: &str,
+        auth_token: Option<&str>,
+    ) -> Result<T, AppError>
+    where
+        T: DeserializeOwned,
+    {
+        let url = format!("{}{endpoint}", self.config.base_url);
+        info!("DELETEリクエスト送信: endpoint={endpoint}, url={url}");
+
+        let mut request = self.client.delete(&url);
+
+        // 認証トークンがある場合は追加
+        if let Some(token) = auth_token {
+            request = request.header("Authorization", format!("Bearer {token}"));
         }
+
+        self.send_request_with_retry(request, "DELETE", endpoint)
+            .await
     }
 
     /// ボディ付きDELETEリクエストを送信
@@ -254,7 +256,7 @@ impl ApiClient {
             .await
     }
 
-    /// リトライ機能付きでリクエストを送信
+    /// リトライ機能付きでリクエストを送信し、レスポンスをデシリアライズする
     async fn send_request_with_retry<T>(
         &self,
         request: reqwest::RequestBuilder,
@@ -264,50 +266,43 @@ impl ApiClient {
     where
         T: DeserializeOwned,
     {
-        let mut attempts = 0;
-        loop {
-            match request.try_clone() {
-                Some(cloned_request) => match cloned_request.send().await {
-                    Ok(response) => {
-                        if response.status().is_success() {
-                            let result: T = response.json().await.map_err(|e| {
-                                AppError::ExternalService(format!("レスポンス解析エラー: {e}"))
-                            })?;
-
-                            info!("{method}リクエスト成功: endpoint={endpoint}");
-                            return Ok(result);
-                        } else {
-                            let error_response = self.handle_error_response(response).await?;
-                            return Err(AppError::ExternalService(format!(
-                                "APIサーバーエラー: {} - {}",
-                                error_response.error.code, error_response.error.message
-                            )));
-                        }
-                    }
-                    Err(e) => {
-                        if attempts < self.config.max_retries {
-                            attempts += 1;
-                            let delay = Duration::from_secs(2_u64.pow(attempts));
-                            warn!(
-                                    "APIリクエスト失敗、リトライします: attempt={attempts}/{}, delay={delay:?}",
-                                    self.config.max_retries
-                                );
-                            tokio::time::sleep(delay).await;
-                            continue;
-                        } else {
-                            return Err(AppError::ExternalService(format!(
-                                "APIサーバーへの接続に失敗しました: {e}"
-                            )));
-                        }
-                    }
-                },
-                None => {
-                    return Err(AppError::ExternalService(
-                        "リクエストのクローンに失敗しました".to_string(),
-                    ));
-                }
-            }
+        let response = self.send_with_retry(request, method, endpoint).await?;
+        response
+            .json()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("レスポンス解析エラー: {e}")))
+    }
+
+    /// リトライ機能付きでリクエストを送信
+    ///
+    /// 再試行の方針は[`send_with_retry`]を参照してください。
+    async fn send_with_retry(
+        &self,
+        request: reqwest::RequestBuilder,
+        method: &str,
+        endpoint: &str,
+    ) -> Result<Response, AppError> {
+        let response = send_with_retry(
+            || {
+                request.try_clone().ok_or_else(|| {
+                    AppError::ExternalService("リクエストのクローンに失敗しました".to_string())
+                })
+            },
+            &self.cooldown,
+            self.config.max_retries,
+            method,
+            endpoint,
+        )
+        .await?;
+
+        if response.status().is_success() {
+            return Ok(response);
         }
+        let error_response = self.handle_error_response(response).await?;
+        Err(AppError::ExternalService(format!(
+            "APIサーバーエラー: {} - {}",
+            error_response.error.code, error_response.error.message
+        )))
     }
 
     /// エラーレスポンスを処理し、詳細なエラー情報を提供
@@ -385,3 +380,230 @@ impl ApiClient {
         }
     }
 }
+
+/// リトライ機能付きでリクエストを送信する
+///
+/// 接続失敗は指数バックオフで`max_retries`回まで再試行します。
+/// 429/503は`Retry-After`に従って待機期間を設定し、通常の再試行回数とは別に
+/// `MAX_RATE_LIMIT_RETRIES`回まで再試行します。
+/// `build_request`は送信のたびに呼び出すため、マルチパートのようにクローンできない
+/// リクエストも再試行できます。
+///
+/// # 引数
+/// * `build_request` - 送信するリクエストを作成する関数
+/// * `cooldown` - レート制限による待機期間
+/// * `max_retries` - 接続失敗時の最大再試行回数
+/// * `method` - ログに出力するHTTPメソッド
+/// * `endpoint` - ログに出力するエンドポイント
+///
+/// # 戻り値
+/// レスポンス（再試行しても成功しなかった場合はエラーのレスポンス）、
+/// または接続に失敗した場合はエラー
+pub async fn send_with_retry(
+    build_request: impl Fn() -> Result<reqwest::RequestBuilder, AppError>,
+    cooldown: &RateLimitCooldown,
+    max_retries: u32,
+    method: &str,
+    endpoint: &str,
+) -> Result<Response, AppError> {
+    let mut attempts = 0;
+    let mut rate_limit_attempts = 0;
+    loop {
+        // 他のリクエストが受けたレート制限も含めて待機する
+        cooldown.wait().await;
+
+        match build_request()?.send().await {
+            Ok(response) if response.status().is_success() => {
+                info!("{method}リクエスト成功: endpoint={endpoint}");
+                return Ok(response);
+            }
+            Ok(response)
+                if is_rate_limit_status(response.status().as_u16())
+                    && rate_limit_attempts < MAX_RATE_LIMIT_RETRIES =>
+            {
+                rate_limit_attempts += 1;
+                let status = response.status().as_u16();
+                let delay = retry_after_delay(
+                    response
+                        .headers()
+                        .get(reqwest::header::RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok()),
+                );
+                warn!(
+                    "APIサーバーのレート制限を受けました、待機後にリトライします: status={status}, attempt={rate_limit_attempts}/{MAX_RATE_LIMIT_RETRIES}, delay={delay:?}"
+                );
+                cooldown.activate(delay, status);
+            }
+            Ok(response) => return Ok(response),
+            Err(e) => {
+                if attempts < max_retries {
+                    attempts += 1;
+                    let delay = Duration::from_secs(2_u64.pow(attempts));
+                    warn!(
+                        "APIリクエスト失敗、リトライします: attempt={attempts}/{max_retries}, delay={delay:?}"
+                    );
+                    tokio::time::sleep(delay).await;
+                } else {
+                    return Err(AppError::ExternalService(format!(
+                        "APIサーバーへの接続に失敗しました: {e}"
+                    )));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::time::Instant;
+
+    /// 決められた順にレスポンスを返すHTTPサーバーを起動する
+    ///
+    /// # 戻り値
+    /// (ベースURL, リクエストを受け付けた時刻の一覧)
+    async fn spawn_mock_server(responses: Vec<String>) -> (String, Arc<Mutex<Vec<Instant>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let arrivals = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = Arc::clone(&arrivals);
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buffer = Vec::new();
+                let mut chunk = [0_u8; 1024];
+                while !buffer.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let read = socket.read(&mut chunk).await.unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    buffer.extend_from_slice(&chunk[..read]);
+                }
+                recorded.lock().unwrap().push(Instant::now());
+                socket.write_all(response.as_bytes()).await.unwrap();
+                socket.shutdown().await.ok();
+            }
+        });
+
+        (base_url, arrivals)
+    }
+
+    fn rate_limited(status: u16, retry_after: &str) -> String {
+        format!(
+            "HTTP/1.1 {status} Too Many Requests\r\nRetry-After: {retry_after}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        )
+    }
+
+    fn ok_response() -> String {
+        let body = r#"{"ok":true}"#;
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    fn client(base_url: &str, cooldown: &Arc<RateLimitCooldown>) -> ApiClient {
+        ApiClient::new_with_config(ApiClientConfig {
+            base_url: base_url.to_string(),
+            timeout_seconds: 10,
+            max_retries: 0,
+        })
+        .unwrap()
+        .with_cooldown(Arc::clone(cooldown))
+    }
+
+    fn gap(arrivals: &Mutex<Vec<Instant>>, from: usize, to: usize) -> Duration {
+        let arrivals = arrivals.lock().unwrap();
+        arrivals[to].duration_since(arrivals[from])
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_seconds() {
+        let (base_url, arrivals) =
+            spawn_mock_server(vec![rate_limited(429, "1"), ok_response()]).await;
+        let cooldown = Arc::new(RateLimitCooldown::new());
+
+        let result: serde_json::Value = client(&base_url, &cooldown)
+            .get("/api/v1/test", None)
+            .await
+            .unwrap();
+
+        assert_eq!(result["ok"], true);
+        assert!(gap(&arrivals, 0, 1) >= Duration::from_millis(950));
+        assert_eq!(cooldown.status().last_status_code, Some(429));
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_http_date() {
+        let retry_at = chrono::Utc::now() + chrono::Duration::seconds(2);
+        let header = retry_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        let (base_url, arrivals) =
+            spawn_mock_server(vec![rate_limited(503, &header), ok_response()]).await;
+        let cooldown = Arc::new(RateLimitCooldown::new());
+
+        let _: serde_json::Value = client(&base_url, &cooldown)
+            .get("/api/v1/test", None)
+            .await
+            .unwrap();
+
+        // HTTP日付は秒単位のため、1〜2秒の待機になる
+        let waited = gap(&arrivals, 0, 1);
+        assert!(waited >= Duration::from_millis(950), "waited={waited:?}");
+        assert!(waited <= Duration::from_millis(2500), "waited={waited:?}");
+        assert_eq!(cooldown.status().last_status_code, Some(503));
+    }
+
+    #[tokio::test]
+    async fn test_cooldown_is_shared_across_requests() {
+        let (base_url, arrivals) =
+            spawn_mock_server(vec![rate_limited(429, "1"), ok_response(), ok_response()]).await;
+        let cooldown = Arc::new(RateLimitCooldown::new());
+
+        let first = client(&base_url, &cooldown);
+        let second = client(&base_url, &cooldown);
+        let first_request = tokio::spawn(async move {
+            first
+                .get::<serde_json::Value>("/api/v1/first", None)
+                .await
+                .unwrap()
+        });
+
+        // 1件目がレート制限を受けてから2件目を送る
+        while arrivals.lock().unwrap().is_empty() || !cooldown.status().cooling_down {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(cooldown.status().cooling_down);
+        let _: serde_json::Value = second.get("/api/v1/second", None).await.unwrap();
+        first_request.await.unwrap();
+
+        // 2件目も待機期間が終わるまで送信されない
+        assert!(gap(&arrivals, 0, 1) >= Duration::from_millis(950));
+        assert!(gap(&arrivals, 0, 2) >= Duration::from_millis(950));
+        assert_eq!(cooldown.status().rate_limited_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_retry_budget() {
+        let responses = (0..=MAX_RATE_LIMIT_RETRIES)
+            .map(|_| rate_limited(429, "0"))
+            .collect();
+        let (base_url, arrivals) = spawn_mock_server(responses).await;
+        let cooldown = Arc::new(RateLimitCooldown::new());
+
+        let result = client(&base_url, &cooldown)
+            .get::<serde_json::Value>("/api/v1/test", None)
+            .await;
+
+        // 通常の再試行回数（0回）とは別の上限まで再試行してから失敗する
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("TOO_MANY_REQUESTS"), "{error}");
+        assert_eq!(
+            arrivals.lock().unwrap().len(),
+            MAX_RATE_LIMIT_RETRIES as usize + 1
+        );
+    }
+}
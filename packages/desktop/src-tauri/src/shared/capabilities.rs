@@ -0,0 +1,183 @@
+/// 利用者向け機能（ケイパビリティ）の登録
+///
+/// 各機能モジュールは、利用者が実行できる操作を`CAPABILITIES`として登録します。
+/// フロントエンドのコマンドパレットは、実行時の状態（ログイン・ストレージの接続・設定・機能フラグ）で
+/// 絞り込んだ一覧を取得するため、ビルドごとに対応するコマンドの一覧と常に一致します。
+/// データを変更するコマンドと破壊的な操作の判定にも同じ登録内容を使用します。
+use serde::Serialize;
+
+/// 機能を利用するための条件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Requirement {
+    /// ログインしていること
+    Authenticated,
+    /// ストレージ（APIサーバー経由のR2）に接続できること
+    StorageOnline,
+    /// APIサーバーのURLが設定されていること
+    ApiServerConfigured,
+    /// 領収書の位置情報の保存を許可していること
+    LocationStorageEnabled,
+    /// デバッグビルドであること
+    DebugBuild,
+}
+
+/// 利用者向け機能
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Capability {
+    /// 機能ID（例: `expenses.create`）
+    pub id: &'static str,
+    /// 表示名の翻訳キー
+    pub label_key: &'static str,
+    /// 実行するTauriコマンド名
+    pub command: &'static str,
+    /// 利用条件
+    pub requires: &'static [Requirement],
+    /// データを変更するかどうか
+    pub mutates: bool,
+    /// 元に戻せない操作（削除など）かどうか。実行前に確認が必要
+    pub destructive: bool,
+    /// コマンドパレットに表示するかどうか（内部処理用のコマンドはfalse）
+    pub in_palette: bool,
+}
+
+/// 利用条件の判定に使う実行時の状態
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CapabilityContext {
+    pub authenticated: bool,
+    pub storage_online: bool,
+    pub api_server_configured: bool,
+    pub location_storage_enabled: bool,
+    pub debug_build: bool,
+}
+
+impl CapabilityContext {
+    /// 条件を満たしているかどうか
+    pub fn satisfies(&self, requirement: Requirement) -> bool {
+        match requirement {
+            Requirement::Authenticated => self.authenticated,
+            Requirement::StorageOnline => self.storage_online,
+            Requirement::ApiServerConfigured => self.api_server_configured,
+            Requirement::LocationStorageEnabled => self.location_storage_enabled,
+            Requirement::DebugBuild => self.debug_build,
+        }
+    }
+}
+
+impl Capability {
+    /// 条件なし・データを変更しない機能を作成する（コマンドパレットに表示）
+    pub const fn new(id: &'static str, label_key: &'static str, command: &'static str) -> Self {
+        Self {
+            id,
+            label_key,
+            command,
+            requires: &[],
+            mutates: false,
+            destructive: false,
+            in_palette: true,
+        }
+    }
+
+    /// 利用条件を指定する
+    pub const fn requires(mut self, requires: &'static [Requirement]) -> Self {
+        self.requires = requires;
+        self
+    }
+
+    /// データを変更する機能とする
+    pub const fn mutating(mut self) -> Self {
+        self.mutates = true;
+        self
+    }
+
+    /// 元に戻せない操作とする（データを変更する機能にもなる）
+    pub const fn destructive(mut self) -> Self {
+        self.mutates = true;
+        self.destructive = true;
+        self
+    }
+
+    /// コマンドパレットに表示しない内部処理用の機能とする
+    pub const fn internal(mut self) -> Self {
+        self.in_palette = false;
+        self
+    }
+
+    /// 現在の状態で利用できるかどうか
+    pub fn is_available(&self, context: &CapabilityContext) -> bool {
+        self.requires
+            .iter()
+            .all(|&requirement| context.satisfies(requirement))
+    }
+}
+
+/// コマンドパレットに表示する、現在利用できる機能を取得する
+///
+/// # 引数
+/// * `registry` - 登録されている機能
+/// * `context` - 実行時の状態
+pub fn available_capabilities<'a>(
+    registry: impl IntoIterator<Item = &'a Capability>,
+    context: &CapabilityContext,
+) -> Vec<Capability> {
+    registry
+        .into_iter()
+        .filter(|capability| capability.in_palette && capability.is_available(context))
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REGISTRY: &[Capability] = &[
+        Capability::new(
+            "updater.check",
+            "capability.updater.check",
+            "check_for_updates",
+        ),
+        Capability::new(
+            "receipts.upload",
+            "capability.receipts.upload",
+            "upload_receipt_via_api",
+        )
+        .requires(&[Requirement::Authenticated, Requirement::StorageOnline])
+        .mutating(),
+        Capability::new(
+            "security.cleanup_expired_tokens",
+            "capability.security.cleanup_expired_tokens",
+            "cleanup_expired_tokens",
+        )
+        .mutating()
+        .internal(),
+    ];
+
+    fn ids(capabilities: &[Capability]) -> Vec<&str> {
+        capabilities
+            .iter()
+            .map(|capability| capability.id)
+            .collect()
+    }
+
+    #[test]
+    fn test_available_capabilities_filters_by_context() {
+        let offline = CapabilityContext {
+            authenticated: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(&available_capabilities(REGISTRY, &offline)),
+            ["updater.check"]
+        );
+
+        let online = CapabilityContext {
+            storage_online: true,
+            ..offline
+        };
+        assert_eq!(
+            ids(&available_capabilities(REGISTRY, &online)),
+            ["updater.check", "receipts.upload"]
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use crate::shared::errors::AppError;
+
 /// アプリケーションの実行環境を表す列挙型
 #[derive(Debug, Clone, PartialEq)]
 pub enum Environment {
@@ -7,26 +9,96 @@ pub enum Environment {
     Production,
 }
 
-/// 環境変数取得エラー
-#[derive(Debug, Clone)]
-pub struct EnvVarError {
-    /// 変数名
-    pub var_name: String,
-    /// エラーメッセージ
-    pub message: String,
+/// 環境変数の設定方法を説明するドキュメント（リポジトリのルートからのパス）
+pub const ENVIRONMENT_VARIABLES_DOC: &str = "packages/desktop/docs/ENVIRONMENT_VARIABLES.md";
+
+/// 環境変数の設定方法を説明するドキュメントの内容（見出しからリンク先を求めるために埋め込む）
+const ENVIRONMENT_VARIABLES_DOC_CONTENT: &str =
+    include_str!("../../../../docs/ENVIRONMENT_VARIABLES.md");
+
+/// 環境変数が設定されていない場合のエラーを作成する
+///
+/// エラーメッセージには、変数の説明があるドキュメントの節へのリンクを含めます。
+///
+/// # 引数
+/// * `var_name` - 環境変数名
+/// * `reason` - 設定されていないと判断した理由
+pub fn missing_env_var_error(var_name: &str, reason: &str) -> AppError {
+    AppError::Configuration(format!(
+        "環境変数 {var_name} が設定されていません（{reason}）。.envファイルに追加してください。ドキュメント: {}",
+        env_var_documentation_link(var_name)
+    ))
 }
 
-impl std::fmt::Display for EnvVarError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "環境変数 {} が見つかりません: {}",
-            self.var_name, self.message
-        )
+/// 複数の環境変数が設定されていない場合のエラーを作成する
+///
+/// 設定されていない変数をすべて列挙し、それぞれの説明があるドキュメントの節へのリンクを含めます。
+///
+/// # 引数
+/// * `var_names` - 設定されていない環境変数名の一覧
+pub fn missing_env_vars_error(var_names: &[String]) -> AppError {
+    let details = var_names
+        .iter()
+        .map(|name| format!("{name}（{}）", env_var_documentation_link(name)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    AppError::Configuration(format!(
+        "環境変数が{}件設定されていません: {details}。.envファイルに追加してください",
+        var_names.len()
+    ))
+}
+
+/// 環境変数の説明があるドキュメントの節へのリンクを取得する
+///
+/// 変数名を含む見出し、または変数名が最初に出てくる節の見出しにリンクします。
+/// ドキュメントに記載がない場合はドキュメントの先頭にリンクします。
+pub fn env_var_documentation_link(var_name: &str) -> String {
+    let mut current_heading = None;
+    let mut in_code_block = false;
+    let mut section = None;
+    for line in ENVIRONMENT_VARIABLES_DOC_CONTENT.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        if let Some(heading) = line
+            .strip_prefix('#')
+            .map(|h| h.trim_start_matches('#').trim())
+        {
+            if heading.contains(var_name) {
+                section = Some(heading);
+                break;
+            }
+            current_heading = Some(heading);
+        } else if section.is_none() && line.contains(var_name) {
+            section = current_heading;
+        }
+    }
+
+    match section {
+        Some(heading) => format!("{ENVIRONMENT_VARIABLES_DOC}#{}", heading_anchor(heading)),
+        None => ENVIRONMENT_VARIABLES_DOC.to_string(),
     }
 }
 
-impl std::error::Error for EnvVarError {}
+/// GitHubが見出しに付けるアンカーを求める
+///
+/// 小文字に変換して空白を`-`に置き換え、`-`と`_`以外の記号を取り除きます（日本語などの文字は残します）。
+fn heading_anchor(heading: &str) -> String {
+    heading
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            c if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
 
 /// 環境変数を取得する（優先順位: 起動時 > コンパイル時 > エラー）
 ///
@@ -34,7 +106,8 @@ impl std::error::Error for EnvVarError {}
 /// * `var_name` - 環境変数名
 ///
 /// # 戻り値
-/// 環境変数の値、または見つからない場合はエラー
+/// 環境変数の値、または見つからない場合は設定方法のドキュメントへのリンクを含む
+/// `AppError::Configuration`
 ///
 /// # 取得順序
 /// 1. 起動時の環境変数（`std::env::var`）
@@ -60,24 +133,18 @@ macro_rules! get_env_var {
                 );
                 Ok(value.to_string())
             } else {
-                Err($crate::shared::config::environment::EnvVarError {
-                    var_name: $var_name.to_string(),
-                    message: format!(
-                        "環境変数 {} はコンパイル時に埋め込まれていますが、値が空です",
-                        $var_name
-                    ),
-                })
+                Err($crate::shared::config::environment::missing_env_var_error(
+                    $var_name,
+                    "コンパイル時に埋め込まれた値が空です",
+                ))
             }
         }
         // 3. どちらも見つからない場合はエラー
         else {
-            Err($crate::shared::config::environment::EnvVarError {
-                var_name: $var_name.to_string(),
-                message: format!(
-                    "起動時の環境変数 {} もコンパイル時の環境変数も見つかりませんでした",
-                    $var_name
-                ),
-            })
+            Err($crate::shared::config::environment::missing_env_var_error(
+                $var_name,
+                "起動時の環境変数にもコンパイル時の環境変数にも見つかりませんでした",
+            ))
         }
     }};
 }
@@ -118,6 +185,35 @@ macro_rules! get_env_var_or_default {
     }};
 }
 
+/// 複数の必須環境変数をまとめて取得する
+///
+/// 最初に見つからなかった変数で中断せず、すべての変数を確認します。
+///
+/// # 引数
+/// * `var_name` - 環境変数名（カンマ区切りで複数指定）
+///
+/// # 戻り値
+/// 指定した順序の環境変数の値、または見つからない変数がある場合は
+/// 見つからない変数をすべて列挙した`AppError::Configuration`
+#[macro_export]
+macro_rules! require_env_vars {
+    ($($var_name:expr),+ $(,)?) => {{
+        let mut values: Vec<String> = Vec::new();
+        let mut missing: Vec<String> = Vec::new();
+        $(
+            match $crate::get_env_var!($var_name) {
+                Ok(value) => values.push(value),
+                Err(_) => missing.push($var_name.to_string()),
+            }
+        )+
+        if missing.is_empty() {
+            Ok(values)
+        } else {
+            Err($crate::shared::config::environment::missing_env_vars_error(&missing))
+        }
+    }};
+}
+
 /// 環境設定を管理する構造体
 #[derive(Debug, Clone)]
 pub struct EnvironmentConfig {
@@ -392,6 +488,77 @@ impl ApiConfig {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_missing_env_var_error_links_to_documentation() {
+        let error = crate::get_env_var!("ORANO_KEIHI_TEST_UNSET_VARIABLE").unwrap_err();
+        assert!(matches!(error, AppError::Configuration(_)));
+        let message = error.to_string();
+        assert!(
+            message.contains("ORANO_KEIHI_TEST_UNSET_VARIABLE"),
+            "{message}"
+        );
+        assert!(message.contains(".envファイル"), "{message}");
+        assert!(message.contains(ENVIRONMENT_VARIABLES_DOC), "{message}");
+    }
+
+    #[test]
+    fn test_require_env_vars_lists_all_missing_variables() {
+        let error = crate::require_env_vars!(
+            "ORANO_KEIHI_TEST_UNSET_VARIABLE_A",
+            "CARGO_PKG_NAME",
+            "ORANO_KEIHI_TEST_UNSET_VARIABLE_B",
+        )
+        .unwrap_err();
+        assert!(matches!(error, AppError::Configuration(_)));
+        let message = error.to_string();
+        assert!(message.contains("2件"), "{message}");
+        assert!(
+            message.contains("ORANO_KEIHI_TEST_UNSET_VARIABLE_A"),
+            "{message}"
+        );
+        assert!(
+            message.contains("ORANO_KEIHI_TEST_UNSET_VARIABLE_B"),
+            "{message}"
+        );
+        assert!(!message.contains("CARGO_PKG_NAME"), "{message}");
+    }
+
+    #[test]
+    fn test_require_env_vars_returns_values_in_order() {
+        let values = crate::require_env_vars!("CARGO_PKG_NAME", "CARGO_PKG_VERSION").unwrap();
+        assert_eq!(values, [env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")]);
+    }
+
+    #[test]
+    fn test_env_var_documentation_link() {
+        // 変数名を含む見出し
+        assert_eq!(
+            env_var_documentation_link("API_SERVER_URL"),
+            format!("{ENVIRONMENT_VARIABLES_DOC}#1-api_server_url")
+        );
+        assert_eq!(
+            env_var_documentation_link("UPDATE_BASE_URL"),
+            format!("{ENVIRONMENT_VARIABLES_DOC}#update_base_url")
+        );
+        // 節の本文に出てくる変数は、その節の見出し
+        assert_eq!(
+            env_var_documentation_link("R2_BUCKET_NAME"),
+            format!("{ENVIRONMENT_VARIABLES_DOC}#3-r2設定")
+        );
+        // 記載がない場合はドキュメントの先頭
+        assert_eq!(
+            env_var_documentation_link("UNDOCUMENTED_VARIABLE"),
+            ENVIRONMENT_VARIABLES_DOC
+        );
+    }
+
+    #[test]
+    fn test_heading_anchor() {
+        assert_eq!(heading_anchor("1. API_SERVER_URL"), "1-api_server_url");
+        assert_eq!(heading_anchor("3. R2設定"), "3-r2設定");
+        assert_eq!(heading_anchor(".envファイルの作成"), "envファイルの作成");
+    }
+
     #[test]
     fn test_environment_equality() {
         // Environment列挙型の等価性をテスト
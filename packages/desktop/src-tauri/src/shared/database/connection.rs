@@ -1,7 +1,9 @@
 use crate::features::migrations::AutoMigrationService;
+use crate::shared::database::migration_lock::{self, MigrationLockOptions, MigrationLockOutcome};
 use crate::shared::errors::{AppError, AppResult};
 use rusqlite::{Connection, Result};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
 use tauri::{AppHandle, Manager};
 
 /// データベース接続を取得する（非同期版）
@@ -33,6 +35,7 @@ pub async fn get_database_connection() -> AppResult<Connection> {
     // データベース接続を開く
     let conn = Connection::open(&database_path)
         .map_err(|e| AppError::Database(format!("データベース接続エラー: {e}")))?;
+    set_foreign_keys_pragma(&conn, true)?;
 
     Ok(conn)
 }
@@ -49,8 +52,9 @@ pub async fn get_database_connection() -> AppResult<Connection> {
 /// 1. アプリケーションデータディレクトリの確保
 /// 2. データベースファイルパスの決定
 /// 3. データベース接続の開設
-/// 4. テーブル作成とマイグレーションの実行
-/// 5. 自動マイグレーションシステムの実行（要件3.1）
+/// 4. WALモードと外部キー制約の有効化
+/// 5. テーブル作成とマイグレーションの実行
+/// 6. 自動マイグレーションシステムの実行（要件3.1）
 pub fn initialize_database(app_handle: &AppHandle) -> AppResult<Connection> {
     eprintln!("データベース初期化を開始します...");
 
@@ -84,13 +88,9 @@ pub fn initialize_database(app_handle: &AppHandle) -> AppResult<Connection> {
         AppError::Database(format!("データベース接続失敗: {e}"))
     })?;
 
-    // テーブルを作成
-    eprintln!("テーブルを作成中...");
-    create_tables(&conn)?;
-
-    // 自動マイグレーションシステムを実行（要件3.1, 3.4, 3.5）
-    eprintln!("自動マイグレーションシステムを実行中...");
-    execute_auto_migration_system(&conn)?;
+    enable_wal_mode(&conn)?;
+    set_foreign_keys_pragma(&conn, true)?;
+    migrate_database(&conn)?;
 
     eprintln!("データベース初期化完了: {database_path:?}");
     log::info!("データベースを初期化しました: {database_path:?}");
@@ -98,6 +98,149 @@ pub fn initialize_database(app_handle: &AppHandle) -> AppResult<Connection> {
     Ok(conn)
 }
 
+/// WAL（Write-Ahead Logging）モードを有効にする
+///
+/// 書き込み中も他の接続から読み取れるようになります。
+/// ジャーナルモードはデータベースファイルに保存されるため、同じファイルを開く他の接続にも適用されます。
+/// WALモードでは`synchronous=NORMAL`でもコミット済みのデータは破損しないため、あわせて設定します。
+///
+/// # 引数
+/// * `conn` - データベース接続
+///
+/// # 戻り値
+/// 成功時はOk(())、WALモードにできなかった場合（インメモリデータベースなど）はエラー
+pub fn enable_wal_mode(conn: &Connection) -> AppResult<()> {
+    let journal_mode: String = conn.query_row("PRAGMA journal_mode=WAL", [], |row| row.get(0))?;
+    if !journal_mode.eq_ignore_ascii_case("wal") {
+        return Err(AppError::Database(format!(
+            "WALモードを有効にできませんでした: journal_mode={journal_mode}"
+        )));
+    }
+
+    conn.pragma_update(None, "synchronous", "NORMAL")?;
+    log::debug!("WALモードを有効にしました");
+    Ok(())
+}
+
+/// 外部キー制約の有効・無効を設定する
+///
+/// SQLiteの外部キー制約は接続ごとの設定で、既定では無効です。
+/// 新しい接続を開いたら、マイグレーションより前に有効にします。
+/// トランザクション中は変更できない（PRAGMAが無視される）ため、設定後に値を確認します。
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `enabled` - 有効にする場合はtrue
+///
+/// # 戻り値
+/// 成功時はOk(())、設定が反映されなかった場合はエラー
+pub fn set_foreign_keys_pragma(conn: &Connection, enabled: bool) -> AppResult<()> {
+    conn.pragma_update(None, "foreign_keys", enabled)?;
+
+    let actual: bool = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0))?;
+    if actual != enabled {
+        return Err(AppError::Database(format!(
+            "外部キー制約を{}にできませんでした（トランザクション中は変更できません）",
+            if enabled { "有効" } else { "無効" }
+        )));
+    }
+    Ok(())
+}
+
+/// 共有のデータベース接続をロックする
+///
+/// ロックを保持したスレッドがパニックしてMutexが汚染されている場合は、並行処理エラーを返します。
+/// 共有の接続をロックする処理はすべてこの関数を経由します。
+///
+/// # 引数
+/// * `db` - 共有のデータベース接続
+///
+/// # 戻り値
+/// ロックした接続、または失敗時は並行処理エラー
+pub fn lock_db(db: &Mutex<Connection>) -> AppResult<MutexGuard<'_, Connection>> {
+    db.lock()
+        .map_err(|e| AppError::concurrency(format!("データベースロックエラー: {e}")))
+}
+
+/// 任意のパスのデータベースを開き、マイグレーションを実行する
+///
+/// アプリケーションデータディレクトリに依存しないため、
+/// 過去バージョンのデータベースファイルのアップグレード検証に使用します。
+///
+/// # 引数
+/// * `database_path` - データベースファイルのパス
+///
+/// # 戻り値
+/// マイグレーション済みのデータベース接続、または失敗時はエラー
+pub fn open_and_migrate_database(database_path: &Path) -> AppResult<Connection> {
+    let conn = Connection::open(database_path)
+        .map_err(|e| AppError::Database(format!("データベース接続失敗: {e}")))?;
+
+    set_foreign_keys_pragma(&conn, true)?;
+    migrate_database(&conn)?;
+
+    log::info!("データベースのマイグレーションが完了しました: {database_path:?}");
+    Ok(conn)
+}
+
+/// インメモリのデータベースを開き、アプリと同じマイグレーションを実行する
+///
+/// 結合テストなど、ファイルを作らずにアプリと同じスキーマが必要な場合に使用します。
+///
+/// # 戻り値
+/// マイグレーション済みのデータベース接続、または失敗時はエラー
+pub fn open_and_migrate_in_memory_database() -> AppResult<Connection> {
+    let conn = Connection::open_in_memory()
+        .map_err(|e| AppError::Database(format!("データベース接続失敗: {e}")))?;
+
+    set_foreign_keys_pragma(&conn, true)?;
+    migrate_database(&conn)?;
+
+    Ok(conn)
+}
+
+/// 他のインスタンスの書き込みを待つ最大時間
+const BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// テーブル作成と自動マイグレーションを順に実行する
+///
+/// 同じデータベースファイルを使う他のインスタンスがマイグレーション中の場合は、
+/// 完了を待ってから実行します。待機時間内に完了しない場合は、
+/// マイグレーション途中のスキーマを使わないようエラーを返します。
+fn migrate_database(conn: &Connection) -> AppResult<()> {
+    migrate_database_with_lock_options(conn, &MigrationLockOptions::default())
+}
+
+/// マイグレーションロックの設定を指定してテーブル作成と自動マイグレーションを実行する
+fn migrate_database_with_lock_options(
+    conn: &Connection,
+    lock_options: &MigrationLockOptions,
+) -> AppResult<()> {
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+
+    let _lock = match migration_lock::acquire(conn, lock_options)? {
+        MigrationLockOutcome::Acquired(lock) => lock,
+        MigrationLockOutcome::Busy(holder) => {
+            log::error!(
+                "他のインスタンス(pid={})がマイグレーション中のため、データベースを開けません",
+                holder.pid
+            );
+            return Err(AppError::Concurrency(format!(
+                "他のインスタンス(pid={})がデータベースのマイグレーション中です。しばらく待ってから再度起動してください",
+                holder.pid
+            )));
+        }
+    };
+
+    // テーブルを作成
+    eprintln!("テーブルを作成中...");
+    create_tables(conn)?;
+
+    // 自動マイグレーションシステムを実行（要件3.1, 3.4, 3.5）
+    eprintln!("自動マイグレーションシステムを実行中...");
+    execute_auto_migration_system(conn)
+}
+
 /// 自動マイグレーションシステムを実行する
 ///
 /// アプリケーション起動時に未適用のマイグレーションを自動で適用します。
@@ -695,7 +838,7 @@ fn drop_receipt_path_column(conn: &Connection) -> AppResult<()> {
 ///
 /// # 戻り値
 /// カラムが存在する場合はtrue、存在しないかエラーの場合はfalse
-fn check_column_exists(conn: &Connection, table_name: &str, column_name: &str) -> bool {
+pub(crate) fn check_column_exists(conn: &Connection, table_name: &str, column_name: &str) -> bool {
     let query = format!("PRAGMA table_info({table_name})");
 
     match conn.prepare(&query) {
@@ -806,6 +949,86 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_migrate_database_fails_while_another_instance_holds_the_lock() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("test.db");
+        let holder_conn = Connection::open(&path).unwrap();
+        let conn = Connection::open(&path).unwrap();
+        let options = MigrationLockOptions {
+            wait_timeout: std::time::Duration::from_millis(100),
+            poll_interval: std::time::Duration::from_millis(20),
+            ..MigrationLockOptions::default()
+        };
+
+        let lock = migration_lock::try_acquire(&holder_conn, &options)
+            .unwrap()
+            .unwrap();
+        let err = migrate_database_with_lock_options(&conn, &options).unwrap_err();
+        assert!(matches!(err, AppError::Concurrency(_)));
+
+        // ロックの解放後はマイグレーションできる
+        drop(lock);
+        migrate_database_with_lock_options(&conn, &options).unwrap();
+    }
+
+    #[test]
+    fn test_enable_wal_mode() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("test.db");
+        let conn = Connection::open(&path).unwrap();
+
+        enable_wal_mode(&conn).unwrap();
+
+        let journal_mode: String = conn
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(journal_mode, "wal");
+        // NORMAL = 1
+        let synchronous: i64 = conn
+            .query_row("PRAGMA synchronous", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(synchronous, 1);
+
+        // ジャーナルモードはファイルに保存され、新しい接続にも適用される
+        let other = Connection::open(&path).unwrap();
+        let journal_mode: String = other
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(journal_mode, "wal");
+    }
+
+    #[test]
+    fn test_enable_wal_mode_rejects_in_memory_database() {
+        let conn = Connection::open_in_memory().unwrap();
+        assert!(enable_wal_mode(&conn).is_err());
+    }
+
+    #[test]
+    fn test_set_foreign_keys_pragma() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE parents (id INTEGER PRIMARY KEY);
+             CREATE TABLE children (id INTEGER PRIMARY KEY, parent_id INTEGER REFERENCES parents(id));",
+        )
+        .unwrap();
+
+        set_foreign_keys_pragma(&conn, true).unwrap();
+        assert!(conn
+            .execute("INSERT INTO children (parent_id) VALUES (1)", [])
+            .is_err());
+
+        set_foreign_keys_pragma(&conn, false).unwrap();
+        assert!(conn
+            .execute("INSERT INTO children (parent_id) VALUES (1)", [])
+            .is_ok());
+
+        // トランザクション中は変更できない
+        conn.execute_batch("BEGIN").unwrap();
+        assert!(set_foreign_keys_pragma(&conn, true).is_err());
+        conn.execute_batch("ROLLBACK").unwrap();
+    }
+
     #[test]
     fn test_check_column_exists() {
         let conn = Connection::open_in_memory().unwrap();
@@ -0,0 +1,375 @@
+/// 複数のアプリケーションインスタンス間のマイグレーションロック
+///
+/// 同じデータベースファイルに対して複数のインスタンスが同時にマイグレーションを実行しないよう、
+/// データベース内の専用テーブルにロックの保持者を記録します。
+/// ロックの取得は`BEGIN IMMEDIATE`のトランザクション内で行うため、プロセス間でも排他されます。
+///
+/// 保持者がクラッシュした場合に備え、保持者のプロセスIDが存在しない場合や、
+/// 取得から一定時間が経過した場合は古いロックとみなして引き継ぎます。
+use crate::shared::errors::{AppError, AppResult};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
+use std::time::{Duration, Instant};
+
+/// ロックテーブルのSQL（マイグレーションより前に必要なため、マイグレーションでは作成しない）
+const LOCK_TABLE_SQL: &str = "
+    CREATE TABLE IF NOT EXISTS app_migration_lock (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        owner_pid INTEGER NOT NULL,
+        owner_token TEXT NOT NULL,
+        acquired_at TEXT NOT NULL
+    );
+";
+
+/// ロック取得の設定
+#[derive(Debug, Clone)]
+pub struct MigrationLockOptions {
+    /// 他のインスタンスがロックを保持している場合に待機する最大時間
+    pub wait_timeout: Duration,
+    /// 待機中にロックを再確認する間隔
+    pub poll_interval: Duration,
+    /// 取得からこの時間が経過したロックは古いロックとみなす
+    pub stale_after: chrono::Duration,
+}
+
+impl Default for MigrationLockOptions {
+    fn default() -> Self {
+        Self {
+            wait_timeout: Duration::from_secs(30),
+            poll_interval: Duration::from_millis(200),
+            stale_after: chrono::Duration::minutes(10),
+        }
+    }
+}
+
+/// ロックの保持者
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockHolder {
+    pub pid: u32,
+    pub token: String,
+    pub acquired_at: DateTime<Utc>,
+}
+
+impl LockHolder {
+    /// 保持者がクラッシュした可能性が高い古いロックかどうか
+    fn is_stale(&self, now: DateTime<Utc>, stale_after: chrono::Duration) -> bool {
+        self.acquired_at + stale_after < now || !process_alive(self.pid)
+    }
+}
+
+/// 取得したマイグレーションロック
+///
+/// 破棄時にロックを解放します。
+pub struct MigrationLock<'a> {
+    conn: &'a Connection,
+    holder: LockHolder,
+}
+
+impl MigrationLock<'_> {
+    /// ロックの保持者（このインスタンス）
+    pub fn holder(&self) -> &LockHolder {
+        &self.holder
+    }
+}
+
+impl Drop for MigrationLock<'_> {
+    fn drop(&mut self) {
+        // 古いロックとして引き継がれていた場合は、他のインスタンスのロックを削除しない
+        if let Err(e) = self.conn.execute(
+            "DELETE FROM app_migration_lock WHERE id = 1 AND owner_token = ?1",
+            params![self.holder.token],
+        ) {
+            log::warn!("マイグレーションロックの解放に失敗しました: {e}");
+        }
+    }
+}
+
+/// ロックの取得結果
+pub enum MigrationLockOutcome<'a> {
+    /// ロックを取得した
+    Acquired(MigrationLock<'a>),
+    /// 待機時間内に他のインスタンスがロックを解放しなかった
+    Busy(LockHolder),
+}
+
+/// マイグレーションロックの取得を一度だけ試みる
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `options` - ロック取得の設定
+///
+/// # 戻り値
+/// 取得した場合はロック、他のインスタンスが保持している場合はその保持者
+pub fn try_acquire<'a>(
+    conn: &'a Connection,
+    options: &MigrationLockOptions,
+) -> AppResult<Result<MigrationLock<'a>, LockHolder>> {
+    conn.execute_batch(LOCK_TABLE_SQL)?;
+
+    let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+    let now = Utc::now();
+
+    if let Some(holder) = current_holder(&tx)? {
+        if !holder.is_stale(now, options.stale_after) {
+            return Ok(Err(holder));
+        }
+        log::warn!(
+            "古いマイグレーションロックを引き継ぎます: pid={}, acquired_at={}",
+            holder.pid,
+            holder.acquired_at.to_rfc3339()
+        );
+    }
+
+    let holder = LockHolder {
+        pid: std::process::id(),
+        token: uuid::Uuid::new_v4().to_string(),
+        acquired_at: now,
+    };
+    tx.execute(
+        "INSERT OR REPLACE INTO app_migration_lock (id, owner_pid, owner_token, acquired_at)
+         VALUES (1, ?1, ?2, ?3)",
+        params![holder.pid, holder.token, holder.acquired_at.to_rfc3339()],
+    )?;
+    tx.commit()?;
+
+    Ok(Ok(MigrationLock { conn, holder }))
+}
+
+/// マイグレーションロックを取得する
+///
+/// 他のインスタンスが保持している場合は、`wait_timeout`まで解放を待ちます。
+///
+/// # 引数
+/// * `conn` - データベース接続
+/// * `options` - ロック取得の設定
+///
+/// # 戻り値
+/// ロックの取得結果
+pub fn acquire<'a>(
+    conn: &'a Connection,
+    options: &MigrationLockOptions,
+) -> AppResult<MigrationLockOutcome<'a>> {
+    let deadline = Instant::now() + options.wait_timeout;
+    let mut logged = false;
+
+    loop {
+        let last_holder = match try_acquire(conn, options) {
+            Ok(Ok(lock)) => return Ok(MigrationLockOutcome::Acquired(lock)),
+            Ok(Err(holder)) => Some(holder),
+            // 他のインスタンスが書き込み中の場合は待機して再試行する
+            Err(AppError::Database(message)) if message.contains("locked") => None,
+            Err(e) => return Err(e),
+        };
+
+        if Instant::now() >= deadline {
+            let holder = match last_holder {
+                Some(holder) => holder,
+                None => current_holder(conn)?.ok_or_else(|| {
+                    AppError::Database("マイグレーションロックを取得できませんでした".to_string())
+                })?,
+            };
+            return Ok(MigrationLockOutcome::Busy(holder));
+        }
+
+        if !logged {
+            if let Some(holder) = &last_holder {
+                log::info!(
+                    "他のインスタンスがマイグレーション中のため待機します: pid={}",
+                    holder.pid
+                );
+            }
+            logged = true;
+        }
+        std::thread::sleep(options.poll_interval);
+    }
+}
+
+/// 現在のロックの保持者を取得する
+fn current_holder(conn: &Connection) -> AppResult<Option<LockHolder>> {
+    let row = conn
+        .query_row(
+            "SELECT owner_pid, owner_token, acquired_at FROM app_migration_lock WHERE id = 1",
+            [],
+            |row| {
+                Ok((
+                    row.get::<_, u32>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            },
+        )
+        .optional()?;
+
+    row.map(|(pid, token, acquired_at)| {
+        let acquired_at = DateTime::parse_from_rfc3339(&acquired_at)
+            .map_err(|e| AppError::Database(format!("ロックの取得日時が不正です: {e}")))?
+            .with_timezone(&Utc);
+        Ok(LockHolder {
+            pid,
+            token,
+            acquired_at,
+        })
+    })
+    .transpose()
+}
+
+/// プロセスが存在するかどうか（判定できない環境では存在するものとみなす）
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // シグナル0は送信せずに存在と権限のみを確認する
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return true;
+    }
+    std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Barrier};
+    use tempfile::TempDir;
+
+    fn open(path: &std::path::Path) -> Connection {
+        let conn = Connection::open(path).unwrap();
+        conn.busy_timeout(Duration::from_secs(5)).unwrap();
+        conn
+    }
+
+    fn quick_options() -> MigrationLockOptions {
+        MigrationLockOptions {
+            wait_timeout: Duration::from_millis(300),
+            poll_interval: Duration::from_millis(20),
+            ..Default::default()
+        }
+    }
+
+    fn insert_holder(conn: &Connection, pid: u32, acquired_at: DateTime<Utc>) {
+        conn.execute_batch(LOCK_TABLE_SQL).unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO app_migration_lock (id, owner_pid, owner_token, acquired_at)
+             VALUES (1, ?1, 'crashed', ?2)",
+            params![pid, acquired_at.to_rfc3339()],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_second_connection_is_blocked_until_release() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.db");
+        let first = open(&path);
+        let second = open(&path);
+
+        let lock = try_acquire(&first, &quick_options()).unwrap().unwrap();
+        let holder = try_acquire(&second, &quick_options())
+            .unwrap()
+            .err()
+            .unwrap();
+        assert_eq!(&holder, lock.holder());
+
+        assert!(matches!(
+            acquire(&second, &quick_options()).unwrap(),
+            MigrationLockOutcome::Busy(_)
+        ));
+
+        drop(lock);
+        assert!(matches!(
+            acquire(&second, &quick_options()).unwrap(),
+            MigrationLockOutcome::Acquired(_)
+        ));
+    }
+
+    #[test]
+    fn test_waiting_instance_acquires_after_release() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.db");
+        let first = open(&path);
+        let lock = try_acquire(&first, &quick_options()).unwrap().unwrap();
+
+        let waiter = {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                let second = open(&path);
+                let options = MigrationLockOptions {
+                    wait_timeout: Duration::from_secs(5),
+                    ..quick_options()
+                };
+                let outcome = acquire(&second, &options).unwrap();
+                matches!(outcome, MigrationLockOutcome::Acquired(_))
+            })
+        };
+
+        std::thread::sleep(Duration::from_millis(100));
+        drop(lock);
+        assert!(waiter.join().unwrap());
+    }
+
+    #[test]
+    fn test_only_one_contender_acquires() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.db");
+        open(&path).execute_batch(LOCK_TABLE_SQL).unwrap();
+
+        let barrier = Arc::new(Barrier::new(4));
+        let acquired = (0..4)
+            .map(|_| {
+                let path = path.clone();
+                let barrier = Arc::clone(&barrier);
+                std::thread::spawn(move || {
+                    let conn = open(&path);
+                    barrier.wait();
+                    let lock = try_acquire(&conn, &quick_options()).unwrap();
+                    // 全員が試行し終えるまでロックを保持する
+                    std::thread::sleep(Duration::from_millis(200));
+                    lock.is_ok()
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .filter(|acquired| *acquired)
+            .count();
+
+        assert_eq!(acquired, 1);
+    }
+
+    #[test]
+    fn test_stale_lock_from_crashed_process_is_taken_over() {
+        let dir = TempDir::new().unwrap();
+        let conn = open(&dir.path().join("test.db"));
+
+        // 存在しないプロセスが保持していたロック
+        insert_holder(&conn, i32::MAX as u32, Utc::now());
+        let lock = try_acquire(&conn, &quick_options()).unwrap().unwrap();
+        assert_eq!(lock.holder().pid, std::process::id());
+        drop(lock);
+
+        // 取得から長時間経過したロック
+        insert_holder(
+            &conn,
+            std::process::id(),
+            Utc::now() - chrono::Duration::hours(1),
+        );
+        assert!(try_acquire(&conn, &quick_options()).unwrap().is_ok());
+    }
+
+    #[test]
+    fn test_release_does_not_remove_lock_taken_over_by_another_instance() {
+        let dir = TempDir::new().unwrap();
+        let conn = open(&dir.path().join("test.db"));
+
+        let lock = try_acquire(&conn, &quick_options()).unwrap().unwrap();
+        insert_holder(&conn, std::process::id(), Utc::now());
+        drop(lock);
+
+        assert_eq!(current_holder(&conn).unwrap().unwrap().token, "crashed");
+    }
+}
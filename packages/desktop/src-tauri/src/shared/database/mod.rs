@@ -1,3 +1,4 @@
 pub mod connection;
+pub mod migration_lock;
 
 pub use connection::*;
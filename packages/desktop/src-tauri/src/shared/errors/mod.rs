@@ -42,6 +42,18 @@ pub enum AppError {
     /// R2（AWS S3）関連のエラー
     #[error("R2エラー: {0}")]
     R2(String),
+
+    /// ユーザー操作による処理のキャンセル（失敗ではない）
+    #[error("キャンセル: {0}")]
+    Cancelled(String),
+
+    /// 処理の文脈を付けた内部エラー（`AppResultExt::map_context`で作成）
+    #[error("{context}: {source}")]
+    Internal {
+        context: String,
+        #[source]
+        source: Box<AppError>,
+    },
 }
 
 /// エラーの重要度を表す列挙型
@@ -74,6 +86,8 @@ impl AppError {
             AppError::Json(_) => "データ形式の解析でエラーが発生しました",
             AppError::Concurrency(_) => "並行処理でエラーが発生しました",
             AppError::R2(_) => "クラウドストレージでエラーが発生しました",
+            AppError::Cancelled(msg) => msg,
+            AppError::Internal { source, .. } => source.user_message(),
         }
     }
 
@@ -101,6 +115,41 @@ impl AppError {
             AppError::Json(_) => ErrorSeverity::Medium,
             AppError::Concurrency(_) => ErrorSeverity::High,
             AppError::R2(_) => ErrorSeverity::Medium,
+            AppError::Cancelled(_) => ErrorSeverity::Low,
+            AppError::Internal { source, .. } => source.severity(),
+        }
+    }
+
+    /// 時間をおいて再試行すれば成功する可能性がある一時的なエラーかどうか
+    ///
+    /// 外部サービス・R2・並行処理のエラーと、タイムアウトまたは割り込みによるI/Oエラーが該当します。
+    /// 入力内容や設定、権限に起因するエラーは再試行しても解決しないためfalseを返します。
+    pub fn is_transient(&self) -> bool {
+        match self {
+            AppError::ExternalService(_) | AppError::R2(_) | AppError::Concurrency(_) => true,
+            AppError::Io(error) => matches!(
+                error.kind(),
+                std::io::ErrorKind::TimedOut | std::io::ErrorKind::Interrupted
+            ),
+            AppError::Internal { source, .. } => source.is_transient(),
+            AppError::Database(_)
+            | AppError::Validation(_)
+            | AppError::NotFound(_)
+            | AppError::Security(_)
+            | AppError::Configuration(_)
+            | AppError::Json(_)
+            | AppError::Cancelled(_) => false,
+        }
+    }
+
+    /// ユーザー操作によるキャンセルかどうか
+    ///
+    /// キャンセルは失敗として扱わず、エラー表示やエラー統計の対象外とします。
+    pub fn is_cancelled(&self) -> bool {
+        match self {
+            AppError::Cancelled(_) => true,
+            AppError::Internal { source, .. } => source.is_cancelled(),
+            _ => false,
         }
     }
 
@@ -181,6 +230,17 @@ impl AppError {
     pub fn r2<S: Into<String>>(message: S) -> Self {
         AppError::R2(message.into())
     }
+
+    /// キャンセルを作成するヘルパー関数
+    ///
+    /// # 引数
+    /// * `message` - キャンセルされた処理の説明
+    ///
+    /// # 戻り値
+    /// キャンセル
+    pub fn cancelled<S: Into<String>>(message: S) -> Self {
+        AppError::Cancelled(message.into())
+    }
 }
 
 /// AppErrorからStringへの変換（Tauriコマンドでの使用のため）
@@ -203,6 +263,75 @@ pub type AppResult<T> = Result<T, AppError>;
 /// R2Error型のエイリアス（後方互換性のため）
 pub type R2Error = AppError;
 
+/// Resultのエラーに処理の文脈を付けるための拡張トレイト
+///
+/// `.map_err(|e| AppError::Database(format!("{context}: {e}")))`のような変換を
+/// `.map_context(context)`と書けるようにします。
+pub trait AppResultExt<T> {
+    /// エラーを文脈付きの`AppError::Internal`で包む
+    ///
+    /// ユーザー向けメッセージと重要度は元のエラーのものを引き継ぎます。
+    ///
+    /// # 引数
+    /// * `context` - 失敗した処理の説明（ログ出力用）
+    fn map_context(self, context: &str) -> AppResult<T>;
+}
+
+impl<T, E: Into<AppError>> AppResultExt<T> for Result<T, E> {
+    fn map_context(self, context: &str) -> AppResult<T> {
+        self.map_err(|e| AppError::Internal {
+            context: context.to_string(),
+            source: Box::new(e.into()),
+        })
+    }
+}
+
+/// フィールド単位のバリデーションエラー
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ValidationError {
+    /// 対象フィールド名（DTOのフィールド名）
+    pub field: String,
+    /// ユーザーに表示するメッセージ
+    pub message: String,
+}
+
+impl ValidationError {
+    /// バリデーション結果から、エラーがあればValidationErrorを作成する
+    ///
+    /// # 引数
+    /// * `field` - 対象フィールド名
+    /// * `result` - そのフィールドのバリデーション結果
+    pub fn from_result(field: &str, result: AppResult<()>) -> Option<Self> {
+        result.err().map(|e| Self {
+            field: field.to_string(),
+            message: e.user_message().to_string(),
+        })
+    }
+
+    /// フィールドごとのバリデーション結果から、エラーのみを集める
+    pub fn collect<'a, I>(results: I) -> Vec<Self>
+    where
+        I: IntoIterator<Item = (&'a str, AppResult<()>)>,
+    {
+        results
+            .into_iter()
+            .filter_map(|(field, result)| Self::from_result(field, result))
+            .collect()
+    }
+
+    /// エラーがあれば、すべてのメッセージをまとめたバリデーションエラーにする
+    ///
+    /// # 戻り値
+    /// エラーがない場合はOk(())、ある場合は改行区切りのメッセージを持つエラー
+    pub fn ensure_none(errors: &[Self]) -> AppResult<()> {
+        if errors.is_empty() {
+            return Ok(());
+        }
+        let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+        Err(AppError::validation(messages.join("\n")))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -261,6 +390,67 @@ mod tests {
         assert!(matches!(external_error, AppError::ExternalService(_)));
     }
 
+    #[test]
+    fn test_is_transient() {
+        use std::io::{Error, ErrorKind};
+
+        assert!(AppError::external_service("API", "503").is_transient());
+        assert!(AppError::r2("接続失敗").is_transient());
+        assert!(AppError::concurrency("ロック取得失敗").is_transient());
+        assert!(AppError::Io(Error::new(ErrorKind::TimedOut, "timeout")).is_transient());
+        assert!(AppError::Io(Error::new(ErrorKind::Interrupted, "interrupted")).is_transient());
+
+        assert!(!AppError::Io(Error::new(ErrorKind::NotFound, "missing")).is_transient());
+        assert!(!AppError::validation("金額が不正です").is_transient());
+        assert!(!AppError::not_found("経費").is_transient());
+        assert!(!AppError::security("認証失敗").is_transient());
+        assert!(!AppError::configuration("設定ファイル不正").is_transient());
+        assert!(!AppError::cancelled("中断しました").is_transient());
+
+        // 文脈付きのエラーは元のエラーで判定する
+        let error = Err::<(), _>(AppError::r2("接続失敗"))
+            .map_context("領収書のアップロード")
+            .unwrap_err();
+        assert!(error.is_transient());
+    }
+
+    #[test]
+    fn test_map_context() {
+        let result: AppResult<()> = Err(AppError::validation("金額が不正です"));
+        let error = result.map_context("経費の保存").unwrap_err();
+
+        assert!(matches!(
+            &error,
+            AppError::Internal { context, source }
+                if context == "経費の保存" && matches!(**source, AppError::Validation(_))
+        ));
+        assert_eq!(
+            error.to_string(),
+            "経費の保存: バリデーションエラー: 金額が不正です"
+        );
+        // ユーザー向けメッセージと重要度は元のエラーを引き継ぐ
+        assert_eq!(error.user_message(), "金額が不正です");
+        assert_eq!(error.severity(), ErrorSeverity::Low);
+
+        // AppErrorに変換できるエラーにも使える
+        let error = Err::<(), _>(rusqlite::Error::QueryReturnedNoRows)
+            .map_context("ユーザーIDの取得")
+            .unwrap_err();
+        assert_eq!(error.severity(), ErrorSeverity::High);
+        assert!(error
+            .to_string()
+            .starts_with("ユーザーIDの取得: データベースエラー:"));
+
+        // キャンセルは文脈を付けてもキャンセルとして扱う
+        let error = Err::<(), _>(AppError::cancelled("中断しました"))
+            .map_context("移行処理")
+            .unwrap_err();
+        assert!(error.is_cancelled());
+
+        let ok: AppResult<i32> = Ok(1);
+        assert_eq!(ok.map_context("未使用").unwrap(), 1);
+    }
+
     #[test]
     fn test_string_conversion() {
         // String変換のテスト
@@ -269,6 +459,51 @@ mod tests {
         assert_eq!(error_string, "テストエラー");
     }
 
+    #[test]
+    fn test_cancelled_is_not_failure() {
+        let cancelled = AppError::cancelled("アップロードがキャンセルされました");
+        assert!(cancelled.is_cancelled());
+        assert_eq!(cancelled.severity(), ErrorSeverity::Low);
+        assert_eq!(
+            cancelled.user_message(),
+            "アップロードがキャンセルされました"
+        );
+
+        assert!(!AppError::concurrency("処理がキャンセルされました").is_cancelled());
+    }
+
+    #[test]
+    fn test_validation_error_collect() {
+        let errors = ValidationError::collect([
+            ("date", Err(AppError::validation("無効な日付です"))),
+            ("amount", Ok(())),
+            (
+                "category",
+                Err(AppError::validation("カテゴリは必須項目です")),
+            ),
+        ]);
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError {
+                    field: "date".to_string(),
+                    message: "無効な日付です".to_string(),
+                },
+                ValidationError {
+                    field: "category".to_string(),
+                    message: "カテゴリは必須項目です".to_string(),
+                },
+            ]
+        );
+
+        let error = ValidationError::ensure_none(&errors).unwrap_err();
+        assert_eq!(
+            error.user_message(),
+            "無効な日付です\nカテゴリは必須項目です"
+        );
+        assert!(ValidationError::ensure_none(&[]).is_ok());
+    }
+
     #[test]
     fn test_error_details() {
         // エラー詳細のテスト
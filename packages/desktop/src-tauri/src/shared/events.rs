@@ -0,0 +1,421 @@
+//! フロントエンドに通知するイベントの登録
+//!
+//! イベント名と通知内容の型をここで一元管理し、バックエンドからの通知はすべて
+//! [`emit_event`]を経由します。フロントエンドは生成された
+//! `src/lib/types/events.ts` からイベント名と通知内容の型を読み込みます。
+//!
+//! イベントを追加・変更したときは、次のコマンドで`events.ts`を生成し直してください。
+//!
+//! ```sh
+//! UPDATE_EVENT_BINDINGS=1 cargo test shared::events
+//! ```
+
+use crate::features::auth::storage_backend::SecureStorageEvent;
+use crate::features::expenses::sync::ExpenseSyncReport;
+use crate::features::receipts::models::CacheNearFullEvent;
+use crate::features::updater::service::{
+    PendingInstallBlockedEvent, UpdateCheckProgress, UpdateInfo,
+};
+use tauri::{Emitter, Runtime};
+
+/// イベントの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    /// アップデートのダウンロードの進捗（%）
+    DownloadProgress,
+    /// アップデートのダウンロード完了
+    DownloadComplete,
+    /// 自動チェックで見つかったアップデート
+    UpdateAvailable,
+    /// 自動チェックのエラー
+    UpdateCheckError,
+    /// 強制チェックの進捗
+    UpdateCheckProgress,
+    /// 実行中の処理のためにインストールを待っている
+    PendingInstallBlocked,
+    /// メニューからのチェックで見つかったアップデート
+    ShowUpdateDialog,
+    /// メニューからのチェックで最新版だった
+    ShowNoUpdateDialog,
+    /// メニューからのチェックのエラー
+    ShowUpdateErrorDialog,
+    /// セキュアストレージの状態変化
+    SecureStorage,
+    /// 領収書キャッシュの空き容量が少ない
+    CacheNearFull,
+    /// 経費の同期でローカルのデータが変わった
+    ExpensesSynced,
+}
+
+impl EventKind {
+    /// すべてのイベントの種類
+    pub const ALL: &'static [EventKind] = &[
+        EventKind::DownloadProgress,
+        EventKind::DownloadComplete,
+        EventKind::UpdateAvailable,
+        EventKind::UpdateCheckError,
+        EventKind::UpdateCheckProgress,
+        EventKind::PendingInstallBlocked,
+        EventKind::ShowUpdateDialog,
+        EventKind::ShowNoUpdateDialog,
+        EventKind::ShowUpdateErrorDialog,
+        EventKind::SecureStorage,
+        EventKind::CacheNearFull,
+        EventKind::ExpensesSynced,
+    ];
+
+    /// イベント名
+    pub const fn name(self) -> &'static str {
+        match self {
+            EventKind::DownloadProgress => "download-progress",
+            EventKind::DownloadComplete => "download-complete",
+            EventKind::UpdateAvailable => "update-available",
+            EventKind::UpdateCheckError => "update-check-error",
+            EventKind::UpdateCheckProgress => "update-check-progress",
+            EventKind::PendingInstallBlocked => "pending-install-blocked",
+            EventKind::ShowUpdateDialog => "show-update-dialog",
+            EventKind::ShowNoUpdateDialog => "show-no-update-dialog",
+            EventKind::ShowUpdateErrorDialog => "show-update-error-dialog",
+            EventKind::SecureStorage => "secure-storage-event",
+            EventKind::CacheNearFull => "cache-near-full",
+            EventKind::ExpensesSynced => "expenses-synced",
+        }
+    }
+
+    /// 通知内容のTypeScriptの型と、型を定義しているモジュール（`src/lib/types`からの相対パス）
+    const fn ts_payload(self) -> (&'static str, Option<&'static str>) {
+        match self {
+            EventKind::DownloadProgress => ("number", None),
+            EventKind::DownloadComplete | EventKind::ShowNoUpdateDialog => ("null", None),
+            EventKind::UpdateCheckError | EventKind::ShowUpdateErrorDialog => ("string", None),
+            EventKind::UpdateAvailable | EventKind::ShowUpdateDialog => {
+                ("UpdateInfo", Some("./updater"))
+            }
+            EventKind::UpdateCheckProgress => ("UpdateCheckProgress", Some("./updater")),
+            EventKind::PendingInstallBlocked => ("PendingInstallBlockedEvent", Some("./updater")),
+            EventKind::SecureStorage => ("SecureStorageEvent", Some("./index")),
+            EventKind::CacheNearFull => ("CacheNearFullEvent", Some("./index")),
+            EventKind::ExpensesSynced => ("ExpenseSyncReport", Some("./index")),
+        }
+    }
+
+    /// TypeScriptの定数名（`download-progress` → `DOWNLOAD_PROGRESS`）
+    fn ts_constant(self) -> String {
+        self.name().replace('-', "_").to_uppercase()
+    }
+}
+
+/// フロントエンドに通知するイベントと通知内容
+#[derive(Debug, Clone)]
+pub enum AppEvent {
+    DownloadProgress(u32),
+    DownloadComplete,
+    UpdateAvailable(UpdateInfo),
+    UpdateCheckError(String),
+    UpdateCheckProgress(UpdateCheckProgress),
+    PendingInstallBlocked(PendingInstallBlockedEvent),
+    ShowUpdateDialog(UpdateInfo),
+    ShowNoUpdateDialog,
+    ShowUpdateErrorDialog(String),
+    SecureStorage(SecureStorageEvent),
+    CacheNearFull(CacheNearFullEvent),
+    ExpensesSynced(ExpenseSyncReport),
+}
+
+impl AppEvent {
+    /// イベントの種類
+    pub fn kind(&self) -> EventKind {
+        match self {
+            AppEvent::DownloadProgress(_) => EventKind::DownloadProgress,
+            AppEvent::DownloadComplete => EventKind::DownloadComplete,
+            AppEvent::UpdateAvailable(_) => EventKind::UpdateAvailable,
+            AppEvent::UpdateCheckError(_) => EventKind::UpdateCheckError,
+            AppEvent::UpdateCheckProgress(_) => EventKind::UpdateCheckProgress,
+            AppEvent::PendingInstallBlocked(_) => EventKind::PendingInstallBlocked,
+            AppEvent::ShowUpdateDialog(_) => EventKind::ShowUpdateDialog,
+            AppEvent::ShowNoUpdateDialog => EventKind::ShowNoUpdateDialog,
+            AppEvent::ShowUpdateErrorDialog(_) => EventKind::ShowUpdateErrorDialog,
+            AppEvent::SecureStorage(_) => EventKind::SecureStorage,
+            AppEvent::CacheNearFull(_) => EventKind::CacheNearFull,
+            AppEvent::ExpensesSynced(_) => EventKind::ExpensesSynced,
+        }
+    }
+
+    /// 通知内容をJSONに変換する
+    pub fn payload(&self) -> serde_json::Result<serde_json::Value> {
+        match self {
+            AppEvent::DownloadProgress(progress) => serde_json::to_value(progress),
+            AppEvent::DownloadComplete | AppEvent::ShowNoUpdateDialog => {
+                Ok(serde_json::Value::Null)
+            }
+            AppEvent::UpdateAvailable(info) | AppEvent::ShowUpdateDialog(info) => {
+                serde_json::to_value(info)
+            }
+            AppEvent::UpdateCheckError(message) | AppEvent::ShowUpdateErrorDialog(message) => {
+                serde_json::to_value(message)
+            }
+            AppEvent::UpdateCheckProgress(progress) => serde_json::to_value(progress),
+            AppEvent::PendingInstallBlocked(event) => serde_json::to_value(event),
+            AppEvent::SecureStorage(event) => serde_json::to_value(event),
+            AppEvent::CacheNearFull(event) => serde_json::to_value(event),
+            AppEvent::ExpensesSynced(report) => serde_json::to_value(report),
+        }
+    }
+}
+
+/// イベントをフロントエンドに通知する
+///
+/// # 引数
+/// * `emitter` - 通知元（`AppHandle`や`Window`）
+/// * `event` - 通知するイベント
+pub fn emit_event<R: Runtime>(emitter: &impl Emitter<R>, event: AppEvent) -> tauri::Result<()> {
+    let payload = event.payload()?;
+    emitter.emit(event.kind().name(), payload)
+}
+
+/// フロントエンド用のTypeScriptの定義を生成する
+pub fn typescript_bindings() -> String {
+    let mut imports: Vec<(&str, Vec<&str>)> = Vec::new();
+    for kind in EventKind::ALL {
+        if let (ty, Some(module)) = kind.ts_payload() {
+            match imports.iter_mut().find(|(m, _)| *m == module) {
+                Some((_, types)) if !types.contains(&ty) => types.push(ty),
+                Some(_) => {}
+                None => imports.push((module, vec![ty])),
+            }
+        }
+    }
+    imports.sort_unstable_by_key(|(module, _)| *module);
+
+    let mut out = String::from(
+        "// このファイルは src-tauri/src/shared/events.rs から生成されています。直接編集しないでください。\n\
+         // 再生成: UPDATE_EVENT_BINDINGS=1 cargo test shared::events\n",
+    );
+    for (module, mut types) in imports {
+        types.sort_unstable();
+        let line = format!("import type {{ {} }} from '{module}';\n", types.join(", "));
+        if line.trim_end().chars().count() <= 80 {
+            out.push_str(&line);
+        } else {
+            // Prettierの折り返しに合わせる
+            out.push_str("import type {\n");
+            for ty in types {
+                out.push_str(&format!("  {ty},\n"));
+            }
+            out.push_str(&format!("}} from '{module}';\n"));
+        }
+    }
+
+    out.push_str("\n// バックエンドから通知されるイベント名\nexport const EVENTS = {\n");
+    for kind in EventKind::ALL {
+        out.push_str(&format!("  {}: '{}',\n", kind.ts_constant(), kind.name()));
+    }
+    out.push_str("} as const;\n\nexport type EventName = (typeof EVENTS)[keyof typeof EVENTS];\n");
+
+    out.push_str("\n// イベントごとの通知内容\nexport interface EventPayloads {\n");
+    for kind in EventKind::ALL {
+        out.push_str(&format!("  '{}': {};\n", kind.name(), kind.ts_payload().0));
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::receipts::models::CacheStats;
+    use crate::features::updater::service::UpdateCheckStage;
+    use std::path::{Path, PathBuf};
+
+    /// 生成したTypeScriptの定義のパス
+    fn bindings_path() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../src/lib/types/events.ts")
+    }
+
+    fn update_info() -> UpdateInfo {
+        UpdateInfo {
+            available: true,
+            current_version: "1.0.0".to_string(),
+            latest_version: Some("1.1.0".to_string()),
+            release_notes: None,
+            content_length: Some(1024),
+            last_checked: 0,
+            download_url: None,
+            signature: None,
+        }
+    }
+
+    /// すべてのイベントの例（種類ごとに少なくとも1つ）
+    fn samples() -> Vec<AppEvent> {
+        vec![
+            AppEvent::DownloadProgress(42),
+            AppEvent::DownloadComplete,
+            AppEvent::UpdateAvailable(update_info()),
+            AppEvent::UpdateCheckError("接続できません".to_string()),
+            AppEvent::UpdateCheckProgress(UpdateCheckProgress {
+                stage: UpdateCheckStage::Connecting,
+            }),
+            AppEvent::PendingInstallBlocked(PendingInstallBlockedEvent {
+                operations: vec![],
+                scheduled_on_next_quit: false,
+            }),
+            AppEvent::ShowUpdateDialog(update_info()),
+            AppEvent::ShowNoUpdateDialog,
+            AppEvent::ShowUpdateErrorDialog("接続できません".to_string()),
+            AppEvent::SecureStorage(SecureStorageEvent::Locked {
+                attempt: 1,
+                max_attempts: 3,
+            }),
+            AppEvent::SecureStorage(SecureStorageEvent::FallbackActivated {
+                reason: "バックエンドなし".to_string(),
+            }),
+            AppEvent::SecureStorage(SecureStorageEvent::CorruptedEntry {
+                key: "session".to_string(),
+            }),
+            AppEvent::SecureStorage(SecureStorageEvent::CorruptedStore {
+                store: "secure.json".to_string(),
+            }),
+            AppEvent::CacheNearFull(CacheNearFullEvent {
+                utilization_percent: 95.0,
+                stats: CacheStats {
+                    total_files: 10,
+                    total_size_bytes: 95,
+                    max_size_bytes: 100,
+                    cache_hit_rate: 0.5,
+                },
+            }),
+            AppEvent::ExpensesSynced(ExpenseSyncReport {
+                full_resync: false,
+                upserted: 1,
+                deleted: 0,
+                total: 1,
+                revision: "rev-1".to_string(),
+                changed: true,
+            }),
+        ]
+    }
+
+    /// TypeScriptのソースから型の定義部分を取り出す
+    fn ts_declaration<'a>(source: &'a str, ty: &str) -> Option<&'a str> {
+        let start = [
+            format!("export interface {ty} "),
+            format!("export type {ty} "),
+        ]
+        .iter()
+        .find_map(|marker| source.find(marker.as_str()))?;
+        let rest = &source[start..];
+        Some(rest.find("\n\n").map_or(rest, |end| &rest[..end]))
+    }
+
+    #[test]
+    fn test_event_names_are_unique_and_kebab_case() {
+        let mut names: Vec<_> = EventKind::ALL.iter().map(|kind| kind.name()).collect();
+        for name in &names {
+            assert!(
+                name.chars().all(|c| c.is_ascii_lowercase() || c == '-'),
+                "イベント名はkebab-caseにしてください: {name}"
+            );
+        }
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), EventKind::ALL.len());
+    }
+
+    #[test]
+    fn test_samples_cover_all_kinds() {
+        let samples = samples();
+        for kind in EventKind::ALL {
+            assert!(
+                samples.iter().any(|event| event.kind() == *kind),
+                "イベントの例がありません: {}",
+                kind.name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_payloads_match_typescript_types() {
+        let sources_dir = bindings_path().parent().unwrap().to_path_buf();
+        for event in samples() {
+            let kind = event.kind();
+            let payload = event.payload().unwrap();
+            let (ty, module) = kind.ts_payload();
+            match (ty, &payload) {
+                ("null", serde_json::Value::Null)
+                | ("number", serde_json::Value::Number(_))
+                | ("string", serde_json::Value::String(_)) => continue,
+                (_, serde_json::Value::Object(fields)) => {
+                    let module = module.expect("オブジェクトの通知内容には型の定義が必要です");
+                    let path = sources_dir.join(format!("{}.ts", module.trim_start_matches("./")));
+                    let source = std::fs::read_to_string(&path).unwrap();
+                    let declaration = ts_declaration(&source, ty)
+                        .unwrap_or_else(|| panic!("{}に{ty}の定義がありません", path.display()));
+                    for field in fields.keys() {
+                        assert!(
+                            declaration.contains(&format!("{field}:"))
+                                || declaration.contains(&format!("{field}?:")),
+                            "{ty}（{}）にフィールド{field}がありません",
+                            kind.name()
+                        );
+                    }
+                }
+                _ => panic!(
+                    "{}の通知内容がTypeScriptの型（{ty}）と一致しません: {payload}",
+                    kind.name()
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_typescript_bindings_are_up_to_date() {
+        let path = bindings_path();
+        let generated = typescript_bindings();
+        if std::env::var_os("UPDATE_EVENT_BINDINGS").is_some() {
+            std::fs::write(&path, &generated).unwrap();
+            return;
+        }
+        let current = std::fs::read_to_string(&path).unwrap_or_default();
+        assert_eq!(
+            current, generated,
+            "events.tsが古くなっています。UPDATE_EVENT_BINDINGS=1 cargo test shared::events で生成し直してください"
+        );
+    }
+
+    /// Rustのソースファイルを再帰的に集める
+    fn rust_sources(dir: &Path, files: &mut Vec<PathBuf>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                rust_sources(&path, files);
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                files.push(path);
+            }
+        }
+    }
+
+    #[test]
+    fn test_events_are_emitted_through_wrapper() {
+        let src_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let this_file = src_dir.join("shared/events.rs");
+        let mut files = Vec::new();
+        rust_sources(&src_dir, &mut files);
+
+        let mut violations = Vec::new();
+        for file in files.iter().filter(|file| **file != this_file) {
+            let source = std::fs::read_to_string(file).unwrap();
+            for (line_number, line) in source.lines().enumerate() {
+                if [".emit(", ".emit_to(", ".emit_filter("]
+                    .iter()
+                    .any(|call| line.contains(call))
+                {
+                    violations.push(format!("{}:{}", file.display(), line_number + 1));
+                }
+            }
+        }
+        assert!(
+            violations.is_empty(),
+            "イベントはshared::events::emit_eventで通知してください: {violations:?}"
+        );
+    }
+}
@@ -0,0 +1,670 @@
+/// エクスポートファイルの出所情報（プロビナンス）
+///
+/// アプリが出力するすべてのエクスポートに、アプリバージョン・スキーマバージョン・
+/// 環境・出力日時（JST）・出力ユーザーID・内容のチェックサムを付与します。
+///
+/// - CSV: 先頭のコメント行に出所情報、末尾のコメント行にチェックサム
+///   （本文を逐次書き出せるよう、チェックサムのみ末尾に置きます）
+/// - JSON: トップレベルの`meta`オブジェクト（本文は`data`）
+/// - ZIP: `manifest.json`エントリ
+///
+/// 取り込み時はチェックサムを検証してから本文を解析し、
+/// スキーマバージョンを互換性表と照合します。
+use crate::shared::config::environment::{get_environment, Environment};
+use crate::shared::errors::{AppError, AppResult};
+use chrono::Utc;
+use chrono_tz::Asia::Tokyo;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Write;
+
+/// エクスポート形式の識別子
+pub const EXPORT_FORMAT_MARKER: &str = "orano-keihi-export";
+
+/// 現在のデータベーススキーマバージョン（最新のマイグレーションのバージョン）
+pub const CURRENT_SCHEMA_VERSION: &str = "3.12.0";
+
+/// ZIPアーカイブ内のマニフェストファイル名
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// CSVのコメント行の接頭辞
+const CSV_COMMENT_PREFIX: &str = "# ";
+
+/// CSV末尾のチェックサム行のキー
+const CSV_CHECKSUM_KEY: &str = "checksum";
+
+/// スキーマの互換性
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompatibilityLevel {
+    /// そのまま取り込める
+    Compatible,
+    /// 取り込めるが警告を表示する
+    Warn,
+    /// 取り込みを拒否する
+    Refuse,
+}
+
+/// スキーマバージョンの互換性表
+///
+/// 出力時のスキーマバージョンの接頭辞と互換性の対応です。上から順に照合し、
+/// どれにも一致しないバージョン（将来のバージョンを含む）は拒否します。
+const COMPATIBILITY_TABLE: &[(&str, CompatibilityLevel)] = &[
+    ("3.12.", CompatibilityLevel::Compatible),
+    // 経費と店舗の紐付けテーブル追加前。紐付けはエクスポート対象外
+    ("3.11.", CompatibilityLevel::Compatible),
+    // 経費の位置情報テーブル追加前。位置情報はエクスポート対象外
+    ("3.10.", CompatibilityLevel::Compatible),
+    // キャッシュ設定テーブル追加前。キャッシュ設定はエクスポート対象外
+    ("3.9.", CompatibilityLevel::Compatible),
+    // 領収書の添付ルール追加前。ルールはエクスポート対象外
+    ("3.8.", CompatibilityLevel::Compatible),
+    // 経費ミラー追加前。ミラーはAPIサーバーとの同期で作成されるため影響なし
+    ("3.7.", CompatibilityLevel::Compatible),
+    // 店舗テーブル追加前。店舗は未設定として取り込む
+    ("3.6.", CompatibilityLevel::Compatible),
+    // 領収書の注釈テーブル追加前。注釈はエクスポート対象外
+    ("3.5.", CompatibilityLevel::Compatible),
+    // 経費の位置情報カラム追加前。位置情報なしとして取り込む
+    ("3.4.", CompatibilityLevel::Compatible),
+    // 経費の税率・税額カラム追加前。税情報は未設定として取り込む
+    ("3.3.", CompatibilityLevel::Compatible),
+    // subscription_paymentsテーブル追加前。エクスポート対象の構造は同じ
+    ("3.2.", CompatibilityLevel::Compatible),
+    // インデックスの追加のみ
+    ("3.1.", CompatibilityLevel::Compatible),
+    // security_eventsテーブル追加前。経費・サブスクリプションの構造は同じ
+    ("3.0.", CompatibilityLevel::Warn),
+    // ユーザーIDがnanoIdに移行する前のため、ユーザーIDが一致しない
+    ("2.", CompatibilityLevel::Refuse),
+    ("1.", CompatibilityLevel::Refuse),
+];
+
+/// スキーマバージョンの互換性を判定する
+///
+/// # 引数
+/// * `schema_version` - エクスポート時のスキーマバージョン
+pub fn check_schema_compatibility(schema_version: &str) -> CompatibilityLevel {
+    COMPATIBILITY_TABLE
+        .iter()
+        .find(|(prefix, _)| schema_version.starts_with(prefix))
+        .map(|(_, level)| *level)
+        .unwrap_or(CompatibilityLevel::Refuse)
+}
+
+/// エクスポートの出所情報
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportMeta {
+    /// アプリバージョン
+    pub app_version: String,
+    /// スキーマバージョン
+    pub schema_version: String,
+    /// 実行環境（development / production）
+    pub environment: String,
+    /// 出力日時（RFC3339形式、JST）
+    pub exported_at: String,
+    /// 出力したユーザーのID（メールアドレスは含めない）
+    pub user_id: Option<String>,
+    /// 本文のSHA-256チェックサム（16進数）
+    #[serde(default)]
+    pub checksum: String,
+}
+
+impl ExportMeta {
+    /// 現在のアプリ情報で出所情報を作成する
+    ///
+    /// # 引数
+    /// * `user_id` - 出力したユーザーのID
+    pub fn current(user_id: Option<&str>) -> Self {
+        let environment = match get_environment() {
+            Environment::Development => "development",
+            Environment::Production => "production",
+        };
+        Self {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            schema_version: CURRENT_SCHEMA_VERSION.to_string(),
+            environment: environment.to_string(),
+            exported_at: Utc::now().with_timezone(&Tokyo).to_rfc3339(),
+            user_id: user_id.map(str::to_string),
+            checksum: String::new(),
+        }
+    }
+
+    /// スキーマの互換性を確認する
+    ///
+    /// # 戻り値
+    /// 警告メッセージ（互換性がある場合は空）、または拒否時はエラー
+    fn verify_compatibility(&self) -> AppResult<Vec<String>> {
+        match check_schema_compatibility(&self.schema_version) {
+            CompatibilityLevel::Compatible => Ok(Vec::new()),
+            CompatibilityLevel::Warn => Ok(vec![format!(
+                "古いスキーマ（{}）で出力されたファイルです。現在のスキーマは{CURRENT_SCHEMA_VERSION}です",
+                self.schema_version
+            )]),
+            CompatibilityLevel::Refuse => Err(AppError::validation(format!(
+                "スキーマ{}で出力されたファイルは現在のスキーマ{CURRENT_SCHEMA_VERSION}と互換性がありません",
+                self.schema_version
+            ))),
+        }
+    }
+}
+
+/// 検証済みの取り込み内容
+#[derive(Debug, Clone)]
+pub struct VerifiedImport<T> {
+    /// 出所情報
+    pub meta: ExportMeta,
+    /// 本文
+    pub body: T,
+    /// 利用者に表示する警告
+    pub warnings: Vec<String>,
+}
+
+/// SHA-256の16進数表現を計算する
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// 書き込んだ内容のチェックサムを計算するライター
+pub struct ChecksumWriter<W: Write> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> ChecksumWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    fn finish(self) -> (W, String) {
+        (self.inner, format!("{:x}", self.hasher.finalize()))
+    }
+}
+
+impl<W: Write> Write for ChecksumWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// 出所情報付きのCSVを書き出す
+///
+/// 本文は`write_body`で逐次書き出し、書き出しながらチェックサムを計算します。
+///
+/// # 引数
+/// * `writer` - 書き込み先
+/// * `meta` - 出所情報（チェックサムは自動で設定されます）
+/// * `write_body` - CSV本文を書き出す処理
+pub fn write_csv_export<W: Write, T>(
+    writer: &mut W,
+    meta: &ExportMeta,
+    write_body: impl FnOnce(&mut dyn Write) -> AppResult<T>,
+) -> AppResult<T> {
+    writeln!(writer, "{CSV_COMMENT_PREFIX}{EXPORT_FORMAT_MARKER}")?;
+    for (key, value) in [
+        ("app_version", meta.app_version.as_str()),
+        ("schema_version", meta.schema_version.as_str()),
+        ("environment", meta.environment.as_str()),
+        ("exported_at", meta.exported_at.as_str()),
+        ("user_id", meta.user_id.as_deref().unwrap_or("")),
+    ] {
+        writeln!(writer, "{CSV_COMMENT_PREFIX}{key}: {value}")?;
+    }
+
+    let mut checksum_writer = ChecksumWriter::new(&mut *writer);
+    let result = write_body(&mut checksum_writer)?;
+    let (writer, checksum) = checksum_writer.finish();
+
+    writeln!(writer, "{CSV_COMMENT_PREFIX}{CSV_CHECKSUM_KEY}: {checksum}")?;
+    writer.flush()?;
+    Ok(result)
+}
+
+/// CSVフィールドをエスケープする
+///
+/// カンマ・ダブルクォート・改行を含む場合はダブルクォートで囲み、
+/// フィールド内のダブルクォートは二重にします。
+pub fn escape_csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// CSVをレコード単位に分割する
+///
+/// ダブルクォートで囲まれたフィールド内のカンマ・改行・エスケープされた引用符に対応します。
+/// 先頭のBOMと空行は無視します。
+///
+/// # 戻り値
+/// (レコード開始行番号, フィールド一覧) のリスト
+pub fn parse_csv_records(content: &str) -> Vec<(usize, Vec<String>)> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_start = 1;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.is_empty() => in_quotes = true,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                fields.push(std::mem::take(&mut field));
+                if fields.iter().any(|f| !f.trim().is_empty()) {
+                    records.push((record_start, std::mem::take(&mut fields)));
+                } else {
+                    fields.clear();
+                }
+                line += 1;
+                record_start = line;
+            }
+            '\n' => {
+                field.push(c);
+                line += 1;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    fields.push(field);
+    if fields.iter().any(|f| !f.trim().is_empty()) {
+        records.push((record_start, fields));
+    }
+
+    records
+}
+
+/// CSVのヘッダー名から列番号を引くためのインデックス
+pub struct CsvHeaderIndex {
+    columns: HashMap<String, usize>,
+}
+
+impl CsvHeaderIndex {
+    /// ヘッダー行からインデックスを作成する（列名の大文字・小文字と前後の空白は区別しない）
+    pub fn new(header: &[String]) -> Self {
+        let columns = header
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.trim().to_lowercase(), i))
+            .collect();
+        Self { columns }
+    }
+
+    /// 候補名のいずれかに一致する列番号を返す
+    pub fn find(&self, aliases: &[&str]) -> Option<usize> {
+        aliases
+            .iter()
+            .find_map(|alias| self.columns.get(&alias.to_lowercase()).copied())
+    }
+
+    /// 候補名のいずれかに一致する列番号を返し、見つからない場合はバリデーションエラーを返す
+    pub fn require(&self, aliases: &[&str]) -> AppResult<usize> {
+        self.find(aliases).ok_or_else(|| {
+            AppError::validation(format!("CSVに必要な列がありません: {}", aliases[0]))
+        })
+    }
+}
+
+/// CSVの出所情報を読み取り、検証する
+///
+/// 出所情報のないCSV（他サービスのエクスポートなど）の場合はNoneを返します。
+///
+/// # 引数
+/// * `content` - CSVファイルの内容
+///
+/// # 戻り値
+/// 検証済みの本文、出所情報がない場合はNone、検証失敗時はエラー
+pub fn read_csv_export(content: &str) -> AppResult<Option<VerifiedImport<&str>>> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+
+    let mut offset = 0;
+    let mut header_lines = Vec::new();
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_end_matches(['\r', '\n']);
+        let Some(comment) = trimmed.strip_prefix(CSV_COMMENT_PREFIX) else {
+            break;
+        };
+        header_lines.push(comment);
+        offset += line.len();
+    }
+
+    if header_lines.first() != Some(&EXPORT_FORMAT_MARKER) {
+        return Ok(None);
+    }
+
+    let field = |key: &str| -> Option<String> {
+        header_lines.iter().find_map(|line| {
+            line.strip_prefix(key)
+                .and_then(|rest| rest.strip_prefix(": "))
+                .map(str::to_string)
+        })
+    };
+    let required = |key: &str| -> AppResult<String> {
+        field(key)
+            .ok_or_else(|| AppError::validation(format!("エクスポートヘッダーに{key}がありません")))
+    };
+
+    // 末尾のチェックサム行を探す
+    let rest = &content[offset..];
+    let checksum_line_prefix = format!("{CSV_COMMENT_PREFIX}{CSV_CHECKSUM_KEY}: ");
+    let body_end = rest
+        .trim_end_matches(['\r', '\n'])
+        .rfind(&checksum_line_prefix)
+        .filter(|&pos| pos == 0 || rest.as_bytes()[pos - 1] == b'\n')
+        .ok_or_else(|| AppError::validation("エクスポートのチェックサムがありません"))?;
+    let body = &rest[..body_end];
+    let expected = rest[body_end + checksum_line_prefix.len()..].trim();
+
+    if sha256_hex(body.as_bytes()) != expected {
+        return Err(AppError::validation(
+            "エクスポートのチェックサムが一致しません。ファイルが破損または改変されています",
+        ));
+    }
+
+    let meta = ExportMeta {
+        app_version: required("app_version")?,
+        schema_version: required("schema_version")?,
+        environment: required("environment")?,
+        exported_at: required("exported_at")?,
+        user_id: field("user_id").filter(|id| !id.is_empty()),
+        checksum: expected.to_string(),
+    };
+    let warnings = meta.verify_compatibility()?;
+
+    Ok(Some(VerifiedImport {
+        meta,
+        body,
+        warnings,
+    }))
+}
+
+/// 出所情報付きのJSONを作成する
+///
+/// # 引数
+/// * `meta` - 出所情報（チェックサムは自動で設定されます）
+/// * `data` - 本文
+///
+/// # 戻り値
+/// `{"meta": {...}, "data": ...}` 形式のJSON
+pub fn wrap_json_export<T: Serialize>(meta: &ExportMeta, data: &T) -> AppResult<serde_json::Value> {
+    let data = serde_json::to_value(data)?;
+    let mut meta = meta.clone();
+    meta.checksum = sha256_hex(serde_json::to_string(&data)?.as_bytes());
+
+    Ok(serde_json::json!({
+        "meta": meta,
+        "data": data,
+    }))
+}
+
+/// JSONの出所情報を読み取り、検証する
+///
+/// # 引数
+/// * `value` - エクスポートされたJSON
+///
+/// # 戻り値
+/// 検証済みの本文、または検証失敗時はエラー
+pub fn read_json_export(
+    mut value: serde_json::Value,
+) -> AppResult<VerifiedImport<serde_json::Value>> {
+    let meta: ExportMeta = value
+        .get_mut("meta")
+        .map(serde_json::Value::take)
+        .ok_or_else(|| AppError::validation("エクスポートにmetaがありません"))
+        .and_then(|meta| Ok(serde_json::from_value(meta)?))?;
+    let data = value
+        .get_mut("data")
+        .map(serde_json::Value::take)
+        .ok_or_else(|| AppError::validation("エクスポートにdataがありません"))?;
+
+    if sha256_hex(serde_json::to_string(&data)?.as_bytes()) != meta.checksum {
+        return Err(AppError::validation(
+            "エクスポートのチェックサムが一致しません。ファイルが破損または改変されています",
+        ));
+    }
+
+    let warnings = meta.verify_compatibility()?;
+    Ok(VerifiedImport {
+        meta,
+        body: data,
+        warnings,
+    })
+}
+
+/// ZIPアーカイブ内のファイル
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// アーカイブ内のパス
+    pub path: String,
+    /// ファイル内容のSHA-256チェックサム
+    pub sha256: String,
+}
+
+/// ZIPアーカイブのマニフェスト（`manifest.json`）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportManifest {
+    /// 出所情報（チェックサムはファイル一覧のチェックサム）
+    pub meta: ExportMeta,
+    /// 含まれるファイル
+    pub files: Vec<ManifestEntry>,
+}
+
+impl ExportManifest {
+    /// アーカイブに含めるファイルからマニフェストを作成する
+    ///
+    /// # 引数
+    /// * `meta` - 出所情報
+    /// * `files` - (パス, 内容) の一覧
+    pub fn build(meta: &ExportMeta, files: &[(&str, &[u8])]) -> Self {
+        let files: Vec<ManifestEntry> = files
+            .iter()
+            .map(|(path, content)| ManifestEntry {
+                path: path.to_string(),
+                sha256: sha256_hex(content),
+            })
+            .collect();
+        let mut meta = meta.clone();
+        meta.checksum = Self::files_checksum(&files);
+        Self { meta, files }
+    }
+
+    fn files_checksum(files: &[ManifestEntry]) -> String {
+        let listing: String = files
+            .iter()
+            .map(|entry| format!("{}  {}\n", entry.sha256, entry.path))
+            .collect();
+        sha256_hex(listing.as_bytes())
+    }
+
+    /// アーカイブから取り出したファイルを検証する
+    ///
+    /// # 引数
+    /// * `files` - (パス, 内容) の一覧
+    ///
+    /// # 戻り値
+    /// 警告メッセージ、または検証失敗時はエラー
+    pub fn verify(&self, files: &[(&str, &[u8])]) -> AppResult<Vec<String>> {
+        if Self::files_checksum(&self.files) != self.meta.checksum {
+            return Err(AppError::validation(
+                "マニフェストのチェックサムが一致しません",
+            ));
+        }
+
+        for entry in &self.files {
+            let content = files
+                .iter()
+                .find(|(path, _)| *path == entry.path)
+                .map(|(_, content)| *content)
+                .ok_or_else(|| {
+                    AppError::validation(format!("アーカイブに{}が含まれていません", entry.path))
+                })?;
+            if sha256_hex(content) != entry.sha256 {
+                return Err(AppError::validation(format!(
+                    "{}のチェックサムが一致しません",
+                    entry.path
+                )));
+            }
+        }
+
+        self.meta.verify_compatibility()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_meta() -> ExportMeta {
+        ExportMeta {
+            app_version: "0.1.0".to_string(),
+            schema_version: CURRENT_SCHEMA_VERSION.to_string(),
+            environment: "development".to_string(),
+            exported_at: "2024-01-01T09:00:00+09:00".to_string(),
+            user_id: Some("user_abc".to_string()),
+            checksum: String::new(),
+        }
+    }
+
+    fn write_csv(meta: &ExportMeta, body: &str) -> String {
+        let mut output = Vec::new();
+        write_csv_export(&mut output, meta, |w| {
+            w.write_all(body.as_bytes())?;
+            Ok(())
+        })
+        .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_schema_compatibility_table() {
+        assert_eq!(
+            check_schema_compatibility(CURRENT_SCHEMA_VERSION),
+            CompatibilityLevel::Compatible
+        );
+        assert_eq!(
+            check_schema_compatibility("3.0.0"),
+            CompatibilityLevel::Warn
+        );
+        assert_eq!(
+            check_schema_compatibility("2.1.0"),
+            CompatibilityLevel::Refuse
+        );
+        // 将来のスキーマも拒否する
+        assert_eq!(
+            check_schema_compatibility("4.0.0"),
+            CompatibilityLevel::Refuse
+        );
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let body = "name,amount\nNetflix,1490\n\"a,b\",1\n";
+        let csv = write_csv(&test_meta(), body);
+        assert!(csv.starts_with("# orano-keihi-export\n"));
+
+        let imported = read_csv_export(&csv).unwrap().unwrap();
+        assert_eq!(imported.body, body);
+        assert_eq!(imported.meta.user_id.as_deref(), Some("user_abc"));
+        assert_eq!(imported.meta.schema_version, CURRENT_SCHEMA_VERSION);
+        assert!(imported.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_csv_without_provenance_is_passed_through() {
+        assert!(read_csv_export("name,amount\nNetflix,1490\n")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_csv_tampered_body_is_rejected() {
+        let csv = write_csv(&test_meta(), "name,amount\nNetflix,1490\n");
+        let tampered = csv.replace("1490", "9999");
+        assert!(read_csv_export(&tampered).is_err());
+
+        let truncated: String = csv.lines().take(7).map(|l| format!("{l}\n")).collect();
+        assert!(read_csv_export(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_csv_schema_mismatch() {
+        let mut meta = test_meta();
+        meta.schema_version = "3.0.0".to_string();
+        let csv = write_csv(&meta, "a\n1\n");
+        let imported = read_csv_export(&csv).unwrap().unwrap();
+        assert_eq!(imported.warnings.len(), 1);
+
+        meta.schema_version = "2.0.0".to_string();
+        assert!(read_csv_export(&write_csv(&meta, "a\n1\n")).is_err());
+    }
+
+    #[test]
+    fn test_parse_csv_records_with_quotes() {
+        let records =
+            parse_csv_records("\u{feff}a,b\n\"x, y\",\"say \"\"hi\"\"\"\n\n\"multi\nline\",z\n");
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1].1, vec!["x, y", "say \"hi\""]);
+        assert_eq!(records[2].0, 4);
+        assert_eq!(records[2].1, vec!["multi\nline", "z"]);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let data = serde_json::json!({"expenses": [{"id": 1, "amount": 1000.0}]});
+        let exported = wrap_json_export(&test_meta(), &data).unwrap();
+        assert!(!exported["meta"]["checksum"].as_str().unwrap().is_empty());
+
+        // ファイルへの保存と読み込みを経由する
+        let text = serde_json::to_string_pretty(&exported).unwrap();
+        let imported = read_json_export(serde_json::from_str(&text).unwrap()).unwrap();
+        assert_eq!(imported.body, data);
+
+        let mut tampered = exported.clone();
+        tampered["data"]["expenses"][0]["amount"] = serde_json::json!(1.0);
+        assert!(read_json_export(tampered).is_err());
+
+        let mut old = exported;
+        old["meta"]["schema_version"] = serde_json::json!("1.0.0");
+        assert!(read_json_export(old).is_err());
+    }
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let files: Vec<(&str, &[u8])> = vec![
+            ("expenses.csv", b"id,amount\n1,1000\n"),
+            ("database.db", b"SQLite format 3\0"),
+        ];
+        let manifest = ExportManifest::build(&test_meta(), &files);
+
+        let text = serde_json::to_string(&manifest).unwrap();
+        let restored: ExportManifest = serde_json::from_str(&text).unwrap();
+        assert!(restored.verify(&files).unwrap().is_empty());
+
+        let modified: Vec<(&str, &[u8])> = vec![("expenses.csv", b"id,amount\n1,1\n"), files[1]];
+        assert!(restored.verify(&modified).is_err());
+        assert!(restored.verify(&files[..1]).is_err());
+    }
+}
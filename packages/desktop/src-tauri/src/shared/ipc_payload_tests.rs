@@ -0,0 +1,215 @@
+//! コマンドの戻り値・イベントの形式のスナップショットテスト
+//!
+//! フロントエンドに渡す主な型を固定値でシリアライズし、
+//! `tests/fixtures/ipc_payloads.json` と比較します。
+//! フィールド名はsnake_case、日時はJST（`+09:00`）のRFC3339形式に統一しているため、
+//! あわせてその規約も検証します。
+//!
+//! 意図してフィールドを変更したときは、次のコマンドでスナップショットを更新し、
+//! `docs/IPC_PAYLOAD_COMPATIBILITY.md` に変更内容を追記してください。
+//!
+//! ```sh
+//! UPDATE_IPC_SNAPSHOT=1 cargo test ipc_payload_tests
+//! ```
+
+#[cfg(test)]
+mod tests {
+    use crate::features::auth::models::{Session, User};
+    use crate::features::expenses::models::Expense;
+    use crate::features::migrations::service::MigrationResult;
+    use crate::features::receipts::models::{
+        CacheStats, NormalizedRect, PerformanceStats, ReceiptAnnotation,
+    };
+    use crate::features::subscriptions::models::Subscription;
+    use crate::features::updater::service::{PendingInstallBlockedEvent, UpdateInfo};
+    use crate::shared::mutation::DeleteResult;
+    use crate::shared::operations::{ActiveOperation, OperationKind};
+    use chrono::{TimeZone, Utc};
+    use serde_json::{json, Value};
+    use std::path::{Path, PathBuf};
+
+    const CREATED_AT: &str = "2025-04-01T10:00:00+09:00";
+    const UPDATED_AT: &str = "2025-04-02T18:30:00+09:00";
+
+    /// スナップショットファイルのパスを取得する
+    fn snapshot_path() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/ipc_payloads.json")
+    }
+
+    /// 各型を固定値でシリアライズする
+    fn payloads() -> Value {
+        let created_at = Utc.with_ymd_and_hms(2025, 4, 1, 1, 0, 0).unwrap();
+        let updated_at = Utc.with_ymd_and_hms(2025, 4, 2, 9, 30, 0).unwrap();
+
+        json!({
+            "Expense": Expense {
+                id: 1,
+                date: "2025-04-01".to_string(),
+                amount: 1100.0,
+                category: "交通費".to_string(),
+                category_id: Some(2),
+                description: Some("タクシー".to_string()),
+                receipt_url: Some("https://receipts.example.com/users/u1/receipts/1.jpg".to_string()),
+                created_at: CREATED_AT.to_string(),
+                updated_at: UPDATED_AT.to_string(),
+                category_color: Some("#3B82F6".to_string()),
+                category_icon: Some("🚃".to_string()),
+                unknown_category: false,
+                tax_rate: Some(0.1),
+                tax_amount: Some(100.0),
+                latitude: None,
+                longitude: None,
+                location_opt_in: false,
+                merchant_id: None,
+                missing_receipt: false,
+            },
+            "Subscription": Subscription {
+                id: 1,
+                name: "クラウドストレージ".to_string(),
+                amount: 1300.0,
+                billing_cycle: "monthly".to_string(),
+                start_date: "2025-04-01".to_string(),
+                category: "通信費".to_string(),
+                category_id: Some(3),
+                is_active: true,
+                receipt_path: None,
+                created_at: CREATED_AT.to_string(),
+                updated_at: UPDATED_AT.to_string(),
+            },
+            "DeleteResult": DeleteResult {
+                id: 1,
+                revision: UPDATED_AT.to_string(),
+            },
+            "ReceiptAnnotation": ReceiptAnnotation {
+                id: 1,
+                expense_id: 1,
+                receipt_url: "https://receipts.example.com/users/u1/receipts/1.jpg".to_string(),
+                page_number: 1,
+                rect: NormalizedRect {
+                    x: 0.25,
+                    y: 0.5,
+                    width: 0.5,
+                    height: 0.125,
+                },
+                note: Some("合計金額".to_string()),
+                created_at: CREATED_AT.to_string(),
+            },
+            "CacheStats": CacheStats {
+                total_files: 10,
+                total_size_bytes: 1024,
+                max_size_bytes: 4096,
+                cache_hit_rate: 0.75,
+            },
+            "PerformanceStats": PerformanceStats {
+                latency_ms: 120,
+                throughput_bps: 0,
+                connection_status: "connected".to_string(),
+                last_measured: UPDATED_AT.to_string(),
+            },
+            "MigrationResult": MigrationResult {
+                success: true,
+                message: "マイグレーションが完了しました".to_string(),
+                backup_path: Some("database_backup_20250401.db".to_string()),
+            },
+            "UpdateInfo": UpdateInfo {
+                available: true,
+                current_version: "1.0.0".to_string(),
+                latest_version: Some("1.1.0".to_string()),
+                release_notes: Some("不具合の修正".to_string()),
+                content_length: Some(2048),
+                last_checked: 1_743_469_200,
+                download_url: None,
+                signature: None,
+            },
+            "PendingInstallBlockedEvent": PendingInstallBlockedEvent {
+                operations: vec![ActiveOperation {
+                    id: 1,
+                    kind: OperationKind::ReceiptUpload,
+                    description: "expense_id=1".to_string(),
+                    started_at: CREATED_AT.to_string(),
+                }],
+                scheduled_on_next_quit: false,
+            },
+            "User": User {
+                id: "u1".to_string(),
+                google_id: "google-1".to_string(),
+                email: "user@example.com".to_string(),
+                name: "経費 太郎".to_string(),
+                picture_url: None,
+                created_at,
+                updated_at,
+            },
+            "Session": Session {
+                id: "s1".to_string(),
+                user_id: "u1".to_string(),
+                expires_at: updated_at,
+                created_at,
+            },
+        })
+    }
+
+    fn is_snake_case(key: &str) -> bool {
+        key.chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    }
+
+    /// フィールド名と日時形式の規約違反を収集する
+    fn collect_violations(value: &Value, path: &str, violations: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    if !is_snake_case(key) {
+                        violations.push(format!(
+                            "{path}.{key}: フィールド名がsnake_caseではありません"
+                        ));
+                    }
+                    collect_violations(child, &format!("{path}.{key}"), violations);
+                }
+            }
+            Value::Array(items) => {
+                for (index, child) in items.iter().enumerate() {
+                    collect_violations(child, &format!("{path}[{index}]"), violations);
+                }
+            }
+            Value::String(text)
+                if chrono::DateTime::parse_from_rfc3339(text).is_ok()
+                    && !text.ends_with("+09:00") =>
+            {
+                violations.push(format!("{path}: 日時がJSTではありません: {text}"));
+            }
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_payloads_follow_naming_and_datetime_conventions() {
+        let mut violations = Vec::new();
+        for (name, payload) in payloads().as_object().unwrap() {
+            collect_violations(payload, name, &mut violations);
+        }
+        assert!(violations.is_empty(), "{violations:#?}");
+    }
+
+    #[test]
+    fn test_payloads_match_snapshot() {
+        let actual = payloads();
+        let path = snapshot_path();
+
+        if std::env::var_os("UPDATE_IPC_SNAPSHOT").is_some() {
+            let text = serde_json::to_string_pretty(&actual).unwrap();
+            std::fs::write(&path, format!("{text}\n")).expect("スナップショットの書き込みに失敗");
+            return;
+        }
+
+        let text = std::fs::read_to_string(&path).expect("スナップショットの読み込みに失敗");
+        let expected: Value = serde_json::from_str(&text).unwrap();
+        for (name, payload) in actual.as_object().unwrap() {
+            assert_eq!(
+                Some(payload),
+                expected.get(name),
+                "{name}の形式がスナップショットと異なります（意図した変更であればスナップショットを更新してください）"
+            );
+        }
+        assert_eq!(actual, expected);
+    }
+}
@@ -13,6 +13,27 @@ pub mod utils;
 /// 汎用APIクライアント
 pub mod api_client;
 
+/// APIサーバーのレート制限への対応
+pub mod rate_limit;
+
+/// 共有エクスポート出所情報
+pub mod export;
+
+/// 変更系コマンドの戻り値
+pub mod mutation;
+
+/// 実行中の重要な処理の登録
+pub mod operations;
+
+/// 利用者向け機能（ケイパビリティ）の登録
+pub mod capabilities;
+
+/// フロントエンドに通知するイベントの登録
+pub mod events;
+
+/// コマンドの戻り値・イベントの形式のスナップショットテスト
+mod ipc_payload_tests;
+
 // 便利な再エクスポート
 pub use api_client::{ApiClient, ApiClientConfig, ErrorDetail, ErrorResponse};
 pub use config::{
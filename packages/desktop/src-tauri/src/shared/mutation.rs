@@ -0,0 +1,59 @@
+/// 変更系コマンドの戻り値
+///
+/// 作成・更新コマンドは保存後のエンティティ全体を、一覧・詳細取得と同じ型で返します。
+/// 削除コマンドは削除したIDとデータのリビジョンを返します。
+/// フロントエンドは戻り値をそのままストアに反映でき、変更のたびに一覧を再取得する必要がありません。
+use serde::{Deserialize, Serialize};
+
+/// 削除コマンドの戻り値
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeleteResult {
+    /// 削除したエンティティのID
+    pub id: i64,
+    /// 削除後のデータのリビジョン（APIサーバーが削除を確定した時刻、RFC3339形式）
+    pub revision: String,
+}
+
+/// API Serverからの削除レスポンス
+///
+/// 経費は`expenseId`、サブスクリプションは`subscriptionId`で削除したIDを返します。
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteResponse {
+    pub success: bool,
+    #[serde(alias = "expenseId", alias = "subscriptionId")]
+    pub id: i64,
+    pub timestamp: String,
+}
+
+impl From<DeleteResponse> for DeleteResult {
+    fn from(response: DeleteResponse) -> Self {
+        Self {
+            id: response.id,
+            revision: response.timestamp,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delete_response_into_result() {
+        for body in [
+            r#"{"success":true,"message":"経費が正常に削除されました","expenseId":12,"timestamp":"2025-04-01T01:00:00.000Z"}"#,
+            r#"{"success":true,"message":"サブスクリプションが正常に削除されました","subscriptionId":12,"timestamp":"2025-04-01T01:00:00.000Z"}"#,
+        ] {
+            let response: DeleteResponse = serde_json::from_str(body).unwrap();
+            assert!(response.success);
+            assert_eq!(
+                DeleteResult::from(response),
+                DeleteResult {
+                    id: 12,
+                    revision: "2025-04-01T01:00:00.000Z".to_string(),
+                }
+            );
+        }
+    }
+}
@@ -0,0 +1,199 @@
+/// 実行中の重要な処理の登録
+///
+/// マイグレーションやデータベースの一括更新、領収書のアップロードなど、
+/// 途中でアプリが終了するとデータが失われる処理を登録します。
+/// アップデーターはインストール前にここを参照し、処理が終わるまで再起動を待ちます。
+/// 処理の登録はガードで管理し、ガードを破棄すると登録が解除されます。
+use crate::shared::utils::get_current_jst_timestamp;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// 重要な処理の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKind {
+    /// データベースのマイグレーション
+    Migration,
+    /// データベースの一括更新（領収書URLの付け替えなど）
+    DatabaseUpdate,
+    /// 領収書のアップロード
+    ReceiptUpload,
+}
+
+/// 実行中の処理
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActiveOperation {
+    pub id: u64,
+    pub kind: OperationKind,
+    pub description: String,
+    pub started_at: String, // 開始日時（RFC3339形式、JST）
+}
+
+/// 実行中の処理の一覧
+#[derive(Debug, Default)]
+pub struct OperationRegistry {
+    next_id: AtomicU64,
+    active: Mutex<BTreeMap<u64, ActiveOperation>>,
+    idle: Notify,
+}
+
+impl OperationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 処理の開始を登録する
+    ///
+    /// # 戻り値
+    /// 破棄すると登録が解除されるガード
+    pub fn begin(
+        self: &Arc<Self>,
+        kind: OperationKind,
+        description: impl Into<String>,
+    ) -> OperationGuard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let operation = ActiveOperation {
+            id,
+            kind,
+            description: description.into(),
+            started_at: get_current_jst_timestamp(),
+        };
+        log::debug!("重要な処理を開始: {operation:?}");
+        self.lock().insert(id, operation);
+
+        OperationGuard {
+            registry: Arc::clone(self),
+            id,
+        }
+    }
+
+    /// 実行中の処理を開始順に取得する
+    pub fn active_operations(&self) -> Vec<ActiveOperation> {
+        self.lock().values().cloned().collect()
+    }
+
+    /// 実行中の処理がないかどうか
+    pub fn is_idle(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// 実行中の処理がなくなるまで待つ
+    ///
+    /// # 戻り値
+    /// 処理がなくなった場合はOk、待機時間を過ぎても残っている場合はその一覧
+    pub async fn wait_until_idle(&self, timeout: Duration) -> Result<(), Vec<ActiveOperation>> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            // 登録解除の通知を取りこぼさないよう、状態の確認より先に待機を登録する
+            let notified = self.idle.notified();
+            if self.is_idle() {
+                return Ok(());
+            }
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                return match self.active_operations() {
+                    operations if operations.is_empty() => Ok(()),
+                    operations => Err(operations),
+                };
+            }
+        }
+    }
+
+    fn finish(&self, id: u64) {
+        let mut active = self.lock();
+        active.remove(&id);
+        let idle = active.is_empty();
+        drop(active);
+
+        log::debug!("重要な処理を終了: id={id}");
+        if idle {
+            self.idle.notify_waiters();
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<u64, ActiveOperation>> {
+        // 登録の追加・削除は途中でパニックしないため、ロックが汚染されても内容は整合している
+        self.active.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 処理の登録を保持するガード
+#[derive(Debug)]
+pub struct OperationGuard {
+    registry: Arc<OperationRegistry>,
+    id: u64,
+}
+
+impl Drop for OperationGuard {
+    fn drop(&mut self) {
+        self.registry.finish(self.id);
+    }
+}
+
+/// アプリ全体で共有する処理の一覧を取得する
+pub fn shared_operations() -> Arc<OperationRegistry> {
+    static OPERATIONS: OnceLock<Arc<OperationRegistry>> = OnceLock::new();
+    Arc::clone(OPERATIONS.get_or_init(|| Arc::new(OperationRegistry::new())))
+}
+
+/// 重要な処理の開始を登録する（アプリ全体で共有する一覧に登録）
+pub fn begin_operation(kind: OperationKind, description: impl Into<String>) -> OperationGuard {
+    shared_operations().begin(kind, description)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guard_registers_and_releases_operation() {
+        let registry = Arc::new(OperationRegistry::new());
+        assert!(registry.is_idle());
+
+        let migration = registry.begin(OperationKind::Migration, "001_initial");
+        let upload = registry.begin(OperationKind::ReceiptUpload, "expense_id=1");
+
+        let active = registry.active_operations();
+        assert_eq!(active.len(), 2);
+        assert_eq!(active[0].kind, OperationKind::Migration);
+        assert_eq!(active[0].description, "001_initial");
+        assert_eq!(active[1].kind, OperationKind::ReceiptUpload);
+
+        drop(migration);
+        assert_eq!(
+            registry.active_operations()[0].kind,
+            OperationKind::ReceiptUpload
+        );
+        drop(upload);
+        assert!(registry.is_idle());
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_idle() {
+        let registry = Arc::new(OperationRegistry::new());
+        assert!(registry
+            .wait_until_idle(Duration::from_millis(10))
+            .await
+            .is_ok());
+
+        let guard = registry.begin(OperationKind::DatabaseUpdate, "rebase");
+        let pending = registry
+            .wait_until_idle(Duration::from_millis(20))
+            .await
+            .unwrap_err();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].kind, OperationKind::DatabaseUpdate);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            drop(guard);
+        });
+        assert!(registry
+            .wait_until_idle(Duration::from_secs(5))
+            .await
+            .is_ok());
+    }
+}
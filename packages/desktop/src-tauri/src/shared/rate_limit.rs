@@ -0,0 +1,201 @@
+/// APIサーバーのレート制限への対応
+///
+/// 429（Too Many Requests）と503（Service Unavailable）を受け取った場合に、
+/// `Retry-After`ヘッダーに従って再試行を遅らせます。
+/// 待機期間はアプリ全体で共有し、あるコマンドが制限を受けた場合は
+/// 他のコマンドのリクエストも同じ期間だけ送信を控えます。
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// `Retry-After`で指定された待機時間の上限
+pub const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// `Retry-After`がない場合の待機時間
+pub const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// レート制限による再試行の上限回数（通常の再試行回数とは別に数える）
+pub const MAX_RATE_LIMIT_RETRIES: u32 = 5;
+
+/// レート制限として扱うHTTPステータスかどうか
+pub fn is_rate_limit_status(status: u16) -> bool {
+    matches!(status, 429 | 503)
+}
+
+/// `Retry-After`ヘッダーの値を解析する
+///
+/// 秒数（例: `120`）とHTTP日付（例: `Wed, 21 Oct 2015 07:28:00 GMT`）の両方に対応します。
+///
+/// # 引数
+/// * `value` - ヘッダーの値
+/// * `now` - 現在時刻
+///
+/// # 戻り値
+/// 待機時間（解析できない場合はNone）
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    // 過去の日時は即時再試行可能とみなす
+    Some(
+        (date.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// `Retry-After`ヘッダーから実際に待機する時間を決める
+///
+/// # 引数
+/// * `header` - ヘッダーの値（存在しない場合はNone）
+///
+/// # 戻り値
+/// 上限で丸めた待機時間
+pub fn retry_after_delay(header: Option<&str>) -> Duration {
+    header
+        .and_then(|value| parse_retry_after(value, Utc::now()))
+        .unwrap_or(DEFAULT_RETRY_AFTER)
+        .min(MAX_RETRY_AFTER)
+}
+
+/// レート制限の状態（ヘルスチェックで返す）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitStatus {
+    /// 待機期間中かどうか
+    pub cooling_down: bool,
+    /// 待機期間の残り（ミリ秒）
+    pub remaining_ms: u64,
+    /// 最後に受け取ったレート制限のステータスコード
+    pub last_status_code: Option<u16>,
+    /// レート制限を受けた回数
+    pub rate_limited_count: u64,
+}
+
+#[derive(Debug, Default)]
+struct CooldownState {
+    until: Option<Instant>,
+    last_status_code: Option<u16>,
+    rate_limited_count: u64,
+}
+
+/// APIリクエストの待機期間
+#[derive(Debug, Default)]
+pub struct RateLimitCooldown {
+    state: Mutex<CooldownState>,
+}
+
+impl RateLimitCooldown {
+    /// 新しい待機期間の管理を作成する
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CooldownState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// レート制限を受けたことを記録し、待機期間を設定する
+    ///
+    /// 既に設定されている待機期間の方が長い場合はそちらを維持します。
+    ///
+    /// # 引数
+    /// * `delay` - 待機時間
+    /// * `status_code` - 受け取ったステータスコード
+    pub fn activate(&self, delay: Duration, status_code: u16) {
+        let mut state = self.lock();
+        let until = Instant::now() + delay;
+        state.until = Some(state.until.map_or(until, |current| current.max(until)));
+        state.last_status_code = Some(status_code);
+        state.rate_limited_count += 1;
+    }
+
+    /// 待機期間の残りを取得する
+    pub fn remaining(&self) -> Option<Duration> {
+        let until = self.lock().until?;
+        let remaining = until.saturating_duration_since(Instant::now());
+        (!remaining.is_zero()).then_some(remaining)
+    }
+
+    /// 待機期間が終わるまで待つ
+    pub async fn wait(&self) {
+        while let Some(remaining) = self.remaining() {
+            log::info!("APIサーバーのレート制限により送信を待機します: {remaining:?}");
+            tokio::time::sleep(remaining).await;
+        }
+    }
+
+    /// 現在の状態を取得する
+    pub fn status(&self) -> RateLimitStatus {
+        let remaining = self.remaining();
+        let state = self.lock();
+        RateLimitStatus {
+            cooling_down: remaining.is_some(),
+            remaining_ms: remaining.map_or(0, |d| d.as_millis() as u64),
+            last_status_code: state.last_status_code,
+            rate_limited_count: state.rate_limited_count,
+        }
+    }
+}
+
+/// アプリ全体で共有する待機期間
+pub fn shared_cooldown() -> Arc<RateLimitCooldown> {
+    static COOLDOWN: OnceLock<Arc<RateLimitCooldown>> = OnceLock::new();
+    Arc::clone(COOLDOWN.get_or_init(|| Arc::new(RateLimitCooldown::new())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 28, 0).unwrap();
+
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        // 過去の日時
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_retry_after_delay_is_capped() {
+        assert_eq!(retry_after_delay(Some("3600")), MAX_RETRY_AFTER);
+        assert_eq!(retry_after_delay(None), DEFAULT_RETRY_AFTER);
+        assert_eq!(retry_after_delay(Some("2")), Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_cooldown_keeps_longest_window() {
+        let cooldown = RateLimitCooldown::new();
+        assert_eq!(cooldown.status(), RateLimitStatus::default());
+
+        let started = Instant::now();
+        cooldown.activate(Duration::from_millis(300), 429);
+        cooldown.activate(Duration::from_millis(100), 503);
+        let status = cooldown.status();
+        assert!(status.cooling_down);
+        assert!(status.remaining_ms > 200 && status.remaining_ms <= 300);
+        assert_eq!(status.last_status_code, Some(503));
+        assert_eq!(status.rate_limited_count, 2);
+
+        cooldown.wait().await;
+        assert!(started.elapsed() >= Duration::from_millis(300));
+        assert!(!cooldown.status().cooling_down);
+    }
+}
@@ -0,0 +1,492 @@
+/// 円単位の金額計算
+///
+/// 消費税の計算・金額の分割・割合の計算を整数（円）で行います。
+/// 端数処理は1回の計算につき1回だけ行い、結果とともに端数処理で増減した額を返します。
+/// 税込金額から税額を求める場合、適格請求書の端数処理（税率ごとに1回、既定は切り捨て）に合わせます。
+use crate::shared::errors::{AppError, AppResult};
+use crate::shared::utils::validate_tax_rate;
+use serde::{Deserialize, Serialize};
+
+/// 端数処理の方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    /// 切り捨て（適格請求書で一般的な方法）
+    #[default]
+    Floor,
+    /// 四捨五入
+    HalfUp,
+    /// 切り上げ
+    Ceil,
+}
+
+/// 割合（1万分率で保持し、浮動小数点の誤差を計算に持ち込まない）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rate {
+    basis_points: i64,
+}
+
+impl Rate {
+    /// 標準税率（10%）
+    pub const STANDARD_TAX: Rate = Rate {
+        basis_points: 1_000,
+    };
+    /// 軽減税率（8%）
+    pub const REDUCED_TAX: Rate = Rate { basis_points: 800 };
+
+    /// 税率から作成する（10%の場合は0.1）
+    pub fn tax(rate: f64) -> AppResult<Self> {
+        validate_tax_rate(rate)?;
+        Ok(Self {
+            basis_points: (rate * 10_000.0).round() as i64,
+        })
+    }
+
+    /// 百分率から作成する（15%の場合は15.0、小数点以下2桁まで）
+    pub fn percent(percent: f64) -> AppResult<Self> {
+        let scaled = percent * 100.0;
+        if !percent.is_finite() || percent < 0.0 || (scaled - scaled.round()).abs() > 1e-6 {
+            return Err(AppError::validation(
+                "割合は0以上の数値（小数点以下2桁まで）で入力してください",
+            ));
+        }
+        Ok(Self {
+            basis_points: scaled.round() as i64,
+        })
+    }
+
+    /// 小数で表した割合（10%の場合は0.1）
+    pub fn as_f64(self) -> f64 {
+        self.basis_points as f64 / 10_000.0
+    }
+}
+
+/// 端数処理した値
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rounded {
+    /// 端数処理後の値（円）
+    pub value: i64,
+    /// 端数処理で増減した額（端数処理後の値 - 端数処理前の値）
+    pub rounding: f64,
+}
+
+/// 分子・分母で表した値を端数処理する
+fn round_ratio(numerator: i128, denominator: i128, mode: RoundingMode) -> Rounded {
+    let value = match mode {
+        RoundingMode::Floor => numerator.div_euclid(denominator),
+        RoundingMode::Ceil => -(-numerator).div_euclid(denominator),
+        RoundingMode::HalfUp => (2 * numerator + denominator).div_euclid(2 * denominator),
+    };
+    let remainder = value * denominator - numerator;
+    Rounded {
+        value: value as i64,
+        rounding: remainder as f64 / denominator as f64,
+    }
+}
+
+fn ensure_non_negative(amount: i64) -> AppResult<()> {
+    if amount < 0 {
+        return Err(AppError::validation("金額は0以上で入力してください"));
+    }
+    Ok(())
+}
+
+/// 消費税の内訳
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TaxBreakdown {
+    /// 税込金額
+    pub inclusive: i64,
+    /// 税抜金額
+    pub exclusive: i64,
+    /// 消費税額
+    pub tax: i64,
+    /// 税率（10%の場合は0.1）
+    pub tax_rate: f64,
+    /// 税額の端数処理の方法
+    pub rounding_mode: RoundingMode,
+    /// 税額の端数処理で増減した額
+    pub rounding: f64,
+}
+
+/// 税込金額から消費税額を求める
+///
+/// 税額 = 税込金額 × 税率 / (1 + 税率) を端数処理し、税抜金額は税込金額から税額を差し引いて求めます。
+/// そのため、税抜金額と税額の合計は常に税込金額と一致します。
+///
+/// # 引数
+/// * `inclusive` - 税込金額（円）
+/// * `rate` - 税率
+/// * `mode` - 税額の端数処理の方法
+pub fn extract_tax(inclusive: i64, rate: Rate, mode: RoundingMode) -> AppResult<TaxBreakdown> {
+    ensure_non_negative(inclusive)?;
+    let tax = round_ratio(
+        i128::from(inclusive) * i128::from(rate.basis_points),
+        10_000 + i128::from(rate.basis_points),
+        mode,
+    );
+    Ok(TaxBreakdown {
+        inclusive,
+        exclusive: inclusive - tax.value,
+        tax: tax.value,
+        tax_rate: rate.as_f64(),
+        rounding_mode: mode,
+        rounding: tax.rounding,
+    })
+}
+
+/// 税抜金額に消費税を加算する
+///
+/// # 引数
+/// * `exclusive` - 税抜金額（円）
+/// * `rate` - 税率
+/// * `mode` - 税額の端数処理の方法
+pub fn add_tax(exclusive: i64, rate: Rate, mode: RoundingMode) -> AppResult<TaxBreakdown> {
+    ensure_non_negative(exclusive)?;
+    let tax = round_ratio(
+        i128::from(exclusive) * i128::from(rate.basis_points),
+        10_000,
+        mode,
+    );
+    Ok(TaxBreakdown {
+        inclusive: exclusive + tax.value,
+        exclusive,
+        tax: tax.value,
+        tax_rate: rate.as_f64(),
+        rounding_mode: mode,
+        rounding: tax.rounding,
+    })
+}
+
+/// 金額の分割結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SplitResult {
+    /// 分割した金額（合計は元の金額と一致する）
+    pub parts: Vec<i64>,
+    /// 均等に分けられず、先頭から1円ずつ加算した額
+    pub remainder: i64,
+}
+
+/// 金額をN人で分割する
+///
+/// 均等に分けられない端数は、先頭から1円ずつ加算します。
+///
+/// # 引数
+/// * `total` - 分割する金額（円）
+/// * `parts` - 分割数
+pub fn split(total: i64, parts: u32) -> AppResult<SplitResult> {
+    ensure_non_negative(total)?;
+    if parts == 0 {
+        return Err(AppError::validation("分割数は1以上で入力してください"));
+    }
+
+    let count = i64::from(parts);
+    let base = total / count;
+    let remainder = total % count;
+    Ok(SplitResult {
+        parts: (0..count)
+            .map(|i| base + i64::from(i < remainder))
+            .collect(),
+        remainder,
+    })
+}
+
+/// 金額に対する割合の計算結果
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PercentageResult {
+    /// 元の金額
+    pub amount: i64,
+    /// 百分率（15%の場合は15.0）
+    pub percent: f64,
+    /// 割合に相当する額（チップなど）
+    pub portion: i64,
+    /// 元の金額と割合に相当する額の合計
+    pub total: i64,
+    /// 端数処理の方法
+    pub rounding_mode: RoundingMode,
+    /// 端数処理で増減した額
+    pub rounding: f64,
+}
+
+/// 金額に対する割合を求める（チップ・手数料など）
+///
+/// # 引数
+/// * `amount` - 金額（円）
+/// * `rate` - 割合
+/// * `mode` - 端数処理の方法
+pub fn percentage_of(amount: i64, rate: Rate, mode: RoundingMode) -> AppResult<PercentageResult> {
+    ensure_non_negative(amount)?;
+    let portion = round_ratio(
+        i128::from(amount) * i128::from(rate.basis_points),
+        10_000,
+        mode,
+    );
+    Ok(PercentageResult {
+        amount,
+        percent: rate.basis_points as f64 / 100.0,
+        portion: portion.value,
+        total: amount + portion.value,
+        rounding_mode: mode,
+        rounding: portion.rounding,
+    })
+}
+
+/// 金額を円単位の整数に変換する（端数がある場合はNone）
+pub fn whole_yen(amount: f64) -> Option<i64> {
+    (amount.is_finite() && amount.fract() == 0.0 && amount.abs() < 1e15).then_some(amount as i64)
+}
+
+/// 計算式（経費フォームのプレビューなどで使用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum CalcExpression {
+    /// 税込金額から消費税額を求める
+    ExtractTax {
+        amount: i64,
+        tax_rate: f64,
+        #[serde(default)]
+        rounding_mode: RoundingMode,
+    },
+    /// 税抜金額に消費税を加算する
+    AddTax {
+        amount: i64,
+        tax_rate: f64,
+        #[serde(default)]
+        rounding_mode: RoundingMode,
+    },
+    /// 金額をN人で分割する
+    Split { amount: i64, parts: u32 },
+    /// 金額に対する割合を求める
+    PercentageOf {
+        amount: i64,
+        percent: f64,
+        #[serde(default)]
+        rounding_mode: RoundingMode,
+    },
+}
+
+/// 計算結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CalcResult {
+    Tax(TaxBreakdown),
+    Split(SplitResult),
+    Percentage(PercentageResult),
+}
+
+/// 計算式を評価する
+pub fn calculate(expression: &CalcExpression) -> AppResult<CalcResult> {
+    match *expression {
+        CalcExpression::ExtractTax {
+            amount,
+            tax_rate,
+            rounding_mode,
+        } => extract_tax(amount, Rate::tax(tax_rate)?, rounding_mode).map(CalcResult::Tax),
+        CalcExpression::AddTax {
+            amount,
+            tax_rate,
+            rounding_mode,
+        } => add_tax(amount, Rate::tax(tax_rate)?, rounding_mode).map(CalcResult::Tax),
+        CalcExpression::Split { amount, parts } => split(amount, parts).map(CalcResult::Split),
+        CalcExpression::PercentageOf {
+            amount,
+            percent,
+            rounding_mode,
+        } => percentage_of(amount, Rate::percent(percent)?, rounding_mode)
+            .map(CalcResult::Percentage),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extracted(inclusive: i64, rate: Rate, mode: RoundingMode) -> (i64, i64) {
+        let breakdown = extract_tax(inclusive, rate, mode).unwrap();
+        assert_eq!(breakdown.exclusive + breakdown.tax, inclusive);
+        (breakdown.exclusive, breakdown.tax)
+    }
+
+    #[test]
+    fn test_extract_tax_invoice_examples() {
+        use RoundingMode::*;
+        let standard = Rate::STANDARD_TAX;
+        let reduced = Rate::REDUCED_TAX;
+
+        // 割り切れる金額
+        assert_eq!(extracted(13_200, standard, Floor), (12_000, 1_200));
+        assert_eq!(extracted(1_100, standard, Floor), (1_000, 100));
+        assert_eq!(extracted(1_080, reduced, Floor), (1_000, 80));
+        assert_eq!(extracted(108, reduced, Floor), (100, 8));
+        assert_eq!(extracted(0, standard, Floor), (0, 0));
+
+        // 1,000円（10%）: 税額90.90…円
+        assert_eq!(extracted(1_000, standard, Floor), (910, 90));
+        assert_eq!(extracted(1_000, standard, HalfUp), (909, 91));
+        assert_eq!(extracted(1_000, standard, Ceil), (909, 91));
+
+        // 5,500円（8%）: 税額407.40…円
+        assert_eq!(extracted(5_500, reduced, Floor), (5_093, 407));
+        assert_eq!(extracted(5_500, reduced, HalfUp), (5_093, 407));
+        assert_eq!(extracted(5_500, reduced, Ceil), (5_092, 408));
+
+        // 1,155円（10%）: 税額105円ちょうど
+        assert_eq!(extracted(1_155, standard, Ceil), (1_050, 105));
+
+        // 高額な金額でも誤差が出ない
+        assert_eq!(
+            extracted(9_999_999_999, standard, Floor),
+            (9_090_909_090, 909_090_909)
+        );
+    }
+
+    #[test]
+    fn test_extract_tax_reports_rounding() {
+        let breakdown = extract_tax(1_000, Rate::STANDARD_TAX, RoundingMode::Floor).unwrap();
+        // 90.9090…円を90円に切り捨て
+        assert!((breakdown.rounding + 10.0 / 11.0).abs() < 1e-9);
+        assert_eq!(breakdown.tax_rate, 0.1);
+
+        let exact = extract_tax(1_100, Rate::STANDARD_TAX, RoundingMode::Floor).unwrap();
+        assert_eq!(exact.rounding, 0.0);
+    }
+
+    #[test]
+    fn test_add_tax_invoice_examples() {
+        use RoundingMode::*;
+
+        let breakdown = add_tax(12_345, Rate::STANDARD_TAX, Floor).unwrap();
+        assert_eq!((breakdown.tax, breakdown.inclusive), (1_234, 13_579));
+        assert_eq!(breakdown.rounding, -0.5);
+        assert_eq!(
+            add_tax(12_345, Rate::STANDARD_TAX, HalfUp).unwrap().tax,
+            1_235
+        );
+        assert_eq!(
+            add_tax(12_345, Rate::STANDARD_TAX, Ceil).unwrap().tax,
+            1_235
+        );
+
+        // 1,999円（8%）: 税額159.92円
+        assert_eq!(add_tax(1_999, Rate::REDUCED_TAX, Floor).unwrap().tax, 159);
+        assert_eq!(add_tax(1_999, Rate::REDUCED_TAX, HalfUp).unwrap().tax, 160);
+        assert_eq!(
+            add_tax(12_000, Rate::STANDARD_TAX, Floor)
+                .unwrap()
+                .inclusive,
+            13_200
+        );
+    }
+
+    #[test]
+    fn test_tax_round_trip_reconciles() {
+        // 税抜金額に加算した税込金額から求めた税額は、端数を切り捨てれば元の税額以上にならない
+        for exclusive in 0..3_000 {
+            for rate in [Rate::STANDARD_TAX, Rate::REDUCED_TAX] {
+                let added = add_tax(exclusive, rate, RoundingMode::Floor).unwrap();
+                let extracted = extract_tax(added.inclusive, rate, RoundingMode::Floor).unwrap();
+                assert_eq!(extracted.exclusive + extracted.tax, added.inclusive);
+                assert!(extracted.tax <= added.tax + 1, "exclusive={exclusive}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_split_distributes_remainder() {
+        let result = split(10_000, 3).unwrap();
+        assert_eq!(result.parts, vec![3_334, 3_333, 3_333]);
+        assert_eq!(result.remainder, 1);
+
+        assert_eq!(split(100, 4).unwrap().parts, vec![25; 4]);
+        assert_eq!(split(2, 5).unwrap().parts, vec![1, 1, 0, 0, 0]);
+        assert_eq!(split(0, 2).unwrap().parts, vec![0, 0]);
+
+        for total in [1, 99, 1_001, 13_200] {
+            for parts in 1..=7 {
+                let result = split(total, parts).unwrap();
+                assert_eq!(result.parts.iter().sum::<i64>(), total);
+                assert!(
+                    result.parts.iter().max().unwrap() - result.parts.iter().min().unwrap() <= 1
+                );
+            }
+        }
+
+        assert!(split(100, 0).is_err());
+        assert!(split(-100, 2).is_err());
+    }
+
+    #[test]
+    fn test_percentage_of() {
+        let tip = percentage_of(3_280, Rate::percent(15.0).unwrap(), RoundingMode::Floor).unwrap();
+        assert_eq!((tip.portion, tip.total), (492, 3_772));
+        assert_eq!(tip.rounding, 0.0);
+
+        let rate = Rate::percent(12.5).unwrap();
+        let floor = percentage_of(1_001, rate, RoundingMode::Floor).unwrap();
+        assert_eq!(floor.portion, 125);
+        assert!((floor.rounding + 0.125).abs() < 1e-9);
+        assert_eq!(
+            percentage_of(1_004, rate, RoundingMode::HalfUp)
+                .unwrap()
+                .portion,
+            126
+        );
+        assert_eq!(
+            percentage_of(1_001, rate, RoundingMode::Ceil)
+                .unwrap()
+                .portion,
+            126
+        );
+
+        assert!(Rate::percent(-1.0).is_err());
+        assert!(Rate::percent(12.345).is_err());
+        assert!(Rate::percent(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_rate_validation() {
+        assert_eq!(Rate::tax(0.1).unwrap(), Rate::STANDARD_TAX);
+        assert_eq!(Rate::tax(0.08).unwrap(), Rate::REDUCED_TAX);
+        // 百分率で入力された値は受け付けない
+        assert!(Rate::tax(10.0).is_err());
+        assert!(extract_tax(-1, Rate::STANDARD_TAX, RoundingMode::Floor).is_err());
+    }
+
+    #[test]
+    fn test_calculate_expression() {
+        let expression: CalcExpression = serde_json::from_value(serde_json::json!({
+            "op": "extract_tax",
+            "amount": 13_200,
+            "tax_rate": 0.1
+        }))
+        .unwrap();
+        let result = serde_json::to_value(calculate(&expression).unwrap()).unwrap();
+        assert_eq!(result["kind"], "tax");
+        assert_eq!(result["exclusive"], 12_000);
+        assert_eq!(result["tax"], 1_200);
+        assert_eq!(result["rounding_mode"], "floor");
+
+        // 円未満の金額は受け付けない
+        assert!(serde_json::from_value::<CalcExpression>(serde_json::json!({
+            "op": "split",
+            "amount": 100.5,
+            "parts": 2
+        }))
+        .is_err());
+
+        assert!(matches!(
+            calculate(&CalcExpression::Split {
+                amount: 100,
+                parts: 3
+            })
+            .unwrap(),
+            CalcResult::Split(SplitResult { remainder: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_whole_yen() {
+        assert_eq!(whole_yen(1_100.0), Some(1_100));
+        assert_eq!(whole_yen(909.09), None);
+        assert_eq!(whole_yen(f64::NAN), None);
+    }
+}
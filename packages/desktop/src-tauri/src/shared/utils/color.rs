@@ -0,0 +1,452 @@
+/// カテゴリ色のコントラスト検証と配色提案
+///
+/// WCAG 2.xの相対輝度・コントラスト比を用いて、カテゴリバッジの色が
+/// ライト/ダーク両方の背景で読み取れるかを判定します。
+/// 基準を満たさない色には、HSL空間で明度を調整した近い色を提案します。
+use crate::shared::errors::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+
+/// ライトテーマのバッジ背景色
+pub const BADGE_BACKGROUND_LIGHT: &str = "#FFFFFF";
+
+/// ダークテーマのバッジ背景色
+pub const BADGE_BACKGROUND_DARK: &str = "#1F2937";
+
+/// バッジに必要な最小コントラスト比（WCAG 2.1 非テキスト・大きな文字の基準）
+pub const MIN_BADGE_CONTRAST: f64 = 3.0;
+
+/// 提案する代替色の数
+pub const SUGGESTION_COUNT: usize = 3;
+
+/// 配色提案で生成できる最大色数
+pub const MAX_PALETTE_SIZE: usize = 24;
+
+/// 明度の探索刻み
+const LIGHTNESS_STEP: f64 = 0.005;
+
+/// 提案同士で最低限離す明度の差
+const MIN_SUGGESTION_LIGHTNESS_GAP: f64 = 0.04;
+
+/// RGB色
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    /// `#RRGGBB`または`#RGB`形式の文字列を解析する
+    ///
+    /// # 引数
+    /// * `hex` - 色文字列
+    ///
+    /// # 戻り値
+    /// 解析したRGB色、形式が不正な場合はバリデーションエラー
+    pub fn parse_hex(hex: &str) -> AppResult<Self> {
+        let invalid = || {
+            AppError::validation(format!(
+                "色は#RRGGBB形式で指定してください（指定値: {hex}）"
+            ))
+        };
+
+        let digits = hex.trim().strip_prefix('#').ok_or_else(invalid)?;
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        let expanded = match digits.len() {
+            3 => digits.chars().flat_map(|c| [c, c]).collect::<String>(),
+            6 => digits.to_string(),
+            _ => return Err(invalid()),
+        };
+        let channel = |i: usize| u8::from_str_radix(&expanded[i..i + 2], 16).map_err(|_| invalid());
+
+        Ok(Self {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        })
+    }
+
+    /// `#RRGGBB`形式の文字列に変換する
+    pub fn to_hex(self) -> String {
+        format!("#{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+    }
+
+    /// WCAGの相対輝度を計算する（0.0〜1.0）
+    pub fn relative_luminance(self) -> f64 {
+        let linear = |channel: u8| {
+            let c = f64::from(channel) / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(self.r) + 0.7152 * linear(self.g) + 0.0722 * linear(self.b)
+    }
+
+    /// HSL色に変換する
+    pub fn to_hsl(self) -> Hsl {
+        let r = f64::from(self.r) / 255.0;
+        let g = f64::from(self.g) / 255.0;
+        let b = f64::from(self.b) / 255.0;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let lightness = (max + min) / 2.0;
+        let delta = max - min;
+
+        if delta == 0.0 {
+            return Hsl {
+                hue: 0.0,
+                saturation: 0.0,
+                lightness,
+            };
+        }
+
+        let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+        let hue = if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+
+        Hsl {
+            hue,
+            saturation,
+            lightness,
+        }
+    }
+}
+
+/// HSL色（色相: 0〜360、彩度・明度: 0.0〜1.0）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    pub hue: f64,
+    pub saturation: f64,
+    pub lightness: f64,
+}
+
+impl Hsl {
+    /// RGB色に変換する
+    pub fn to_rgb(self) -> Rgb {
+        let saturation = self.saturation.clamp(0.0, 1.0);
+        let lightness = self.lightness.clamp(0.0, 1.0);
+        let hue = self.hue.rem_euclid(360.0);
+
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let x = chroma * (1.0 - ((hue / 60.0).rem_euclid(2.0) - 1.0).abs());
+        let m = lightness - chroma / 2.0;
+        let (r, g, b) = match hue {
+            h if h < 60.0 => (chroma, x, 0.0),
+            h if h < 120.0 => (x, chroma, 0.0),
+            h if h < 180.0 => (0.0, chroma, x),
+            h if h < 240.0 => (0.0, x, chroma),
+            h if h < 300.0 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let channel = |value: f64| ((value + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+
+        Rgb {
+            r: channel(r),
+            g: channel(g),
+            b: channel(b),
+        }
+    }
+}
+
+/// 2色のコントラスト比を計算する（1.0〜21.0）
+pub fn contrast_ratio(a: Rgb, b: Rgb) -> f64 {
+    let (la, lb) = (a.relative_luminance(), b.relative_luminance());
+    let (lighter, darker) = if la >= lb { (la, lb) } else { (lb, la) };
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+/// バッジ色のコントラスト検証結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BadgeColorCheck {
+    /// 正規化した色（#RRGGBB形式）
+    pub color: String,
+    /// ライトテーマ背景とのコントラスト比
+    pub contrast_light: f64,
+    /// ダークテーマ背景とのコントラスト比
+    pub contrast_dark: f64,
+    /// 両方の背景で基準を満たすか
+    pub accessible: bool,
+    /// 基準を満たさない場合の代替色
+    pub suggestions: Vec<String>,
+}
+
+fn badge_backgrounds() -> (Rgb, Rgb) {
+    (
+        Rgb::parse_hex(BADGE_BACKGROUND_LIGHT).expect("ライト背景色の定義が不正です"),
+        Rgb::parse_hex(BADGE_BACKGROUND_DARK).expect("ダーク背景色の定義が不正です"),
+    )
+}
+
+/// ライト/ダーク両方の背景で基準を満たすか
+fn is_accessible_badge_color(color: Rgb) -> bool {
+    let (light, dark) = badge_backgrounds();
+    contrast_ratio(color, light) >= MIN_BADGE_CONTRAST
+        && contrast_ratio(color, dark) >= MIN_BADGE_CONTRAST
+}
+
+/// 色相・彩度を保ったまま、基準を満たす明度の一覧を取得する
+fn accessible_lightness_values(hue: f64, saturation: f64) -> Vec<(f64, Rgb)> {
+    let steps = (1.0 / LIGHTNESS_STEP).round() as usize;
+    (0..=steps)
+        .map(|i| i as f64 * LIGHTNESS_STEP)
+        .map(|lightness| {
+            let rgb = Hsl {
+                hue,
+                saturation,
+                lightness,
+            }
+            .to_rgb();
+            (lightness, rgb)
+        })
+        .filter(|(_, rgb)| is_accessible_badge_color(*rgb))
+        .collect()
+}
+
+/// 基準を満たす近い色を提案する
+///
+/// 元の色の色相を保ち、明度を調整した色を元の明度に近い順に選びます。
+/// 候補が足りない場合は彩度を下げた色も探索します。
+///
+/// # 引数
+/// * `color` - 元の色
+/// * `count` - 提案数
+pub fn suggest_accessible_alternatives(color: Rgb, count: usize) -> Vec<String> {
+    let original = color.to_hsl();
+    let mut suggestions: Vec<(f64, String)> = Vec::new();
+
+    for saturation_scale in [1.0, 0.75, 0.5, 0.0] {
+        let mut candidates =
+            accessible_lightness_values(original.hue, original.saturation * saturation_scale);
+        candidates.sort_by(|(a, _), (b, _)| {
+            (a - original.lightness)
+                .abs()
+                .total_cmp(&(b - original.lightness).abs())
+        });
+
+        for (lightness, rgb) in candidates {
+            if suggestions.len() >= count {
+                return suggestions.into_iter().map(|(_, hex)| hex).collect();
+            }
+            let hex = rgb.to_hex();
+            let far_enough = suggestions.iter().all(|(chosen, chosen_hex)| {
+                *chosen_hex != hex && (chosen - lightness).abs() >= MIN_SUGGESTION_LIGHTNESS_GAP
+            });
+            if far_enough {
+                suggestions.push((lightness, hex));
+            }
+        }
+    }
+
+    suggestions.into_iter().map(|(_, hex)| hex).collect()
+}
+
+/// バッジ色のコントラストを検証する
+///
+/// # 引数
+/// * `hex` - 色文字列（#RRGGBB形式）
+///
+/// # 戻り値
+/// 検証結果（基準を満たさない場合は代替色を含む）
+pub fn check_badge_color(hex: &str) -> AppResult<BadgeColorCheck> {
+    let color = Rgb::parse_hex(hex)?;
+    let (light, dark) = badge_backgrounds();
+    let contrast_light = contrast_ratio(color, light);
+    let contrast_dark = contrast_ratio(color, dark);
+    let accessible = contrast_light >= MIN_BADGE_CONTRAST && contrast_dark >= MIN_BADGE_CONTRAST;
+
+    Ok(BadgeColorCheck {
+        color: color.to_hex(),
+        contrast_light,
+        contrast_dark,
+        accessible,
+        suggestions: if accessible {
+            Vec::new()
+        } else {
+            suggest_accessible_alternatives(color, SUGGESTION_COUNT)
+        },
+    })
+}
+
+/// バッジ色が基準を満たすことを検証する
+///
+/// # 引数
+/// * `hex` - 色文字列（#RRGGBB形式）
+///
+/// # 戻り値
+/// 基準を満たす場合はOk(())、満たさない場合は代替色を含むバリデーションエラー
+pub fn validate_badge_color(hex: &str) -> AppResult<()> {
+    let check = check_badge_color(hex)?;
+    if check.accessible {
+        return Ok(());
+    }
+
+    Err(AppError::validation(format!(
+        "色{}は背景とのコントラストが不足しています（ライト: {:.2}、ダーク: {:.2}、必要: {MIN_BADGE_CONTRAST:.1}）。候補: {}",
+        check.color,
+        check.contrast_light,
+        check.contrast_dark,
+        check.suggestions.join(", ")
+    )))
+}
+
+/// 互いに区別しやすく、基準を満たす配色を提案する
+///
+/// 色相を等間隔に分け、それぞれ基準を満たす明度の範囲の中央を選びます。
+///
+/// # 引数
+/// * `count` - 色数（1〜MAX_PALETTE_SIZE）
+///
+/// # 戻り値
+/// #RRGGBB形式の色の一覧
+pub fn suggest_palette(count: usize) -> AppResult<Vec<String>> {
+    if count == 0 || count > MAX_PALETTE_SIZE {
+        return Err(AppError::validation(format!(
+            "色数は1〜{MAX_PALETTE_SIZE}の範囲で指定してください"
+        )));
+    }
+
+    // 色数が多い場合は彩度を交互に変えて隣接色を区別しやすくする
+    let saturations: &[f64] = if count > 12 { &[0.7, 0.45] } else { &[0.65] };
+
+    (0..count)
+        .map(|i| {
+            let hue = 360.0 * i as f64 / count as f64;
+            let saturation = saturations[i % saturations.len()];
+            let candidates = accessible_lightness_values(hue, saturation);
+            let (_, rgb) = candidates.get(candidates.len() / 2).ok_or_else(|| {
+                AppError::validation(format!("色相{hue:.0}で基準を満たす色が見つかりません"))
+            })?;
+            Ok(rgb.to_hex())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(hex: &str) -> Rgb {
+        Rgb::parse_hex(hex).unwrap()
+    }
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!(
+            rgb("#FF8000"),
+            Rgb {
+                r: 255,
+                g: 128,
+                b: 0
+            }
+        );
+        assert_eq!(
+            rgb("#f80"),
+            Rgb {
+                r: 255,
+                g: 136,
+                b: 0
+            }
+        );
+        assert_eq!(rgb("#ff8000").to_hex(), "#FF8000");
+        assert!(Rgb::parse_hex("FF8000").is_err());
+        assert!(Rgb::parse_hex("#FF80").is_err());
+        assert!(Rgb::parse_hex("#GG8000").is_err());
+    }
+
+    #[test]
+    fn test_contrast_ratio_matches_wcag_reference_values() {
+        let white = rgb("#FFFFFF");
+        assert!((contrast_ratio(rgb("#000000"), white) - 21.0).abs() < 1e-9);
+        assert!((contrast_ratio(white, white) - 1.0).abs() < 1e-9);
+        // WCAGで広く参照される値
+        assert!((contrast_ratio(rgb("#767676"), white) - 4.54).abs() < 0.01);
+        assert!((contrast_ratio(rgb("#777777"), white) - 4.48).abs() < 0.01);
+        assert!((contrast_ratio(rgb("#0000FF"), white) - 8.59).abs() < 0.01);
+        assert!((contrast_ratio(rgb("#FF0000"), white) - 4.0).abs() < 0.01);
+        // 順序に依存しない
+        assert_eq!(
+            contrast_ratio(rgb("#336699"), white),
+            contrast_ratio(white, rgb("#336699"))
+        );
+    }
+
+    #[test]
+    fn test_hsl_round_trip() {
+        for hex in [
+            "#FF6B6B", "#4ECDC4", "#336699", "#808080", "#000000", "#FFFFFF",
+        ] {
+            assert_eq!(rgb(hex).to_hsl().to_rgb(), rgb(hex), "{hex}");
+        }
+    }
+
+    #[test]
+    fn test_check_badge_color_suggests_alternatives() {
+        // 白背景で読めない薄いグレー
+        let check = check_badge_color("#D1D5DB").unwrap();
+        assert!(!check.accessible);
+        assert!(check.contrast_light < MIN_BADGE_CONTRAST);
+        assert_eq!(check.suggestions.len(), SUGGESTION_COUNT);
+        for suggestion in &check.suggestions {
+            assert!(
+                check_badge_color(suggestion).unwrap().accessible,
+                "{suggestion}"
+            );
+        }
+
+        // 色相を保った近い色が提案される
+        let check = check_badge_color("#FFE4E1").unwrap();
+        assert_eq!(check.suggestions.len(), SUGGESTION_COUNT);
+        let original_hue = rgb("#FFE4E1").to_hsl().hue;
+        let suggested_hue = rgb(&check.suggestions[0]).to_hsl().hue;
+        assert!((original_hue - suggested_hue).abs() < 10.0);
+
+        let accessible = check_badge_color("#808080").unwrap();
+        assert!(accessible.accessible);
+        assert!(accessible.suggestions.is_empty());
+        assert!(validate_badge_color("#808080").is_ok());
+
+        let error = validate_badge_color("#D1D5DB").unwrap_err();
+        assert!(matches!(error, AppError::Validation(_)));
+        assert!(error.user_message().contains("候補"));
+    }
+
+    #[test]
+    fn test_suggest_palette_is_accessible_and_distinct() {
+        for count in [1, 6, 12, MAX_PALETTE_SIZE] {
+            let palette = suggest_palette(count).unwrap();
+            assert_eq!(palette.len(), count);
+
+            let colors: Vec<Rgb> = palette.iter().map(|hex| rgb(hex)).collect();
+            for color in &colors {
+                assert!(is_accessible_badge_color(*color), "{}", color.to_hex());
+            }
+            for (i, a) in colors.iter().enumerate() {
+                for b in &colors[i + 1..] {
+                    let distance = ((f64::from(a.r) - f64::from(b.r)).powi(2)
+                        + (f64::from(a.g) - f64::from(b.g)).powi(2)
+                        + (f64::from(a.b) - f64::from(b.b)).powi(2))
+                    .sqrt();
+                    assert!(
+                        distance >= 20.0,
+                        "{} と {} が近すぎます",
+                        a.to_hex(),
+                        b.to_hex()
+                    );
+                }
+            }
+        }
+
+        assert!(suggest_palette(0).is_err());
+        assert!(suggest_palette(MAX_PALETTE_SIZE + 1).is_err());
+    }
+}
@@ -0,0 +1,369 @@
+/// 日付計算ユーティリティ
+///
+/// 「今日」は常にJST（日本標準時）で判定します。
+/// UTCで判定すると、日本時間の0時〜9時の間は前日扱いになってしまうためです。
+use crate::shared::errors::{AppError, AppResult};
+use chrono::{Datelike, Days, Months, NaiveDate, Utc};
+use chrono_tz::Asia::Tokyo;
+use serde::{Deserialize, Serialize};
+
+/// JSTでの今日の日付を取得する
+pub fn today_jst() -> NaiveDate {
+    Utc::now().with_timezone(&Tokyo).date_naive()
+}
+
+/// YYYY-MM-DD形式の日付を解析する
+///
+/// # 引数
+/// * `date_str` - 日付文字列
+///
+/// # 戻り値
+/// 日付、または形式が不正な場合はバリデーションエラー
+pub fn parse_ymd(date_str: &str) -> AppResult<NaiveDate> {
+    NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+        .map_err(|_| AppError::validation(format!("日付の形式が正しくありません: {date_str}")))
+}
+
+/// 2つの日付の差を日数で取得する
+///
+/// # 戻り値
+/// `to`が`from`より後の場合は正の値
+pub fn days_between(from: NaiveDate, to: NaiveDate) -> i64 {
+    (to - from).num_days()
+}
+
+/// 開始日から一定の月数ごとに繰り返す日付のうち、基準日以降で最初の日付を計算する
+///
+/// 各回の日付は開始日から直接月数を加算して求めるため、月末開始の場合も
+/// 短い月で月末日に丸められた後、元の日付に戻ります（1/31 → 2/29 → 3/31）。
+///
+/// # 引数
+/// * `start` - 開始日（初回）
+/// * `months_per_cycle` - 繰り返しの月数（1以上）
+/// * `reference` - 基準日
+///
+/// # 戻り値
+/// 基準日以降で最初の日付（開始日が基準日以降の場合は開始日）、計算できない場合はNone
+pub fn next_cycle_date(
+    start: NaiveDate,
+    months_per_cycle: u32,
+    reference: NaiveDate,
+) -> Option<NaiveDate> {
+    if months_per_cycle == 0 {
+        return None;
+    }
+    if start >= reference {
+        return Some(start);
+    }
+
+    // 基準月までの経過月数から、基準日の直前の回を求めて1回ずつ進める
+    let elapsed_months =
+        (reference.year() - start.year()) * 12 + reference.month() as i32 - start.month() as i32;
+    let mut cycles = u32::try_from(elapsed_months).ok()? / months_per_cycle;
+    loop {
+        let candidate = start.checked_add_months(Months::new(cycles * months_per_cycle))?;
+        if candidate >= reference {
+            return Some(candidate);
+        }
+        cycles += 1;
+    }
+}
+
+/// 集計期間の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PeriodType {
+    /// 週単位（月曜始まり）
+    Weekly,
+    /// 月単位（暦月）
+    #[default]
+    Monthly,
+    /// 年単位（会計年度）
+    Yearly,
+}
+
+/// 期間の開始日と終了日（両端を含む）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DateRange {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl DateRange {
+    /// 日付が期間内かどうかを判定する
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+    /// 期間の日数を取得する
+    pub fn days(&self) -> i64 {
+        days_between(self.start, self.end) + 1
+    }
+}
+
+/// 会計年度の開始月を検証する
+///
+/// # 引数
+/// * `month` - 開始月（1〜12）
+pub fn validate_fiscal_year_start_month(month: u32) -> AppResult<()> {
+    if (1..=12).contains(&month) {
+        Ok(())
+    } else {
+        Err(AppError::validation(
+            "会計年度の開始月は1〜12の範囲で指定してください",
+        ))
+    }
+}
+
+/// 日付を含む週（月曜〜日曜）を取得する
+pub fn week_range(date: NaiveDate) -> DateRange {
+    let start = date - Days::new(u64::from(date.weekday().num_days_from_monday()));
+    DateRange {
+        start,
+        end: start + Days::new(6),
+    }
+}
+
+/// 日付を含む暦月を取得する
+pub fn month_range(date: NaiveDate) -> DateRange {
+    let start = date.with_day(1).unwrap_or(date);
+    let end = start
+        .checked_add_months(Months::new(1))
+        .and_then(|next| next.pred_opt())
+        .unwrap_or(NaiveDate::MAX);
+    DateRange { start, end }
+}
+
+/// 日付を含む会計年度を取得する
+///
+/// 会計年度は開始月の1日から12か月間です。開始月が4の場合、
+/// 2025-03-31は2024-04-01〜2025-03-31の年度に含まれます。
+///
+/// # 引数
+/// * `date` - 対象日
+/// * `start_month` - 会計年度の開始月（1〜12、1の場合は暦年）
+pub fn fiscal_year_range(date: NaiveDate, start_month: u32) -> AppResult<DateRange> {
+    validate_fiscal_year_start_month(start_month)?;
+
+    let start_year = if date.month() >= start_month {
+        date.year()
+    } else {
+        date.year() - 1
+    };
+    let start = NaiveDate::from_ymd_opt(start_year, start_month, 1)
+        .ok_or_else(|| AppError::validation(format!("会計年度を計算できません: {date}")))?;
+    let end = start
+        .checked_add_months(Months::new(12))
+        .and_then(|next| next.pred_opt())
+        .ok_or_else(|| AppError::validation(format!("会計年度を計算できません: {date}")))?;
+    Ok(DateRange { start, end })
+}
+
+/// 日付を含む集計期間を取得する
+///
+/// # 引数
+/// * `period_type` - 期間の種類
+/// * `date` - 対象日
+/// * `fiscal_year_start_month` - 会計年度の開始月（年単位の場合のみ使用）
+pub fn period_range(
+    period_type: PeriodType,
+    date: NaiveDate,
+    fiscal_year_start_month: u32,
+) -> AppResult<DateRange> {
+    match period_type {
+        PeriodType::Weekly => Ok(week_range(date)),
+        PeriodType::Monthly => Ok(month_range(date)),
+        PeriodType::Yearly => fiscal_year_range(date, fiscal_year_start_month),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        parse_ymd(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_ymd_and_days_between() {
+        assert_eq!(
+            date("2024-02-29"),
+            NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+        );
+        assert!(parse_ymd("2023-02-29").is_err());
+        assert!(parse_ymd("2024/01/01").is_err());
+
+        assert_eq!(days_between(date("2024-02-28"), date("2024-03-01")), 2);
+        assert_eq!(days_between(date("2024-03-01"), date("2024-02-28")), -2);
+        assert_eq!(days_between(date("2024-03-01"), date("2024-03-01")), 0);
+    }
+
+    #[test]
+    fn test_next_cycle_date() {
+        let reference = date("2024-03-15");
+        assert_eq!(
+            next_cycle_date(date("2024-01-20"), 1, reference),
+            Some(date("2024-03-20"))
+        );
+        // 基準日当日を含む
+        assert_eq!(
+            next_cycle_date(date("2024-01-15"), 1, reference),
+            Some(date("2024-03-15"))
+        );
+        assert_eq!(
+            next_cycle_date(date("2024-01-10"), 1, reference),
+            Some(date("2024-04-10"))
+        );
+        assert_eq!(
+            next_cycle_date(date("2022-02-01"), 12, reference),
+            Some(date("2025-02-01"))
+        );
+        assert_eq!(
+            next_cycle_date(date("2024-04-01"), 1, reference),
+            Some(date("2024-04-01"))
+        );
+        assert_eq!(next_cycle_date(date("2024-01-01"), 0, reference), None);
+
+        // 月末開始は短い月で丸められ、次の月は元の日付に戻る
+        assert_eq!(
+            next_cycle_date(date("2024-01-31"), 1, date("2024-02-10")),
+            Some(date("2024-02-29"))
+        );
+        assert_eq!(
+            next_cycle_date(date("2024-01-31"), 1, date("2024-03-01")),
+            Some(date("2024-03-31"))
+        );
+        // うるう日開始の年額
+        assert_eq!(
+            next_cycle_date(date("2024-02-29"), 12, date("2024-03-01")),
+            Some(date("2025-02-28"))
+        );
+        assert_eq!(
+            next_cycle_date(date("2024-02-29"), 12, date("2028-02-01")),
+            Some(date("2028-02-29"))
+        );
+    }
+
+    fn range(start: &str, end: &str) -> DateRange {
+        DateRange {
+            start: date(start),
+            end: date(end),
+        }
+    }
+
+    #[test]
+    fn test_week_range() {
+        // 月曜・日曜はその週の端になる
+        assert_eq!(
+            week_range(date("2024-05-13")),
+            range("2024-05-13", "2024-05-19")
+        );
+        assert_eq!(
+            week_range(date("2024-05-19")),
+            range("2024-05-13", "2024-05-19")
+        );
+        // 月をまたぐ週
+        assert_eq!(
+            week_range(date("2024-05-01")),
+            range("2024-04-29", "2024-05-05")
+        );
+        // うるう日を含む週
+        assert_eq!(
+            week_range(date("2024-02-29")),
+            range("2024-02-26", "2024-03-03")
+        );
+        // 年をまたぐ週
+        let new_year = week_range(date("2025-01-01"));
+        assert_eq!(new_year, range("2024-12-30", "2025-01-05"));
+        assert_eq!(new_year.days(), 7);
+        assert!(new_year.contains(date("2024-12-31")));
+        assert!(!new_year.contains(date("2025-01-06")));
+    }
+
+    #[test]
+    fn test_month_range() {
+        assert_eq!(
+            month_range(date("2024-02-15")),
+            range("2024-02-01", "2024-02-29")
+        );
+        assert_eq!(
+            month_range(date("2023-02-01")),
+            range("2023-02-01", "2023-02-28")
+        );
+        assert_eq!(
+            month_range(date("2024-12-31")),
+            range("2024-12-01", "2024-12-31")
+        );
+        assert_eq!(month_range(date("2024-04-30")).days(), 30);
+    }
+
+    #[test]
+    fn test_fiscal_year_range() {
+        // 4月始まり: 3月までは前年度
+        assert_eq!(
+            fiscal_year_range(date("2025-03-31"), 4).unwrap(),
+            range("2024-04-01", "2025-03-31")
+        );
+        assert_eq!(
+            fiscal_year_range(date("2025-04-01"), 4).unwrap(),
+            range("2025-04-01", "2026-03-31")
+        );
+        // 年をまたぐ日付
+        assert_eq!(
+            fiscal_year_range(date("2024-12-31"), 4).unwrap(),
+            fiscal_year_range(date("2025-01-01"), 4).unwrap()
+        );
+        // うるう年の2月末を含む年度
+        assert_eq!(
+            fiscal_year_range(date("2024-02-29"), 3).unwrap(),
+            range("2023-03-01", "2024-02-29")
+        );
+        assert_eq!(
+            fiscal_year_range(date("2024-02-29"), 3).unwrap().days(),
+            366
+        );
+        // 1月始まりは暦年
+        assert_eq!(
+            fiscal_year_range(date("2024-07-01"), 1).unwrap(),
+            range("2024-01-01", "2024-12-31")
+        );
+        // 12月始まり
+        assert_eq!(
+            fiscal_year_range(date("2024-11-30"), 12).unwrap(),
+            range("2023-12-01", "2024-11-30")
+        );
+
+        assert!(fiscal_year_range(date("2024-07-01"), 0).is_err());
+        assert!(fiscal_year_range(date("2024-07-01"), 13).is_err());
+    }
+
+    #[test]
+    fn test_period_range() {
+        let day = date("2025-01-01");
+        assert_eq!(
+            period_range(PeriodType::Weekly, day, 4).unwrap(),
+            range("2024-12-30", "2025-01-05")
+        );
+        assert_eq!(
+            period_range(PeriodType::Monthly, day, 4).unwrap(),
+            range("2025-01-01", "2025-01-31")
+        );
+        assert_eq!(
+            period_range(PeriodType::Yearly, day, 4).unwrap(),
+            range("2024-04-01", "2025-03-31")
+        );
+        assert_eq!(PeriodType::default(), PeriodType::Monthly);
+        assert_eq!(
+            serde_json::to_string(&PeriodType::Weekly).unwrap(),
+            "\"weekly\""
+        );
+    }
+
+    #[test]
+    fn test_today_jst_is_within_a_day_of_utc() {
+        let utc_today = Utc::now().date_naive();
+        let diff = days_between(utc_today, today_jst());
+        assert!((0..=1).contains(&diff));
+    }
+}
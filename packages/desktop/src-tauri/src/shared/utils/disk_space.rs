@@ -0,0 +1,181 @@
+//! ディスクの空き容量チェック
+//!
+//! キャッシュ保存・バックアップ・エクスポートの前に書き込み先ボリュームの空き容量を確認し、
+//! 途中で書き込みに失敗して壊れたファイルが残ることを防ぎます。
+//! 必要量には書き込むデータのサイズに加えて、処理ごとの余裕分（ヘッドルーム）を含めます。
+
+use crate::shared::errors::{AppError, AppResult};
+use std::io;
+use std::path::Path;
+
+const MB: u64 = 1024 * 1024;
+
+/// キャッシュ保存時に残しておく空き容量（下回る場合はキャッシュせずに続行）
+pub const CACHE_WRITE_HEADROOM_BYTES: u64 = 200 * MB;
+
+/// バックアップ作成時に残しておく空き容量（下回る場合はバックアップを作成しない）
+pub const BACKUP_HEADROOM_BYTES: u64 = 100 * MB;
+
+/// エクスポート・レポート出力時に残しておく空き容量
+pub const EXPORT_HEADROOM_BYTES: u64 = 50 * MB;
+
+/// 空き容量が少ないと判断する閾値（アプリの状態表示用）
+pub const LOW_DISK_SPACE_THRESHOLD_BYTES: u64 = 500 * MB;
+
+/// 指定したパスがあるボリュームの空き容量（バイト）を取得する
+///
+/// パスが存在しない場合（これから作成する出力ファイルなど）は、存在する親ディレクトリで確認します。
+pub fn available_space(path: &Path) -> io::Result<u64> {
+    let existing = path
+        .ancestors()
+        .find(|ancestor| !ancestor.as_os_str().is_empty() && ancestor.exists())
+        .unwrap_or_else(|| Path::new("."));
+    platform::available_space(existing)
+}
+
+/// 書き込みに必要な空き容量があるか確認する
+///
+/// # 引数
+/// * `path` - 書き込み先のパス（ファイルまたはディレクトリ）
+/// * `required_bytes` - 必要な空き容量（書き込むサイズ + ヘッドルーム）
+///
+/// # 戻り値
+/// 空き容量（バイト）。不足している場合は必要量と空き容量を含むエラー
+pub fn check_disk_space(path: &Path, required_bytes: u64) -> AppResult<u64> {
+    check_disk_space_with(available_space, path, required_bytes)
+}
+
+/// 空き容量の取得方法を指定して確認する（テストで空き容量を差し替えるため）
+///
+/// 空き容量を取得できない環境では確認を省略し、書き込みを妨げません。
+pub fn check_disk_space_with<F>(probe: F, path: &Path, required_bytes: u64) -> AppResult<u64>
+where
+    F: Fn(&Path) -> io::Result<u64>,
+{
+    let available = match probe(path) {
+        Ok(available) => available,
+        Err(e) => {
+            log::warn!("空き容量を取得できないため確認を省略します: {path:?}: {e}");
+            return Ok(u64::MAX);
+        }
+    };
+
+    if available < required_bytes {
+        return Err(AppError::Io(io::Error::new(
+            io::ErrorKind::StorageFull,
+            format!(
+                "ディスクの空き容量が不足しています（必要: {}、空き: {}）",
+                format_megabytes(required_bytes),
+                format_megabytes(available)
+            ),
+        )));
+    }
+
+    Ok(available)
+}
+
+/// 空き容量不足のエラーかどうか
+pub fn is_insufficient_space(error: &AppError) -> bool {
+    matches!(error, AppError::Io(e) if e.kind() == io::ErrorKind::StorageFull)
+}
+
+fn format_megabytes(bytes: u64) -> String {
+    format!("{:.1}MB", bytes as f64 / MB as f64)
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::ffi::CString;
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    pub fn available_space(path: &Path) -> io::Result<u64> {
+        let c_path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        // SAFETY: c_pathはNUL終端された有効な文字列で、statは書き込み可能な領域
+        if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        #[allow(clippy::unnecessary_cast)]
+        Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::io;
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::Path;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    pub fn available_space(path: &Path) -> io::Result<u64> {
+        let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+        let mut available: u64 = 0;
+        // SAFETY: wideはNUL終端されたUTF-16文字列で、availableは書き込み可能な領域
+        let ok = unsafe {
+            GetDiskFreeSpaceExW(
+                wide.as_ptr(),
+                &mut available,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        if ok == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(available)
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use std::io;
+    use std::path::Path;
+
+    pub fn available_space(_path: &Path) -> io::Result<u64> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "この環境では空き容量を取得できません",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_disk_space_with_probe() {
+        let path = Path::new("/data");
+
+        // 空き容量が足りる場合は空き容量を返す
+        assert_eq!(
+            check_disk_space_with(|_| Ok(300 * MB), path, 200 * MB).unwrap(),
+            300 * MB
+        );
+
+        // 不足する場合は必要量と空き容量を含むエラー
+        let error = check_disk_space_with(|_| Ok(150 * MB), path, 200 * MB).unwrap_err();
+        assert!(is_insufficient_space(&error));
+        let message = error.to_string();
+        assert!(message.contains("200.0MB"), "{message}");
+        assert!(message.contains("150.0MB"), "{message}");
+
+        // 空き容量を取得できない場合は書き込みを妨げない
+        assert!(check_disk_space_with(
+            |_| Err(io::Error::from(io::ErrorKind::Unsupported)),
+            path,
+            200 * MB
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_available_space_of_missing_path_uses_parent() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("exports/2025/report.json");
+        assert!(available_space(&missing).unwrap() > 0);
+    }
+}
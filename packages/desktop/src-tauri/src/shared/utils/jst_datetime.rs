@@ -0,0 +1,99 @@
+//! 日時フィールドのシリアライズ形式
+//!
+//! コマンドの戻り値に含める日時は、JST（`+09:00`）のRFC3339形式に統一します。
+//! `DateTime<Utc>`のフィールドには`#[serde(with = "crate::shared::utils::jst_datetime")]`を、
+//! `Option<DateTime<Utc>>`のフィールドには`jst_datetime::option`を指定します。
+//! 読み込み時はオフセット付きのRFC3339形式であれば受け付けます（UTCで保存された既存データを含む）。
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Asia::Tokyo;
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// 日時をJSTのRFC3339形式に変換する
+pub fn format(datetime: &DateTime<Utc>) -> String {
+    datetime.with_timezone(&Tokyo).to_rfc3339()
+}
+
+/// RFC3339形式の文字列を日時に変換する
+pub fn parse(value: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
+    DateTime::parse_from_rfc3339(value).map(|datetime| datetime.with_timezone(&Utc))
+}
+
+pub fn serialize<S: Serializer>(
+    datetime: &DateTime<Utc>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format(datetime))
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse(&value).map_err(serde::de::Error::custom)
+}
+
+/// `Option<DateTime<Utc>>`用のシリアライズ形式
+pub mod option {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        datetime: &Option<DateTime<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match datetime {
+            Some(datetime) => serializer.serialize_some(&format(datetime)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<Utc>>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|value| parse(&value).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde::Serialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Payload {
+        #[serde(with = "super")]
+        created_at: DateTime<Utc>,
+        #[serde(with = "super::option")]
+        expires_at: Option<DateTime<Utc>>,
+    }
+
+    #[test]
+    fn test_serializes_in_jst_and_reads_any_offset() {
+        let created_at = Utc.with_ymd_and_hms(2025, 4, 1, 1, 0, 0).unwrap();
+        let payload = Payload {
+            created_at,
+            expires_at: None,
+        };
+
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["created_at"], "2025-04-01T10:00:00+09:00");
+        assert!(json["expires_at"].is_null());
+
+        // UTCで保存された既存データも読み込める
+        let restored: Payload = serde_json::from_str(
+            r#"{"created_at":"2025-04-01T01:00:00Z","expires_at":"2025-04-02T10:00:00+09:00"}"#,
+        )
+        .unwrap();
+        assert_eq!(restored.created_at, created_at);
+        assert_eq!(
+            restored.expires_at,
+            Some(Utc.with_ymd_and_hms(2025, 4, 2, 1, 0, 0).unwrap())
+        );
+
+        assert!(serde_json::from_str::<Payload>(
+            r#"{"created_at":"2025-04-01 10:00:00","expires_at":null}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,298 @@
+//! 店舗名の正規化
+//!
+//! 説明や領収書の文字列に含まれる店舗名を、表記ゆれを吸収した比較用の文字列に変換します。
+//! 正規化した文字列は別名の照合とグループ化にのみ使用し、表示や保存済みの説明には使用しません。
+//!
+//! 正規化の内容:
+//! - 全角英数字・記号と全角スペースを半角に変換する
+//! - 半角カタカナを全角カタカナに変換する（濁点・半濁点は結合する）
+//! - 英字を小文字に変換する
+//! - 店舗番号（`#123`、`No.5`、`12号店`、単独の数字）を取り除く
+//! - 中黒・連続する空白を1つの空白にまとめ、前後の空白を取り除く
+
+/// 半角カタカナ（U+FF66〜U+FF9D）に対応する全角カタカナ
+const HALFWIDTH_KATAKANA: [char; 56] = [
+    'ヲ', 'ァ', 'ィ', 'ゥ', 'ェ', 'ォ', 'ャ', 'ュ', 'ョ', 'ッ', 'ー', 'ア', 'イ', 'ウ', 'エ', 'オ',
+    'カ', 'キ', 'ク', 'ケ', 'コ', 'サ', 'シ', 'ス', 'セ', 'ソ', 'タ', 'チ', 'ツ', 'テ', 'ト', 'ナ',
+    'ニ', 'ヌ', 'ネ', 'ノ', 'ハ', 'ヒ', 'フ', 'ヘ', 'ホ', 'マ', 'ミ', 'ム', 'メ', 'モ', 'ヤ', 'ユ',
+    'ヨ', 'ラ', 'リ', 'ル', 'レ', 'ロ', 'ワ', 'ン',
+];
+
+/// 半角の濁点
+const HALFWIDTH_VOICED_MARK: char = '\u{FF9E}';
+
+/// 半角の半濁点
+const HALFWIDTH_SEMI_VOICED_MARK: char = '\u{FF9F}';
+
+/// 店舗名を比較用に正規化する
+///
+/// # 引数
+/// * `text` - 店舗名を含む文字列
+///
+/// # 戻り値
+/// 正規化した文字列（店舗番号のみの場合などは空文字列）
+pub fn normalize_merchant_name(text: &str) -> String {
+    let folded = fold_width(text).to_lowercase();
+    let separated: String = folded
+        .chars()
+        .map(|c| if c == '・' { ' ' } else { c })
+        .collect();
+
+    let tokens: Vec<&str> = separated.split_whitespace().collect();
+    let mut kept = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        // 「No. 12」「# 12」のように番号が次の語に分かれている場合
+        if matches!(token, "#" | "no" | "no.")
+            && tokens.get(i + 1).is_some_and(|next| is_digits(next))
+        {
+            i += 2;
+            continue;
+        }
+        if !is_store_number(token) {
+            let stripped = strip_store_number_suffix(token);
+            if !stripped.is_empty() {
+                kept.push(stripped);
+            }
+        }
+        i += 1;
+    }
+
+    kept.join(" ")
+}
+
+/// 全角英数字・記号を半角に、半角カタカナを全角に変換する
+fn fold_width(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\u{3000}' => folded.push(' '),
+            '\u{FF01}'..='\u{FF5E}' => {
+                folded.push(char::from_u32(c as u32 - 0xFEE0).unwrap_or(c));
+            }
+            '\u{FF65}' => folded.push('・'),
+            '\u{FF66}'..='\u{FF9D}' => {
+                folded.push(HALFWIDTH_KATAKANA[(c as u32 - 0xFF66) as usize]);
+            }
+            HALFWIDTH_VOICED_MARK | HALFWIDTH_SEMI_VOICED_MARK => {
+                // 直前のカタカナと結合する（結合できない濁点・半濁点は取り除く）
+                let semi_voiced = c == HALFWIDTH_SEMI_VOICED_MARK;
+                if let Some(composed) = folded
+                    .chars()
+                    .next_back()
+                    .and_then(|prev| compose_kana(prev, semi_voiced))
+                {
+                    folded.pop();
+                    folded.push(composed);
+                }
+            }
+            _ => folded.push(c),
+        }
+    }
+    folded
+}
+
+/// カタカナに濁点・半濁点を結合する
+///
+/// # 引数
+/// * `kana` - 直前の全角カタカナ
+/// * `semi_voiced` - 半濁点の場合はtrue
+fn compose_kana(kana: char, semi_voiced: bool) -> Option<char> {
+    if semi_voiced {
+        return matches!(kana, 'ハ' | 'ヒ' | 'フ' | 'ヘ' | 'ホ')
+            .then(|| char::from_u32(kana as u32 + 2))
+            .flatten();
+    }
+    match kana {
+        'ウ' => Some('ヴ'),
+        'カ' | 'キ' | 'ク' | 'ケ' | 'コ' | 'サ' | 'シ' | 'ス' | 'セ' | 'ソ' | 'タ' | 'チ'
+        | 'ツ' | 'テ' | 'ト' | 'ハ' | 'ヒ' | 'フ' | 'ヘ' | 'ホ' => {
+            char::from_u32(kana as u32 + 1)
+        }
+        _ => None,
+    }
+}
+
+fn is_digits(token: &str) -> bool {
+    !token.is_empty() && token.chars().all(|c| c.is_ascii_digit())
+}
+
+/// 語全体が店舗番号かどうか（`123`、`#123`、`no.123`、`12号店`）
+fn is_store_number(token: &str) -> bool {
+    if is_digits(token) {
+        return true;
+    }
+    if let Some(rest) = token.strip_prefix('#') {
+        return is_digits(rest);
+    }
+    if let Some(rest) = token
+        .strip_prefix("no.")
+        .or_else(|| token.strip_prefix("no"))
+    {
+        if is_digits(rest) {
+            return true;
+        }
+    }
+    token
+        .strip_suffix("号店")
+        .or_else(|| token.strip_suffix('号'))
+        .is_some_and(|rest| is_digits(rest.strip_prefix('第').unwrap_or(rest)))
+}
+
+/// 語末に付いた店舗番号（`starbucks#123`）を取り除く
+fn strip_store_number_suffix(token: &str) -> &str {
+    match token.rfind('#') {
+        Some(pos) if is_digits(&token[pos + 1..]) => &token[..pos],
+        _ => token,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trim_and_collapse_whitespace() {
+        assert_eq!(
+            normalize_merchant_name("  スターバックス  "),
+            "スターバックス"
+        );
+        assert_eq!(
+            normalize_merchant_name("スターバックス\t  コーヒー"),
+            "スターバックス コーヒー"
+        );
+        assert_eq!(normalize_merchant_name("   "), "");
+        assert_eq!(normalize_merchant_name(""), "");
+    }
+
+    #[test]
+    fn test_fullwidth_alphanumerics_are_folded() {
+        assert_eq!(normalize_merchant_name("ＳＴＡＲＢＵＣＫＳ"), "starbucks");
+        assert_eq!(normalize_merchant_name("ｓｔａｒｂｕｃｋｓ"), "starbucks");
+        assert_eq!(normalize_merchant_name("ＡＢＣマート"), "abcマート");
+        assert_eq!(
+            normalize_merchant_name("セブン－イレブン"),
+            "セブン-イレブン"
+        );
+        assert_eq!(normalize_merchant_name("ローソン１００"), "ローソン100");
+    }
+
+    #[test]
+    fn test_ideographic_space_is_folded() {
+        assert_eq!(
+            normalize_merchant_name("スターバックス　コーヒー"),
+            "スターバックス コーヒー"
+        );
+        assert_eq!(normalize_merchant_name("　スタバ　"), "スタバ");
+    }
+
+    #[test]
+    fn test_halfwidth_katakana_is_folded() {
+        assert_eq!(normalize_merchant_name("ｽﾀｰﾊﾞｯｸｽ"), "スターバックス");
+        assert_eq!(normalize_merchant_name("ｽﾀﾊﾞ"), "スタバ");
+        assert_eq!(normalize_merchant_name("ﾌｧﾐﾘｰﾏｰﾄ"), "ファミリーマート");
+        assert_eq!(normalize_merchant_name("ﾄﾞﾄｰﾙｺｰﾋｰ"), "ドトールコーヒー");
+        // 半濁点
+        assert_eq!(normalize_merchant_name("ﾊﾟﾝﾄﾘｰ"), "パントリー");
+        assert_eq!(normalize_merchant_name("ﾎﾟﾌﾟﾗ"), "ポプラ");
+        // ヴ
+        assert_eq!(normalize_merchant_name("ｳﾞｨﾚｯｼﾞ"), "ヴィレッジ");
+    }
+
+    #[test]
+    fn test_full_and_half_width_forms_match() {
+        let forms = [
+            "スターバックス",
+            "ｽﾀｰﾊﾞｯｸｽ",
+            "  スターバックス　",
+            "ｽﾀｰﾊﾞｯｸｽ #12",
+        ];
+        for form in forms {
+            assert_eq!(normalize_merchant_name(form), "スターバックス", "{form}");
+        }
+    }
+
+    #[test]
+    fn test_uncomposable_voiced_mark_is_dropped() {
+        // 濁点を付けられない文字の後の濁点は取り除く
+        assert_eq!(normalize_merchant_name("ｱﾞ"), "ア");
+        assert_eq!(normalize_merchant_name("ﾞ"), "");
+        assert_eq!(normalize_merchant_name("Aﾟ"), "a");
+    }
+
+    #[test]
+    fn test_case_folding() {
+        assert_eq!(normalize_merchant_name("STARBUCKS"), "starbucks");
+        assert_eq!(
+            normalize_merchant_name("StarBucks Coffee"),
+            "starbucks coffee"
+        );
+        assert_eq!(
+            normalize_merchant_name("ＳｔａｒＢｕｃｋｓ"),
+            normalize_merchant_name("starbucks")
+        );
+        // 日本語の文字は大文字・小文字の区別がないため変化しない
+        assert_eq!(normalize_merchant_name("すき家"), "すき家");
+    }
+
+    #[test]
+    fn test_store_numbers_are_stripped() {
+        assert_eq!(normalize_merchant_name("STARBUCKS #123"), "starbucks");
+        assert_eq!(normalize_merchant_name("STARBUCKS#123"), "starbucks");
+        assert_eq!(normalize_merchant_name("STARBUCKS ＃１２３"), "starbucks");
+        assert_eq!(normalize_merchant_name("STARBUCKS # 123"), "starbucks");
+        assert_eq!(normalize_merchant_name("Starbucks No.45"), "starbucks");
+        assert_eq!(normalize_merchant_name("Starbucks No. 45"), "starbucks");
+        assert_eq!(normalize_merchant_name("Starbucks no45"), "starbucks");
+        assert_eq!(
+            normalize_merchant_name("セブンイレブン 12号店"),
+            "セブンイレブン"
+        );
+        assert_eq!(
+            normalize_merchant_name("セブンイレブン 第3号店"),
+            "セブンイレブン"
+        );
+        assert_eq!(
+            normalize_merchant_name("セブンイレブン 4567"),
+            "セブンイレブン"
+        );
+    }
+
+    #[test]
+    fn test_names_containing_digits_are_kept() {
+        // 店舗名の一部の数字は店舗番号として扱わない
+        assert_eq!(
+            normalize_merchant_name("ローソンストア100"),
+            "ローソンストア100"
+        );
+        assert_eq!(normalize_merchant_name("7-Eleven"), "7-eleven");
+        assert_eq!(normalize_merchant_name("No Name Cafe"), "no name cafe");
+        assert_eq!(normalize_merchant_name("Cafe #A1"), "cafe #a1");
+    }
+
+    #[test]
+    fn test_middle_dot_is_treated_as_separator() {
+        assert_eq!(
+            normalize_merchant_name("スターバックス・コーヒー"),
+            "スターバックス コーヒー"
+        );
+        assert_eq!(
+            normalize_merchant_name("ｽﾀｰﾊﾞｯｸｽ･ｺｰﾋｰ"),
+            "スターバックス コーヒー"
+        );
+    }
+
+    #[test]
+    fn test_normalization_is_idempotent() {
+        for text in [
+            "ｽﾀｰﾊﾞｯｸｽ･ｺｰﾋｰ ＃１２",
+            "STARBUCKS #123",
+            "ＡＢＣマート　No.5",
+            "すき家",
+        ] {
+            let once = normalize_merchant_name(text);
+            assert_eq!(normalize_merchant_name(&once), once, "{text}");
+        }
+    }
+}
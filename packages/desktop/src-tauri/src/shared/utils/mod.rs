@@ -1,7 +1,15 @@
 use crate::shared::errors::{AppError, AppResult};
 use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
 use chrono_tz::Asia::Tokyo;
-
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+pub mod calc;
+pub mod color;
+pub mod date_utils;
+pub mod disk_space;
+pub mod jst_datetime;
+pub mod merchant_name;
 pub mod nanoid;
 
 /// 日付文字列のバリデーション
@@ -24,8 +32,12 @@ pub fn validate_date(date_str: &str) -> AppResult<()> {
         ));
     }
 
-    // ハイフンの位置チェック
-    if (date_str.chars().nth(4) != Some('-')) || (date_str.chars().nth(7) != Some('-')) {
+    // ハイフンの位置と数字のチェック（chronoが許容する空白や符号を受け付けないため）
+    let is_valid_format = date_str.bytes().enumerate().all(|(i, b)| match i {
+        4 | 7 => b == b'-',
+        _ => b.is_ascii_digit(),
+    });
+    if !is_valid_format {
         return Err(AppError::validation(
             "日付はYYYY-MM-DD形式で入力してください",
         ));
@@ -46,6 +58,43 @@ pub fn validate_date(date_str: &str) -> AppResult<()> {
     Ok(())
 }
 
+/// 月の指定のバリデーション
+///
+/// # 引数
+/// * `month` - 月（YYYY-MM形式）
+///
+/// # 戻り値
+/// 有効な月の場合はOk(())、無効な場合はエラー
+///
+/// # バリデーション規則
+/// - YYYY-MM形式であること
+/// - 月が01〜12であること
+/// - 1900年以降、2100年以前であること
+pub fn validate_month(month: &str) -> AppResult<()> {
+    let is_valid_format = month.len() == 7
+        && month.bytes().enumerate().all(|(i, b)| match i {
+            4 => b == b'-',
+            _ => b.is_ascii_digit(),
+        });
+    if !is_valid_format {
+        return Err(AppError::validation("月はYYYY-MM形式で入力してください"));
+    }
+
+    // 形式を確認済みのため、数値への変換は失敗しない
+    let year: i32 = month[..4].parse().unwrap_or_default();
+    let month_number: u32 = month[5..].parse().unwrap_or_default();
+    if !(1..=12).contains(&month_number) {
+        return Err(AppError::validation("無効な月です"));
+    }
+    if !(1900..=2100).contains(&year) {
+        return Err(AppError::validation(
+            "月は1900年から2100年の間で入力してください",
+        ));
+    }
+
+    Ok(())
+}
+
 /// 金額のバリデーション
 ///
 /// # 引数
@@ -89,6 +138,102 @@ pub fn validate_amount(amount: f64) -> AppResult<()> {
     Ok(())
 }
 
+/// 金額の通貨
+///
+/// 未指定の場合は日本円として扱います。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CurrencyCode {
+    #[default]
+    Jpy,
+    Usd,
+    Eur,
+    Gbp,
+}
+
+impl CurrencyCode {
+    /// ISO 4217の通貨コードを取得する
+    pub fn code(&self) -> &'static str {
+        match self {
+            CurrencyCode::Jpy => "JPY",
+            CurrencyCode::Usd => "USD",
+            CurrencyCode::Eur => "EUR",
+            CurrencyCode::Gbp => "GBP",
+        }
+    }
+}
+
+/// 日本円の金額のバリデーション
+///
+/// # 引数
+/// * `amount` - 金額
+///
+/// # 戻り値
+/// 有効な金額の場合はOk(())、無効な場合はエラー
+///
+/// # バリデーション規則
+/// - `validate_amount`の規則を満たすこと
+/// - 整数であること（円未満の端数は不可）
+pub fn validate_amount_jpy(amount: f64) -> AppResult<()> {
+    validate_amount(amount)?;
+    if amount.fract() != 0.0 {
+        return Err(AppError::validation("日本円の金額は整数で入力してください"));
+    }
+    Ok(())
+}
+
+/// 通貨に応じた金額のバリデーション
+///
+/// # 引数
+/// * `amount` - 金額
+/// * `currency` - 通貨
+///
+/// # 戻り値
+/// 有効な金額の場合はOk(())、無効な場合はエラー
+///
+/// # バリデーション規則
+/// - 日本円: 整数であること（`validate_amount_jpy`）
+/// - その他の通貨: 小数点以下2桁まで（`validate_amount`）
+pub fn validate_amount_for_currency(amount: f64, currency: CurrencyCode) -> AppResult<()> {
+    match currency {
+        CurrencyCode::Jpy => validate_amount_jpy(amount),
+        CurrencyCode::Usd | CurrencyCode::Eur | CurrencyCode::Gbp => validate_amount(amount),
+    }
+}
+
+/// 消費税率のバリデーション
+///
+/// # 引数
+/// * `rate` - 税率（10%の場合は0.1）
+///
+/// # 戻り値
+/// 有効な税率の場合はOk(())、無効な場合はエラー
+///
+/// # バリデーション規則
+/// - 0以上1未満の数値であること（百分率で入力された10などは不正とする）
+/// - 小数点以下は4桁まで
+pub fn validate_tax_rate(rate: f64) -> AppResult<()> {
+    if !rate.is_finite() {
+        return Err(AppError::validation("無効な税率です"));
+    }
+
+    if !(0.0..1.0).contains(&rate) {
+        return Err(AppError::validation(
+            "税率は0以上1未満の小数で入力してください（例: 10%の場合は0.1）",
+        ));
+    }
+
+    // 小数点以下の桁数チェック（4桁まで）
+    let scaled = rate * 10_000.0;
+    if (scaled - scaled.round()).abs() > 1e-6 {
+        return Err(AppError::validation(
+            "税率は小数点以下4桁まで入力してください",
+        ));
+    }
+
+    Ok(())
+}
+
 /// 文字列の長さバリデーション
 ///
 /// # 引数
@@ -108,6 +253,34 @@ pub fn validate_text_length(text: &str, max_length: usize, field_name: &str) ->
     Ok(())
 }
 
+/// サブスクリプションのサービス名の最大文字数
+pub const MAX_SUBSCRIPTION_NAME_LENGTH: usize = 100;
+
+/// サービス名のバリデーション
+///
+/// # 引数
+/// * `name` - サービス名
+///
+/// # 戻り値
+/// 有効なサービス名の場合はOk(())、無効な場合はエラー
+///
+/// # バリデーション規則
+/// - 必須項目であること
+/// - 100文字以内であること
+///
+/// 絵文字や結合文字を含む名前でも見た目どおりの文字数になるよう、
+/// `char`単位ではなく書記素クラスタ単位で数える
+pub fn validate_subscription_name(name: &str) -> AppResult<()> {
+    validate_required_field(name, "サービス名")?;
+    let grapheme_count = name.graphemes(true).count();
+    if grapheme_count > MAX_SUBSCRIPTION_NAME_LENGTH {
+        return Err(AppError::validation(format!(
+            "サービス名は{MAX_SUBSCRIPTION_NAME_LENGTH}文字以内で入力してください（現在: {grapheme_count}文字）"
+        )));
+    }
+    Ok(())
+}
+
 /// 必須フィールドのバリデーション
 ///
 /// # 引数
@@ -163,8 +336,7 @@ pub fn validate_description(description: &Option<String>) -> AppResult<()> {
 /// # 戻り値
 /// JST形式のRFC3339文字列
 pub fn get_current_jst_timestamp() -> String {
-    let now_jst = Utc::now().with_timezone(&Tokyo);
-    now_jst.to_rfc3339()
+    jst_datetime::format(&Utc::now())
 }
 
 /// 日付文字列をJSTのDateTimeに変換
@@ -239,6 +411,17 @@ pub fn normalize_string(text: &str) -> String {
     text.trim().to_string()
 }
 
+/// 金額を通貨の精度（小数点以下2桁）に丸める
+///
+/// # 引数
+/// * `amount` - 金額
+///
+/// # 戻り値
+/// 小数点以下2桁に四捨五入した金額
+pub fn round_to_currency_precision(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
 /// 金額を文字列形式でフォーマット（カンマ区切り）
 ///
 /// # 引数
@@ -258,6 +441,7 @@ pub fn format_amount(amount: f64) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_validate_date() {
@@ -293,6 +477,51 @@ mod tests {
         assert!(validate_amount(1.234).is_err()); // 小数点以下3桁
     }
 
+    #[test]
+    fn test_validate_amount_jpy() {
+        assert!(validate_amount_jpy(1.0).is_ok());
+        assert!(validate_amount_jpy(9999999999.0).is_ok());
+
+        // 円未満の端数は不可
+        assert!(validate_amount_jpy(100.5).is_err());
+        assert!(validate_amount_jpy(0.01).is_err());
+
+        // 共通の規則も適用される
+        assert!(validate_amount_jpy(0.0).is_err());
+        assert!(validate_amount_jpy(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_validate_amount_for_currency() {
+        assert!(validate_amount_for_currency(100.5, CurrencyCode::Jpy).is_err());
+        assert!(validate_amount_for_currency(100.0, CurrencyCode::Jpy).is_ok());
+        assert!(validate_amount_for_currency(100.50, CurrencyCode::Usd).is_ok());
+        assert!(validate_amount_for_currency(100.505, CurrencyCode::Usd).is_err());
+        assert!(validate_amount_for_currency(4.99, CurrencyCode::Eur).is_ok());
+
+        // 未指定は日本円
+        assert_eq!(CurrencyCode::default(), CurrencyCode::Jpy);
+        assert_eq!(
+            serde_json::from_str::<CurrencyCode>("\"USD\"").unwrap(),
+            CurrencyCode::Usd
+        );
+        assert_eq!(CurrencyCode::Gbp.code(), "GBP");
+    }
+
+    #[test]
+    fn test_validate_tax_rate() {
+        assert!(validate_tax_rate(0.1).is_ok());
+        assert!(validate_tax_rate(0.08).is_ok());
+        assert!(validate_tax_rate(0.0).is_ok());
+        assert!(validate_tax_rate(0.0825).is_ok());
+
+        assert!(validate_tax_rate(-0.1).is_err());
+        assert!(validate_tax_rate(1.0).is_err());
+        assert!(validate_tax_rate(10.0).is_err());
+        assert!(validate_tax_rate(0.12345).is_err());
+        assert!(validate_tax_rate(f64::NAN).is_err());
+    }
+
     #[test]
     fn test_validate_text_length() {
         // 有効な長さ
@@ -303,6 +532,42 @@ mod tests {
         assert!(validate_text_length("これは非常に長いテキストです", 5, "テスト").is_err());
     }
 
+    #[test]
+    fn test_validate_subscription_name() {
+        // 有効な名前
+        assert!(validate_subscription_name("Netflix").is_ok());
+        assert!(validate_subscription_name("動画配信サービス🎬").is_ok());
+
+        // 必須チェック
+        assert!(validate_subscription_name("").is_err());
+        assert!(validate_subscription_name("　 ").is_err());
+    }
+
+    #[test]
+    fn test_validate_subscription_name_counts_graphemes() {
+        // 家族の絵文字（ZWJ結合）と肌の色付き絵文字は1文字として数える
+        let family = "👨‍👩‍👧‍👦";
+        let thumbs_up = "👍🏽";
+        assert_eq!(family.chars().count(), 7);
+        assert_eq!(thumbs_up.chars().count(), 2);
+
+        let name = format!("{}{}", "音楽".repeat(49), family);
+        assert_eq!(name.chars().count(), 105);
+        assert!(validate_subscription_name(&name).is_ok());
+
+        let name = format!("{}{}{}", "音楽".repeat(49), family, thumbs_up);
+        assert!(validate_subscription_name(&name).is_ok());
+
+        // 濁点の結合文字も1文字として数える
+        let name = "か\u{3099}".repeat(MAX_SUBSCRIPTION_NAME_LENGTH);
+        assert!(validate_subscription_name(&name).is_ok());
+
+        // 上限を超える場合はエラー
+        let name = format!("{}{}", "🎵".repeat(MAX_SUBSCRIPTION_NAME_LENGTH), family);
+        let err = validate_subscription_name(&name).unwrap_err();
+        assert!(err.to_string().contains("現在: 101文字"));
+    }
+
     #[test]
     fn test_validate_required_field() {
         // 有効な値
@@ -349,6 +614,26 @@ mod tests {
         assert!(validate_https_url("ftp://example.com").is_err()); // 異なるプロトコル
     }
 
+    #[test]
+    fn test_validate_month() {
+        assert!(validate_month("2024-01").is_ok());
+        assert!(validate_month("2024-12").is_ok());
+
+        for invalid in [
+            "",
+            "2024-1",
+            "2024/01",
+            "2024-00",
+            "2024-13",
+            "1899-12",
+            "2101-01",
+            "２０２４-01",
+            "2024-01-01",
+        ] {
+            assert!(validate_month(invalid).is_err(), "{invalid}");
+        }
+    }
+
     #[test]
     fn test_get_current_jst_timestamp() {
         let timestamp = get_current_jst_timestamp();
@@ -385,6 +670,14 @@ mod tests {
         assert_eq!(normalize_string("   "), "");
     }
 
+    #[test]
+    fn test_round_to_currency_precision() {
+        assert_eq!(round_to_currency_precision(1000.0), 1000.0);
+        assert_eq!(round_to_currency_precision(980.0 / 12.0), 81.67);
+        assert_eq!(round_to_currency_precision(0.1 + 0.2), 0.3);
+        assert_eq!(round_to_currency_precision(-12.346), -12.35);
+    }
+
     #[test]
     fn test_format_amount() {
         assert_eq!(format_amount(1000.0), "1000");
@@ -392,4 +685,68 @@ mod tests {
         assert_eq!(format_amount(1234567.89), "1234567.89");
         assert_eq!(format_amount(0.01), "0.01");
     }
+
+    /// `YYYY-MM-DD`形式（ASCIIの数字とハイフン）かどうか
+    fn is_yyyy_mm_dd(s: &str) -> bool {
+        s.len() == 10
+            && s.bytes().enumerate().all(|(i, b)| match i {
+                4 | 7 => b == b'-',
+                _ => b.is_ascii_digit(),
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_validate_amount_rejects_out_of_range(amount in proptest::num::f64::ANY) {
+            let in_range = amount.is_finite() && amount > 0.0 && amount < 10_000_000_000.0;
+            if !in_range {
+                prop_assert!(validate_amount(amount).is_err());
+            }
+        }
+
+        #[test]
+        fn prop_validate_amount_rejects_non_finite(
+            amount in proptest::num::f64::INFINITE | proptest::num::f64::QUIET_NAN
+        ) {
+            prop_assert!(validate_amount(amount).is_err());
+        }
+
+        #[test]
+        fn prop_validate_amount_rejects_negative(amount in proptest::num::f64::NEGATIVE) {
+            prop_assert!(validate_amount(amount).is_err());
+        }
+
+        #[test]
+        fn prop_validate_amount_rejects_ten_digits_or_more(amount in 10_000_000_000.0..f64::MAX) {
+            prop_assert!(validate_amount(amount).is_err());
+        }
+
+        #[test]
+        fn prop_validate_date_accepts_only_valid_calendar_dates(s in "\\PC*") {
+            // 任意の文字列でパニックせず、受け付けるのは実在するYYYY-MM-DDのみ
+            if validate_date(&s).is_ok() {
+                prop_assert!(is_yyyy_mm_dd(&s));
+                prop_assert!(NaiveDate::parse_from_str(&s, "%Y-%m-%d").is_ok());
+            }
+        }
+
+        #[test]
+        fn prop_validate_date_matches_calendar(s in "[0-9]{4}-[0-9]{2}-[0-9]{2}") {
+            let expected = NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+                .map(|date| (1900..=2100).contains(&date.year()))
+                .unwrap_or(false);
+            prop_assert_eq!(validate_date(&s).is_ok(), expected);
+        }
+
+        #[test]
+        fn prop_validate_date_accepts_every_day_in_range(
+            year in 1900i32..=2100,
+            month in 1u32..=12,
+            day in 1u32..=31,
+        ) {
+            let s = format!("{year:04}-{month:02}-{day:02}");
+            let exists = NaiveDate::from_ymd_opt(year, month, day).is_some();
+            prop_assert_eq!(validate_date(&s).is_ok(), exists);
+        }
+    }
 }
@@ -1,6 +1,15 @@
-import type { UpdateInfo, UpdaterConfig } from '$lib/types/updater';
+import type {
+  AppVersionInfo,
+  DeltaUpdateInfo,
+  PendingInstallBlockedEvent,
+  UpdateCheckProgress,
+  UpdateInfo,
+  UpdaterConfig,
+} from '$lib/types/updater';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
+import { EVENTS } from '$lib/types/events';
+import { listenEvent } from '$lib/utils/events';
 
 /**
  * アップデートサービス
@@ -46,11 +55,24 @@ export class UpdaterService {
   }
 
   /**
-   * 現在のアプリケーションバージョンを取得
+   * 現在のバージョンからの差分アップデートが配信されているかを確認
+   * @returns 利用できる差分の情報（差分がない場合はnull）
    */
-  static async getAppVersion(): Promise<string> {
+  static async checkForDeltaUpdate(): Promise<DeltaUpdateInfo | null> {
     try {
-      return await invoke<string>('get_app_version');
+      return await invoke<DeltaUpdateInfo | null>('check_for_delta_update');
+    } catch (error) {
+      console.error('差分アップデート確認エラー:', error);
+      throw new Error(`差分アップデートの確認に失敗しました: ${String(error)}`);
+    }
+  }
+
+  /**
+   * 現在のアプリケーションバージョンとビルド情報を取得
+   */
+  static async getAppVersion(): Promise<AppVersionInfo> {
+    try {
+      return await invoke<AppVersionInfo>('get_app_version');
     } catch (error) {
       console.error('バージョン取得エラー:', error);
       throw new Error(`バージョンの取得に失敗しました: ${String(error)}`);
@@ -95,6 +117,20 @@ export class UpdaterService {
     }
   }
 
+  /**
+   * 指定したバージョンのリリースノート（Markdown）を取得
+   * @param version バージョン
+   * @returns リリースノートのMarkdown
+   */
+  static async getChangelog(version: string): Promise<string> {
+    try {
+      return await invoke<string>('get_update_changelog', { version });
+    } catch (error) {
+      console.error('リリースノート取得エラー:', error);
+      throw new Error(`リリースノートの取得に失敗しました: ${String(error)}`);
+    }
+  }
+
   /**
    * 自動アップデートチェックを開始
    */
@@ -125,10 +161,11 @@ export class UpdaterService {
 
   /**
    * アプリケーションを再起動してアップデートをインストール
+   * @param force 未適用のマイグレーションがあっても再起動する場合はtrue
    */
-  static async restartApplication(): Promise<void> {
+  static async restartApplication(force = false): Promise<void> {
     try {
-      await invoke<void>('restart_application');
+      await invoke<void>('restart_application', { force });
     } catch (error) {
       console.error('アプリケーション再起動エラー:', error);
       throw new Error(
@@ -145,7 +182,7 @@ export class UpdaterService {
     callback: (updateInfo: UpdateInfo) => void
   ): Promise<() => void> {
     try {
-      const unlisten = await listen<UpdateInfo>('update-available', (event) => {
+      const unlisten = await listenEvent(EVENTS.UPDATE_AVAILABLE, (event) => {
         console.info('アップデートが利用可能です:', event.payload);
         callback(event.payload);
       });
@@ -183,7 +220,7 @@ export class UpdaterService {
     callback: () => void
   ): Promise<() => void> {
     try {
-      const unlisten = await listen('download-complete', () => {
+      const unlisten = await listenEvent(EVENTS.DOWNLOAD_COMPLETE, () => {
         console.info('ダウンロードが完了しました');
         callback();
       });
@@ -196,6 +233,52 @@ export class UpdaterService {
     }
   }
 
+  /**
+   * アップデートチェックの進捗イベントをリッスン
+   * @param callback 強制チェックの段階が進むたびに呼ばれるコールバック
+   */
+  static async listenForUpdateCheckProgress(
+    callback: (progress: UpdateCheckProgress) => void
+  ): Promise<() => void> {
+    try {
+      const unlisten = await listenEvent(
+        EVENTS.UPDATE_CHECK_PROGRESS,
+        (event) => {
+          callback(event.payload);
+        }
+      );
+      return unlisten;
+    } catch (error) {
+      console.error('アップデートチェック進捗リスナー設定エラー:', error);
+      throw new Error(
+        `アップデートチェック進捗の設定に失敗しました: ${String(error)}`
+      );
+    }
+  }
+
+  /**
+   * インストール待ちの通知イベントをリッスン
+   * @param callback 実行中の処理のためにインストールを待つときのコールバック
+   */
+  static async listenForPendingInstallBlocked(
+    callback: (event: PendingInstallBlockedEvent) => void
+  ): Promise<() => void> {
+    try {
+      const unlisten = await listenEvent(
+        EVENTS.PENDING_INSTALL_BLOCKED,
+        (event) => {
+          callback(event.payload);
+        }
+      );
+      return unlisten;
+    } catch (error) {
+      console.error('インストール待ち通知リスナー設定エラー:', error);
+      throw new Error(
+        `インストール待ち通知の設定に失敗しました: ${String(error)}`
+      );
+    }
+  }
+
   /**
    * ダウンロード進捗イベントをリッスン
    * @param callback ダウンロード進捗が更新されたときのコールバック
@@ -204,7 +287,7 @@ export class UpdaterService {
     callback: (progress: number) => void
   ): Promise<() => void> {
     try {
-      const unlisten = await listen<number>('download-progress', (event) => {
+      const unlisten = await listenEvent(EVENTS.DOWNLOAD_PROGRESS, (event) => {
         callback(event.payload);
       });
       return unlisten;
@@ -243,10 +326,13 @@ export class UpdaterService {
     callback: (error: string) => void
   ): Promise<() => void> {
     try {
-      const unlisten = await listen<string>('update-check-error', (event) => {
-        console.error('アップデートチェックエラー:', event.payload);
-        callback(event.payload);
-      });
+      const unlisten = await listenEvent(
+        EVENTS.UPDATE_CHECK_ERROR,
+        (event) => {
+          console.error('アップデートチェックエラー:', event.payload);
+          callback(event.payload);
+        }
+      );
       return unlisten;
     } catch (error) {
       console.error('アップデートエラーリスナー設定エラー:', error);
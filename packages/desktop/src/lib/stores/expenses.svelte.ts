@@ -1,4 +1,9 @@
-import type { Expense, Subscription } from '../types';
+import type {
+  CreateExpenseDto,
+  Expense,
+  Subscription,
+  UpdateExpenseDto,
+} from '../types';
 import {
   getExpenses,
   createExpense,
@@ -11,6 +16,8 @@ import {
   deleteSubscription,
   getMonthlySubscriptionTotal,
 } from '../utils/tauri';
+import { EVENTS } from '../types/events';
+import { listenEvent } from '../utils/events';
 
 /**
  * 経費とサブスクリプションの状態管理ストア
@@ -38,6 +45,9 @@ class ExpenseStore {
   // 月額サブスクリプション合計
   monthlySubscriptionTotal = $state<number>(0);
 
+  // バックグラウンド同期の通知を購読済みかどうか
+  private listeningForSync = false;
+
   /**
    * フィルタリングされた経費リスト（派生状態）
    */
@@ -108,6 +118,7 @@ class ExpenseStore {
    * 経費一覧を読み込む
    */
   async loadExpenses(): Promise<void> {
+    await this.listenForSync();
     this.isLoading = true;
     this.error = null;
 
@@ -131,11 +142,32 @@ class ExpenseStore {
     }
   }
 
+  /**
+   * バックグラウンド同期で経費が変更されたときに再読み込みする
+   *
+   * 経費一覧はローカルのデータから返され、APIサーバーとの差分同期は後から行われるため
+   */
+  private async listenForSync(): Promise<void> {
+    if (this.listeningForSync) {
+      return;
+    }
+    this.listeningForSync = true;
+    try {
+      await listenEvent(EVENTS.EXPENSES_SYNCED, () => {
+        void this.loadExpenses();
+      });
+    } catch (err) {
+      this.listeningForSync = false;
+      console.warn('経費の同期通知の購読に失敗しました:', err);
+    }
+  }
+
   /**
    * 新しい経費を作成する
    */
   async addExpense(
-    expense: Omit<Expense, 'id' | 'created_at' | 'updated_at'>
+    expense: Omit<Expense, 'id' | 'created_at' | 'updated_at'> &
+      Pick<CreateExpenseDto, 'receipt_followup'>
   ): Promise<boolean> {
     this.isLoading = true;
     this.error = null;
@@ -146,6 +178,7 @@ class ExpenseStore {
         amount: expense.amount,
         category: expense.category,
         description: expense.description,
+        receipt_followup: expense.receipt_followup,
       });
 
       if (result.error) {
@@ -173,7 +206,8 @@ class ExpenseStore {
    */
   async modifyExpense(
     id: number,
-    updates: Partial<Omit<Expense, 'id' | 'created_at' | 'updated_at'>>
+    updates: Partial<Omit<Expense, 'id' | 'created_at' | 'updated_at'>> &
+      Pick<UpdateExpenseDto, 'receipt_followup'>
   ): Promise<boolean> {
     this.isLoading = true;
     this.error = null;
@@ -422,6 +456,24 @@ class ExpenseStore {
     void this.loadExpenses();
   }
 
+  /**
+   * コマンドが返した経費をリストに反映する（再取得せずに更新する）
+   */
+  applyExpense(expense: Expense): void {
+    this.expenses = this.expenses.map((exp) =>
+      exp.id === expense.id ? expense : exp
+    );
+  }
+
+  /**
+   * コマンドが返したサブスクリプションをリストに反映する（再取得せずに更新する）
+   */
+  applySubscription(subscription: Subscription): void {
+    this.subscriptions = this.subscriptions.map((sub) =>
+      sub.id === subscription.id ? subscription : sub
+    );
+  }
+
   /**
    * エラーをクリアする
    */
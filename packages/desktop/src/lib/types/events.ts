@@ -0,0 +1,46 @@
+// このファイルは src-tauri/src/shared/events.rs から生成されています。直接編集しないでください。
+// 再生成: UPDATE_EVENT_BINDINGS=1 cargo test shared::events
+import type {
+  CacheNearFullEvent,
+  ExpenseSyncReport,
+  SecureStorageEvent,
+} from './index';
+import type {
+  PendingInstallBlockedEvent,
+  UpdateCheckProgress,
+  UpdateInfo,
+} from './updater';
+
+// バックエンドから通知されるイベント名
+export const EVENTS = {
+  DOWNLOAD_PROGRESS: 'download-progress',
+  DOWNLOAD_COMPLETE: 'download-complete',
+  UPDATE_AVAILABLE: 'update-available',
+  UPDATE_CHECK_ERROR: 'update-check-error',
+  UPDATE_CHECK_PROGRESS: 'update-check-progress',
+  PENDING_INSTALL_BLOCKED: 'pending-install-blocked',
+  SHOW_UPDATE_DIALOG: 'show-update-dialog',
+  SHOW_NO_UPDATE_DIALOG: 'show-no-update-dialog',
+  SHOW_UPDATE_ERROR_DIALOG: 'show-update-error-dialog',
+  SECURE_STORAGE_EVENT: 'secure-storage-event',
+  CACHE_NEAR_FULL: 'cache-near-full',
+  EXPENSES_SYNCED: 'expenses-synced',
+} as const;
+
+export type EventName = (typeof EVENTS)[keyof typeof EVENTS];
+
+// イベントごとの通知内容
+export interface EventPayloads {
+  'download-progress': number;
+  'download-complete': null;
+  'update-available': UpdateInfo;
+  'update-check-error': string;
+  'update-check-progress': UpdateCheckProgress;
+  'pending-install-blocked': PendingInstallBlockedEvent;
+  'show-update-dialog': UpdateInfo;
+  'show-no-update-dialog': null;
+  'show-update-error-dialog': string;
+  'secure-storage-event': SecureStorageEvent;
+  'cache-near-full': CacheNearFullEvent;
+  'expenses-synced': ExpenseSyncReport;
+}
@@ -8,10 +8,43 @@ export interface Expense {
   description?: string;
   receipt_path?: string; // 後方互換性のため残す
   receipt_url?: string; // R2対応の新しいフィールド
+  tax_rate?: number; // 消費税率（例: 0.1）
+  tax_amount?: number; // 消費税額（amountに含まれる）
+  latitude?: number; // 領収書のEXIFから取得した緯度（保存を許可した場合のみ）
+  longitude?: number; // 領収書のEXIFから取得した経度（保存を許可した場合のみ）
+  location_opt_in?: boolean; // 利用者の許可に基づいて位置情報を保存した経費
+  receipt_status?: ReceiptStatus; // 領収書の添付状態（Rust側で算出）
+  merchant_id?: number | null; // 紐付けられた店舗のID
+  missing_receipt?: boolean; // 領収書の添付ルールに反して領収書が未添付
   created_at: string;
   updated_at: string;
 }
 
+// 経費一覧の並び順
+export type ExpenseSortKey = 'date_desc' | 'date_asc' | 'amount_desc' | 'amount_asc';
+
+// 経費一覧のページ取得条件
+export interface GetExpensesQuery {
+  from_date?: string; // 開始日（YYYY-MM-DD形式、この日を含む）
+  to_date?: string; // 終了日（YYYY-MM-DD形式、この日を含む）
+  category?: string;
+  limit?: number; // 取得件数（1〜500、未指定の場合は一致したすべて）
+  offset?: number; // 読み飛ばす件数
+  sort_by?: ExpenseSortKey; // 未指定の場合は日付の新しい順
+}
+
+// ページ単位の経費一覧
+export interface ExpensePage {
+  expenses: Expense[];
+  total_count: number; // ページに関係なく条件に一致した件数
+}
+
+// 領収書の添付状態
+export type ReceiptStatus =
+  | { status: 'none' }
+  | { status: 'attached'; url: string }
+  | { status: 'broken' };
+
 // 経費作成用DTO
 export interface CreateExpenseDto {
   date: string;
@@ -19,6 +52,9 @@ export interface CreateExpenseDto {
   category: string; // 後方互換性のため残す
   category_id?: number; // カテゴリーID（推奨）
   description?: string;
+  tax_rate?: number; // 消費税率（例: 0.1）
+  tax_amount?: number; // 消費税額（amountに含まれる）
+  receipt_followup?: boolean; // 領収書を後で添付する（領収書が必須のルールでも保存する）
 }
 
 // 経費更新用DTO
@@ -29,6 +65,7 @@ export interface UpdateExpenseDto {
   category_id?: number; // カテゴリーID（推奨）
   description?: string;
   receipt_url?: string;
+  receipt_followup?: boolean; // 領収書を後で添付する（領収書が必須のルールでも保存する）
 }
 
 // サブスクリプションデータモデル
@@ -78,6 +115,36 @@ export interface MonthlyTotalResponse {
   activeSubscriptions: number;
 }
 
+// 削除コマンドの戻り値
+export interface DeleteResult {
+  id: number;
+  revision: string; // 削除後のデータのリビジョン（RFC3339形式）
+}
+
+// 経費の同期結果（expenses-syncedイベントでも通知される）
+export interface ExpenseSyncReport {
+  full_resync: boolean; // 全件を取得し直したかどうか
+  upserted: number; // 作成・更新した経費の件数
+  deleted: number; // 削除した経費の件数
+  total: number; // 同期後のローカルの経費の件数
+  revision: string; // 同期後のリビジョン
+  changed: boolean; // ローカルの経費が変わったかどうか
+}
+
+// 経費の件数とチェックサム
+export interface ExpenseChecksum {
+  count: number;
+  checksum: string;
+}
+
+// 経費の同期の整合性チェック結果
+export interface ExpenseIntegrityReport {
+  local: ExpenseChecksum;
+  server: ExpenseChecksum;
+  matches: boolean;
+  resync: ExpenseSyncReport | null; // 一致しなかったため全件を取得し直した結果
+}
+
 // カテゴリデータモデル
 export interface Category {
   id: number;
@@ -142,6 +209,235 @@ export interface ReceiptCache {
   last_accessed: string;
 }
 
+// 領収書上の矩形（ページに対する0〜1の相対座標、左上原点）
+export interface NormalizedRect {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+// 領収書の注釈（該当する明細行のハイライト）
+export interface ReceiptAnnotation {
+  id: number;
+  expense_id: number;
+  receipt_url: string;
+  page_number: number; // 1始まり（画像は常に1）
+  rect: NormalizedRect;
+  note?: string;
+  created_at: string;
+}
+
+// 注釈追加DTO
+export interface CreateReceiptAnnotationDto {
+  expense_id: number;
+  page_number?: number;
+  rect: NormalizedRect;
+  note?: string;
+}
+
+// 領収書の再圧縮設定（省略した項目は既定値）
+export interface RecompressOptions {
+  min_size_bytes?: number; // 対象とする最小ファイルサイズ（既定: 2MB）
+  min_savings_percent?: number; // 置き換えに必要な削減率（既定: 20%）
+  dry_run?: boolean; // trueの場合は削減見込みのみを報告
+  max_concurrent?: number; // 同時に処理する領収書数（既定: 3）
+}
+
+// 領収書ごとの再圧縮の処理状況
+export type RecompressStatus =
+  | 'projected'
+  | 'uploaded'
+  | 'replaced'
+  | 'completed'
+  | 'skipped'
+  | 'failed';
+
+// 領収書ごとの再圧縮結果
+export interface RecompressItemResult {
+  expense_id: number;
+  receipt_url: string; // 元の領収書URL
+  status: RecompressStatus;
+  original_size: number;
+  new_size?: number;
+  new_url?: string;
+  message?: string; // スキップ・失敗の理由
+}
+
+// 領収書の再圧縮結果
+export interface RecompressReport {
+  dry_run: boolean;
+  processed: number;
+  replaced: number; // ドライランでは置き換え対象の件数
+  skipped: number;
+  failed: number;
+  bytes_saved: number; // ドライランでは削減見込み
+  items: RecompressItemResult[];
+}
+
+// 読み取り専用クエリの実行結果
+export interface QueryResult {
+  columns: string[];
+  rows: unknown[][];
+  truncated: boolean; // 上限件数で打ち切った場合はtrue
+  row_limit: number;
+  duration_ms: number;
+}
+
+// リリース前スモークテストの手順ごとの結果
+export interface SmokeTestStep {
+  step: string; // 手順ID（リリース間で変更しない）
+  status: 'passed' | 'failed' | 'skipped';
+  duration_ms: number;
+  detail: string | null;
+}
+
+// リリース前スモークテストのレポート
+export interface SmokeTestReport {
+  format_version: number;
+  run_id: string;
+  app_version: string;
+  schema_version: string;
+  storage: 'remote' | 'mock'; // 領収書の保存先（未ログイン時はモック）
+  started_at: string;
+  duration_ms: number;
+  passed: boolean; // 失敗した手順がない場合はtrue
+  steps: SmokeTestStep[];
+}
+
+// 店舗
+export interface Merchant {
+  id: number;
+  name: string;
+  aliases: string[]; // 照合に使用する別名（店舗名を含む）
+  created_at: string;
+  updated_at: string;
+}
+
+// 店舗別の支出
+export interface MerchantSpending {
+  merchant_id: number;
+  merchant_name: string;
+  count: number;
+  total_amount: number;
+}
+
+// 店舗のグループ化の提案
+export interface MerchantGroupProposal {
+  normalized_name: string;
+  suggested_name: string;
+  existing_merchant_id?: number | null; // 別名が一致する既存の店舗
+  descriptions: string[];
+  expense_ids: number[];
+  total_amount: number;
+}
+
+// 店舗のグループ化を確定するためのDTO
+export interface ConfirmMerchantGroupDto {
+  merchant_id?: number | null; // 指定した場合は既存の店舗に追加する
+  name: string;
+  aliases: string[];
+  expense_ids: number[];
+}
+
+// 領収書の添付を求める強さ（hard: 領収書がない経費は保存しない、soft: 保存して未添付として記録する）
+export type ReceiptEnforcement = 'hard' | 'soft';
+
+// カテゴリごとの領収書の添付ルール
+export interface ReceiptPolicy {
+  id: number;
+  category: string; // 登録時のカテゴリ名
+  category_id?: number | null; // カテゴリーID（名称変更後も同じルールを適用する）
+  min_amount: number; // この金額以上の経費に領収書を求める
+  enforcement: ReceiptEnforcement;
+  created_at: string;
+  updated_at: string;
+}
+
+// 領収書の添付ルール作成用DTO
+export interface CreateReceiptPolicyDto {
+  category: string;
+  category_id?: number | null;
+  min_amount: number;
+  enforcement: ReceiptEnforcement;
+}
+
+// 領収書の添付ルール更新用DTO（指定した項目のみ更新）
+export interface UpdateReceiptPolicyDto {
+  min_amount?: number;
+  enforcement?: ReceiptEnforcement;
+}
+
+// 領収書の添付ルールに反して領収書が未添付の経費
+export interface MissingReceiptExpense extends Expense {
+  policy_id: number;
+  enforcement: ReceiptEnforcement;
+  followup_requested: boolean; // 後で添付することを明示して保存した
+  flagged_at: string;
+}
+
+// 端数処理の方法
+export type RoundingMode = 'floor' | 'half_up' | 'ceil';
+
+// 金額の計算式（opで計算の種類を指定、金額は円単位の整数）
+export type CalcExpression =
+  | { op: 'extract_tax'; amount: number; tax_rate: number; rounding_mode?: RoundingMode }
+  | { op: 'add_tax'; amount: number; tax_rate: number; rounding_mode?: RoundingMode }
+  | { op: 'split'; amount: number; parts: number }
+  | { op: 'percentage_of'; amount: number; percent: number; rounding_mode?: RoundingMode };
+
+// 消費税の内訳
+export interface TaxBreakdown {
+  kind: 'tax';
+  inclusive: number; // 税込金額
+  exclusive: number; // 税抜金額
+  tax: number; // 消費税額
+  tax_rate: number; // 税率（10%の場合は0.1）
+  rounding_mode: RoundingMode;
+  rounding: number; // 端数処理で増減した額
+}
+
+// 金額の分割結果
+export interface SplitResult {
+  kind: 'split';
+  parts: number[]; // 合計は元の金額と一致する
+  remainder: number; // 先頭から1円ずつ加算した額
+}
+
+// 金額に対する割合の計算結果
+export interface PercentageResult {
+  kind: 'percentage';
+  amount: number;
+  percent: number;
+  portion: number; // 割合に相当する額
+  total: number; // 元の金額との合計
+  rounding_mode: RoundingMode;
+  rounding: number; // 端数処理で増減した額
+}
+
+export type CalcResult = TaxBreakdown | SplitResult | PercentageResult;
+
+// 起動時の動作の設定
+export interface StartupSettings {
+  start_minimized: boolean; // トレイアイコンのみを表示して起動する
+  launch_at_login: boolean; // OSに登録されている実際の状態
+  restore_last_session: boolean; // 前回表示していた画面と絞り込み条件を復元する
+}
+
+// 起動時の動作の設定の更新（指定した項目のみ更新）
+export interface UpdateStartupSettingsDto {
+  start_minimized?: boolean;
+  launch_at_login?: boolean;
+  restore_last_session?: boolean;
+}
+
+// 前回表示していた画面の状態
+export interface SessionState {
+  route: string; // 画面のパス（例: "/expenses"）
+  filters: Record<string, unknown>;
+  saved_at?: string | null; // 保存日時（RFC3339形式、JST）
+}
+
 // キャッシュ統計情報型
 export interface CacheStats {
   total_files: number;
@@ -150,6 +446,29 @@ export interface CacheStats {
   cache_hit_rate: number;
 }
 
+// キャッシュの上限を超えた場合の削除方式
+export type EvictionStrategy = 'LRU' | 'LFU' | 'FIFO';
+
+// 領収書キャッシュの保持ポリシー型
+export interface CachePolicy {
+  max_size_mb: number; // 最大キャッシュサイズ（MB、1〜10240）
+  max_age_days: number | null; // 最後のアクセスからの保持日数（nullの場合は期限なし）
+  eviction_strategy: EvictionStrategy;
+}
+
+// キャッシュ容量警告イベント（cache-near-full）
+export interface CacheNearFullEvent {
+  utilization_percent: number;
+  stats: CacheStats;
+}
+
+// セキュアストレージの状態変化イベント（secure-storage-event）
+export type SecureStorageEvent =
+  | { kind: 'locked'; attempt: number; max_attempts: number }
+  | { kind: 'fallback_activated'; reason: string }
+  | { kind: 'corrupted_entry'; key: string }
+  | { kind: 'corrupted_store'; store: string };
+
 // セキュリティ関連型
 export interface SystemDiagnosticInfo {
   environment: string;
@@ -163,6 +482,43 @@ export interface SystemDiagnosticInfo {
   target_os: string;
 }
 
+// APIサーバーのレート制限の状態型
+export interface RateLimitStatus {
+  cooling_down: boolean;
+  remaining_ms: number;
+  last_status_code: number | null;
+  rate_limited_count: number;
+}
+
+// アプリの状態型
+export interface AppHealth {
+  app_data_dir: string;
+  available_disk_bytes: number | null; // 取得できない場合はnull
+  low_disk_space: boolean;
+  secure_storage_warning: string | null; // フォールバックストレージを使用している場合の警告
+  rate_limit: RateLimitStatus;
+  checked_at: string; // RFC3339形式（JST）
+}
+
+// コマンドパレットに表示する機能の利用条件
+export type CapabilityRequirement =
+  | 'authenticated'
+  | 'storage_online'
+  | 'api_server_configured'
+  | 'location_storage_enabled'
+  | 'debug_build';
+
+// コマンドパレットに表示する機能型
+export interface Capability {
+  id: string; // 例: "expenses.create"
+  label_key: string; // 表示名の翻訳キー（例: "capability.expenses.create"）
+  command: string; // 実行するTauriコマンド名
+  requires: CapabilityRequirement[];
+  mutates: boolean;
+  destructive: boolean; // 実行前に確認が必要
+  in_palette: boolean;
+}
+
 // 環境情報型
 export interface EnvironmentInfo {
   environment: string;
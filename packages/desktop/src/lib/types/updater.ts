@@ -14,6 +14,10 @@ export interface UpdateInfo {
   content_length?: number;
   /** 最後にチェックした時刻（Unix timestamp） */
   last_checked: number;
+  /** ダウンロードURL */
+  download_url?: string;
+  /** 署名情報 */
+  signature?: string;
 }
 
 /**
@@ -30,6 +34,75 @@ export interface UpdaterConfig {
   skipped_versions: string[];
   /** 最後にチェックした時刻（Unix timestamp） */
   last_check_time?: number;
+  /** 重要な処理の実行中はインストールを待たず、次回終了時にインストールする */
+  schedule_install_on_next_quit: boolean;
+  /** 次回の自動チェック予定時刻（RFC3339形式、JST）。自動チェックが無効か未チェックの場合はnull */
+  next_check_at?: string | null;
+}
+
+/**
+ * 差分アップデートの情報の型定義
+ */
+export interface DeltaUpdateInfo {
+  /** 差分の適用元のバージョン */
+  from_version: string;
+  /** 差分を適用した後のバージョン */
+  to_version: string;
+  /** 差分ファイルのURL */
+  patch_url: string;
+  /** 差分ファイルのサイズ（バイト） */
+  patch_size: number;
+  /** 差分ファイルのSHA-256 */
+  patch_sha256: string;
+  /** 完全なバイナリのサイズ（バイト） */
+  full_size: number;
+  /** 差分を適用した後の実行ファイルのSHA-256 */
+  target_sha256: string;
+}
+
+/**
+ * アプリケーションのバージョンとビルド情報の型定義
+ */
+export interface AppVersionInfo {
+  /** バージョン */
+  version: string;
+  /** ビルド日（YYYY-MM-DD形式） */
+  build_date?: string;
+  /** ビルド元のコミットハッシュ */
+  git_commit?: string;
+  /** ビルドプロファイル */
+  build_profile: 'debug' | 'release';
+}
+
+/**
+ * アップデートチェックの進捗（update-check-progress）の型定義
+ */
+export interface UpdateCheckProgress {
+  /** チェックの段階（doneは失敗した場合も通知される） */
+  stage: 'connecting' | 'fetching_metadata' | 'comparing_versions' | 'done';
+}
+
+/**
+ * 実行中の重要な処理の型定義
+ */
+export interface ActiveOperation {
+  id: number;
+  /** 処理の種類 */
+  kind: 'migration' | 'database_update' | 'receipt_upload';
+  /** 処理の説明 */
+  description: string;
+  /** 開始日時（RFC3339形式、JST） */
+  started_at: string;
+}
+
+/**
+ * インストール待ちの通知（pending-install-blocked）の型定義
+ */
+export interface PendingInstallBlockedEvent {
+  /** 完了を待っている処理 */
+  operations: ActiveOperation[];
+  /** 待たずに次回終了時のインストールに切り替えたかどうか */
+  scheduled_on_next_quit: boolean;
 }
 
 /**
@@ -0,0 +1,22 @@
+import {
+  listen,
+  type EventCallback,
+  type UnlistenFn,
+} from '@tauri-apps/api/event';
+import type { EventName, EventPayloads } from '../types/events';
+
+/**
+ * バックエンドから通知されるイベントをリッスンする
+ *
+ * イベント名と通知内容の型はバックエンドから生成した`types/events.ts`に従います。
+ *
+ * @param name - イベント名（`EVENTS`の値）
+ * @param handler - イベントを受け取ったときのコールバック
+ * @returns リッスンを解除する関数
+ */
+export function listenEvent<N extends EventName>(
+  name: N,
+  handler: EventCallback<EventPayloads[N]>
+): Promise<UnlistenFn> {
+  return listen<EventPayloads[N]>(name, handler);
+}
@@ -2,6 +2,7 @@
 
 import { invoke } from '@tauri-apps/api/core';
 import type {
+  AppHealth,
   SystemDiagnosticInfo,
   EnvironmentInfo,
   R2DiagnosticInfo,
@@ -22,6 +23,18 @@ export async function getSystemDiagnosticInfo(): Promise<SystemDiagnosticInfo> {
   }
 }
 
+/**
+ * アプリの状態（アプリデータのボリュームの空き容量など）を取得
+ */
+export async function getAppHealth(): Promise<AppHealth> {
+  try {
+    return await invoke<AppHealth>('get_app_health');
+  } catch (error) {
+    console.error('アプリの状態の取得に失敗しました:', error);
+    throw error;
+  }
+}
+
 /**
  * セキュリティ設定の検証
  */
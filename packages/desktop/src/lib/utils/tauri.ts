@@ -1,13 +1,36 @@
 import { invoke } from '@tauri-apps/api/core';
 import { authStore } from '../stores/auth.svelte';
 import type {
+  CalcExpression,
+  CalcResult,
   Category,
   Expense,
+  ExpensePage,
+  GetExpensesQuery,
   CreateExpenseDto,
   UpdateExpenseDto,
   Subscription,
   CreateSubscriptionDto,
   UpdateSubscriptionDto,
+  DeleteResult,
+  ExpenseIntegrityReport,
+  ExpenseSyncReport,
+  Capability,
+  ConfirmMerchantGroupDto,
+  CreateReceiptPolicyDto,
+  Merchant,
+  MerchantGroupProposal,
+  MerchantSpending,
+  MissingReceiptExpense,
+  QueryResult,
+  ReceiptPolicy,
+  RecompressOptions,
+  RecompressReport,
+  SessionState,
+  SmokeTestReport,
+  StartupSettings,
+  UpdateReceiptPolicyDto,
+  UpdateStartupSettingsDto,
   TauriResult,
 } from '../types';
 
@@ -124,7 +147,25 @@ export async function getExpenses(
   return handleTauriCommand(
     invoke<Expense[]>('get_expenses', {
       month,
-      category,
+      filter: category ? { category } : undefined,
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 経費一覧を1ページ分取得する
+ *
+ * @param query - ページ取得条件（期間・カテゴリ・件数・開始位置・並び順）
+ * @returns 条件に一致した件数と指定したページの経費、またはエラー
+ */
+export async function getExpensesPage(
+  query: GetExpensesQuery
+): Promise<TauriResult<ExpensePage>> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<ExpensePage>('get_expenses', {
+      query,
       sessionToken: sessionToken,
     })
   );
@@ -155,12 +196,14 @@ export async function updateExpense(
  * 経費を削除する
  *
  * @param id - 削除する経費のID
- * @returns 成功またはエラー
+ * @returns 削除した経費のIDとリビジョンまたはエラー
  */
-export async function deleteExpense(id: number): Promise<TauriResult<void>> {
+export async function deleteExpense(
+  id: number
+): Promise<TauriResult<DeleteResult>> {
   const sessionToken = getAuthToken();
   const result = await handleTauriCommand(
-    invoke<void>('delete_expense', {
+    invoke<DeleteResult>('delete_expense', {
       id,
       sessionToken: sessionToken,
     })
@@ -222,7 +265,7 @@ export async function getSubscriptions(
   const sessionToken = getAuthToken();
   return handleTauriCommand(
     invoke<Subscription[]>('get_subscriptions', {
-      activeOnly: activeOnly,
+      filter: activeOnly ? { is_active: true } : undefined,
       sessionToken: sessionToken,
     })
   );
@@ -307,20 +350,66 @@ export async function saveSubscriptionReceipt(
  * 経費の領収書を削除する
  *
  * @param expenseId - 経費ID
- * @returns 成功またはエラー
+ * @returns 領収書を削除した後の経費データまたはエラー
  */
 export async function deleteExpenseReceipt(
   expenseId: number
-): Promise<TauriResult<boolean>> {
+): Promise<TauriResult<Expense>> {
   const sessionToken = getAuthToken();
   return handleTauriCommand(
-    invoke<boolean>('delete_expense_receipt', {
+    invoke<Expense>('delete_expense_receipt', {
       expenseId: expenseId,
       sessionToken: sessionToken,
     })
   );
 }
 
+/**
+ * APIサーバーと経費を差分同期する
+ *
+ * @returns 同期結果またはエラー
+ */
+export async function syncExpenses(): Promise<
+  TauriResult<ExpenseSyncReport>
+> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<ExpenseSyncReport>('sync_expenses', { sessionToken: sessionToken })
+  );
+}
+
+/**
+ * APIサーバーから経費を全件取得し直す
+ *
+ * @returns 同期結果またはエラー
+ */
+export async function forceFullExpenseResync(): Promise<
+  TauriResult<ExpenseSyncReport>
+> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<ExpenseSyncReport>('force_full_expense_resync', {
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * ローカルの経費とAPIサーバーの件数・チェックサムを比較する
+ *
+ * @returns 整合性チェック結果またはエラー
+ */
+export async function checkExpenseSyncIntegrity(): Promise<
+  TauriResult<ExpenseIntegrityReport>
+> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<ExpenseIntegrityReport>('check_expense_sync_integrity', {
+      sessionToken: sessionToken,
+    })
+  );
+}
+
 /**
  * 経費の領収書を削除する
  *
@@ -417,6 +506,436 @@ export async function deleteReceiptFromR2(
   );
 }
 
+/**
+ * 店舗の一覧を取得する
+ *
+ * @returns 別名を含む店舗の一覧またはエラー
+ */
+export async function getMerchants(): Promise<TauriResult<Merchant[]>> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<Merchant[]>('get_merchants', {
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 店舗を作成する
+ *
+ * 作成後、別名が一致する未設定の経費は自動的に紐付けられます。
+ *
+ * @param name - 店舗名
+ * @param aliases - 照合に使用する別名
+ * @returns 作成された店舗またはエラー
+ */
+export async function createMerchant(
+  name: string,
+  aliases?: string[]
+): Promise<TauriResult<Merchant>> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<Merchant>('create_merchant', {
+      name,
+      aliases: aliases ?? null,
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 店舗名を変更する（変更前の名前は別名として残る）
+ *
+ * @param merchantId - 店舗ID
+ * @param name - 新しい店舗名
+ * @returns 更新された店舗またはエラー
+ */
+export async function updateMerchantName(
+  merchantId: number,
+  name: string
+): Promise<TauriResult<Merchant>> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<Merchant>('update_merchant_name', {
+      merchantId,
+      name,
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 店舗を削除する（紐付けられた経費は未設定に戻る）
+ *
+ * @param merchantId - 店舗ID
+ * @returns 削除結果またはエラー
+ */
+export async function deleteMerchant(
+  merchantId: number
+): Promise<TauriResult<boolean>> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<boolean>('delete_merchant', {
+      merchantId,
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 店舗に別名を追加する
+ *
+ * @param merchantId - 店舗ID
+ * @param alias - 別名
+ * @returns 更新された店舗またはエラー
+ */
+export async function addMerchantAlias(
+  merchantId: number,
+  alias: string
+): Promise<TauriResult<Merchant>> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<Merchant>('add_merchant_alias', {
+      merchantId,
+      alias,
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 店舗の別名を削除する
+ *
+ * @param merchantId - 店舗ID
+ * @param alias - 別名
+ * @returns 更新された店舗またはエラー
+ */
+export async function deleteMerchantAlias(
+  merchantId: number,
+  alias: string
+): Promise<TauriResult<Merchant>> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<Merchant>('delete_merchant_alias', {
+      merchantId,
+      alias,
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 経費を説明または領収書の文字列から店舗に紐付ける
+ *
+ * 経費の説明は変更されません。
+ *
+ * @param expenseId - 経費ID
+ * @param ocrText - 領収書から読み取った文字列
+ * @returns 紐付けた店舗ID（一致しない場合はnull）またはエラー
+ */
+export async function linkExpenseMerchant(
+  expenseId: number,
+  ocrText?: string
+): Promise<TauriResult<number | null>> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<number | null>('link_expense_merchant', {
+      expenseId,
+      ocrText: ocrText ?? null,
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 店舗別の支出を集計する
+ *
+ * @param startDate - 開始日（YYYY-MM-DD形式）
+ * @param endDate - 終了日（YYYY-MM-DD形式）
+ * @returns 店舗別の件数と合計金額またはエラー
+ */
+export async function getSpendingByMerchant(
+  startDate?: string,
+  endDate?: string
+): Promise<TauriResult<MerchantSpending[]>> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<MerchantSpending[]>('get_spending_by_merchant', {
+      startDate: startDate ?? null,
+      endDate: endDate ?? null,
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 店舗が未設定の経費のグループ化の提案を取得する
+ *
+ * @returns 正規化した店舗名ごとの提案またはエラー
+ */
+export async function getMerchantGroupProposals(): Promise<
+  TauriResult<MerchantGroupProposal[]>
+> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<MerchantGroupProposal[]>('get_merchant_group_proposals', {
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 店舗のグループ化の提案を確定する
+ *
+ * @param dto - 確定する店舗名・別名・経費
+ * @returns 作成または更新された店舗またはエラー
+ */
+export async function confirmMerchantGroup(
+  dto: ConfirmMerchantGroupDto
+): Promise<TauriResult<Merchant>> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<Merchant>('confirm_merchant_group', {
+      dto,
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 領収書の添付ルールの一覧を取得する
+ *
+ * @returns カテゴリ名順のルールの一覧またはエラー
+ */
+export async function getReceiptPolicies(): Promise<
+  TauriResult<ReceiptPolicy[]>
+> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<ReceiptPolicy[]>('get_receipt_policies', {
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 領収書の添付ルールを作成する
+ *
+ * @param dto - カテゴリ・最低金額・強さ
+ * @returns 作成されたルールまたはエラー
+ */
+export async function createReceiptPolicy(
+  dto: CreateReceiptPolicyDto
+): Promise<TauriResult<ReceiptPolicy>> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<ReceiptPolicy>('create_receipt_policy', {
+      dto,
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 領収書の添付ルールの最低金額・強さを変更する
+ *
+ * @param policyId - ルールID
+ * @param dto - 変更する項目
+ * @returns 更新されたルールまたはエラー
+ */
+export async function updateReceiptPolicy(
+  policyId: number,
+  dto: UpdateReceiptPolicyDto
+): Promise<TauriResult<ReceiptPolicy>> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<ReceiptPolicy>('update_receipt_policy', {
+      policyId,
+      dto,
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 領収書の添付ルールを削除する（このルールによる未添付の記録も解除される）
+ *
+ * @param policyId - ルールID
+ * @returns 削除結果またはエラー
+ */
+export async function deleteReceiptPolicy(
+  policyId: number
+): Promise<TauriResult<boolean>> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<boolean>('delete_receipt_policy', {
+      policyId,
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 領収書の添付ルールに反して領収書が未添付の経費を取得する
+ *
+ * @param startDate - 開始日（YYYY-MM-DD形式、この日を含む）
+ * @param endDate - 終了日（YYYY-MM-DD形式、この日を含む）
+ * @returns 日付の新しい順の経費一覧またはエラー
+ */
+export async function getMissingReceiptExpenses(
+  startDate?: string,
+  endDate?: string
+): Promise<TauriResult<MissingReceiptExpense[]>> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<MissingReceiptExpense[]>('get_missing_receipt_expenses', {
+      startDate: startDate ?? null,
+      endDate: endDate ?? null,
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 金額の計算式を評価する（税抜金額・消費税額などのプレビュー用）
+ *
+ * @param expression - 計算式
+ * @returns 端数処理で増減した額を含む計算結果またはエラー
+ */
+export async function calculate(expression: CalcExpression): Promise<TauriResult<CalcResult>> {
+  return handleTauriCommand(invoke<CalcResult>('calculate', { expression }));
+}
+
+/**
+ * アップロード済みの領収書を再圧縮して置き換える
+ *
+ * 中断した場合は次回の実行時に続きから再開します。
+ *
+ * @param options - 再圧縮の設定（dry_runを指定すると削減見込みのみを返す）
+ * @returns 削減されたバイト数とスキップ・失敗した領収書を含む結果またはエラー
+ */
+export async function recompressExistingReceipts(
+  options?: RecompressOptions
+): Promise<TauriResult<RecompressReport>> {
+  const sessionToken = getAuthToken();
+  if (!sessionToken) {
+    return {
+      success: false,
+      error: '認証が必要です。ログインしてください。',
+    };
+  }
+
+  return handleTauriCommand(
+    invoke<RecompressReport>('recompress_existing_receipts', {
+      options: options ?? null,
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 読み取り専用のSQLクエリを実行する（上級者向け）
+ *
+ * 初回利用時は`QUERY_CONSOLE_CONFIRMATION_REQUIRED`エラーを返すため、
+ * ユーザーの確認後に`acknowledged`をtrueにして再実行します。
+ *
+ * @param sql - 単一のSELECT文
+ * @param params - `?`に割り当てるパラメーター
+ * @param rowLimit - 取得件数の上限
+ * @param acknowledged - 初回利用時の確認に同意したかどうか
+ * @returns 列名と行またはエラー
+ */
+export async function runReadonlyQuery(
+  sql: string,
+  params?: unknown[],
+  rowLimit?: number,
+  acknowledged?: boolean
+): Promise<TauriResult<QueryResult>> {
+  const sessionToken = getAuthToken();
+  if (!sessionToken) {
+    return {
+      success: false,
+      error: '認証が必要です。ログインしてください。',
+    };
+  }
+
+  return handleTauriCommand(
+    invoke<QueryResult>('run_readonly_query', {
+      sql,
+      params: params ?? null,
+      rowLimit: rowLimit ?? null,
+      acknowledged: acknowledged ?? null,
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * リリース前のスモークテストを実行する（開発者向け）
+ *
+ * ログイン中はAPIサーバー経由で領収書を確認し、未ログインの場合はモックを使用します。
+ *
+ * @returns 手順ごとの成否と所要時間のレポートまたはエラー
+ */
+export async function runReleaseSmokeTest(): Promise<TauriResult<SmokeTestReport>> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<SmokeTestReport>('run_release_smoke_test', {
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 起動時の動作の設定を取得する
+ *
+ * @returns 設定（launch_at_loginはOSに登録されている実際の状態）またはエラー
+ */
+export async function getStartupSettings(): Promise<
+  TauriResult<StartupSettings>
+> {
+  return handleTauriCommand(invoke<StartupSettings>('get_startup_settings'));
+}
+
+/**
+ * 起動時の動作の設定を更新する
+ *
+ * ログイン時の起動を登録できない場合は、OSの設定の変更方法を含むエラーを返します。
+ *
+ * @param dto - 更新する項目
+ * @returns 更新後の設定またはエラー
+ */
+export async function updateStartupSettings(
+  dto: UpdateStartupSettingsDto
+): Promise<TauriResult<StartupSettings>> {
+  return handleTauriCommand(
+    invoke<StartupSettings>('update_startup_settings', { dto })
+  );
+}
+
+/**
+ * 表示中の画面と絞り込み条件を保存する（前回の画面の復元が無効な場合は保存されない）
+ *
+ * @param state - 画面のパスと絞り込み条件
+ * @returns 保存結果またはエラー
+ */
+export async function saveSessionState(
+  state: SessionState
+): Promise<TauriResult<void>> {
+  return handleTauriCommand(invoke<void>('save_session_state', { state }));
+}
+
+/**
+ * 前回表示していた画面と絞り込み条件を取得する
+ *
+ * @returns 保存された状態（復元が無効な場合や未保存の場合はnull）またはエラー
+ */
+export async function getSessionState(): Promise<
+  TauriResult<SessionState | null>
+> {
+  return handleTauriCommand(invoke<SessionState | null>('get_session_state'));
+}
+
 /**
  * R2接続をテストする
  *
@@ -466,6 +985,40 @@ export async function getCacheStats(): Promise<
   );
 }
 
+/**
+ * 領収書キャッシュの保持ポリシーを取得する
+ *
+ * @returns 保持ポリシー（未設定の場合は既定値）またはエラー
+ */
+export async function getCachePolicy(): Promise<
+  TauriResult<import('../types').CachePolicy>
+> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<import('../types').CachePolicy>('get_cache_policy', {
+      sessionToken: sessionToken,
+    })
+  );
+}
+
+/**
+ * 領収書キャッシュの保持ポリシーを変更する
+ *
+ * @param policy - 保持ポリシー（次回のキャッシュサイズ管理から適用）
+ * @returns 成功時はvoid、失敗時はエラー
+ */
+export async function setCachePolicy(
+  policy: import('../types').CachePolicy
+): Promise<TauriResult<void>> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<void>('set_cache_policy', {
+      policy: policy,
+      sessionToken: sessionToken,
+    })
+  );
+}
+
 // ========================================
 // 並列処理とパフォーマンス関連のコマンド
 // ========================================
@@ -702,11 +1255,11 @@ export async function deleteSubscriptionReceiptFromR2(
  * サブスクリプションの領収書パスをDBから削除する（ユーザー認証付き）
  *
  * @param subscriptionId - サブスクリプションID
- * @returns 成功またはエラー
+ * @returns 領収書パスを削除した後のサブスクリプションデータまたはエラー
  */
 export async function deleteSubscriptionReceipt(
   subscriptionId: number
-): Promise<TauriResult<boolean>> {
+): Promise<TauriResult<Subscription>> {
   const sessionToken = getAuthToken();
   if (!sessionToken) {
     return {
@@ -716,7 +1269,7 @@ export async function deleteSubscriptionReceipt(
   }
 
   return handleTauriCommand(
-    invoke<boolean>('delete_subscription_receipt_via_api', {
+    invoke<Subscription>('delete_subscription_receipt_via_api', {
       subscriptionId: subscriptionId,
       sessionToken: sessionToken,
     })
@@ -726,16 +1279,38 @@ export async function deleteSubscriptionReceipt(
  * サブスクリプションを削除する
  *
  * @param id - 削除するサブスクリプションのID
- * @returns 成功またはエラー
+ * @returns 削除したサブスクリプションのIDとリビジョンまたはエラー
  */
 export async function deleteSubscription(
   id: number
-): Promise<TauriResult<void>> {
+): Promise<TauriResult<DeleteResult>> {
   const sessionToken = getAuthToken();
   return handleTauriCommand(
-    invoke<void>('delete_subscription', {
+    invoke<DeleteResult>('delete_subscription', {
       id,
       sessionToken: sessionToken,
     })
   );
 }
+
+// ========================================
+// コマンドパレット関連のコマンド
+// ========================================
+
+/**
+ * 現在利用できる機能の一覧を取得する
+ *
+ * ログイン状態・ストレージの接続状態・設定に応じてバックエンドが絞り込んだ一覧を返す
+ *
+ * @returns 利用できる機能の一覧またはエラー
+ */
+export async function getAvailableCapabilities(): Promise<
+  TauriResult<Capability[]>
+> {
+  const sessionToken = getAuthToken();
+  return handleTauriCommand(
+    invoke<Capability[]>('get_available_capabilities', {
+      sessionToken: sessionToken,
+    })
+  );
+}